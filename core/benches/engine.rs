@@ -0,0 +1,43 @@
+//! Criterion benchmarks of the core engine on the standard workloads in
+//! `pyorderbook_core::scenarios`: insert-heavy, cancel-heavy and
+//! match-heavy, each at two sizes, reported per message.
+//!
+//! Run with `cargo bench -p pyorderbook-core --bench engine`. To gate a
+//! change on performance, save a baseline on the base commit and compare
+//! against it:
+//!
+//! ```text
+//! cargo bench -p pyorderbook-core --bench engine -- --save-baseline base
+//! cargo bench -p pyorderbook-core --bench engine -- --baseline base
+//! ```
+//!
+//! Criterion then marks each benchmark that regressed beyond noise.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use pyorderbook_core::Scenario;
+
+const SIZES: [usize; 2] = [1_000, 100_000];
+
+fn scenarios(c: &mut Criterion) {
+    for scenario in Scenario::ALL {
+        let mut group = c.benchmark_group(scenario.name());
+        for ops in SIZES {
+            group.throughput(Throughput::Elements(ops as u64));
+            group.bench_function(ops.to_string(), |b| {
+                b.iter_batched_ref(
+                    || scenario.prepare(ops),
+                    |workload| workload.run(),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = scenarios
+}
+criterion_main!(benches);
@@ -0,0 +1,179 @@
+//! Replay of a synthetic feed shaped like a NASDAQ ITCH day for one liquid
+//! symbol, timing the price-level layer: adds, cancels and marketable orders
+//! against both sides of one symbol of a `Book`.
+//!
+//! The message mix and price distances follow what ITCH traffic looks like:
+//! about half the messages add an order, most of the rest delete one, a few
+//! execute against the touch, and adds cluster within a few ticks of it with
+//! a thin tail further out. Most levels then hold one or two orders, which
+//! is what the level queue is sized for.
+//!
+//! Run with `cargo bench -p pyorderbook-core --bench levels`. To compare a
+//! change, save a baseline on the base commit and bench against it, as for
+//! the `engine` bench:
+//!
+//! ```text
+//! cargo bench -p pyorderbook-core --bench levels -- --save-baseline base
+//! cargo bench -p pyorderbook-core --bench levels -- --baseline base
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use pyorderbook_core::{Book, OrderCore, PriceLevel, Rng, Side};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+const OPS: usize = 100_000;
+const SYMBOL: &str = "ITCH";
+/// Live orders the feed hovers around; past it, adds turn into deletes.
+const LIVE_ORDERS: usize = 400;
+
+enum Op {
+    Add(OrderCore),
+    Cancel(Uuid),
+    Take(OrderCore),
+}
+
+/// Ticks from the touch for a passive add: geometric near it, with one add
+/// in five spread uniformly over the next two hundred ticks.
+fn passive_offset(rng: &mut Rng) -> i64 {
+    if rng.below(5) == 0 {
+        return 20 + rng.below(200) as i64;
+    }
+    let mut ticks = 1;
+    while ticks < 20 && rng.below(10) < 8 {
+        ticks += 1;
+    }
+    ticks
+}
+
+fn feed(ops: usize) -> Vec<Op> {
+    let mut rng = Rng::new(Rng::SEED);
+    let mut mid: i64 = 10_000;
+    let mut live: Vec<Uuid> = Vec::new();
+    let mut feed = Vec::with_capacity(ops);
+    let order = |side, cents, lots: u64| {
+        OrderCore::new(side, SYMBOL, Decimal::new(cents, 2), lots as i64 * 100).unwrap()
+    };
+    for _ in 0..ops {
+        let side = if rng.below(2) == 0 {
+            Side::Bid
+        } else {
+            Side::Ask
+        };
+        let sign = if side == Side::Bid { -1 } else { 1 };
+        let roll = match rng.below(100) {
+            roll @ 0..=49 if live.len() >= LIVE_ORDERS => 50 + roll % 46,
+            roll => roll,
+        };
+        match roll {
+            0..=49 => {
+                let add = order(
+                    side,
+                    mid + sign * passive_offset(&mut rng),
+                    1 + rng.below(5),
+                );
+                live.push(add.id);
+                feed.push(Op::Add(add));
+            }
+            50..=95 if !live.is_empty() => {
+                let idx = rng.below(live.len() as u64) as usize;
+                feed.push(Op::Cancel(live.swap_remove(idx)));
+            }
+            _ => {
+                // Crosses up to three ticks through the touch.
+                let take = order(side, mid - sign * rng.below(4) as i64, 1 + rng.below(3));
+                feed.push(Op::Take(take));
+                mid += rng.below(3) as i64 - 1;
+            }
+        }
+    }
+    feed
+}
+
+/// The book the feed is replayed into.
+#[derive(Default)]
+struct Replay {
+    book: Book,
+}
+
+impl Replay {
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::Add(order) => self.book.enqueue(order),
+            Op::Cancel(id) => {
+                self.book.cancel(id);
+            }
+            Op::Take(order) => {
+                self.book.match_order(&order);
+            }
+        }
+    }
+
+    /// Quantity over the best ten levels of both sides, as a feed handler
+    /// publishing depth reads it after each message.
+    fn top_of_book(&self) -> i64 {
+        self.book.symbol_book(SYMBOL).map_or(0, |book| {
+            [&book.bids, &book.asks]
+                .iter()
+                .flat_map(|side| side.levels.iter().rev().take(10))
+                .map(PriceLevel::quantity)
+                .sum()
+        })
+    }
+
+    /// Share of levels holding one, two, and more orders.
+    fn occupancy(&self) -> [f64; 3] {
+        let mut counts = [0usize; 3];
+        if let Some(book) = self.book.symbol_book(SYMBOL) {
+            for level in book.bids.levels.iter().chain(&book.asks.levels) {
+                counts[level.orders.len().clamp(1, 3) - 1] += 1;
+            }
+        }
+        let total = counts.iter().sum::<usize>().max(1) as f64;
+        counts.map(|count| count as f64 / total)
+    }
+}
+
+/// Replay `feed`, reading the top of the book after each message if `depth`
+/// is set.
+fn replay(feed: Vec<Op>, depth: bool) -> Replay {
+    let mut replay = Replay::default();
+    let mut checksum = 0;
+    for op in feed {
+        replay.apply(op);
+        if depth {
+            checksum += replay.top_of_book();
+        }
+    }
+    std::hint::black_box(checksum);
+    replay
+}
+
+fn levels(c: &mut Criterion) {
+    let occupancy = replay(feed(OPS), false).occupancy();
+    println!(
+        "levels: final occupancy 1 order {:.0}%, 2 orders {:.0}%, 3+ orders {:.0}%",
+        occupancy[0] * 100.0,
+        occupancy[1] * 100.0,
+        occupancy[2] * 100.0
+    );
+    let mut group = c.benchmark_group("levels");
+    group.throughput(Throughput::Elements(OPS as u64));
+    for (name, depth) in [("messages", false), ("messages + depth", true)] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || feed(OPS),
+                |feed| replay(feed, depth),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = levels
+}
+criterion_main!(benches);
@@ -0,0 +1,214 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::level::SymbolBook;
+use crate::order::{OrderCore, Side};
+use crate::trade::MatchResult;
+
+// ---------------------------------------------------------------------------
+// Book — multi-symbol limit order book
+// ---------------------------------------------------------------------------
+
+/// Multi-symbol limit order book with price-time priority matching.
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+    symbols: HashMap<String, SymbolBook<OrderCore>>,
+    /// order id -> (symbol, side, price) of every resting order.
+    order_map: HashMap<Uuid, (String, Side, Decimal)>,
+}
+
+impl Book {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match an incoming order and rest any unfilled remainder.
+    pub fn match_order(&mut self, order: &OrderCore) -> MatchResult {
+        let sym_book = self.symbols.entry(order.symbol.clone()).or_default();
+        let result = sym_book.side_mut(order.side.other()).match_incoming(
+            order.id,
+            order.side,
+            order.price,
+            order.quantity,
+        );
+        for order_id in &result.filled {
+            self.order_map.remove(order_id);
+        }
+        if result.remaining_quantity > 0 {
+            let mut remainder = order.clone();
+            remainder.quantity = result.remaining_quantity;
+            self.enqueue(remainder);
+        }
+        result
+    }
+
+    /// Rest an order without matching it.
+    pub fn enqueue(&mut self, order: OrderCore) {
+        self.order_map
+            .insert(order.id, (order.symbol.clone(), order.side, order.price));
+        let ascending = order.side == Side::Bid;
+        let side = order.side;
+        self.symbols
+            .entry(order.symbol.clone())
+            .or_default()
+            .side_mut(side)
+            .insert(order, ascending);
+    }
+
+    /// Rest many orders without matching them, in arrival order within each
+    /// price level.
+    pub fn enqueue_batch(&mut self, orders: impl IntoIterator<Item = OrderCore>) {
+        let mut by_side: HashMap<(String, Side), Vec<OrderCore>> = HashMap::new();
+        for order in orders {
+            self.order_map
+                .insert(order.id, (order.symbol.clone(), order.side, order.price));
+            by_side
+                .entry((order.symbol.clone(), order.side))
+                .or_default()
+                .push(order);
+        }
+        for ((symbol, side), entries) in by_side {
+            self.symbols
+                .entry(symbol)
+                .or_default()
+                .side_mut(side)
+                .insert_batch(entries, side == Side::Bid);
+        }
+    }
+
+    /// Remove a resting order, returning it; `None` if it is not resting.
+    pub fn cancel(&mut self, order_id: Uuid) -> Option<OrderCore> {
+        let (symbol, side, price) = self.order_map.remove(&order_id)?;
+        self.symbols.get_mut(&symbol)?.side_mut(side).remove_order(
+            price,
+            order_id,
+            side == Side::Bid,
+        )
+    }
+
+    /// A resting order by id.
+    pub fn get_order(&self, order_id: Uuid) -> Option<&OrderCore> {
+        let (symbol, side, price) = self.order_map.get(&order_id)?;
+        let one_side = self.symbols.get(symbol)?.side(*side);
+        let idx = one_side.find_level(*price, *side == Side::Bid).ok()?;
+        one_side.levels[idx]
+            .orders
+            .iter()
+            .find(|o| o.id == order_id)
+    }
+
+    /// Best bid price and its aggregate quantity.
+    pub fn best_bid(&self, symbol: &str) -> Option<(Decimal, i64)> {
+        self.depth(symbol, Side::Bid, 1).pop()
+    }
+
+    /// Best ask price and its aggregate quantity.
+    pub fn best_ask(&self, symbol: &str) -> Option<(Decimal, i64)> {
+        self.depth(symbol, Side::Ask, 1).pop()
+    }
+
+    /// Up to `levels` aggregated `(price, quantity)` levels, best first.
+    pub fn depth(&self, symbol: &str, side: Side, levels: usize) -> Vec<(Decimal, i64)> {
+        self.symbols.get(symbol).map_or_else(Vec::new, |sym_book| {
+            sym_book
+                .side(side)
+                .levels
+                .iter()
+                .rev()
+                .take(levels)
+                .map(|lvl| (lvl.price, lvl.quantity()))
+                .collect()
+        })
+    }
+
+    /// The levels of `symbol`, if it has seen an order.
+    pub fn symbol_book(&self, symbol: &str) -> Option<&SymbolBook<OrderCore>> {
+        self.symbols.get(symbol)
+    }
+
+    /// Symbols that have seen at least one order.
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.symbols.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: Side, price: i64, quantity: i64) -> OrderCore {
+        OrderCore::new(side, "AAPL", Decimal::from(price), quantity).unwrap()
+    }
+
+    #[test]
+    fn match_order_fills_price_then_time_priority() {
+        let mut book = Book::new();
+        let first = order(Side::Ask, 10, 5);
+        let second = order(Side::Ask, 10, 5);
+        let better = order(Side::Ask, 9, 2);
+        for o in [&first, &second, &better] {
+            assert!(book.match_order(o).trades.is_empty());
+        }
+
+        let result = book.match_order(&order(Side::Bid, 10, 8));
+        let fills: Vec<_> = result
+            .trades
+            .iter()
+            .map(|t| (t.standing_order_id, t.fill_price, t.fill_quantity))
+            .collect();
+        assert_eq!(
+            fills,
+            vec![
+                (better.id, Decimal::from(9), 2),
+                (first.id, Decimal::from(10), 5),
+                (second.id, Decimal::from(10), 1),
+            ]
+        );
+        assert_eq!(result.filled, vec![better.id, first.id]);
+        let remaining: Vec<_> = result.trades.iter().map(|t| t.standing_remaining).collect();
+        assert_eq!(remaining, vec![0, 0, 4]);
+        assert_eq!(book.get_order(second.id).map(|o| o.quantity), Some(4));
+        assert!(book.get_order(first.id).is_none());
+    }
+
+    #[test]
+    fn remainder_rests_and_cancel_removes_it() {
+        let mut book = Book::new();
+        let bid = order(Side::Bid, 10, 3);
+        let result = book.match_order(&bid);
+        assert_eq!(result.remaining_quantity, 3);
+        assert_eq!(book.best_bid("AAPL"), Some((Decimal::from(10), 3)));
+
+        assert_eq!(book.cancel(bid.id).map(|o| o.quantity), Some(3));
+        assert!(book.cancel(bid.id).is_none());
+        assert_eq!(book.best_bid("AAPL"), None);
+        assert_eq!(book.depth("MSFT", Side::Ask, 5), Vec::new());
+    }
+
+    #[test]
+    fn enqueue_batch_merges_levels_in_price_order() {
+        let mut book = Book::new();
+        book.enqueue(order(Side::Ask, 12, 1));
+        let first = order(Side::Ask, 11, 2);
+        let second = order(Side::Ask, 11, 3);
+        book.enqueue_batch([
+            order(Side::Ask, 13, 4),
+            first.clone(),
+            order(Side::Ask, 12, 5),
+            second.clone(),
+            order(Side::Bid, 9, 6),
+        ]);
+        let levels = [(11, 5), (12, 6), (13, 4)].map(|(p, q)| (Decimal::from(p), q));
+        assert_eq!(book.depth("AAPL", Side::Ask, 5), levels.to_vec());
+        assert_eq!(book.best_bid("AAPL"), Some((Decimal::from(9), 6)));
+
+        let fills: Vec<_> = book
+            .match_order(&order(Side::Bid, 11, 5))
+            .trades
+            .iter()
+            .map(|t| t.standing_order_id)
+            .collect();
+        assert_eq!(fills, vec![first.id, second.id]);
+    }
+}
@@ -0,0 +1,92 @@
+use rust_decimal::Decimal;
+
+// ---------------------------------------------------------------------------
+// LevelCache — recently found price levels of one side
+// ---------------------------------------------------------------------------
+
+/// Prices remembered per side.
+const SLOTS: usize = 4;
+
+/// Counts of level lookups a [`LevelCache`] answered versus left to the
+/// binary search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LevelCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl LevelCacheStats {
+    pub fn merge(&mut self, other: LevelCacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+}
+
+/// The level indices of the last few prices looked up on a side, so bursts
+/// of activity at one price skip the binary search. Entries are only hints:
+/// inserting or removing a level shifts the indices after it, so a hint is
+/// used only if the level it points at still has its price, and is simply
+/// found again otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct LevelCache {
+    slots: [Option<(Decimal, usize)>; SLOTS],
+    /// Slot the next new price replaces, round robin.
+    next: usize,
+    pub stats: LevelCacheStats,
+}
+
+impl LevelCache {
+    /// The remembered index of `price`, if `price_at` confirms it; counts a
+    /// hit or a miss.
+    pub fn get(
+        &mut self,
+        price: Decimal,
+        price_at: impl Fn(usize) -> Option<Decimal>,
+    ) -> Option<usize> {
+        let found = self.slots.iter().find_map(|slot| match *slot {
+            Some((cached, idx)) if cached == price && price_at(idx) == Some(price) => Some(idx),
+            _ => None,
+        });
+        match found {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        found
+    }
+
+    /// Remember that `price` is at level `idx`, replacing a stale hint for
+    /// the same price or else the oldest slot.
+    pub fn put(&mut self, price: Decimal, idx: usize) {
+        let slot = self
+            .slots
+            .iter()
+            .position(|slot| matches!(slot, Some((cached, _)) if *cached == price))
+            .unwrap_or_else(|| {
+                let next = self.next;
+                self.next = (next + 1) % SLOTS;
+                next
+            });
+        self.slots[slot] = Some((price, idx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_are_checked_before_use() {
+        let mut cache = LevelCache::default();
+        let mut prices = vec![Decimal::ONE, Decimal::TWO];
+        let price_at = |prices: &Vec<Decimal>, idx: usize| prices.get(idx).copied();
+        assert_eq!(cache.get(Decimal::TWO, |i| price_at(&prices, i)), None);
+        cache.put(Decimal::TWO, 1);
+        assert_eq!(cache.get(Decimal::TWO, |i| price_at(&prices, i)), Some(1));
+        // A level inserted in front shifts the hint off its price.
+        prices.insert(0, Decimal::ZERO);
+        assert_eq!(cache.get(Decimal::TWO, |i| price_at(&prices, i)), None);
+        cache.put(Decimal::TWO, 2);
+        assert_eq!(cache.get(Decimal::TWO, |i| price_at(&prices, i)), Some(2));
+        assert_eq!(cache.stats, LevelCacheStats { hits: 2, misses: 2 });
+    }
+}
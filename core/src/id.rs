@@ -0,0 +1,17 @@
+use uuid::Uuid;
+
+/// Fresh id for a new order or trade.
+#[cfg(feature = "random-ids")]
+pub fn new_id() -> Uuid {
+    Uuid::new_v4()
+}
+
+/// Fresh id for a new order or trade: unique within the process, without
+/// needing a random number generator.
+#[cfg(not(feature = "random-ids"))]
+pub fn new_id() -> Uuid {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    Uuid::from_u64_pair(0, NEXT.fetch_add(1, Ordering::Relaxed))
+}
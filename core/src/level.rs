@@ -0,0 +1,391 @@
+use rust_decimal::Decimal;
+use smallvec::SmallVec;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::cache::LevelCache;
+use crate::order::{Resting, Side};
+use crate::pool::Pool;
+use crate::trade::{MatchResult, Trade};
+
+// ---------------------------------------------------------------------------
+// PriceLevel / OneSide / SymbolBook — generic over the resting entry type
+// ---------------------------------------------------------------------------
+
+/// Orders a level holds inline before its queue moves to the heap. Most
+/// levels of a live book hold one or two.
+pub const LEVEL_INLINE: usize = 2;
+
+/// The FIFO queue of a price level. Short queues live inside the level
+/// itself, so creating and emptying typical levels allocates nothing; the
+/// price is that taking from the front of a deep queue shifts the rest.
+pub type LevelQueue<E> = SmallVec<[E; LEVEL_INLINE]>;
+
+/// A price level with a FIFO queue of orders.
+#[derive(Clone, Debug)]
+pub struct PriceLevel<E> {
+    pub price: Decimal,
+    pub orders: LevelQueue<E>,
+}
+
+impl<E: Resting> PriceLevel<E> {
+    /// Aggregate resting quantity at this level.
+    pub fn quantity(&self) -> i64 {
+        self.orders.iter().map(Resting::quantity).sum()
+    }
+
+    /// Aggregate displayed quantity at this level.
+    pub fn displayed_quantity(&self) -> i64 {
+        self.orders.iter().map(Resting::displayed).sum()
+    }
+}
+
+/// One side of the book (bids or asks) for a single symbol.
+/// - Bids: sorted ascending by price -> best bid (highest) at the **back**
+/// - Asks: sorted descending by price -> best ask (lowest) at the **back**
+#[derive(Clone, Debug)]
+pub struct OneSide<E> {
+    pub levels: Vec<PriceLevel<E>>,
+    /// Queues of emptied levels and entries of filled orders, for reuse.
+    pub pool: Pool<E>,
+    /// Indices of recently looked-up prices.
+    pub cache: LevelCache,
+}
+
+impl<E> Default for OneSide<E> {
+    fn default() -> Self {
+        OneSide {
+            levels: Vec::new(),
+            pool: Pool::default(),
+            cache: LevelCache::default(),
+        }
+    }
+}
+
+impl<E: Resting> OneSide<E> {
+    /// Find the index of a price level via binary search.
+    /// `ascending` = true for bids, false for asks.
+    pub fn find_level(&self, price: Decimal, ascending: bool) -> Result<usize, usize> {
+        self.levels.binary_search_by(|lvl| {
+            if ascending {
+                lvl.price.cmp(&price)
+            } else {
+                price.cmp(&lvl.price)
+            }
+        })
+    }
+
+    /// `find_level` for inserts and cancels, trying the cache of recently
+    /// touched prices before searching.
+    pub fn locate(&mut self, price: Decimal, ascending: bool) -> Result<usize, usize> {
+        let levels = &self.levels;
+        if let Some(idx) = self
+            .cache
+            .get(price, |idx| levels.get(idx).map(|lvl| lvl.price))
+        {
+            return Ok(idx);
+        }
+        let found = self.find_level(price, ascending);
+        if let Ok(idx) = found {
+            self.cache.put(price, idx);
+        }
+        found
+    }
+
+    /// Best level (highest bid or lowest ask), if any.
+    pub fn best(&self) -> Option<&PriceLevel<E>> {
+        self.levels.last()
+    }
+
+    /// Insert an order into the correct price level, creating it if needed.
+    pub fn insert(&mut self, entry: E, ascending: bool) {
+        match self.locate(entry.price(), ascending) {
+            Ok(idx) => {
+                self.levels[idx].orders.push(entry);
+            }
+            Err(idx) => {
+                let mut orders = self.pool.take_level();
+                let price = entry.price();
+                orders.push(entry);
+                self.levels.insert(idx, PriceLevel { price, orders });
+                self.cache.put(price, idx);
+            }
+        }
+    }
+
+    /// Insert many orders, keeping their relative order within each level.
+    /// Orders at existing levels are appended in place; new levels are built
+    /// up front and merged into the side in a single pass, so seeding a deep
+    /// side does not shift the level vector once per new price.
+    pub fn insert_batch(&mut self, entries: impl IntoIterator<Item = E>, ascending: bool) {
+        let mut fresh: BTreeMap<Decimal, LevelQueue<E>> = BTreeMap::new();
+        for entry in entries {
+            match self.locate(entry.price(), ascending) {
+                Ok(idx) => self.levels[idx].orders.push(entry),
+                Err(_) => fresh
+                    .entry(entry.price())
+                    .or_insert_with(|| self.pool.take_level())
+                    .push(entry),
+            }
+        }
+        if fresh.is_empty() {
+            return;
+        }
+        let mut fresh: Vec<PriceLevel<E>> = fresh
+            .into_iter()
+            .map(|(price, orders)| PriceLevel { price, orders })
+            .collect();
+        if !ascending {
+            fresh.reverse();
+        }
+        let existing = std::mem::take(&mut self.levels);
+        let mut merged = Vec::with_capacity(existing.len() + fresh.len());
+        let mut existing = existing.into_iter().peekable();
+        for level in fresh {
+            while let Some(next) = existing.next_if(|lvl| (lvl.price < level.price) == ascending) {
+                merged.push(next);
+            }
+            merged.push(level);
+        }
+        merged.extend(existing);
+        self.levels = merged;
+    }
+
+    /// Remove a specific order by id from the level at the given price,
+    /// returning the removed entry.
+    pub fn remove_order(&mut self, price: Decimal, order_id: Uuid, ascending: bool) -> Option<E> {
+        let idx = self.locate(price, ascending).ok()?;
+        let level = &mut self.levels[idx];
+        let pos = level.orders.iter().position(|o| o.id() == order_id)?;
+        let entry = level.orders.remove(pos);
+        if level.orders.is_empty() {
+            let emptied = self.levels.remove(idx);
+            self.pool.recycle_level(emptied.orders);
+        }
+        Some(entry)
+    }
+
+    /// Subtract quantities executed at `price` from resting orders, dropping
+    /// orders that are fully filled and levels left empty. Returns the ids of
+    /// the fully filled orders.
+    pub fn apply_executions(&mut self, executed: &HashMap<Uuid, i64>, price: Decimal) -> Vec<Uuid> {
+        let mut filled = Vec::new();
+        for level in &mut self.levels {
+            for entry in &mut level.orders {
+                if let Some(&qty) = executed.get(&entry.id()) {
+                    entry.set_quantity(entry.quantity() - qty);
+                    entry.record_fill(price, qty);
+                }
+            }
+            level.orders.retain(|entry| {
+                let done = entry.quantity() == 0;
+                if done {
+                    filled.push(entry.id());
+                }
+                !done
+            });
+        }
+        let levels = std::mem::take(&mut self.levels);
+        for level in levels {
+            if level.orders.is_empty() {
+                self.pool.recycle_level(level.orders);
+            } else {
+                self.levels.push(level);
+            }
+        }
+        filled
+    }
+
+    /// Match an incoming order against this side, which must be the side
+    /// opposite `incoming_side`. Fills best levels first; within a level,
+    /// displayed quantity trades before hidden quantity, FIFO within each
+    /// tier. Fully filled standing orders are removed.
+    pub fn match_incoming(
+        &mut self,
+        incoming_id: Uuid,
+        incoming_side: Side,
+        incoming_price: Decimal,
+        incoming_qty: i64,
+    ) -> MatchResult {
+        self.match_incoming_with(
+            incoming_id,
+            incoming_side,
+            incoming_price,
+            incoming_qty,
+            |standing_price| incoming_side.calc_fill_price(incoming_price, standing_price),
+        )
+    }
+
+    /// `match_incoming` with the execution price of each fill given by
+    /// `fill_price` from the standing order's price.
+    pub fn match_incoming_with(
+        &mut self,
+        incoming_id: Uuid,
+        incoming_side: Side,
+        incoming_price: Decimal,
+        incoming_qty: i64,
+        fill_price: impl Fn(Decimal) -> Decimal,
+    ) -> MatchResult {
+        let mut result = MatchResult {
+            remaining_quantity: incoming_qty,
+            ..MatchResult::default()
+        };
+
+        while result.remaining_quantity > 0 {
+            let Some(level) = self.levels.last_mut() else {
+                break;
+            };
+            if !incoming_side.price_is_matchable(incoming_price, level.price) {
+                break;
+            }
+
+            while result.remaining_quantity > 0 {
+                // The first order still displaying quantity, else the first
+                // order of the hidden tier.
+                let Some(idx) = level
+                    .orders
+                    .iter()
+                    .position(|o| o.displayed() > 0)
+                    .or((!level.orders.is_empty()).then_some(0))
+                else {
+                    break;
+                };
+                let standing = &mut level.orders[idx];
+                let displayed = standing.displayed();
+                let available = if displayed > 0 {
+                    displayed
+                } else {
+                    standing.quantity()
+                };
+                let matched_qty = result.remaining_quantity.min(available);
+                standing.set_quantity(standing.quantity() - matched_qty);
+                if displayed > 0 {
+                    standing.set_displayed(displayed - matched_qty);
+                }
+                result.remaining_quantity -= matched_qty;
+
+                let fill_price = fill_price(standing.price());
+                standing.record_fill(fill_price, matched_qty);
+                result.trades.push(
+                    Trade::new(incoming_id, standing.id(), matched_qty, fill_price)
+                        .with_standing(standing.price(), standing.quantity()),
+                );
+
+                if standing.quantity() == 0 {
+                    result.filled.push(standing.id());
+                    let entry = level.orders.remove(idx);
+                    self.pool.recycle_entry(entry);
+                } else if displayed > 0 && standing.displayed() == 0 && standing.replenish() {
+                    // A refreshed tranche loses time priority.
+                    let entry = level.orders.remove(idx);
+                    level.orders.push(entry);
+                }
+            }
+
+            if level.orders.is_empty() {
+                if let Some(emptied) = self.levels.pop() {
+                    self.pool.recycle_level(emptied.orders);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Per-symbol book state.
+#[derive(Clone, Debug)]
+pub struct SymbolBook<E> {
+    pub bids: OneSide<E>,
+    pub asks: OneSide<E>,
+}
+
+impl<E> Default for SymbolBook<E> {
+    fn default() -> Self {
+        SymbolBook {
+            bids: OneSide::default(),
+            asks: OneSide::default(),
+        }
+    }
+}
+
+impl<E> SymbolBook<E> {
+    pub fn side(&self, side: Side) -> &OneSide<E> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    pub fn side_mut(&mut self, side: Side) -> &mut OneSide<E> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entry showing at most `peak` of its quantity at a time.
+    struct Iceberg {
+        id: Uuid,
+        quantity: i64,
+        shown: i64,
+        peak: i64,
+    }
+
+    impl Resting for Iceberg {
+        fn id(&self) -> Uuid {
+            self.id
+        }
+        fn price(&self) -> Decimal {
+            Decimal::ONE
+        }
+        fn quantity(&self) -> i64 {
+            self.quantity
+        }
+        fn set_quantity(&mut self, quantity: i64) {
+            self.quantity = quantity;
+        }
+        fn displayed(&self) -> i64 {
+            self.shown.min(self.quantity)
+        }
+        fn set_displayed(&mut self, displayed: i64) {
+            self.shown = displayed;
+        }
+        fn replenish(&mut self) -> bool {
+            self.shown = self.peak.min(self.quantity);
+            self.shown > 0
+        }
+    }
+
+    #[test]
+    fn displayed_quantity_trades_before_hidden_and_icebergs_requeue() {
+        let id = |n| Uuid::from_u64_pair(0, n);
+        let entry = |n, quantity, peak| Iceberg {
+            id: id(n),
+            quantity,
+            shown: peak,
+            peak,
+        };
+        let mut asks = OneSide::default();
+        asks.insert(entry(1, 5, 0), false);
+        asks.insert(entry(2, 6, 2), false);
+        asks.insert(entry(3, 3, 3), false);
+        assert_eq!(asks.levels[0].displayed_quantity(), 5);
+
+        let result = asks.match_incoming(id(9), Side::Bid, Decimal::ONE, 9);
+        let fills: Vec<_> = result
+            .trades
+            .iter()
+            .map(|t| (t.standing_order_id, t.fill_quantity))
+            .collect();
+        // The hidden order is first in time but fills last.
+        assert_eq!(fills, [(id(2), 2), (id(3), 3), (id(2), 2), (id(2), 2)]);
+        assert_eq!(asks.levels[0].quantity(), 5);
+        assert_eq!(asks.levels[0].displayed_quantity(), 0);
+    }
+}
@@ -0,0 +1,36 @@
+//! Pure-Rust limit order book and matching engine.
+//!
+//! This crate is the engine behind the `pyorderbook` Python package, with no
+//! Python dependency. It provides price-time priority matching with FIFO
+//! queues at each price level and decimal prices.
+//!
+//! ```
+//! use pyorderbook_core::{Book, OrderCore, Side};
+//! use rust_decimal::Decimal;
+//!
+//! let mut book = Book::new();
+//! let ask = OrderCore::new(Side::Ask, "AAPL", Decimal::new(1500, 1), 100).unwrap();
+//! book.match_order(&ask);
+//!
+//! let bid = OrderCore::new(Side::Bid, "AAPL", Decimal::new(1510, 1), 40).unwrap();
+//! let result = book.match_order(&bid);
+//! assert_eq!(result.trades[0].fill_price, Decimal::new(1500, 1));
+//! assert_eq!(book.best_ask("AAPL"), Some((Decimal::new(1500, 1), 60)));
+//! ```
+
+pub mod book;
+pub mod cache;
+pub mod id;
+pub mod level;
+pub mod order;
+pub mod pool;
+pub mod scenarios;
+pub mod trade;
+
+pub use book::Book;
+pub use cache::{LevelCache, LevelCacheStats};
+pub use level::{LevelQueue, OneSide, PriceLevel, SymbolBook};
+pub use order::{OrderCore, OrderError, Resting, Side};
+pub use pool::{Pool, PoolStats};
+pub use scenarios::{Rng, Scenario, Step, Workload};
+pub use trade::{MatchResult, Trade};
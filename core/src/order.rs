@@ -0,0 +1,181 @@
+use rust_decimal::Decimal;
+use std::fmt;
+use uuid::Uuid;
+
+// ---------------------------------------------------------------------------
+// Side
+// ---------------------------------------------------------------------------
+
+/// Bid (buy) or ask (sell) side of an order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl Side {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Side::Bid => "bid",
+            Side::Ask => "ask",
+        }
+    }
+
+    /// Return the opposite side.
+    pub fn other(self) -> Side {
+        match self {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        }
+    }
+
+    /// Check if incoming price can match against standing price.
+    pub fn price_is_matchable(self, incoming: Decimal, standing: Decimal) -> bool {
+        match self {
+            Side::Bid => incoming >= standing,
+            Side::Ask => incoming <= standing,
+        }
+    }
+
+    /// Determine the fill price for a trade.
+    pub fn calc_fill_price(self, incoming: Decimal, standing: Decimal) -> Decimal {
+        match self {
+            Side::Bid => incoming.min(standing),
+            Side::Ask => incoming.max(standing),
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrderCore — a limit order as the engine sees it
+// ---------------------------------------------------------------------------
+
+/// A limit order: `quantity` is the open (unfilled) quantity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderCore {
+    pub id: Uuid,
+    pub side: Side,
+    pub symbol: String,
+    pub price: Decimal,
+    pub quantity: i64,
+}
+
+impl OrderCore {
+    /// Create an order with a fresh id.
+    pub fn new(
+        side: Side,
+        symbol: impl Into<String>,
+        price: Decimal,
+        quantity: i64,
+    ) -> Result<Self, OrderError> {
+        if quantity <= 0 {
+            return Err(OrderError::NonPositiveQuantity(quantity));
+        }
+        Ok(OrderCore {
+            id: crate::id::new_id(),
+            side,
+            symbol: symbol.into(),
+            price,
+            quantity,
+        })
+    }
+}
+
+/// Why an order was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderError {
+    NonPositiveQuantity(i64),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::NonPositiveQuantity(_) => {
+                f.write_str("Order quantity must be greater than zero")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+/// An order that can rest in a `PriceLevel` queue.
+///
+/// Implemented by `OrderCore`; embedders that carry extra per-order state
+/// (time in force, auction flags, ...) implement it for their own entry type
+/// and reuse the level structures and matching loop.
+pub trait Resting {
+    fn id(&self) -> Uuid;
+    fn price(&self) -> Decimal;
+    fn quantity(&self) -> i64;
+    fn set_quantity(&mut self, quantity: i64);
+
+    /// Called for each execution against the order, after its quantity has
+    /// been reduced. Entries that track fill statistics override this.
+    fn record_fill(&mut self, _price: Decimal, _quantity: i64) {}
+
+    /// Quantity shown to the market; the rest is hidden and trades after
+    /// every displayed order at the same price. Defaults to all of it.
+    fn displayed(&self) -> i64 {
+        self.quantity()
+    }
+
+    /// Set the displayed quantity after a fill against it. Entries that
+    /// hide quantity override this along with `displayed`.
+    fn set_displayed(&mut self, _displayed: i64) {}
+
+    /// Called when fills use up the displayed quantity while hidden
+    /// quantity remains. An iceberg shows its next tranche and returns
+    /// true, which sends it to the back of its level's queue.
+    fn replenish(&mut self) -> bool {
+        false
+    }
+}
+
+impl Resting for OrderCore {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn price(&self) -> Decimal {
+        self.price
+    }
+
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: i64) {
+        self.quantity = quantity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn side_helpers_match_limit_order_rules() {
+        let bid = Decimal::from(101);
+        let ask = Decimal::from(100);
+        assert!(Side::Bid.price_is_matchable(bid, ask));
+        assert!(!Side::Ask.price_is_matchable(bid, ask));
+        assert_eq!(Side::Bid.calc_fill_price(bid, ask), ask);
+        assert_eq!(Side::Bid.other(), Side::Ask);
+    }
+
+    #[test]
+    fn new_rejects_non_positive_quantity() {
+        assert_eq!(
+            OrderCore::new(Side::Bid, "X", Decimal::ONE, 0),
+            Err(OrderError::NonPositiveQuantity(0))
+        );
+        assert!(OrderCore::new(Side::Bid, "X", Decimal::ONE, 1).is_ok());
+    }
+}
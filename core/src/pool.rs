@@ -0,0 +1,144 @@
+use crate::level::LevelQueue;
+
+// ---------------------------------------------------------------------------
+// Pool — spare level queues and order entries for reuse
+// ---------------------------------------------------------------------------
+
+/// Spare level queues kept per side; more are dropped.
+const MAX_SPARE_LEVELS: usize = 64;
+/// Largest queue capacity worth keeping, so one deep level doesn't pin its
+/// buffer for good.
+const MAX_SPARE_CAPACITY: usize = 1024;
+/// Spare entries kept per side; more are dropped.
+const MAX_SPARE_ENTRIES: usize = 1024;
+
+/// Counts of what a [`Pool`] handed out fresh versus reused.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub levels_allocated: u64,
+    pub levels_reused: u64,
+    pub entries_allocated: u64,
+    pub entries_reused: u64,
+}
+
+impl PoolStats {
+    pub fn merge(&mut self, other: PoolStats) {
+        self.levels_allocated += other.levels_allocated;
+        self.levels_reused += other.levels_reused;
+        self.entries_allocated += other.entries_allocated;
+        self.entries_reused += other.entries_reused;
+    }
+}
+
+/// The order queues of emptied levels and the entries of filled orders,
+/// kept so that new levels and resting orders reuse their allocations
+/// instead of making fresh ones. Cloning a pool keeps its stats but not its
+/// spares.
+#[derive(Debug)]
+pub struct Pool<E> {
+    levels: Vec<LevelQueue<E>>,
+    entries: Vec<E>,
+    pub stats: PoolStats,
+}
+
+impl<E> Default for Pool<E> {
+    fn default() -> Self {
+        Pool {
+            levels: Vec::new(),
+            entries: Vec::new(),
+            stats: PoolStats::default(),
+        }
+    }
+}
+
+impl<E> Clone for Pool<E> {
+    fn clone(&self) -> Self {
+        Pool {
+            stats: self.stats,
+            ..Pool::default()
+        }
+    }
+}
+
+impl<E> Pool<E> {
+    /// An empty queue for a new level, reusing a spare one if there is.
+    pub fn take_level(&mut self) -> LevelQueue<E> {
+        match self.levels.pop() {
+            Some(orders) => {
+                self.stats.levels_reused += 1;
+                orders
+            }
+            None => {
+                self.stats.levels_allocated += 1;
+                LevelQueue::new()
+            }
+        }
+    }
+
+    /// Keep the queue of an emptied level, dropping any entries left in it.
+    pub fn recycle_level(&mut self, mut orders: LevelQueue<E>) {
+        if self.levels.len() < MAX_SPARE_LEVELS && orders.capacity() <= MAX_SPARE_CAPACITY {
+            orders.clear();
+            self.levels.push(orders);
+        }
+    }
+
+    /// A spare entry for the caller to overwrite, or None if it must build
+    /// a fresh one (counted as allocated).
+    pub fn take_entry(&mut self) -> Option<E> {
+        let entry = self.entries.pop();
+        match entry {
+            Some(_) => self.stats.entries_reused += 1,
+            None => self.stats.entries_allocated += 1,
+        }
+        entry
+    }
+
+    /// Keep the entry of an order that left the book.
+    pub fn recycle_entry(&mut self, entry: E) {
+        if self.entries.len() < MAX_SPARE_ENTRIES {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Spare level queues held.
+    pub fn spare_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Spare entries held.
+    pub fn spare_entries(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recycled_levels_and_entries_are_reused() {
+        let mut pool: Pool<u32> = Pool::default();
+        let mut orders = pool.take_level();
+        orders.push(7);
+        pool.recycle_level(orders);
+        assert!(pool.take_level().is_empty());
+        assert_eq!(pool.take_entry(), None);
+        pool.recycle_entry(3);
+        assert_eq!(pool.take_entry(), Some(3));
+        assert_eq!(
+            pool.stats,
+            PoolStats {
+                levels_allocated: 1,
+                levels_reused: 1,
+                entries_allocated: 1,
+                entries_reused: 1,
+            }
+        );
+        pool.recycle_entry(4);
+        assert_eq!(pool.clone().spare_entries(), 0);
+        // One deep level's buffer is not kept.
+        pool.recycle_level(LevelQueue::with_capacity(MAX_SPARE_CAPACITY + 1));
+        assert_eq!(pool.spare_levels(), 0);
+    }
+}
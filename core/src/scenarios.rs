@@ -0,0 +1,178 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::book::Book;
+use crate::order::{OrderCore, Side};
+
+// ---------------------------------------------------------------------------
+// Scenarios — standard workloads for timing the engine
+// ---------------------------------------------------------------------------
+
+/// Symbols the workloads spread their orders over.
+const SYMBOLS: [&str; 4] = ["AAPL", "MSFT", "NVDA", "SPY"];
+/// Price levels per side and symbol.
+const LEVELS: u64 = 100;
+
+/// Deterministic xorshift generator, so every run builds the same workload.
+/// The benches build their feeds with it too.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Seed used by the standard workloads.
+    pub const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// A generator from a nonzero `seed`.
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+/// A standard workload on [`Book`]. The criterion benches and
+/// `pyorderbook.run_benchmarks()` both time these, so numbers from either
+/// describe the same work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scenario {
+    /// Passive orders resting across symbols and levels, none crossing.
+    InsertHeavy,
+    /// Cancelling every order of a seeded book, in random order.
+    CancelHeavy,
+    /// Marketable orders each filling two resting orders of a
+    /// seeded book.
+    MatchHeavy,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 3] = [
+        Scenario::InsertHeavy,
+        Scenario::CancelHeavy,
+        Scenario::MatchHeavy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::InsertHeavy => "insert_heavy",
+            Scenario::CancelHeavy => "cancel_heavy",
+            Scenario::MatchHeavy => "match_heavy",
+        }
+    }
+
+    /// The book and the `ops` messages of one run, built outside the timed
+    /// part.
+    pub fn prepare(self, ops: usize) -> Workload {
+        let mut rng = Rng::new(Rng::SEED);
+        let mut book = Book::new();
+        let steps = match self {
+            Scenario::InsertHeavy => (0..ops).map(|_| Step::Submit(passive(&mut rng))).collect(),
+            Scenario::CancelHeavy => {
+                let orders: Vec<OrderCore> = (0..ops).map(|_| passive(&mut rng)).collect();
+                let mut ids: Vec<Uuid> = orders.iter().map(|order| order.id).collect();
+                book.enqueue_batch(orders);
+                for idx in (1..ids.len()).rev() {
+                    ids.swap(idx, rng.below(idx as u64 + 1) as usize);
+                }
+                ids.into_iter().map(Step::Cancel).collect()
+            }
+            Scenario::MatchHeavy => {
+                // Two resting asks of 100 per marketable bid of 200 on the
+                // same symbol, so the book drains exactly by the last one.
+                let symbol = |idx: usize| SYMBOLS[idx % SYMBOLS.len()];
+                book.enqueue_batch(
+                    (0..ops * 2).map(|idx| {
+                        order(Side::Ask, symbol(idx / 2), 10_000 + rng.below(LEVELS), 100)
+                    }),
+                );
+                (0..ops)
+                    .map(|idx| Step::Submit(order(Side::Bid, symbol(idx), 10_000 + LEVELS, 200)))
+                    .collect()
+            }
+        };
+        Workload { book, steps }
+    }
+}
+
+/// A passive order a few levels off a fixed touch on a random symbol and
+/// side, so bids and asks never cross.
+fn passive(rng: &mut Rng) -> OrderCore {
+    let symbol = SYMBOLS[rng.below(SYMBOLS.len() as u64) as usize];
+    let offset = 1 + rng.below(LEVELS);
+    let (side, cents) = match rng.below(2) {
+        0 => (Side::Bid, 10_000 - offset),
+        _ => (Side::Ask, 10_000 + offset),
+    };
+    order(side, symbol, cents, 100 * (1 + rng.below(5) as i64))
+}
+
+fn order(side: Side, symbol: &str, cents: u64, quantity: i64) -> OrderCore {
+    OrderCore::new(side, symbol, Decimal::new(cents as i64, 2), quantity)
+        .expect("positive quantity")
+}
+
+/// One message of a workload.
+#[derive(Clone, Debug)]
+pub enum Step {
+    Submit(OrderCore),
+    Cancel(Uuid),
+}
+
+/// A prepared run of a [`Scenario`]: the book and the messages to apply.
+#[derive(Clone, Debug)]
+pub struct Workload {
+    pub book: Book,
+    pub steps: Vec<Step>,
+}
+
+impl Workload {
+    /// Apply every message, returning the trades made plus the orders
+    /// cancelled, which keeps the work from being optimized away.
+    pub fn run(&mut self) -> usize {
+        let mut done = 0;
+        for step in &self.steps {
+            done += match step {
+                Step::Submit(order) => self.book.match_order(order).trades.len(),
+                Step::Cancel(id) => usize::from(self.book.cancel(*id).is_some()),
+            };
+        }
+        done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenarios_do_the_work_they_are_named_for() {
+        let levels = |book: &Book| -> usize {
+            SYMBOLS
+                .iter()
+                .flat_map(|symbol| [Side::Bid, Side::Ask].map(|side| (symbol, side)))
+                .map(|(symbol, side)| book.depth(symbol, side, usize::MAX).len())
+                .sum()
+        };
+
+        let mut insert = Scenario::InsertHeavy.prepare(400);
+        assert_eq!(insert.run(), 0);
+        assert!(levels(&insert.book) > 100);
+
+        let mut cancel = Scenario::CancelHeavy.prepare(400);
+        assert_eq!(cancel.run(), 400);
+        assert_eq!(levels(&cancel.book), 0);
+
+        let mut matching = Scenario::MatchHeavy.prepare(400);
+        assert_eq!(matching.run(), 800);
+        assert_eq!(levels(&matching.book), 0);
+    }
+}
@@ -0,0 +1,65 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+// ---------------------------------------------------------------------------
+// Trade
+// ---------------------------------------------------------------------------
+
+/// A single execution between an incoming and a standing order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trade {
+    /// Unique id of this execution.
+    pub trade_id: Uuid,
+    pub incoming_order_id: Uuid,
+    pub standing_order_id: Uuid,
+    pub fill_quantity: i64,
+    pub fill_price: Decimal,
+    /// Limit price the standing order rested at.
+    pub standing_price: Decimal,
+    /// Quantity the standing order still has open after this fill.
+    pub standing_remaining: i64,
+}
+
+impl Trade {
+    /// Create a trade with a fresh id. The standing order is assumed to
+    /// rest at `fill_price` and be fully filled; see `with_standing`.
+    pub fn new(
+        incoming_order_id: Uuid,
+        standing_order_id: Uuid,
+        fill_quantity: i64,
+        fill_price: Decimal,
+    ) -> Self {
+        Trade {
+            trade_id: crate::id::new_id(),
+            incoming_order_id,
+            standing_order_id,
+            fill_quantity,
+            fill_price,
+            standing_price: fill_price,
+            standing_remaining: 0,
+        }
+    }
+
+    /// Record the standing order's resting price and open quantity after
+    /// the fill.
+    pub fn with_standing(mut self, price: Decimal, remaining: i64) -> Self {
+        self.standing_price = price;
+        self.standing_remaining = remaining;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MatchResult
+// ---------------------------------------------------------------------------
+
+/// Outcome of matching one incoming order against the book.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchResult {
+    /// Executions in the order they happened.
+    pub trades: Vec<Trade>,
+    /// Incoming quantity left unfilled.
+    pub remaining_quantity: i64,
+    /// Standing orders fully filled (and removed) by this match.
+    pub filled: Vec<Uuid>,
+}
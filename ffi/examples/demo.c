@@ -0,0 +1,43 @@
+// Build the library with `cargo build -p pyorderbook-ffi --release`, then:
+//   cc examples/demo.c -Iinclude ../target/release/libpyorderbook.a -lpthread -ldl -lm -o demo
+#include <stdio.h>
+
+#include "pyorderbook.h"
+
+static int check(PobStatus status) {
+  if (status != POB_STATUS_OK) {
+    fprintf(stderr, "error: %s\n", pob_status_message(status));
+    return 1;
+  }
+  return 0;
+}
+
+int main(void) {
+  PobBook *book = pob_book_new();
+  PobDecimal ask_price = {15025, 2};  // 150.25
+  PobDecimal bid_price = {151, 0};
+
+  if (check(pob_book_submit(book, POB_SIDE_ASK, "AAPL", ask_price, 100, NULL)) ||
+      check(pob_book_submit(book, POB_SIDE_BID, "AAPL", bid_price, 40, NULL))) {
+    pob_book_free(book);
+    return 1;
+  }
+
+  PobTrade trades[16];
+  size_t count = 0;
+  pob_book_poll_trades(book, trades, 16, &count);
+  for (size_t i = 0; i < count; i++) {
+    printf("filled %lld @ %lld e-%u\n", (long long)trades[i].quantity,
+           (long long)trades[i].price.mantissa, trades[i].price.scale);
+  }
+
+  PobLevel asks[5];
+  pob_book_snapshot(book, "AAPL", POB_SIDE_ASK, asks, 5, &count);
+  for (size_t i = 0; i < count; i++) {
+    printf("ask level %lld e-%u x %lld\n", (long long)asks[i].price.mantissa,
+           asks[i].price.scale, (long long)asks[i].quantity);
+  }
+
+  pob_book_free(book);
+  return 0;
+}
@@ -0,0 +1,137 @@
+#ifndef PYORDERBOOK_H
+#define PYORDERBOOK_H
+
+/* Generated by cbindgen from ffi/src/lib.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum PobSide {
+  POB_SIDE_BID = 0,
+  POB_SIDE_ASK = 1,
+} PobSide;
+
+// Result code returned by every fallible call.
+typedef enum PobStatus {
+  POB_STATUS_OK = 0,
+  // A required pointer argument was null.
+  POB_STATUS_NULL_POINTER = 1,
+  // A symbol was not valid UTF-8, a decimal scale exceeded 28 or a side
+  // was not a `PobSide` value.
+  POB_STATUS_INVALID_ARGUMENT = 2,
+  // Order quantity was zero or negative.
+  POB_STATUS_INVALID_QUANTITY = 3,
+  // The order id is not resting in the book.
+  POB_STATUS_NOT_FOUND = 4,
+  // A decimal value does not fit in a `PobDecimal`.
+  POB_STATUS_OVERFLOW = 5,
+} PobStatus;
+
+// Opaque book handle created by `pob_book_new`.
+typedef struct PobBook PobBook;
+
+// Exact decimal value: `mantissa * 10^-scale`, with `scale <= 28`.
+typedef struct PobDecimal {
+  int64_t mantissa;
+  uint32_t scale;
+} PobDecimal;
+
+// 128-bit UUID in RFC 4122 byte order.
+typedef struct PobUuid {
+  uint8_t bytes[16];
+} PobUuid;
+
+typedef struct PobTrade {
+  struct PobUuid trade_id;
+  struct PobUuid incoming_order_id;
+  struct PobUuid standing_order_id;
+  struct PobDecimal price;
+  int64_t quantity;
+} PobTrade;
+
+// One aggregated price level.
+typedef struct PobLevel {
+  struct PobDecimal price;
+  int64_t quantity;
+} PobLevel;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create an empty book. Release it with `pob_book_free`.
+struct PobBook *pob_book_new(void);
+
+// Destroy a book. Null is ignored.
+//
+// # Safety
+// `book` must be null or a pointer returned by `pob_book_new` that has not
+// been freed.
+void pob_book_free(struct PobBook *book);
+
+// Submit a limit order: it matches immediately and any remainder rests.
+// Resulting trades are queued for `pob_book_poll_trades`. The new order's
+// id is written to `out_order_id` when it is not null.
+//
+// # Safety
+// `book` must be a live book handle, `symbol` a NUL-terminated string and
+// `out_order_id` null or writable.
+enum PobStatus pob_book_submit(struct PobBook *book,
+                               uint32_t side,
+                               const char *symbol,
+                               struct PobDecimal price,
+                               int64_t quantity,
+                               struct PobUuid *out_order_id);
+
+// Cancel a resting order.
+//
+// # Safety
+// `book` must be a live book handle.
+enum PobStatus pob_book_cancel(struct PobBook *book, struct PobUuid order_id);
+
+// Number of executed trades waiting to be polled; 0 for a null book.
+//
+// # Safety
+// `book` must be null or a live book handle.
+size_t pob_book_pending_trades(const struct PobBook *book);
+
+// Move up to `capacity` queued trades, oldest first, into `out` and write
+// how many were copied to `out_len`. A trade whose price does not fit a
+// `PobDecimal` returns `Overflow`: the trades before it are still moved and
+// counted in `out_len`, and it stays at the head of the queue.
+//
+// # Safety
+// `book` must be a live book handle, `out` must have room for `capacity`
+// trades and `out_len` must be writable.
+enum PobStatus pob_book_poll_trades(struct PobBook *book,
+                                    struct PobTrade *out,
+                                    size_t capacity,
+                                    size_t *out_len);
+
+// Copy up to `capacity` aggregated levels of one side of `symbol`, best
+// first, into `out` and write how many were copied to `out_len`. Unknown
+// symbols yield zero levels. A level whose price does not fit a
+// `PobDecimal` returns `Overflow`, with `out_len` counting the levels above
+// it.
+//
+// # Safety
+// `book` must be a live book handle, `symbol` a NUL-terminated string,
+// `out` must have room for `capacity` levels and `out_len` must be writable.
+enum PobStatus pob_book_snapshot(const struct PobBook *book,
+                                 const char *symbol,
+                                 uint32_t side,
+                                 struct PobLevel *out,
+                                 size_t capacity,
+                                 size_t *out_len);
+
+// Static, NUL-terminated description of a status code, taken as `uint32_t`.
+const char *pob_status_message(uint32_t status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PYORDERBOOK_H */
@@ -0,0 +1,517 @@
+//! C ABI for the pyorderbook matching engine.
+//!
+//! Every function returns a `PobStatus` (or a value that cannot fail) and
+//! writes results through out-pointers, so callers in C, C++, Julia or R can
+//! check errors uniformly. Prices cross the boundary as `PobDecimal`
+//! (`mantissa * 10^-scale`) so no precision is lost to binary floats.
+//!
+//! Enum arguments are taken as `uint32_t` rather than as the enum types, since
+//! an out-of-range value in a Rust enum is undefined behaviour; unknown values
+//! return `InvalidArgument`.
+//!
+//! Executions are queued inside the book and drained with
+//! `pob_book_poll_trades`.
+
+use pyorderbook_core::{Book, OrderCore, Side, Trade};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::ffi::{c_char, CStr};
+use uuid::Uuid;
+
+// ---------------------------------------------------------------------------
+// Stable C structs
+// ---------------------------------------------------------------------------
+
+/// Result code returned by every fallible call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PobStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A symbol was not valid UTF-8, a decimal scale exceeded 28 or a side
+    /// was not a `PobSide` value.
+    InvalidArgument = 2,
+    /// Order quantity was zero or negative.
+    InvalidQuantity = 3,
+    /// The order id is not resting in the book.
+    NotFound = 4,
+    /// A decimal value does not fit in a `PobDecimal`.
+    Overflow = 5,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PobSide {
+    Bid = 0,
+    Ask = 1,
+}
+
+/// Exact decimal value: `mantissa * 10^-scale`, with `scale <= 28`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PobDecimal {
+    pub mantissa: i64,
+    pub scale: u32,
+}
+
+/// 128-bit UUID in RFC 4122 byte order.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PobUuid {
+    pub bytes: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PobTrade {
+    pub trade_id: PobUuid,
+    pub incoming_order_id: PobUuid,
+    pub standing_order_id: PobUuid,
+    pub price: PobDecimal,
+    pub quantity: i64,
+}
+
+/// One aggregated price level.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PobLevel {
+    pub price: PobDecimal,
+    pub quantity: i64,
+}
+
+/// Opaque book handle created by `pob_book_new`.
+#[derive(Default)]
+pub struct PobBook {
+    book: Book,
+    trades: VecDeque<Trade>,
+}
+
+/// Read a `PobSide` passed as `uint32_t`.
+fn read_side(side: u32) -> Result<Side, PobStatus> {
+    match side {
+        s if s == PobSide::Bid as u32 => Ok(Side::Bid),
+        s if s == PobSide::Ask as u32 => Ok(Side::Ask),
+        _ => Err(PobStatus::InvalidArgument),
+    }
+}
+
+impl From<Uuid> for PobUuid {
+    fn from(id: Uuid) -> Self {
+        PobUuid {
+            bytes: *id.as_bytes(),
+        }
+    }
+}
+
+impl From<PobUuid> for Uuid {
+    fn from(id: PobUuid) -> Self {
+        Uuid::from_bytes(id.bytes)
+    }
+}
+
+impl TryFrom<PobDecimal> for Decimal {
+    type Error = PobStatus;
+
+    fn try_from(value: PobDecimal) -> Result<Self, PobStatus> {
+        Decimal::try_from_i128_with_scale(value.mantissa.into(), value.scale)
+            .map_err(|_| PobStatus::InvalidArgument)
+    }
+}
+
+impl TryFrom<Decimal> for PobDecimal {
+    type Error = PobStatus;
+
+    fn try_from(value: Decimal) -> Result<Self, PobStatus> {
+        let mantissa = i64::try_from(value.mantissa()).map_err(|_| PobStatus::Overflow)?;
+        Ok(PobDecimal {
+            mantissa,
+            scale: value.scale(),
+        })
+    }
+}
+
+impl TryFrom<&Trade> for PobTrade {
+    type Error = PobStatus;
+
+    fn try_from(trade: &Trade) -> Result<Self, PobStatus> {
+        Ok(PobTrade {
+            trade_id: trade.trade_id.into(),
+            incoming_order_id: trade.incoming_order_id.into(),
+            standing_order_id: trade.standing_order_id.into(),
+            price: trade.fill_price.try_into()?,
+            quantity: trade.fill_quantity,
+        })
+    }
+}
+
+/// Read a NUL-terminated UTF-8 string argument.
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<&'a str, PobStatus> {
+    if ptr.is_null() {
+        return Err(PobStatus::NullPointer);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| PobStatus::InvalidArgument)
+}
+
+macro_rules! try_status {
+    ($expr:expr) => {
+        match $expr {
+            Ok(value) => value,
+            Err(status) => return status,
+        }
+    };
+}
+
+// ---------------------------------------------------------------------------
+// Book lifecycle
+// ---------------------------------------------------------------------------
+
+/// Create an empty book. Release it with `pob_book_free`.
+#[no_mangle]
+pub extern "C" fn pob_book_new() -> *mut PobBook {
+    Box::into_raw(Box::default())
+}
+
+/// Destroy a book. Null is ignored.
+///
+/// # Safety
+/// `book` must be null or a pointer returned by `pob_book_new` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_free(book: *mut PobBook) {
+    if !book.is_null() {
+        drop(Box::from_raw(book));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Orders
+// ---------------------------------------------------------------------------
+
+/// Submit a limit order: it matches immediately and any remainder rests.
+/// Resulting trades are queued for `pob_book_poll_trades`. The new order's
+/// id is written to `out_order_id` when it is not null.
+///
+/// # Safety
+/// `book` must be a live book handle, `symbol` a NUL-terminated string and
+/// `out_order_id` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_submit(
+    book: *mut PobBook,
+    side: u32,
+    symbol: *const c_char,
+    price: PobDecimal,
+    quantity: i64,
+    out_order_id: *mut PobUuid,
+) -> PobStatus {
+    let Some(handle) = book.as_mut() else {
+        return PobStatus::NullPointer;
+    };
+    let side = try_status!(read_side(side));
+    let symbol = try_status!(read_str(symbol));
+    let price = try_status!(Decimal::try_from(price));
+    let order = try_status!(
+        OrderCore::new(side, symbol, price, quantity).map_err(|_| PobStatus::InvalidQuantity)
+    );
+
+    let result = handle.book.match_order(&order);
+    handle.trades.extend(result.trades);
+    if let Some(out) = out_order_id.as_mut() {
+        *out = order.id.into();
+    }
+    PobStatus::Ok
+}
+
+/// Cancel a resting order.
+///
+/// # Safety
+/// `book` must be a live book handle.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_cancel(book: *mut PobBook, order_id: PobUuid) -> PobStatus {
+    let Some(handle) = book.as_mut() else {
+        return PobStatus::NullPointer;
+    };
+    match handle.book.cancel(order_id.into()) {
+        Some(_) => PobStatus::Ok,
+        None => PobStatus::NotFound,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Market data
+// ---------------------------------------------------------------------------
+
+/// Number of executed trades waiting to be polled; 0 for a null book.
+///
+/// # Safety
+/// `book` must be null or a live book handle.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_pending_trades(book: *const PobBook) -> usize {
+    book.as_ref().map_or(0, |handle| handle.trades.len())
+}
+
+/// Move up to `capacity` queued trades, oldest first, into `out` and write
+/// how many were copied to `out_len`. A trade whose price does not fit a
+/// `PobDecimal` returns `Overflow`: the trades before it are still moved and
+/// counted in `out_len`, and it stays at the head of the queue.
+///
+/// # Safety
+/// `book` must be a live book handle, `out` must have room for `capacity`
+/// trades and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_poll_trades(
+    book: *mut PobBook,
+    out: *mut PobTrade,
+    capacity: usize,
+    out_len: *mut usize,
+) -> PobStatus {
+    let (Some(handle), Some(out_len)) = (book.as_mut(), out_len.as_mut()) else {
+        return PobStatus::NullPointer;
+    };
+    if out.is_null() && capacity > 0 {
+        return PobStatus::NullPointer;
+    }
+    let mut count = 0;
+    let mut status = PobStatus::Ok;
+    for trade in handle.trades.iter().take(capacity) {
+        match PobTrade::try_from(trade) {
+            Ok(trade) => out.add(count).write(trade),
+            Err(error) => {
+                status = error;
+                break;
+            }
+        }
+        count += 1;
+    }
+    handle.trades.drain(..count);
+    *out_len = count;
+    status
+}
+
+/// Copy up to `capacity` aggregated levels of one side of `symbol`, best
+/// first, into `out` and write how many were copied to `out_len`. Unknown
+/// symbols yield zero levels. A level whose price does not fit a
+/// `PobDecimal` returns `Overflow`, with `out_len` counting the levels above
+/// it.
+///
+/// # Safety
+/// `book` must be a live book handle, `symbol` a NUL-terminated string,
+/// `out` must have room for `capacity` levels and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn pob_book_snapshot(
+    book: *const PobBook,
+    symbol: *const c_char,
+    side: u32,
+    out: *mut PobLevel,
+    capacity: usize,
+    out_len: *mut usize,
+) -> PobStatus {
+    let (Some(handle), Some(out_len)) = (book.as_ref(), out_len.as_mut()) else {
+        return PobStatus::NullPointer;
+    };
+    if out.is_null() && capacity > 0 {
+        return PobStatus::NullPointer;
+    }
+    let side = try_status!(read_side(side));
+    let symbol = try_status!(read_str(symbol));
+    let levels = handle.book.depth(symbol, side, capacity);
+    *out_len = 0;
+    for (i, &(price, quantity)) in levels.iter().enumerate() {
+        let price = try_status!(PobDecimal::try_from(price));
+        out.add(i).write(PobLevel { price, quantity });
+        *out_len = i + 1;
+    }
+    PobStatus::Ok
+}
+
+/// Static, NUL-terminated description of a status code, taken as `uint32_t`.
+#[no_mangle]
+pub extern "C" fn pob_status_message(status: u32) -> *const c_char {
+    let message: &'static CStr = match status {
+        s if s == PobStatus::Ok as u32 => c"ok",
+        s if s == PobStatus::NullPointer as u32 => c"required pointer argument was null",
+        s if s == PobStatus::InvalidArgument as u32 => c"invalid argument",
+        s if s == PobStatus::InvalidQuantity as u32 => c"order quantity must be greater than zero",
+        s if s == PobStatus::NotFound as u32 => c"order not found",
+        s if s == PobStatus::Overflow as u32 => c"decimal value out of range",
+        _ => c"unknown status",
+    };
+    message.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn price(mantissa: i64) -> PobDecimal {
+        PobDecimal { mantissa, scale: 2 }
+    }
+
+    #[test]
+    fn submit_poll_snapshot_and_cancel() {
+        unsafe {
+            let book = pob_book_new();
+            let symbol = c"AAPL".as_ptr();
+            let mut resting = PobUuid::default();
+            let status = pob_book_submit(
+                book,
+                PobSide::Ask as u32,
+                symbol,
+                price(15025),
+                10,
+                &mut resting,
+            );
+            assert_eq!(status, PobStatus::Ok);
+            let status = pob_book_submit(
+                book,
+                PobSide::Bid as u32,
+                symbol,
+                price(15100),
+                4,
+                ptr::null_mut(),
+            );
+            assert_eq!(status, PobStatus::Ok);
+            assert_eq!(pob_book_pending_trades(book), 1);
+
+            let mut trades = [PobTrade::default(); 4];
+            let mut len = 0;
+            let status = pob_book_poll_trades(book, trades.as_mut_ptr(), 4, &mut len);
+            assert_eq!((status, len), (PobStatus::Ok, 1));
+            assert_eq!(trades[0].standing_order_id, resting);
+            assert_eq!((trades[0].price, trades[0].quantity), (price(15025), 4));
+            assert_eq!(pob_book_pending_trades(book), 0);
+
+            let mut levels = [PobLevel::default(); 2];
+            let status = pob_book_snapshot(
+                book,
+                symbol,
+                PobSide::Ask as u32,
+                levels.as_mut_ptr(),
+                2,
+                &mut len,
+            );
+            assert_eq!((status, len), (PobStatus::Ok, 1));
+            assert_eq!(levels[0].quantity, 6);
+
+            assert_eq!(pob_book_cancel(book, resting), PobStatus::Ok);
+            assert_eq!(pob_book_cancel(book, resting), PobStatus::NotFound);
+            pob_book_free(book);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_return_error_codes() {
+        unsafe {
+            let book = pob_book_new();
+            let symbol = c"AAPL".as_ptr();
+            let bad_scale = PobDecimal {
+                mantissa: 1,
+                scale: 29,
+            };
+            let cases = [
+                (
+                    pob_book_submit(
+                        book,
+                        PobSide::Bid as u32,
+                        symbol,
+                        price(1),
+                        0,
+                        ptr::null_mut(),
+                    ),
+                    PobStatus::InvalidQuantity,
+                ),
+                (
+                    pob_book_submit(
+                        book,
+                        PobSide::Bid as u32,
+                        symbol,
+                        bad_scale,
+                        1,
+                        ptr::null_mut(),
+                    ),
+                    PobStatus::InvalidArgument,
+                ),
+                (
+                    pob_book_submit(
+                        book,
+                        PobSide::Bid as u32,
+                        ptr::null(),
+                        price(1),
+                        1,
+                        ptr::null_mut(),
+                    ),
+                    PobStatus::NullPointer,
+                ),
+                (
+                    pob_book_submit(
+                        ptr::null_mut(),
+                        PobSide::Bid as u32,
+                        symbol,
+                        price(1),
+                        1,
+                        ptr::null_mut(),
+                    ),
+                    PobStatus::NullPointer,
+                ),
+            ];
+            for (status, expected) in cases {
+                assert_eq!(status, expected);
+            }
+            let message = CStr::from_ptr(pob_status_message(PobStatus::NotFound as u32));
+            assert_eq!(message.to_str(), Ok("order not found"));
+            pob_book_free(book);
+        }
+    }
+
+    #[test]
+    fn unknown_enum_values_are_rejected() {
+        unsafe {
+            let book = pob_book_new();
+            let symbol = c"AAPL".as_ptr();
+            let status = pob_book_submit(book, 7, symbol, price(1), 1, ptr::null_mut());
+            assert_eq!(status, PobStatus::InvalidArgument);
+            let mut len = 0;
+            let status = pob_book_snapshot(book, symbol, 2, ptr::null_mut(), 0, &mut len);
+            assert_eq!(status, PobStatus::InvalidArgument);
+            let message = CStr::from_ptr(pob_status_message(99));
+            assert_eq!(message.to_str(), Ok("unknown status"));
+            pob_book_free(book);
+        }
+    }
+
+    #[test]
+    fn overflowing_trade_reports_how_many_were_moved() {
+        unsafe {
+            let book = pob_book_new();
+            let trade = |fill_price| Trade {
+                trade_id: Uuid::new_v4(),
+                incoming_order_id: Uuid::new_v4(),
+                standing_order_id: Uuid::new_v4(),
+                fill_quantity: 1,
+                fill_price,
+                standing_price: fill_price,
+                standing_remaining: 0,
+            };
+            (*book).trades.extend([
+                trade(Decimal::ONE),
+                trade(Decimal::MAX),
+                trade(Decimal::ONE),
+            ]);
+
+            let mut trades = [PobTrade::default(); 4];
+            let mut len = usize::MAX;
+            let status = pob_book_poll_trades(book, trades.as_mut_ptr(), 4, &mut len);
+            assert_eq!((status, len), (PobStatus::Overflow, 1));
+            assert_eq!(pob_book_pending_trades(book), 2);
+            let status = pob_book_poll_trades(book, trades.as_mut_ptr(), 4, &mut len);
+            assert_eq!((status, len), (PobStatus::Overflow, 0));
+            pob_book_free(book);
+        }
+    }
+}
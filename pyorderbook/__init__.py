@@ -1,3 +1,4 @@
+import logging
 from importlib.metadata import PackageNotFoundError, version
 
 try:
@@ -5,22 +6,61 @@ try:
 except PackageNotFoundError:
     __version__ = "v0.5.0"
 
+# Engine records go to the "pyorderbook.engine" logger; stay silent unless
+# the application configures logging.
+logging.getLogger("pyorderbook").addHandler(logging.NullHandler())
+
 _USING_RUST = False
 
 try:
     from pyorderbook._rust import (
+        Agent,
+        AuctionDesignation,
+        AuctionIndicatives,
+        AuctionResult,
         Book,
+        CascadeReport,
+        Condition,
+        DarkBook,
+        Event,
+        Instrument,
+        LevelChanged,
+        Nbbo,
         Order,
+        OrderAccepted,
+        OrderCancelled,
+        OrderEvent,
+        OrderExpired,
+        OrderHandle,
         OrderQueue,
+        OrderRejected,
+        OrderServer,
         OrderStatus,
         PriceLevel,
+        QuotesPulled,
+        Rejection,
+        ReplaySummary,
+        SessionStats,
+        SettlementLine,
+        ShardedBook,
+        SharedBookReader,
         Side,
         Snapshot,
         SnapshotLevel,
+        SplitApplied,
+        StateChanged,
+        StopActivation,
+        SymbolChanged,
+        TensorSnapshot,
         Trade,
         TradeBlotter,
+        TradeExecuted,
+        TradingState,
         ask,
         bid,
+        nbbo,
+        run_benchmarks,
+        sync_log_level,
     )
 
     _USING_RUST = True
@@ -31,8 +71,11 @@ except ImportError:
     from pyorderbook.snapshot import Snapshot, SnapshotLevel
     from pyorderbook.trade_blotter import Trade, TradeBlotter
 
+from pyorderbook.aio import AsyncBook, TradeStream  # noqa: E402
+
 easter_egg = "artificial lake"
 __all__ = [
+    "AsyncBook",
     "Book",
     "bid",
     "ask",
@@ -45,6 +88,49 @@ __all__ = [
     "SnapshotLevel",
     "Trade",
     "TradeBlotter",
+    "TradeStream",
     "easter_egg",
     "_USING_RUST",
 ]
+
+# Engine features below are implemented in the Rust backend only.
+if _USING_RUST:
+    __all__ += [
+        "Agent",
+        "AuctionDesignation",
+        "AuctionIndicatives",
+        "AuctionResult",
+        "CascadeReport",
+        "Condition",
+        "DarkBook",
+        "Event",
+        "Instrument",
+        "LevelChanged",
+        "Nbbo",
+        "nbbo",
+        "run_benchmarks",
+        "sync_log_level",
+        "OrderAccepted",
+        "OrderCancelled",
+        "OrderEvent",
+        "OrderExpired",
+        "OrderHandle",
+        "OrderRejected",
+        "OrderServer",
+        "QuotesPulled",
+        "Rejection",
+        "ReplaySummary",
+        "ShardedBook",
+        "SharedBookReader",
+        "SessionStats",
+        "SettlementLine",
+        "SplitApplied",
+        "StateChanged",
+        "StopActivation",
+        "SymbolChanged",
+        "TensorSnapshot",
+        "TradeExecuted",
+        "TradingState",
+        "MarketEnv",
+    ]
+    from pyorderbook.env import MarketEnv  # noqa: E402
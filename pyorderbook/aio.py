@@ -0,0 +1,114 @@
+"""asyncio front-end for the order book.
+
+`AsyncBook` serializes calls into a `Book` for event-loop code
+(paper-trading gateways, simulators mixing live and replayed data) and fans
+executions out to async trade streams.
+"""
+
+import asyncio
+from collections.abc import AsyncIterator
+from typing import Any, cast, overload
+
+from pyorderbook import Book, Order, Trade, TradeBlotter
+
+_CLOSED = object()
+
+
+class TradeStream:
+    """Async iterator over trades executed by an AsyncBook.
+
+    Trades are queued from the moment the stream is created, so none are
+    missed between subscribing and the first `async for` iteration.
+    """
+
+    def __init__(self, owner: "AsyncBook", maxsize: int = 0) -> None:
+        self._owner = owner
+        self._queue: asyncio.Queue[object] = asyncio.Queue(maxsize)
+
+    def __aiter__(self) -> AsyncIterator[Trade]:
+        return self
+
+    async def __anext__(self) -> Trade:
+        item = await self._queue.get()
+        if item is _CLOSED:
+            raise StopAsyncIteration
+        return cast(Trade, item)
+
+    def close(self) -> None:
+        """Stop receiving trades; pending iteration ends after queued ones."""
+        self._owner._streams.discard(self)
+        if self._queue.full():
+            self._queue.get_nowait()
+        self._queue.put_nowait(_CLOSED)
+
+
+class AsyncBook:
+    """asyncio wrapper around `Book`.
+
+    Every call is awaited and serialized with a lock: `match` and `cancel`
+    run with `asyncio.to_thread`, and other `Book` methods (`snapshot`,
+    `get_order`, ...) are forwarded as coroutines that wait for any pending
+    `match` or `cancel` before reading the book. Plain attributes are
+    returned unchanged.
+    """
+
+    def __init__(self, book: Book | None = None) -> None:
+        self.book = book if book is not None else Book()
+        self._lock = asyncio.Lock()
+        self._streams: set[TradeStream] = set()
+
+    @overload
+    async def match(self, orders: Order) -> TradeBlotter: ...
+
+    @overload
+    async def match(self, orders: list[Order]) -> list[TradeBlotter]: ...
+
+    async def match(self, orders: Order | list[Order]) -> TradeBlotter | list[TradeBlotter]:
+        """Match order(s) off the event loop and publish resulting trades."""
+        async with self._lock:
+            result = await asyncio.to_thread(self.book.match, orders)
+        blotters = result if isinstance(result, list) else [result]
+        for blotter in blotters:
+            self._publish(blotter)
+        return result
+
+    async def cancel(self, order: Order) -> None:
+        """Cancel a standing order off the event loop."""
+        async with self._lock:
+            await asyncio.to_thread(self.book.cancel, order)
+
+    def trades(self, maxsize: int = 0) -> TradeStream:
+        """Subscribe to executed trades: `async for trade in book.trades(): ...`.
+
+        A bounded stream (`maxsize > 0`) drops trades when the consumer
+        falls behind rather than blocking matching.
+        """
+        stream = TradeStream(self, maxsize)
+        self._streams.add(stream)
+        return stream
+
+    def close(self) -> None:
+        """End every open trade stream."""
+        for stream in list(self._streams):
+            stream.close()
+
+    def _publish(self, blotter: TradeBlotter) -> None:
+        trades = list(blotter.trades)
+        cascade = getattr(blotter, "cascade", None)
+        if cascade is not None:
+            trades.extend(cascade.trades)
+        for stream in self._streams:
+            for trade in trades:
+                if not stream._queue.full():
+                    stream._queue.put_nowait(trade)
+
+    def __getattr__(self, name: str) -> Any:
+        attr = getattr(self.book, name)
+        if not callable(attr):
+            return attr
+
+        async def call(*args: Any, **kwargs: Any) -> Any:
+            async with self._lock:
+                return attr(*args, **kwargs)
+
+        return call
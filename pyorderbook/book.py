@@ -1,3 +1,4 @@
+import copy
 import heapq as pq
 import logging
 from collections import defaultdict
@@ -17,6 +18,8 @@ Symbol: TypeAlias = str
 Price: TypeAlias = Decimal
 PriceLevelHeap: TypeAlias = list[PriceLevel]
 REQUIRED_PARQUET_COLUMNS: tuple[str, str, str, str] = ("side", "symbol", "price", "quantity")
+ROW_ERROR_POLICIES: tuple[str, str, str] = ("raise", "skip", "collect")
+RowError: TypeAlias = tuple[int, str]
 
 
 def _read_parquet_rows(path: str) -> list[dict[str, object]]:
@@ -60,40 +63,82 @@ class Book:
         return Order(Side.ASK, symbol, price, quantity)
 
     @overload
-    def match(self, orders: Order) -> TradeBlotter: ...
+    def match(self, orders: Order, *, skip_empty: bool = False) -> TradeBlotter: ...
 
     @overload
-    def match(self, orders: list[Order]) -> list[TradeBlotter]: ...
+    def match(self, orders: list[Order], *, skip_empty: bool = False) -> list[TradeBlotter]: ...
 
-    def match(self, orders: Order | list[Order]) -> TradeBlotter | list[TradeBlotter]:
+    def match(
+        self, orders: Order | list[Order], *, skip_empty: bool = False
+    ) -> TradeBlotter | list[TradeBlotter]:
         """Match incoming order(s) against standing orders in the book.
         :param order: incoming order
+        :param skip_empty: in list mode, omit blotters of orders that did not trade
         :returns: TradeBlotter object containing trade metadata on the trades
         which occured during the matching process
         """
         if isinstance(orders, list):
-            return [self.match(order) for order in orders]
+            blotters = [self.match(order) for order in orders]
+            return [b for b in blotters if b.trades] if skip_empty else blotters
         elif isinstance(orders, Order):
             return self._match(orders)
         raise ValueError("Invalid input type", type(orders))
 
-    def replay_parquet(self, path: str) -> list[TradeBlotter]:
-        """Replay an event-stream parquet file through the matching engine."""
+    def preview(self, order: Order) -> TradeBlotter:
+        """Return the TradeBlotter matching order would produce, leaving the book
+        and the order unchanged.
+        :param order: incoming order
+        :returns: would-be TradeBlotter
+        """
+        return copy.deepcopy(self)._match(copy.deepcopy(order))
+
+    def replay_parquet(
+        self, path: str, *, skip_empty: bool = False, on_error: str = "raise"
+    ) -> list[TradeBlotter] | tuple[list[TradeBlotter], list[RowError]]:
+        """Replay an event-stream parquet file through the matching engine.
+        :param on_error: "raise", "skip" or "collect" malformed rows; "collect"
+        returns (blotters, [(row_index, reason), ...])
+        """
+        orders, errors = self._parquet_orders(path, on_error)
         blotters: list[TradeBlotter] = []
-        for row_idx, row in enumerate(_read_parquet_rows(path)):
-            order = self._order_from_parquet_row(row, row_idx)
+        for order in orders:
             blotter = self.match(order)
             if not isinstance(blotter, TradeBlotter):
                 raise TypeError("Expected TradeBlotter from single-order replay")
-            blotters.append(blotter)
-        return blotters
-
-    def ingest_parquet(self, path: str) -> int:
-        """Ingest a snapshot parquet file directly as standing orders."""
-        rows = _read_parquet_rows(path)
-        for row_idx, row in enumerate(rows):
-            self.enqueue_order(self._order_from_parquet_row(row, row_idx))
-        return len(rows)
+            if blotter.trades or not skip_empty:
+                blotters.append(blotter)
+        return (blotters, errors) if on_error == "collect" else blotters
+
+    def ingest_parquet(
+        self, path: str, *, on_error: str = "raise"
+    ) -> int | tuple[int, list[RowError]]:
+        """Ingest a snapshot parquet file directly as standing orders.
+        :param on_error: as in replay_parquet; "collect" returns (count, errors)
+        """
+        orders, errors = self._parquet_orders(path, on_error)
+        for order in orders:
+            self.enqueue_order(order)
+        return (len(orders), errors) if on_error == "collect" else len(orders)
+
+    def _parquet_orders(self, path: str, on_error: str) -> tuple[list[Order], list[RowError]]:
+        """Orders of a parquet file's rows, and (row_index, reason) of rows
+        that could not be parsed when on_error is not "raise".
+        """
+        if on_error not in ROW_ERROR_POLICIES:
+            raise ValueError(
+                f"Invalid on_error '{on_error}'. Expected 'raise', 'skip' or 'collect'."
+            )
+        orders: list[Order] = []
+        errors: list[RowError] = []
+        for row_idx, row in enumerate(_read_parquet_rows(path)):
+            try:
+                orders.append(self._order_from_parquet_row(row, row_idx))
+            except ValueError as exc:
+                if on_error == "raise":
+                    raise
+                if on_error == "collect":
+                    errors.append((row_idx, str(exc)))
+        return orders, errors
 
     @classmethod
     def from_parquet(cls, path: str) -> "Book":
@@ -185,7 +230,17 @@ class Book:
         standing_order.quantity -= matched_quantity
         incoming_order.quantity -= matched_quantity
         fill_price = incoming_order.side.calc_fill_price(incoming_order.price, standing_order.price)
-        trade = Trade(incoming_order.id, standing_order.id, matched_quantity, fill_price)
+        standing_order.record_fill(fill_price, matched_quantity)
+        incoming_order.record_fill(fill_price, matched_quantity)
+        trade = Trade(
+            incoming_order.id,
+            standing_order.id,
+            matched_quantity,
+            fill_price,
+            symbol=standing_order.symbol,
+            standing_price=standing_order.price,
+            standing_remaining=standing_order.quantity,
+        )
         logger.debug("Filled Order: %s", trade)
         return trade
 
@@ -198,6 +253,17 @@ class Book:
         """
         return self.level_map[symbol][side].get(price, None)
 
+    def level_orders(
+        self, symbol: str, side: Side, price: Decimal, limit: int | None = None
+    ) -> list[tuple[UUID, int]]:
+        """Return (order id, quantity) pairs at a price level in priority order
+        :param limit: keep only the first `limit` orders
+        """
+        level = self.level_map[symbol][side].get(price)
+        if level is None:
+            return []
+        return [(order.id, order.quantity) for order in level.orders.values()][:limit]
+
     def enqueue_order(self, order: Order) -> None:
         """Add order to book.
         - enqueue order to price level
@@ -271,8 +337,19 @@ class Book:
         return self.order_map
 
     def get_levels(self) -> defaultdict[str, dict[Side, PriceLevelHeap]]:
+        """Return the per-side level heaps; only the first entry is guaranteed best.
+        Use bid_prices/ask_prices for sorted views.
+        """
         return self.levels
 
+    def bid_prices(self, symbol: Symbol) -> list[Price]:
+        """Return bid prices of a symbol, highest first"""
+        return sorted(self.level_map[symbol][Side.BID], reverse=True)
+
+    def ask_prices(self, symbol: Symbol) -> list[Price]:
+        """Return ask prices of a symbol, lowest first"""
+        return sorted(self.level_map[symbol][Side.ASK])
+
     def get_level_map(self) -> defaultdict[str, dict[Side, dict[Price, PriceLevel]]]:
         return self.level_map
 
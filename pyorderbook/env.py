@@ -0,0 +1,266 @@
+"""Gymnasium-style trading environment around a `Book`.
+
+`MarketEnv` replays (or generates) an order flow into a fresh book on every
+`reset`, lets an agent act between slices of that flow, and scores it on
+mark-to-market PnL. It follows the Gymnasium `reset`/`step` protocol
+without depending on gymnasium.
+"""
+
+from collections.abc import Callable, Iterable, Iterator
+from decimal import Decimal
+from typing import Any
+
+from pyorderbook import Book, Order, OrderRejected, Rejection, Side, TradeBlotter, ask, bid
+
+FlowItem = Order | tuple[int, Order]
+Flow = Iterable[FlowItem] | Callable[[], Iterable[FlowItem]]
+
+
+def _as_array(tensor: Any) -> Any:
+    """Wrap a TensorSnapshot in numpy when it is installed (without copying)."""
+    try:
+        import numpy
+    except ImportError:
+        return tensor
+    return numpy.asarray(tensor)
+
+
+class MarketEnv:
+    """Single-symbol market simulation with reset/step semantics.
+
+    `flow` is the background order flow: Orders, or `(timestamp, Order)`
+    pairs that first advance the book's clock. Pass a callable returning a
+    fresh iterable to generate a new episode on each reset; any other
+    iterable is replayed from the start.
+
+    Each `step(action)` applies the agent's action, then feeds the next
+    `events_per_step` flow items. An action is None (do nothing), a
+    `("bid" | "ask", price, quantity)` order spec, `("cancel", order)`,
+    `("cancel_all",)` for every open agent order, or a list of these.
+    Agent orders are booked to `account` in `symbol`.
+
+    The observation is the book's `tensor_snapshot(symbol, depth)`, or its
+    `feature_vector(symbol)` when `features` names features to enable
+    (see `Book.enable_features`); as a numpy array when numpy is
+    installed. The reward is the change in the agent's PnL: cash from its
+    fills plus its position marked at the midpoint (the last mark while
+    the book is one-sided). An episode terminates when the flow runs out
+    and is truncated after `max_steps` steps.
+
+    Orders beyond `max_order_quantity`, or that could take the position
+    (counting working orders on the same side) past `max_position`, are
+    refused with code "risk_limit"; prices further than `price_band` (a
+    fraction) from the mark with "price_band". Orders the book refuses
+    carry its code, e.g. "bad_tick". With `on_reject="report"` (the
+    default) each refused action is listed as a `Rejection` under
+    `info["rejections"]` and the episode carries on, so malformed actions
+    ("unknown_action") or cancels of orders no longer working
+    ("unknown_order") cannot crash a training loop. `on_reject="raise"`
+    raises `OrderRejected` instead.
+    """
+
+    def __init__(
+        self,
+        flow: Flow,
+        symbol: str,
+        *,
+        events_per_step: int = 1,
+        depth: int = 10,
+        features: list[str] | None = None,
+        account: str = "agent",
+        max_steps: int | None = None,
+        book_factory: Callable[[], Book] = Book,
+        on_reject: str = "report",
+        max_position: int | None = None,
+        max_order_quantity: int | None = None,
+        price_band: float | None = None,
+    ) -> None:
+        if events_per_step < 1:
+            raise ValueError("events_per_step must be at least 1")
+        if on_reject not in ("report", "raise"):
+            raise ValueError(f"Invalid on_reject {on_reject!r}. Expected 'report' or 'raise'.")
+        self.flow = flow
+        self.symbol = symbol
+        self.events_per_step = events_per_step
+        self.depth = depth
+        self.features = features
+        self.account = account
+        self.max_steps = max_steps
+        self.book_factory = book_factory
+        self.on_reject = on_reject
+        self.max_position = max_position
+        self.max_order_quantity = max_order_quantity
+        self.price_band = None if price_band is None else Decimal(str(price_band))
+        self.book: Book = book_factory()
+        self._events: Iterator[FlowItem] = iter(())
+        self._orders: dict[Any, Order] = {}
+        self._rejections: list[Rejection] = []
+        self._mark: Decimal | None = None
+        self._pnl = Decimal(0)
+        self._steps = 0
+        self._exhausted = False
+
+    def reset(
+        self, *, seed: int | None = None, options: dict[str, Any] | None = None
+    ) -> tuple[Any, dict[str, Any]]:
+        """Start an episode on a fresh book; returns (observation, info)."""
+        del seed, options
+        self.book = self.book_factory()
+        if self.features:
+            self.book.enable_features(self.symbol, self.features)
+        flow = self.flow() if callable(self.flow) else self.flow
+        self._events = iter(flow)
+        self._orders = {}
+        self._rejections = []
+        self._mark = None
+        self._pnl = Decimal(0)
+        self._steps = 0
+        self._exhausted = False
+        return self._observe(), self._info([])
+
+    def step(self, action: Any) -> tuple[Any, float, bool, bool, dict[str, Any]]:
+        """Apply `action`, advance the flow; returns (observation, reward,
+        terminated, truncated, info)."""
+        self._rejections = []
+        blotters = self._act(action)
+        for _ in range(self.events_per_step):
+            item = next(self._events, None)
+            if item is None:
+                self._exhausted = True
+                break
+            self._feed(item)
+        self._steps += 1
+        pnl = self.pnl()
+        reward = float(pnl - self._pnl)
+        self._pnl = pnl
+        truncated = self.max_steps is not None and self._steps >= self.max_steps
+        return self._observe(), reward, self._exhausted, truncated, self._info(blotters)
+
+    def position(self) -> int:
+        """Agent's net quantity in the symbol."""
+        return sum(line.net_quantity for line in self._settlement())
+
+    def pnl(self) -> Decimal:
+        """Agent's cash from fills plus its position at the current mark."""
+        lines = self._settlement()
+        cash = sum((line.cash_delta for line in lines), Decimal(0))
+        position = sum(line.net_quantity for line in lines)
+        if self._update_mark() is None or position == 0:
+            return cash
+        instrument = self.book.instrument(self.symbol)
+        multiplier = instrument.multiplier if instrument is not None else Decimal(1)
+        return cash + position * self._mark * multiplier
+
+    def open_orders(self) -> list[Order]:
+        """The agent's orders still working in the book."""
+        live = [o for o in self._orders.values() if self.book.get_order(o.id) is not None]
+        self._orders = {o.id: o for o in live}
+        return live
+
+    def _update_mark(self) -> Decimal | None:
+        top = self.book.snapshot(self.symbol, 1)
+        if top is not None and top.midpoint is not None:
+            self._mark = top.midpoint
+        return self._mark
+
+    def _settlement(self) -> list[Any]:
+        lines = self.book.settlement_report(self.account)
+        return [line for line in lines if line.symbol == self.symbol]
+
+    def _act(self, action: Any) -> list[TradeBlotter]:
+        if action is None:
+            return []
+        if isinstance(action, list):
+            return [blotter for one in action for blotter in self._act(one)]
+        kind, *args = action if isinstance(action, tuple) and action else (None,)
+        if kind == "cancel_all" and not args:
+            for order in self.open_orders():
+                self.book.cancel(order)
+            return []
+        if kind == "cancel" and len(args) == 1:
+            (order,) = args
+            if self.book.get_order(order.id) is None:
+                self._reject("unknown_order", f"Order {order.id} is not working", order.id)
+            else:
+                self.book.cancel(order)
+            return []
+        make = {"bid": bid, "ask": ask}.get(kind)
+        if make is None or len(args) != 2:
+            self._reject("unknown_action", f"Unknown action {action!r}")
+            return []
+        price, quantity = args
+        order = make(self.symbol, price, quantity, account=self.account)
+        refusal = self._check_limits(order)
+        if refusal is not None:
+            self._reject(*refusal, order.id)
+            return []
+        try:
+            blotter = self.book.match(order)
+        except ValueError as err:
+            if self.on_reject == "raise":
+                raise
+            self._reject(getattr(err, "code", "invalid"), str(err), order.id)
+            return []
+        self._orders[order.id] = order
+        return [blotter]
+
+    def _check_limits(self, order: Order) -> tuple[str, str] | None:
+        """(code, reason) if `order` breaks the env's own limits."""
+        if self.max_order_quantity is not None and order.quantity > self.max_order_quantity:
+            return (
+                "risk_limit",
+                f"Quantity {order.quantity} exceeds max_order_quantity "
+                f"{self.max_order_quantity}",
+            )
+        if self.max_position is not None:
+            working = 0
+            for open_order in self.open_orders():
+                handle = self.book.get_order(open_order.id)
+                if handle is not None and open_order.side == order.side:
+                    working += handle.quantity
+            sign = 1 if order.side == Side.BID else -1
+            exposure = self.position() + sign * (working + order.quantity)
+            if abs(exposure) > self.max_position:
+                return (
+                    "risk_limit",
+                    f"Order could take the position to {exposure}, beyond max_position "
+                    f"{self.max_position}",
+                )
+        mark = self._update_mark()
+        if self.price_band is not None and mark is not None:
+            if abs(order.price - mark) > self.price_band * abs(mark):
+                return (
+                    "price_band",
+                    f"Price {order.price} is outside the {self.price_band} band around "
+                    f"the mark {mark}",
+                )
+        return None
+
+    def _reject(self, code: str, reason: str, order_id: Any = None) -> None:
+        if self.on_reject == "raise":
+            err = OrderRejected(reason)
+            err.code = code
+            raise err
+        self._rejections.append(Rejection(code, reason, order_id, self.book.current_time))
+
+    def _feed(self, item: FlowItem) -> None:
+        if isinstance(item, tuple):
+            timestamp, order = item
+            self.book.advance_time(timestamp)
+        else:
+            order = item
+        self.book.match(order)
+
+    def _observe(self) -> Any:
+        if self.features:
+            return _as_array(self.book.feature_vector(self.symbol))
+        return _as_array(self.book.tensor_snapshot(self.symbol, self.depth))
+
+    def _info(self, blotters: list[TradeBlotter]) -> dict[str, Any]:
+        return {
+            "blotters": blotters,
+            "rejections": self._rejections,
+            "position": self.position(),
+            "pnl": self._pnl,
+            "steps": self._steps,
+        }
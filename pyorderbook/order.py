@@ -1,4 +1,5 @@
 import operator
+from copy import copy
 from collections.abc import Callable
 from decimal import Decimal
 from enum import StrEnum, auto
@@ -55,6 +56,22 @@ class Order:
         self.symbol: Symbol = symbol
         self.side: Side = side
         self.original_quantity: int = quantity
+        self.filled_quantity: int = 0
+        self.last_fill_price: Price | None = None
+        self._fill_notional: Decimal = Decimal(0)
+
+    def record_fill(self, price: Price, quantity: int) -> None:
+        """Account for an execution of `quantity` at `price`"""
+        self.filled_quantity += quantity
+        self.last_fill_price = price
+        self._fill_notional += price * quantity
+
+    @property
+    def average_fill_price(self) -> Price | None:
+        """Volume-weighted price of the fills so far, or None"""
+        if not self.filled_quantity:
+            return None
+        return self._fill_notional / self.filled_quantity
 
     @property
     def status(self) -> OrderStatus:
@@ -85,6 +102,10 @@ class Order:
     def get_status(self) -> OrderStatus:
         return self.status
 
+    def snapshot(self) -> "Order":
+        """Return a frozen copy of the order's current state"""
+        return copy(self)
+
 
 bid = partial(Order, Side.BID)
 ask = partial(Order, Side.ASK)
@@ -3,7 +3,7 @@ from decimal import Decimal
 from typing import TypeAlias
 from uuid import UUID
 
-from pyorderbook.order import Order
+from pyorderbook.order import Order, Side
 
 Price: TypeAlias = Decimal
 
@@ -16,6 +16,15 @@ class Trade:
     standing_order_id: UUID
     fill_quantity: int
     fill_price: Price
+    symbol: str = ""
+    # price the standing order rested at; defaults to fill_price
+    standing_price: Price | None = None
+    # standing order's open quantity right after this fill
+    standing_remaining: int = 0
+
+    def __post_init__(self) -> None:
+        if self.standing_price is None:
+            self.standing_price = self.fill_price
 
     def get_incoming_order_id(self) -> UUID:
         return self.incoming_order_id
@@ -41,9 +50,23 @@ class TradeBlotter:
     trades: list[Trade]
     total_cost: float = field(default=0, init=False)
     average_price: float = field(default=0, init=False)
+    # distinct price levels traded through, worst fill and per-unit
+    # volume-weighted improvement over the order's limit
+    levels_walked: int = field(default=0, init=False)
+    worst_fill_price: Price | None = field(default=None, init=False)
+    price_improvement: Decimal = field(default=Decimal(0), init=False)
 
     def __post_init__(self) -> None:
+        self.levels_walked = len({trade.fill_price for trade in self.trades})
         if self.trades:
+            self.worst_fill_price = self.trades[-1].fill_price
+            sign = 1 if self.order.side == Side.BID else -1
+            improvement = sum(
+                sign * (self.order.price - trade.fill_price) * trade.fill_quantity
+                for trade in self.trades
+            )
+            filled = sum(trade.fill_quantity for trade in self.trades)
+            self.price_improvement = round(Decimal(improvement) / filled, 2)
             self.total_cost = round(
                 float(sum(trade.fill_price * trade.fill_quantity for trade in self.trades)), 2
             )
@@ -0,0 +1,191 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::order::{decimal_to_py, Order, Side};
+use crate::reject::Rejection;
+use crate::strategy::LatencyQueue;
+use crate::trade::TradeBlotter;
+
+// ---------------------------------------------------------------------------
+// Agent — one participant of a multi-agent simulation
+// ---------------------------------------------------------------------------
+
+/// A strategy taking part in `Book.simulate`, trading under its own
+/// account with its own latency and risk limits.
+///
+/// `strategy` is called like a `replay_with_strategy` strategy:
+/// `on_event(book, blotter)` after every source row, and optionally
+/// `on_reject(book, rejection)` when one of its orders is refused. Every
+/// order it submits is booked to `account`. Orders above
+/// `max_order_quantity`, or that could take its position in the symbol
+/// (counting its working orders on the same side) past `max_position`,
+/// are refused with code "risk_limit".
+///
+/// After a simulation, `blotters` holds the TradeBlotters of the agent's
+/// own orders, `rejections` its refused orders, `positions` its net
+/// quantity per symbol, and `pnl` its cash from fills plus those
+/// positions marked at the midpoint (else the last trade price).
+#[pyclass]
+pub struct Agent {
+    pub strategy: PyObject,
+    pub account: String,
+    pub latency: i64,
+    pub max_position: Option<i64>,
+    pub max_order_quantity: Option<i64>,
+    pub queue: LatencyQueue,
+    pub blotters: Vec<Py<TradeBlotter>>,
+    pub rejections: Vec<Rejection>,
+    pub positions: HashMap<String, i64>,
+    pub pnl: Decimal,
+}
+
+impl Agent {
+    /// Forget the results and in-flight actions of a previous simulation.
+    pub fn reset(&mut self) {
+        self.queue = LatencyQueue::new(self.latency);
+        self.blotters.clear();
+        self.rejections.clear();
+        self.positions.clear();
+        self.pnl = Decimal::ZERO;
+    }
+
+    /// Why `order` breaks the agent's risk limits, given its net
+    /// `position` in the symbol and the quantity of its `working` orders
+    /// on the order's side; None if it does not.
+    pub fn check_limits(&self, order: &Order, position: i64, working: i64) -> Option<String> {
+        if let Some(limit) = self.max_order_quantity.filter(|&l| order.quantity > l) {
+            return Some(format!(
+                "Quantity {} exceeds {}'s max_order_quantity {}",
+                order.quantity, self.account, limit
+            ));
+        }
+        let sign = match order.side {
+            Side::BID => 1,
+            Side::ASK => -1,
+        };
+        let exposure = position + sign * (working + order.quantity);
+        self.max_position
+            .filter(|&limit| exposure.abs() > limit)
+            .map(|limit| {
+                format!(
+                    "Order could take {}'s position in {} to {}, beyond max_position {}",
+                    self.account, order.symbol, exposure, limit
+                )
+            })
+    }
+}
+
+#[pymethods]
+impl Agent {
+    #[new]
+    #[pyo3(signature = (strategy, account, *, latency = 0, max_position = None, max_order_quantity = None))]
+    fn new(
+        strategy: PyObject,
+        account: String,
+        latency: i64,
+        max_position: Option<i64>,
+        max_order_quantity: Option<i64>,
+    ) -> PyResult<Self> {
+        if latency < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "latency cannot be negative",
+            ));
+        }
+        if max_position.is_some_and(|l| l < 0) || max_order_quantity.is_some_and(|l| l <= 0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "max_position cannot be negative and max_order_quantity must be positive",
+            ));
+        }
+        Ok(Agent {
+            strategy,
+            account,
+            latency,
+            max_position,
+            max_order_quantity,
+            queue: LatencyQueue::new(latency),
+            blotters: Vec::new(),
+            rejections: Vec::new(),
+            positions: HashMap::new(),
+            pnl: Decimal::ZERO,
+        })
+    }
+
+    #[getter]
+    fn strategy(&self, py: Python<'_>) -> PyObject {
+        self.strategy.clone_ref(py)
+    }
+
+    #[getter]
+    fn account(&self) -> &str {
+        &self.account
+    }
+
+    #[getter]
+    fn latency(&self) -> i64 {
+        self.latency
+    }
+
+    #[getter]
+    fn max_position(&self) -> Option<i64> {
+        self.max_position
+    }
+
+    #[getter]
+    fn max_order_quantity(&self) -> Option<i64> {
+        self.max_order_quantity
+    }
+
+    #[getter]
+    fn blotters(&self, py: Python<'_>) -> Vec<Py<TradeBlotter>> {
+        self.blotters.iter().map(|b| b.clone_ref(py)).collect()
+    }
+
+    #[getter]
+    fn rejections(&self) -> Vec<Rejection> {
+        self.rejections.clone()
+    }
+
+    #[getter]
+    fn positions(&self) -> HashMap<String, i64> {
+        self.positions.clone()
+    }
+
+    #[getter]
+    fn pnl(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.pnl)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Agent(account={:?}, latency={}, pnl={})",
+            self.account, self.latency, self.pnl
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_count_position_and_working_orders() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let agent = Agent::new(py.None(), "a".to_string(), 0, Some(10), Some(5)).unwrap();
+            let buy =
+                |quantity| Order::try_new(Side::BID, "X".to_string(), 10.0, quantity).unwrap();
+            let sell = Order::try_new(Side::ASK, "X".to_string(), 10.0, 5).unwrap();
+            assert!(agent.check_limits(&buy(6), 0, 0).is_some());
+            assert!(agent.check_limits(&buy(5), 3, 2).is_none());
+            assert!(agent.check_limits(&buy(5), 3, 3).is_some());
+            // Selling reduces a long position.
+            assert!(agent.check_limits(&sell, 12, 0).is_none());
+        });
+    }
+}
@@ -0,0 +1,470 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::order::{Order, Side};
+use crate::trade::Trade;
+
+// ---------------------------------------------------------------------------
+// TradingState — per-symbol session phase
+// ---------------------------------------------------------------------------
+
+/// Trading phase of a symbol.
+///
+/// - PRE_OPEN: orders accumulate without matching until the opening uncross.
+/// - CONTINUOUS: regular price-time matching (the default).
+/// - CLOSED: after the closing uncross; new orders are rejected.
+/// - HALTED: new orders are queued unmatched until `Book.resume`.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum TradingState {
+    PRE_OPEN,
+    CONTINUOUS,
+    CLOSED,
+    HALTED,
+}
+
+#[pymethods]
+impl TradingState {
+    pub(crate) fn __str__(&self) -> &'static str {
+        match self {
+            TradingState::PRE_OPEN => "pre_open",
+            TradingState::CONTINUOUS => "continuous",
+            TradingState::CLOSED => "closed",
+            TradingState::HALTED => "halted",
+        }
+    }
+
+    fn __repr__(&self) -> &'static str {
+        match self {
+            TradingState::PRE_OPEN => "TradingState.PRE_OPEN",
+            TradingState::CONTINUOUS => "TradingState.CONTINUOUS",
+            TradingState::CLOSED => "TradingState.CLOSED",
+            TradingState::HALTED => "TradingState.HALTED",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuctionDesignation — on-open / on-close order types
+// ---------------------------------------------------------------------------
+
+/// Which auction an order is reserved for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AuctionKind {
+    Open,
+    Close,
+}
+
+impl AuctionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuctionKind::Open => "open",
+            AuctionKind::Close => "close",
+        }
+    }
+
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "open" => Ok(AuctionKind::Open),
+            "close" => Ok(AuctionKind::Close),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid auction '{}'. Expected 'open' or 'close'.",
+                text
+            ))),
+        }
+    }
+}
+
+/// Auction-only order designation.
+///
+/// Designated orders never match continuously; they wait for the
+/// corresponding uncross. Market variants (MOO/MOC) ignore the order price.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum AuctionDesignation {
+    /// Market-on-open.
+    MOO,
+    /// Limit-on-open.
+    LOO,
+    /// Market-on-close.
+    MOC,
+    /// Limit-on-close.
+    LOC,
+}
+
+#[pymethods]
+impl AuctionDesignation {
+    fn __str__(&self) -> &'static str {
+        match self {
+            AuctionDesignation::MOO => "moo",
+            AuctionDesignation::LOO => "loo",
+            AuctionDesignation::MOC => "moc",
+            AuctionDesignation::LOC => "loc",
+        }
+    }
+
+    fn __repr__(&self) -> &'static str {
+        match self {
+            AuctionDesignation::MOO => "AuctionDesignation.MOO",
+            AuctionDesignation::LOO => "AuctionDesignation.LOO",
+            AuctionDesignation::MOC => "AuctionDesignation.MOC",
+            AuctionDesignation::LOC => "AuctionDesignation.LOC",
+        }
+    }
+}
+
+impl AuctionDesignation {
+    pub fn kind(self) -> AuctionKind {
+        match self {
+            AuctionDesignation::MOO | AuctionDesignation::LOO => AuctionKind::Open,
+            AuctionDesignation::MOC | AuctionDesignation::LOC => AuctionKind::Close,
+        }
+    }
+
+    pub fn is_market(self) -> bool {
+        matches!(self, AuctionDesignation::MOO | AuctionDesignation::MOC)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Uncross calculation — pure Rust
+// ---------------------------------------------------------------------------
+
+/// One order participating in an uncross.
+#[derive(Clone, Debug)]
+pub struct AuctionOrder {
+    pub id: Uuid,
+    pub side: Side,
+    /// `None` for market orders, which accept any auction price.
+    pub limit: Option<Decimal>,
+    pub quantity: i64,
+    /// Arrival sequence used for time priority.
+    pub seq: u64,
+}
+
+impl AuctionOrder {
+    fn accepts(&self, price: Decimal) -> bool {
+        match self.limit {
+            None => true,
+            Some(limit) => self.side.price_is_matchable(limit, price),
+        }
+    }
+}
+
+/// Result of an uncross: the single clearing price and the fills.
+#[derive(Clone, Debug)]
+pub struct Uncross {
+    pub price: Decimal,
+    pub volume: i64,
+    /// Signed surplus at the clearing price: positive = excess buy interest.
+    pub imbalance: i64,
+    /// (bid id, ask id, quantity) in execution order.
+    pub fills: Vec<(Uuid, Uuid, i64)>,
+}
+
+/// Find the clearing price that maximizes executable volume.
+///
+/// Ties are broken by the smallest absolute imbalance, then by proximity
+/// to `reference`, then by the lowest price. When only market orders are
+/// present the reference price is used. Returns `None` if nothing crosses.
+pub fn compute_uncross(orders: &[AuctionOrder], reference: Option<Decimal>) -> Option<Uncross> {
+    let mut candidates: Vec<Decimal> = orders.iter().filter_map(|o| o.limit).collect();
+    if candidates.is_empty() {
+        candidates.extend(reference);
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let mut best: Option<(Decimal, i64, i64)> = None;
+    for &price in &candidates {
+        let (buy, sell) =
+            orders
+                .iter()
+                .filter(|o| o.accepts(price))
+                .fold((0i64, 0i64), |(b, s), o| match o.side {
+                    Side::BID => (b + o.quantity, s),
+                    Side::ASK => (b, s + o.quantity),
+                });
+        let volume = buy.min(sell);
+        if volume == 0 {
+            continue;
+        }
+        let imbalance = buy - sell;
+        let better = match best {
+            None => true,
+            Some((best_price, best_volume, best_imbalance)) => {
+                // Candidates ascend, so strict comparisons keep the lowest
+                // price when every criterion ties.
+                let distance = |p: Decimal| reference.map(|r| (p - r).abs());
+                volume > best_volume
+                    || (volume == best_volume && imbalance.abs() < best_imbalance.abs())
+                    || (volume == best_volume
+                        && imbalance.abs() == best_imbalance.abs()
+                        && distance(price) < distance(best_price))
+            }
+        };
+        if better {
+            best = Some((price, volume, imbalance));
+        }
+    }
+    let (price, volume, imbalance) = best?;
+
+    // Priority: market orders first, then by limit price, then arrival.
+    let mut bids: Vec<&AuctionOrder> = orders
+        .iter()
+        .filter(|o| o.side == Side::BID && o.accepts(price))
+        .collect();
+    bids.sort_by(|a, b| {
+        b.limit
+            .is_none()
+            .cmp(&a.limit.is_none())
+            .then(b.limit.cmp(&a.limit))
+            .then(a.seq.cmp(&b.seq))
+    });
+    let mut asks: Vec<&AuctionOrder> = orders
+        .iter()
+        .filter(|o| o.side == Side::ASK && o.accepts(price))
+        .collect();
+    asks.sort_by(|a, b| {
+        b.limit
+            .is_none()
+            .cmp(&a.limit.is_none())
+            .then(a.limit.cmp(&b.limit))
+            .then(a.seq.cmp(&b.seq))
+    });
+
+    let mut fills = Vec::new();
+    let (mut bi, mut ai) = (0, 0);
+    let (mut bid_left, mut ask_left) = (
+        bids.first().map_or(0, |o| o.quantity),
+        asks.first().map_or(0, |o| o.quantity),
+    );
+    let mut remaining = volume;
+    while remaining > 0 && bi < bids.len() && ai < asks.len() {
+        let qty = bid_left.min(ask_left).min(remaining);
+        fills.push((bids[bi].id, asks[ai].id, qty));
+        remaining -= qty;
+        bid_left -= qty;
+        ask_left -= qty;
+        if bid_left == 0 {
+            bi += 1;
+            bid_left = bids.get(bi).map_or(0, |o| o.quantity);
+        }
+        if ask_left == 0 {
+            ai += 1;
+            ask_left = asks.get(ai).map_or(0, |o| o.quantity);
+        }
+    }
+
+    Some(Uncross {
+        price,
+        volume,
+        imbalance,
+        fills,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// AuctionResult — returned by Book.uncross()
+// ---------------------------------------------------------------------------
+
+/// Outcome of an opening or closing auction.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct AuctionResult {
+    pub auction: AuctionKind,
+    pub price: Option<Decimal>,
+    pub volume: i64,
+    /// Signed unmatched interest at the clearing price (buy minus sell).
+    pub imbalance: i64,
+    pub trades: Vec<Trade>,
+    /// Auction-only orders left unexecuted and therefore cancelled.
+    pub cancelled: Vec<Order>,
+}
+
+#[pymethods]
+impl AuctionResult {
+    #[getter]
+    fn auction(&self) -> &'static str {
+        self.auction.as_str()
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn volume(&self) -> i64 {
+        self.volume
+    }
+
+    #[getter]
+    fn imbalance(&self) -> i64 {
+        self.imbalance
+    }
+
+    #[getter]
+    fn trades(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let list = pyo3::types::PyList::empty(py);
+        for t in &self.trades {
+            list.append(Py::new(py, t.clone())?)?;
+        }
+        Ok(list.into())
+    }
+
+    #[getter]
+    fn cancelled(&self) -> Vec<Order> {
+        self.cancelled.clone()
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuctionIndicatives — pre-uncross price discovery data
+// ---------------------------------------------------------------------------
+
+/// What the pending auction would do if it uncrossed now.
+///
+/// `price` is None while nothing crosses. `imbalance_side` is the side
+/// with surplus interest at that price (None when balanced) and
+/// `imbalance_quantity` the unsigned surplus.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct AuctionIndicatives {
+    pub auction: AuctionKind,
+    pub price: Option<Decimal>,
+    pub matched_volume: i64,
+    /// Signed surplus: positive = excess buy interest.
+    pub imbalance: i64,
+}
+
+impl AuctionIndicatives {
+    pub fn new(auction: AuctionKind, uncross: Option<&Uncross>) -> Self {
+        AuctionIndicatives {
+            auction,
+            price: uncross.map(|u| u.price),
+            matched_volume: uncross.map_or(0, |u| u.volume),
+            imbalance: uncross.map_or(0, |u| u.imbalance),
+        }
+    }
+}
+
+#[pymethods]
+impl AuctionIndicatives {
+    #[getter]
+    fn auction(&self) -> &'static str {
+        self.auction.as_str()
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn matched_volume(&self) -> i64 {
+        self.matched_volume
+    }
+
+    #[getter]
+    fn imbalance_side(&self) -> Option<Side> {
+        match self.imbalance.signum() {
+            1 => Some(Side::BID),
+            -1 => Some(Side::ASK),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn imbalance_quantity(&self) -> i64 {
+        self.imbalance.abs()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AuctionIndicatives(auction={}, price={:?}, matched_volume={}, imbalance={})",
+            self.auction.as_str(),
+            self.price,
+            self.matched_volume,
+            self.imbalance
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(s: &str) -> Decimal {
+        Decimal::from_str_exact(s).unwrap()
+    }
+
+    fn order(side: Side, limit: Option<&str>, quantity: i64, seq: u64) -> AuctionOrder {
+        AuctionOrder {
+            id: Uuid::new_v4(),
+            side,
+            limit: limit.map(px),
+            quantity,
+            seq,
+        }
+    }
+
+    #[test]
+    fn uncross_maximizes_volume_and_fills_in_priority_order() {
+        let orders = vec![
+            order(Side::BID, Some("10.2"), 100, 0),
+            order(Side::BID, Some("10.0"), 100, 1),
+            order(Side::BID, None, 50, 2),
+            order(Side::ASK, Some("9.9"), 120, 3),
+            order(Side::ASK, Some("10.1"), 100, 4),
+        ];
+
+        let uncross = compute_uncross(&orders, None).unwrap();
+
+        // At 10.1: buy 150 (mkt + 10.2), sell 220 -> 150.
+        // At 10.0: buy 250, sell 120 -> 120. At 10.2: buy 150, sell 220 -> 150.
+        // 10.1 and 10.2 tie on volume and imbalance; lowest price wins.
+        assert_eq!(uncross.price, px("10.1"));
+        assert_eq!(uncross.volume, 150);
+        assert_eq!(uncross.imbalance, -70);
+        // Market bid fills first, against the cheapest ask.
+        assert_eq!(uncross.fills[0], (orders[2].id, orders[3].id, 50));
+        assert_eq!(uncross.fills.iter().map(|f| f.2).sum::<i64>(), 150);
+    }
+
+    #[test]
+    fn uncross_uses_reference_for_market_only_books_and_tie_breaks() {
+        let market_only = vec![order(Side::BID, None, 10, 0), order(Side::ASK, None, 4, 1)];
+        assert!(compute_uncross(&market_only, None).is_none());
+        let uncross = compute_uncross(&market_only, Some(px("50"))).unwrap();
+        assert_eq!(uncross.price, px("50"));
+        assert_eq!(uncross.volume, 4);
+        assert_eq!(uncross.imbalance, 6);
+
+        let tied = vec![
+            order(Side::BID, Some("11"), 10, 0),
+            order(Side::ASK, Some("9"), 10, 1),
+        ];
+        let uncross = compute_uncross(&tied, Some(px("10.9"))).unwrap();
+        assert_eq!(uncross.price, px("11"));
+
+        let no_cross = vec![
+            order(Side::BID, Some("9"), 10, 0),
+            order(Side::ASK, Some("10"), 10, 1),
+        ];
+        assert!(compute_uncross(&no_cross, None).is_none());
+    }
+}
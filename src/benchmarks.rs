@@ -0,0 +1,86 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyorderbook_core::Scenario;
+use std::time::{Duration, Instant};
+
+// ---------------------------------------------------------------------------
+// run_benchmarks — the core engine's standard workloads, timed from Python
+// ---------------------------------------------------------------------------
+
+/// Best-of-`runs` time of `scenario` over `ops` messages; building each
+/// run's book and messages is not timed.
+fn best_time(scenario: Scenario, ops: usize, runs: usize) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let mut workload = scenario.prepare(ops);
+            let start = Instant::now();
+            std::hint::black_box(workload.run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Time the engine's insert-heavy, cancel-heavy and match-heavy workloads
+/// on this machine, the same ones `cargo bench -p pyorderbook-core` runs.
+///
+/// Returns `{scenario: {"ops", "best_ns", "ns_per_op", "ops_per_sec"}}`.
+/// Given the result of an earlier run as `baseline`, raises RuntimeError
+/// if any scenario got slower per op by more than `tolerance`.
+#[pyfunction]
+#[pyo3(signature = (ops=100_000, runs=5, baseline=None, tolerance=0.2))]
+pub fn run_benchmarks<'py>(
+    py: Python<'py>,
+    ops: usize,
+    runs: usize,
+    baseline: Option<&Bound<'py, PyDict>>,
+    tolerance: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    if ops == 0 || runs == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "ops and runs must be positive",
+        ));
+    }
+    if tolerance < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "tolerance must not be negative",
+        ));
+    }
+    let results = PyDict::new(py);
+    let mut regressions = Vec::new();
+    for scenario in Scenario::ALL {
+        let best = py.allow_threads(|| best_time(scenario, ops, runs));
+        let ns_per_op = best.as_nanos() as f64 / ops as f64;
+        let result = PyDict::new(py);
+        result.set_item("ops", ops)?;
+        result.set_item("best_ns", best.as_nanos())?;
+        result.set_item("ns_per_op", ns_per_op)?;
+        result.set_item("ops_per_sec", ops as f64 / best.as_secs_f64().max(1e-9))?;
+        results.set_item(scenario.name(), result)?;
+
+        let previous = match baseline {
+            Some(baseline) => baseline.get_item(scenario.name())?,
+            None => None,
+        };
+        let Some(previous) = previous else {
+            continue;
+        };
+        let before: f64 = previous.get_item("ns_per_op")?.extract()?;
+        if ns_per_op > before * (1.0 + tolerance) {
+            regressions.push(format!(
+                "{} {:.0} ns/op vs {:.0}",
+                scenario.name(),
+                ns_per_op,
+                before
+            ));
+        }
+    }
+    if !regressions.is_empty() {
+        return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+            "Slower than baseline by more than {:.0}%: {}",
+            tolerance * 100.0,
+            regressions.join(", ")
+        )));
+    }
+    Ok(results)
+}
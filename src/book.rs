@@ -1,13 +1,71 @@
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyDict, PyList, PyString};
+use pyorderbook_core::{LevelCacheStats, PoolStats, Resting};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::time::Instant;
 use uuid::Uuid;
 
-use crate::order::{decimal_to_py, uuid_to_py, Order, Side};
+use crate::agents::Agent;
+use crate::auction::{
+    compute_uncross, AuctionDesignation, AuctionIndicatives, AuctionKind, AuctionOrder,
+    AuctionResult, TradingState,
+};
+use crate::calendar::{SessionEvent, SessionSchedule};
+use crate::capacity::{CapacityAction, CapacityLimits, Occupancy};
+use crate::cashflow::{CashFlow, CashFlowKind, CashFlowSchedule};
+use crate::conditions::{Condition, Quote};
+use crate::convert::{decimals_to_py, order_ids_from_py, uuids_to_py};
+use crate::corporate::SplitAdjustment;
+use crate::decay::{LevelAnchor, LiquidityDecay};
+use crate::dedupe::DedupeCache;
+use crate::disconnect::DisconnectSchedule;
+use crate::dropcopy::DropCopy;
+use crate::enrich::{Enricher, Enrichers};
+use crate::events::to_event;
+use crate::features::FeatureSet;
+use crate::feed::{message_sequence, message_symbol, FeedUpdate, SequenceTracker};
+use crate::flow::OrderFlow;
+use crate::fx::FxTable;
+use crate::hashing::StateHasher;
+use crate::history::{OrderEvent, OrderEventKind, OrderHistories};
+use crate::icebergs::{IcebergDetector, DEFAULT_MIN_REFILLS, DEFAULT_REFILL_WINDOW};
+use crate::instrument::{Instrument, Precision, DEFAULT_PRICE_PRECISION, SECONDS_PER_DAY};
+use crate::journal::{CheckpointCadence, Journal, JournalEntry};
+use crate::latency::{LatencyRecorder, OrderTimestamps, DEFAULT_LATENCY_CAPACITY};
+use crate::logging::TARGET;
+use crate::market_profile::MarketProfile;
+use crate::metrics::{CounterValue, Exposition, SymbolCounters};
+use crate::order::{
+    decimal_to_py, extract_decimal, extract_order_id, price_to_decimal, uuid_to_py, FillStats,
+    Order, OrderStatus, Side,
+};
+use crate::pattern::SymbolPattern;
+use crate::pricing::FillPricePolicy;
+use crate::profile::{Phase, Profiler, DEFAULT_SAMPLE_EVERY};
+use crate::publisher::{BusEvent, Publisher};
+use crate::quality::ExecutionQuality;
+use crate::quoting::{QuoteOrders, QuoteProtection, RateLimit};
+use crate::reject::{code_of, RejectCode, RejectPolicy, Rejection};
+use crate::replay::{ReplaySummary, ReplayTally};
+use crate::session::SessionStats;
+use crate::settlement::{SettlementLedger, SettlementLine};
+use crate::shared::SnapshotRing;
 use crate::snapshot::{Snapshot, SnapshotLevel};
+use crate::spread::{ImpliedLevel, Spread};
+use crate::stops::{stop_is_triggered, CascadeReport, StopActivation, StopTrigger};
+use crate::stp::{self, PreventionPlan, SelfTradePrevention};
+use crate::strategy::{extract_actions, LatencyQueue, StrategyAction};
+use crate::symbols::{SymbolId, SymbolTable};
+use crate::tensor::TensorSnapshot;
+use crate::throttle::{Admission, Throttle, ThrottleAction, Throttles};
 use crate::trade::{PriceLevel, Trade, TradeBlotter};
+use crate::validators::{Check, Validators};
 
 // ---------------------------------------------------------------------------
 // Internal data structures (not exposed to Python)
@@ -20,98 +78,190 @@ struct OrderEntry {
     price: Decimal,
     quantity: i64,
     original_quantity: i64,
-    symbol: String,
+    symbol: SymbolId,
     side: Side,
+    short: bool,
+    expire_time: Option<i64>,
+    auction: Option<AuctionDesignation>,
+    stop_price: Option<Decimal>,
+    stop_trigger: StopTrigger,
+    condition: Option<Condition>,
+    /// Book-assigned arrival sequence, used for auction time priority.
+    seq: u64,
+    fills: FillStats,
+    account: Option<String>,
+    session_id: Option<String>,
+    /// Iceberg peak, or 0 for a hidden order; None displays everything.
+    display: Option<i64>,
+    /// Displayed part of the current iceberg tranche.
+    shown: i64,
 }
 
 impl OrderEntry {
-    fn from_order(order: &Order) -> Self {
+    /// The entry of `order`, whose symbol is interned as `symbol`.
+    fn new(order: &Order, symbol: SymbolId) -> Self {
         OrderEntry {
             id: order.id,
             price: order.price,
             quantity: order.quantity,
             original_quantity: order.original_quantity,
-            symbol: order.symbol.clone(),
+            symbol,
             side: order.side,
+            short: order.short,
+            expire_time: order.expire_time,
+            auction: order.auction,
+            stop_price: order.stop_price,
+            stop_trigger: order.stop_trigger,
+            condition: order.condition.clone(),
+            seq: 0,
+            fills: order.fills,
+            account: order.account.clone(),
+            session_id: order.session_id.clone(),
+            display: order.display_quantity,
+            shown: order
+                .display_quantity
+                .map_or(order.quantity, |peak| peak.min(order.quantity)),
         }
     }
 
-    fn to_order(&self) -> Order {
+    /// Overwrite a spare entry with `order`.
+    fn assign(&mut self, order: &Order, symbol: SymbolId) {
+        *self = Self::new(order, symbol);
+    }
+
+    /// The order this entry holds, its symbol resolved through `symbols`.
+    fn to_order(&self, symbols: &SymbolTable) -> Order {
         Order {
             id: self.id,
             price: self.price,
             quantity: self.quantity,
             original_quantity: self.original_quantity,
-            symbol: self.symbol.clone(),
+            symbol: symbols.name(self.symbol).to_string(),
             side: self.side,
+            short: self.short,
+            expire_time: self.expire_time,
+            auction: self.auction,
+            stop_price: self.stop_price,
+            fills: self.fills,
+            account: self.account.clone(),
+            session_id: self.session_id.clone(),
+            display_quantity: self.display,
+            stop_trigger: self.stop_trigger,
+            condition: self.condition.clone(),
         }
     }
-}
 
-/// A price level with a FIFO queue of orders.
-#[derive(Clone, Debug)]
-struct PriceLevelInner {
-    price: Decimal,
-    orders: VecDeque<OrderEntry>,
-}
+    fn to_auction_order(&self) -> AuctionOrder {
+        AuctionOrder {
+            id: self.id,
+            side: self.side,
+            limit: match self.auction {
+                Some(designation) if designation.is_market() => None,
+                _ => Some(self.price),
+            },
+            quantity: self.quantity,
+            seq: self.seq,
+        }
+    }
 
-/// One side of the book (bids or asks) for a single symbol.
-/// - Bids: sorted ascending by price -> best bid (highest) at the **back**
-/// - Asks: sorted descending by price -> best ask (lowest) at the **back**
-#[derive(Clone, Debug, Default)]
-struct OneSide {
-    levels: Vec<PriceLevelInner>,
+    /// Restate the entry for a stock split. An iceberg shows a fresh
+    /// tranche of its restated peak. Returns `None`, leaving the entry
+    /// unchanged, if any restated field overflows.
+    fn split(&mut self, split: &SplitAdjustment) -> Option<()> {
+        let price = split.price(self.side, self.price)?;
+        let stop_price = match self.stop_price {
+            Some(stop) => Some(split.price(self.side, stop)?),
+            None => None,
+        };
+        let quantity = split.quantity(self.quantity)?;
+        let original_quantity = split.quantity(self.original_quantity)?;
+        let display = match self.display {
+            Some(peak) => Some(split.quantity(peak)?.max(peak.min(split.lot_size))),
+            None => None,
+        };
+        let last_price = match self.fills.last_price {
+            Some(last) => Some(last.checked_div(split.ratio)?),
+            None => None,
+        };
+        self.price = price;
+        self.stop_price = stop_price;
+        self.quantity = quantity;
+        self.original_quantity = original_quantity;
+        self.display = display;
+        self.shown = display.map_or(quantity, |peak| peak.min(quantity));
+        self.fills.quantity = original_quantity - quantity;
+        self.fills.last_price = last_price;
+        Some(())
+    }
 }
 
-impl OneSide {
-    /// Find the index of a price level via binary search.
-    /// `ascending` = true for bids, false for asks.
-    fn find_level(&self, price: Decimal, ascending: bool) -> Result<usize, usize> {
-        self.levels.binary_search_by(|lvl| {
-            if ascending {
-                lvl.price.cmp(&price)
-            } else {
-                price.cmp(&lvl.price)
-            }
-        })
+impl Resting for OrderEntry {
+    fn id(&self) -> Uuid {
+        self.id
     }
 
-    /// Insert an order into the correct price level, creating it if needed.
-    fn insert(&mut self, entry: OrderEntry, ascending: bool) {
-        match self.find_level(entry.price, ascending) {
-            Ok(idx) => {
-                self.levels[idx].orders.push_back(entry);
-            }
-            Err(idx) => {
-                let mut orders = VecDeque::new();
-                let price = entry.price;
-                orders.push_back(entry);
-                self.levels.insert(idx, PriceLevelInner { price, orders });
-            }
+    fn price(&self) -> Decimal {
+        self.price
+    }
+
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: i64) {
+        self.quantity = quantity;
+    }
+
+    fn record_fill(&mut self, price: Decimal, quantity: i64) {
+        self.fills.record(price, quantity);
+    }
+
+    fn displayed(&self) -> i64 {
+        match self.display {
+            Some(_) => self.shown.min(self.quantity),
+            None => self.quantity,
         }
     }
 
-    /// Remove a specific order by id from the level at the given price.
-    fn remove_order(&mut self, price: Decimal, order_id: Uuid, ascending: bool) -> bool {
-        if let Ok(idx) = self.find_level(price, ascending) {
-            let level = &mut self.levels[idx];
-            if let Some(pos) = level.orders.iter().position(|o| o.id == order_id) {
-                level.orders.remove(pos);
-                if level.orders.is_empty() {
-                    self.levels.remove(idx);
-                }
-                return true;
+    fn set_displayed(&mut self, displayed: i64) {
+        self.shown = displayed;
+    }
+
+    fn replenish(&mut self) -> bool {
+        match self.display {
+            Some(peak) if peak > 0 => {
+                self.shown = peak.min(self.quantity);
+                true
             }
+            _ => false,
         }
-        false
     }
 }
 
-/// Per-symbol book state.
-#[derive(Clone, Debug, Default)]
-struct SymbolBook {
-    bids: OneSide,
-    asks: OneSide,
+/// Level structures and the matching loop come from `pyorderbook-core`;
+/// the binding stores its richer `OrderEntry` in them.
+type SymbolBook = pyorderbook_core::SymbolBook<OrderEntry>;
+
+/// Where a resting order is: its symbol, side and price level.
+type Location = (SymbolId, Side, Decimal);
+
+/// A map keyed by order id. Ids arrive from callers, so these maps hash
+/// with aHash under a per-process random key rather than SipHash: as fast
+/// as an unkeyed hasher on 16-byte keys, but without collisions a client
+/// could construct ahead of time.
+type IdMap<V> = HashMap<Uuid, V, ahash::RandomState>;
+
+/// Python-visible copy of a price level.
+fn level_copy(
+    side: Side,
+    level: &pyorderbook_core::PriceLevel<OrderEntry>,
+    symbols: &SymbolTable,
+) -> PriceLevel {
+    let mut copy = PriceLevel::from_rust(side, level.price);
+    for entry in &level.orders {
+        copy.orders.append_order(entry.to_order(symbols));
+    }
+    copy
 }
 
 // ---------------------------------------------------------------------------
@@ -130,12 +280,73 @@ struct ParquetOrderRow {
     side: Side,
     symbol: String,
     price: f64,
-    quantity: i64,
+    /// As given; converted to engine units by `Book::row_order`.
+    quantity: Decimal,
+    row_idx: usize,
+    /// Optional event time; replay advances the book clock to it.
+    timestamp: Option<i64>,
+    /// Optional good-till-date expiry.
+    expire_time: Option<i64>,
+    /// Optional source order id, used to break timestamp ties.
+    order_id: Option<String>,
+}
+
+/// `(row_index, reason)` of rows a loader could not parse.
+type RowErrors = Vec<(usize, String)>;
+
+/// What a loader does with a row it cannot parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RowErrorPolicy {
+    /// Fail the whole load on the first bad row.
+    Raise,
+    /// Leave bad rows out.
+    Skip,
+    /// Leave bad rows out and report them as `(row_index, reason)`.
+    Collect,
 }
 
-impl ParquetOrderRow {
-    fn to_order(&self) -> PyResult<Order> {
-        Order::try_new(self.side, self.symbol.clone(), self.price, self.quantity)
+impl RowErrorPolicy {
+    fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "raise" => Ok(RowErrorPolicy::Raise),
+            "skip" => Ok(RowErrorPolicy::Skip),
+            "collect" => Ok(RowErrorPolicy::Collect),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid on_error '{}'. Expected 'raise', 'skip' or 'collect'.",
+                text
+            ))),
+        }
+    }
+
+    /// Split parse results into good rows and `(row_index, reason)` of bad
+    /// ones, or fail on the first bad row under `Raise`.
+    fn sort_rows(
+        self,
+        parsed: impl IntoIterator<Item = (usize, PyResult<ParquetOrderRow>)>,
+        py: Python<'_>,
+    ) -> PyResult<(Vec<ParquetOrderRow>, RowErrors)> {
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
+        for (row_idx, row) in parsed {
+            match row {
+                Ok(row) => rows.push(row),
+                Err(err) if self == RowErrorPolicy::Raise => return Err(err),
+                Err(err) => {
+                    if self == RowErrorPolicy::Collect {
+                        errors.push((row_idx, err.value(py).to_string()));
+                    }
+                }
+            }
+        }
+        Ok((rows, errors))
+    }
+
+    /// A loader's result, paired with the row errors under `Collect`.
+    fn wrap(self, result: PyObject, errors: RowErrors, py: Python<'_>) -> PyResult<PyObject> {
+        if self != RowErrorPolicy::Collect {
+            return Ok(result);
+        }
+        Ok((result, errors).into_pyobject(py)?.into_any().unbind())
     }
 }
 
@@ -176,12 +387,12 @@ fn extract_row_price(value: &Bound<'_, pyo3::PyAny>, row_idx: usize) -> PyResult
     })
 }
 
-fn extract_row_quantity(value: &Bound<'_, pyo3::PyAny>, row_idx: usize) -> PyResult<i64> {
+fn extract_row_quantity(value: &Bound<'_, pyo3::PyAny>, row_idx: usize) -> PyResult<Decimal> {
     if let Ok(quantity) = value.extract::<i64>() {
-        return Ok(quantity);
+        return Ok(Decimal::from(quantity));
     }
     let as_str: String = value.str()?.extract()?;
-    as_str.parse::<i64>().map_err(|_| {
+    Decimal::from_str_exact(&as_str).map_err(|_| {
         pyo3::exceptions::PyValueError::new_err(format!(
             "Invalid quantity at row {}: '{}'",
             row_idx, as_str
@@ -189,12 +400,355 @@ fn extract_row_quantity(value: &Bound<'_, pyo3::PyAny>, row_idx: usize) -> PyRes
     })
 }
 
-fn read_parquet_rows(path: &str, py: Python<'_>) -> PyResult<Vec<ParquetOrderRow>> {
-    let pq = py.import("pyarrow.parquet").map_err(|_| {
+fn extract_optional_row_time(
+    row: &Bound<'_, PyDict>,
+    field: &str,
+    row_idx: usize,
+) -> PyResult<Option<i64>> {
+    match row.get_item(field)? {
+        Some(value) if !value.is_none() => value.extract::<i64>().map(Some).map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid {} at row {}: expected an integer",
+                field, row_idx
+            ))
+        }),
+        _ => Ok(None),
+    }
+}
+
+/// What `Book.halt` does with a symbol's open orders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HaltPolicy {
+    /// Leave every order in place.
+    Keep,
+    /// Cancel resting, stop and auction orders alike.
+    CancelAll,
+    /// Cancel untriggered stops and market-on-open/close orders.
+    CancelMarketAndStop,
+}
+
+impl HaltPolicy {
+    fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "keep" => Ok(HaltPolicy::Keep),
+            "cancel_all" => Ok(HaltPolicy::CancelAll),
+            "cancel_market_and_stop" => Ok(HaltPolicy::CancelMarketAndStop),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid open_orders '{}'. Expected 'keep', 'cancel_all' or \
+                 'cancel_market_and_stop'.",
+                text
+            ))),
+        }
+    }
+
+    /// Whether a held (stop or auction) order is cancelled.
+    fn cancels_held(self, entry: &OrderEntry) -> bool {
+        match self {
+            HaltPolicy::Keep => false,
+            HaltPolicy::CancelAll => true,
+            HaltPolicy::CancelMarketAndStop => {
+                entry.stop_price.is_some()
+                    || entry.condition.is_some()
+                    || entry.auction.is_some_and(|a| a.is_market())
+            }
+        }
+    }
+}
+
+/// What the book does with an order whose id is already resting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DuplicatePolicy {
+    /// Refuse the new order with code "duplicate_id".
+    Reject,
+    /// Cancel the resting order, then accept the new one.
+    Replace,
+    /// Rest both; id lookups reach the earliest until it leaves the book.
+    AllowMultiple,
+}
+
+impl DuplicatePolicy {
+    fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "reject" => Ok(DuplicatePolicy::Reject),
+            "replace" => Ok(DuplicatePolicy::Replace),
+            "allow_multiple" => Ok(DuplicatePolicy::AllowMultiple),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid duplicate policy '{}'. Expected 'reject', 'replace' or \
+                 'allow_multiple'.",
+                text
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DuplicatePolicy::Reject => "reject",
+            DuplicatePolicy::Replace => "replace",
+            DuplicatePolicy::AllowMultiple => "allow_multiple",
+        }
+    }
+}
+
+/// Order of merged rows that share a timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TieBreak {
+    /// Order of the input files, then row order within a file.
+    File,
+    /// Ascending `order_id` column.
+    OrderId,
+    /// Bids before asks, then file order.
+    BidFirst,
+    /// Asks before bids, then file order.
+    AskFirst,
+}
+
+impl TieBreak {
+    fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "file" => Ok(TieBreak::File),
+            "order_id" => Ok(TieBreak::OrderId),
+            "bid_first" => Ok(TieBreak::BidFirst),
+            "ask_first" => Ok(TieBreak::AskFirst),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid tie_break '{}'. Expected 'file', 'order_id', 'bid_first' or 'ask_first'.",
+                text
+            ))),
+        }
+    }
+
+    /// Stable-sort `rows` by timestamp, ordering ties by this rule.
+    fn sort(self, rows: &mut [(i64, ParquetOrderRow)]) {
+        let side_rank = |row: &ParquetOrderRow, first: Side| u8::from(row.side != first);
+        match self {
+            TieBreak::File => rows.sort_by_key(|(ts, _)| *ts),
+            TieBreak::OrderId => {
+                rows.sort_by(|(a_ts, a), (b_ts, b)| (a_ts, &a.order_id).cmp(&(b_ts, &b.order_id)))
+            }
+            TieBreak::BidFirst => rows.sort_by_key(|(ts, row)| (*ts, side_rank(row, Side::BID))),
+            TieBreak::AskFirst => rows.sort_by_key(|(ts, row)| (*ts, side_rank(row, Side::ASK))),
+        }
+    }
+}
+
+/// How snapshot ingestion treats crossed and zero-quantity levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SnapshotPolicy {
+    /// Leave out every level inside the crossed range; zero-quantity
+    /// levels add nothing.
+    Drop,
+    /// Rest the bids, then match crossing asks against them as incoming
+    /// orders; zero-quantity levels add nothing.
+    UncrossByMatching,
+    /// Reject crossed snapshots and zero-quantity levels.
+    Error,
+}
+
+impl SnapshotPolicy {
+    fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "drop" => Ok(SnapshotPolicy::Drop),
+            "uncross_by_matching" => Ok(SnapshotPolicy::UncrossByMatching),
+            "error" => Ok(SnapshotPolicy::Error),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid on_crossed '{}'. Expected 'drop', 'uncross_by_matching' or 'error'.",
+                text
+            ))),
+        }
+    }
+
+    /// One symbol's levels as they should be applied, or an error under
+    /// `Error`. Levels are returned bids first, then asks from the lowest.
+    fn screen(
+        self,
+        symbol: &str,
+        mut levels: Vec<(Side, Decimal, i64)>,
+    ) -> PyResult<Vec<(Side, Decimal, i64)>> {
+        if levels.iter().any(|(_, _, quantity)| *quantity < 0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Level quantity cannot be negative",
+            ));
+        }
+        let zero = levels.iter().find(|(_, _, quantity)| *quantity == 0);
+        if let (Some((side, price, _)), SnapshotPolicy::Error) = (zero, self) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Zero-quantity {} level at {} in {} snapshot",
+                side, price, symbol
+            )));
+        }
+        let live = |wanted: Side| {
+            levels
+                .iter()
+                .filter(move |(side, _, quantity)| *side == wanted && *quantity > 0)
+                .map(|(_, price, _)| *price)
+        };
+        let best_bid = live(Side::BID).max();
+        let best_ask = live(Side::ASK).min();
+        if let (Some(best_bid), Some(best_ask)) = (best_bid, best_ask) {
+            if best_bid >= best_ask {
+                match self {
+                    SnapshotPolicy::Error => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "{} snapshot is crossed: best bid {} >= best ask {}",
+                            symbol, best_bid, best_ask
+                        )))
+                    }
+                    SnapshotPolicy::Drop => levels.retain(|(side, price, _)| match side {
+                        Side::BID => *price < best_ask,
+                        Side::ASK => *price > best_bid,
+                    }),
+                    SnapshotPolicy::UncrossByMatching => {}
+                }
+            }
+        }
+        levels.sort_by_key(|(side, price, _)| (*side == Side::ASK, *price));
+        Ok(levels)
+    }
+}
+
+/// Levels of a resync snapshot as (side, price, quantity).
+fn extract_resync_levels(snapshot: &Bound<'_, pyo3::PyAny>) -> PyResult<Vec<(Side, Decimal, i64)>> {
+    if let Ok(snap) = snapshot.extract::<PyRef<Snapshot>>() {
+        let bids = snap.bids.iter().map(|l| (Side::BID, l.price, l.quantity));
+        let asks = snap.asks.iter().map(|l| (Side::ASK, l.price, l.quantity));
+        return Ok(bids.chain(asks).collect());
+    }
+    let dict = snapshot.downcast::<PyDict>().map_err(|_| {
+        pyo3::exceptions::PyTypeError::new_err(
+            "Snapshot must be a Snapshot or a dict with 'bids' and 'asks'",
+        )
+    })?;
+    let mut levels = Vec::new();
+    for (key, side) in [("bids", Side::BID), ("asks", Side::ASK)] {
+        let Some(entries) = dict.get_item(key)? else {
+            continue;
+        };
+        for entry in entries.try_iter()? {
+            let entry = entry?;
+            let price = extract_decimal(&entry.get_item(0)?, "price")?;
+            let quantity: i64 = entry.get_item(1)?.extract()?;
+            levels.push((side, price, quantity));
+        }
+    }
+    Ok(levels)
+}
+
+/// Optional row fields the loaders read besides `PARQUET_COLUMNS`.
+const OPTIONAL_ROW_FIELDS: [&str; 3] = ["timestamp", "expire_time", "order_id"];
+
+/// How a source's columns map onto the row fields the loaders read.
+#[derive(Debug, Default)]
+struct RowSchema {
+    /// Field -> source column, for fields stored under another name.
+    columns: HashMap<String, String>,
+    /// Field -> mapping or callable that turns a source value into the
+    /// value the loader expects, e.g. `{1: "bid", 2: "ask"}` for sides.
+    transforms: HashMap<String, Py<pyo3::PyAny>>,
+}
+
+impl RowSchema {
+    fn new(
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let mut schema = RowSchema::default();
+        for (name, entries) in [("columns", columns), ("transforms", transforms)] {
+            for (field, value) in entries.into_iter().flat_map(|dict| dict.iter()) {
+                let field: String = field.extract()?;
+                if !PARQUET_COLUMNS
+                    .iter()
+                    .chain(&OPTIONAL_ROW_FIELDS)
+                    .any(|known| *known == field)
+                {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Unknown field '{}' in {}; expected one of [{}, {}].",
+                        field,
+                        name,
+                        PARQUET_COLUMNS.join(", "),
+                        OPTIONAL_ROW_FIELDS.join(", ")
+                    )));
+                }
+                if name == "columns" {
+                    schema.columns.insert(field, value.extract()?);
+                } else {
+                    schema.transforms.insert(field, value.unbind());
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Source column holding `field`.
+    fn column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.columns.get(field).map_or(field, String::as_str)
+    }
+
+    /// `row` keyed by field name with transforms applied; rows pass
+    /// through untouched when nothing is remapped.
+    fn apply<'py>(
+        &self,
+        row: Bound<'py, pyo3::PyAny>,
+        row_idx: usize,
+    ) -> PyResult<Bound<'py, pyo3::PyAny>> {
+        if self.columns.is_empty() && self.transforms.is_empty() {
+            return Ok(row);
+        }
+        let py = row.py();
+        let source = row.downcast::<PyDict>().map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!("Row {} is not a mapping.", row_idx))
+        })?;
+        let fields = PyDict::new(py);
+        for field in PARQUET_COLUMNS.iter().chain(&OPTIONAL_ROW_FIELDS) {
+            let Some(value) = source.get_item(self.column(field))? else {
+                continue;
+            };
+            let value = match self.transforms.get(*field).map(|t| t.bind(py)) {
+                Some(table) if table.is_instance_of::<PyDict>() => {
+                    table.get_item(&value).map_err(|_| {
+                        pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unmapped {} at row {}: {}",
+                            field, row_idx, value
+                        ))
+                    })?
+                }
+                Some(transform) => transform.call1((value,))?,
+                None => value,
+            };
+            fields.set_item(field, value)?;
+        }
+        Ok(fields.into_any())
+    }
+}
+
+/// The callable behind an `on_event` argument: the object itself, or the
+/// `put` method of a queue.
+fn event_sink(target: &Bound<'_, pyo3::PyAny>) -> PyResult<PyObject> {
+    if target.is_callable() {
+        return Ok(target.clone().unbind());
+    }
+    match target.getattr("put") {
+        Ok(put) => Ok(put.unbind()),
+        Err(_) => Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "on_event must be callable or have a put method, got {}",
+            target.get_type().name()?
+        ))),
+    }
+}
+
+fn import_pyarrow<'py>(module: &str, py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    py.import(module).map_err(|_| {
         pyo3::exceptions::PyImportError::new_err(
             "pyarrow is required for parquet ingestion. Install with `pip install pyarrow`.",
         )
-    })?;
+    })
+}
+
+fn read_parquet_rows(
+    path: &str,
+    schema: &RowSchema,
+    on_error: RowErrorPolicy,
+    py: Python<'_>,
+) -> PyResult<(Vec<ParquetOrderRow>, RowErrors)> {
+    let pq = import_pyarrow("pyarrow.parquet", py)?;
 
     let table = pq.call_method1("read_table", (path,)).map_err(|err| {
         pyo3::exceptions::PyValueError::new_err(format!(
@@ -204,7 +758,8 @@ fn read_parquet_rows(path: &str, py: Python<'_>) -> PyResult<Vec<ParquetOrderRow
     })?;
 
     let column_names: Vec<String> = table.getattr("column_names")?.extract()?;
-    let missing_columns: Vec<&str> = PARQUET_COLUMNS
+    let required: Vec<&str> = PARQUET_COLUMNS.iter().map(|f| schema.column(f)).collect();
+    let missing_columns: Vec<&str> = required
         .iter()
         .copied()
         .filter(|name| !column_names.iter().any(|existing| existing == name))
@@ -212,49 +767,235 @@ fn read_parquet_rows(path: &str, py: Python<'_>) -> PyResult<Vec<ParquetOrderRow
     if !missing_columns.is_empty() {
         return Err(pyo3::exceptions::PyValueError::new_err(format!(
             "Parquet file must contain columns [{}]; missing [{}].",
-            PARQUET_COLUMNS.join(", "),
+            required.join(", "),
             missing_columns.join(", ")
         )));
     }
 
     let rows_obj = table.call_method0("to_pylist")?;
     let rows = rows_obj.downcast::<PyList>()?;
-    let mut parsed_rows = Vec::with_capacity(rows.len());
+    let parsed = rows.iter().enumerate().map(|(row_idx, row)| {
+        let row = schema.apply(row, row_idx);
+        (row_idx, row.and_then(|row| parse_order_row(&row, row_idx)))
+    });
+    on_error.sort_rows(parsed, py)
+}
 
-    for (row_idx, row_any) in rows.iter().enumerate() {
-        let row = row_any.downcast::<PyDict>().map_err(|_| {
-            pyo3::exceptions::PyValueError::new_err(format!(
-                "Parquet row {} is not a mapping.",
-                row_idx
-            ))
-        })?;
+/// Parse one event-stream row (a dict with the parquet columns).
+fn parse_order_row(row_any: &Bound<'_, pyo3::PyAny>, row_idx: usize) -> PyResult<ParquetOrderRow> {
+    let row = row_any.downcast::<PyDict>().map_err(|_| {
+        pyo3::exceptions::PyValueError::new_err(format!("Row {} is not a mapping.", row_idx))
+    })?;
 
-        let side_text: String = read_required_row_field(&row, "side", row_idx)?.extract()?;
-        let side = parse_parquet_side(&side_text, row_idx)?;
+    let side_text: String = read_required_row_field(row, "side", row_idx)?.extract()?;
+    let side = parse_parquet_side(&side_text, row_idx)?;
 
-        let symbol: String = read_required_row_field(&row, "symbol", row_idx)?.extract()?;
-        if symbol.is_empty() {
-            return Err(pyo3::exceptions::PyValueError::new_err(format!(
-                "Symbol cannot be empty at row {}",
-                row_idx
-            )));
+    let symbol: String = read_required_row_field(row, "symbol", row_idx)?.extract()?;
+    if symbol.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Symbol cannot be empty at row {}",
+            row_idx
+        )));
+    }
+
+    let price = extract_row_price(&read_required_row_field(row, "price", row_idx)?, row_idx)?;
+    let quantity =
+        extract_row_quantity(&read_required_row_field(row, "quantity", row_idx)?, row_idx)?;
+
+    Ok(ParquetOrderRow {
+        side,
+        symbol,
+        price,
+        quantity,
+        row_idx,
+        timestamp: extract_optional_row_time(row, "timestamp", row_idx)?,
+        expire_time: extract_optional_row_time(row, "expire_time", row_idx)?,
+        order_id: match row.get_item("order_id")? {
+            Some(value) if !value.is_none() => Some(value.str()?.extract()?),
+            _ => None,
+        },
+    })
+}
+
+/// Rows of a mapping of equal-length columns.
+fn read_column_rows(columns: &Bound<'_, PyDict>) -> PyResult<Vec<ParquetOrderRow>> {
+    let py = columns.py();
+    let mut names = Vec::new();
+    let mut values: Vec<Vec<Bound<'_, pyo3::PyAny>>> = Vec::new();
+    for (name, column) in columns.iter() {
+        names.push(name);
+        values.push(column.try_iter()?.collect::<PyResult<_>>()?);
+    }
+    let len = values.first().map_or(0, Vec::len);
+    if values.iter().any(|column| column.len() != len) {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Order columns must all have the same length",
+        ));
+    }
+    (0..len)
+        .map(|row_idx| {
+            let row = PyDict::new(py);
+            for (name, column) in names.iter().zip(&values) {
+                row.set_item(name, &column[row_idx])?;
+            }
+            parse_order_row(row.as_any(), row_idx)
+        })
+        .collect()
+}
+
+/// Rows of a replay source: a parquet path (str or path-like) or an
+/// iterable of row dicts.
+fn read_source_rows(
+    source: &Bound<'_, pyo3::PyAny>,
+    schema: &RowSchema,
+    on_error: RowErrorPolicy,
+    py: Python<'_>,
+) -> PyResult<(Vec<ParquetOrderRow>, RowErrors)> {
+    if source.is_instance_of::<PyString>() || source.hasattr("__fspath__")? {
+        let path: String = py
+            .import("os")?
+            .call_method1("fspath", (source,))?
+            .extract()?;
+        return read_parquet_rows(&path, schema, on_error, py);
+    }
+    let mut parsed = Vec::new();
+    for (row_idx, row) in source.try_iter()?.enumerate() {
+        let row = schema.apply(row?, row_idx);
+        parsed.push((row_idx, row.and_then(|row| parse_order_row(&row, row_idx))));
+    }
+    on_error.sort_rows(parsed, py)
+}
+
+// ---------------------------------------------------------------------------
+// MatchIter — lazy results of Book.match(..., lazy=True)
+// ---------------------------------------------------------------------------
+
+/// Iterator that matches the next input order each time it is advanced.
+#[pyclass]
+pub struct MatchIter {
+    book: Py<Book>,
+    orders: Py<pyo3::types::PyIterator>,
+    skip_empty: bool,
+}
+
+#[pymethods]
+impl MatchIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<TradeBlotter>> {
+        for item in self.orders.bind(py).clone() {
+            let order: PyRef<Order> = item?.extract()?;
+            let mut book = self.book.borrow_mut(py);
+            let blotter = book.match_single(&order)?;
+            book.publish()?;
+            if !(self.skip_empty && blotter.trades.is_empty()) {
+                return Ok(Some(blotter));
+            }
         }
+        Ok(None)
+    }
+}
 
-        let price = extract_row_price(&read_required_row_field(&row, "price", row_idx)?, row_idx)?;
-        let quantity = extract_row_quantity(
-            &read_required_row_field(&row, "quantity", row_idx)?,
-            row_idx,
-        )?;
+// ---------------------------------------------------------------------------
+// OrderHandle — live view of an order held by a book
+// ---------------------------------------------------------------------------
 
-        parsed_rows.push(ParquetOrderRow {
-            side,
-            symbol,
-            price,
-            quantity,
-        });
+/// An `Order` returned by `Book.get_order` and `Book.order_map` that reads
+/// its state from the book on every access, so quantity, price and status
+/// follow fills and amends. Once the order leaves the book, `quantity`
+/// reads 0 and `resting` is False. `snapshot()` returns a frozen `Order`.
+#[pyclass(extends = Order)]
+pub struct OrderHandle {
+    book: Py<Book>,
+}
+
+impl OrderHandle {
+    fn new(py: Python<'_>, book: Py<Book>, order: Order) -> PyResult<Py<OrderHandle>> {
+        Py::new(
+            py,
+            PyClassInitializer::from(order).add_subclass(OrderHandle { book }),
+        )
+    }
+
+    /// The order as the book holds it now, if it is still there.
+    fn lookup(slf: &Bound<'_, Self>) -> PyResult<Option<Order>> {
+        let id = slf.as_super().borrow().id;
+        let book = slf.borrow().book.clone_ref(slf.py());
+        let found = book.bind(slf.py()).try_borrow()?.find_order(id);
+        Ok(found)
+    }
+
+    /// Current state; an order that left the book has nothing open.
+    fn current(slf: &Bound<'_, Self>) -> PyResult<Order> {
+        Ok(Self::lookup(slf)?.unwrap_or_else(|| {
+            let mut order = slf.as_super().borrow().clone();
+            order.quantity = 0;
+            order
+        }))
+    }
+}
+
+#[pymethods]
+impl OrderHandle {
+    /// Whether the order is still resting or held by the book.
+    #[getter]
+    fn resting(slf: &Bound<'_, Self>) -> PyResult<bool> {
+        Ok(Self::lookup(slf)?.is_some())
+    }
+
+    #[getter]
+    fn price(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        decimal_to_py(slf.py(), Self::current(slf)?.price)
+    }
+
+    #[getter]
+    fn quantity(slf: &Bound<'_, Self>) -> PyResult<i64> {
+        Ok(Self::current(slf)?.quantity)
+    }
+
+    #[getter]
+    fn original_quantity(slf: &Bound<'_, Self>) -> PyResult<i64> {
+        Ok(Self::current(slf)?.original_quantity)
+    }
+
+    #[getter]
+    fn expire_time(slf: &Bound<'_, Self>) -> PyResult<Option<i64>> {
+        Ok(Self::current(slf)?.expire_time)
+    }
+
+    #[getter]
+    fn stop_price(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(slf.py(), Self::current(slf)?.stop_price)
+    }
+
+    #[getter]
+    fn status(slf: &Bound<'_, Self>) -> PyResult<OrderStatus> {
+        Ok(Self::current(slf)?.status())
+    }
+
+    #[getter]
+    fn filled_quantity(slf: &Bound<'_, Self>) -> PyResult<i64> {
+        Ok(Self::current(slf)?.fills.quantity)
+    }
+
+    #[getter]
+    fn average_fill_price(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        let average = Self::current(slf)?.fills.average_price();
+        crate::snapshot::optional_decimal_to_py(slf.py(), average)
+    }
+
+    #[getter]
+    fn last_fill_price(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        let last = Self::current(slf)?.fills.last_price;
+        crate::snapshot::optional_decimal_to_py(slf.py(), last)
     }
 
-    Ok(parsed_rows)
+    /// A copy of the order's current state that no longer tracks the book.
+    fn snapshot(slf: &Bound<'_, Self>) -> PyResult<Order> {
+        Self::current(slf)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -264,54 +1005,559 @@ fn read_parquet_rows(path: &str, py: Python<'_>) -> PyResult<Vec<ParquetOrderRow
 /// Main order book and matching engine.
 #[pyclass]
 pub struct Book {
-    symbols: HashMap<String, SymbolBook>,
+    /// Every symbol seen, interned; the order index and the per-symbol
+    /// book, state and statistics below key on the ids.
+    symbol_table: SymbolTable,
+    /// Symbol ids are dense and assigned by the book, so FxHash suffices.
+    symbols: FxHashMap<SymbolId, SymbolBook>,
     /// Maps order_id -> (symbol, side, price) for fast lookup/cancel.
-    order_map: HashMap<Uuid, (String, Side, Decimal)>,
+    order_map: IdMap<Location>,
+    /// Registered reference data, keyed by symbol.
+    instruments: FxHashMap<SymbolId, Instrument>,
+    /// Base currency and FX rates that reports convert amounts with.
+    fx: FxTable,
+    /// Available short-sale locates per symbol. `None` disables enforcement.
+    locates: Option<FxHashMap<SymbolId, i64>>,
+    /// Simulated clock; `None` until the first `advance_time` call.
+    current_time: Option<i64>,
+    /// Resting good-till-date orders keyed by (expire_time, order_id).
+    expiries: BTreeSet<(i64, Uuid)>,
+    /// Next arrival sequence number.
+    next_seq: u64,
+    /// Per-symbol trading phase; symbols not listed are CONTINUOUS.
+    trading_states: FxHashMap<SymbolId, TradingState>,
+    /// Simulated-clock cutoffs after which designated orders are rejected.
+    auction_cutoffs: FxHashMap<(SymbolId, AuctionKind), i64>,
+    /// Session phases each scheduled symbol moves through on the clock.
+    schedules: FxHashMap<SymbolId, SessionSchedule>,
+    /// MOO/LOO/MOC/LOC orders held out of continuous matching, per symbol.
+    auction_orders: FxHashMap<SymbolId, Vec<OrderEntry>>,
+    /// Orders that arrived while their symbol was HALTED, in arrival order.
+    halted_orders: FxHashMap<SymbolId, Vec<OrderEntry>>,
+    /// Untriggered stop orders per symbol, in arrival order.
+    stops: FxHashMap<SymbolId, Vec<OrderEntry>>,
+    /// Synthetic order standing for each L2-maintained level.
+    l2_levels: FxHashMap<(SymbolId, Side, Decimal), Uuid>,
+    /// Set quantity and time of each synthetic level, for liquidity decay.
+    level_anchors: FxHashMap<(SymbolId, Side, Decimal), LevelAnchor>,
+    /// How synthetic levels age with the clock; `None` leaves them static.
+    liquidity_decay: Option<LiquidityDecay>,
+    /// Venue order id -> book order id for L3 feed orders.
+    feed_orders: HashMap<String, Uuid>,
+    /// Per-symbol sequence tracking for sequenced feed messages.
+    feed_sequences: FxHashMap<SymbolId, SequenceTracker>,
+    /// Optional trade/L2 bus publisher.
+    publisher: Option<Publisher>,
+    /// Quantity executed per resting order (fills before it rested plus
+    /// trades against it since); zero entries are omitted.
+    fill_ledger: HashMap<Uuid, i64>,
+    /// Per-order lifecycle logs; `None` unless recording is enabled.
+    order_history: Option<OrderHistories>,
+    /// Trade statistics per symbol since the last `roll_session`.
+    sessions: FxHashMap<SymbolId, SessionStats>,
+    /// Account of every live order booked to one.
+    accounts: HashMap<Uuid, String>,
+    /// Per-account trade totals since the last `roll_session`, and the net
+    /// positions carried into it.
+    settlements: SettlementLedger,
+    /// Dividends and funding to pay positions when the clock reaches them.
+    cash_flows: CashFlowSchedule,
+    /// How continuous-matching fills are priced.
+    fill_price_policy: FillPricePolicy,
+    /// Blotters of recent submissions made with a `dedupe_key`.
+    dedupe: DedupeCache,
+    /// Per-order processing timestamps; `None` unless tracking is on.
+    latency: Option<LatencyRecorder>,
+    /// Time spent per phase on sampled orders; `None` unless profiling.
+    profiler: Option<Profiler>,
+    /// Likely iceberg refills seen in L3 feed updates, when detecting.
+    icebergs: Option<IcebergDetector>,
+    /// Inputs since journaling started; `None` unless it is enabled.
+    journal: Option<Journal>,
+    /// Online features per symbol, from `enable_features`.
+    features: FxHashMap<SymbolId, FeatureSet>,
+    /// Per-event order flow series per symbol, from `enable_order_flow`.
+    flows: FxHashMap<SymbolId, OrderFlow>,
+    /// Fills and midpoints per symbol, from `enable_execution_quality`.
+    qualities: FxHashMap<SymbolId, ExecutionQuality>,
+    /// Volume and time at price per symbol, from `enable_volume_profile`.
+    profiles: FxHashMap<SymbolId, MarketProfile>,
+    /// Ring file snapshots are shared through with other processes.
+    shared: Option<SnapshotRing>,
+    /// Drop-copy subscriptions, in the order they were added.
+    drop_copies: Vec<DropCopy>,
+    /// Account of every live order some drop copy covers.
+    drop_copy_orders: HashMap<Uuid, String>,
+    /// Events awaiting drop-copy delivery, with the account each is for.
+    drop_copy_events: VecDeque<(String, BusEvent)>,
+    /// Live orders of each two-sided quote by symbol and `quote_id`.
+    quotes: FxHashMap<(SymbolId, String), QuoteOrders>,
+    /// Quote throttles and fill protections per account.
+    quote_protection: HashMap<String, QuoteProtection>,
+    /// (account, symbol, fills) of protections tripped since the last
+    /// publish, whose quotes are still to be pulled.
+    tripped_quotes: Vec<(String, String, usize)>,
+    /// Event sinks of orders submitted with `on_event`, while live.
+    order_callbacks: HashMap<Uuid, PyObject>,
+    /// Events for watched orders awaiting delivery to their sinks.
+    order_events: VecDeque<(Uuid, BusEvent)>,
+    /// Book-wide price/quantity precision; `None` keeps the defaults.
+    precision: Option<Precision>,
+    /// Per-symbol precision overriding the book-wide policy.
+    symbol_precision: FxHashMap<SymbolId, Precision>,
+    /// Spread instruments, in registration order.
+    spreads: Vec<Spread>,
+    /// Whether external quantities are whole-asset amounts (see
+    /// `set_fractional_quantities`) rather than engine units.
+    fractional_quantities: bool,
+    /// How trades within an account or group are prevented; None allows them.
+    self_trade_prevention: Option<SelfTradePrevention>,
+    /// Account -> anti-internalization group.
+    internalization_groups: HashMap<String, String>,
+    /// Sessions disconnected with a grace period, awaiting their cancels.
+    disconnects: DisconnectSchedule,
+    /// Message-rate limits and the orders queued behind them.
+    throttles: Throttles,
+    /// Lifetime event counts per symbol, for `metrics_text`.
+    counters: FxHashMap<SymbolId, SymbolCounters>,
+    /// Run the invariant checker after every input.
+    debug_checks: bool,
+    /// What to do with an order whose id is already resting.
+    duplicate_policy: DuplicatePolicy,
+    /// Locations of resting orders whose id `order_map` already holds, in
+    /// arrival order (only under `DuplicatePolicy::AllowMultiple`).
+    duplicate_ids: IdMap<VecDeque<Location>>,
+    /// Caps on resting orders and levels, when set.
+    capacity: Option<CapacityLimits>,
+    /// User checks every order passes before matching.
+    validators: Validators,
+    /// Fields added to trades before they are returned or published.
+    enrichers: Enrichers,
 }
 
 #[pymethods]
 impl Book {
+    /// An empty book. With `debug_checks=True` it runs `check_invariants`
+    /// after every input and raises AssertionError, naming the input, on the
+    /// first violation; for development, as the checks walk the whole book.
     #[new]
-    fn new() -> Self {
-        Book {
-            symbols: HashMap::new(),
-            order_map: HashMap::new(),
-        }
+    #[pyo3(signature = (*, debug_checks = false))]
+    fn py_new(py: Python<'_>, debug_checks: bool) -> PyResult<Self> {
+        crate::logging::sync_log_level(py)?;
+        Ok(Book {
+            debug_checks,
+            ..Book::new()
+        })
     }
 
     /// Create a bid order using the same signature as pyorderbook.bid.
-    fn bid(&self, symbol: String, price: f64, quantity: i64) -> PyResult<Order> {
-        Order::try_new(Side::BID, symbol, price, quantity)
+    #[pyo3(signature = (symbol, price, quantity, *, expire_time = None, auction = None, stop_price = None, account = None, session_id = None, display_quantity = None, stop_trigger = None, condition = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn bid(
+        &self,
+        symbol: String,
+        price: f64,
+        quantity: &Bound<'_, pyo3::PyAny>,
+        expire_time: Option<i64>,
+        auction: Option<AuctionDesignation>,
+        stop_price: Option<f64>,
+        account: Option<String>,
+        session_id: Option<String>,
+        display_quantity: Option<i64>,
+        stop_trigger: Option<&str>,
+        condition: Option<Condition>,
+    ) -> PyResult<Order> {
+        let quantity = self.quantity_units(&symbol, extract_decimal(quantity, "quantity")?)?;
+        crate::order::bid(
+            symbol,
+            price,
+            quantity,
+            expire_time,
+            auction,
+            stop_price,
+            account,
+            session_id,
+            display_quantity,
+            stop_trigger,
+            condition,
+        )
     }
 
     /// Create an ask order using the same signature as pyorderbook.ask.
-    fn ask(&self, symbol: String, price: f64, quantity: i64) -> PyResult<Order> {
-        Order::try_new(Side::ASK, symbol, price, quantity)
+    #[pyo3(signature = (symbol, price, quantity, *, short = false, expire_time = None, auction = None, stop_price = None, account = None, session_id = None, display_quantity = None, stop_trigger = None, condition = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn ask(
+        &self,
+        symbol: String,
+        price: f64,
+        quantity: &Bound<'_, pyo3::PyAny>,
+        short: bool,
+        expire_time: Option<i64>,
+        auction: Option<AuctionDesignation>,
+        stop_price: Option<f64>,
+        account: Option<String>,
+        session_id: Option<String>,
+        display_quantity: Option<i64>,
+        stop_trigger: Option<&str>,
+        condition: Option<Condition>,
+    ) -> PyResult<Order> {
+        let quantity = self.quantity_units(&symbol, extract_decimal(quantity, "quantity")?)?;
+        crate::order::ask(
+            symbol,
+            price,
+            quantity,
+            short,
+            expire_time,
+            auction,
+            stop_price,
+            account,
+            session_id,
+            display_quantity,
+            stop_trigger,
+            condition,
+        )
     }
 
-    /// Match incoming order(s). Accepts a single Order or a list of Orders.
-    /// Returns a TradeBlotter or list of TradeBlotters respectively.
-    #[pyo3(name = "match")]
+    /// Match incoming order(s). Accepts a single Order or any iterable of
+    /// Orders (list, tuple, generator, ...). Returns a TradeBlotter or a
+    /// list of TradeBlotters respectively.
+    ///
+    /// With `lazy=True` an iterable is matched one order at a time as the
+    /// returned iterator is consumed, so huge inputs stream without
+    /// materializing either side. With `skip_empty=True` blotters of orders
+    /// that did not trade are left out of the results.
+    ///
+    /// A single Order may carry a `dedupe_key`: resubmitting under a key
+    /// the book has already matched returns the original blotter without
+    /// matching again, so at-least-once streams can be replayed safely.
+    /// Rejected submissions are not remembered. The book keeps the most
+    /// recent keys, 10,000 unless `set_dedupe_capacity` says otherwise.
+    ///
+    /// A single Order may also carry `on_event`, a callable or a queue
+    /// (anything with `put`). It receives the order's typed `Event`s from
+    /// then on: a `TradeExecuted` per fill, including fills long after
+    /// `match` returned, and an `OrderCancelled` or `OrderExpired` if it
+    /// leaves the book unfilled. Events are delivered when the book call
+    /// that produced them finishes.
+    #[pyo3(
+        name = "match",
+        signature = (orders, *, lazy = false, skip_empty = false, dedupe_key = None, on_event = None)
+    )]
     fn match_orders(
-        &mut self,
+        slf: &Bound<'_, Self>,
         orders: &Bound<'_, pyo3::PyAny>,
+        lazy: bool,
+        skip_empty: bool,
+        dedupe_key: Option<String>,
+        on_event: Option<&Bound<'_, pyo3::PyAny>>,
         py: Python<'_>,
     ) -> PyResult<PyObject> {
-        if let Ok(list) = orders.downcast::<PyList>() {
-            let mut blotters: Vec<Py<TradeBlotter>> = Vec::new();
-            for item in list.iter() {
-                let order: PyRef<Order> = item.extract()?;
-                let blotter = self.match_single(&order)?;
-                blotters.push(Py::new(py, blotter)?);
+        if let Ok(order) = orders.extract::<PyRef<Order>>() {
+            let callback = on_event.map(event_sink).transpose()?;
+            let mut book = slf.borrow_mut();
+            if let Some(seen) = dedupe_key.as_deref().and_then(|key| book.dedupe.get(key)) {
+                return Ok(Py::new(py, seen.clone())?.into_any());
             }
-            Ok(PyList::new(py, blotters)?.into())
-        } else {
-            let order: PyRef<Order> = orders.extract()?;
-            let blotter = self.match_single(&order)?;
-            Ok(Py::new(py, blotter)?.into_any().into())
+            if let Some(callback) = callback {
+                book.order_callbacks.insert(order.id, callback);
+            }
+            let blotter = book.match_single(&order).inspect_err(|_| {
+                book.order_callbacks.remove(&order.id);
+            })?;
+            if let Some(key) = dedupe_key {
+                book.dedupe.insert(key, blotter.clone());
+            }
+            book.publish()?;
+            let converting = Instant::now();
+            let blotter = Py::new(py, blotter)?.into_any();
+            if let Some(profiler) = book.profiler.as_mut() {
+                profiler.add_boundary(converting.elapsed(), 1);
+            }
+            return Ok(blotter);
+        }
+        for (name, given) in [
+            ("dedupe_key", dedupe_key.is_some()),
+            ("on_event", on_event.is_some()),
+        ] {
+            if given {
+                return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                    "{} applies to a single Order, not an iterable",
+                    name
+                )));
+            }
+        }
+        let Ok(iter) = orders.try_iter() else {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                "Expected an Order or an iterable of Orders, got {}",
+                orders.get_type().name()?
+            )));
+        };
+        if lazy {
+            let matches = MatchIter {
+                book: slf.clone().unbind(),
+                orders: iter.unbind(),
+                skip_empty,
+            };
+            return Ok(Py::new(py, matches)?.into_any());
+        }
+        let extracting = Instant::now();
+        let incoming = iter
+            .map(|item| Ok(item?.extract::<PyRef<Order>>()?.clone()))
+            .collect::<PyResult<Vec<Order>>>()?;
+        let extracted = extracting.elapsed();
+        let mut guard = slf.borrow_mut();
+        let book: &mut Book = &mut guard;
+        // The GIL stays held, as for a single Order: releasing it while the
+        // book is mutably borrowed would let another thread see it borrowed.
+        let matched = incoming
+            .iter()
+            .map(|order| book.match_single(order))
+            .collect::<PyResult<Vec<TradeBlotter>>>();
+        book.publish()?;
+        let converting = Instant::now();
+        let blotters = matched?
+            .into_iter()
+            .filter(|blotter| !(skip_empty && blotter.trades.is_empty()))
+            .map(|blotter| Py::new(py, blotter))
+            .collect::<PyResult<Vec<_>>>()?;
+        let blotters = PyList::new(py, blotters)?;
+        if let Some(profiler) = book.profiler.as_mut() {
+            profiler.add_boundary(extracted + converting.elapsed(), incoming.len());
+        }
+        Ok(blotters.into())
+    }
+
+    /// Choose how continuous-matching fills are priced.
+    ///
+    /// "passive" (the default) fills at the standing order's price;
+    /// "midpoint" halfway between the aggressor's limit and the standing
+    /// price, as for pegged crosses; "reference" at `reference_price` and
+    /// "last_sale" at the symbol's last trade price this session, each
+    /// only when both orders accept it, else at the passive price. Prices
+    /// are rounded to the symbol's precision. Auctions keep their uncross
+    /// price.
+    #[pyo3(signature = (policy, reference_price = None))]
+    fn set_fill_price_policy(
+        &mut self,
+        policy: &str,
+        reference_price: Option<&Bound<'_, pyo3::PyAny>>,
+    ) -> PyResult<()> {
+        let reference = reference_price
+            .map(|p| crate::order::extract_decimal(p, "reference_price"))
+            .transpose()?;
+        self.fill_price_policy = FillPricePolicy::parse(policy, reference)?;
+        Ok(())
+    }
+
+    /// Name of the fill price policy in force.
+    #[getter]
+    fn fill_price_policy(&self) -> &'static str {
+        self.fill_price_policy.name()
+    }
+
+    /// Choose what happens to an order whose id is already resting.
+    ///
+    /// "reject" (the default) refuses it with code "duplicate_id";
+    /// "replace" cancels the resting order and accepts the new one; and
+    /// "allow_multiple" rests both, with cancels, amends and other id
+    /// lookups reaching the earliest until it leaves the book.
+    fn set_duplicate_policy(&mut self, policy: &str) -> PyResult<()> {
+        self.duplicate_policy = DuplicatePolicy::parse(policy)?;
+        Ok(())
+    }
+
+    /// Name of the duplicate order id policy in force.
+    #[getter]
+    fn duplicate_policy(&self) -> &'static str {
+        self.duplicate_policy.name()
+    }
+
+    /// Add `check` to the validators every order passes before matching.
+    /// It is called with the Order: returning None or True passes it,
+    /// False or a string (the reason) rejects it with code "validation"
+    /// and `validator` set to `name`. Validators run in the order added.
+    fn add_validator(&mut self, name: String, check: &Bound<'_, pyo3::PyAny>) -> PyResult<()> {
+        if !check.is_callable() {
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "check must be callable",
+            ));
+        }
+        self.validators
+            .add(name, Check::Python(check.clone().unbind()))
+    }
+
+    /// Add a validator rejecting orders for any symbol not in `symbols`.
+    #[pyo3(signature = (symbols, *, name = "symbol_allow_list".to_string()))]
+    fn add_symbol_allow_list(&mut self, symbols: HashSet<String>, name: String) -> PyResult<()> {
+        self.validators.add(name, Check::Symbols(symbols))
+    }
+
+    /// Add a validator rejecting limit prices more than `max_deviation`
+    /// (a fraction, e.g. 0.1 for 10%) away from the symbol's reference
+    /// price: its entry in `references`, else its last trade this session,
+    /// else its midpoint. Orders pass while a symbol has none.
+    #[pyo3(signature = (max_deviation, *, references = None, name = "price_band".to_string()))]
+    fn add_price_band(
+        &mut self,
+        max_deviation: &Bound<'_, pyo3::PyAny>,
+        references: Option<HashMap<String, Bound<'_, pyo3::PyAny>>>,
+        name: String,
+    ) -> PyResult<()> {
+        let max_deviation = extract_decimal(max_deviation, "max_deviation")?;
+        if max_deviation < Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "max_deviation cannot be negative",
+            ));
+        }
+        let references = references
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(symbol, price)| Ok((symbol, extract_decimal(&price, "reference")?)))
+            .collect::<PyResult<HashMap<String, Decimal>>>()?;
+        self.validators.add(
+            name,
+            Check::PriceBand {
+                max_deviation,
+                references,
+            },
+        )
+    }
+
+    /// Remove the validator called `name`. Returns False if there is none.
+    fn remove_validator(&mut self, name: &str) -> bool {
+        self.validators.remove(name)
+    }
+
+    /// Names of the registered validators, in the order they run.
+    #[getter]
+    fn validators(&self) -> Vec<String> {
+        self.validators.names()
+    }
+
+    /// Add `enrich` to the enrichers every trade passes through before it
+    /// is returned or published. It is called with the Trade and returns a
+    /// mapping of fields (bool, int, float, str or Decimal values) to merge
+    /// into `Trade.extra`, or None. Enrichers run in the order added; one
+    /// that raises is reported through `sys.unraisablehook`.
+    fn add_trade_enricher(
+        &mut self,
+        name: String,
+        enrich: &Bound<'_, pyo3::PyAny>,
+    ) -> PyResult<()> {
+        if !enrich.is_callable() {
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "enrich must be callable",
+            ));
+        }
+        self.enrichers
+            .add(name, Enricher::Python(enrich.clone().unbind()))
+    }
+
+    /// Add an enricher setting `incoming_fee` and `standing_fee` to the
+    /// fill's notional times `taker_rate` and `maker_rate` (negative for a
+    /// rebate). Both sides of an auction uncross pay the taker rate.
+    #[pyo3(signature = (maker_rate, taker_rate, *, name = "fees".to_string()))]
+    fn add_fee_enricher(
+        &mut self,
+        maker_rate: &Bound<'_, pyo3::PyAny>,
+        taker_rate: &Bound<'_, pyo3::PyAny>,
+        name: String,
+    ) -> PyResult<()> {
+        let maker_rate = extract_decimal(maker_rate, "maker_rate")?;
+        let taker_rate = extract_decimal(taker_rate, "taker_rate")?;
+        self.enrichers.add(
+            name,
+            Enricher::Fees {
+                maker_rate,
+                taker_rate,
+            },
+        )
+    }
+
+    /// Add an enricher setting `venue` on every trade.
+    #[pyo3(signature = (venue, *, name = "venue".to_string()))]
+    fn add_venue_enricher(&mut self, venue: String, name: String) -> PyResult<()> {
+        self.enrichers.add(name, Enricher::Venue(venue))
+    }
+
+    /// Add an enricher setting `liquidity` to how the incoming order
+    /// traded: "removed" in continuous matching, "auction" in an uncross.
+    #[pyo3(signature = (*, name = "liquidity".to_string()))]
+    fn add_liquidity_enricher(&mut self, name: String) -> PyResult<()> {
+        self.enrichers.add(name, Enricher::Liquidity)
+    }
+
+    /// Remove the trade enricher called `name`. Returns False if there is
+    /// none.
+    fn remove_trade_enricher(&mut self, name: &str) -> bool {
+        self.enrichers.remove(name)
+    }
+
+    /// Names of the registered trade enrichers, in the order they run.
+    #[getter]
+    fn trade_enrichers(&self) -> Vec<String> {
+        self.enrichers.names()
+    }
+
+    /// Stop orders of the same account from trading with each other, or
+    /// allow it again with None.
+    ///
+    /// When an incoming order reaches a resting order of its own account
+    /// (or of an account in the same anti-internalization group), mode
+    /// "cancel_newest" cancels the rest of the incoming order,
+    /// "cancel_oldest" cancels the resting order and keeps matching, and
+    /// "cancel_both" does both. Fills ahead of the conflicting order
+    /// stand. Cancelled orders are listed in the blotter's `prevented`.
+    /// Orders without an account never conflict.
+    #[pyo3(signature = (mode))]
+    fn set_self_trade_prevention(&mut self, mode: Option<&str>) -> PyResult<()> {
+        self.self_trade_prevention = mode.map(SelfTradePrevention::parse).transpose()?;
+        Ok(())
+    }
+
+    /// Self-trade prevention mode in force, or None.
+    #[getter]
+    fn self_trade_prevention(&self) -> Option<&'static str> {
+        self.self_trade_prevention.map(SelfTradePrevention::as_str)
+    }
+
+    /// Put `accounts` in anti-internalization `group`, or take them out of
+    /// their groups with None. Orders of accounts in one group are kept
+    /// from trading with each other by `set_self_trade_prevention`, as if
+    /// they were one account.
+    #[pyo3(signature = (group, accounts))]
+    fn set_anti_internalization_group(&mut self, group: Option<String>, accounts: Vec<String>) {
+        for account in accounts {
+            match &group {
+                Some(group) => self.internalization_groups.insert(account, group.clone()),
+                None => self.internalization_groups.remove(&account),
+            };
         }
     }
 
+    /// Anti-internalization group of `account`, or None.
+    fn anti_internalization_group(&self, account: &str) -> Option<String> {
+        self.internalization_groups.get(account).cloned()
+    }
+
+    /// Remember the blotters of the `capacity` most recent `dedupe_key`
+    /// submissions, forgetting older keys at once if there are more.
+    fn set_dedupe_capacity(&mut self, capacity: usize) {
+        self.dedupe.set_capacity(capacity);
+    }
+
+    /// The TradeBlotter `match(order)` would return, without changing the
+    /// book.
+    ///
+    /// The order is matched by the same code path against a scratch copy
+    /// of its symbol's book, carrying the stops, instruments, trading
+    /// phase, locates and clock that matching consults, so it is rejected
+    /// or triggers stops exactly as a real submission would. Publishers,
+    /// order history and session statistics see nothing.
+    fn preview(&self, order: PyRef<Order>) -> PyResult<TradeBlotter> {
+        self.overlay(&order.symbol).match_single(&order)
+    }
+
     /// Replay an event-stream parquet file through the matching engine.
     ///
     /// Expected columns:
@@ -320,16 +1566,350 @@ impl Book {
     /// - price: numeric
     /// - quantity: integer
     ///
-    /// Returns a list of TradeBlotter entries, one per input row.
-    fn replay_parquet(&mut self, path: &str, py: Python<'_>) -> PyResult<PyObject> {
-        let rows = read_parquet_rows(path, py)?;
+    /// Optional columns:
+    /// - timestamp: integer event time; the book clock is advanced to it
+    ///   (firing expirations) before the row is matched
+    /// - expire_time: integer good-till-date expiry
+    ///
+    /// Returns a list of TradeBlotter entries, one per input row, or only
+    /// those of rows that traded with `skip_empty=True`. With
+    /// `summary=True` a ReplaySummary is returned instead, and rows the book
+    /// rejects are recorded in it rather than aborting the replay.
+    ///
+    /// `on_error` decides what happens to malformed rows: "raise" (the
+    /// default) fails before anything is replayed, "skip" leaves them out,
+    /// and "collect" leaves them out and returns `(result, errors)` with
+    /// `errors` a list of `(row_index, reason)`. In a summary, collected
+    /// rows are added to `rejects` instead.
+    ///
+    /// Files with another layout are read through `columns`, mapping row
+    /// fields to the file's column names (`{"price": "px"}`), and
+    /// `transforms`, mapping fields to a dict or callable applied to each
+    /// value (`{"side": {1: "bid", 2: "ask"}}`).
+    #[pyo3(signature = (
+        path,
+        *,
+        skip_empty = false,
+        summary = false,
+        on_error = "raise",
+        columns = None,
+        transforms = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn replay_parquet(
+        &mut self,
+        path: &str,
+        skip_empty: bool,
+        summary: bool,
+        on_error: &str,
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let on_error = RowErrorPolicy::parse(on_error)?;
+        let schema = RowSchema::new(columns, transforms)?;
+        let (rows, errors) = read_parquet_rows(path, &schema, on_error, py)?;
+        if summary {
+            let summary = self.replay_summary(&rows, errors, py)?;
+            return Ok(Py::new(py, summary)?.into_any());
+        }
+        let blotters = self.replay_rows(rows, skip_empty, py)?;
+        self.publish()?;
+        on_error.wrap(PyList::new(py, blotters)?.into(), errors, py)
+    }
+
+    /// Replay several event-stream parquet files as one stream.
+    ///
+    /// Rows of all files (see `replay_parquet` for the columns) are merged
+    /// by their `timestamp`, which every row must carry. Returns the
+    /// TradeBlotters of the merged stream as `replay_parquet` does.
+    ///
+    /// `tie_break` orders rows with equal timestamps: "file" (the default)
+    /// keeps the order of `paths`, then row order; "order_id" sorts by an
+    /// `order_id` column every row must then carry; "bid_first" and
+    /// "ask_first" put one side ahead, then fall back to "file".
+    #[pyo3(signature = (paths, *, by = "timestamp", skip_empty = false, tie_break = "file"))]
+    fn replay_many(
+        &mut self,
+        paths: &Bound<'_, pyo3::PyAny>,
+        by: &str,
+        skip_empty: bool,
+        tie_break: &str,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let tie_break = TieBreak::parse(tie_break)?;
+        if by != "timestamp" {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid by '{}'. Expected 'timestamp'.",
+                by
+            )));
+        }
+        if paths.is_instance_of::<PyString>() {
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "paths must be a list of paths; use replay_parquet for a single file",
+            ));
+        }
+        let fspath = py.import("os")?.getattr("fspath")?;
+        let mut merged = Vec::new();
+        for path in paths.try_iter()? {
+            let path: String = fspath.call1((path?,))?.extract()?;
+            let (rows, _) =
+                read_parquet_rows(&path, &RowSchema::default(), RowErrorPolicy::Raise, py)?;
+            for row in rows {
+                let ts = row.timestamp.ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Row {} of '{}' has no timestamp to merge by",
+                        row.row_idx, path
+                    ))
+                })?;
+                if tie_break == TieBreak::OrderId && row.order_id.is_none() {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Row {} of '{}' has no order_id to break ties by",
+                        row.row_idx, path
+                    )));
+                }
+                merged.push((ts, row));
+            }
+        }
+        tie_break.sort(&mut merged);
+        let blotters = self.replay_rows(merged.into_iter().map(|(_, row)| row), skip_empty, py)?;
+        self.publish()?;
+        Ok(PyList::new(py, blotters)?.into())
+    }
+
+    /// Replay an event stream with a trading strategy in the loop.
+    ///
+    /// `source` is a parquet path (see `replay_parquet`) or an iterable of
+    /// row dicts with the same fields. After each row is matched,
+    /// `strategy.on_event(book, blotter)` is called with this book and the
+    /// row's TradeBlotter. It returns None, an Order, a
+    /// `("cancel", order_or_id)` tuple, or a list of these.
+    ///
+    /// Returned actions reach the book `latency` clock units after the
+    /// event (the row's timestamp, else the current time): each is applied
+    /// before the first later row whose timestamp is at or past its due
+    /// time, advancing the clock to it. Actions still in flight at the end
+    /// of the stream are applied in order. Cancels of orders no longer
+    /// resting are ignored. Strategies should act through return values
+    /// rather than mutating the book, so that latency applies.
+    ///
+    /// With `on_reject="report"`, a strategy order the book refuses does
+    /// not end the replay: `strategy.on_reject(book, rejection)` is called
+    /// instead, if defined, with a `Rejection` whose `code` says why (e.g.
+    /// "bad_tick", "trading_state"). It may return actions like `on_event`.
+    /// The default "raise" propagates the error.
+    ///
+    /// Returns a TradeBlotter per matched order, source rows and strategy
+    /// orders interleaved in the order they reached the book. With
+    /// `skip_empty=True` only blotters with trades are returned; the
+    /// strategy still sees every row. `on_error`, `columns` and
+    /// `transforms` apply to source rows as in `replay_parquet`.
+    #[pyo3(signature = (
+        source,
+        strategy,
+        *,
+        latency = 0,
+        skip_empty = false,
+        on_error = "raise",
+        on_reject = "raise",
+        columns = None,
+        transforms = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn replay_with_strategy(
+        slf: &Bound<'_, Self>,
+        source: &Bound<'_, pyo3::PyAny>,
+        strategy: &Bound<'_, pyo3::PyAny>,
+        latency: i64,
+        skip_empty: bool,
+        on_error: &str,
+        on_reject: &str,
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        if latency < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "latency cannot be negative",
+            ));
+        }
+        let on_error = RowErrorPolicy::parse(on_error)?;
+        let on_reject = RejectPolicy::parse(on_reject)?;
+        let schema = RowSchema::new(columns, transforms)?;
+        let (rows, errors) = read_source_rows(source, &schema, on_error, py)?;
+        let mut queue = LatencyQueue::new(latency);
         let mut blotters: Vec<Py<TradeBlotter>> = Vec::with_capacity(rows.len());
+        let apply = |until: Option<i64>,
+                     queue: &mut LatencyQueue,
+                     blotters: &mut Vec<Py<TradeBlotter>>|
+         -> PyResult<()> {
+            loop {
+                let (now, rejected) = {
+                    let mut book = slf.borrow_mut();
+                    let rejected = book.apply_strategy_actions(
+                        queue, until, blotters, skip_empty, on_reject, py,
+                    )?;
+                    (until.or(book.current_time).unwrap_or(0), rejected)
+                };
+                if rejected.is_empty() || !strategy.hasattr("on_reject")? {
+                    return Ok(());
+                }
+                for rejection in rejected {
+                    let result = strategy.call_method1("on_reject", (slf, rejection))?;
+                    queue.schedule(now, extract_actions(&result)?);
+                }
+            }
+        };
         for row in rows {
-            let order = row.to_order()?;
-            let blotter = self.match_single(&order)?;
-            blotters.push(Py::new(py, blotter)?);
+            let now = row.timestamp.or(slf.borrow().current_time).unwrap_or(0);
+            apply(Some(now), &mut queue, &mut blotters)?;
+            let event = {
+                let mut book = slf.borrow_mut();
+                if let Some(ts) = row.timestamp {
+                    book.advance_clock(ts)?;
+                }
+                let order = book.row_order(&row)?;
+                let blotter = book.match_single(&order)?;
+                let traded = !blotter.trades.is_empty();
+                let event = Py::new(py, blotter)?;
+                if traded || !skip_empty {
+                    blotters.push(event.clone_ref(py));
+                }
+                event
+            };
+            let result = strategy.call_method1("on_event", (slf, event))?;
+            queue.schedule(now, extract_actions(&result)?);
         }
-        Ok(PyList::new(py, blotters)?.into())
+        apply(None, &mut queue, &mut blotters)?;
+        slf.borrow_mut().publish()?;
+        on_error.wrap(PyList::new(py, blotters)?.into(), errors, py)
+    }
+
+    /// Replay an event stream with several agents trading in the loop.
+    ///
+    /// Like `replay_with_strategy`, but with a list of `Agent`s, each
+    /// wrapping a strategy with its own account, latency and risk limits.
+    /// After each row every agent's `on_event(book, blotter)` is called in
+    /// list order. Actions of all agents reach the book in order of their
+    /// due times (ties go to the agent listed first). Orders are booked
+    /// to the submitting agent's account.
+    ///
+    /// Refused orders are recorded in the agent's `rejections` and passed
+    /// to its strategy's `on_reject(book, rejection)`, if defined. Its
+    /// return value is scheduled like `on_event`'s. With
+    /// `on_reject="raise"` the first refusal ends the simulation instead.
+    /// Each agent's results from a previous simulation are cleared at the
+    /// start. At the end its `positions` and `pnl` are filled in from the
+    /// book's settlement totals.
+    ///
+    /// Returns every TradeBlotter, source rows and agent orders
+    /// interleaved in the order they reached the book. `skip_empty`,
+    /// `on_error`, `columns` and `transforms` work as in
+    /// `replay_with_strategy`.
+    #[pyo3(signature = (
+        source,
+        agents,
+        *,
+        skip_empty = false,
+        on_error = "raise",
+        on_reject = "report",
+        columns = None,
+        transforms = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn simulate(
+        slf: &Bound<'_, Self>,
+        source: &Bound<'_, pyo3::PyAny>,
+        agents: Vec<Py<Agent>>,
+        skip_empty: bool,
+        on_error: &str,
+        on_reject: &str,
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let on_error = RowErrorPolicy::parse(on_error)?;
+        let on_reject = RejectPolicy::parse(on_reject)?;
+        let mut accounts = HashSet::new();
+        for agent in &agents {
+            let mut agent = agent.borrow_mut(py);
+            if !accounts.insert(agent.account.clone()) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Agents must have distinct accounts; '{}' is repeated",
+                    agent.account
+                )));
+            }
+            agent.reset();
+        }
+        let schema = RowSchema::new(columns, transforms)?;
+        let (rows, errors) = read_source_rows(source, &schema, on_error, py)?;
+        let mut blotters: Vec<Py<TradeBlotter>> = Vec::with_capacity(rows.len());
+        let apply = |until: Option<i64>, blotters: &mut Vec<Py<TradeBlotter>>| -> PyResult<()> {
+            loop {
+                let next = agents
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, agent)| agent.borrow(py).queue.next_due().map(|due| (due, i)))
+                    .filter(|&(due, _)| until.is_none_or(|until| due <= until))
+                    .min();
+                let Some((due, i)) = next else {
+                    return Ok(());
+                };
+                let rejection = {
+                    let mut agent = agents[i].borrow_mut(py);
+                    let Some((_, action)) = agent.queue.pop_due(None) else {
+                        continue;
+                    };
+                    slf.borrow_mut().apply_agent_action(
+                        &mut agent, due, action, blotters, skip_empty, on_reject, py,
+                    )?
+                };
+                let Some(rejection) = rejection else {
+                    continue;
+                };
+                let strategy = agents[i].borrow(py).strategy.clone_ref(py);
+                if strategy.bind(py).hasattr("on_reject")? {
+                    let result = strategy.call_method1(py, "on_reject", (slf, rejection))?;
+                    let actions = extract_actions(result.bind(py))?;
+                    agents[i].borrow_mut(py).queue.schedule(due, actions);
+                }
+            }
+        };
+        for row in rows {
+            let now = row.timestamp.or(slf.borrow().current_time).unwrap_or(0);
+            apply(Some(now), &mut blotters)?;
+            let event = {
+                let mut book = slf.borrow_mut();
+                if let Some(ts) = row.timestamp {
+                    book.advance_clock(ts)?;
+                }
+                let order = book.row_order(&row)?;
+                let blotter = book.match_single(&order)?;
+                let traded = !blotter.trades.is_empty();
+                let event = Py::new(py, blotter)?;
+                if traded || !skip_empty {
+                    blotters.push(event.clone_ref(py));
+                }
+                event
+            };
+            for agent in &agents {
+                let strategy = agent.borrow(py).strategy.clone_ref(py);
+                let result = strategy.call_method1(py, "on_event", (slf, event.clone_ref(py)))?;
+                let actions = extract_actions(result.bind(py))?;
+                agent.borrow_mut(py).queue.schedule(now, actions);
+            }
+        }
+        apply(None, &mut blotters)?;
+        let book = slf.borrow();
+        for agent in &agents {
+            let mut agent = agent.borrow_mut(py);
+            let (positions, pnl) = book.mark_account(&agent.account)?;
+            agent.positions = positions;
+            agent.pnl = pnl;
+        }
+        drop(book);
+        slf.borrow_mut().publish()?;
+        on_error.wrap(PyList::new(py, blotters)?.into(), errors, py)
     }
 
     /// Ingest a snapshot parquet file directly into the book as standing orders.
@@ -340,345 +1920,5771 @@ impl Book {
     /// - price: numeric
     /// - quantity: integer
     ///
-    /// Returns the number of ingested rows.
-    fn ingest_parquet(&mut self, path: &str, py: Python<'_>) -> PyResult<usize> {
-        let rows = read_parquet_rows(path, py)?;
-        for row in &rows {
-            self.enqueue_internal(&row.to_order()?);
+    /// With `aggregated=True` each row is a price level holding its total
+    /// quantity, ingested as one synthetic order per level exactly as if
+    /// passed to `apply_l2_update`, and crossed symbols are handled by
+    /// `on_crossed` as in `resync_from_snapshot`.
+    ///
+    /// Returns the number of ingested rows. `on_error`, `columns` and
+    /// `transforms` work as in `replay_parquet`; with
+    /// `on_error="collect"` the result is `(count, errors)`.
+    #[pyo3(signature = (
+        path,
+        aggregated = false,
+        *,
+        on_error = "raise",
+        columns = None,
+        transforms = None,
+        on_crossed = "drop"
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn ingest_parquet(
+        &mut self,
+        path: &str,
+        aggregated: bool,
+        on_error: &str,
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+        on_crossed: &str,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let on_error = RowErrorPolicy::parse(on_error)?;
+        let schema = RowSchema::new(columns, transforms)?;
+        let policy = SnapshotPolicy::parse(on_crossed)?;
+        let (rows, errors) = read_parquet_rows(path, &schema, on_error, py)?;
+        if aggregated {
+            let mut symbols: Vec<&str> = Vec::new();
+            let mut levels: HashMap<&str, Vec<(Side, Decimal, i64)>> = HashMap::new();
+            for row in &rows {
+                let level = (row.side, price_to_decimal(row.price)?, self.row_units(row)?);
+                levels
+                    .entry(&row.symbol)
+                    .or_insert_with(|| {
+                        symbols.push(&row.symbol);
+                        Vec::new()
+                    })
+                    .push(level);
+            }
+            for symbol in symbols {
+                let symbol_levels = levels.remove(symbol).unwrap_or_default();
+                self.seed_snapshot_levels(symbol, symbol_levels, policy)?;
+            }
+        } else {
+            for row in &rows {
+                let order = self.row_order(row)?;
+                self.accept_order(&order)?;
+                self.rest_order(&order);
+            }
         }
-        Ok(rows.len())
+        self.publish()?;
+        let count = rows.len().into_pyobject(py)?.into_any().unbind();
+        on_error.wrap(count, errors, py)
     }
 
-    /// Build a Book from a snapshot parquet file.
+    /// Build a Book from a snapshot parquet file. Options are those of
+    /// `ingest_parquet`, except that `on_error` may only be "raise" or
+    /// "skip".
     #[staticmethod]
-    fn from_parquet(path: &str, py: Python<'_>) -> PyResult<Self> {
+    #[pyo3(signature = (
+        path,
+        aggregated = false,
+        *,
+        on_error = "raise",
+        columns = None,
+        transforms = None,
+        on_crossed = "drop"
+    ))]
+    fn from_parquet(
+        path: &str,
+        aggregated: bool,
+        on_error: &str,
+        columns: Option<&Bound<'_, PyDict>>,
+        transforms: Option<&Bound<'_, PyDict>>,
+        on_crossed: &str,
+        py: Python<'_>,
+    ) -> PyResult<Self> {
+        if RowErrorPolicy::parse(on_error)? == RowErrorPolicy::Collect {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "from_parquet has no result to collect errors into; use ingest_parquet",
+            ));
+        }
         let mut book = Book::new();
-        book.ingest_parquet(path, py)?;
+        book.ingest_parquet(
+            path, aggregated, on_error, columns, transforms, on_crossed, py,
+        )?;
         Ok(book)
     }
 
-    /// Cancel a standing order.
+    /// Build a Book seeded from plain Python data, without touching disk.
     ///
-    /// Raises KeyError (with the UUID) if the order is not in the book,
-    /// matching the Python backend behavior.
-    fn cancel(&mut self, order: PyRef<Order>, py: Python<'_>) -> PyResult<()> {
-        let order_id = order.id;
-        let (symbol, side, price) = match self.order_map.remove(&order_id) {
-            Some(v) => v,
-            None => {
-                // Match Python: raises KeyError with the UUID object as argument
-                let py_uuid = uuid_to_py(py, order_id)?;
-                return Err(pyo3::exceptions::PyKeyError::new_err(py_uuid));
+    /// `levels` maps each symbol to `{"bid": [(price, quantity), ...],
+    /// "ask": [...]}`; either side may be omitted. Every level becomes one
+    /// synthetic resting order, exactly as if passed to `apply_l2_update`,
+    /// so a repeated price keeps the last quantity and 0 adds nothing.
+    /// Crossed symbols are handled by `on_crossed` as in
+    /// `resync_from_snapshot`.
+    #[staticmethod]
+    #[pyo3(signature = (levels, *, on_crossed = "drop"))]
+    fn from_levels(levels: &Bound<'_, PyDict>, on_crossed: &str) -> PyResult<Self> {
+        let policy = SnapshotPolicy::parse(on_crossed)?;
+        let mut book = Book::new();
+        for (symbol, sides) in levels.iter() {
+            let symbol: String = symbol.extract()?;
+            let sides = sides.downcast::<PyDict>().map_err(|_| {
+                pyo3::exceptions::PyTypeError::new_err(format!(
+                    "Levels for {} must be a dict with 'bid' and/or 'ask'",
+                    symbol
+                ))
+            })?;
+            let mut symbol_levels = Vec::new();
+            for (side_name, entries) in sides.iter() {
+                let side_name: String = side_name.extract()?;
+                let side = match side_name.as_str() {
+                    "bid" => Side::BID,
+                    "ask" => Side::ASK,
+                    _ => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Invalid side '{}' for {}. Expected 'bid' or 'ask'.",
+                            side_name, symbol
+                        )))
+                    }
+                };
+                for entry in entries.try_iter()? {
+                    let entry = entry?;
+                    let price = extract_decimal(&entry.get_item(0)?, "price")?;
+                    let quantity: i64 = entry.get_item(1)?.extract()?;
+                    symbol_levels.push((side, price, quantity));
+                }
             }
-        };
-
-        let sym_book = self.symbols.get_mut(&symbol).ok_or_else(|| {
-            pyo3::exceptions::PyValueError::new_err(format!(
-                "Price Level {}:{}:{} doesn't exist!",
-                symbol, side, price
-            ))
-        })?;
-
-        let ascending = matches!(side, Side::BID);
-        let one_side = if ascending {
-            &mut sym_book.bids
-        } else {
-            &mut sym_book.asks
-        };
-
-        if !one_side.remove_order(price, order_id, ascending) {
-            return Err(pyo3::exceptions::PyValueError::new_err(format!(
-                "Price Level {}:{}:{} doesn't exist!",
-                symbol, side, price
-            )));
+            book.seed_snapshot_levels(&symbol, symbol_levels, policy)?;
         }
-        Ok(())
-    }
-
-    /// Execute order fill between incoming and standing orders.
-    /// Updates both orders' quantities and returns a Trade.
-    fn fill(
-        &self,
-        incoming_order: &mut Order,
-        standing_order: &mut Order,
-        py: Python<'_>,
-    ) -> PyResult<Py<Trade>> {
-        let matched_quantity = incoming_order.quantity.min(standing_order.quantity);
-        standing_order.quantity -= matched_quantity;
-        incoming_order.quantity -= matched_quantity;
-        let fill_price = incoming_order
-            .side
-            .calc_fill_price(incoming_order.price, standing_order.price);
-        let trade = Trade::from_rust(
-            incoming_order.id,
-            standing_order.id,
-            matched_quantity,
-            fill_price,
-        );
-        Py::new(py, trade)
-    }
-
-    /// Add order to book directly (enqueue without matching).
-    fn enqueue_order(&mut self, order: PyRef<Order>) {
-        self.enqueue_internal(&order);
+        Ok(book)
     }
 
-    /// Return an Order by its id, or None.
-    fn get_order(
+    /// Write the resting orders to parquet, readable by `ingest_parquet`.
+    ///
+    /// One row per order with columns side, symbol, price, quantity and
+    /// expire_time, symbols in name order and each side best level first
+    /// in time priority. Quantities are asset amounts under
+    /// `set_fractional_quantities`, else engine units. Stop and auction
+    /// orders waiting outside the book are not written.
+    ///
+    /// `compression` is any codec pyarrow supports ("snappy", "zstd",
+    /// "gzip", "none", ...). With `partition_by`, e.g. `["symbol"]`, `path`
+    /// is the root of a hive-style dataset (`symbol=AAPL/...`) instead of a
+    /// single file. Returns the number of rows written.
+    #[pyo3(signature = (path, *, compression = "snappy", partition_by = None))]
+    fn export_parquet(
         &self,
-        order_id: &Bound<'_, pyo3::PyAny>,
+        path: &str,
+        compression: &str,
+        partition_by: Option<Vec<String>>,
         py: Python<'_>,
-    ) -> PyResult<Option<Py<Order>>> {
-        let id_str: String = order_id.str()?.extract()?;
-        let uid = Uuid::parse_str(&id_str).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid order_id: {}", e))
-        })?;
+    ) -> PyResult<usize> {
+        const EXPORT_COLUMNS: [&str; 5] = ["side", "symbol", "price", "quantity", "expire_time"];
+        if let Some(column) = partition_by
+            .iter()
+            .flatten()
+            .find(|column| !EXPORT_COLUMNS.contains(&column.as_str()))
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot partition by '{}'; expected columns from [{}].",
+                column,
+                EXPORT_COLUMNS.join(", ")
+            )));
+        }
+        let pa = import_pyarrow("pyarrow", py)?;
+        let pq = import_pyarrow("pyarrow.parquet", py)?;
 
-        let Some((symbol, side, price)) = self.order_map.get(&uid) else {
-            return Ok(None);
-        };
-        let sym_book = match self.symbols.get(symbol) {
-            Some(b) => b,
-            None => return Ok(None),
-        };
-        let ascending = matches!(side, Side::BID);
-        let one_side = if ascending {
-            &sym_book.bids
-        } else {
-            &sym_book.asks
-        };
-        if let Ok(idx) = one_side.find_level(*price, ascending) {
-            for entry in &one_side.levels[idx].orders {
-                if entry.id == uid {
-                    return Ok(Some(Py::new(py, entry.to_order())?));
+        let mut symbols: Vec<(&str, &SymbolBook)> = self.symbol_books().collect();
+        symbols.sort_by_key(|(symbol, _)| *symbol);
+        let (mut sides, mut names, mut prices) = (Vec::new(), Vec::new(), Vec::new());
+        let (mut quantities, mut expiries) = (Vec::new(), Vec::new());
+        for (symbol, sym_book) in symbols {
+            for (side, one_side) in [(Side::BID, &sym_book.bids), (Side::ASK, &sym_book.asks)] {
+                for entry in one_side.levels.iter().rev().flat_map(|l| &l.orders) {
+                    let quantity = if self.fractional_quantities {
+                        let amount = Decimal::from(entry.quantity) * self.quantity_unit(symbol);
+                        decimal_to_py(py, amount)?
+                    } else {
+                        entry.quantity.into_pyobject(py)?.into_any().unbind()
+                    };
+                    sides.push(side.as_str());
+                    names.push(symbol);
+                    prices.push(entry.price.to_f64());
+                    quantities.push(quantity);
+                    expiries.push(entry.expire_time);
                 }
             }
         }
-        Ok(None)
+        let count = sides.len();
+        let columns = PyDict::new(py);
+        columns.set_item("side", sides)?;
+        columns.set_item("symbol", names)?;
+        columns.set_item("price", prices)?;
+        columns.set_item("quantity", quantities)?;
+        columns.set_item("expire_time", expiries)?;
+        let table = pa.call_method1("table", (columns,))?;
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("compression", compression)?;
+        let written = match partition_by {
+            Some(partition_cols) => {
+                kwargs.set_item("partition_cols", partition_cols)?;
+                pq.call_method("write_to_dataset", (table, path), Some(&kwargs))
+            }
+            None => pq.call_method("write_table", (table, path), Some(&kwargs)),
+        };
+        written.map_err(|err| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Failed to write parquet '{}': {}",
+                path, err
+            ))
+        })?;
+        Ok(count)
     }
 
-    /// Return a PriceLevel snapshot for a given symbol/side/price, or None.
+    /// Set the total quantity resting at one price level from an L2 delta.
     ///
-    /// Note: returns a snapshot (copy) of the current state. Modifications to
-    /// the returned PriceLevel do not affect the book. This matches the Rust
-    /// backend's value semantics. The Python backend returns a live reference.
-    fn get_level(
-        &self,
+    /// Each L2-maintained level is a single synthetic resting order; a
+    /// `new_quantity` of 0 deletes the level. Updates bypass matching, so
+    /// the feed is trusted to stay uncrossed.
+    fn apply_l2_update(
+        &mut self,
         symbol: &str,
         side: Side,
         price: &Bound<'_, pyo3::PyAny>,
-        py: Python<'_>,
-    ) -> PyResult<Option<Py<PriceLevel>>> {
-        let price_str: String = price.str()?.extract()?;
-        let decimal_price = Decimal::from_str_exact(&price_str).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid price: {}", e))
-        })?;
-
-        let sym_book = match self.symbols.get(symbol) {
-            Some(b) => b,
-            None => return Ok(None),
-        };
-        let ascending = matches!(side, Side::BID);
-        let one_side = if ascending {
-            &sym_book.bids
-        } else {
-            &sym_book.asks
-        };
+        new_quantity: i64,
+    ) -> PyResult<()> {
+        let price = extract_decimal(price, "price")?;
+        self.set_level_quantity(symbol, side, price, new_quantity)?;
+        self.publish()
+    }
 
-        if let Ok(idx) = one_side.find_level(decimal_price, ascending) {
-            let level = &one_side.levels[idx];
-            let mut pl = PriceLevel::from_rust(side, level.price);
-            for entry in &level.orders {
-                pl.orders.append_order(entry.to_order());
+    /// Apply normalized market-data message(s) to the book, without
+    /// matching. Accepts a single dict or a list of dicts.
+    ///
+    /// Every message has a `type`:
+    /// - "add_level" / "change_level": symbol, side, price, quantity —
+    ///   set the level's total quantity (see `apply_l2_update`)
+    /// - "delete_level": symbol, side, price
+    /// - "add_order": order_id, symbol, side, price, quantity
+    /// - "cancel_order" / "execute_order": order_id, optional quantity —
+    ///   reduce the order, removing it when nothing is left
+    ///
+    /// `order_id` is the venue's identifier (any value; compared as str).
+    ///
+    /// Messages carrying an integer `sequence` (and a `symbol`) are applied
+    /// in sequence order per symbol: duplicates are dropped and messages
+    /// ahead of a gap are buffered until the gap fills or the symbol is
+    /// resynced (see `feed_gap` / `resync_from_snapshot`). With
+    /// `strict=True` a gap raises ValueError instead of only being flagged.
+    #[pyo3(signature = (update, *, strict = false))]
+    fn apply_update(&mut self, update: &Bound<'_, pyo3::PyAny>, strict: bool) -> PyResult<()> {
+        if let Ok(list) = update.downcast::<PyList>() {
+            for item in list.iter() {
+                self.apply_update(&item, strict)?;
             }
-            return Ok(Some(Py::new(py, pl)?));
+            return Ok(());
         }
-        Ok(None)
+        let msg = update.downcast::<PyDict>().map_err(|_| {
+            pyo3::exceptions::PyTypeError::new_err("Feed update must be a dict or list of dicts")
+        })?;
+        let feed_update = FeedUpdate::from_dict(msg)?;
+        let Some(seq) = message_sequence(msg)? else {
+            self.apply_feed_update(feed_update)?;
+            return self.publish();
+        };
+        let symbol = message_symbol(msg)?.ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err("Sequenced feed updates must carry a symbol")
+        })?;
+        let id = self.symbol_table.intern(&symbol);
+        let tracker = self.feed_sequences.entry(id).or_default();
+        for ready in tracker.admit(seq, feed_update) {
+            self.apply_feed_update(ready)?;
+        }
+        self.publish()?;
+        match self.feed_gap(&symbol) {
+            Some((expected, received)) if strict => {
+                Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Sequence gap on {}: expected {}, received {}",
+                    symbol, expected, received
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Last applied feed sequence number for a symbol, or None.
+    fn feed_sequence(&self, symbol: &str) -> Option<u64> {
+        self.per_symbol(&self.feed_sequences, symbol)
+            .and_then(|t| t.last)
+    }
+
+    /// `(expected, received)` sequence numbers while a symbol's feed has a
+    /// gap and later messages are buffered, else None.
+    fn feed_gap(&self, symbol: &str) -> Option<(u64, u64)> {
+        self.per_symbol(&self.feed_sequences, symbol)
+            .and_then(|t| t.gap())
+    }
+
+    /// Atomically replace a symbol's book with a venue snapshot taken as of
+    /// `sequence`, then apply buffered deltas that follow it.
+    ///
+    /// `snapshot` is a `Snapshot` or a dict with "bids" and "asks" lists of
+    /// `(price, quantity)` pairs. Every resting order for the symbol is
+    /// replaced by one synthetic order per snapshot level.
+    ///
+    /// `on_crossed` handles snapshots caught mid-update: "drop" (the
+    /// default) leaves out the levels inside the crossed range,
+    /// "uncross_by_matching" matches crossing asks against the bids, and
+    /// "error" rejects crossed snapshots as well as zero-quantity levels,
+    /// which the other policies treat as empty placeholders.
+    #[pyo3(signature = (symbol, snapshot, sequence, *, on_crossed = "drop"))]
+    fn resync_from_snapshot(
+        &mut self,
+        symbol: &str,
+        snapshot: &Bound<'_, pyo3::PyAny>,
+        sequence: u64,
+        on_crossed: &str,
+    ) -> PyResult<()> {
+        let policy = SnapshotPolicy::parse(on_crossed)?;
+        let levels = policy.screen(symbol, extract_resync_levels(snapshot)?)?;
+        // Check everything first so a bad level leaves the book untouched.
+        for &(side, price, quantity) in &levels {
+            if quantity > 0 {
+                let order = Order::try_new_at(side, symbol.to_string(), price, quantity)?;
+                self.validate_order(&order)?;
+            }
+        }
+
+        let resting: Vec<Uuid> = self
+            .symbol_book(symbol)
+            .map(|sym_book| {
+                sym_book
+                    .bids
+                    .levels
+                    .iter()
+                    .chain(&sym_book.asks.levels)
+                    .flat_map(|level| &level.orders)
+                    .map(|entry| entry.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in resting {
+            self.remove_resting(id)?;
+        }
+        let id = self.symbol_table.intern(symbol);
+        self.l2_levels.retain(|(sym, _, _), _| *sym != id);
+        self.level_anchors.retain(|(sym, _, _), _| *sym != id);
+        let order_map = &self.order_map;
+        self.feed_orders.retain(|_, id| order_map.contains_key(id));
+
+        self.seed_snapshot_levels(symbol, levels, policy)?;
+        let tracker = self.feed_sequences.entry(id).or_default();
+        for ready in tracker.resync(sequence) {
+            self.apply_feed_update(ready)?;
+        }
+        self.publish()
+    }
+
+    /// Publish trades and L2 level changes to `callback`, `batch_size`
+    /// events at a time. Pass None to remove the publisher.
+    ///
+    /// Events are dicts: `{"type": "trade", "symbol", "trade_id",
+    /// "incoming_order_id", "standing_order_id", "price", "quantity"}` and
+    /// `{"type": "level", "symbol", "side", "price", "quantity"}`, where a
+    /// level's quantity is its new total (0 = removed). With
+    /// `encoding="json"` each batch is a JSON string instead, suitable for
+    /// forwarding to a ZeroMQ/IPC socket. With `encoding="event"` batches
+    /// are lists of typed `Event` objects (`TradeExecuted`, `LevelChanged`)
+    /// and also carry `OrderAccepted`, `OrderCancelled`, `OrderExpired`,
+    /// `StateChanged`, `SplitApplied` and `SymbolChanged` events.
+    ///
+    /// Events wait in a ring buffer of `capacity`. A callback exception
+    /// propagates from the call that triggered delivery, after the book has
+    /// been updated, and the undelivered batch stays buffered; once the
+    /// buffer is full the oldest events are dropped (see `publisher_stats`).
+    #[pyo3(signature = (callback, *, batch_size = 1, capacity = 65_536, encoding = "dict"))]
+    fn set_publisher(
+        &mut self,
+        callback: Option<PyObject>,
+        batch_size: usize,
+        capacity: usize,
+        encoding: &str,
+    ) -> PyResult<()> {
+        self.publisher = callback
+            .map(|cb| Publisher::new(cb, batch_size, capacity, encoding))
+            .transpose()?;
+        Ok(())
+    }
+
+    /// Share this book with other processes through a ring file at `path`
+    /// (ideally on /dev/shm), or stop sharing with `path=None`.
+    ///
+    /// After every call that changes the book, the depth-`depth`
+    /// snapshots of the symbols matching `symbols` are written to the next
+    /// of `slots` slots of `slot_size` bytes each. Readers open the file
+    /// with `SharedBookReader(path)` and always get the latest complete
+    /// snapshot without blocking the writer. A snapshot larger than a
+    /// slot raises ValueError.
+    #[pyo3(signature = (path, *, symbols = "*", depth = 10, slots = 8, slot_size = 1 << 20))]
+    fn share_snapshots(
+        &mut self,
+        path: Option<&str>,
+        symbols: &str,
+        depth: isize,
+        slots: u32,
+        slot_size: u32,
+    ) -> PyResult<()> {
+        self.shared = path
+            .map(|path| SnapshotRing::create(path, slots, slot_size, symbols.to_string(), depth))
+            .transpose()?;
+        self.share_snapshots_now()
+    }
+
+    /// Deliver every buffered event, including a final partial batch.
+    fn flush_publisher(&mut self, py: Python<'_>) -> PyResult<()> {
+        self.publish()?;
+        match self.publisher.as_mut() {
+            Some(publisher) => publisher.deliver(py, true),
+            None => Ok(()),
+        }
+    }
+
+    /// Publisher counters as dict(pending, published, dropped), or None.
+    fn publisher_stats(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let Some(publisher) = self.publisher.as_ref() else {
+            return Ok(None);
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("pending", publisher.ring.pending())?;
+        dict.set_item("published", publisher.published)?;
+        dict.set_item("dropped", publisher.ring.dropped)?;
+        Ok(Some(dict.into()))
+    }
+
+    /// Cancel a standing order.
+    ///
+    /// Raises KeyError (with the UUID) if the order is not in the book,
+    /// matching the Python backend behavior.
+    fn cancel(&mut self, order: PyRef<Order>, py: Python<'_>) -> PyResult<()> {
+        let order_id = order.id;
+        match self.cancel_order(order_id)? {
+            true => self.publish(),
+            false => {
+                // Match Python: raises KeyError with the UUID object as argument
+                let py_uuid = uuid_to_py(py, order_id)?;
+                Err(pyo3::exceptions::PyKeyError::new_err(py_uuid))
+            }
+        }
+    }
+
+    /// Cancel every resting and held order in the symbols matching `symbol`
+    /// (an exact symbol or a glob such as `"ES*"`), returning how many were
+    /// cancelled.
+    #[pyo3(signature = (symbol = "*"))]
+    fn cancel_all(&mut self, symbol: &str) -> PyResult<usize> {
+        let pattern = SymbolPattern::new(symbol);
+        let duplicates = self
+            .duplicate_ids
+            .iter()
+            .flat_map(|(id, located)| located.iter().map(move |l| (id, l)));
+        let resting = self
+            .order_map
+            .iter()
+            .chain(duplicates)
+            .filter(|(_, (symbol, _, _))| pattern.matches(self.symbol_table.name(*symbol)))
+            .map(|(id, _)| *id);
+        let held = self
+            .auction_orders
+            .iter()
+            .chain(&self.stops)
+            .filter(|(symbol, _)| pattern.matches(self.symbol_table.name(**symbol)))
+            .flat_map(|(_, entries)| entries.iter().map(|entry| entry.id));
+        let ids: Vec<Uuid> = resting.chain(held).collect();
+        for &id in &ids {
+            self.cancel_order(id)?;
+        }
+        self.publish()?;
+        Ok(ids.len())
+    }
+
+    /// Cancel every resting and held order passing all the given filters,
+    /// returning the cancelled order ids.
+    ///
+    /// `symbol` may be a glob such as `"ES*"`. `price_below` and
+    /// `price_above` compare limit prices strictly, and `older_than_seq`
+    /// keeps orders that arrived before that value of `sequence`.
+    #[pyo3(signature = (symbol = None, side = None, price_below = None, price_above = None, older_than_seq = None))]
+    fn cancel_where(
+        &mut self,
+        symbol: Option<&str>,
+        side: Option<Side>,
+        price_below: Option<&Bound<'_, pyo3::PyAny>>,
+        price_above: Option<&Bound<'_, pyo3::PyAny>>,
+        older_than_seq: Option<u64>,
+        py: Python<'_>,
+    ) -> PyResult<Vec<PyObject>> {
+        let pattern = SymbolPattern::new(symbol.unwrap_or("*"));
+        let below = price_below
+            .map(|price| extract_decimal(price, "price_below"))
+            .transpose()?;
+        let above = price_above
+            .map(|price| extract_decimal(price, "price_above"))
+            .transpose()?;
+        let symbol_table = &self.symbol_table;
+        let selected = |entry: &&OrderEntry| {
+            pattern.matches(symbol_table.name(entry.symbol))
+                && side.is_none_or(|side| entry.side == side)
+                && below.is_none_or(|price| entry.price < price)
+                && above.is_none_or(|price| entry.price > price)
+                && older_than_seq.is_none_or(|seq| entry.seq < seq)
+        };
+        let resting = self
+            .symbols
+            .values()
+            .flat_map(|sym_book| sym_book.bids.levels.iter().chain(&sym_book.asks.levels))
+            .flat_map(|level| level.orders.iter());
+        let held = self
+            .auction_orders
+            .values()
+            .chain(self.stops.values())
+            .flatten();
+        let ids: Vec<Uuid> = resting
+            .chain(held)
+            .filter(selected)
+            .map(|entry| entry.id)
+            .collect();
+        for &id in &ids {
+            self.cancel_order(id)?;
+        }
+        self.publish()?;
+        Ok(uuids_to_py(py, ids)?
+            .into_iter()
+            .map(Bound::unbind)
+            .collect())
+    }
+
+    /// Cancel many orders by id in one pass, returning a list of bools,
+    /// True where that order was resting or held and is now cancelled.
+    ///
+    /// `ids` is a numpy array or other buffer of 16-byte UUIDs (dtype
+    /// `"S16"`, or `uint8` of shape `(n, 16)`, as from `UUID.bytes`), of
+    /// 64-bit sequence ids (`uint64`, each `n` standing for `UUID(int=n)`
+    /// as the engine's counter ids are), or any iterable of Orders, UUIDs
+    /// or id strings.
+    fn cancel_many(&mut self, ids: &Bound<'_, pyo3::PyAny>) -> PyResult<Vec<bool>> {
+        let ids = order_ids_from_py(ids)?;
+        let cancelled = ids
+            .into_iter()
+            .map(|id| self.cancel_order(id))
+            .collect::<PyResult<Vec<bool>>>()?;
+        self.publish()?;
+        Ok(cancelled)
+    }
+
+    /// Disconnect the connection session `session_id`: cancel every
+    /// resting and held order tagged with it (see `Order.session_id`),
+    /// publishing an `OrderCancelled` for each, and return their ids.
+    ///
+    /// With a `delay`, the cancels wait until the simulated clock reaches
+    /// the current time plus `delay`, and `reconnect` within that grace
+    /// period calls them off; orders the session sends meanwhile are
+    /// cancelled with the rest. Returns an empty list in that case.
+    #[pyo3(signature = (session_id, *, delay = 0))]
+    fn disconnect(
+        &mut self,
+        session_id: &str,
+        delay: i64,
+        py: Python<'_>,
+    ) -> PyResult<Vec<PyObject>> {
+        let cancelled = self.journaled(
+            || JournalEntry::Disconnect {
+                session_id: session_id.to_string(),
+                delay,
+            },
+            |book| book.disconnect_session(session_id, delay),
+        )?;
+        self.publish()?;
+        Ok(uuids_to_py(py, cancelled)?
+            .into_iter()
+            .map(Bound::unbind)
+            .collect())
+    }
+
+    /// Call off the pending cancel-on-disconnect of `session_id`; false if
+    /// none was pending.
+    fn reconnect(&mut self, session_id: &str) -> PyResult<bool> {
+        self.journaled(
+            || JournalEntry::Reconnect(session_id.to_string()),
+            |book| Ok(book.reconnect_session(session_id)),
+        )
+    }
+
+    /// Sessions disconnected with a delay, mapped to the simulated time at
+    /// which their orders will be cancelled.
+    fn pending_disconnects(&self) -> HashMap<String, i64> {
+        self.disconnects.deadlines().clone()
+    }
+
+    /// Replace the two-sided quote `quote_id` on `symbol` in one operation:
+    /// its previous bid and ask are cancelled (if still resting) and the new
+    /// ones matched. Quote ids are scoped to their symbol. Returns `(bid_blotter, ask_blotter)`; a side quoted with
+    /// quantity 0 is left empty and its blotter is None.
+    ///
+    /// Both new orders are checked before anything is cancelled, so a
+    /// rejected quote leaves the previous one in place. The bid must be
+    /// below the ask. Quotes booked to an `account` count against its
+    /// `set_quote_protection` limits.
+    #[pyo3(signature = (symbol, bid_price, bid_qty, ask_price, ask_qty, quote_id, *, account = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn quote(
+        &mut self,
+        symbol: String,
+        bid_price: f64,
+        bid_qty: &Bound<'_, pyo3::PyAny>,
+        ask_price: f64,
+        ask_qty: &Bound<'_, pyo3::PyAny>,
+        quote_id: String,
+        account: Option<String>,
+    ) -> PyResult<(Option<TradeBlotter>, Option<TradeBlotter>)> {
+        let bid_qty = self.quantity_units(&symbol, extract_decimal(bid_qty, "bid_qty")?)?;
+        let ask_qty = self.quantity_units(&symbol, extract_decimal(ask_qty, "ask_qty")?)?;
+        let leg = |side: Side, price: f64, quantity: i64| -> PyResult<Option<Order>> {
+            if quantity == 0 {
+                return Ok(None);
+            }
+            let order = Order::try_new(side, symbol.clone(), price, quantity)?
+                .with_account(account.clone());
+            self.validate_order(&order)?;
+            self.check_trading_state(&order)?;
+            Ok(Some(order))
+        };
+        let bid = leg(Side::BID, bid_price, bid_qty)?;
+        let ask = leg(Side::ASK, ask_price, ask_qty)?;
+        if let (Some(bid), Some(ask)) = (&bid, &ask) {
+            if bid.price >= ask.price {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Quote {} bid {} must be below its ask {}",
+                    quote_id, bid.price, ask.price
+                )));
+            }
+        }
+        let now = self.current_time.unwrap_or(0);
+        if let Some(limits) = account
+            .as_ref()
+            .and_then(|account| self.quote_protection.get_mut(account))
+        {
+            if let Err(rate) = limits.allow_update(now) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Quote {} rejected: account {} is limited to {} updates per {} clock units",
+                    quote_id,
+                    account.as_deref().unwrap_or_default(),
+                    rate.limit,
+                    rate.window
+                )));
+            }
+        }
+        let key = (self.symbol_table.intern(&symbol), quote_id);
+        if let Some(old) = self.quotes.remove(&key) {
+            for id in old.ids() {
+                self.cancel_order(id)?;
+            }
+        }
+        let bid = bid.map(|order| self.match_single(&order)).transpose()?;
+        let ask = ask.map(|order| self.match_single(&order)).transpose()?;
+        let id = |blotter: &Option<TradeBlotter>| blotter.as_ref().map(|b| b.order.id);
+        let orders = QuoteOrders {
+            account,
+            bid: id(&bid),
+            ask: id(&ask),
+        };
+        self.quotes.insert(key, orders);
+        self.publish()?;
+        Ok((bid, ask))
+    }
+
+    /// Copy every order event and execution of orders booked to
+    /// `accounts`, whoever submitted them, to `sink` (a callable or a
+    /// queue) and/or a JSON-lines journal at `path`.
+    ///
+    /// The sink receives the typed `Event`s of `set_publisher(...,
+    /// encoding="event")` with `account` set; journal lines are their dict
+    /// form plus an `account` key. Orders accepted before the drop copy
+    /// was added are not covered.
+    #[pyo3(signature = (accounts, sink = None, *, path = None))]
+    fn add_drop_copy(
+        &mut self,
+        accounts: Vec<String>,
+        sink: Option<&Bound<'_, pyo3::PyAny>>,
+        path: Option<&str>,
+    ) -> PyResult<()> {
+        let sink = sink.map(event_sink).transpose()?;
+        let copy = DropCopy::new(accounts.into_iter().collect(), sink, path)?;
+        self.drop_copies.push(copy);
+        Ok(())
+    }
+
+    /// Exchange-style market-maker protections for `account`'s quotes,
+    /// measured on the simulated clock.
+    ///
+    /// With `max_updates` and `update_window`, a `quote` beyond that many
+    /// updates within the window is rejected. With `max_fills` and
+    /// `fill_window`, that many fills against the account's quotes within
+    /// the window pull all of them, publishing a `QuotesPulled` event.
+    /// Calling with neither pair removes the protections.
+    #[pyo3(signature = (account, *, max_updates = None, update_window = None, max_fills = None, fill_window = None))]
+    fn set_quote_protection(
+        &mut self,
+        account: String,
+        max_updates: Option<usize>,
+        update_window: Option<i64>,
+        max_fills: Option<usize>,
+        fill_window: Option<i64>,
+    ) -> PyResult<()> {
+        let rate = |limit: Option<usize>, window: Option<i64>, names: &str| match (limit, window) {
+            (None, None) => Ok(None),
+            (Some(limit), Some(window)) if limit > 0 && window > 0 => {
+                Ok(Some(RateLimit { limit, window }))
+            }
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{} must be given together and be positive",
+                names
+            ))),
+        };
+        let updates = rate(max_updates, update_window, "max_updates and update_window")?;
+        let fills = rate(max_fills, fill_window, "max_fills and fill_window")?;
+        if updates.is_none() && fills.is_none() {
+            self.quote_protection.remove(&account);
+        } else {
+            self.quote_protection
+                .insert(account, QuoteProtection::new(updates, fills));
+        }
+        Ok(())
+    }
+
+    /// Exchange-style message throttle: at most `max_messages` orders
+    /// within any `window` clock units, from the connection session
+    /// `session_id` (see `Order.session_id`) or, without one, into the
+    /// whole book.
+    ///
+    /// With `on_limit="reject"` an order beyond the limit raises
+    /// `OrderRejected` with code "throttled"; with `on_limit="queue"` it
+    /// waits (with an empty blotter) and is matched once the simulated
+    /// clock advances far enough, in arrival order. Queued orders can be
+    /// cancelled; one the book refuses when released is dropped, counted
+    /// as a rejection of its symbol and logged. Passing None for both
+    /// limits removes it.
+    #[pyo3(signature = (max_messages, window, *, session_id = None, on_limit = "reject"))]
+    fn set_rate_limit(
+        &mut self,
+        max_messages: Option<usize>,
+        window: Option<i64>,
+        session_id: Option<String>,
+        on_limit: &str,
+    ) -> PyResult<()> {
+        let action = ThrottleAction::parse(on_limit)?;
+        let throttle = match (max_messages, window) {
+            (None, None) => None,
+            (Some(limit), Some(window)) if limit > 0 && window > 0 => {
+                Some(Throttle::new(RateLimit { limit, window }, action))
+            }
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "max_messages and window must be given together and be positive",
+                ))
+            }
+        };
+        match (session_id, throttle) {
+            (None, throttle) => self.throttles.global = throttle,
+            (Some(session), Some(throttle)) => {
+                self.throttles.sessions.insert(session, throttle);
+            }
+            (Some(session), None) => {
+                self.throttles.sessions.remove(&session);
+            }
+        }
+        Ok(())
+    }
+
+    /// Utilization of the rate limits at the current simulated time, as
+    /// a dict with `"global"` (the book-wide limit's stats, or None) and
+    /// `"sessions"` (dict[session_id, stats]). Each stats dict has
+    /// `max_messages`, `window`, `on_limit`, `in_window` (messages in the
+    /// window ending now), `utilization` (their fraction of the limit),
+    /// `sent`, `rejected`, `queued` (orders waiting) and `dropped` (queued
+    /// orders the book refused once released).
+    fn rate_limit_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let now = self.current_time.unwrap_or(0);
+        let stats = |throttle: &Throttle, scope: Option<&str>| -> PyResult<PyObject> {
+            let dict = PyDict::new(py);
+            let in_window = throttle.in_window(now);
+            dict.set_item("max_messages", throttle.rate.limit)?;
+            dict.set_item("window", throttle.rate.window)?;
+            dict.set_item("on_limit", throttle.action.as_str())?;
+            dict.set_item("in_window", in_window)?;
+            dict.set_item("utilization", in_window as f64 / throttle.rate.limit as f64)?;
+            dict.set_item("sent", throttle.sent)?;
+            dict.set_item("rejected", throttle.rejected)?;
+            dict.set_item("queued", self.throttles.queued(scope))?;
+            dict.set_item("dropped", throttle.dropped)?;
+            Ok(dict.into())
+        };
+        let sessions = PyDict::new(py);
+        for (session, throttle) in &self.throttles.sessions {
+            sessions.set_item(session, stats(throttle, Some(session))?)?;
+        }
+        let result = PyDict::new(py);
+        let global = self
+            .throttles
+            .global
+            .as_ref()
+            .map(|throttle| stats(throttle, None))
+            .transpose()?;
+        result.set_item("global", global)?;
+        result.set_item("sessions", sessions)?;
+        Ok(result.into())
+    }
+
+    /// Engine statistics as a dict: `resting_orders` and `price_levels`
+    /// across the book, and how the allocation pools fared.
+    ///
+    /// The queues of emptied price levels and the entries of filled orders
+    /// are kept per side for reuse; `levels_allocated` and
+    /// `entries_allocated` count those made fresh, `levels_reused` and
+    /// `entries_reused` those taken from the pools, and `spare_levels` and
+    /// `spare_entries` what the pools hold now.
+    ///
+    /// Each side also remembers the levels of its last few prices looked
+    /// up; `level_cache_hits` and `level_cache_misses` count the lookups
+    /// answered from it and those that fell back to a binary search.
+    fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let mut pools = PoolStats::default();
+        let mut cache = LevelCacheStats::default();
+        let (mut orders, mut levels, mut spare_levels, mut spare_entries) = (0, 0, 0, 0);
+        for sym_book in self.symbols.values() {
+            for one_side in [&sym_book.bids, &sym_book.asks] {
+                pools.merge(one_side.pool.stats);
+                cache.merge(one_side.cache.stats);
+                orders += one_side
+                    .levels
+                    .iter()
+                    .map(|l| l.orders.len())
+                    .sum::<usize>();
+                levels += one_side.levels.len();
+                spare_levels += one_side.pool.spare_levels();
+                spare_entries += one_side.pool.spare_entries();
+            }
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("resting_orders", orders)?;
+        dict.set_item("price_levels", levels)?;
+        dict.set_item("levels_allocated", pools.levels_allocated)?;
+        dict.set_item("levels_reused", pools.levels_reused)?;
+        dict.set_item("spare_levels", spare_levels)?;
+        dict.set_item("entries_allocated", pools.entries_allocated)?;
+        dict.set_item("entries_reused", pools.entries_reused)?;
+        dict.set_item("spare_entries", spare_entries)?;
+        dict.set_item("level_cache_hits", cache.hits)?;
+        dict.set_item("level_cache_misses", cache.misses)?;
+        Ok(dict.into())
+    }
+
+    /// Cap the book's size: resting orders per symbol, price levels per
+    /// side of a symbol, and resting orders in the whole book. Stop and
+    /// auction orders, held rather than resting, don't count.
+    ///
+    /// With `on_limit="reject"` an order that would rest past a cap raises
+    /// `OrderRejected` with code "capacity" on arrival, even if it would
+    /// have traded; with `on_limit="evict"` it is accepted and the
+    /// worst-priced orders on its side, latest arrival first, are cancelled
+    /// (which may be the new order itself) until the caps hold. Caps are
+    /// enforced as orders arrive, not on what already rests. Setting them
+    /// restarts the counts of `capacity_stats`; passing none removes them.
+    #[pyo3(signature = (*, max_orders_per_symbol = None, max_levels_per_side = None, max_total_orders = None, on_limit = "reject"))]
+    fn set_capacity_limits(
+        &mut self,
+        max_orders_per_symbol: Option<usize>,
+        max_levels_per_side: Option<usize>,
+        max_total_orders: Option<usize>,
+        on_limit: &str,
+    ) -> PyResult<()> {
+        let action = CapacityAction::parse(on_limit)?;
+        let caps = [max_orders_per_symbol, max_levels_per_side, max_total_orders];
+        if caps.contains(&Some(0)) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Capacity limits must be at least 1",
+            ));
+        }
+        self.capacity = caps.iter().any(Option::is_some).then_some(CapacityLimits {
+            max_orders_per_symbol,
+            max_levels_per_side,
+            max_total_orders,
+            action,
+            rejected: 0,
+            evicted: 0,
+        });
+        Ok(())
+    }
+
+    /// The capacity limits as a dict of `max_orders_per_symbol`,
+    /// `max_levels_per_side`, `max_total_orders`, `on_limit`,
+    /// `total_orders` (resting now), `rejected` and `evicted`, or None if
+    /// no limits are set.
+    fn capacity_stats(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let Some(limits) = self.capacity.as_ref() else {
+            return Ok(None);
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("max_orders_per_symbol", limits.max_orders_per_symbol)?;
+        dict.set_item("max_levels_per_side", limits.max_levels_per_side)?;
+        dict.set_item("max_total_orders", limits.max_total_orders)?;
+        dict.set_item("on_limit", limits.action.as_str())?;
+        dict.set_item("total_orders", self.resting_count())?;
+        dict.set_item("rejected", limits.rejected)?;
+        dict.set_item("evicted", limits.evicted)?;
+        Ok(Some(dict.into()))
+    }
+
+    /// Record when each matched order is received, passes its checks,
+    /// finishes matching and has its blotter ready, on a monotonic
+    /// nanosecond clock. The latest `capacity` orders are kept for
+    /// `latency_stats`; turning tracking off discards them.
+    #[pyo3(signature = (enabled = true, *, capacity = DEFAULT_LATENCY_CAPACITY))]
+    fn set_latency_tracking(&mut self, enabled: bool, capacity: usize) -> PyResult<()> {
+        if capacity == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "capacity must be at least 1",
+            ));
+        }
+        self.latency = enabled.then(|| LatencyRecorder::new(capacity));
+        Ok(())
+    }
+
+    /// Per-phase latency over the tracked orders, as dict[phase, dict] with
+    /// `count`, `mean_ns`, `p50_ns`, `p99_ns` and `max_ns`. Phases are
+    /// "risk_check", "match", "ack" (blotter construction and stop
+    /// cascades) and "total". None unless tracking is on.
+    fn latency_stats(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let Some(latency) = self.latency.as_ref() else {
+            return Ok(None);
+        };
+        let phases = PyDict::new(py);
+        for (name, stats) in latency.stats() {
+            let phase = PyDict::new(py);
+            phase.set_item("count", stats.count)?;
+            phase.set_item("mean_ns", stats.mean)?;
+            phase.set_item("p50_ns", stats.p50)?;
+            phase.set_item("p99_ns", stats.p99)?;
+            phase.set_item("max_ns", stats.max)?;
+            phases.set_item(name, phase)?;
+        }
+        Ok(Some(phases.into()))
+    }
+
+    /// Attribute the time spent on orders to phases: validation, level
+    /// lookup, the matching loop, other engine work, and building the
+    /// Python objects `match` takes and returns. One order in
+    /// `sample_every` is timed, keeping the clock reads off most orders;
+    /// the Python boundary is timed per `match` call. Turning profiling
+    /// off discards the totals.
+    #[pyo3(signature = (enabled = true, *, sample_every = DEFAULT_SAMPLE_EVERY))]
+    fn set_profiling(&mut self, enabled: bool, sample_every: u64) -> PyResult<()> {
+        if sample_every == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "sample_every must be at least 1",
+            ));
+        }
+        self.profiler = enabled.then(|| Profiler::new(sample_every));
+        Ok(())
+    }
+
+    /// Where order handling time goes, as a dict with `orders`, `sampled`,
+    /// `sample_every`, `phases` (dict[phase, dict] with `mean_ns` per order
+    /// and `share` of the total) and `bound`: "boundary" when building
+    /// Python objects takes longer than the engine itself, else
+    /// "matching". None unless profiling is on.
+    fn profile_report(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let Some(profiler) = self.profiler.as_ref() else {
+            return Ok(None);
+        };
+        let means = profiler.means();
+        let total: f64 = means.iter().map(|(_, mean)| mean).sum();
+        let boundary: f64 = means
+            .iter()
+            .filter(|(phase, _)| *phase == Phase::PythonObjects)
+            .map(|(_, mean)| mean)
+            .sum();
+        let phases = PyDict::new(py);
+        for (phase, mean) in means {
+            let stats = PyDict::new(py);
+            stats.set_item("mean_ns", mean)?;
+            stats.set_item("share", if total > 0.0 { mean / total } else { 0.0 })?;
+            phases.set_item(phase.name(), stats)?;
+        }
+        let report = PyDict::new(py);
+        report.set_item("orders", profiler.orders)?;
+        report.set_item("sampled", profiler.sampled)?;
+        report.set_item("sample_every", profiler.sample_every())?;
+        report.set_item("phases", phases)?;
+        let bound = if boundary > total - boundary {
+            "boundary"
+        } else {
+            "matching"
+        };
+        report.set_item("bound", bound)?;
+        Ok(Some(report.into()))
+    }
+
+    /// Watch order-by-order feed updates (`apply_update`) for likely
+    /// iceberg refills: an `execute_order` leaving nothing of an order,
+    /// then an `add_order` of the same side, price and size within
+    /// `window` messages on the symbol. A price and size refilled at least
+    /// `min_refills` times is reported. Turning detection off discards
+    /// what was found.
+    #[pyo3(signature = (enabled = true, *, window = DEFAULT_REFILL_WINDOW, min_refills = DEFAULT_MIN_REFILLS))]
+    fn set_iceberg_detection(
+        &mut self,
+        enabled: bool,
+        window: usize,
+        min_refills: u32,
+    ) -> PyResult<()> {
+        if window == 0 || min_refills == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "window and min_refills must be at least 1",
+            ));
+        }
+        self.icebergs = enabled.then(|| IcebergDetector::new(window, min_refills));
+        Ok(())
+    }
+
+    /// Likely icebergs per symbol, as `{symbol: [{"side", "price", "size",
+    /// "refills"}]}` with the most refilled first; only `symbol` if given.
+    /// None unless detection is on.
+    #[pyo3(signature = (symbol = None))]
+    fn iceberg_report(&self, py: Python<'_>, symbol: Option<String>) -> PyResult<Option<PyObject>> {
+        let Some(icebergs) = self.icebergs.as_ref() else {
+            return Ok(None);
+        };
+        let symbols = match symbol {
+            Some(symbol) => vec![symbol],
+            None => icebergs.symbols(),
+        };
+        let report = PyDict::new(py);
+        for symbol in symbols {
+            let found = PyList::empty(py);
+            for candidate in icebergs.report(&symbol) {
+                let entry = PyDict::new(py);
+                entry.set_item("side", candidate.side.as_str())?;
+                entry.set_item("price", decimal_to_py(py, candidate.price)?)?;
+                entry.set_item("size", candidate.size)?;
+                entry.set_item("refills", candidate.refills)?;
+                found.append(entry)?;
+            }
+            report.set_item(symbol, found)?;
+        }
+        Ok(Some(report.into()))
+    }
+
+    /// The book's metrics in the Prometheus text exposition format, for a
+    /// scrape endpoint to return as is.
+    ///
+    /// Per symbol: counters of orders accepted and rejected, orders
+    /// cancelled and expired, trades and traded volume since the book was
+    /// created, and gauges of resting orders and, per side, displayed
+    /// quantity, price levels and best price. With latency tracking on,
+    /// a `pyorderbook_latency_ns` summary per phase.
+    fn metrics_text(&self) -> String {
+        let mut symbols: Vec<&str> = self
+            .counters
+            .keys()
+            .map(|id| self.symbol_table.name(*id))
+            .chain(self.symbol_books().map(|(symbol, _)| symbol))
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        let counted = |field: CounterValue| {
+            symbols.iter().map(move |symbol| {
+                let counters = self
+                    .per_symbol(&self.counters, symbol)
+                    .copied()
+                    .unwrap_or_default();
+                let labels = vec![("symbol", symbol.to_string())];
+                (labels, field(&counters).to_string())
+            })
+        };
+        let mut out = Exposition::default();
+        for (name, help, field) in SymbolCounters::FAMILIES {
+            out.family(name, "counter", help, counted(field));
+        }
+
+        let books: Vec<(&str, &SymbolBook)> = symbols
+            .iter()
+            .filter_map(|symbol| Some((*symbol, self.symbol_book(symbol)?)))
+            .collect();
+        out.family(
+            "pyorderbook_resting_orders",
+            "gauge",
+            "Orders resting in the book.",
+            books.iter().map(|(symbol, book)| {
+                let count: usize = [&book.bids, &book.asks]
+                    .iter()
+                    .flat_map(|side| &side.levels)
+                    .map(|level| level.orders.len())
+                    .sum();
+                (vec![("symbol", symbol.to_string())], count.to_string())
+            }),
+        );
+        let per_side = |value: fn(&pyorderbook_core::OneSide<OrderEntry>) -> Option<String>| {
+            books.iter().flat_map(move |(symbol, book)| {
+                [(Side::BID, &book.bids), (Side::ASK, &book.asks)]
+                    .into_iter()
+                    .filter_map(move |(side, levels)| {
+                        let labels = vec![
+                            ("symbol", symbol.to_string()),
+                            ("side", side.as_str().to_string()),
+                        ];
+                        Some((labels, value(levels)?))
+                    })
+            })
+        };
+        out.family(
+            "pyorderbook_depth_quantity",
+            "gauge",
+            "Displayed quantity resting on one side.",
+            per_side(|side| {
+                let total: i64 = side.levels.iter().map(|l| l.displayed_quantity()).sum();
+                Some(total.to_string())
+            }),
+        );
+        out.family(
+            "pyorderbook_depth_levels",
+            "gauge",
+            "Price levels showing quantity on one side.",
+            per_side(|side| {
+                let shown = side.levels.iter().filter(|l| l.displayed_quantity() > 0);
+                Some(shown.count().to_string())
+            }),
+        );
+        out.family(
+            "pyorderbook_best_price",
+            "gauge",
+            "Best displayed price on one side.",
+            per_side(|side| {
+                let best = side
+                    .levels
+                    .iter()
+                    .rev()
+                    .find(|l| l.displayed_quantity() > 0)?;
+                Some(best.price.to_string())
+            }),
+        );
+
+        if let Some(latency) = self.latency.as_ref() {
+            let stats = latency.stats();
+            let name = "pyorderbook_latency_ns";
+            out.family(
+                name,
+                "summary",
+                "Order processing latency by phase, over the tracked orders.",
+                stats.iter().flat_map(|(phase, stats)| {
+                    [("0.5", stats.p50), ("0.99", stats.p99), ("1", stats.max)].map(
+                        |(quantile, value)| {
+                            let labels = vec![
+                                ("phase", phase.to_string()),
+                                ("quantile", quantile.to_string()),
+                            ];
+                            (labels, value.to_string())
+                        },
+                    )
+                }),
+            );
+            for (phase, stats) in &stats {
+                let labels = [("phase", phase.to_string())];
+                let sum = stats.mean * stats.count as f64;
+                out.sample(&format!("{}_sum", name), &labels, &sum.to_string());
+                out.sample(
+                    &format!("{}_count", name),
+                    &labels,
+                    &stats.count.to_string(),
+                );
+            }
+        }
+        out.finish()
+    }
+
+    /// Arrival sequence the next order to rest or be held will get, for
+    /// `cancel_where(older_than_seq=...)`.
+    #[getter]
+    fn sequence(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Amend a standing order's price and/or quantity.
+    ///
+    /// Reducing the quantity at an unchanged price keeps time priority; any
+    /// other change cancels and resubmits the order, which loses priority and
+    /// may trade immediately. Raises KeyError if the order is not resting.
+    #[pyo3(signature = (order, *, price = None, quantity = None))]
+    fn amend(
+        &mut self,
+        order: PyRef<Order>,
+        price: Option<&Bound<'_, pyo3::PyAny>>,
+        quantity: Option<i64>,
+        py: Python<'_>,
+    ) -> PyResult<TradeBlotter> {
+        let price = price.map(|p| extract_decimal(p, "price")).transpose()?;
+        match self.amend_order(order.id, price, quantity)? {
+            Some(blotter) => {
+                self.publish()?;
+                Ok(blotter)
+            }
+            None => {
+                let py_uuid = uuid_to_py(py, order.id)?;
+                Err(pyo3::exceptions::PyKeyError::new_err(py_uuid))
+            }
+        }
+    }
+
+    /// Reduce a resting order's quantity by `quantity_delta` in place,
+    /// keeping its time priority (an exchange partial cancel, ITCH "X").
+    ///
+    /// `order_id` is the Order or its id. Reducing by the whole remaining
+    /// quantity cancels the order. Returns the quantity left. Raises
+    /// KeyError if the order is not resting and ValueError if the delta is
+    /// not positive or exceeds the remaining quantity.
+    fn reduce(
+        &mut self,
+        order_id: &Bound<'_, pyo3::PyAny>,
+        quantity_delta: i64,
+        py: Python<'_>,
+    ) -> PyResult<i64> {
+        let order_id = extract_order_id(order_id)?;
+        match self.reduce_order(order_id, quantity_delta)? {
+            Some(remaining) => {
+                self.publish()?;
+                Ok(remaining)
+            }
+            None => {
+                let py_uuid = uuid_to_py(py, order_id)?;
+                Err(pyo3::exceptions::PyKeyError::new_err(py_uuid))
+            }
+        }
+    }
+
+    /// Start (or, with `enabled=False`, stop and discard) recording the
+    /// lifecycle of every order accepted from now on; see `order_history`.
+    #[pyo3(signature = (enabled = true))]
+    fn record_order_history(&mut self, enabled: bool) {
+        match (enabled, self.order_history.is_some()) {
+            (true, false) => self.order_history = Some(OrderHistories::default()),
+            (false, _) => self.order_history = None,
+            (true, true) => {}
+        }
+    }
+
+    /// Recorded lifecycle of an order, oldest first: accepted,
+    /// partially_filled / filled (with fill quantity and price), amended,
+    /// cancelled and expired events, each stamped with the simulated clock.
+    ///
+    /// `order_id` is the Order or its id. Returns None for orders accepted
+    /// while recording was off. Raises ValueError if recording is not
+    /// enabled (see `record_order_history`).
+    fn order_history(
+        &self,
+        order_id: &Bound<'_, pyo3::PyAny>,
+    ) -> PyResult<Option<Vec<OrderEvent>>> {
+        let history = self.order_history.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(
+                "Order history is not being recorded; call record_order_history() first",
+            )
+        })?;
+        Ok(history
+            .events(extract_order_id(order_id)?)
+            .map(<[_]>::to_vec))
+    }
+
+    /// Start (or, with `enabled=False`, stop and discard) journaling the
+    /// book's inputs, so `at_sequence` and `at_time` can rebuild past
+    /// states. The journal starts from a copy of the book as it is now.
+    ///
+    /// With `checkpoint_every` and/or `checkpoint_interval`, the journal
+    /// also copies the book after that many inputs, or once that much
+    /// simulated time has passed, since the last copy; rebuilding then
+    /// replays only from the nearest copy. Enabling an active journal
+    /// again just changes the cadence.
+    #[pyo3(signature = (enabled = true, *, checkpoint_every = None, checkpoint_interval = None))]
+    fn record_journal(
+        &mut self,
+        enabled: bool,
+        checkpoint_every: Option<usize>,
+        checkpoint_interval: Option<i64>,
+    ) -> PyResult<()> {
+        if checkpoint_every == Some(0) || checkpoint_interval.is_some_and(|i| i <= 0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "checkpoint_every and checkpoint_interval must be positive",
+            ));
+        }
+        let cadence = CheckpointCadence {
+            every: checkpoint_every,
+            interval: checkpoint_interval,
+        };
+        match (enabled, self.journal.as_mut()) {
+            (true, None) => self.journal = Some(Journal::new(self.fork(), cadence)),
+            (true, Some(journal)) => journal.cadence = cadence,
+            (false, _) => self.journal = None,
+        }
+        Ok(())
+    }
+
+    /// Number of inputs journaled so far, or None if journaling is off.
+    #[getter]
+    fn journal_length(&self) -> Option<usize> {
+        self.journal.as_ref().map(Journal::len)
+    }
+
+    /// Journal sequences the book was copied at, oldest first: 0 for the
+    /// start of the journal, then one per checkpoint. None if journaling
+    /// is off.
+    #[getter]
+    fn journal_checkpoints(&self) -> Option<Vec<usize>> {
+        self.journal.as_ref().map(Journal::checkpoint_sequences)
+    }
+
+    /// A new Book holding this book's state after the first `sequence`
+    /// journaled inputs, rebuilt by replaying them from where the journal
+    /// started.
+    ///
+    /// The journal records order submissions (`match`, `quote` and the
+    /// replay methods), cancels, amends and reductions, clock advances and
+    /// trading-phase changes (`set_trading_state`, `halt`, `resume`,
+    /// `uncross`). Feed updates, snapshots and configuration changes made
+    /// after journaling started are not replayed. The rebuilt book has no
+    /// publisher, drop copies, order callbacks or journal of its own.
+    /// Raises ValueError if journaling is off or `sequence` is beyond the
+    /// journal.
+    fn at_sequence(&self, sequence: usize) -> PyResult<Book> {
+        let journal = self.journal()?;
+        if sequence > journal.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "sequence {} is beyond the journal's {} entries",
+                sequence,
+                journal.len()
+            )));
+        }
+        journal.replay(sequence)
+    }
+
+    /// A new Book holding this book's state as of simulated time
+    /// `timestamp`: after every journaled input made while the clock read
+    /// `timestamp` or earlier (see `at_sequence`). Replay starts from the
+    /// latest checkpoint before then.
+    fn at_time(&self, timestamp: i64) -> PyResult<Book> {
+        let journal = self.journal()?;
+        journal.replay(journal.entries_until(timestamp))
+    }
+
+    /// Execute order fill between incoming and standing orders.
+    /// Updates both orders' quantities and returns a Trade.
+    fn fill(
+        &self,
+        incoming_order: &mut Order,
+        standing_order: &mut Order,
+        py: Python<'_>,
+    ) -> PyResult<Py<Trade>> {
+        let matched_quantity = incoming_order.quantity.min(standing_order.quantity);
+        standing_order.quantity -= matched_quantity;
+        incoming_order.quantity -= matched_quantity;
+        let fill_price = incoming_order
+            .side
+            .calc_fill_price(incoming_order.price, standing_order.price);
+        let trade = Trade::from_rust(
+            incoming_order.id,
+            standing_order.id,
+            matched_quantity,
+            fill_price,
+        )
+        .with_symbol(&standing_order.symbol)
+        .with_standing(standing_order.price, standing_order.quantity);
+        Py::new(py, trade)
+    }
+
+    /// Add order to book directly (enqueue without matching).
+    ///
+    /// Auction-designated orders are held for their uncross and stop orders
+    /// until triggered.
+    fn enqueue_order(&mut self, order: PyRef<Order>) -> PyResult<()> {
+        self.accept_order(&order)?;
+        self.rest_order(&order);
+        self.publish()
+    }
+
+    /// Add many orders to the book without matching, returning how many
+    /// were enqueued.
+    ///
+    /// `orders` is an iterable of Orders, or a mapping of equal-length
+    /// `side`, `symbol`, `price` and `quantity` columns (plus optional
+    /// `expire_time`), such as `table.to_pydict()`. Malformed input is
+    /// rejected before anything is enqueued. Each order then goes through the
+    /// same checks as `enqueue_order`; if one is rejected, the orders before
+    /// it stay enqueued and the error is raised.
+    fn enqueue_orders(&mut self, orders: &Bound<'_, pyo3::PyAny>) -> PyResult<usize> {
+        let orders = match orders.downcast::<PyDict>() {
+            Ok(columns) => read_column_rows(columns)?
+                .iter()
+                .map(|row| self.row_order(row))
+                .collect::<PyResult<Vec<_>>>()?,
+            Err(_) => orders
+                .try_iter()?
+                .map(|order| Ok(order?.extract::<PyRef<Order>>()?.clone()))
+                .collect::<PyResult<Vec<_>>>()?,
+        };
+        let mut plain = Vec::with_capacity(orders.len());
+        let mut outcome = Ok(());
+        for order in &orders {
+            if let Err(err) = self.accept_order(order) {
+                outcome = Err(err);
+                break;
+            }
+            if order.is_triggered() || order.auction.is_some() {
+                self.rest_order(order);
+            } else {
+                plain.push(order);
+            }
+        }
+        self.enqueue_batch(&plain);
+        self.publish()?;
+        outcome.map(|()| orders.len())
+    }
+
+    /// Trading phase of a symbol (CONTINUOUS unless set otherwise).
+    fn trading_state(&self, symbol: &str) -> TradingState {
+        self.state_of(symbol)
+    }
+
+    /// Move a symbol to a new trading phase.
+    ///
+    /// While PRE_OPEN, limit orders rest without matching until
+    /// `uncross(symbol, "open")`. CLOSED rejects all new orders.
+    fn set_trading_state(&mut self, symbol: &str, state: TradingState) -> PyResult<()> {
+        self.journaled(
+            || JournalEntry::SetTradingState(symbol.to_string(), state),
+            |book| {
+                book.change_state(symbol, state);
+                Ok(())
+            },
+        )?;
+        self.publish()
+    }
+
+    /// Halt trading in `symbol` and return the orders cancelled by it.
+    ///
+    /// `open_orders` decides what happens to its open orders: "keep" (the
+    /// default) leaves them, "cancel_all" cancels resting, stop and auction
+    /// orders, and "cancel_market_and_stop" cancels untriggered stops and
+    /// MOO/MOC orders. While HALTED, new orders pass the usual checks and
+    /// are queued unmatched for `resume`; they can be cancelled meanwhile.
+    #[pyo3(signature = (symbol, *, open_orders = "keep"))]
+    fn halt(&mut self, symbol: &str, open_orders: &str) -> PyResult<Vec<Order>> {
+        let policy = HaltPolicy::parse(open_orders)?;
+        let cancelled = self.journaled(
+            || JournalEntry::Halt(symbol.to_string(), policy),
+            |book| book.halt_symbol(symbol, policy),
+        )?;
+        self.publish()?;
+        Ok(cancelled)
+    }
+
+    /// Lift a halt on `symbol`, releasing the orders queued meanwhile in
+    /// arrival order.
+    ///
+    /// By default the symbol returns to CONTINUOUS, the queued orders are
+    /// matched and their TradeBlotters returned. With `reopen_auction=True`
+    /// it goes through PRE_OPEN instead: the queued orders rest, the
+    /// opening uncross runs (see `uncross`, with `reference_price`) and its
+    /// AuctionResult is returned.
+    #[pyo3(signature = (symbol, *, reopen_auction = false, reference_price = None))]
+    fn resume(
+        &mut self,
+        symbol: &str,
+        reopen_auction: bool,
+        reference_price: Option<&Bound<'_, pyo3::PyAny>>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let reference = reference_price
+            .map(|p| crate::order::extract_decimal(p, "reference_price"))
+            .transpose()?;
+        let resumed = self.journaled(
+            || JournalEntry::Resume {
+                symbol: symbol.to_string(),
+                reopen_auction,
+                reference,
+            },
+            |book| book.resume_symbol(symbol, reopen_auction, reference),
+        );
+        let result = match resumed {
+            Ok((_, Some(auction))) => Py::new(py, auction).map(Py::into_any),
+            Ok((blotters, None)) => PyList::new(py, blotters).map(|list| list.into()),
+            Err(err) => Err(err),
+        };
+        self.publish()?;
+        result
+    }
+    /// Reject auction-designated orders for `auction` ("open" or "close")
+    /// once the simulated clock reaches `timestamp`.
+    fn set_auction_cutoff(
+        &mut self,
+        symbol: String,
+        auction: &str,
+        timestamp: i64,
+    ) -> PyResult<()> {
+        let kind = AuctionKind::parse(auction)?;
+        let id = self.symbol_table.intern(&symbol);
+        self.auction_cutoffs.insert((id, kind), timestamp);
+        Ok(())
+    }
+
+    /// Move `symbol` through its trading day on the simulated clock.
+    ///
+    /// At `pre_open` it enters PRE_OPEN. At `open` the opening auction
+    /// runs, if pre-opened, and continuous trading starts. At `close` the
+    /// closing auction runs and the symbol closes, or trades on until
+    /// `post` when a post-market session is given. With `day_length` the
+    /// times are offsets into a day of that many clock units and repeat
+    /// daily; otherwise they are clock times and fire once.
+    ///
+    /// Transitions fire as `advance_time`, or a replay's timestamps, pass
+    /// them, in time order across symbols and with the clock at each, so
+    /// orders expiring earlier miss the auction. A halted symbol stays
+    /// halted. Replaces any earlier schedule for the symbol.
+    #[pyo3(signature = (symbol, open, close, *, pre_open = None, post = None, day_length = None))]
+    fn set_session_schedule(
+        &mut self,
+        symbol: String,
+        open: i64,
+        close: i64,
+        pre_open: Option<i64>,
+        post: Option<i64>,
+        day_length: Option<i64>,
+    ) -> PyResult<()> {
+        let schedule = SessionSchedule::new(pre_open, open, close, post, day_length)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let id = self.symbol_table.intern(&symbol);
+        self.schedules.insert(id, schedule);
+        Ok(())
+    }
+
+    /// Stop moving `symbol` through a session schedule, leaving it in its
+    /// current phase; false if it had none.
+    fn remove_session_schedule(&mut self, symbol: &str) -> bool {
+        self.symbol_table
+            .get(symbol)
+            .is_some_and(|id| self.schedules.remove(&id).is_some())
+    }
+
+    /// Run the opening or closing auction for a symbol.
+    ///
+    /// All resting limit orders and the held orders designated for this
+    /// auction cross at the single price that maximizes executed volume.
+    /// `reference_price` breaks ties and prices auctions containing only
+    /// market orders. Designated orders left unexecuted are cancelled.
+    ///
+    /// The opening auction requires PRE_OPEN and moves the symbol to
+    /// CONTINUOUS; the closing auction requires CONTINUOUS and moves it to
+    /// CLOSED.
+    #[pyo3(signature = (symbol, auction, reference_price = None))]
+    fn uncross(
+        &mut self,
+        symbol: String,
+        auction: &str,
+        reference_price: Option<&Bound<'_, pyo3::PyAny>>,
+    ) -> PyResult<AuctionResult> {
+        let kind = AuctionKind::parse(auction)?;
+        let reference = reference_price
+            .map(|p| crate::order::extract_decimal(p, "reference_price"))
+            .transpose()?;
+        let result = self.journaled(
+            || JournalEntry::Uncross(symbol.clone(), kind, reference),
+            |book| book.run_auction(&symbol, kind, reference),
+        )?;
+        self.publish()?;
+        Ok(result)
+    }
+
+    /// Indicative uncross price, matched volume and imbalance of the
+    /// auction pending for `symbol`, as disseminated before the uncross.
+    ///
+    /// `auction` defaults to "open" during PRE_OPEN and "close" during
+    /// CONTINUOUS; other phases have no auction pending. Figures cover the
+    /// same orders `uncross` would cross and are recomputed from the
+    /// current book on every call, so they follow each arriving order.
+    #[pyo3(signature = (symbol, auction = None, reference_price = None))]
+    fn auction_indicatives(
+        &self,
+        symbol: &str,
+        auction: Option<&str>,
+        reference_price: Option<&Bound<'_, pyo3::PyAny>>,
+    ) -> PyResult<AuctionIndicatives> {
+        let state = self.state_of(symbol);
+        let kind = match (auction, state) {
+            (Some(auction), _) => AuctionKind::parse(auction)?,
+            (None, TradingState::PRE_OPEN) => AuctionKind::Open,
+            (None, TradingState::CONTINUOUS) => AuctionKind::Close,
+            (None, state) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "No auction pending for {} while {}",
+                    symbol,
+                    state.__str__()
+                )))
+            }
+        };
+        let reference = reference_price
+            .map(|p| crate::order::extract_decimal(p, "reference_price"))
+            .transpose()?;
+        let designated: Vec<OrderEntry> = self
+            .per_symbol(&self.auction_orders, symbol)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.auction.is_some_and(|a| a.kind() == kind))
+            .cloned()
+            .collect();
+        let participants = self.auction_participants(symbol, &designated);
+        let uncross = compute_uncross(&participants, reference);
+        Ok(AuctionIndicatives::new(kind, uncross.as_ref()))
+    }
+
+    /// Restate `symbol`'s open orders for a stock split of `ratio` new
+    /// shares per old share (below 1 for a reverse split), returning the
+    /// orders cancelled because nothing of them was left.
+    ///
+    /// Limit and stop prices are divided by `ratio` and rounded to the
+    /// instrument's tick (else the price precision), bids down and asks up,
+    /// so the book stays uncrossed. Quantities are multiplied by `ratio` and
+    /// rounded down to whole lots. Resting orders keep their time priority;
+    /// where levels merge, orders from the better one queue first. Held
+    /// auction, stop and halt-queued orders, L2-maintained levels and short
+    /// locates are restated too. Orders rounded down to no quantity, or to
+    /// a non-positive price the instrument does not allow, are cancelled.
+    /// Raises ValueError, changing nothing, if a restated price or quantity
+    /// would overflow. Publishes a `SplitApplied` event.
+    fn apply_split(&mut self, symbol: &str, ratio: &Bound<'_, PyAny>) -> PyResult<Vec<Order>> {
+        let ratio = crate::order::extract_decimal(ratio, "ratio")?;
+        let cancelled = self.journaled(
+            || JournalEntry::Split(symbol.to_string(), ratio),
+            |book| book.split_symbol(symbol, ratio),
+        )?;
+        self.publish()?;
+        Ok(cancelled)
+    }
+
+    /// Rename symbol `old` to `new` everywhere in the book, as after a
+    /// ticker change.
+    ///
+    /// Open orders, instrument data, trading state, feed sequences,
+    /// session and settlement totals and enabled analytics all carry over
+    /// to `new`, and orders submitted for `new` join the renamed book.
+    /// Raises ValueError if `new` has orders or a registered instrument or
+    /// spread; anything else recorded under `new` is replaced. Publishes a
+    /// `SymbolChanged` event.
+    fn apply_symbol_change(&mut self, old: &str, new: &str) -> PyResult<()> {
+        self.journaled(
+            || JournalEntry::SymbolChange(old.to_string(), new.to_string()),
+            |book| book.change_symbol(old, new),
+        )?;
+        self.publish()
+    }
+
+    /// Register (or replace) reference data for an instrument.
+    fn register_instrument(&mut self, instrument: Instrument) {
+        let id = self.symbol_table.intern(&instrument.symbol);
+        self.instruments.insert(id, instrument);
+    }
+
+    /// Register `symbol` as a spread of `front` minus `back`: buying one
+    /// unit buys the front and sells the back.
+    ///
+    /// Orders in any of the three books first match outright liquidity in
+    /// their own book, then liquidity implied by the other two, best
+    /// implied price first. An implied fill trades both other books at
+    /// their best prices and prints in the order's symbol at the implied
+    /// price, naming the first leg's standing order.
+    fn register_spread(&mut self, symbol: String, front: String, back: String) -> PyResult<()> {
+        if symbol == front || symbol == back || front == back {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Spread {} needs two distinct legs other than itself, got {} and {}",
+                symbol, front, back
+            )));
+        }
+        if self.spreads.iter().any(|spread| spread.symbol == symbol) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Spread {} is already registered",
+                symbol
+            )));
+        }
+        self.spreads.push(Spread {
+            symbol,
+            front,
+            back,
+        });
+        Ok(())
+    }
+
+    /// Best implied `(bid, ask)` for `symbol` across the spreads it belongs
+    /// to, each a `(price, quantity)` pair or None.
+    fn implied_bbo(&self, symbol: &str, py: Python<'_>) -> PyResult<(PyObject, PyObject)> {
+        let quote = |side: Side| -> PyResult<PyObject> {
+            match self.best_implied(symbol, side) {
+                Some(level) => Ok((decimal_to_py(py, level.price)?, level.quantity)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind()),
+                None => Ok(py.None()),
+            }
+        };
+        // The implied bid is what an incoming ask could sell into.
+        Ok((quote(Side::ASK)?, quote(Side::BID)?))
+    }
+
+    /// Return the registered Instrument for a symbol, or None.
+    fn instrument(&self, symbol: &str) -> Option<Instrument> {
+        self.per_symbol(&self.instruments, symbol).cloned()
+    }
+
+    /// Trade statistics for `symbol` in the current session (last price,
+    /// open/high/low, volume, notional, trade count), or None if it has not
+    /// traded since the last `roll_session`.
+    ///
+    /// A glob such as `"ES*"` returns dict[symbol, SessionStats] for every
+    /// matching symbol that traded.
+    fn session_stats(&self, symbol: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let pattern = SymbolPattern::new(symbol);
+        if !pattern.is_glob() {
+            let stats = self.per_symbol(&self.sessions, symbol).cloned();
+            return Ok(stats.into_pyobject(py)?.into_any().unbind());
+        }
+        let stats: HashMap<&str, SessionStats> = self
+            .sessions
+            .iter()
+            .map(|(id, stats)| (self.symbol_table.name(*id), stats))
+            .filter(|(symbol, _)| pattern.matches(symbol))
+            .map(|(symbol, stats)| (symbol, stats.clone()))
+            .collect();
+        Ok(stats.into_pyobject(py)?.into_any().unbind())
+    }
+
+    /// Start a new session, returning the closing statistics of every
+    /// symbol that traded in the one just ended.
+    fn roll_session(&mut self) -> HashMap<String, SessionStats> {
+        self.settlements.roll();
+        std::mem::take(&mut self.sessions)
+            .into_iter()
+            .map(|(id, stats)| (self.symbol_table.name(id).to_string(), stats))
+            .collect()
+    }
+
+    /// Net every trade since the last `roll_session` per account and
+    /// symbol into SettlementLines, ordered by account then symbol.
+    ///
+    /// Lists `account` only, or every account when omitted, with trades
+    /// of orders booked to no account under None. `maker_fee` and
+    /// `taker_fee` are rates of notional charged on fills where the
+    /// account's order rested or aggressed; auction fills count as maker
+    /// and negative rates are rebates.
+    ///
+    /// Each line is in its instrument's currency, or converted into
+    /// `currency` with the rates given to `set_fx_rate`.
+    #[pyo3(signature = (account = None, *, maker_fee = None, taker_fee = None, currency = None))]
+    fn settlement_report(
+        &self,
+        account: Option<&str>,
+        maker_fee: Option<&Bound<'_, pyo3::PyAny>>,
+        taker_fee: Option<&Bound<'_, pyo3::PyAny>>,
+        currency: Option<&str>,
+    ) -> PyResult<Vec<SettlementLine>> {
+        let rate = |fee: Option<&Bound<'_, pyo3::PyAny>>, name| {
+            fee.map_or(Ok(Decimal::ZERO), |fee| extract_decimal(fee, name))
+        };
+        let maker_fee = rate(maker_fee, "maker_fee")?;
+        let taker_fee = rate(taker_fee, "taker_fee")?;
+        let mut lines = self.settlements.report(account, maker_fee, taker_fee);
+        for line in &mut lines {
+            line.currency = self.currency_of(&line.symbol).map(str::to_string);
+            let Some(target) = currency else {
+                continue;
+            };
+            let from = self.known_currency(&line.symbol)?;
+            for amount in [
+                &mut line.buy_notional,
+                &mut line.sell_notional,
+                &mut line.fees,
+                &mut line.cash_flows,
+            ] {
+                *amount = self.fx.convert(*amount, from, target)?;
+            }
+            line.currency = Some(target.to_string());
+        }
+        Ok(lines)
+    }
+
+    /// Pay a dividend or funding on `symbol` at clock `timestamp` to every
+    /// account with a net position in it then.
+    ///
+    /// A "dividend" pays `amount` per unit held, times the multiplier, to
+    /// longs and charges it to shorts. For "funding", `amount` is a rate of
+    /// the position's notional at the midpoint, else the last trade, that
+    /// longs pay shorts when positive. Payments are booked when
+    /// `advance_time` or a replay's timestamps reach `timestamp`, to net
+    /// positions including those carried across `roll_session`. They show
+    /// as `cash_flows` on settlement lines and count in `cash_delta` and
+    /// agents' PnL.
+    /// Raises ValueError if the clock is already at `timestamp` or past it.
+    #[pyo3(signature = (symbol, timestamp, amount, *, kind = "dividend"))]
+    fn add_cash_flow(
+        &mut self,
+        symbol: String,
+        timestamp: i64,
+        amount: &Bound<'_, PyAny>,
+        kind: &str,
+    ) -> PyResult<()> {
+        let kind = CashFlowKind::parse(kind)?;
+        let amount = extract_decimal(amount, "amount")?;
+        if let Some(now) = self.current_time.filter(|now| timestamp <= *now) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cash flow at {} is not after the clock at {}",
+                timestamp, now
+            )));
+        }
+        let flow = CashFlow {
+            symbol,
+            kind,
+            amount,
+        };
+        self.cash_flows.add(timestamp, flow);
+        Ok(())
+    }
+
+    /// Cash flows added with `add_cash_flow` that the clock has not reached.
+    #[getter]
+    fn pending_cash_flows(&self) -> usize {
+        self.cash_flows.len()
+    }
+
+    /// Report amounts in `currency`: agents' PnL is converted into it, and
+    /// it is what `set_fx_rate` quotes against. Changing it drops the
+    /// rates set so far.
+    fn set_base_currency(&mut self, currency: String) {
+        self.fx.set_base(currency);
+    }
+
+    /// The base currency, or None if not set.
+    #[getter]
+    fn base_currency(&self) -> Option<&str> {
+        self.fx.base()
+    }
+
+    /// Set one unit of `currency` to be worth `rate` units of the base
+    /// currency. Raises ValueError without a base currency or for a rate
+    /// that is not positive.
+    fn set_fx_rate(&mut self, currency: String, rate: &Bound<'_, PyAny>) -> PyResult<()> {
+        let rate = extract_decimal(rate, "rate")?;
+        self.fx.set_rate(currency, rate)
+    }
+
+    /// `amount` of `currency` converted into `to`, by default the base
+    /// currency. Raises ValueError if a rate is missing.
+    #[pyo3(signature = (amount, currency, to = None))]
+    fn convert(
+        &self,
+        py: Python<'_>,
+        amount: &Bound<'_, PyAny>,
+        currency: &str,
+        to: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let amount = extract_decimal(amount, "amount")?;
+        let to = match to.or(self.fx.base()) {
+            Some(to) => to,
+            None => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "No base currency is set; pass to= or call set_base_currency() first",
+                ))
+            }
+        };
+        decimal_to_py(py, self.fx.convert(amount, currency, to)?)
+    }
+
+    /// Set the decimal places used for prices and quantities in `symbol`,
+    /// or book-wide when `symbol` is None (per-symbol settings win).
+    ///
+    /// Orders priced finer than `price_precision` are rejected, and blotter
+    /// statistics and VWAPs are rounded to it instead of the default two
+    /// places. Quantities remain integers counting units of
+    /// `10**-quantity_precision`, so notional values (blotter `total_cost`,
+    /// snapshot and session notionals) are scaled by that unit. Resting
+    /// orders are not revalidated.
+    #[pyo3(signature = (price_precision, quantity_precision = 0, *, symbol = None))]
+    fn set_precision(
+        &mut self,
+        price_precision: u32,
+        quantity_precision: u32,
+        symbol: Option<String>,
+    ) -> PyResult<()> {
+        let precision = Precision::try_new(price_precision, quantity_precision)?;
+        match symbol {
+            Some(symbol) => {
+                let id = self.symbol_table.intern(&symbol);
+                self.symbol_precision.insert(id, precision);
+            }
+            None => self.precision = Some(precision),
+        }
+        Ok(())
+    }
+
+    /// `(price_precision, quantity_precision)` in effect for `symbol` (or
+    /// book-wide when None), or None if no precision was configured.
+    #[pyo3(signature = (symbol = None))]
+    fn precision(&self, symbol: Option<&str>) -> Option<(u32, u32)> {
+        let precision = match symbol {
+            Some(symbol) => self.precision_of(symbol),
+            None => self.precision,
+        };
+        precision.map(|p| (p.price, p.quantity))
+    }
+
+    /// Take quantities passed to `Book.bid`/`Book.ask` and in replayed or
+    /// ingested rows as asset amounts (e.g. 0.001 BTC) instead of integer
+    /// units.
+    ///
+    /// Amounts are converted to whole units of the symbol's
+    /// `quantity_precision` (see `set_precision`) and rejected if finer.
+    /// Orders, trades and levels keep integer units; `to_quantity` and
+    /// `SnapshotLevel.size` convert back.
+    #[pyo3(signature = (enabled = true))]
+    fn set_fractional_quantities(&mut self, enabled: bool) {
+        self.fractional_quantities = enabled;
+    }
+
+    /// Asset amount of `units` engine quantity units in `symbol`.
+    fn to_quantity(&self, symbol: &str, units: i64, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, Decimal::from(units) * self.quantity_unit(symbol))
+    }
+
+    /// Current simulated time, or None if the clock was never advanced.
+    #[getter]
+    pub(crate) fn current_time(&self) -> Option<i64> {
+        self.current_time
+    }
+
+    /// Advance the simulated clock to `timestamp`, firing every time-driven
+    /// event due up to and including it.
+    ///
+    /// This runs session transitions due under `set_session_schedule`,
+    /// ages synthetic levels under `set_liquidity_decay`, then expires
+    /// good-till-date orders whose `expire_time` is at or before
+    /// `timestamp`. Returns the expired orders with their remaining
+    /// quantity. Time cannot move backwards.
+    fn advance_time(&mut self, timestamp: i64) -> PyResult<Vec<Order>> {
+        let expired = self.advance_clock(timestamp)?;
+        self.publish()?;
+        Ok(expired)
+    }
+
+    /// Age synthetic levels (those set by snapshots, `from_levels` and L2
+    /// updates) as the simulated clock advances, so a static snapshot
+    /// thins out and refills like a live book over a long backtest.
+    ///
+    /// With `half_life`, a level's quantity halves every `half_life` clock
+    /// units since it was last set; with `refresh_after`, it returns to
+    /// that quantity every `refresh_after` units, unless the opposite side
+    /// has since moved through its price. Between refreshes trading only
+    /// takes quantity away. Calling this re-anchors existing levels at the
+    /// current time; with neither argument, levels stay as they are.
+    #[pyo3(signature = (*, half_life = None, refresh_after = None))]
+    fn set_liquidity_decay(
+        &mut self,
+        half_life: Option<i64>,
+        refresh_after: Option<i64>,
+    ) -> PyResult<()> {
+        for (name, period) in [("half_life", half_life), ("refresh_after", refresh_after)] {
+            if period.is_some_and(|period| period <= 0) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "{} must be positive",
+                    name
+                )));
+            }
+        }
+        self.liquidity_decay =
+            (half_life.is_some() || refresh_after.is_some()).then_some(LiquidityDecay {
+                half_life,
+                refresh_after,
+            });
+        let live: Vec<_> = self
+            .l2_levels
+            .iter()
+            .filter_map(|(key, id)| Some((*key, self.find_resting(*id)?.quantity)))
+            .collect();
+        self.level_anchors.clear();
+        for (key, quantity) in live {
+            self.anchor_level(key, quantity);
+        }
+        Ok(())
+    }
+
+    /// Set the shares available to borrow for short sales in `symbol`.
+    ///
+    /// The first call enables locate enforcement: from then on every short
+    /// ASK must be covered by its symbol's remaining locate, which is
+    /// consumed on acceptance and restored for any quantity cancelled.
+    fn set_locate(&mut self, symbol: String, quantity: i64) -> PyResult<()> {
+        if quantity < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Locate quantity cannot be negative",
+            ));
+        }
+        let id = self.symbol_table.intern(&symbol);
+        self.locates
+            .get_or_insert_with(FxHashMap::default)
+            .insert(id, quantity);
+        Ok(())
+    }
+
+    /// Remaining locate for a symbol, or None when enforcement is disabled.
+    fn available_locate(&self, symbol: &str) -> Option<i64> {
+        self.locates
+            .as_ref()
+            .map(|locates| self.per_symbol(locates, symbol).copied().unwrap_or(0))
+    }
+
+    /// Disable locate enforcement and drop the locate table.
+    fn clear_locates(&mut self) {
+        self.locates = None;
+    }
+
+    /// Registered instruments as dict[symbol, Instrument].
+    #[getter]
+    fn instruments(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        for inst in self.instruments.values() {
+            dict.set_item(&inst.symbol, Py::new(py, inst.clone())?)?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Return a live handle to an order by its id, or None.
+    ///
+    /// The handle reflects fills and amends as they happen; call
+    /// `.snapshot()` on it for a frozen copy.
+    fn get_order(
+        slf: &Bound<'_, Self>,
+        order_id: &Bound<'_, pyo3::PyAny>,
+    ) -> PyResult<Option<Py<OrderHandle>>> {
+        let id_str: String = order_id.str()?.extract()?;
+        let uid = Uuid::parse_str(&id_str).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid order_id: {}", e))
+        })?;
+        let order = slf.borrow().find_order(uid);
+        order
+            .map(|order| OrderHandle::new(slf.py(), slf.clone().unbind(), order))
+            .transpose()
+    }
+
+    /// Return a PriceLevel snapshot for a given symbol/side/price, or None.
+    ///
+    /// Note: returns a snapshot (copy) of the current state. Modifications to
+    /// the returned PriceLevel do not affect the book. This matches the Rust
+    /// backend's value semantics. The Python backend returns a live reference.
+    fn get_level(
+        &self,
+        symbol: &str,
+        side: Side,
+        price: &Bound<'_, pyo3::PyAny>,
+        py: Python<'_>,
+    ) -> PyResult<Option<Py<PriceLevel>>> {
+        let level = self.level_at(symbol, side, extract_decimal(price, "price")?);
+        level
+            .map(|level| Py::new(py, level_copy(side, level, &self.symbol_table)))
+            .transpose()
+    }
+
+    /// Return `(order_id, quantity)` pairs resting at a level, in priority
+    /// order, without copying the orders. `limit` keeps only the first ones.
+    #[pyo3(signature = (symbol, side, price, limit = None))]
+    fn level_orders(
+        &self,
+        symbol: &str,
+        side: Side,
+        price: &Bound<'_, pyo3::PyAny>,
+        limit: Option<usize>,
+        py: Python<'_>,
+    ) -> PyResult<Vec<(PyObject, i64)>> {
+        let Some(level) = self.level_at(symbol, side, extract_decimal(price, "price")?) else {
+            return Ok(Vec::new());
+        };
+        let entries = || level.orders.iter().take(limit.unwrap_or(usize::MAX));
+        let ids = uuids_to_py(py, entries().map(|entry| entry.id))?;
+        Ok(ids
+            .into_iter()
+            .zip(entries())
+            .map(|(id, entry)| (id.unbind(), entry.quantity))
+            .collect())
+    }
+
+    /// Expose order_map as dict[UUID, OrderHandle] of resting orders for
+    /// API parity.
+    #[getter]
+    fn order_map(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        let py = slf.py();
+        let dict = PyDict::new(py);
+        let book = slf.borrow();
+        let ids = uuids_to_py(py, book.order_map.keys().copied())?;
+        for (uid, py_uid) in book.order_map.keys().zip(ids) {
+            if let Some(order) = book.find_resting(*uid) {
+                let handle = OrderHandle::new(py, slf.clone().unbind(), order)?;
+                dict.set_item(py_uid, handle)?;
+            }
+        }
+        Ok(dict.into())
+    }
+
+    /// Expose levels as defaultdict-like structure for API parity.
+    /// Returns dict[symbol, dict[Side, list[PriceLevel]]], each list ordered
+    /// best to worst (highest bid, lowest ask first).
+    #[getter]
+    fn levels(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let outer = PyDict::new(py);
+        for (symbol, sym_book) in self.symbol_books() {
+            let inner = PyDict::new(py);
+            for (side, one_side) in [(Side::BID, &sym_book.bids), (Side::ASK, &sym_book.asks)] {
+                let list = PyList::empty(py);
+                for lvl in one_side.levels.iter().rev() {
+                    list.append(Py::new(py, level_copy(side, lvl, &self.symbol_table))?)?;
+                }
+                inner.set_item(side, list)?;
+            }
+            outer.set_item(symbol, inner)?;
+        }
+        Ok(outer.into())
+    }
+
+    /// Expose level_map as dict[symbol, dict[Side, dict[Price, PriceLevel]]] for API parity.
+    /// Prices iterate best to worst, as in `levels`.
+    #[getter]
+    fn level_map(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let outer = PyDict::new(py);
+        for (symbol, sym_book) in self.symbol_books() {
+            let inner = PyDict::new(py);
+            for (side, one_side) in [(Side::BID, &sym_book.bids), (Side::ASK, &sym_book.asks)] {
+                let map = PyDict::new(py);
+                let prices = decimals_to_py(py, one_side.levels.iter().rev().map(|l| l.price))?;
+                for (lvl, price) in one_side.levels.iter().rev().zip(prices) {
+                    map.set_item(
+                        price,
+                        Py::new(py, level_copy(side, lvl, &self.symbol_table))?,
+                    )?;
+                }
+                inner.set_item(side, map)?;
+            }
+            outer.set_item(symbol, inner)?;
+        }
+        Ok(outer.into())
+    }
+
+    /// Bid prices of `symbol`, highest first.
+    fn bid_prices(&self, symbol: &str, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.side_prices(symbol, Side::BID, py)
+    }
+
+    /// Ask prices of `symbol`, lowest first.
+    fn ask_prices(&self, symbol: &str, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.side_prices(symbol, Side::ASK, py)
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+
+    /// Whether the invariant checker runs after every input.
+    #[getter]
+    fn debug_checks(&self) -> bool {
+        self.debug_checks
+    }
+
+    /// Verify the book's internal invariants, raising AssertionError that
+    /// lists every violation found.
+    ///
+    /// Checks level sort order, that no symbol in continuous trading is
+    /// crossed, `order_map` and level consistency, positive resting
+    /// quantities, and that each resting order's filled quantity equals the
+    /// trades printed against it. Intended for property and fuzz tests.
+    fn check_invariants(&self) -> PyResult<()> {
+        let violations = self.invariant_violations();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(pyo3::exceptions::PyAssertionError::new_err(format!(
+            "{} invariant violation(s):\n{}",
+            violations.len(),
+            violations.join("\n")
+        )))
+    }
+
+    /// Deterministic hash of the book state, as a `bits`-bit integer (64 or
+    /// 128).
+    ///
+    /// Covers every resting order in queue order plus held stop and auction
+    /// orders (side, price, open and original quantity, flags), non-default
+    /// trading states and the simulated clock. Order ids are excluded since
+    /// they are random per run, so replaying the same events reproduces the
+    /// same hash on any platform and release.
+    #[pyo3(signature = (bits = 64))]
+    fn state_hash(&self, bits: u32) -> PyResult<u128> {
+        let mut hasher = StateHasher::default();
+        self.hash_state(&mut hasher);
+        match bits {
+            64 => Ok(u128::from(hasher.finish64())),
+            128 => Ok(hasher.finish128()),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "bits must be 64 or 128, got {}",
+                bits
+            ))),
+        }
+    }
+
+    /// Return a snapshot whose levels are aggregated into fixed-width price
+    /// bands, each labelled by its lower edge (e.g. every 0.10), best first.
+    ///
+    /// `depth` limits the number of bands per side; by default every band
+    /// is returned. Spread and midpoint come from the unbanded best prices,
+    /// and VWAPs and notionals from the levels the bands cover.
+    #[pyo3(signature = (symbol, band_width, depth = None))]
+    fn banded_snapshot(
+        &self,
+        symbol: &str,
+        band_width: &Bound<'_, pyo3::PyAny>,
+        depth: Option<usize>,
+    ) -> PyResult<Option<Snapshot>> {
+        let width = extract_decimal(band_width, "band_width")?;
+        if width <= Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "band_width must be greater than zero",
+            ));
+        }
+        let Some(full) = self.snapshot(symbol, isize::MAX) else {
+            return Ok(None);
+        };
+        let depth = depth.unwrap_or(usize::MAX);
+        let (bids, bid_count) = band_levels(&full.bids, width, depth);
+        let (asks, ask_count) = band_levels(&full.asks, width, depth);
+        let (bid_levels, ask_levels) = (&full.bids[..bid_count], &full.asks[..ask_count]);
+        let precision = self.precision_of(symbol);
+        let round = |vwap: Option<Decimal>| match precision {
+            Some(p) => vwap.map(|v| p.round_price(v)),
+            None => vwap,
+        };
+        let multiplier = self.multiplier(symbol);
+        Ok(Some(Snapshot {
+            bid_vwap: round(compute_vwap(bid_levels)),
+            ask_vwap: round(compute_vwap(ask_levels)),
+            bid_notional: compute_notional(bid_levels, multiplier),
+            ask_notional: compute_notional(ask_levels, multiplier),
+            bids,
+            asks,
+            ..full
+        }))
+    }
+
+    /// Snapshots of every symbol matching `symbol` (a glob such as `"ES*"`;
+    /// all symbols by default) as dict[symbol, Snapshot].
+    #[pyo3(signature = (symbol = "*", depth = 5))]
+    fn snapshot_all(&self, symbol: &str, depth: isize) -> HashMap<String, Snapshot> {
+        let pattern = SymbolPattern::new(symbol);
+        self.symbol_books()
+            .filter(|(symbol, _)| pattern.matches(symbol))
+            .filter_map(|(symbol, _)| Some((symbol.to_string(), self.snapshot(symbol, depth)?)))
+            .collect()
+    }
+
+    /// The top `depth` levels of `symbol` as a `TensorSnapshot`: a float64
+    /// array of shape `(2, 2, depth)` (bid/ask, price/size, level) that
+    /// `numpy.asarray` wraps without copying. Missing levels, and every
+    /// level of a symbol never seen, hold `fill`.
+    #[pyo3(signature = (symbol, depth = 10, *, fill = 0.0))]
+    fn tensor_snapshot(&self, symbol: &str, depth: usize, fill: f64) -> TensorSnapshot {
+        let snapshot = self.snapshot(symbol, depth as isize);
+        TensorSnapshot::new(snapshot.as_ref(), depth, fill)
+    }
+
+    /// Maintain online features of `symbol`, read with `feature_vector`.
+    ///
+    /// `features` lists, in the order of the vector: "spread", "mid",
+    /// "microprice" (best prices weighted by the opposite size),
+    /// "imbalance_<n>" ((bid - ask) / (bid + ask) size over the top `n`
+    /// levels) and "ofi_<window>" (order flow imbalance at the touch over
+    /// the trailing window). Windows are clock units, or a duration such
+    /// as "1s" or "500ms" when the clock counts `clock_unit` ("ns", "us",
+    /// "ms" or "s"). Order flow is sampled after every book call. An empty
+    /// list stops tracking the symbol.
+    #[pyo3(signature = (symbol, features, *, clock_unit = "ns"))]
+    fn enable_features(
+        &mut self,
+        symbol: String,
+        features: Vec<String>,
+        clock_unit: &str,
+    ) -> PyResult<()> {
+        if features.is_empty() {
+            if let Some(id) = self.symbol_table.get(&symbol) {
+                self.features.remove(&id);
+            }
+            return Ok(());
+        }
+        let mut set = FeatureSet::new(features, clock_unit)?;
+        set.observe(
+            self.current_time.unwrap_or(0),
+            self.best_level(&symbol, Side::BID),
+            self.best_level(&symbol, Side::ASK),
+        );
+        let id = self.symbol_table.intern(&symbol);
+        self.features.insert(id, set);
+        Ok(())
+    }
+
+    /// Current features of `symbol` as a float64 `TensorSnapshot` of shape
+    /// `(n,)`, in `enable_features` order; `numpy.asarray` wraps it without
+    /// copying. Undefined values (the spread of a one-sided book) are NaN.
+    /// Raises KeyError if the symbol has no features enabled.
+    fn feature_vector(&self, symbol: &str) -> PyResult<TensorSnapshot> {
+        let features = self.enabled_features(symbol)?;
+        let snapshot = self.snapshot(symbol, features.depth() as isize);
+        let now = self.current_time.unwrap_or(0);
+        Ok(TensorSnapshot::vector(
+            features.values(now, snapshot.as_ref()),
+        ))
+    }
+
+    /// Names of the features `feature_vector(symbol)` returns, in order.
+    fn feature_names(&self, symbol: &str) -> PyResult<Vec<String>> {
+        Ok(self.enabled_features(symbol)?.names.clone())
+    }
+
+    /// Record the order flow of `symbol` event by event, read with
+    /// `order_flow`, or stop and discard it with `enabled=False`.
+    ///
+    /// Every order, cancel and feed update that moves the symbol's quote
+    /// or trades adds a row: the clock, the order flow imbalance at the
+    /// touch (Cont, Kukanov and Stoikov), the buy and sell volume of its
+    /// trades as classified by Lee-Ready (the quote test against the
+    /// midpoint before the event, then the tick test), their difference,
+    /// and the midpoint and best bid and ask sizes after it. Trades neither
+    /// test decides are left out.
+    #[pyo3(signature = (symbol, enabled = true))]
+    fn enable_order_flow(&mut self, symbol: String, enabled: bool) {
+        if !enabled {
+            if let Some(id) = self.symbol_table.get(&symbol) {
+                self.flows.remove(&id);
+            }
+            return;
+        }
+        let bid = self.best_level(&symbol, Side::BID);
+        let ask = self.best_level(&symbol, Side::ASK);
+        let id = self.symbol_table.intern(&symbol);
+        self.flows
+            .entry(id)
+            .or_insert_with(|| OrderFlow::new(bid, ask));
+    }
+
+    /// The order flow rows of `symbol` as `{column: TensorSnapshot}`, one
+    /// float64 vector per column ("time", "ofi", "buy_volume",
+    /// "sell_volume", "signed_volume", "mid", "bid_size", "ask_size");
+    /// `numpy.asarray` wraps each
+    /// without copying. With `clear=True` the rows are dropped once read.
+    /// Raises KeyError if the symbol's flow is not recorded.
+    #[pyo3(signature = (symbol, *, clear = false))]
+    fn order_flow(&mut self, py: Python<'_>, symbol: &str, clear: bool) -> PyResult<PyObject> {
+        let id = self.symbol_table.get(symbol);
+        let flow = id.and_then(|id| self.flows.get_mut(&id)).ok_or_else(|| {
+            pyo3::exceptions::PyKeyError::new_err(format!(
+                "Order flow of {} is not recorded; call enable_order_flow() first",
+                symbol
+            ))
+        })?;
+        let columns = PyDict::new(py);
+        for (name, values) in crate::flow::COLUMNS.iter().zip(flow.columns()) {
+            columns.set_item(name, TensorSnapshot::vector(values.clone()))?;
+        }
+        if clear {
+            flow.clear();
+        }
+        Ok(columns.into())
+    }
+
+    /// Price impact and resilience of `symbol`, estimated from its order
+    /// flow rows (see `enable_order_flow`), as a dict:
+    ///
+    /// - "kyle_lambda": the least-squares slope of the midpoint change on
+    ///   signed volume over consecutive intervals of `window` clock units,
+    ///   with its "r_squared" and the number of "intervals"
+    /// - "resilience": the mean clock time touch depth took to get back to
+    ///   its level before each large trade, over the "large_trades" that
+    ///   were "recovered"
+    ///
+    /// An event is a large trade when it traded at least `large_trade`,
+    /// by default the 90th percentile of events that traded; the threshold
+    /// used is reported as "large_trade". Estimates are None without the
+    /// data to make them. Raises KeyError if the symbol's flow is not
+    /// recorded.
+    #[pyo3(signature = (symbol, window, *, large_trade = None))]
+    fn liquidity_estimates(
+        &self,
+        py: Python<'_>,
+        symbol: &str,
+        window: i64,
+        large_trade: Option<f64>,
+    ) -> PyResult<PyObject> {
+        if window <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "window must be positive",
+            ));
+        }
+        let flow = self.per_symbol(&self.flows, symbol).ok_or_else(|| {
+            pyo3::exceptions::PyKeyError::new_err(format!(
+                "Order flow of {} is not recorded; call enable_order_flow() first",
+                symbol
+            ))
+        })?;
+        let estimates = crate::liquidity::estimate(flow.columns(), window, large_trade);
+        let dict = PyDict::new(py);
+        dict.set_item("kyle_lambda", estimates.kyle_lambda)?;
+        dict.set_item("r_squared", estimates.r_squared)?;
+        dict.set_item("intervals", estimates.intervals)?;
+        dict.set_item("large_trade", estimates.large_trade)?;
+        dict.set_item("large_trades", estimates.large_trades)?;
+        dict.set_item("recovered", estimates.recovered)?;
+        dict.set_item("resilience", estimates.resilience)?;
+        Ok(dict.into())
+    }
+
+    /// Measure the execution quality of `symbol`'s fills, read with
+    /// `execution_quality`, or stop and discard it with `enabled=False`.
+    /// Fills are signed by Lee-Ready, as in `enable_order_flow`, and
+    /// measured against the quote before the event that made them.
+    #[pyo3(signature = (symbol, enabled = true))]
+    fn enable_execution_quality(&mut self, symbol: String, enabled: bool) {
+        if !enabled {
+            if let Some(id) = self.symbol_table.get(&symbol) {
+                self.qualities.remove(&id);
+            }
+            return;
+        }
+        let now = self.current_time.unwrap_or(0);
+        let bid = self.best_level(&symbol, Side::BID).map(|(price, _)| price);
+        let ask = self.best_level(&symbol, Side::ASK).map(|(price, _)| price);
+        let id = self.symbol_table.intern(&symbol);
+        self.qualities
+            .entry(id)
+            .or_insert_with(|| ExecutionQuality::new(now, bid, ask));
+    }
+
+    /// Volume-weighted execution quality of `symbol`'s fills since
+    /// `enable_execution_quality`, as a dict:
+    ///
+    /// - "effective_spread": 2 × |fill − midpoint|
+    /// - "realized_spread": 2 × (fill − midpoint `horizon` clock units
+    ///   later), signed so a buyer paying above the later mid is positive
+    /// - "price_impact": the effective spread less the realized spread
+    /// - "price_improvement": how far fills beat the quoted best price
+    /// - "trades", "volume": the classified fills measured
+    /// - "unclassified_volume": fills against a one-sided book, or that
+    ///   Lee-Ready cannot sign
+    /// - "pending": fills less than `horizon` old, left out of the
+    ///   realized spread and price impact
+    ///
+    /// Averages are Decimals, or None without fills to average.
+    /// Raises KeyError if the symbol is not measured.
+    #[pyo3(signature = (symbol, horizon = 0))]
+    fn execution_quality(&self, py: Python<'_>, symbol: &str, horizon: i64) -> PyResult<PyObject> {
+        if horizon < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "horizon cannot be negative",
+            ));
+        }
+        let quality = self.per_symbol(&self.qualities, symbol).ok_or_else(|| {
+            pyo3::exceptions::PyKeyError::new_err(format!(
+                "Execution quality of {} is not measured; call enable_execution_quality() first",
+                symbol
+            ))
+        })?;
+        let report = quality.report(horizon, self.current_time.unwrap_or(0));
+        let average = |value| crate::snapshot::optional_decimal_to_py(py, value);
+        let dict = PyDict::new(py);
+        dict.set_item("trades", report.trades)?;
+        dict.set_item("volume", report.volume)?;
+        dict.set_item("unclassified_volume", report.unclassified_volume)?;
+        dict.set_item("pending", report.pending)?;
+        dict.set_item("effective_spread", average(report.effective_spread)?)?;
+        dict.set_item("realized_spread", average(report.realized_spread)?)?;
+        dict.set_item("price_impact", average(report.price_impact)?)?;
+        dict.set_item("price_improvement", average(report.price_improvement)?)?;
+        Ok(dict.into())
+    }
+
+    /// Build the volume profile of `symbol` from here on, read with
+    /// `volume_profile`, or stop and discard it with `enabled=False`.
+    #[pyo3(signature = (symbol, enabled = true))]
+    fn enable_volume_profile(&mut self, symbol: String, enabled: bool) {
+        if !enabled {
+            if let Some(id) = self.symbol_table.get(&symbol) {
+                self.profiles.remove(&id);
+            }
+            return;
+        }
+        let now = self.current_time.unwrap_or(0);
+        let mid = self.midpoint(&symbol);
+        let id = self.symbol_table.intern(&symbol);
+        self.profiles
+            .entry(id)
+            .or_insert_with(|| MarketProfile::new(now, mid));
+    }
+
+    /// The volume profile of `symbol` in price buckets of `bucket`, lowest
+    /// first, as `[{"price", "volume", "time"}]`: each bucket's lowest
+    /// price, the volume traded in it, and the clock time the midpoint
+    /// spent in it (time-at-price, as in a market profile). Buckets with
+    /// neither are left out. Raises KeyError if the symbol's profile is
+    /// not built.
+    fn volume_profile<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: &str,
+        bucket: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let bucket = extract_decimal(bucket, "bucket")?;
+        if bucket <= Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "bucket must be positive",
+            ));
+        }
+        let profile = self.per_symbol(&self.profiles, symbol).ok_or_else(|| {
+            pyo3::exceptions::PyKeyError::new_err(format!(
+                "Volume profile of {} is not built; call enable_volume_profile() first",
+                symbol
+            ))
+        })?;
+        let rows = PyList::empty(py);
+        for level in profile.levels(bucket, self.current_time.unwrap_or(0)) {
+            let row = PyDict::new(py);
+            row.set_item("price", decimal_to_py(py, level.price)?)?;
+            row.set_item("volume", level.volume)?;
+            row.set_item("time", level.time)?;
+            rows.append(row)?;
+        }
+        Ok(rows)
+    }
+
+    /// Return an L2 depth snapshot for a symbol, or None if never seen.
+    #[pyo3(signature = (symbol, depth = 5))]
+    pub(crate) fn snapshot(&self, symbol: &str, depth: isize) -> Option<Snapshot> {
+        let sym_book = self.symbol_book(symbol)?;
+        let depth = depth.max(0) as usize;
+
+        // Bids: sorted ascending, best (highest) at back → iterate reversed
+        let bid_levels: Vec<SnapshotLevel> = sym_book
+            .bids
+            .levels
+            .iter()
+            .rev()
+            .filter(|lvl| lvl.displayed_quantity() > 0)
+            .take(depth)
+            .map(|lvl| SnapshotLevel::from_rust(lvl.price, lvl.displayed_quantity()))
+            .collect();
+
+        // Asks: sorted descending, best (lowest) at back → iterate reversed
+        let ask_levels: Vec<SnapshotLevel> = sym_book
+            .asks
+            .levels
+            .iter()
+            .rev()
+            .filter(|lvl| lvl.displayed_quantity() > 0)
+            .take(depth)
+            .map(|lvl| SnapshotLevel::from_rust(lvl.price, lvl.displayed_quantity()))
+            .collect();
+
+        let unit = self.quantity_unit(symbol);
+        let (bid_levels, ask_levels): (Vec<_>, Vec<_>) = (
+            bid_levels.into_iter().map(|l| l.with_unit(unit)).collect(),
+            ask_levels.into_iter().map(|l| l.with_unit(unit)).collect(),
+        );
+
+        let best_bid = bid_levels.first().map(|l| l.price);
+        let best_ask = ask_levels.first().map(|l| l.price);
+
+        let spread = match (best_bid, best_ask) {
+            (Some(b), Some(a)) => Some(a - b),
+            _ => None,
+        };
+
+        let midpoint = match (best_bid, best_ask) {
+            (Some(b), Some(a)) => Some((a + b) / Decimal::from(2)),
+            _ => None,
+        };
+
+        let precision = self.precision_of(symbol);
+        let round = |vwap: Option<Decimal>| match precision {
+            Some(p) => vwap.map(|v| p.round_price(v)),
+            None => vwap,
+        };
+        let bid_vwap = round(compute_vwap(&bid_levels));
+        let ask_vwap = round(compute_vwap(&ask_levels));
+
+        let multiplier = self.multiplier(symbol);
+        let bid_notional = compute_notional(&bid_levels, multiplier);
+        let ask_notional = compute_notional(&ask_levels, multiplier);
+
+        Some(Snapshot {
+            bids: bid_levels,
+            asks: ask_levels,
+            spread,
+            midpoint,
+            bid_vwap,
+            ask_vwap,
+            bid_notional,
+            ask_notional,
+        })
+    }
+}
+
+fn compute_vwap(levels: &[SnapshotLevel]) -> Option<Decimal> {
+    let mut sum_pq = Decimal::ZERO;
+    let mut sum_q: i64 = 0;
+    for lvl in levels {
+        sum_pq += lvl.price * Decimal::from(lvl.quantity);
+        sum_q += lvl.quantity;
+    }
+    if sum_q == 0 {
+        None
+    } else {
+        Some(sum_pq / Decimal::from(sum_q))
+    }
+}
+
+/// Aggregate best-first levels into at most `depth` bands of `width`, each
+/// priced at its lower edge. Also returns how many levels the bands cover.
+fn band_levels(
+    levels: &[SnapshotLevel],
+    width: Decimal,
+    depth: usize,
+) -> (Vec<SnapshotLevel>, usize) {
+    let mut bands: Vec<SnapshotLevel> = Vec::new();
+    let mut covered = 0;
+    for lvl in levels {
+        let edge = (lvl.price / width).floor() * width;
+        if let Some(band) = bands.last_mut().filter(|band| band.price == edge) {
+            band.quantity += lvl.quantity;
+        } else if bands.len() == depth {
+            break;
+        } else {
+            bands.push(SnapshotLevel::from_rust(edge, lvl.quantity).with_unit(lvl.unit));
+        }
+        covered += 1;
+    }
+    (bands, covered)
+}
+
+fn compute_notional(levels: &[SnapshotLevel], multiplier: Decimal) -> Decimal {
+    levels
+        .iter()
+        .map(|lvl| lvl.price * Decimal::from(lvl.quantity))
+        .sum::<Decimal>()
+        * multiplier
+}
+
+impl Book {
+    pub(crate) fn new() -> Self {
+        Book {
+            symbol_table: SymbolTable::default(),
+            symbols: FxHashMap::default(),
+            order_map: IdMap::default(),
+            instruments: FxHashMap::default(),
+            fx: FxTable::default(),
+            locates: None,
+            current_time: None,
+            expiries: BTreeSet::new(),
+            next_seq: 0,
+            trading_states: FxHashMap::default(),
+            auction_cutoffs: FxHashMap::default(),
+            schedules: FxHashMap::default(),
+            auction_orders: FxHashMap::default(),
+            halted_orders: FxHashMap::default(),
+            stops: FxHashMap::default(),
+            l2_levels: FxHashMap::default(),
+            level_anchors: FxHashMap::default(),
+            liquidity_decay: None,
+            feed_orders: HashMap::new(),
+            feed_sequences: FxHashMap::default(),
+            publisher: None,
+            fill_ledger: HashMap::new(),
+            order_history: None,
+            sessions: FxHashMap::default(),
+            accounts: HashMap::new(),
+            settlements: SettlementLedger::default(),
+            cash_flows: CashFlowSchedule::default(),
+            fill_price_policy: FillPricePolicy::Passive,
+            dedupe: DedupeCache::default(),
+            latency: None,
+            profiler: None,
+            icebergs: None,
+            journal: None,
+            features: FxHashMap::default(),
+            flows: FxHashMap::default(),
+            qualities: FxHashMap::default(),
+            profiles: FxHashMap::default(),
+            shared: None,
+            drop_copies: Vec::new(),
+            drop_copy_orders: HashMap::new(),
+            drop_copy_events: VecDeque::new(),
+            quotes: FxHashMap::default(),
+            quote_protection: HashMap::new(),
+            tripped_quotes: Vec::new(),
+            order_callbacks: HashMap::new(),
+            order_events: VecDeque::new(),
+            precision: None,
+            symbol_precision: FxHashMap::default(),
+            spreads: Vec::new(),
+            fractional_quantities: false,
+            self_trade_prevention: None,
+            internalization_groups: HashMap::new(),
+            disconnects: DisconnectSchedule::default(),
+            throttles: Throttles::default(),
+            counters: FxHashMap::default(),
+            debug_checks: false,
+            duplicate_policy: DuplicatePolicy::Reject,
+            duplicate_ids: IdMap::default(),
+            capacity: None,
+            validators: Validators::default(),
+            enrichers: Enrichers::default(),
+        }
+    }
+
+    /// Contract multiplier for a symbol; 1 when no instrument is registered.
+    /// Notional scale of one quantity unit: the instrument multiplier times
+    /// the unit implied by the quantity precision.
+    fn multiplier(&self, symbol: &str) -> Decimal {
+        let multiplier = self
+            .per_symbol(&self.instruments, symbol)
+            .map_or(Decimal::ONE, |inst| inst.multiplier);
+        match self.precision_of(symbol) {
+            Some(precision) => multiplier * precision.quantity_unit(),
+            None => multiplier,
+        }
+    }
+
+    /// Asset amount of one engine quantity unit.
+    fn quantity_unit(&self, symbol: &str) -> Decimal {
+        self.precision_of(symbol)
+            .map_or(Decimal::ONE, |p| p.quantity_unit())
+    }
+
+    /// Engine units for an externally supplied quantity: an asset amount in
+    /// fractional mode, otherwise already a whole unit count.
+    fn quantity_units(&self, symbol: &str, quantity: Decimal) -> PyResult<i64> {
+        let unit = if self.fractional_quantities {
+            self.quantity_unit(symbol)
+        } else {
+            Decimal::ONE
+        };
+        quantity
+            .checked_div(unit)
+            .filter(|units| units.fract().is_zero())
+            .and_then(|units| units.to_i64())
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid quantity {} for {}: expected a whole multiple of {}",
+                    quantity, symbol, unit
+                ))
+            })
+    }
+
+    fn row_units(&self, row: &ParquetOrderRow) -> PyResult<i64> {
+        self.quantity_units(&row.symbol, row.quantity).map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid quantity at row {}: '{}'",
+                row.row_idx, row.quantity
+            ))
+        })
+    }
+
+    fn row_order(&self, row: &ParquetOrderRow) -> PyResult<Order> {
+        let quantity = self.row_units(row)?;
+        Ok(
+            Order::try_new(row.side, row.symbol.clone(), row.price, quantity)?
+                .with_expire_time(row.expire_time),
+        )
+    }
+
+    /// The book of `symbol`, if it has ever had a resting order.
+    fn symbol_book(&self, symbol: &str) -> Option<&SymbolBook> {
+        self.symbols.get(&self.symbol_table.get(symbol)?)
+    }
+
+    fn symbol_book_mut(&mut self, symbol: &str) -> Option<&mut SymbolBook> {
+        self.symbols.get_mut(&self.symbol_table.get(symbol)?)
+    }
+
+    /// `map`'s value for `symbol`, if the symbol has been seen.
+    fn per_symbol<'a, V>(&self, map: &'a FxHashMap<SymbolId, V>, symbol: &str) -> Option<&'a V> {
+        map.get(&self.symbol_table.get(symbol)?)
+    }
+
+    /// Every symbol book with its symbol.
+    fn symbol_books(&self) -> impl Iterator<Item = (&str, &SymbolBook)> {
+        self.symbols
+            .iter()
+            .map(|(id, sym_book)| (self.symbol_table.name(*id), sym_book))
+    }
+
+    /// The entry `order` rests or waits as, its symbol interned.
+    fn entry_of(&mut self, order: &Order) -> OrderEntry {
+        OrderEntry::new(order, self.symbol_table.intern(&order.symbol))
+    }
+
+    fn order_of(&self, entry: &OrderEntry) -> Order {
+        entry.to_order(&self.symbol_table)
+    }
+
+    fn level_at(
+        &self,
+        symbol: &str,
+        side: Side,
+        price: Decimal,
+    ) -> Option<&pyorderbook_core::PriceLevel<OrderEntry>> {
+        let ascending = matches!(side, Side::BID);
+        let sym_book = self.symbol_book(symbol)?;
+        let one_side = if ascending {
+            &sym_book.bids
+        } else {
+            &sym_book.asks
+        };
+        let idx = one_side.find_level(price, ascending).ok()?;
+        Some(&one_side.levels[idx])
+    }
+
+    /// Prices on one side of `symbol`, best first.
+    fn side_prices(&self, symbol: &str, side: Side, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let Some(sym_book) = self.symbol_book(symbol) else {
+            return Ok(Vec::new());
+        };
+        let one_side = if matches!(side, Side::BID) {
+            &sym_book.bids
+        } else {
+            &sym_book.asks
+        };
+        let prices = decimals_to_py(py, one_side.levels.iter().rev().map(|lvl| lvl.price))?;
+        Ok(prices.into_iter().map(Bound::unbind).collect())
+    }
+
+    /// Current state of a resting or held order.
+    fn find_order(&self, order_id: Uuid) -> Option<Order> {
+        self.find_resting(order_id).or_else(|| {
+            self.auction_orders
+                .values()
+                .chain(self.stops.values())
+                .chain(self.halted_orders.values())
+                .flatten()
+                .find(|entry| entry.id == order_id)
+                .map(|entry| self.order_of(entry))
+        })
+    }
+
+    /// Whether the order is resting or held for an auction, trigger or
+    /// resume.
+    pub(crate) fn is_live(&self, order_id: Uuid) -> bool {
+        self.order_map.contains_key(&order_id)
+            || self
+                .auction_orders
+                .values()
+                .chain(self.stops.values())
+                .chain(self.halted_orders.values())
+                .flatten()
+                .any(|entry| entry.id == order_id)
+    }
+
+    fn find_resting(&self, order_id: Uuid) -> Option<Order> {
+        let (symbol, side, price) = self.order_map.get(&order_id)?;
+        let ascending = matches!(side, Side::BID);
+        let sym_book = self.symbols.get(symbol)?;
+        let one_side = if ascending {
+            &sym_book.bids
+        } else {
+            &sym_book.asks
+        };
+        let idx = one_side.find_level(*price, ascending).ok()?;
+        one_side.levels[idx]
+            .orders
+            .iter()
+            .find(|entry| entry.id == order_id)
+            .map(|entry| self.order_of(entry))
+    }
+
+    fn precision_of(&self, symbol: &str) -> Option<Precision> {
+        self.per_symbol(&self.symbol_precision, symbol)
+            .copied()
+            .or(self.precision)
+    }
+
+    /// A scratch book holding `symbol`'s resting and pending orders, the
+    /// resting orders of books linked to it by spreads, and the
+    /// configuration matching reads, for what-if matching.
+    fn overlay(&self, symbol: &str) -> Book {
+        let mut scratch = Book::new();
+        scratch.symbol_table = self.symbol_table.clone();
+        scratch.spreads = self.spreads.clone();
+        let linked: BTreeSet<&str> = std::iter::once(symbol)
+            .chain(
+                self.spreads
+                    .iter()
+                    .filter(|spread| spread.involves(symbol))
+                    .flat_map(|spread| [&spread.symbol, &spread.front, &spread.back])
+                    .map(String::as_str),
+            )
+            .collect();
+        for id in linked.iter().filter_map(|s| self.symbol_table.get(s)) {
+            if let Some(sym_book) = self.symbols.get(&id) {
+                scratch.symbols.insert(id, sym_book.clone());
+            }
+            if let Some(stats) = self.sessions.get(&id) {
+                scratch.sessions.insert(id, stats.clone());
+            }
+        }
+        scratch.order_map = self
+            .order_map
+            .iter()
+            .filter(|(_, (s, _, _))| linked.contains(self.symbol_table.name(*s)))
+            .map(|(id, located)| (*id, *located))
+            .collect();
+        for (pending, source) in [
+            (&mut scratch.stops, &self.stops),
+            (&mut scratch.auction_orders, &self.auction_orders),
+        ] {
+            if let Some((id, entries)) = self
+                .symbol_table
+                .get(symbol)
+                .and_then(|id| Some((id, source.get(&id)?)))
+            {
+                pending.insert(id, entries.clone());
+            }
+        }
+        scratch.instruments = self.instruments.clone();
+        scratch.locates = self.locates.clone();
+        scratch.current_time = self.current_time;
+        scratch.next_seq = self.next_seq;
+        scratch.trading_states = self.trading_states.clone();
+        scratch.auction_cutoffs = self.auction_cutoffs.clone();
+        scratch.precision = self.precision;
+        scratch.symbol_precision = self.symbol_precision.clone();
+        scratch.fractional_quantities = self.fractional_quantities;
+        scratch.fill_price_policy = self.fill_price_policy;
+        scratch
+    }
+
+    /// A copy of the book's orders, market state and configuration, without
+    /// its publisher, sinks, callbacks, dedupe keys or recorders.
+    pub(crate) fn fork(&self) -> Book {
+        Book {
+            symbol_table: self.symbol_table.clone(),
+            symbols: self.symbols.clone(),
+            order_map: self.order_map.clone(),
+            instruments: self.instruments.clone(),
+            fx: self.fx.clone(),
+            locates: self.locates.clone(),
+            current_time: self.current_time,
+            expiries: self.expiries.clone(),
+            next_seq: self.next_seq,
+            trading_states: self.trading_states.clone(),
+            auction_cutoffs: self.auction_cutoffs.clone(),
+            schedules: self.schedules.clone(),
+            auction_orders: self.auction_orders.clone(),
+            halted_orders: self.halted_orders.clone(),
+            stops: self.stops.clone(),
+            l2_levels: self.l2_levels.clone(),
+            level_anchors: self.level_anchors.clone(),
+            liquidity_decay: self.liquidity_decay,
+            feed_orders: self.feed_orders.clone(),
+            feed_sequences: self.feed_sequences.clone(),
+            fill_ledger: self.fill_ledger.clone(),
+            order_history: self.order_history.clone(),
+            sessions: self.sessions.clone(),
+            accounts: self.accounts.clone(),
+            settlements: self.settlements.clone(),
+            cash_flows: self.cash_flows.clone(),
+            fill_price_policy: self.fill_price_policy,
+            quotes: self.quotes.clone(),
+            quote_protection: self.quote_protection.clone(),
+            precision: self.precision,
+            symbol_precision: self.symbol_precision.clone(),
+            spreads: self.spreads.clone(),
+            fractional_quantities: self.fractional_quantities,
+            self_trade_prevention: self.self_trade_prevention,
+            internalization_groups: self.internalization_groups.clone(),
+            disconnects: self.disconnects.clone(),
+            throttles: self.throttles.clone(),
+            counters: self.counters.clone(),
+            debug_checks: self.debug_checks,
+            duplicate_policy: self.duplicate_policy,
+            duplicate_ids: self.duplicate_ids.clone(),
+            capacity: self.capacity.clone(),
+            validators: Python::with_gil(|py| self.validators.clone_ref(py)),
+            enrichers: Python::with_gil(|py| self.enrichers.clone_ref(py)),
+            ..Book::new()
+        }
+    }
+
+    fn enabled_features(&self, symbol: &str) -> PyResult<&FeatureSet> {
+        self.per_symbol(&self.features, symbol).ok_or_else(|| {
+            pyo3::exceptions::PyKeyError::new_err(format!(
+                "No features enabled for {}; call enable_features() first",
+                symbol
+            ))
+        })
+    }
+
+    fn journal(&self) -> PyResult<&Journal> {
+        self.journal.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(
+                "The journal is not enabled; call record_journal() first",
+            )
+        })
+    }
+
+    /// Run `op`, journaling `entry` if it succeeds and is not part of
+    /// another journaled call, then check the invariants if `debug_checks`
+    /// is on.
+    fn journaled<T>(
+        &mut self,
+        entry: impl FnOnce() -> JournalEntry,
+        op: impl FnOnce(&mut Self) -> PyResult<T>,
+    ) -> PyResult<T> {
+        if !self.debug_checks {
+            return self.record_input(entry, op);
+        }
+        let input = entry();
+        let result = self.record_input(|| input.clone(), op);
+        // A nested input that failed its check already named itself.
+        if let Err(err) = &result {
+            if Python::with_gil(|py| err.is_instance_of::<pyo3::exceptions::PyAssertionError>(py)) {
+                return result;
+            }
+        }
+        let violations = self.invariant_violations();
+        if !violations.is_empty() {
+            return Err(pyo3::exceptions::PyAssertionError::new_err(format!(
+                "{} invariant violation(s) after {:?}:\n{}",
+                violations.len(),
+                input,
+                violations.join("\n")
+            )));
+        }
+        result
+    }
+
+    /// `journaled` without the debug checks.
+    fn record_input<T>(
+        &mut self,
+        entry: impl FnOnce() -> JournalEntry,
+        op: impl FnOnce(&mut Self) -> PyResult<T>,
+    ) -> PyResult<T> {
+        let Some(journal) = self.journal.as_mut() else {
+            return op(self);
+        };
+        journal.depth += 1;
+        let result = op(self);
+        let mut due = false;
+        if let Some(journal) = self.journal.as_mut() {
+            journal.depth -= 1;
+            if result.is_ok() && journal.depth == 0 {
+                due = journal.record(self.current_time, entry());
+            }
+        }
+        if due {
+            let checkpoint = self.fork();
+            if let Some(journal) = self.journal.as_mut() {
+                journal.checkpoint(checkpoint);
+            }
+        }
+        result
+    }
+
+    /// Repeat a journaled input.
+    pub(crate) fn apply_journal_entry(&mut self, entry: &JournalEntry) -> PyResult<()> {
+        match entry {
+            JournalEntry::Match(order) => self.match_single(order).map(drop),
+            JournalEntry::Cancel(order_id) => self.cancel_order(*order_id).map(drop),
+            JournalEntry::Amend {
+                order_id,
+                price,
+                quantity,
+            } => self.amend_order(*order_id, *price, *quantity).map(drop),
+            JournalEntry::AdvanceTime(timestamp) => self.advance_clock(*timestamp).map(drop),
+            JournalEntry::SetTradingState(symbol, state) => {
+                self.change_state(symbol, *state);
+                Ok(())
+            }
+            JournalEntry::Halt(symbol, policy) => self.halt_symbol(symbol, *policy).map(drop),
+            JournalEntry::Resume {
+                symbol,
+                reopen_auction,
+                reference,
+            } => self
+                .resume_symbol(symbol, *reopen_auction, *reference)
+                .map(drop),
+            JournalEntry::Uncross(symbol, kind, reference) => {
+                self.run_auction(symbol, *kind, *reference).map(drop)
+            }
+            JournalEntry::Disconnect { session_id, delay } => {
+                self.disconnect_session(session_id, *delay).map(drop)
+            }
+            JournalEntry::Reconnect(session_id) => {
+                self.reconnect_session(session_id);
+                Ok(())
+            }
+            JournalEntry::Split(symbol, ratio) => self.split_symbol(symbol, *ratio).map(drop),
+            JournalEntry::SymbolChange(old, new) => self.change_symbol(old, new),
+        }
+    }
+
+    /// Blotter for `order`, with statistics in the symbol's precision.
+    pub(crate) fn blotter(&self, order: Order, trades: Vec<Trade>) -> TradeBlotter {
+        let multiplier = self.multiplier(&order.symbol);
+        let price_precision = self
+            .precision_of(&order.symbol)
+            .map_or(DEFAULT_PRICE_PRECISION, |p| p.price);
+        TradeBlotter::from_rust(order, trades, multiplier, price_precision)
+    }
+
+    /// How far the clock is into `symbol`'s trading day, scaled to seconds
+    /// since midnight. None unless the clock has been set and the symbol's
+    /// session schedule gives a `day_length`, the only place the clock's
+    /// unit is known.
+    fn second_of_day(&self, symbol: &str) -> Option<u32> {
+        let now = self.current_time?;
+        let day = self.per_symbol(&self.schedules, symbol)?.day_length?;
+        let offset = i128::from(now.rem_euclid(day));
+        u32::try_from(offset * i128::from(SECONDS_PER_DAY) / i128::from(day)).ok()
+    }
+
+    /// Validate an order against its registered instrument, if any.
+    ///
+    /// Zero and negative prices are only accepted for instruments that
+    /// opt in via `allow_non_positive_prices`. MOO/MOC orders are unpriced.
+    pub(crate) fn validate_order(&self, order: &Order) -> PyResult<()> {
+        let priced = !order.auction.is_some_and(|a| a.is_market());
+        if let Some(precision) = self.precision_of(&order.symbol) {
+            if priced {
+                precision.check_price(&order.symbol, order.price)?;
+            }
+            if let Some(stop_price) = order.stop_price {
+                precision.check_price(&order.symbol, stop_price)?;
+            }
+        }
+        match self.per_symbol(&self.instruments, &order.symbol) {
+            Some(inst) => {
+                if let Some(second) = self.second_of_day(&order.symbol) {
+                    if !inst.is_trading_time(second) {
+                        return Err(RejectCode::TradingState.err(format!(
+                            "{} is outside its trading hours at second {} of its day",
+                            order.symbol, second
+                        )));
+                    }
+                }
+                inst.validate(order)
+            }
+            None if priced && order.price <= Decimal::ZERO => {
+                Err(RejectCode::BadPrice.err(format!(
+                    "Price {} must be positive for {}; register an Instrument with \
+                     allow_non_positive_prices=True to permit zero or negative prices",
+                    order.price, order.symbol
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Remove a resting order from its level and from `order_map`,
+    /// restoring any short-sale locate it consumed.
+    ///
+    /// Returns `Ok(None)` if the order is not resting in the book.
+    fn remove_resting(&mut self, order_id: Uuid) -> PyResult<Option<OrderEntry>> {
+        let Some(&(symbol, side, price)) = self.order_map.get(&order_id) else {
+            return Ok(None);
+        };
+        self.touch_located((symbol, side, price));
+        let name = self.symbol_table.name(symbol);
+        let level_missing = || {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Price Level {}:{}:{} doesn't exist!",
+                name, side, price
+            ))
+        };
+
+        let sym_book = self.symbols.get_mut(&symbol).ok_or_else(level_missing)?;
+        let ascending = matches!(side, Side::BID);
+        let one_side = if ascending {
+            &mut sym_book.bids
+        } else {
+            &mut sym_book.asks
+        };
+        let entry = one_side
+            .remove_order(price, order_id, ascending)
+            .ok_or_else(level_missing)?;
+
+        self.restore_locate(&entry);
+        self.unindex(order_id);
+        self.accounts.remove(&order_id);
+        Ok(Some(entry))
+    }
+
+    /// Update `order_map` after an order with `id` left its level: drop
+    /// the id, or, if duplicates of it still rest, point it at the earliest
+    /// of them, whose fills the ledger then tracks.
+    fn unindex(&mut self, id: Uuid) {
+        let Some(duplicates) = self.duplicate_ids.remove(&id) else {
+            self.order_map.remove(&id);
+            self.fill_ledger.remove(&id);
+            return;
+        };
+        let indexed = self.order_map.remove(&id);
+        // How many entries with this id each recorded location still
+        // holds, to tell which of the locations are left.
+        let mut left: HashMap<&Location, usize> = HashMap::new();
+        let mut remaining: VecDeque<Location> = VecDeque::new();
+        for located in indexed.iter().chain(&duplicates) {
+            let count = left
+                .entry(located)
+                .or_insert_with(|| self.entries_at(id, located).count());
+            if *count > 0 {
+                *count -= 1;
+                remaining.push_back(*located);
+            }
+        }
+        let Some(first) = remaining.pop_front() else {
+            self.fill_ledger.remove(&id);
+            return;
+        };
+        let filled = self
+            .entries_at(id, &first)
+            .next()
+            .map_or(0, |entry| entry.original_quantity - entry.quantity);
+        self.fill_ledger.insert(id, filled);
+        self.order_map.insert(id, first);
+        if !remaining.is_empty() {
+            self.duplicate_ids.insert(id, remaining);
+        }
+    }
+
+    /// Resting entries with `id` at a `(symbol, side, price)` location.
+    fn entries_at<'a>(
+        &'a self,
+        id: Uuid,
+        (symbol, side, price): &'a Location,
+    ) -> impl Iterator<Item = &'a OrderEntry> + 'a {
+        self.symbols
+            .get(symbol)
+            .into_iter()
+            .flat_map(move |sym_book| &sym_book.side((*side).into()).levels)
+            .filter(move |level| level.price == *price)
+            .flat_map(|level| &level.orders)
+            .filter(move |entry| entry.id == id)
+    }
+
+    /// Credit executions to resting orders in the fill ledger; orders no
+    /// longer resting are dropped from it.
+    fn record_fills(&mut self, fills: impl IntoIterator<Item = (Uuid, i64)>) {
+        for (order_id, quantity) in fills {
+            if self.order_map.contains_key(&order_id) {
+                *self.fill_ledger.entry(order_id).or_insert(0) += quantity;
+            } else {
+                self.fill_ledger.remove(&order_id);
+            }
+        }
+    }
+
+    /// Feed the canonical encoding of the book state to `hasher`. Symbols
+    /// without orders in the default trading state are skipped, so how a
+    /// symbol was first seen doesn't matter.
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        fn write_entry(hasher: &mut StateHasher, entry: &OrderEntry) {
+            hasher.write_u8(match entry.side {
+                Side::BID => 0,
+                Side::ASK => 1,
+            });
+            hasher.write_decimal(entry.price);
+            hasher.write_i64(entry.quantity);
+            hasher.write_i64(entry.original_quantity);
+            hasher.write_bool(entry.short);
+            hasher.write_option(entry.expire_time, StateHasher::write_i64);
+            hasher.write_option(entry.auction, |h, designation| {
+                h.write_u8(match designation {
+                    AuctionDesignation::MOO => 0,
+                    AuctionDesignation::LOO => 1,
+                    AuctionDesignation::MOC => 2,
+                    AuctionDesignation::LOC => 3,
+                })
+            });
+            hasher.write_option(entry.stop_price, StateHasher::write_decimal);
+        }
+        fn write_entries<'a>(
+            hasher: &mut StateHasher,
+            entries: impl ExactSizeIterator<Item = &'a OrderEntry>,
+        ) {
+            hasher.write_len(entries.len());
+            for entry in entries {
+                write_entry(hasher, entry);
+            }
+        }
+
+        hasher.write_option(self.current_time, StateHasher::write_i64);
+        let no_orders: Vec<OrderEntry> = Vec::new();
+        let mut symbols: Vec<&str> = self
+            .symbol_books()
+            .map(|(symbol, _)| symbol)
+            .chain(
+                self.stops
+                    .keys()
+                    .chain(self.auction_orders.keys())
+                    .chain(self.trading_states.keys())
+                    .map(|id| self.symbol_table.name(*id)),
+            )
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        for symbol in symbols {
+            let state = self.state_of(symbol);
+            let sym_book = self.symbol_book(symbol);
+            let stops = self.per_symbol(&self.stops, symbol).unwrap_or(&no_orders);
+            let auction = self
+                .per_symbol(&self.auction_orders, symbol)
+                .unwrap_or(&no_orders);
+            let resting =
+                sym_book.is_some_and(|b| !b.bids.levels.is_empty() || !b.asks.levels.is_empty());
+            if !resting
+                && stops.is_empty()
+                && auction.is_empty()
+                && state == TradingState::CONTINUOUS
+            {
+                continue;
+            }
+            hasher.write_str(symbol);
+            hasher.write_u8(match state {
+                TradingState::PRE_OPEN => 0,
+                TradingState::CONTINUOUS => 1,
+                TradingState::CLOSED => 2,
+                TradingState::HALTED => 3,
+            });
+            let sides: [&[pyorderbook_core::PriceLevel<OrderEntry>]; 2] = match sym_book {
+                Some(b) => [&b.bids.levels, &b.asks.levels],
+                None => [&[], &[]],
+            };
+            for levels in sides {
+                // Best level first, then FIFO within the level.
+                hasher.write_len(levels.len());
+                for level in levels.iter().rev() {
+                    hasher.write_decimal(level.price);
+                    write_entries(hasher, level.orders.iter());
+                }
+            }
+            write_entries(hasher, stops.iter());
+            write_entries(hasher, auction.iter());
+            if state == TradingState::HALTED {
+                let queued = self
+                    .per_symbol(&self.halted_orders, symbol)
+                    .unwrap_or(&no_orders);
+                write_entries(hasher, queued.iter());
+            }
+        }
+    }
+
+    /// Every broken invariant, described; empty when the book is sound.
+    fn invariant_violations(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        // Feed-driven quantities change without trades in this book.
+        let feed_managed: std::collections::HashSet<Uuid> = self
+            .l2_levels
+            .values()
+            .chain(self.feed_orders.values())
+            .copied()
+            .collect();
+        let mut resting = 0;
+
+        for (symbol, sym_book) in self.symbol_books() {
+            for (side, one_side) in [(Side::BID, &sym_book.bids), (Side::ASK, &sym_book.asks)] {
+                let ascending = matches!(side, Side::BID);
+                for pair in one_side.levels.windows(2) {
+                    let sorted = if ascending {
+                        pair[0].price < pair[1].price
+                    } else {
+                        pair[0].price > pair[1].price
+                    };
+                    if !sorted {
+                        violations.push(format!(
+                            "{} {} levels out of order: {} before {}",
+                            symbol, side, pair[0].price, pair[1].price
+                        ));
+                    }
+                }
+                for level in &one_side.levels {
+                    if level.orders.is_empty() {
+                        violations.push(format!(
+                            "{} {} level {} is empty",
+                            symbol, side, level.price
+                        ));
+                    }
+                    for entry in &level.orders {
+                        resting += 1;
+                        let located = (entry.symbol, entry.side, level.price);
+                        if entry.price != level.price || entry.side != side {
+                            violations.push(format!(
+                                "Order {} ({} @ {}) sits in {} level {}",
+                                entry.id, entry.side, entry.price, side, level.price
+                            ));
+                        }
+                        let duplicated = self.duplicate_ids.get(&entry.id);
+                        if self.order_map.get(&entry.id) != Some(&located)
+                            && !duplicated.is_some_and(|d| d.contains(&located))
+                        {
+                            violations.push(format!(
+                                "Order {} in {} {} level {} is missing from order_map",
+                                entry.id, symbol, side, level.price
+                            ));
+                        }
+                        if entry.quantity <= 0 || entry.quantity > entry.original_quantity {
+                            violations.push(format!(
+                                "Order {} has quantity {} of original {}",
+                                entry.id, entry.quantity, entry.original_quantity
+                            ));
+                        }
+                        let filled = self.fill_ledger.get(&entry.id).copied().unwrap_or(0);
+                        // Duplicates share one ledger entry.
+                        if !feed_managed.contains(&entry.id)
+                            && duplicated.is_none()
+                            && entry.original_quantity - entry.quantity != filled
+                        {
+                            violations.push(format!(
+                                "Order {} is filled {} of {} but trades account for {}",
+                                entry.id,
+                                entry.original_quantity - entry.quantity,
+                                entry.original_quantity,
+                                filled
+                            ));
+                        }
+                    }
+                }
+            }
+            let best_bid = sym_book.bids.levels.last().map(|l| l.price);
+            let best_ask = sym_book.asks.levels.last().map(|l| l.price);
+            if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
+                if bid >= ask && self.state_of(symbol) == TradingState::CONTINUOUS {
+                    violations.push(format!(
+                        "{} is crossed: best bid {} >= best ask {}",
+                        symbol, bid, ask
+                    ));
+                }
+            }
+        }
+
+        let indexed = self.resting_count();
+        if resting != indexed {
+            violations.push(format!(
+                "order_map has {} entries but {} orders are resting",
+                indexed, resting
+            ));
+        }
+        violations
+    }
+
+    /// Record a level change for the publisher, if one is attached.
+    fn touch_level(&mut self, symbol: &str, side: Side, price: Decimal) {
+        if let Some(publisher) = self.publisher.as_mut() {
+            publisher.ring.touch_level(symbol, side, price);
+        }
+    }
+
+    fn touch_located(&mut self, (symbol, side, price): Location) {
+        if let Some(publisher) = self.publisher.as_mut() {
+            let symbol = self.symbol_table.name(symbol);
+            publisher.ring.touch_level(symbol, side, price);
+        }
+    }
+
+    fn publish_trades(&mut self, symbol: &str, trades: &[Trade]) {
+        for trade in trades {
+            self.record_trade(symbol, trade.fill_price, trade.fill_quantity);
+        }
+        if !self.quote_protection.is_empty() {
+            self.record_quote_fills(symbol, trades);
+        }
+        if !self.drop_copy_orders.is_empty() {
+            for trade in trades {
+                for id in [trade.incoming_order_id, trade.standing_order_id] {
+                    if let Some(account) = self.drop_copy_orders.get(&id) {
+                        self.drop_copy_events
+                            .push_back((account.clone(), BusEvent::trade(symbol, trade)));
+                    }
+                }
+            }
+        }
+        if !self.order_callbacks.is_empty() {
+            for trade in trades {
+                for id in [trade.incoming_order_id, trade.standing_order_id] {
+                    if self.order_callbacks.contains_key(&id) {
+                        self.order_events
+                            .push_back((id, BusEvent::trade(symbol, trade)));
+                    }
+                }
+            }
+        }
+        if let Some(publisher) = self.publisher.as_mut() {
+            for trade in trades {
+                publisher.ring.push(BusEvent::trade(symbol, trade));
+            }
+        }
+    }
+
+    /// Count fills against protected accounts' quotes, noting accounts
+    /// whose protection trips.
+    fn record_quote_fills(&mut self, symbol: &str, trades: &[Trade]) {
+        let now = self.current_time.unwrap_or(0);
+        for trade in trades {
+            for id in [trade.incoming_order_id, trade.standing_order_id] {
+                let Some(account) = self
+                    .quotes
+                    .values()
+                    .find(|quote| quote.ids().any(|quoted| quoted == id))
+                    .and_then(|quote| quote.account.clone())
+                else {
+                    continue;
+                };
+                let tripped = self
+                    .quote_protection
+                    .get_mut(&account)
+                    .and_then(|protection| protection.record_fill(now));
+                if let Some(fills) = tripped {
+                    self.tripped_quotes
+                        .push((account, symbol.to_string(), fills));
+                }
+            }
+        }
+    }
+
+    /// Cancel every quote of the accounts whose fill protection tripped.
+    fn pull_tripped_quotes(&mut self) -> PyResult<()> {
+        for (account, symbol, fills) in std::mem::take(&mut self.tripped_quotes) {
+            let quote_ids: Vec<(SymbolId, String)> = self
+                .quotes
+                .iter()
+                .filter(|(_, quote)| quote.account.as_ref() == Some(&account))
+                .map(|(quote_id, _)| quote_id.clone())
+                .collect();
+            let mut cancelled = 0;
+            for quote_id in quote_ids {
+                if let Some(quote) = self.quotes.remove(&quote_id) {
+                    for id in quote.ids() {
+                        cancelled += usize::from(self.cancel_order(id)?);
+                    }
+                }
+            }
+            self.emit(|| BusEvent::QuotesPulled {
+                symbol,
+                account,
+                fills,
+                cancelled,
+            });
+        }
+        Ok(())
+    }
+
+    /// Deliver queued events to the sinks of their orders, then forget the
+    /// sinks of orders no longer live. An event stays queued if its sink
+    /// raises.
+    fn notify_orders(&mut self) -> PyResult<()> {
+        if self.order_events.is_empty() {
+            return Ok(());
+        }
+        Python::with_gil(|py| -> PyResult<()> {
+            while let Some((order_id, event)) = self.order_events.front() {
+                if let Some(sink) = self.order_callbacks.get(order_id) {
+                    sink.call1(py, (to_event(py, event)?,))?;
+                }
+                self.order_events.pop_front();
+            }
+            Ok(())
+        })?;
+        let done: Vec<Uuid> = self
+            .order_callbacks
+            .keys()
+            .filter(|id| !self.is_live(**id))
+            .copied()
+            .collect();
+        for id in done {
+            self.order_callbacks.remove(&id);
+        }
+        Ok(())
+    }
+
+    /// Deliver queued events to every drop copy covering their account,
+    /// then forget orders no longer live.
+    fn deliver_drop_copies(&mut self) -> PyResult<()> {
+        if self.drop_copy_events.is_empty() {
+            return Ok(());
+        }
+        Python::with_gil(|py| -> PyResult<()> {
+            while let Some((account, event)) = self.drop_copy_events.front() {
+                for copy in self.drop_copies.iter_mut().filter(|c| c.covers(account)) {
+                    copy.deliver(py, account, event)?;
+                }
+                self.drop_copy_events.pop_front();
+            }
+            Ok(())
+        })?;
+        for copy in &mut self.drop_copies {
+            copy.flush()?;
+        }
+        let done: Vec<Uuid> = self
+            .drop_copy_orders
+            .keys()
+            .filter(|id| !self.is_live(**id))
+            .copied()
+            .collect();
+        for id in done {
+            self.drop_copy_orders.remove(&id);
+        }
+        Ok(())
+    }
+
+    /// Turn touched levels into level events and deliver full batches.
+    fn publish(&mut self) -> PyResult<()> {
+        self.pull_tripped_quotes()?;
+        self.notify_orders()?;
+        self.deliver_drop_copies()?;
+        self.observe_features();
+        self.share_snapshots_now()?;
+        let Some(mut publisher) = self.publisher.take() else {
+            return Ok(());
+        };
+        for (symbol, side, price) in publisher.ring.take_dirty_levels() {
+            let quantity = self.level_quantity(&symbol, side, price);
+            publisher.ring.push(BusEvent::Level {
+                symbol,
+                side,
+                price,
+                quantity,
+            });
+        }
+        let delivered = Python::with_gil(|py| publisher.deliver(py, false));
+        self.publisher = Some(publisher);
+        delivered
+    }
+
+    /// Hold a trade on `symbol` for its order flow and execution quality,
+    /// until the event is observed, and add it to its volume profile; each
+    /// if recorded.
+    fn record_trade(&mut self, symbol: &str, price: Decimal, quantity: i64) {
+        let Some(id) = self.symbol_table.get(symbol) else {
+            return;
+        };
+        if let Some(flow) = self.flows.get_mut(&id) {
+            flow.trade(price, quantity);
+        }
+        if let Some(quality) = self.qualities.get_mut(&id) {
+            quality.trade(price, quantity);
+        }
+        if let Some(profile) = self.profiles.get_mut(&id) {
+            profile.trade(price, quantity);
+        }
+    }
+
+    /// Close an event on `symbol` in its order flow, execution quality and
+    /// volume profile, if recorded.
+    fn observe_flow(&mut self, symbol: &str) {
+        let Some(id) = self.symbol_table.get(symbol) else {
+            return;
+        };
+        if !self.flows.contains_key(&id)
+            && !self.qualities.contains_key(&id)
+            && !self.profiles.contains_key(&id)
+        {
+            return;
+        }
+        let now = self.current_time.unwrap_or(0);
+        let bid = self.best_level(symbol, Side::BID);
+        let ask = self.best_level(symbol, Side::ASK);
+        if let Some(flow) = self.flows.get_mut(&id) {
+            flow.observe(now, bid, ask);
+        }
+        let (bid, ask) = (bid.map(|(price, _)| price), ask.map(|(price, _)| price));
+        if let Some(quality) = self.qualities.get_mut(&id) {
+            quality.observe(now, bid, ask);
+        }
+        if let Some(profile) = self.profiles.get_mut(&id) {
+            profile.observe(
+                now,
+                bid.zip(ask).map(|(bid, ask)| (bid + ask) / Decimal::TWO),
+            );
+        }
+    }
+
+    /// Feed each featured symbol's best levels to its rolling features.
+    fn observe_features(&mut self) {
+        let now = self.current_time.unwrap_or(0);
+        let touches: Vec<_> = self
+            .features
+            .keys()
+            .map(|&id| {
+                let symbol = self.symbol_table.name(id);
+                let bid = self.best_level(symbol, Side::BID);
+                let ask = self.best_level(symbol, Side::ASK);
+                (id, bid, ask)
+            })
+            .collect();
+        for (id, bid, ask) in touches {
+            if let Some(features) = self.features.get_mut(&id) {
+                features.observe(now, bid, ask);
+            }
+        }
+    }
+
+    /// Write the shared symbols' snapshots to the ring, if sharing.
+    fn share_snapshots_now(&mut self) -> PyResult<()> {
+        let Some(ring) = self.shared.as_ref() else {
+            return Ok(());
+        };
+        let snapshots = self.snapshot_all(&ring.symbols, ring.depth);
+        let time = self.current_time;
+        match self.shared.as_mut() {
+            Some(ring) => ring.write(time, &snapshots),
+            None => Ok(()),
+        }
+    }
+
+    /// Total resting quantity at one price level.
+    fn level_quantity(&self, symbol: &str, side: Side, price: Decimal) -> i64 {
+        let Some(sym_book) = self.symbol_book(symbol) else {
+            return 0;
+        };
+        let ascending = matches!(side, Side::BID);
+        let one_side = if ascending {
+            &sym_book.bids
+        } else {
+            &sym_book.asks
+        };
+        one_side
+            .find_level(price, ascending)
+            .map_or(0, |idx| one_side.levels[idx].displayed_quantity())
+    }
+
+    /// Mutable access to a resting order.
+    fn resting_entry_mut(&mut self, order_id: Uuid) -> Option<&mut OrderEntry> {
+        let (symbol, side, price) = self.order_map.get(&order_id)?;
+        if let Some(publisher) = self.publisher.as_mut() {
+            let name = self.symbol_table.name(*symbol);
+            publisher.ring.touch_level(name, *side, *price);
+        }
+        let ascending = matches!(side, Side::BID);
+        let sym_book = self.symbols.get_mut(symbol)?;
+        let one_side = if ascending {
+            &mut sym_book.bids
+        } else {
+            &mut sym_book.asks
+        };
+        let idx = one_side.locate(*price, ascending).ok()?;
+        one_side.levels[idx]
+            .orders
+            .iter_mut()
+            .find(|entry| entry.id == order_id)
+    }
+
+    fn apply_feed_update(&mut self, update: FeedUpdate) -> PyResult<()> {
+        match update {
+            FeedUpdate::SetLevel {
+                symbol,
+                side,
+                price,
+                quantity,
+            } => {
+                if let Some(icebergs) = self.icebergs.as_mut() {
+                    icebergs.other(&symbol);
+                }
+                self.set_level_quantity(&symbol, side, price, quantity)?;
+                self.observe_flow(&symbol);
+                Ok(())
+            }
+            FeedUpdate::AddOrder {
+                order_id,
+                symbol,
+                side,
+                price,
+                quantity,
+            } => {
+                if self.feed_order_id(&order_id).is_some() {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Feed order '{}' is already in the book",
+                        order_id
+                    )));
+                }
+                let order = Order::try_new_at(side, symbol, price, quantity)?;
+                self.validate_order(&order)?;
+                self.enqueue_internal(&order);
+                self.feed_orders.insert(order_id, order.id);
+                if let Some(icebergs) = self.icebergs.as_mut() {
+                    icebergs.added(&order.symbol, side, price, quantity);
+                }
+                self.observe_flow(&order.symbol);
+                Ok(())
+            }
+            FeedUpdate::ReduceOrder {
+                order_id,
+                quantity,
+                executed,
+            } => {
+                let id = self
+                    .feed_order_id(&order_id)
+                    .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(order_id.clone()))?;
+                let resting = self.resting_entry_mut(id).map(|entry| {
+                    (
+                        entry.symbol,
+                        entry.side,
+                        entry.price,
+                        entry.original_quantity,
+                        entry.quantity,
+                    )
+                });
+                let remaining = match (quantity, self.resting_entry_mut(id)) {
+                    (Some(qty), Some(entry)) => {
+                        if qty <= 0 || qty > entry.quantity {
+                            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                                "Cannot reduce feed order '{}' by {}: {} remaining",
+                                order_id, qty, entry.quantity
+                            )));
+                        }
+                        entry.quantity -= qty;
+                        entry.quantity
+                    }
+                    _ => 0,
+                };
+                if remaining == 0 {
+                    self.remove_resting(id)?;
+                    self.feed_orders.remove(&order_id);
+                }
+                let Some((symbol, side, price, size, before)) = resting else {
+                    return Ok(());
+                };
+                if self.icebergs.is_none()
+                    && self.flows.is_empty()
+                    && self.qualities.is_empty()
+                    && self.profiles.is_empty()
+                {
+                    return Ok(());
+                }
+                let symbol = self.symbol_table.name(symbol).to_string();
+                if let Some(icebergs) = self.icebergs.as_mut() {
+                    if executed && remaining == 0 {
+                        icebergs.exhausted(&symbol, side, price, size);
+                    } else {
+                        icebergs.other(&symbol);
+                    }
+                }
+                if executed {
+                    self.record_trade(&symbol, price, before - remaining);
+                }
+                self.observe_flow(&symbol);
+                Ok(())
+            }
+        }
+    }
+
+    /// Book id of a venue order that is still resting.
+    fn feed_order_id(&self, order_id: &str) -> Option<Uuid> {
+        self.feed_orders
+            .get(order_id)
+            .copied()
+            .filter(|id| self.order_map.contains_key(id))
+    }
+
+    /// Apply one symbol's snapshot levels as synthetic L2 orders after
+    /// screening them with `policy`.
+    fn seed_snapshot_levels(
+        &mut self,
+        symbol: &str,
+        levels: Vec<(Side, Decimal, i64)>,
+        policy: SnapshotPolicy,
+    ) -> PyResult<()> {
+        for (side, price, quantity) in policy.screen(symbol, levels)? {
+            let best_bid = self
+                .symbol_book(symbol)
+                .and_then(|sym_book| sym_book.bids.levels.last())
+                .map(|level| level.price);
+            let crosses = side == Side::ASK && quantity > 0 && best_bid >= Some(price);
+            if crosses && policy == SnapshotPolicy::UncrossByMatching {
+                let order = Order::try_new_at(side, symbol.to_string(), price, quantity)?;
+                self.match_single(&order)?;
+                if let Some(rested) = self.find_resting(order.id) {
+                    let key = (self.symbol_table.intern(symbol), side, price);
+                    self.anchor_level(key, rested.quantity);
+                    self.l2_levels.insert(key, order.id);
+                }
+            } else {
+                self.set_level_quantity(symbol, side, price, quantity)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Create, resize or delete the synthetic order backing an L2 level,
+    /// restarting its liquidity decay.
+    fn set_level_quantity(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        price: Decimal,
+        new_quantity: i64,
+    ) -> PyResult<()> {
+        if new_quantity < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Level quantity cannot be negative",
+            ));
+        }
+        let key = (self.symbol_table.intern(symbol), side, price);
+        self.anchor_level(key, new_quantity);
+        self.resize_level(key, new_quantity)
+    }
+
+    /// Remember `quantity` as what a synthetic level was set to now.
+    fn anchor_level(&mut self, key: (SymbolId, Side, Decimal), quantity: i64) {
+        if quantity == 0 {
+            self.level_anchors.remove(&key);
+        } else {
+            let since = self.current_time.unwrap_or(0);
+            self.level_anchors
+                .insert(key, LevelAnchor { quantity, since });
+        }
+    }
+
+    /// Resize the synthetic order at `key` without touching its anchor.
+    fn resize_level(&mut self, key: (SymbolId, Side, Decimal), new_quantity: i64) -> PyResult<()> {
+        let (side, price) = (key.1, key.2);
+        // Synthetic orders can be consumed by matching since they were set.
+        let existing = self
+            .l2_levels
+            .get(&key)
+            .copied()
+            .filter(|id| self.order_map.contains_key(id));
+        match existing {
+            Some(id) if new_quantity == 0 => {
+                self.remove_resting(id)?;
+                self.l2_levels.remove(&key);
+            }
+            Some(id) => {
+                if let Some(entry) = self.resting_entry_mut(id) {
+                    entry.quantity = new_quantity;
+                    entry.original_quantity = new_quantity;
+                }
+            }
+            None if new_quantity == 0 => {
+                self.l2_levels.remove(&key);
+            }
+            None => {
+                let symbol = self.symbol_table.name(key.0).to_string();
+                let order = Order::try_new_at(side, symbol, price, new_quantity)?;
+                self.validate_order(&order)?;
+                self.enqueue_internal(&order);
+                self.l2_levels.insert(key, order.id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel a resting or held order; false if the book doesn't know it.
+    /// Pulling a resting order can move the quote and trigger stops on it.
+    pub(crate) fn cancel_order(&mut self, order_id: Uuid) -> PyResult<bool> {
+        self.journaled(
+            || JournalEntry::Cancel(order_id),
+            |book| {
+                let symbol = book
+                    .order_map
+                    .get(&order_id)
+                    .map(|(s, _, _)| book.symbol_table.name(*s).to_string());
+                let removed = book.remove_order(order_id)?;
+                if let Some(symbol) = symbol {
+                    book.run_stop_cascade(&symbol, &[]);
+                    book.observe_flow(&symbol);
+                }
+                Ok(removed)
+            },
+        )
+    }
+
+    /// `cancel_order` without journaling.
+    fn remove_order(&mut self, order_id: Uuid) -> PyResult<bool> {
+        let removed = match self.remove_resting(order_id)? {
+            Some(entry) => Some(entry),
+            None => self.remove_held(order_id).or_else(|| {
+                let queued = self.throttles.remove(order_id)?;
+                Some(self.entry_of(&queued))
+            }),
+        };
+        if let Some(entry) = &removed {
+            self.counters.entry(entry.symbol).or_default().cancelled += 1;
+            let symbol = self.symbol_table.name(entry.symbol).to_string();
+            self.record_history(|history, now| {
+                history.close(order_id, now, OrderEventKind::Cancelled, entry.price)
+            });
+            self.emit(|| BusEvent::OrderCancelled {
+                symbol,
+                order_id,
+                remaining: entry.quantity,
+            });
+        }
+        Ok(removed.is_some())
+    }
+
+    /// `disconnect` without journaling or publishing.
+    fn disconnect_session(&mut self, session_id: &str, delay: i64) -> PyResult<Vec<Uuid>> {
+        if delay < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "delay cannot be negative",
+            ));
+        }
+        if delay == 0 {
+            return self.remove_session_orders(session_id);
+        }
+        let Some(now) = self.current_time else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "A disconnect delay needs the simulated clock; call advance_time first",
+            ));
+        };
+        self.disconnects
+            .schedule(session_id.to_string(), now + delay);
+        Ok(Vec::new())
+    }
+
+    fn reconnect_session(&mut self, session_id: &str) -> bool {
+        self.disconnects.cancel(session_id)
+    }
+
+    /// Cancel the resting and held orders of `session_id`, in arrival
+    /// order, without journaling.
+    fn remove_session_orders(&mut self, session_id: &str) -> PyResult<Vec<Uuid>> {
+        let resting = self
+            .symbols
+            .values()
+            .flat_map(|sym_book| sym_book.bids.levels.iter().chain(&sym_book.asks.levels))
+            .flat_map(|level| level.orders.iter());
+        let held = self
+            .auction_orders
+            .values()
+            .chain(self.stops.values())
+            .chain(self.halted_orders.values())
+            .flatten();
+        let mut owned: Vec<(u64, Uuid)> = resting
+            .chain(held)
+            .filter(|entry| entry.session_id.as_deref() == Some(session_id))
+            .map(|entry| (entry.seq, entry.id))
+            .collect();
+        owned.sort_unstable();
+        let queued = self
+            .throttles
+            .queue
+            .iter()
+            .filter(|order| order.session_id.as_deref() == Some(session_id))
+            .map(|order| (u64::MAX, order.id));
+        owned.extend(queued.collect::<Vec<_>>());
+        let mut cancelled = Vec::with_capacity(owned.len());
+        for (_, id) in owned {
+            if self.remove_order(id)? {
+                cancelled.push(id);
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Amend a resting order; `Ok(None)` if it is not resting.
+    pub(crate) fn amend_order(
+        &mut self,
+        order_id: Uuid,
+        price: Option<Decimal>,
+        quantity: Option<i64>,
+    ) -> PyResult<Option<TradeBlotter>> {
+        self.journaled(
+            || JournalEntry::Amend {
+                order_id,
+                price,
+                quantity,
+            },
+            |book| book.replace_order(order_id, price, quantity),
+        )
+    }
+
+    /// `amend_order` without journaling.
+    fn replace_order(
+        &mut self,
+        order_id: Uuid,
+        price: Option<Decimal>,
+        quantity: Option<i64>,
+    ) -> PyResult<Option<TradeBlotter>> {
+        let Some(current) = self.resting_entry_mut(order_id).map(|e| e.clone()) else {
+            return Ok(None);
+        };
+        let quantity = quantity.unwrap_or(current.quantity);
+        if quantity <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Amended quantity must be greater than zero",
+            ));
+        }
+        let mut amended = self.order_of(&current);
+        amended.price = price.unwrap_or(current.price);
+        amended.quantity = quantity;
+        amended.original_quantity += quantity - current.quantity;
+        self.validate_order(&amended)?;
+
+        if amended.price == current.price && quantity <= current.quantity {
+            if let Some(entry) = self.resting_entry_mut(order_id) {
+                entry.quantity = quantity;
+                entry.original_quantity = amended.original_quantity;
+            }
+            self.record_history(|history, now| {
+                history.amend(order_id, now, amended.price, quantity)
+            });
+            let mut released = current;
+            released.quantity -= quantity;
+            self.restore_locate(&released);
+            return Ok(Some(self.blotter(amended, Vec::new())));
+        }
+
+        self.remove_resting(order_id)?;
+        self.record_history(|history, now| history.amend(order_id, now, amended.price, quantity));
+        match self.match_single(&amended) {
+            Ok(blotter) => Ok(Some(blotter)),
+            Err(err) => {
+                self.record_history(|history, _| history.retract_amend(order_id, current.quantity));
+                // A rejected replace leaves the original order working, at
+                // the back of its level.
+                let original = self.order_of(&current);
+                if original.short {
+                    if let Some(locates) = self.locates.as_mut() {
+                        *locates.entry(current.symbol).or_insert(0) -= original.quantity;
+                    }
+                }
+                self.enqueue_internal(&original);
+                Err(err)
+            }
+        }
+    }
+
+    /// Partially cancel a resting order; `Ok(None)` if it is not resting.
+    pub(crate) fn reduce_order(&mut self, order_id: Uuid, delta: i64) -> PyResult<Option<i64>> {
+        if delta <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Reduce quantity must be greater than zero",
+            ));
+        }
+        let Some(remaining) = self.resting_entry_mut(order_id).map(|e| e.quantity) else {
+            return Ok(None);
+        };
+        if delta > remaining {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot reduce order {} by {}: {} remaining",
+                order_id, delta, remaining
+            )));
+        }
+        if delta == remaining {
+            self.cancel_order(order_id)?;
+        } else {
+            self.amend_order(order_id, None, Some(remaining - delta))?;
+        }
+        Ok(Some(remaining - delta))
+    }
+
+    /// Hand the locate consumed by unexecuted short quantity back.
+    fn restore_locate(&mut self, entry: &OrderEntry) {
+        if entry.short {
+            if let Some(locates) = self.locates.as_mut() {
+                *locates.entry(entry.symbol).or_insert(0) += entry.quantity;
+            }
+        }
+    }
+
+    /// Remove a held auction, untriggered stop or halt-queued order,
+    /// restoring any locate it consumed.
+    fn remove_held(&mut self, order_id: Uuid) -> Option<OrderEntry> {
+        let held = self
+            .auction_orders
+            .values_mut()
+            .chain(self.stops.values_mut())
+            .chain(self.halted_orders.values_mut())
+            .find(|orders| orders.iter().any(|o| o.id == order_id))?;
+        let pos = held.iter().position(|o| o.id == order_id)?;
+        let entry = held.remove(pos);
+        self.restore_locate(&entry);
+        self.accounts.remove(&order_id);
+        Some(entry)
+    }
+
+    fn state_of(&self, symbol: &str) -> TradingState {
+        self.per_symbol(&self.trading_states, symbol)
+            .copied()
+            .unwrap_or(TradingState::CONTINUOUS)
+    }
+
+    /// Check the symbol's trading phase and auction cutoffs.
+    fn check_trading_state(&self, order: &Order) -> PyResult<()> {
+        let state = self.state_of(&order.symbol);
+        if state == TradingState::CLOSED {
+            return Err(
+                RejectCode::TradingState.err(format!("Trading in {} is closed", order.symbol))
+            );
+        }
+        let Some(designation) = order.auction else {
+            return Ok(());
+        };
+        let kind = designation.kind();
+        if kind == AuctionKind::Open && state != TradingState::PRE_OPEN {
+            return Err(RejectCode::TradingState.err(format!(
+                "On-open orders for {} are only accepted during PRE_OPEN",
+                order.symbol
+            )));
+        }
+        let cutoff = self
+            .symbol_table
+            .get(&order.symbol)
+            .and_then(|id| self.auction_cutoffs.get(&(id, kind)));
+        if let (Some(&cutoff), Some(now)) = (cutoff, self.current_time) {
+            if now >= cutoff {
+                return Err(RejectCode::TradingState.err(format!(
+                    "The {} auction cutoff for {} passed at {}",
+                    kind.as_str(),
+                    order.symbol,
+                    cutoff
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The `designated` orders plus every resting limit order in `symbol`,
+    /// as uncross participants.
+    fn auction_participants(&self, symbol: &str, designated: &[OrderEntry]) -> Vec<AuctionOrder> {
+        let mut participants: Vec<AuctionOrder> = designated
+            .iter()
+            .map(OrderEntry::to_auction_order)
+            .collect();
+        if let Some(sym_book) = self.symbol_book(symbol) {
+            participants.extend(
+                sym_book
+                    .bids
+                    .levels
+                    .iter()
+                    .chain(&sym_book.asks.levels)
+                    .flat_map(|level| &level.orders)
+                    .map(OrderEntry::to_auction_order),
+            );
+        }
+        participants
+    }
+
+    /// Uncross one auction and apply its executions to the book.
+    fn run_auction(
+        &mut self,
+        symbol: &str,
+        kind: AuctionKind,
+        reference: Option<Decimal>,
+    ) -> PyResult<AuctionResult> {
+        let (required, next) = match kind {
+            AuctionKind::Open => (TradingState::PRE_OPEN, TradingState::CONTINUOUS),
+            AuctionKind::Close => (TradingState::CONTINUOUS, TradingState::CLOSED),
+        };
+        let state = self.state_of(symbol);
+        if state != required {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot run the {} auction for {} in state {:?}",
+                kind.as_str(),
+                symbol,
+                state
+            )));
+        }
+
+        let id = self.symbol_table.intern(symbol);
+        let held = self.auction_orders.remove(&id).unwrap_or_default();
+        let (mut designated, other): (Vec<OrderEntry>, Vec<OrderEntry>) = held
+            .into_iter()
+            .partition(|entry| entry.auction.is_some_and(|a| a.kind() == kind));
+        if !other.is_empty() {
+            self.auction_orders.insert(id, other);
+        }
+
+        let participants = self.auction_participants(symbol, &designated);
+
+        let mut result = AuctionResult {
+            auction: kind,
+            price: None,
+            volume: 0,
+            imbalance: 0,
+            trades: Vec::new(),
+            cancelled: Vec::new(),
+        };
+        if let Some(uncross) = compute_uncross(&participants, reference) {
+            // The ask is the standing side of each uncross trade; market
+            // asks rest at the uncross price.
+            let asks: HashMap<Uuid, &AuctionOrder> = participants
+                .iter()
+                .filter(|p| p.side == Side::ASK)
+                .map(|p| (p.id, p))
+                .collect();
+            let mut executed: HashMap<Uuid, i64> = HashMap::new();
+            for &(bid_id, ask_id, qty) in &uncross.fills {
+                *executed.entry(bid_id).or_insert(0) += qty;
+                let ask_filled = executed.entry(ask_id).or_insert(0);
+                *ask_filled += qty;
+                let ask = asks[&ask_id];
+                result.trades.push(
+                    Trade::from_rust(bid_id, ask_id, qty, uncross.price)
+                        .with_symbol(symbol)
+                        .with_standing(
+                            ask.limit.unwrap_or(uncross.price),
+                            ask.quantity - *ask_filled,
+                        ),
+                );
+            }
+            self.enrichers.apply(&mut result.trades, true);
+            self.publish_trades(symbol, &result.trades);
+            self.record_session(symbol, &result.trades);
+            self.record_settlement(
+                symbol,
+                uncross
+                    .fills
+                    .iter()
+                    .map(|&(bid_id, ask_id, qty)| (bid_id, ask_id, uncross.price, qty)),
+                None,
+            );
+            self.record_history(|history, now| {
+                for t in &result.trades {
+                    for id in [t.incoming_order_id, t.standing_order_id] {
+                        history.fill(id, now, t.trade_id, t.fill_price, t.fill_quantity);
+                    }
+                }
+            });
+            let sym_book = self.symbol_table.get(symbol).map(|id| &self.symbols[&id]);
+            if let (Some(publisher), Some(sym_book)) = (self.publisher.as_mut(), sym_book) {
+                for level in &sym_book.bids.levels {
+                    publisher.ring.touch_level(symbol, Side::BID, level.price);
+                }
+                for level in &sym_book.asks.levels {
+                    publisher.ring.touch_level(symbol, Side::ASK, level.price);
+                }
+            }
+            if let Some(sym_book) = self.symbol_book_mut(symbol) {
+                let mut filled = sym_book.bids.apply_executions(&executed, uncross.price);
+                filled.extend(sym_book.asks.apply_executions(&executed, uncross.price));
+                for id in filled {
+                    self.unindex(id);
+                    self.accounts.remove(&id);
+                }
+            }
+            for entry in &mut designated {
+                if let Some(&qty) = executed.get(&entry.id) {
+                    entry.quantity -= qty;
+                    entry.fills.record(uncross.price, qty);
+                }
+            }
+            self.record_fills(executed);
+            result.price = Some(uncross.price);
+            result.volume = uncross.volume;
+            result.imbalance = uncross.imbalance;
+        }
+
+        for entry in designated.iter().filter(|entry| entry.quantity > 0) {
+            self.restore_locate(entry);
+            self.record_history(|history, now| {
+                history.close(entry.id, now, OrderEventKind::Cancelled, entry.price)
+            });
+            self.emit(|| BusEvent::OrderCancelled {
+                symbol: symbol.to_string(),
+                order_id: entry.id,
+                remaining: entry.quantity,
+            });
+            result.cancelled.push(self.order_of(entry));
+        }
+        for entry in &designated {
+            self.accounts.remove(&entry.id);
+        }
+        self.change_state(symbol, next);
+        self.observe_flow(symbol);
+        Ok(result)
+    }
+
+    /// Match each row in turn, advancing the clock to row timestamps.
+    fn replay_rows(
+        &mut self,
+        rows: impl IntoIterator<Item = ParquetOrderRow>,
+        skip_empty: bool,
+        py: Python<'_>,
+    ) -> PyResult<Vec<Py<TradeBlotter>>> {
+        let mut blotters = Vec::new();
+        for row in rows {
+            if let Some(ts) = row.timestamp {
+                self.advance_clock(ts)?;
+            }
+            let order = self.row_order(&row)?;
+            let blotter = self.match_single(&order)?;
+            if !(skip_empty && blotter.trades.is_empty()) {
+                blotters.push(Py::new(py, blotter)?);
+            }
+        }
+        Ok(blotters)
+    }
+
+    /// Replay `rows`, tallying outcomes instead of keeping blotters.
+    fn replay_summary(
+        &mut self,
+        rows: &[ParquetOrderRow],
+        malformed: RowErrors,
+        py: Python<'_>,
+    ) -> PyResult<ReplaySummary> {
+        let mut tally = ReplayTally::start();
+        for (row_idx, reason) in malformed {
+            tally.reject(row_idx, reason);
+        }
+        for row in rows {
+            let outcome = row
+                .timestamp
+                .map_or(Ok(Vec::new()), |ts| self.advance_clock(ts))
+                .and_then(|_| self.row_order(row))
+                .and_then(|order| Ok((self.match_single(&order)?, order)));
+            match outcome {
+                Ok((blotter, order)) => {
+                    let held = order.is_triggered() || order.auction.is_some();
+                    let rested = self.order_map.contains_key(&order.id)
+                        || (held && blotter.trades.is_empty());
+                    tally.record(&blotter, rested);
+                }
+                Err(err) => tally.reject(row.row_idx, err.value(py).to_string()),
+            }
+        }
+        self.publish()?;
+        Ok(tally.finish())
+    }
+
+    /// Apply queued strategy actions due by `until` (all when `None`),
+    /// advancing the clock to each one's arrival time. Blotters of orders
+    /// submitted are appended to `blotters`, minus empty ones if `skip_empty`.
+    /// Orders the book refuses are returned as Rejections under
+    /// `RejectPolicy::Report`.
+    fn apply_strategy_actions(
+        &mut self,
+        queue: &mut LatencyQueue,
+        until: Option<i64>,
+        blotters: &mut Vec<Py<TradeBlotter>>,
+        skip_empty: bool,
+        on_reject: RejectPolicy,
+        py: Python<'_>,
+    ) -> PyResult<Vec<Rejection>> {
+        let mut rejected = Vec::new();
+        while let Some((due, action)) = queue.pop_due(until) {
+            if self.current_time.is_none_or(|now| now < due) {
+                self.advance_clock(due)?;
+            }
+            match action {
+                StrategyAction::Submit(order) => match self.match_single(&order) {
+                    Ok(blotter) => {
+                        if !(skip_empty && blotter.trades.is_empty()) {
+                            blotters.push(Py::new(py, blotter)?);
+                        }
+                    }
+                    Err(err) => match code_of(py, &err) {
+                        Some(code) if on_reject == RejectPolicy::Report => {
+                            rejected.push(Rejection::from_rust(
+                                code,
+                                err.value(py).to_string(),
+                                Some(order.id),
+                                self.current_time,
+                            ));
+                        }
+                        _ => return Err(err),
+                    },
+                },
+                StrategyAction::Cancel(order_id) => {
+                    self.cancel_order(order_id)?;
+                }
+            }
+        }
+        Ok(rejected)
+    }
+
+    /// Apply one action of `agent` arriving at `due`: an order booked to
+    /// its account and checked against its risk limits, or a cancel. A
+    /// refused order is recorded on the agent and returned under
+    /// `RejectPolicy::Report`.
+    #[allow(clippy::too_many_arguments)]
+    fn apply_agent_action(
+        &mut self,
+        agent: &mut Agent,
+        due: i64,
+        action: StrategyAction,
+        blotters: &mut Vec<Py<TradeBlotter>>,
+        skip_empty: bool,
+        on_reject: RejectPolicy,
+        py: Python<'_>,
+    ) -> PyResult<Option<Rejection>> {
+        if self.current_time.is_none_or(|now| now < due) {
+            self.advance_clock(due)?;
+        }
+        let mut order = match action {
+            StrategyAction::Submit(order) => *order,
+            StrategyAction::Cancel(order_id) => {
+                self.cancel_order(order_id)?;
+                return Ok(None);
+            }
+        };
+        order.account = Some(agent.account.clone());
+        let position = self
+            .settlements
+            .report(Some(&agent.account), Decimal::ZERO, Decimal::ZERO)
+            .iter()
+            .filter(|line| line.symbol == order.symbol)
+            .map(|line| line.bought - line.sold)
+            .sum();
+        let working = self
+            .accounts
+            .iter()
+            .filter(|(_, account)| **account == agent.account)
+            .filter_map(|(id, _)| self.find_order(*id))
+            .filter(|working| working.symbol == order.symbol && working.side == order.side)
+            .map(|working| working.quantity)
+            .sum();
+        let outcome = match agent.check_limits(&order, position, working) {
+            Some(reason) => Err(("risk_limit".to_string(), reason)),
+            None => match self.match_single(&order) {
+                Ok(blotter) => Ok(blotter),
+                Err(err) => match code_of(py, &err) {
+                    Some(code) if on_reject == RejectPolicy::Report => {
+                        Err((code, err.value(py).to_string()))
+                    }
+                    _ => return Err(err),
+                },
+            },
+        };
+        match outcome {
+            Ok(blotter) => {
+                let blotter = Py::new(py, blotter)?;
+                if !(skip_empty && blotter.borrow(py).trades.is_empty()) {
+                    blotters.push(blotter.clone_ref(py));
+                }
+                agent.blotters.push(blotter);
+                Ok(None)
+            }
+            Err((_, reason)) if on_reject == RejectPolicy::Raise => {
+                Err(RejectCode::RiskLimit.err(reason))
+            }
+            Err((code, reason)) => {
+                let rejection =
+                    Rejection::from_rust(code, reason, Some(order.id), self.current_time);
+                agent.rejections.push(rejection.clone());
+                Ok(Some(rejection))
+            }
+        }
+    }
+
+    /// Currency amounts in `symbol` are in: its instrument's, else the base
+    /// currency.
+    fn currency_of(&self, symbol: &str) -> Option<&str> {
+        self.per_symbol(&self.instruments, symbol)
+            .map(|inst| inst.currency.as_str())
+            .or(self.fx.base())
+    }
+
+    fn known_currency(&self, symbol: &str) -> PyResult<&str> {
+        self.currency_of(symbol).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "The currency of {} is unknown; register its instrument or set a base currency",
+                symbol
+            ))
+        })
+    }
+
+    /// The midpoint of `symbol`, else its last trade price this session.
+    fn mark_price(&self, symbol: &str) -> Option<Decimal> {
+        let midpoint = self
+            .best_level(symbol, Side::BID)
+            .zip(self.best_level(symbol, Side::ASK))
+            .map(|((bid, _), (ask, _))| (bid + ask) / Decimal::TWO);
+        midpoint.or(self.per_symbol(&self.sessions, symbol).map(|s| s.last))
+    }
+
+    /// Net position per symbol of `account`, and its cash from fills and
+    /// cash flows plus those positions marked at the midpoint, else the
+    /// last trade price; in the base currency when one is set.
+    fn mark_account(&self, account: &str) -> PyResult<(HashMap<String, i64>, Decimal)> {
+        let mut positions = HashMap::new();
+        let mut pnl = Decimal::ZERO;
+        for line in self
+            .settlements
+            .report(Some(account), Decimal::ZERO, Decimal::ZERO)
+        {
+            let net = line.bought - line.sold;
+            let mut value = line.sell_notional - line.buy_notional + line.cash_flows;
+            if let Some(mark) = self.mark_price(&line.symbol) {
+                value += Decimal::from(net) * mark * self.multiplier(&line.symbol);
+            }
+            if let Some(base) = self.fx.base() {
+                value = self
+                    .fx
+                    .convert(value, self.known_currency(&line.symbol)?, base)?;
+            }
+            pnl += value;
+            positions.insert(line.symbol, net);
+        }
+        Ok((positions, pnl))
+    }
+
+    /// Move the clock forward and expire due GTD orders.
+    fn advance_clock(&mut self, timestamp: i64) -> PyResult<Vec<Order>> {
+        self.journaled(
+            || JournalEntry::AdvanceTime(timestamp),
+            |book| book.move_clock(timestamp),
+        )
     }
 
-    /// Expose order_map as dict[UUID, Order] for API parity.
-    #[getter]
-    fn order_map(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let dict = PyDict::new(py);
-        for (uid, (symbol, side, price)) in &self.order_map {
-            // Look up the actual order entry to get current quantity
-            let sym_book = match self.symbols.get(symbol) {
-                Some(b) => b,
-                None => continue,
+    /// `advance_clock` without journaling.
+    fn move_clock(&mut self, timestamp: i64) -> PyResult<Vec<Order>> {
+        if let Some(now) = self.current_time {
+            if timestamp < now {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Cannot move time backwards from {} to {}",
+                    now, timestamp
+                )));
+            }
+        }
+        let mut expired = Vec::new();
+        let mut flows = self.cash_flows.take_due(timestamp).into_iter().peekable();
+        for (at, symbol, event) in self.due_session_events(timestamp) {
+            while let Some((_, flow)) = flows.next_if(|(time, _)| *time <= at) {
+                self.pay_cash_flow(&flow);
+            }
+            self.current_time = Some(at);
+            expired.extend(self.expire_orders(at)?);
+            self.run_session_event(&symbol, event)?;
+        }
+        for (_, flow) in flows {
+            self.pay_cash_flow(&flow);
+        }
+        self.current_time = Some(timestamp);
+        self.decay_levels(timestamp)?;
+        expired.extend(self.expire_orders(timestamp)?);
+        for session_id in self.disconnects.take_due(timestamp) {
+            self.remove_session_orders(&session_id)?;
+        }
+        for order in self.throttles.release(timestamp) {
+            // A released order the book now refuses is dropped, as a
+            // gateway would reject it.
+            // It was counted as an order when it queued.
+            let result = self.execute_order(&order);
+            if result.is_err() {
+                self.throttles.drop_released(&order);
+                self.count_outcome(&order, &result);
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Scheduled session events after the clock up to `to`, as (time,
+    /// symbol, event), earliest first.
+    fn due_session_events(&self, to: i64) -> Vec<(i64, String, SessionEvent)> {
+        let mut due: Vec<_> = self
+            .schedules
+            .iter()
+            .flat_map(|(&id, schedule)| {
+                let symbol = self.symbol_table.name(id);
+                let events = schedule.due(self.current_time, to).into_iter();
+                events.map(move |(at, event)| (at, symbol.to_string(), event))
+            })
+            .collect();
+        due.sort();
+        due
+    }
+
+    /// Move `symbol` into the phase its schedule starts at `event`, running
+    /// the auction that calls for. A halted symbol stays halted.
+    fn run_session_event(&mut self, symbol: &str, event: SessionEvent) -> PyResult<()> {
+        let next = self.per_symbol(&self.schedules, symbol);
+        let Some(next) = next.map(|s| s.state_after(event)) else {
+            return Ok(());
+        };
+        let auction = match (event, self.state_of(symbol)) {
+            (_, TradingState::HALTED) => return Ok(()),
+            (SessionEvent::Open, TradingState::PRE_OPEN) => Some(AuctionKind::Open),
+            (SessionEvent::Close, TradingState::CONTINUOUS) => Some(AuctionKind::Close),
+            _ => None,
+        };
+        if let Some(kind) = auction {
+            self.run_auction(symbol, kind, None)?;
+        }
+        self.change_state(symbol, next);
+        Ok(())
+    }
+
+    /// Book `flow` to every open position in its symbol.
+    fn pay_cash_flow(&mut self, flow: &CashFlow) {
+        let multiplier = self.multiplier(&flow.symbol);
+        let mark = self.mark_price(&flow.symbol);
+        let paid = self
+            .settlements
+            .pay(&flow.symbol, |net| flow.payment(net, multiplier, mark));
+        log::info!(
+            target: TARGET,
+            event = "cash_flow",
+            symbol = flow.symbol.as_str(),
+            kind = flow.kind.as_str(),
+            amount:% = flow.amount,
+            positions = paid;
+            "{} {} of {} paid to {} position(s)", flow.symbol, flow.kind.as_str(), flow.amount, paid
+        );
+    }
+
+    /// Expire good-till-date orders due by `now`, returning them.
+    fn expire_orders(&mut self, now: i64) -> PyResult<Vec<Order>> {
+        let mut expired = Vec::new();
+        while let Some(&(expire_time, order_id)) = self.expiries.first() {
+            if expire_time > now {
+                break;
+            }
+            self.expiries.pop_first();
+            // Orders filled or cancelled since enqueue are no longer resting.
+            let entry = match self.remove_resting(order_id)? {
+                Some(entry) => Some(entry),
+                None => self.remove_held(order_id),
             };
-            let ascending = matches!(side, Side::BID);
-            let one_side = if ascending {
-                &sym_book.bids
+            if let Some(entry) = entry {
+                let symbol = self.symbol_table.name(entry.symbol).to_string();
+                self.record_history(|history, now| {
+                    history.close(order_id, now, OrderEventKind::Expired, entry.price)
+                });
+                self.emit(|| BusEvent::OrderExpired {
+                    symbol: symbol.clone(),
+                    order_id,
+                    remaining: entry.quantity,
+                });
+                self.counters.entry(entry.symbol).or_default().expired += 1;
+                expired.push(self.order_of(&entry));
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Age synthetic levels to `now` under the liquidity decay model.
+    /// Levels refilled by a refresh are left out while the opposite side
+    /// would cross them.
+    fn decay_levels(&mut self, now: i64) -> PyResult<()> {
+        let Some(decay) = self.liquidity_decay else {
+            return Ok(());
+        };
+        let mut anchors: Vec<_> = self
+            .level_anchors
+            .iter()
+            .map(|(key, anchor)| (*key, *anchor))
+            .collect();
+        let name = |id| self.symbol_table.name(id);
+        anchors.sort_by(|(a, _), (b, _)| {
+            (name(a.0), a.1 == Side::ASK, a.2).cmp(&(name(b.0), b.1 == Side::ASK, b.2))
+        });
+        for (key, anchor) in anchors {
+            let (anchor, target, refreshed) = decay.step(anchor, now);
+            let live = self
+                .l2_levels
+                .get(&key)
+                .and_then(|id| self.find_resting(*id))
+                .map_or(0, |order| order.quantity);
+            let quantity = if refreshed && live == 0 && self.level_would_cross(&key) {
+                0
+            } else if refreshed {
+                target
             } else {
-                &sym_book.asks
+                live.min(target)
             };
-            if let Ok(idx) = one_side.find_level(*price, ascending) {
-                for entry in &one_side.levels[idx].orders {
-                    if entry.id == *uid {
-                        let py_uuid = uuid_to_py(py, *uid)?;
-                        let py_order = Py::new(py, entry.to_order())?;
-                        dict.set_item(py_uuid, py_order)?;
-                        break;
-                    }
-                }
+            if quantity != live {
+                self.resize_level(key, quantity)?;
+            }
+            if target == 0 && decay.refresh_after.is_none() {
+                self.level_anchors.remove(&key);
+            } else {
+                self.level_anchors.insert(key, anchor);
             }
         }
-        Ok(dict.into())
+        Ok(())
     }
 
-    /// Expose levels as defaultdict-like structure for API parity.
-    /// Returns dict[symbol, dict[Side, list[PriceLevel]]].
-    #[getter]
-    fn levels(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let outer = PyDict::new(py);
-        for (symbol, sym_book) in &self.symbols {
-            let inner = PyDict::new(py);
-            // Build bid levels list (in heap order for parity)
-            let bid_list = pyo3::types::PyList::empty(py);
-            for lvl in &sym_book.bids.levels {
-                let mut pl = PriceLevel::from_rust(Side::BID, lvl.price);
-                for entry in &lvl.orders {
-                    pl.orders.append_order(entry.to_order());
-                }
-                bid_list.append(Py::new(py, pl)?)?;
+    /// Whether resting at `key` would cross the opposite best price.
+    fn level_would_cross(&self, (symbol, side, price): &(SymbolId, Side, Decimal)) -> bool {
+        let Some(sym_book) = self.symbols.get(symbol) else {
+            return false;
+        };
+        let opposite = match side {
+            Side::BID => &sym_book.asks,
+            Side::ASK => &sym_book.bids,
+        };
+        opposite.levels.last().is_some_and(|best| {
+            pyorderbook_core::Side::from(*side).price_is_matchable(*price, best.price)
+        })
+    }
+
+    /// Pre-trade checks for an order entering the book: instrument
+    /// validation, trading phase and auction cutoffs, GTD expiry, then
+    /// short-sale locate consumption.
+    fn accept_order(&mut self, order: &Order) -> PyResult<()> {
+        if order.is_triggered() && order.auction.is_some() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Stop and conditional orders cannot carry an auction designation",
+            ));
+        }
+        self.validate_order(order)?;
+        self.validators
+            .check(order, |symbol| self.market_reference(symbol))?;
+        self.check_trading_state(order)?;
+        let duplicate = self.order_map.contains_key(&order.id);
+        if duplicate && self.duplicate_policy == DuplicatePolicy::Reject {
+            return Err(
+                RejectCode::DuplicateId.err(format!("Order {} is already resting", order.id))
+            );
+        }
+        self.check_capacity(order)?;
+        if let (Some(expire_time), Some(now)) = (order.expire_time, self.current_time) {
+            if expire_time <= now {
+                return Err(RejectCode::Expired.err(format!(
+                    "Order already expired: expire_time {} is not after current time {}",
+                    expire_time, now
+                )));
             }
-            let ask_list = pyo3::types::PyList::empty(py);
-            for lvl in &sym_book.asks.levels {
-                let mut pl = PriceLevel::from_rust(Side::ASK, lvl.price);
-                for entry in &lvl.orders {
-                    pl.orders.append_order(entry.to_order());
+        }
+        if order.short {
+            if let Some(locates) = self.locates.as_mut() {
+                let id = self.symbol_table.intern(&order.symbol);
+                let available = locates.get(&id).copied().unwrap_or(0);
+                if available < order.quantity {
+                    return Err(RejectCode::Locate.err(format!(
+                        "Short sale of {} {} rejected: only {} available to borrow",
+                        order.quantity, order.symbol, available
+                    )));
                 }
-                ask_list.append(Py::new(py, pl)?)?;
+                locates.insert(id, available - order.quantity);
             }
-            inner.set_item(Side::BID, bid_list)?;
-            inner.set_item(Side::ASK, ask_list)?;
-            outer.set_item(symbol, inner)?;
         }
-        Ok(outer.into())
+        if duplicate && self.duplicate_policy == DuplicatePolicy::Replace {
+            self.remove_order(order.id)?;
+        }
+        self.record_history(|history, now| {
+            history.accept(order.id, now, order.price, order.quantity)
+        });
+        if let Some(account) = &order.account {
+            if self.drop_copies.iter().any(|copy| copy.covers(account)) {
+                self.drop_copy_orders.insert(order.id, account.clone());
+            }
+        }
+        log::debug!(
+            target: TARGET,
+            event = "accepted",
+            order_id:% = order.id,
+            symbol = order.symbol.as_str(),
+            side = order.side.as_str(),
+            price:% = order.price,
+            quantity = order.quantity;
+            "order {} accepted", order.id
+        );
+        self.emit(|| BusEvent::OrderAccepted {
+            symbol: order.symbol.clone(),
+            order_id: order.id,
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+        });
+        if let Some(account) = &order.account {
+            self.accounts.insert(order.id, account.clone());
+        }
+        Ok(())
     }
 
-    /// Expose level_map as dict[symbol, dict[Side, dict[Price, PriceLevel]]] for API parity.
-    #[getter]
-    fn level_map(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let outer = PyDict::new(py);
-        for (symbol, sym_book) in &self.symbols {
-            let inner = PyDict::new(py);
-            let bid_map = PyDict::new(py);
-            for lvl in &sym_book.bids.levels {
-                let mut pl = PriceLevel::from_rust(Side::BID, lvl.price);
-                for entry in &lvl.orders {
-                    pl.orders.append_order(entry.to_order());
-                }
-                let py_price = decimal_to_py(py, lvl.price)?;
-                bid_map.set_item(py_price, Py::new(py, pl)?)?;
-            }
-            let ask_map = PyDict::new(py);
-            for lvl in &sym_book.asks.levels {
-                let mut pl = PriceLevel::from_rust(Side::ASK, lvl.price);
-                for entry in &lvl.orders {
-                    pl.orders.append_order(entry.to_order());
-                }
-                let py_price = decimal_to_py(py, lvl.price)?;
-                ask_map.set_item(py_price, Py::new(py, pl)?)?;
-            }
-            inner.set_item(Side::BID, bid_map)?;
-            inner.set_item(Side::ASK, ask_map)?;
-            outer.set_item(symbol, inner)?;
+    /// Queue a lifecycle event for publishers that want them, and for the
+    /// sink of a watched order leaving the book.
+    fn emit(&mut self, event: impl FnOnce() -> BusEvent) {
+        let lifecycle = self
+            .publisher
+            .as_ref()
+            .is_some_and(Publisher::wants_lifecycle);
+        if !lifecycle && self.order_callbacks.is_empty() && self.drop_copy_orders.is_empty() {
+            return;
+        }
+        let event = event();
+        if let Some(account) = event
+            .order_id()
+            .and_then(|id| self.drop_copy_orders.get(&id))
+        {
+            self.drop_copy_events
+                .push_back((account.clone(), event.clone()));
+        }
+        if let BusEvent::OrderCancelled { order_id, .. } | BusEvent::OrderExpired { order_id, .. } =
+            &event
+        {
+            if self.order_callbacks.contains_key(order_id) {
+                self.order_events.push_back((*order_id, event.clone()));
+            }
+        }
+        if let Some(publisher) = self.publisher.as_mut().filter(|_| lifecycle) {
+            publisher.ring.push(event);
         }
-        Ok(outer.into())
     }
 
-    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
-        let self_obj = crate::getter::pyref_to_object(py, &slf);
-        crate::getter::handle_getter_attr(py, self_obj, name)
+    fn change_state(&mut self, symbol: &str, state: TradingState) {
+        let previous = self.state_of(symbol);
+        let id = self.symbol_table.intern(symbol);
+        if self.trading_states.insert(id, state) != Some(state) {
+            log::info!(
+                target: TARGET,
+                event = "state_changed",
+                symbol = symbol,
+                from = previous.__str__(),
+                to = state.__str__();
+                "{} trading state {} -> {}", symbol, previous.__str__(), state.__str__()
+            );
+            self.emit(|| BusEvent::StateChanged {
+                symbol: symbol.to_string(),
+                state,
+            });
+        }
     }
 
-    /// Return an L2 depth snapshot for a symbol, or None if never seen.
-    #[pyo3(signature = (symbol, depth = 5))]
-    fn snapshot(&self, symbol: &str, depth: isize) -> Option<Snapshot> {
-        let sym_book = self.symbols.get(symbol)?;
-        let depth = depth.max(0) as usize;
+    /// Cancel what `policy` says of `symbol`'s open orders and halt it,
+    /// returning the cancelled orders.
+    fn halt_symbol(&mut self, symbol: &str, policy: HaltPolicy) -> PyResult<Vec<Order>> {
+        let state = self.state_of(symbol);
+        if matches!(state, TradingState::CLOSED | TradingState::HALTED) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot halt {}: trading is {}",
+                symbol,
+                state.__str__()
+            )));
+        }
+        let mut doomed: Vec<Uuid> = self
+            .per_symbol(&self.stops, symbol)
+            .into_iter()
+            .chain(self.per_symbol(&self.auction_orders, symbol))
+            .flatten()
+            .filter(|entry| policy.cancels_held(entry))
+            .map(|entry| entry.id)
+            .collect();
+        if policy == HaltPolicy::CancelAll {
+            if let Some(sym_book) = self.symbol_book(symbol) {
+                doomed.extend(
+                    sym_book
+                        .bids
+                        .levels
+                        .iter()
+                        .chain(&sym_book.asks.levels)
+                        .flat_map(|level| &level.orders)
+                        .map(|entry| entry.id),
+                );
+            }
+        }
+        let mut cancelled = Vec::new();
+        for order_id in doomed {
+            if let Some(order) = self.find_order(order_id) {
+                self.cancel_order(order_id)?;
+                cancelled.push(order);
+            }
+        }
+        self.change_state(symbol, TradingState::HALTED);
+        Ok(cancelled)
+    }
 
-        // Bids: sorted ascending, best (highest) at back → iterate reversed
-        let bid_levels: Vec<SnapshotLevel> = sym_book
-            .bids
-            .levels
+    /// `apply_split` without journaling or publishing.
+    fn split_symbol(&mut self, symbol: &str, ratio: Decimal) -> PyResult<Vec<Order>> {
+        if ratio <= Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Split ratio must be greater than zero, got {}",
+                ratio
+            )));
+        }
+        let instrument = self.per_symbol(&self.instruments, symbol);
+        let split = SplitAdjustment {
+            ratio,
+            tick: instrument.and_then(|inst| inst.tick_size),
+            price_precision: self.precision_of(symbol).map(|p| p.price),
+            lot_size: instrument.map_or(1, |inst| inst.lot_size),
+        };
+        let non_positive = instrument.is_some_and(|inst| inst.allow_non_positive_prices);
+        let survives = |entry: &OrderEntry| {
+            split.quantity(entry.quantity).is_some_and(|q| q > 0)
+                && (non_positive
+                    || entry.price <= Decimal::ZERO
+                    || split
+                        .price(entry.side, entry.price)
+                        .is_some_and(|p| p > Decimal::ZERO))
+        };
+        let overflow = |what: &str| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Split ratio {} overflows the restated {} of {}",
+                ratio, what, symbol
+            ))
+        };
+        let id = self.symbol_table.get(symbol);
+        let entries = || {
+            let resting = id
+                .and_then(|id| self.symbols.get(&id))
+                .into_iter()
+                .flat_map(|sym_book| sym_book.bids.levels.iter().chain(&sym_book.asks.levels))
+                .flat_map(|level| &level.orders);
+            let held = [&self.auction_orders, &self.stops, &self.halted_orders]
+                .into_iter()
+                .filter_map(move |held| held.get(&id?))
+                .flatten();
+            resting.chain(held)
+        };
+
+        // Check every restated value before changing anything, so a split
+        // that overflows leaves the book as it was.
+        let mut total: i64 = 0;
+        for entry in entries() {
+            let mut restated = entry.clone();
+            restated
+                .split(&split)
+                .ok_or_else(|| overflow(&format!("order {}", entry.id)))?;
+            total = total
+                .checked_add(restated.quantity)
+                .ok_or_else(|| overflow("open quantity"))?;
+        }
+        for ((_, side, price), anchor) in self
+            .level_anchors
             .iter()
-            .rev()
-            .take(depth)
-            .map(|lvl| {
-                let qty: i64 = lvl.orders.iter().map(|o| o.quantity).sum();
-                SnapshotLevel::from_rust(lvl.price, qty)
-            })
+            .filter(|((sym, ..), _)| Some(*sym) == id)
+        {
+            let quantity = split
+                .price(*side, *price)
+                .and(split.quantity(anchor.quantity))
+                .ok_or_else(|| overflow("level anchors"))?;
+            total = total
+                .checked_add(quantity)
+                .ok_or_else(|| overflow("open quantity"))?;
+        }
+        if let Some(&available) = self.locates.as_ref().and_then(|l| l.get(&id?)) {
+            split
+                .quantity(available)
+                .ok_or_else(|| overflow("locates"))?;
+        }
+
+        let doomed: Vec<Uuid> = entries()
+            .filter(|entry| !survives(entry))
+            .map(|entry| entry.id)
             .collect();
+        let mut cancelled = Vec::new();
+        for order_id in doomed {
+            if let Some(order) = self.find_order(order_id) {
+                self.remove_order(order_id)?;
+                cancelled.push(order);
+            }
+        }
 
-        // Asks: sorted descending, best (lowest) at back → iterate reversed
-        let ask_levels: Vec<SnapshotLevel> = sym_book
-            .asks
-            .levels
+        // Rebuild the symbol's book best level first, so orders from the
+        // better of two merging levels keep priority. Synthetic orders of
+        // L2 levels that merge become one.
+        let synthetic: HashSet<Uuid> = self
+            .l2_levels
             .iter()
-            .rev()
-            .take(depth)
-            .map(|lvl| {
-                let qty: i64 = lvl.orders.iter().map(|o| o.quantity).sum();
-                SnapshotLevel::from_rust(lvl.price, qty)
+            .filter(|((sym, ..), _)| Some(*sym) == id)
+            .map(|(_, order_id)| *order_id)
+            .collect();
+        let mut merged = Vec::new();
+        if let Some((id, mut sym_book)) = id.and_then(|id| Some((id, self.symbols.remove(&id)?))) {
+            let mut restated = SymbolBook::default();
+            for side in [Side::BID, Side::ASK] {
+                let levels = std::mem::take(&mut sym_book.side_mut(side.into()).levels);
+                let mut entries: Vec<OrderEntry> = Vec::new();
+                let mut synthetic_at: HashMap<Decimal, usize> = HashMap::new();
+                for level in levels.into_iter().rev() {
+                    self.touch_level(symbol, side, level.price);
+                    for mut entry in level.orders {
+                        entry
+                            .split(&split)
+                            .ok_or_else(|| overflow(&format!("order {}", entry.id)))?;
+                        self.touch_level(symbol, side, entry.price);
+                        if synthetic.contains(&entry.id) {
+                            if let Some(&index) = synthetic_at.get(&entry.price) {
+                                let kept = &mut entries[index];
+                                kept.quantity += entry.quantity;
+                                kept.original_quantity += entry.original_quantity;
+                                kept.shown = kept.quantity;
+                                merged.push(entry.id);
+                                continue;
+                            }
+                            synthetic_at.insert(entry.price, entries.len());
+                        }
+                        if !self.duplicate_ids.contains_key(&entry.id) {
+                            let filled = entry.original_quantity - entry.quantity;
+                            if filled == 0 {
+                                self.fill_ledger.remove(&entry.id);
+                            } else {
+                                self.fill_ledger.insert(entry.id, filled);
+                            }
+                        }
+                        entries.push(entry);
+                    }
+                }
+                restated
+                    .side_mut(side.into())
+                    .insert_batch(entries, matches!(side, Side::BID));
+            }
+            self.symbols.insert(id, restated);
+            let located = self
+                .order_map
+                .values_mut()
+                .chain(self.duplicate_ids.values_mut().flatten());
+            for (symbol, side, price) in located {
+                if *symbol == id {
+                    *price = split
+                        .price(*side, *price)
+                        .ok_or_else(|| overflow("order prices"))?;
+                }
+            }
+        }
+        for order_id in merged {
+            self.unindex(order_id);
+            self.accounts.remove(&order_id);
+        }
+        for held in [
+            &mut self.auction_orders,
+            &mut self.stops,
+            &mut self.halted_orders,
+        ] {
+            for entry in id.and_then(|id| held.get_mut(&id)).into_iter().flatten() {
+                entry
+                    .split(&split)
+                    .ok_or_else(|| overflow(&format!("order {}", entry.id)))?;
+            }
+        }
+        self.l2_levels.retain(|(sym, ..), _| Some(*sym) != id);
+        if let Some((sym, sym_book)) = id.and_then(|id| Some((id, self.symbols.get(&id)?))) {
+            for (side, one_side) in [(Side::BID, &sym_book.bids), (Side::ASK, &sym_book.asks)] {
+                for level in &one_side.levels {
+                    for entry in level.orders.iter().filter(|e| synthetic.contains(&e.id)) {
+                        self.l2_levels.insert((sym, side, level.price), entry.id);
+                    }
+                }
+            }
+        }
+        let anchors: Vec<_> = self
+            .level_anchors
+            .extract_if(|(sym, ..), _| Some(*sym) == id)
+            .collect();
+        for ((sym, side, price), anchor) in anchors {
+            let quantity = split
+                .quantity(anchor.quantity)
+                .ok_or_else(|| overflow("level anchors"))?;
+            let price = split
+                .price(side, price)
+                .ok_or_else(|| overflow("level anchors"))?;
+            self.level_anchors
+                .entry((sym, side, price))
+                .and_modify(|kept| kept.quantity += quantity)
+                .or_insert(LevelAnchor { quantity, ..anchor });
+        }
+        if let Some(available) = id.and_then(|id| self.locates.as_mut()?.get_mut(&id)) {
+            *available = split
+                .quantity(*available)
+                .ok_or_else(|| overflow("locates"))?;
+        }
+        log::info!(
+            target: TARGET,
+            event = "split",
+            symbol = symbol,
+            ratio = ratio.to_string().as_str(),
+            cancelled = cancelled.len();
+            "{} split {} for 1, {} order(s) cancelled", symbol, ratio, cancelled.len()
+        );
+        self.emit(|| BusEvent::Split {
+            symbol: symbol.to_string(),
+            ratio,
+        });
+        Ok(cancelled)
+    }
+
+    /// `apply_symbol_change` without journaling or publishing.
+    fn change_symbol(&mut self, old: &str, new: &str) -> PyResult<()> {
+        let has_orders = self
+            .symbol_book(new)
+            .is_some_and(|b| !b.bids.levels.is_empty() || !b.asks.levels.is_empty())
+            || [&self.auction_orders, &self.stops, &self.halted_orders]
+                .into_iter()
+                .any(|held| {
+                    self.per_symbol(held, new)
+                        .is_some_and(|entries| !entries.is_empty())
+                });
+        let has_spread = self
+            .spreads
+            .iter()
+            .any(|s| [&s.symbol, &s.front, &s.back].contains(&&new.to_string()));
+        let has_instrument = self.per_symbol(&self.instruments, new).is_some();
+        if old == new || has_orders || has_spread || has_instrument {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot rename {} to {}: {} is already in use",
+                old, new, new
+            )));
+        }
+        // State keyed by the symbol's id follows the rename; what was kept
+        // for `new` is dropped.
+        let id = self.symbol_table.intern(old);
+        let levels: Vec<(Side, Decimal)> = self
+            .symbols
+            .get(&id)
+            .into_iter()
+            .flat_map(|sym_book| {
+                let bids = sym_book.bids.levels.iter().map(|l| (Side::BID, l.price));
+                bids.chain(sym_book.asks.levels.iter().map(|l| (Side::ASK, l.price)))
             })
             .collect();
+        for (side, price) in levels {
+            self.touch_level(old, side, price);
+            self.touch_level(new, side, price);
+        }
+        if let Some(displaced) = self.symbol_table.rename(id, new) {
+            self.symbols.remove(&displaced);
+            self.trading_states.remove(&displaced);
+            self.auction_orders.remove(&displaced);
+            self.halted_orders.remove(&displaced);
+            self.stops.remove(&displaced);
+            self.sessions.remove(&displaced);
+            self.counters.remove(&displaced);
+            self.symbol_precision.remove(&displaced);
+            if let Some(locates) = self.locates.as_mut() {
+                locates.remove(&displaced);
+            }
+            self.schedules.remove(&displaced);
+            self.feed_sequences.remove(&displaced);
+            self.features.remove(&displaced);
+            self.flows.remove(&displaced);
+            self.qualities.remove(&displaced);
+            self.profiles.remove(&displaced);
+            self.auction_cutoffs.retain(|(sym, _), _| *sym != displaced);
+            self.l2_levels.retain(|(sym, ..), _| *sym != displaced);
+            self.level_anchors.retain(|(sym, ..), _| *sym != displaced);
+            self.quotes.retain(|(sym, _), _| *sym != displaced);
+        }
+        if let Some(instrument) = self.instruments.get_mut(&id) {
+            instrument.symbol = new.to_string();
+        }
+        self.settlements.rename_symbol(old, new);
+        self.cash_flows.rename_symbol(old, new);
+        for spread in &mut self.spreads {
+            for leg in [&mut spread.symbol, &mut spread.front, &mut spread.back] {
+                if leg == old {
+                    *leg = new.to_string();
+                }
+            }
+        }
+        for order in self.throttles.queue.iter_mut().filter(|o| o.symbol == old) {
+            order.symbol = new.to_string();
+        }
+        log::info!(
+            target: TARGET,
+            event = "symbol_changed",
+            symbol = old,
+            new_symbol = new;
+            "{} renamed to {}", old, new
+        );
+        self.emit(|| BusEvent::SymbolChanged {
+            symbol: old.to_string(),
+            new_symbol: new.to_string(),
+        });
+        Ok(())
+    }
 
-        let best_bid = bid_levels.first().map(|l| l.price);
-        let best_ask = ask_levels.first().map(|l| l.price);
+    /// Lift a halt and release the queued orders: their blotters, and the
+    /// opening auction's result when reopening through one.
+    fn resume_symbol(
+        &mut self,
+        symbol: &str,
+        reopen_auction: bool,
+        reference: Option<Decimal>,
+    ) -> PyResult<(Vec<TradeBlotter>, Option<AuctionResult>)> {
+        if self.state_of(symbol) != TradingState::HALTED {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{} is not halted",
+                symbol
+            )));
+        }
+        let queued = self
+            .symbol_table
+            .get(symbol)
+            .and_then(|id| self.halted_orders.remove(&id))
+            .unwrap_or_default();
+        let next = if reopen_auction {
+            TradingState::PRE_OPEN
+        } else {
+            TradingState::CONTINUOUS
+        };
+        self.change_state(symbol, next);
+        let blotters = queued
+            .iter()
+            .map(|entry| self.route_accepted(&self.order_of(entry)))
+            .collect::<PyResult<Vec<TradeBlotter>>>()?;
+        let auction = match reopen_auction {
+            true => Some(self.run_auction(symbol, AuctionKind::Open, reference)?),
+            false => None,
+        };
+        Ok((blotters, auction))
+    }
 
-        let spread = match (best_bid, best_ask) {
-            (Some(b), Some(a)) => Some(a - b),
-            _ => None,
+    /// Book fills in `symbol`, as (buyer id, seller id, price, quantity),
+    /// to the settlement totals of the orders' accounts. Every order but
+    /// the `aggressor` provided liquidity.
+    fn record_settlement(
+        &mut self,
+        symbol: &str,
+        fills: impl IntoIterator<Item = (Uuid, Uuid, Decimal, i64)>,
+        aggressor: Option<Uuid>,
+    ) {
+        let multiplier = self.multiplier(symbol);
+        for (buyer, seller, price, quantity) in fills {
+            let notional = price * Decimal::from(quantity) * multiplier;
+            for (order_id, side) in [(buyer, Side::BID), (seller, Side::ASK)] {
+                let account = self.accounts.get(&order_id).map(String::as_str);
+                let maker = aggressor != Some(order_id);
+                self.settlements
+                    .record(account, symbol, side, quantity, notional, maker);
+            }
+        }
+    }
+
+    /// Fold executions in `symbol` into its session statistics.
+    fn record_session(&mut self, symbol: &str, trades: &[Trade]) {
+        let multiplier = self.multiplier(symbol);
+        let precision = self.precision_of(symbol);
+        let id = self.symbol_table.intern(symbol);
+        for trade in trades {
+            let (price, quantity) = (trade.fill_price, trade.fill_quantity);
+            log::info!(
+                target: TARGET,
+                event = "trade",
+                symbol = symbol,
+                trade_id:% = trade.trade_id,
+                incoming_order_id:% = trade.incoming_order_id,
+                standing_order_id:% = trade.standing_order_id,
+                price:% = price,
+                quantity = quantity;
+                "{} traded {} at {}", symbol, quantity, price
+            );
+            let counters = self.counters.entry(id).or_default();
+            counters.trades += 1;
+            counters.volume += quantity;
+            self.sessions
+                .entry(id)
+                .and_modify(|stats| stats.record(price, quantity, multiplier))
+                .or_insert_with(|| SessionStats::new(price, quantity, multiplier))
+                .precision = precision;
+        }
+    }
+
+    /// Apply `update` to the order history log, if recording.
+    fn record_history(&mut self, update: impl FnOnce(&mut OrderHistories, Option<i64>)) {
+        if let Some(history) = self.order_history.as_mut() {
+            update(history, self.current_time);
+        }
+    }
+
+    /// Best resting price and its aggregate quantity on one side of a symbol.
+    pub(crate) fn best_level(&self, symbol: &str, side: Side) -> Option<(Decimal, i64)> {
+        let sym_book = self.symbol_book(symbol)?;
+        let one_side = match side {
+            Side::BID => &sym_book.bids,
+            Side::ASK => &sym_book.asks,
         };
+        // Best level sits at the back for both sides; levels holding only
+        // hidden quantity are not shown.
+        one_side
+            .levels
+            .iter()
+            .rev()
+            .map(|lvl| (lvl.price, lvl.displayed_quantity()))
+            .find(|&(_, quantity)| quantity > 0)
+    }
 
-        let midpoint = match (best_bid, best_ask) {
-            (Some(b), Some(a)) => Some((a + b) / Decimal::from(2)),
-            _ => None,
+    /// Price a price band compares against when given none: the last
+    /// trade this session, else the midpoint.
+    fn market_reference(&self, symbol: &str) -> Option<Decimal> {
+        self.per_symbol(&self.sessions, symbol)
+            .map(|stats| stats.last)
+            .or_else(|| self.midpoint(symbol))
+    }
+
+    /// Midpoint of the best bid and offer, or None while either side is
+    /// empty.
+    pub(crate) fn midpoint(&self, symbol: &str) -> Option<Decimal> {
+        let (bid, _) = self.best_level(symbol, Side::BID)?;
+        let (ask, _) = self.best_level(symbol, Side::ASK)?;
+        Some((bid + ask) / Decimal::TWO)
+    }
+
+    /// Rest an accepted order without matching: designated orders are held
+    /// for their auction, stops and conditional orders until triggered,
+    /// everything else joins the book.
+    fn rest_order(&mut self, order: &Order) {
+        if !order.is_triggered() && order.auction.is_none() {
+            self.enqueue_internal(order);
+            return;
+        }
+        let mut entry = self.entry_of(order);
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+        let held = if order.is_triggered() {
+            &mut self.stops
+        } else {
+            &mut self.auction_orders
         };
+        held.entry(entry.symbol).or_default().push(entry);
+        if let Some(expire_time) = order.expire_time {
+            self.expiries.insert((expire_time, order.id));
+        }
+    }
 
-        let bid_vwap = compute_vwap(&bid_levels);
-        let ask_vwap = compute_vwap(&ask_levels);
+    /// Queue an accepted order on a halted symbol until it resumes.
+    fn hold_for_resume(&mut self, order: &Order) {
+        let mut entry = self.entry_of(order);
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+        self.halted_orders
+            .entry(entry.symbol)
+            .or_default()
+            .push(entry);
+        if let Some(expire_time) = order.expire_time {
+            self.expiries.insert((expire_time, order.id));
+        }
+    }
 
-        Some(Snapshot {
-            bids: bid_levels,
-            asks: ask_levels,
-            spread,
-            midpoint,
-            bid_vwap,
-            ask_vwap,
-        })
+    /// Market price a stop on `side` with trigger `trigger` compares with
+    /// its stop price now, if the quote provides one.
+    fn stop_reference(&self, symbol: &str, side: Side, trigger: StopTrigger) -> Option<Decimal> {
+        match trigger {
+            StopTrigger::LastTrade => None,
+            StopTrigger::Bbo => self
+                .best_level(symbol, side.other())
+                .map(|(price, _)| price),
+            StopTrigger::Midpoint => self.midpoint(symbol),
+        }
     }
-}
 
-fn compute_vwap(levels: &[SnapshotLevel]) -> Option<Decimal> {
-    let mut sum_pq = Decimal::ZERO;
-    let mut sum_q: i64 = 0;
-    for lvl in levels {
-        sum_pq += lvl.price * Decimal::from(lvl.quantity);
-        sum_q += lvl.quantity;
+    /// Remove and return the stops on `symbol` triggered by a trade at
+    /// `price` (with `Some`) or by the current quote (with `None`), each
+    /// with the price that triggered it, in arrival order. Conditional
+    /// orders are checked against the quote only, and report the opposite
+    /// best price (else their own) as their trigger.
+    fn take_triggered_stops(
+        &mut self,
+        symbol: &str,
+        price: Option<Decimal>,
+    ) -> Vec<(OrderEntry, Decimal)> {
+        if price.is_none() && self.state_of(symbol) != TradingState::CONTINUOUS {
+            return Vec::new();
+        }
+        let Some(id) = self.symbol_table.get(symbol) else {
+            return Vec::new();
+        };
+        let Some(mut pending) = self.stops.remove(&id) else {
+            return Vec::new();
+        };
+        let quote = match price {
+            Some(_) => Quote::default(),
+            None => Quote {
+                bid: self.best_level(symbol, Side::BID),
+                ask: self.best_level(symbol, Side::ASK),
+            },
+        };
+        let mut triggered = Vec::new();
+        pending.retain(|entry| {
+            if let Some(condition) = &entry.condition {
+                let hit = price.is_none() && condition.holds(entry.side, &quote);
+                if hit {
+                    let opposite = match entry.side {
+                        Side::BID => quote.ask,
+                        Side::ASK => quote.bid,
+                    };
+                    let reference = opposite.map_or(entry.price, |(price, _)| price);
+                    triggered.push((entry.clone(), reference));
+                }
+                return !hit;
+            }
+            let reference = match price {
+                Some(_) if entry.stop_trigger != StopTrigger::LastTrade => None,
+                Some(price) => Some(price),
+                None => self.stop_reference(symbol, entry.side, entry.stop_trigger),
+            };
+            let hit = reference.filter(|&reference| {
+                entry
+                    .stop_price
+                    .is_some_and(|stop| stop_is_triggered(entry.side, stop, reference))
+            });
+            if let Some(reference) = hit {
+                triggered.push((entry.clone(), reference));
+            }
+            hit.is_none()
+        });
+        self.stops.insert(id, pending);
+        triggered
+    }
+
+    /// Activate stops triggered by `trades`, then by the activated stops'
+    /// own executions, until the book is quiet. Stops triggered by the
+    /// quote are checked each time the trades so far have been worked
+    /// through.
+    ///
+    /// Activated stops match as limit orders at their price and any
+    /// remainder rests in the book.
+    fn run_stop_cascade(&mut self, symbol: &str, trades: &[Trade]) -> CascadeReport {
+        let mut report = CascadeReport::default();
+        let mut pending: VecDeque<(Trade, u32)> = trades.iter().map(|t| (t.clone(), 0)).collect();
+        // Generation of the latest change to the book.
+        let mut latest = 0;
+        loop {
+            while let Some((trigger, generation)) = pending.pop_front() {
+                for (entry, _) in self.take_triggered_stops(symbol, Some(trigger.fill_price)) {
+                    let activation = self.activate_stop(symbol, entry, generation + 1);
+                    pending.extend(
+                        activation
+                            .trades
+                            .iter()
+                            .map(|t| (t.clone(), generation + 1)),
+                    );
+                    latest = latest.max(generation + 1);
+                    report.activations.push(StopActivation {
+                        trigger_trade_id: Some(trigger.trade_id),
+                        trigger_price: trigger.fill_price,
+                        ..activation
+                    });
+                }
+            }
+            let triggered = self.take_triggered_stops(symbol, None);
+            if triggered.is_empty() {
+                return report;
+            }
+            latest += 1;
+            for (entry, reference) in triggered {
+                let activation = self.activate_stop(symbol, entry, latest);
+                pending.extend(activation.trades.iter().map(|t| (t.clone(), latest)));
+                report.activations.push(StopActivation {
+                    trigger_price: reference,
+                    ..activation
+                });
+            }
+        }
+    }
+
+    /// Match a triggered stop as a limit order at its price, resting any
+    /// remainder; the activation has no trigger filled in yet.
+    fn activate_stop(
+        &mut self,
+        symbol: &str,
+        mut entry: OrderEntry,
+        generation: u32,
+    ) -> StopActivation {
+        let mut result =
+            self.match_inner(entry.id, entry.price, entry.side, entry.quantity, symbol);
+        entry.quantity = result.remaining_qty;
+        for t in &result.trades {
+            entry.fills.record(t.fill_price, t.fill_quantity);
+        }
+        let order = self.order_of(&entry);
+        if result.remaining_qty > 0 {
+            self.enqueue_internal(&order);
+        } else {
+            self.accounts.remove(&entry.id);
+        }
+        self.enrichers.apply(&mut result.trades, false);
+        self.publish_trades(symbol, &result.trades);
+        StopActivation {
+            order,
+            generation,
+            trigger_trade_id: None,
+            trigger_price: entry.price,
+            trades: result.trades,
+        }
     }
-    if sum_q == 0 {
-        None
-    } else {
-        Some(sum_pq / Decimal::from(sum_q))
+
+    fn take_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
     }
-}
 
-impl Book {
     fn enqueue_internal(&mut self, order: &Order) {
-        let entry = OrderEntry::from_order(order);
+        let entry = self.register_resting(order);
         let ascending = matches!(order.side, Side::BID);
-        let sym_book = self.symbols.entry(order.symbol.clone()).or_default();
+        let lookup = self.profiler.as_ref().and_then(Profiler::mark);
+        let sym_book = self.symbols.entry(entry.symbol).or_default();
         let one_side = if ascending {
             &mut sym_book.bids
         } else {
             &mut sym_book.asks
         };
         one_side.insert(entry, ascending);
-        self.order_map
-            .insert(order.id, (order.symbol.clone(), order.side, order.price));
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.add(Phase::LevelLookup, lookup);
+        }
+        self.evict_over_capacity(&order.symbol, order.side);
+    }
+
+    /// Rest plain limit orders in one pass per side, in arrival order
+    /// within each level.
+    fn enqueue_batch(&mut self, orders: &[&Order]) {
+        let mut by_side: HashMap<(SymbolId, Side), Vec<OrderEntry>> = HashMap::new();
+        for order in orders {
+            let entry = self.register_resting(order);
+            by_side
+                .entry((entry.symbol, order.side))
+                .or_default()
+                .push(entry);
+        }
+        let mut sides = Vec::with_capacity(by_side.len());
+        for ((symbol, side), entries) in by_side {
+            let ascending = matches!(side, Side::BID);
+            let sym_book = self.symbols.entry(symbol).or_default();
+            let one_side = if ascending {
+                &mut sym_book.bids
+            } else {
+                &mut sym_book.asks
+            };
+            one_side.insert_batch(entries, ascending);
+            sides.push((symbol, side));
+        }
+        for (symbol, side) in sides {
+            let symbol = self.symbol_table.name(symbol).to_string();
+            self.evict_over_capacity(&symbol, side);
+        }
+    }
+
+    /// Resting orders in the book, duplicates of an id included.
+    fn resting_count(&self) -> usize {
+        let duplicates: usize = self.duplicate_ids.values().map(VecDeque::len).sum();
+        self.order_map.len() + duplicates
+    }
+
+    /// Resting orders and levels around `symbol`'s `side`, counting an
+    /// order about to rest at `arriving` if given.
+    fn occupancy(&self, symbol: &str, side: Side, arriving: Option<Decimal>) -> Occupancy {
+        let sym_book = self.symbol_book(symbol);
+        let symbol_orders: usize = sym_book.map_or(0, |sym_book| {
+            [&sym_book.bids, &sym_book.asks]
+                .iter()
+                .flat_map(|one_side| &one_side.levels)
+                .map(|level| level.orders.len())
+                .sum()
+        });
+        let levels = sym_book.map_or(&[][..], |sym_book| &sym_book.side(side.into()).levels);
+        let new_level =
+            arriving.is_some_and(|price| !levels.iter().any(|level| level.price == price));
+        let arriving = usize::from(arriving.is_some());
+        Occupancy {
+            symbol_orders: symbol_orders + arriving,
+            side_levels: levels.len() + usize::from(new_level),
+            total_orders: self.resting_count() + arriving,
+        }
+    }
+
+    /// Refuse an order that would rest past a capacity limit, under the
+    /// "reject" action.
+    fn check_capacity(&mut self, order: &Order) -> PyResult<()> {
+        if order.is_triggered() || order.auction.is_some() {
+            return Ok(());
+        }
+        let Some(limits) = self.capacity.as_ref() else {
+            return Ok(());
+        };
+        if limits.action != CapacityAction::Reject {
+            return Ok(());
+        }
+        let occupancy = self.occupancy(&order.symbol, order.side, Some(order.price));
+        let Some(cap) = limits.exceeded(occupancy) else {
+            return Ok(());
+        };
+        if let Some(limits) = self.capacity.as_mut() {
+            limits.rejected += 1;
+        }
+        Err(RejectCode::Capacity.err(format!(
+            "Order {} would exceed the capacity limit of {}",
+            order.id, cap
+        )))
+    }
+
+    /// Under the "evict" capacity action, cancel the worst-priced orders on
+    /// `symbol`'s `side`, latest arrival first, until the caps hold.
+    fn evict_over_capacity(&mut self, symbol: &str, side: Side) {
+        while let Some(limits) = self.capacity.as_ref() {
+            if limits.action != CapacityAction::Evict {
+                return;
+            }
+            let Some(cap) = limits.exceeded(self.occupancy(symbol, side, None)) else {
+                return;
+            };
+            let victim = self
+                .symbol_book(symbol)
+                .and_then(|sym_book| sym_book.side(side.into()).levels.first())
+                .and_then(|level| level.orders.last())
+                .map(|entry| entry.id);
+            let Some(victim) = victim else {
+                return;
+            };
+            if !matches!(self.remove_order(victim), Ok(true)) {
+                return;
+            }
+            log::warn!(
+                target: TARGET,
+                event = "evicted",
+                order_id:% = victim,
+                symbol = symbol;
+                "order {} evicted to keep the book within {}", victim, cap
+            );
+            if let Some(limits) = self.capacity.as_mut() {
+                limits.evicted += 1;
+            }
+        }
+    }
+
+    /// Sequence and index an order about to rest, returning its entry.
+    fn register_resting(&mut self, order: &Order) -> OrderEntry {
+        self.touch_level(&order.symbol, order.side, order.price);
+        let id = self.symbol_table.intern(&order.symbol);
+        let pool = &mut self
+            .symbols
+            .entry(id)
+            .or_default()
+            .side_mut(order.side.into())
+            .pool;
+        let mut entry = match pool.take_entry() {
+            Some(mut spare) => {
+                spare.assign(order, id);
+                spare
+            }
+            None => OrderEntry::new(order, id),
+        };
+        entry.seq = self.take_seq();
+        let located = (id, order.side, order.price);
+        match self.order_map.entry(order.id) {
+            std::collections::hash_map::Entry::Occupied(_) => {
+                let duplicates = self.duplicate_ids.entry(order.id).or_default();
+                duplicates.push_back(located);
+            }
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                slot.insert(located);
+            }
+        }
+        let filled = order.original_quantity - order.quantity;
+        if filled != 0 {
+            self.fill_ledger.insert(order.id, filled);
+        }
+        if let Some(expire_time) = order.expire_time {
+            self.expiries.insert((expire_time, order.id));
+        }
+        entry
     }
 
     /// Core matching logic — pure Rust, no Python objects involved.
@@ -690,88 +7696,406 @@ impl Book {
         incoming_qty: i64,
         symbol: &str,
     ) -> MatchResult {
-        let mut trades: Vec<Trade> = Vec::new();
-        let mut remaining_qty = incoming_qty;
-
-        let sym_book = self.symbols.entry(symbol.to_string()).or_default();
-
+        let policy = self.fill_price_policy;
+        let precision = self.precision_of(symbol);
+        let lookup = self.profiler.as_ref().and_then(Profiler::mark);
+        let id = self.symbol_table.intern(symbol);
+        let last_sale = self.sessions.get(&id).map(|stats| stats.last);
+        let sym_book = self.symbols.entry(id).or_default();
         let opposite = match incoming_side {
             Side::BID => &mut sym_book.asks,
             Side::ASK => &mut sym_book.bids,
         };
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.add(Phase::LevelLookup, lookup);
+        }
+        let matching = self.profiler.as_ref().and_then(Profiler::mark);
+        let result = opposite.match_incoming_with(
+            incoming_id,
+            incoming_side.into(),
+            incoming_price,
+            incoming_qty,
+            |standing_price| {
+                let price =
+                    policy.fill_price(incoming_side, incoming_price, standing_price, last_sale);
+                precision.map_or(price, |p| p.round_price(price))
+            },
+        );
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.add(Phase::Matching, matching);
+        }
 
-        while remaining_qty > 0 && !opposite.levels.is_empty() {
-            let standing_price = opposite.levels.last().unwrap().price;
-
-            if !incoming_side.price_is_matchable(incoming_price, standing_price) {
-                break;
+        self.record_fills(
+            result
+                .trades
+                .iter()
+                .map(|t| (t.standing_order_id, t.fill_quantity)),
+        );
+        for &order_id in &result.filled {
+            self.unindex(order_id);
+        }
+        self.record_history(|history, now| {
+            for t in &result.trades {
+                for id in [t.incoming_order_id, t.standing_order_id] {
+                    history.fill(id, now, t.trade_id, t.fill_price, t.fill_quantity);
+                }
             }
+        });
+        let trades: Vec<Trade> = result
+            .trades
+            .into_iter()
+            .map(|t| Trade::from(t).with_symbol(symbol))
+            .collect();
+        self.record_session(symbol, &trades);
+        self.record_settlement(
+            symbol,
+            trades.iter().map(|t| {
+                let (buyer, seller) = match incoming_side {
+                    Side::BID => (t.incoming_order_id, t.standing_order_id),
+                    Side::ASK => (t.standing_order_id, t.incoming_order_id),
+                };
+                (buyer, seller, t.fill_price, t.fill_quantity)
+            }),
+            Some(incoming_id),
+        );
+        for order_id in &result.filled {
+            self.accounts.remove(order_id);
+        }
+        for trade in &trades {
+            self.touch_level(symbol, incoming_side.other(), trade.standing_price);
+        }
 
-            let last_idx = opposite.levels.len() - 1;
-            let level = &mut opposite.levels[last_idx];
-
-            while remaining_qty > 0 && !level.orders.is_empty() {
-                let standing = level.orders.front_mut().unwrap();
-                let matched_qty = remaining_qty.min(standing.quantity);
-
-                standing.quantity -= matched_qty;
-                remaining_qty -= matched_qty;
+        MatchResult {
+            trades,
+            remaining_qty: result.remaining_quantity,
+        }
+    }
 
-                let fill_price = incoming_side.calc_fill_price(incoming_price, standing.price);
+    /// Match a single incoming order, enqueue remainder, return TradeBlotter.
+    pub(crate) fn match_single(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
+        self.journaled(
+            || JournalEntry::Match(incoming.clone()),
+            |book| book.process_order(incoming),
+        )
+    }
 
-                trades.push(Trade::from_rust(
-                    incoming_id,
-                    standing.id,
-                    matched_qty,
-                    fill_price,
-                ));
+    /// `match_single` without journaling.
+    fn process_order(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.begin_order();
+        }
+        let result = self.admit_order(incoming);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.end_order();
+        }
+        self.observe_flow(&incoming.symbol);
+        self.count_outcome(incoming, &result);
+        result
+    }
 
-                if standing.quantity == 0 {
-                    let filled = level.orders.pop_front().unwrap();
-                    self.order_map.remove(&filled.id);
+    /// Count `incoming` as an order or a rejection of its symbol, logging
+    /// why it was rejected.
+    fn count_outcome(&mut self, incoming: &Order, result: &PyResult<TradeBlotter>) {
+        let id = self.symbol_table.intern(&incoming.symbol);
+        let counters = self.counters.entry(id).or_default();
+        match result {
+            Ok(_) => counters.orders += 1,
+            Err(err) => {
+                counters.rejected += 1;
+                if log::log_enabled!(target: TARGET, log::Level::Warn) {
+                    let (code, reason) = Python::with_gil(|py| {
+                        (crate::reject::code_of(py, err), err.value(py).to_string())
+                    });
+                    log::warn!(
+                        target: TARGET,
+                        event = "rejected",
+                        order_id:% = incoming.id,
+                        symbol = incoming.symbol.as_str(),
+                        code = code.as_deref().unwrap_or("error");
+                        "order {} rejected: {}", incoming.id, reason
+                    );
                 }
             }
+        }
+    }
 
-            if opposite
-                .levels
-                .last()
-                .map_or(false, |l| l.orders.is_empty())
+    /// `process_order` without counting the outcome.
+    fn admit_order(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
+        if !self.throttles.is_empty() {
+            self.validate_order(incoming)?;
+            match self
+                .throttles
+                .admit(incoming, self.current_time.unwrap_or(0))
             {
-                opposite.levels.pop();
+                Admission::Send => {}
+                Admission::Queue => return Ok(self.blotter(incoming.clone(), Vec::new())),
+                Admission::Reject(scope, rate) => {
+                    return Err(RejectCode::Throttled.err(format!(
+                        "Order {} throttled: {} is limited to {} messages per {} clock units",
+                        incoming.id,
+                        scope.map_or("the book".to_string(), |s| format!("session {}", s)),
+                        rate.limit,
+                        rate.window
+                    )));
+                }
             }
         }
+        self.execute_order(incoming)
+    }
 
-        MatchResult {
-            trades,
-            remaining_qty,
+    /// `process_order` past the rate limits.
+    fn execute_order(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
+        let received = self.latency.as_ref().map(LatencyRecorder::now);
+        let validation = self.profiler.as_ref().and_then(Profiler::mark);
+        self.accept_order(incoming)?;
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.add(Phase::Validation, validation);
+        }
+        let risk_checked = self.latency.as_ref().map(LatencyRecorder::now);
+        let mut blotter = if self.state_of(&incoming.symbol) == TradingState::HALTED {
+            self.hold_for_resume(incoming);
+            self.blotter(incoming.clone(), Vec::new())
+        } else {
+            self.route_accepted(incoming)?
+        };
+        if let (Some(latency), Some(received), Some(risk_checked)) =
+            (self.latency.as_mut(), received, risk_checked)
+        {
+            let timestamps = OrderTimestamps {
+                received,
+                risk_checked,
+                matched: latency.matched.take().unwrap_or(risk_checked),
+                acked: latency.now(),
+            };
+            latency.record(timestamps);
+            blotter.timestamps = Some(timestamps);
         }
+        Ok(blotter)
     }
 
-    /// Match a single incoming order, enqueue remainder, return TradeBlotter.
-    fn match_single(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
+    /// Match, rest or hold an order that already passed `accept_order`.
+    fn route_accepted(&mut self, incoming: &Order) -> PyResult<TradeBlotter> {
         let symbol = incoming.symbol.clone();
+
+        // Stops and conditional orders wait for their trigger; designated
+        // orders and pre-open interest wait for the uncross.
+        if incoming.is_triggered()
+            || incoming.auction.is_some()
+            || self.state_of(&symbol) == TradingState::PRE_OPEN
+        {
+            self.rest_order(incoming);
+            let mut blotter = self.blotter(incoming.clone(), Vec::new());
+            // A stop on the quote may already be through its price, and a
+            // condition may already hold.
+            let on_quote =
+                incoming.stop_price.is_some() && incoming.stop_trigger != StopTrigger::LastTrade;
+            if on_quote || incoming.condition.is_some() {
+                blotter.cascade = self.run_stop_cascade(&symbol, &[]);
+            }
+            return Ok(blotter);
+        }
+
         let incoming_side = incoming.side;
         let incoming_price = incoming.price;
 
-        let result = self.match_inner(
+        let (plan, mut prevented) = self.prevent_self_trades(incoming)?;
+        let mut result = self.match_inner(
             incoming.id,
             incoming_price,
             incoming_side,
-            incoming.quantity,
+            plan.match_quantity,
             &symbol,
         );
+        result.remaining_qty += incoming.quantity - plan.match_quantity;
+        if plan.cancel_incoming {
+            let mut rest = incoming.clone();
+            rest.quantity = result.remaining_qty;
+            self.cancel_unrested(&rest);
+            prevented.push(rest);
+            result.remaining_qty = 0;
+        }
+        if result.remaining_qty > 0 && self.spreads.iter().any(|s| s.involves(&symbol)) {
+            let implied = self.match_implied(
+                incoming.id,
+                incoming_price,
+                incoming_side,
+                result.remaining_qty,
+                &symbol,
+            );
+            result.trades.extend(implied.trades);
+            result.remaining_qty = implied.remaining_qty;
+        }
+        if let Some(latency) = self.latency.as_mut() {
+            latency.matched = Some(latency.now());
+        }
+
+        let mut result_order = incoming.clone();
+        result_order.quantity = result.remaining_qty;
+        for t in &result.trades {
+            result_order.fills.record(t.fill_price, t.fill_quantity);
+        }
 
         // Enqueue remainder
         if result.remaining_qty > 0 {
-            let mut remainder = incoming.clone();
-            remainder.quantity = result.remaining_qty;
-            self.enqueue_internal(&remainder);
+            self.enqueue_internal(&result_order);
+        } else {
+            self.accounts.remove(&incoming.id);
         }
 
-        let mut result_order = incoming.clone();
-        result_order.quantity = result.remaining_qty;
+        self.enrichers.apply(&mut result.trades, false);
+        self.publish_trades(&symbol, &result.trades);
+        let cascade = self.run_stop_cascade(&symbol, &result.trades);
+        let mut blotter = self.blotter(result_order, result.trades);
+        blotter.cascade = cascade;
+        blotter.prevented = prevented;
+        Ok(blotter)
+    }
+
+    /// Prevention key of `account`: its anti-internalization group, else
+    /// the account itself.
+    fn prevention_key<'a>(&'a self, account: &'a str) -> &'a str {
+        self.internalization_groups
+            .get(account)
+            .map_or(account, String::as_str)
+    }
+
+    /// Plan how `incoming` matches under self-trade prevention, cancelling
+    /// the resting orders the plan says to. Returns the plan and the
+    /// cancelled orders.
+    fn prevent_self_trades(&mut self, incoming: &Order) -> PyResult<(PreventionPlan, Vec<Order>)> {
+        let unrestricted = PreventionPlan {
+            match_quantity: incoming.quantity,
+            cancel_resting: Vec::new(),
+            cancel_incoming: false,
+        };
+        let (Some(mode), Some(account), Some(sym_book)) = (
+            self.self_trade_prevention,
+            incoming.account.as_deref(),
+            self.symbol_book(&incoming.symbol),
+        ) else {
+            return Ok((unrestricted, Vec::new()));
+        };
+        let opposite = match incoming.side {
+            Side::BID => &sym_book.asks,
+            Side::ASK => &sym_book.bids,
+        };
+        let standing = opposite
+            .levels
+            .iter()
+            .rev()
+            .take_while(|level| {
+                incoming
+                    .side
+                    .price_is_matchable(incoming.price, level.price)
+            })
+            .flat_map(|level| &level.orders)
+            .map(|entry| {
+                let key = entry.account.as_deref().map(|a| self.prevention_key(a));
+                (entry.id, key, entry.quantity)
+            });
+        let plan = stp::plan(
+            mode,
+            self.prevention_key(account),
+            incoming.quantity,
+            standing,
+        );
+        let mut prevented = Vec::new();
+        for &order_id in &plan.cancel_resting {
+            if let Some(order) = self.find_order(order_id) {
+                self.remove_order(order_id)?;
+                prevented.push(order);
+            }
+        }
+        Ok((plan, prevented))
+    }
+
+    /// Cancel what is left of an order that never rested.
+    fn cancel_unrested(&mut self, order: &Order) {
+        self.accounts.remove(&order.id);
+        let entry = self.entry_of(order);
+        self.restore_locate(&entry);
+        self.record_history(|history, now| {
+            history.close(order.id, now, OrderEventKind::Cancelled, order.price)
+        });
+        self.emit(|| BusEvent::OrderCancelled {
+            symbol: order.symbol.clone(),
+            order_id: order.id,
+            remaining: order.quantity,
+        });
+    }
+
+    /// Best implied level an order for `symbol` on `side` could trade
+    /// against, over every spread `symbol` belongs to. Legs not in
+    /// continuous trading imply nothing.
+    fn best_implied(&self, symbol: &str, side: Side) -> Option<ImpliedLevel<'_>> {
+        self.spreads
+            .iter()
+            .filter_map(|spread| {
+                let legs = spread.implied_legs(symbol, side)?;
+                let mut quotes = [(Decimal::ZERO, 0); 2];
+                for (quote, leg) in quotes.iter_mut().zip(&legs) {
+                    if self.state_of(leg.symbol) != TradingState::CONTINUOUS {
+                        return None;
+                    }
+                    *quote = self.best_level(leg.symbol, leg.side.other())?;
+                }
+                Some(ImpliedLevel::from_quotes(legs, quotes))
+            })
+            .reduce(|best, level| match side {
+                Side::BID if level.price < best.price => level,
+                Side::ASK if level.price > best.price => level,
+                _ => best,
+            })
+    }
 
-        Ok(TradeBlotter::from_rust(result_order, result.trades))
+    /// Fill up to `quantity` of an incoming order against implied
+    /// liquidity, trading each leg book at its best price.
+    fn match_implied(
+        &mut self,
+        incoming_id: Uuid,
+        incoming_price: Decimal,
+        incoming_side: Side,
+        quantity: i64,
+        symbol: &str,
+    ) -> MatchResult {
+        let mut trades = Vec::new();
+        let mut remaining = quantity;
+        while remaining > 0 {
+            let Some(level) = self
+                .best_implied(symbol, incoming_side)
+                .filter(|level| incoming_side.price_is_matchable(incoming_price, level.price))
+            else {
+                break;
+            };
+            let fill = remaining.min(level.quantity);
+            let price = level.price;
+            let legs: Vec<(String, Side, Decimal)> = level
+                .legs
+                .iter()
+                .map(|(leg, price)| (leg.symbol.to_string(), leg.side, *price))
+                .collect();
+            let mut first_leg = None;
+            for (leg_symbol, leg_side, leg_price) in legs {
+                let mut leg = self.match_inner(incoming_id, leg_price, leg_side, fill, &leg_symbol);
+                self.enrichers.apply(&mut leg.trades, false);
+                self.publish_trades(&leg_symbol, &leg.trades);
+                first_leg = first_leg.or(leg.trades.into_iter().next());
+            }
+            let Some(first) = first_leg else {
+                break;
+            };
+            trades.push(
+                Trade::from_rust(incoming_id, first.standing_order_id, fill, price)
+                    .with_symbol(symbol)
+                    .with_standing(price, first.standing_remaining),
+            );
+            remaining -= fill;
+        }
+        self.record_session(symbol, &trades);
+        MatchResult {
+            trades,
+            remaining_qty: remaining,
+        }
     }
 }
 
@@ -803,12 +8127,259 @@ mod tests {
         assert!(!book.order_map.contains_key(&standing_ask.id));
         assert!(book.order_map.contains_key(&incoming_bid.id));
 
-        let symbol_book = book.symbols.get("AAPL").unwrap();
+        let symbol_book = book.symbol_book("AAPL").unwrap();
         assert_eq!(symbol_book.asks.levels.len(), 0);
         assert_eq!(symbol_book.bids.levels.len(), 1);
         assert_eq!(symbol_book.bids.levels[0].orders[0].quantity, 2);
     }
 
+    #[test]
+    fn matching_is_sign_agnostic_for_opted_in_instruments() {
+        let mut book = Book::new();
+        let spread = Order::try_new(Side::BID, "CL-SPR".to_string(), -0.5, 1).unwrap();
+        assert!(book.match_single(&spread).is_err());
+
+        let mut inst = Instrument::try_new(
+            "CL-SPR".to_string(),
+            None,
+            1,
+            "USD".to_string(),
+            Decimal::ONE,
+            vec![],
+        )
+        .unwrap();
+        inst.allow_non_positive_prices = true;
+        book.register_instrument(inst);
+
+        let ask_neg = Order::try_new(Side::ASK, "CL-SPR".to_string(), -0.25, 3).unwrap();
+        let ask_zero = Order::try_new(Side::ASK, "CL-SPR".to_string(), 0.0, 3).unwrap();
+        book.match_single(&ask_zero).unwrap();
+        book.match_single(&ask_neg).unwrap();
+
+        let snapshot = book.snapshot("CL-SPR", 5).unwrap();
+        assert_eq!(
+            snapshot.asks[0].price,
+            Decimal::from_str_exact("-0.25").unwrap()
+        );
+        assert_eq!(snapshot.asks[1].price, Decimal::ZERO);
+
+        // A bid at +0.1 sweeps the negative level first, then the zero level.
+        let bid = Order::try_new(Side::BID, "CL-SPR".to_string(), 0.1, 4).unwrap();
+        let blotter = book.match_single(&bid).unwrap();
+        assert_eq!(blotter.trades.len(), 2);
+        assert_eq!(
+            blotter.trades[0].fill_price,
+            Decimal::from_str_exact("-0.25").unwrap()
+        );
+        assert_eq!(blotter.trades[1].fill_price, Decimal::ZERO);
+
+        // A bid below zero stops short of the remaining zero-priced ask.
+        book.match_single(&spread).unwrap();
+        let snapshot = book.snapshot("CL-SPR", 5).unwrap();
+        assert_eq!(
+            snapshot.spread,
+            Some(Decimal::from_str_exact("0.5").unwrap())
+        );
+        assert_eq!(
+            snapshot.midpoint,
+            Some(Decimal::from_str_exact("-0.25").unwrap())
+        );
+    }
+
+    #[test]
+    fn short_sales_consume_and_restore_locates() {
+        let mut book = Book::new();
+        let short = Order::try_new(Side::ASK, "GME".to_string(), 20.0, 100)
+            .unwrap()
+            .with_short(true)
+            .unwrap();
+
+        // No locate table: shorts are unrestricted.
+        book.match_single(&short).unwrap();
+        assert_eq!(book.available_locate("GME"), None);
+
+        let mut book = Book::new();
+        book.set_locate("GME".to_string(), 150).unwrap();
+        book.match_single(&short).unwrap();
+        assert_eq!(book.available_locate("GME"), Some(50));
+
+        let second = Order::try_new(Side::ASK, "GME".to_string(), 20.0, 60)
+            .unwrap()
+            .with_short(true)
+            .unwrap();
+        assert!(book.match_single(&second).is_err());
+        assert_eq!(book.available_locate("GME"), Some(50));
+
+        // Long sales never touch the locate table.
+        let long = Order::try_new(Side::ASK, "GME".to_string(), 20.0, 60).unwrap();
+        book.match_single(&long).unwrap();
+        assert_eq!(book.available_locate("GME"), Some(50));
+        assert_eq!(book.available_locate("AMC"), Some(0));
+    }
+
+    #[test]
+    fn advance_clock_expires_resting_gtd_orders() {
+        let mut book = Book::new();
+        let gtd = Order::try_new(Side::BID, "AAPL".to_string(), 10.0, 5)
+            .unwrap()
+            .with_expire_time(Some(100));
+        let gtc = Order::try_new(Side::BID, "AAPL".to_string(), 9.0, 5).unwrap();
+        book.match_single(&gtd).unwrap();
+        book.match_single(&gtc).unwrap();
+
+        assert!(book.advance_clock(99).unwrap().is_empty());
+        let expired = book.advance_clock(100).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, gtd.id);
+        assert!(!book.order_map.contains_key(&gtd.id));
+        assert!(book.order_map.contains_key(&gtc.id));
+        assert!(book.expiries.is_empty());
+
+        assert!(book.advance_clock(50).is_err());
+        let late = Order::try_new(Side::ASK, "AAPL".to_string(), 11.0, 1)
+            .unwrap()
+            .with_expire_time(Some(100));
+        assert!(book.match_single(&late).is_err());
+    }
+
+    #[test]
+    fn opening_auction_crosses_held_and_pre_open_orders() {
+        let mut book = Book::new();
+        let moo = Order::try_new(Side::BID, "AAPL".to_string(), 0.0, 30)
+            .unwrap()
+            .with_auction(Some(AuctionDesignation::MOO));
+        assert!(book.match_single(&moo).is_err());
+
+        book.set_trading_state("AAPL", TradingState::PRE_OPEN)
+            .unwrap();
+        book.set_auction_cutoff("AAPL".to_string(), "open", 100)
+            .unwrap();
+        let bid = Order::try_new(Side::BID, "AAPL".to_string(), 10.0, 50).unwrap();
+        let ask = Order::try_new(Side::ASK, "AAPL".to_string(), 9.5, 60).unwrap();
+        let loo = Order::try_new(Side::BID, "AAPL".to_string(), 9.0, 10)
+            .unwrap()
+            .with_auction(Some(AuctionDesignation::LOO));
+        for order in [&moo, &bid, &ask, &loo] {
+            assert!(book.match_single(order).unwrap().trades.is_empty());
+        }
+        assert!(!book.order_map.contains_key(&moo.id));
+
+        book.advance_clock(100).unwrap();
+        let late = Order::try_new(Side::BID, "AAPL".to_string(), 0.0, 5)
+            .unwrap()
+            .with_auction(Some(AuctionDesignation::MOO));
+        assert!(book.match_single(&late).is_err());
+
+        let result = book.run_auction("AAPL", AuctionKind::Open, None).unwrap();
+        assert_eq!(result.price, Some(Decimal::from_str_exact("9.5").unwrap()));
+        assert_eq!(result.volume, 60);
+        assert_eq!(result.trades[0].incoming_order_id, moo.id);
+        // The LOO bid at 9 is below the clearing price and is cancelled.
+        assert_eq!(result.cancelled.len(), 1);
+        assert_eq!(result.cancelled[0].id, loo.id);
+        assert!(!book.order_map.contains_key(&ask.id));
+        assert_eq!(
+            book.best_level("AAPL", Side::BID),
+            Some((Decimal::from(10), 20))
+        );
+        assert_eq!(book.state_of("AAPL"), TradingState::CONTINUOUS);
+        assert!(book.run_auction("AAPL", AuctionKind::Open, None).is_err());
+    }
+
+    #[test]
+    fn stop_cascade_reports_generations_and_triggers() {
+        let mut book = Book::new();
+        let order = |side, price, qty| Order::try_new(side, "X".to_string(), price, qty).unwrap();
+        for (price, qty) in [(99.0, 5), (98.0, 5), (97.0, 5)] {
+            book.match_single(&order(Side::BID, price, qty)).unwrap();
+        }
+        let stop_a = order(Side::ASK, 98.0, 5)
+            .with_stop_price(Some(99.0))
+            .unwrap();
+        let stop_b = order(Side::ASK, 97.0, 5)
+            .with_stop_price(Some(98.0))
+            .unwrap();
+        let untouched = order(Side::ASK, 90.0, 5)
+            .with_stop_price(Some(90.0))
+            .unwrap();
+        for stop in [&stop_a, &stop_b, &untouched] {
+            assert!(book.match_single(stop).unwrap().trades.is_empty());
+        }
+
+        let blotter = book.match_single(&order(Side::ASK, 99.0, 5)).unwrap();
+        let cascade = &blotter.cascade.activations;
+        assert_eq!(cascade.len(), 2);
+        assert_eq!(cascade[0].order.id, stop_a.id);
+        assert_eq!(cascade[0].generation, 1);
+        assert_eq!(
+            cascade[0].trigger_trade_id,
+            Some(blotter.trades[0].trade_id)
+        );
+        assert_eq!(cascade[1].order.id, stop_b.id);
+        assert_eq!(cascade[1].generation, 2);
+        assert_eq!(
+            cascade[1].trigger_trade_id,
+            Some(cascade[0].trades[0].trade_id)
+        );
+        assert_eq!(blotter.cascade.depth(), 2);
+        assert!(book
+            .per_symbol(&book.stops, "X")
+            .unwrap()
+            .iter()
+            .any(|s| s.id == untouched.id));
+        assert!(book.best_level("X", Side::BID).is_none());
+    }
+
+    #[test]
+    fn l2_updates_maintain_one_synthetic_order_per_level() {
+        let mut book = Book::new();
+        let px = Decimal::from(100);
+        book.set_level_quantity("X", Side::BID, px, 10).unwrap();
+        book.set_level_quantity("X", Side::BID, px, 25).unwrap();
+        assert_eq!(book.best_level("X", Side::BID), Some((px, 25)));
+        assert_eq!(book.order_map.len(), 1);
+
+        // A synthetic level consumed by matching is recreated on update.
+        let sell = Order::try_new(Side::ASK, "X".to_string(), 100.0, 25).unwrap();
+        assert_eq!(book.match_single(&sell).unwrap().trades.len(), 1);
+        book.set_level_quantity("X", Side::BID, px, 5).unwrap();
+        assert_eq!(book.best_level("X", Side::BID), Some((px, 5)));
+
+        book.set_level_quantity("X", Side::BID, px, 0).unwrap();
+        assert!(book.best_level("X", Side::BID).is_none());
+        assert!(book.order_map.is_empty());
+        assert!(book.set_level_quantity("X", Side::BID, px, -1).is_err());
+    }
+
+    #[test]
+    fn l3_feed_updates_track_venue_order_ids() {
+        let mut book = Book::new();
+        let add = |id: &str, qty| FeedUpdate::AddOrder {
+            order_id: id.to_string(),
+            symbol: "BTC".to_string(),
+            side: Side::ASK,
+            price: Decimal::from(64_000),
+            quantity: qty,
+        };
+        let reduce = |id: &str, qty| FeedUpdate::ReduceOrder {
+            order_id: id.to_string(),
+            quantity: qty,
+            executed: false,
+        };
+        book.apply_feed_update(add("a1", 3)).unwrap();
+        book.apply_feed_update(add("a2", 4)).unwrap();
+        assert!(book.apply_feed_update(add("a1", 1)).is_err());
+        assert_eq!(book.best_level("BTC", Side::ASK).unwrap().1, 7);
+
+        book.apply_feed_update(reduce("a1", Some(1))).unwrap();
+        assert!(book.apply_feed_update(reduce("a1", Some(5))).is_err());
+        book.apply_feed_update(reduce("a2", None)).unwrap();
+        assert_eq!(book.best_level("BTC", Side::ASK).unwrap().1, 2);
+        book.apply_feed_update(reduce("a1", Some(2))).unwrap();
+        assert!(book.best_level("BTC", Side::ASK).is_none());
+        assert!(book.feed_orders.is_empty());
+    }
+
     #[test]
     fn snapshot_clamps_depth_and_computes_two_sided_metrics() {
         let mut book = Book::new();
@@ -821,8 +8392,14 @@ mod tests {
 
         assert_eq!(snapshot.bids.len(), 1);
         assert_eq!(snapshot.asks.len(), 1);
-        assert_eq!(snapshot.bids[0].price, Decimal::from_str_exact("99").unwrap());
-        assert_eq!(snapshot.asks[0].price, Decimal::from_str_exact("101").unwrap());
+        assert_eq!(
+            snapshot.bids[0].price,
+            Decimal::from_str_exact("99").unwrap()
+        );
+        assert_eq!(
+            snapshot.asks[0].price,
+            Decimal::from_str_exact("101").unwrap()
+        );
         assert_eq!(snapshot.spread, Some(Decimal::from_str_exact("2").unwrap()));
         assert_eq!(
             snapshot.midpoint,
@@ -850,4 +8427,134 @@ mod tests {
         assert_eq!(parse_parquet_side("ASK", 1).unwrap(), Side::ASK);
         assert!(parse_parquet_side("buy", 2).is_err());
     }
+
+    #[test]
+    fn snapshot_policy_screens_crossed_levels() {
+        let d = Decimal::from;
+        let crossed = vec![
+            (Side::BID, d(101), 1),
+            (Side::BID, d(99), 2),
+            (Side::ASK, d(102), 3),
+            (Side::ASK, d(100), 4),
+            (Side::ASK, d(103), 0),
+        ];
+        let kept = SnapshotPolicy::Drop.screen("X", crossed.clone()).unwrap();
+        assert_eq!(
+            kept,
+            vec![
+                (Side::BID, d(99), 2),
+                (Side::ASK, d(102), 3),
+                (Side::ASK, d(103), 0)
+            ]
+        );
+        let all = SnapshotPolicy::UncrossByMatching
+            .screen("X", crossed.clone())
+            .unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[2], (Side::ASK, d(100), 4));
+        assert!(SnapshotPolicy::Error.screen("X", crossed).is_err());
+        let placeholder = vec![(Side::BID, d(1), 0)];
+        assert!(SnapshotPolicy::Error.screen("X", placeholder).is_err());
+    }
+
+    #[test]
+    fn row_error_policy_parses_known_names() {
+        assert_eq!(
+            RowErrorPolicy::parse("raise").unwrap(),
+            RowErrorPolicy::Raise
+        );
+        assert_eq!(RowErrorPolicy::parse("Skip").unwrap(), RowErrorPolicy::Skip);
+        assert_eq!(
+            RowErrorPolicy::parse("collect").unwrap(),
+            RowErrorPolicy::Collect
+        );
+        assert!(RowErrorPolicy::parse("ignore").is_err());
+    }
+
+    #[test]
+    fn tie_break_orders_rows_sharing_a_timestamp() {
+        let row = |side, order_id: &str, row_idx| ParquetOrderRow {
+            side,
+            symbol: "X".to_string(),
+            price: 10.0,
+            quantity: Decimal::ONE,
+            row_idx,
+            timestamp: Some(1),
+            expire_time: None,
+            order_id: Some(order_id.to_string()),
+        };
+        let rows = vec![
+            (2, row(Side::BID, "a", 0)),
+            (1, row(Side::ASK, "c", 1)),
+            (1, row(Side::BID, "b", 2)),
+        ];
+        let order_of = |tie_break: TieBreak| {
+            let mut sorted = rows.clone();
+            tie_break.sort(&mut sorted);
+            sorted.iter().map(|(_, r)| r.row_idx).collect::<Vec<_>>()
+        };
+        assert_eq!(order_of(TieBreak::File), vec![1, 2, 0]);
+        assert_eq!(order_of(TieBreak::OrderId), vec![2, 1, 0]);
+        assert_eq!(order_of(TieBreak::BidFirst), vec![2, 1, 0]);
+        assert_eq!(order_of(TieBreak::AskFirst), vec![1, 2, 0]);
+        assert!(TieBreak::parse("random").is_err());
+    }
+
+    #[test]
+    fn invariant_checker_accepts_matching_and_flags_corruption() {
+        let mut book = Book::new();
+        let ask = Order::try_new(Side::ASK, "AAPL".to_string(), 10.0, 5).unwrap();
+        book.match_single(&ask).unwrap();
+        book.match_single(&Order::try_new(Side::BID, "AAPL".to_string(), 10.0, 2).unwrap())
+            .unwrap();
+        book.match_single(&Order::try_new(Side::BID, "AAPL".to_string(), 11.0, 7).unwrap())
+            .unwrap();
+        book.match_single(&Order::try_new(Side::ASK, "AAPL".to_string(), 12.0, 1).unwrap())
+            .unwrap();
+        assert!(book.invariant_violations().is_empty());
+
+        let sym_book = book.symbol_book_mut("AAPL").unwrap();
+        sym_book.bids.levels[0].orders[0].quantity -= 1;
+        sym_book
+            .asks
+            .levels
+            .insert(0, sym_book.asks.levels[0].clone());
+        let violations = book.invariant_violations();
+        assert!(violations.iter().any(|v| v.contains("trades account for")));
+        assert!(violations.iter().any(|v| v.contains("out of order")));
+        assert!(violations.iter().any(|v| v.contains("order_map has")));
+    }
+
+    #[test]
+    fn debug_checks_raise_on_the_input_after_corruption() {
+        pyo3::prepare_freethreaded_python();
+        let mut book = Book {
+            debug_checks: true,
+            ..Book::new()
+        };
+        let order = |side, price| Order::try_new(side, "AAPL".to_string(), price, 5).unwrap();
+        book.match_single(&order(Side::ASK, 10.0)).unwrap();
+        book.symbol_book_mut("AAPL").unwrap().asks.levels[0].orders[0].quantity = 0;
+
+        let err = book.match_single(&order(Side::BID, 9.0)).unwrap_err();
+        Python::with_gil(|py| {
+            assert!(err.is_instance_of::<pyo3::exceptions::PyAssertionError>(py));
+            let report = err.value(py).to_string();
+            assert!(report.contains("after Match("), "{}", report);
+            assert!(report.contains("quantity"), "{}", report);
+        });
+    }
+
+    #[test]
+    fn band_levels_merges_adjacent_prices_up_to_depth() {
+        let d = |s: &str| Decimal::from_str_exact(s).unwrap();
+        let levels: Vec<SnapshotLevel> = [("10.09", 1), ("10.02", 2), ("9.95", 3), ("9.80", 4)]
+            .iter()
+            .map(|&(price, qty)| SnapshotLevel::from_rust(d(price), qty))
+            .collect();
+        let (bands, covered) = band_levels(&levels, d("0.1"), 2);
+        let summary: Vec<_> = bands.iter().map(|b| (b.price, b.quantity)).collect();
+        assert_eq!(summary, vec![(d("10.0"), 3), (d("9.9"), 3)]);
+        assert_eq!(covered, 3);
+    }
 }
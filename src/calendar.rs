@@ -0,0 +1,150 @@
+use crate::auction::TradingState;
+
+// ---------------------------------------------------------------------------
+// SessionSchedule — a symbol's trading day on the simulated clock
+// ---------------------------------------------------------------------------
+
+/// A point of the trading day at which a schedule acts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SessionEvent {
+    /// Orders rest unmatched for the opening auction.
+    PreOpen,
+    /// The opening auction runs and continuous trading starts.
+    Open,
+    /// The closing auction runs; trading ends unless a post-market
+    /// session follows.
+    Close,
+    /// The post-market session ends.
+    Post,
+}
+
+/// When a symbol's session phases start, as clock times, or as offsets
+/// into a day of `day_length` clock units repeating forever.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionSchedule {
+    pub pre_open: Option<i64>,
+    pub open: i64,
+    pub close: i64,
+    pub post: Option<i64>,
+    pub day_length: Option<i64>,
+}
+
+impl SessionSchedule {
+    /// The schedule, if its times run pre-open, open, close, post in
+    /// that order (each optional phase strictly before or after its
+    /// neighbour) and fall within the day.
+    pub fn new(
+        pre_open: Option<i64>,
+        open: i64,
+        close: i64,
+        post: Option<i64>,
+        day_length: Option<i64>,
+    ) -> Result<Self, String> {
+        if pre_open.is_some_and(|pre_open| pre_open >= open) {
+            return Err("pre_open must come before open".to_string());
+        }
+        if close <= open {
+            return Err("close must come after open".to_string());
+        }
+        if post.is_some_and(|post| post <= close) {
+            return Err("post must come after close".to_string());
+        }
+        if let Some(day) = day_length {
+            let first = pre_open.unwrap_or(open);
+            let last = post.unwrap_or(close);
+            if first < 0 || last >= day {
+                return Err(format!(
+                    "Session times must fall within the day [0, {})",
+                    day
+                ));
+            }
+        }
+        Ok(SessionSchedule {
+            pre_open,
+            open,
+            close,
+            post,
+            day_length,
+        })
+    }
+
+    /// The day's events at their offsets, in order.
+    fn events(&self) -> impl Iterator<Item = (i64, SessionEvent)> {
+        let pre_open = self.pre_open.map(|at| (at, SessionEvent::PreOpen));
+        let post = self.post.map(|at| (at, SessionEvent::Post));
+        pre_open
+            .into_iter()
+            .chain([
+                (self.open, SessionEvent::Open),
+                (self.close, SessionEvent::Close),
+            ])
+            .chain(post)
+    }
+
+    /// Events after clock `from` up to and including `to`, earliest first.
+    /// A repeating schedule fires at most the last day's worth: the days
+    /// before would only have repeated the same transitions.
+    pub fn due(&self, from: Option<i64>, to: i64) -> Vec<(i64, SessionEvent)> {
+        let Some(day) = self.day_length else {
+            return self
+                .events()
+                .filter(|&(at, _)| from.is_none_or(|from| at > from) && at <= to)
+                .collect();
+        };
+        let from = from.map_or(to - day, |from| from.max(to - day));
+        (from.div_euclid(day)..=to.div_euclid(day))
+            .flat_map(|d| {
+                self.events()
+                    .map(move |(offset, event)| (d * day + offset, event))
+            })
+            .filter(|&(at, _)| at > from && at <= to)
+            .collect()
+    }
+
+    /// The phase `event` starts.
+    pub fn state_after(&self, event: SessionEvent) -> TradingState {
+        match event {
+            SessionEvent::PreOpen => TradingState::PRE_OPEN,
+            SessionEvent::Open => TradingState::CONTINUOUS,
+            SessionEvent::Close if self.post.is_some() => TradingState::CONTINUOUS,
+            SessionEvent::Close | SessionEvent::Post => TradingState::CLOSED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeating_days_fire_each_event_once() {
+        use SessionEvent::*;
+        let schedule = SessionSchedule::new(Some(10), 20, 60, Some(80), Some(100)).unwrap();
+        assert_eq!(schedule.due(Some(0), 20), [(10, PreOpen), (20, Open)]);
+        assert_eq!(schedule.due(Some(20), 59), []);
+        assert_eq!(
+            schedule.due(Some(70), 120),
+            [(80, Post), (110, PreOpen), (120, Open)]
+        );
+        // A first reading of the clock, or a jump of many days, fires the
+        // last day's events only.
+        assert_eq!(
+            schedule.due(None, 215),
+            [(120, Open), (160, Close), (180, Post), (210, PreOpen)]
+        );
+        assert_eq!(
+            schedule.due(Some(0), 1_015),
+            [(920, Open), (960, Close), (980, Post), (1_010, PreOpen)]
+        );
+        assert_eq!(schedule.state_after(Close), TradingState::CONTINUOUS);
+
+        let once = SessionSchedule::new(None, 5, 9, None, None).unwrap();
+        assert_eq!(once.due(None, 9), [(5, Open), (9, Close)]);
+        assert_eq!(once.due(Some(9), 1_000), []);
+        assert_eq!(once.state_after(Close), TradingState::CLOSED);
+
+        assert!(SessionSchedule::new(Some(20), 20, 60, None, None).is_err());
+        assert!(SessionSchedule::new(None, 20, 20, None, None).is_err());
+        assert!(SessionSchedule::new(None, 20, 60, Some(100), Some(100)).is_err());
+    }
+}
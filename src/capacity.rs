@@ -0,0 +1,115 @@
+use pyo3::prelude::*;
+
+// ---------------------------------------------------------------------------
+// CapacityLimits — caps on the book's size
+// ---------------------------------------------------------------------------
+
+/// What happens to an order that would take the book past a cap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapacityAction {
+    /// Refuse it with code "capacity".
+    Reject,
+    /// Rest it, then cancel the worst-priced orders on its side until the
+    /// caps hold again.
+    Evict,
+}
+
+impl CapacityAction {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "reject" => Ok(CapacityAction::Reject),
+            "evict" => Ok(CapacityAction::Evict),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid on_limit '{}'. Expected 'reject' or 'evict'.",
+                text
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CapacityAction::Reject => "reject",
+            CapacityAction::Evict => "evict",
+        }
+    }
+}
+
+/// Resting orders and levels around one order: in its symbol, on its
+/// side, and in the whole book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Occupancy {
+    pub symbol_orders: usize,
+    pub side_levels: usize,
+    pub total_orders: usize,
+}
+
+/// The caps set on the book, with running counts of the orders they
+/// turned away.
+#[derive(Clone, Debug)]
+pub struct CapacityLimits {
+    pub max_orders_per_symbol: Option<usize>,
+    pub max_levels_per_side: Option<usize>,
+    pub max_total_orders: Option<usize>,
+    pub action: CapacityAction,
+    pub rejected: u64,
+    pub evicted: u64,
+}
+
+impl CapacityLimits {
+    /// The first cap `occupancy` exceeds, described; None if all hold.
+    pub fn exceeded(&self, occupancy: Occupancy) -> Option<String> {
+        let caps = [
+            (
+                self.max_orders_per_symbol,
+                occupancy.symbol_orders,
+                "orders per symbol",
+            ),
+            (
+                self.max_levels_per_side,
+                occupancy.side_levels,
+                "levels per side",
+            ),
+            (
+                self.max_total_orders,
+                occupancy.total_orders,
+                "orders in the book",
+            ),
+        ];
+        caps.into_iter().find_map(|(cap, count, what)| {
+            cap.filter(|&cap| count > cap)
+                .map(|cap| format!("at most {} {}", cap, what))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exceeded_names_the_first_broken_cap() {
+        let limits = CapacityLimits {
+            max_orders_per_symbol: Some(3),
+            max_levels_per_side: None,
+            max_total_orders: Some(5),
+            action: CapacityAction::parse("EVICT").unwrap(),
+            rejected: 0,
+            evicted: 0,
+        };
+        let occupancy = |symbol_orders, total_orders| Occupancy {
+            symbol_orders,
+            side_levels: 100,
+            total_orders,
+        };
+        assert_eq!(limits.exceeded(occupancy(3, 5)), None);
+        assert_eq!(
+            limits.exceeded(occupancy(3, 6)).as_deref(),
+            Some("at most 5 orders in the book")
+        );
+        assert_eq!(
+            limits.exceeded(occupancy(4, 6)).as_deref(),
+            Some("at most 3 orders per symbol")
+        );
+        assert!(CapacityAction::parse("drop").is_err());
+    }
+}
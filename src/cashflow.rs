@@ -0,0 +1,137 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+// ---------------------------------------------------------------------------
+// CashFlow — dividends and funding paid to positions on the clock
+// ---------------------------------------------------------------------------
+
+/// How a cash flow's amount turns into payments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CashFlowKind {
+    /// An amount per unit held: longs receive it, shorts pay it.
+    Dividend,
+    /// A rate of the position's marked notional that longs pay shorts
+    /// when positive, as on perpetual futures.
+    Funding,
+}
+
+impl CashFlowKind {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text {
+            "dividend" => Ok(CashFlowKind::Dividend),
+            "funding" => Ok(CashFlowKind::Funding),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown cash flow kind '{}'; expected 'dividend' or 'funding'",
+                other
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CashFlowKind::Dividend => "dividend",
+            CashFlowKind::Funding => "funding",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CashFlow {
+    pub symbol: String,
+    pub kind: CashFlowKind,
+    pub amount: Decimal,
+}
+
+impl CashFlow {
+    /// What a net position of `quantity` receives (negative: pays), given
+    /// the symbol's multiplier and mark price. Funding without a mark
+    /// cannot be priced.
+    pub fn payment(
+        &self,
+        quantity: i64,
+        multiplier: Decimal,
+        mark: Option<Decimal>,
+    ) -> Option<Decimal> {
+        let units = Decimal::from(quantity) * multiplier;
+        match self.kind {
+            CashFlowKind::Dividend => Some(self.amount * units),
+            CashFlowKind::Funding => Some(-self.amount * units * mark?),
+        }
+    }
+}
+
+/// Cash flows waiting for the clock, by time and then the order added.
+#[derive(Clone, Debug, Default)]
+pub struct CashFlowSchedule {
+    pending: BTreeMap<(i64, u64), CashFlow>,
+    added: u64,
+}
+
+impl CashFlowSchedule {
+    pub fn add(&mut self, time: i64, flow: CashFlow) {
+        self.pending.insert((time, self.added), flow);
+        self.added += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Remove and return the flows due by `now`, earliest first.
+    pub fn take_due(&mut self, now: i64) -> Vec<(i64, CashFlow)> {
+        let later = self.pending.split_off(&(now.saturating_add(1), 0));
+        std::mem::replace(&mut self.pending, later)
+            .into_iter()
+            .map(|((time, _), flow)| (time, flow))
+            .collect()
+    }
+
+    /// Pay flows scheduled for `old` to symbol `new` instead.
+    pub fn rename_symbol(&mut self, old: &str, new: &str) {
+        for flow in self.pending.values_mut().filter(|f| f.symbol == old) {
+            flow.symbol = new.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flows_fall_due_in_time_order_and_pay_by_kind() {
+        let flow = |symbol: &str, kind, amount| CashFlow {
+            symbol: symbol.to_string(),
+            kind,
+            amount,
+        };
+        let mut schedule = CashFlowSchedule::default();
+        let dividend = flow("X", CashFlowKind::Dividend, Decimal::new(5, 1));
+        let funding = flow("Y", CashFlowKind::Funding, Decimal::new(1, 3));
+        schedule.add(20, funding.clone());
+        schedule.add(10, dividend.clone());
+        schedule.add(30, dividend.clone());
+        schedule.rename_symbol("Y", "Z");
+
+        assert!(schedule.take_due(9).is_empty());
+        let due = schedule.take_due(20);
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0], (10, dividend.clone()));
+        assert_eq!((due[1].0, due[1].1.symbol.as_str()), (20, "Z"));
+        assert_eq!(schedule.len(), 1);
+
+        let hundred = Decimal::from(100);
+        assert_eq!(
+            dividend.payment(-4, Decimal::ONE, None),
+            Some(Decimal::from(-2))
+        );
+        // Longs pay 0.1% of 10 × 100 × 50.
+        assert_eq!(
+            funding.payment(10, Decimal::from(50), Some(hundred)),
+            Some(Decimal::from(-50))
+        );
+        assert_eq!(funding.payment(10, Decimal::ONE, None), None);
+        assert!(CashFlowKind::parse("coupon").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::order::{extract_decimal, Side};
+
+// ---------------------------------------------------------------------------
+// Condition — book-state predicates a conditional order waits for
+// ---------------------------------------------------------------------------
+
+/// One predicate on a symbol's quote.
+#[derive(Clone, Debug, PartialEq)]
+enum Clause {
+    /// Both sides quoted and the spread at most this wide.
+    SpreadAtMost(Decimal),
+    /// Displayed quantity at the best level of `side` (the side the order
+    /// trades against when None) at least this much.
+    DepthAtLeast { side: Option<Side>, quantity: i64 },
+}
+
+/// The quote of a symbol as conditions see it: the best price and
+/// displayed quantity of each side.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Quote {
+    pub bid: Option<(Decimal, i64)>,
+    pub ask: Option<(Decimal, i64)>,
+}
+
+impl Quote {
+    fn best(&self, side: Side) -> Option<(Decimal, i64)> {
+        match side {
+            Side::BID => self.bid,
+            Side::ASK => self.ask,
+        }
+    }
+}
+
+/// When a conditional order enters the book: once every clause holds
+/// for the symbol's quote. Build one with `Condition.spread_at_most` or
+/// `Condition.depth_at_least`, and combine them with `&`.
+///
+/// The order waits like a stop, off the book, and is checked whenever
+/// the quote may have moved: after trades, resting orders and cancels.
+/// Once it holds, the order matches as a limit order at its price and
+/// any remainder rests.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    clauses: Vec<Clause>,
+}
+
+impl Condition {
+    /// Whether every clause holds for an order on `side` given `quote`.
+    pub fn holds(&self, side: Side, quote: &Quote) -> bool {
+        self.clauses.iter().all(|clause| match *clause {
+            Clause::SpreadAtMost(max_spread) => match (quote.bid, quote.ask) {
+                (Some((bid, _)), Some((ask, _))) => ask - bid <= max_spread,
+                _ => false,
+            },
+            Clause::DepthAtLeast {
+                side: depth_side,
+                quantity,
+            } => quote
+                .best(depth_side.unwrap_or(side.other()))
+                .is_some_and(|(_, shown)| shown >= quantity),
+        })
+    }
+}
+
+#[pymethods]
+impl Condition {
+    /// Hold until both sides are quoted with a spread of at most
+    /// `max_spread`.
+    #[staticmethod]
+    fn spread_at_most(max_spread: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let max_spread = extract_decimal(max_spread, "max_spread")?;
+        if max_spread < Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "max_spread cannot be negative",
+            ));
+        }
+        Ok(Condition {
+            clauses: vec![Clause::SpreadAtMost(max_spread)],
+        })
+    }
+
+    /// Hold until the best level of `side` shows at least `quantity`; by
+    /// default that is the side the order trades against.
+    #[staticmethod]
+    #[pyo3(signature = (quantity, *, side = None))]
+    fn depth_at_least(quantity: i64, side: Option<Side>) -> PyResult<Self> {
+        if quantity <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "quantity must be positive",
+            ));
+        }
+        Ok(Condition {
+            clauses: vec![Clause::DepthAtLeast { side, quantity }],
+        })
+    }
+
+    /// A condition holding when both this one and `other` do.
+    fn __and__(&self, other: &Condition) -> Condition {
+        Condition {
+            clauses: self.clauses.iter().chain(&other.clauses).cloned().collect(),
+        }
+    }
+
+    fn __eq__(&self, other: &Condition) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> String {
+        let clauses: Vec<String> = self
+            .clauses
+            .iter()
+            .map(|clause| match clause {
+                Clause::SpreadAtMost(max_spread) => format!("spread <= {}", max_spread),
+                Clause::DepthAtLeast {
+                    side: Some(side),
+                    quantity,
+                } => format!("{} depth >= {}", side.as_str(), quantity),
+                Clause::DepthAtLeast {
+                    side: None,
+                    quantity,
+                } => format!("depth >= {}", quantity),
+            })
+            .collect();
+        format!("Condition({})", clauses.join(" & "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_clause_must_hold() {
+        let spread = Condition {
+            clauses: vec![Clause::SpreadAtMost(Decimal::ONE)],
+        };
+        let depth = Condition {
+            clauses: vec![Clause::DepthAtLeast {
+                side: None,
+                quantity: 10,
+            }],
+        };
+        let both = spread.__and__(&depth);
+        let quote = |bid: i64, ask: i64, ask_shown: i64| Quote {
+            bid: Some((Decimal::from(bid), 5)),
+            ask: Some((Decimal::from(ask), ask_shown)),
+        };
+
+        assert!(spread.holds(Side::BID, &quote(100, 101, 1)));
+        assert!(!spread.holds(Side::BID, &quote(100, 102, 1)));
+        assert!(!spread.holds(Side::BID, &Quote::default()));
+        // Depth defaults to the side the order trades against.
+        assert!(depth.holds(Side::BID, &quote(100, 105, 10)));
+        assert!(!depth.holds(Side::ASK, &quote(100, 105, 10)));
+        assert!(!both.holds(Side::BID, &quote(100, 105, 10)));
+        assert!(both.holds(Side::BID, &quote(100, 101, 10)));
+        assert_eq!(both.clauses.len(), 2);
+    }
+}
@@ -0,0 +1,201 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyMemoryView, PyTuple, PyType};
+use pyo3::{ffi, intern};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::order::{decimal_cls, extract_order_id, uuid_cls};
+
+// ---------------------------------------------------------------------------
+// Batch conversion — uuid.UUID and decimal.Decimal objects in bulk
+// ---------------------------------------------------------------------------
+
+static SAFE_UNKNOWN: GILOnceCell<PyObject> = GILOnceCell::new();
+
+/// `uuid.SafeUUID.unknown`, the `is_safe` of every UUID built here.
+fn safe_unknown(py: Python<'_>) -> PyResult<&PyObject> {
+    SAFE_UNKNOWN.get_or_try_init(py, || {
+        Ok(py
+            .import("uuid")?
+            .getattr("SafeUUID")?
+            .getattr("unknown")?
+            .unbind())
+    })
+}
+
+/// Set a slot of `obj` past the type's `__setattr__`, which `uuid.UUID`
+/// overrides to stay immutable; this is what `UUID.__init__` does itself.
+fn set_slot(
+    obj: &Bound<'_, PyAny>,
+    name: &Bound<'_, PyAny>,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    // SAFETY: all three pointers are live, owned by the caller's bounds.
+    let status =
+        unsafe { ffi::PyObject_GenericSetAttr(obj.as_ptr(), name.as_ptr(), value.as_ptr()) };
+    if status == -1 {
+        return Err(PyErr::fetch(obj.py()));
+    }
+    Ok(())
+}
+
+/// Builds `uuid.UUID` objects by allocating them directly and giving them
+/// their 128-bit value, skipping the hex string `UUID(str)` would format
+/// and parse again; the result is indistinguishable from `UUID(int=...)`.
+struct UuidBuilder<'py> {
+    cls: &'py Bound<'py, PyType>,
+    no_args: Bound<'py, PyTuple>,
+    unknown: &'py Bound<'py, PyAny>,
+}
+
+impl<'py> UuidBuilder<'py> {
+    fn new(py: Python<'py>) -> PyResult<Self> {
+        Ok(UuidBuilder {
+            cls: uuid_cls(py)?.bind(py),
+            no_args: PyTuple::empty(py),
+            unknown: safe_unknown(py)?.bind(py),
+        })
+    }
+
+    fn build(&self, id: Uuid) -> PyResult<Bound<'py, PyAny>> {
+        let py = self.cls.py();
+        // SAFETY: `cls` is a type and `no_args` a tuple; generic new only
+        // allocates, and a null result is turned into the error.
+        let obj = unsafe {
+            Bound::from_owned_ptr_or_err(
+                py,
+                ffi::PyType_GenericNew(
+                    self.cls.as_type_ptr(),
+                    self.no_args.as_ptr(),
+                    std::ptr::null_mut(),
+                ),
+            )?
+        };
+        let int = id.as_u128().into_pyobject(py)?;
+        set_slot(&obj, intern!(py, "int"), int.as_any())?;
+        set_slot(&obj, intern!(py, "is_safe"), self.unknown)?;
+        Ok(obj)
+    }
+}
+
+/// A `uuid.UUID` for `id`.
+pub fn uuid_object(py: Python<'_>, id: Uuid) -> PyResult<Bound<'_, PyAny>> {
+    UuidBuilder::new(py)?.build(id)
+}
+
+/// `uuid.UUID` objects for `ids`, in order.
+pub fn uuids_to_py<'py>(
+    py: Python<'py>,
+    ids: impl IntoIterator<Item = Uuid>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let builder = UuidBuilder::new(py)?;
+    ids.into_iter().map(|id| builder.build(id)).collect()
+}
+
+/// `decimal.Decimal` objects for `values`, in order. Each distinct value is
+/// built once and shared by its repeats, as prices in a batch of trades or
+/// orders often are; Decimals are immutable, so sharing is invisible.
+pub fn decimals_to_py<'py>(
+    py: Python<'py>,
+    values: impl IntoIterator<Item = Decimal>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let cls = decimal_cls(py)?.bind(py);
+    // Keyed by mantissa and scale rather than by value, which would
+    // conflate 1.0 with 1.00.
+    let mut built: HashMap<(i128, u32), Bound<'py, PyAny>> = HashMap::new();
+    values
+        .into_iter()
+        .map(|value| {
+            let key = (value.mantissa(), value.scale());
+            if let Some(obj) = built.get(&key) {
+                return Ok(obj.clone());
+            }
+            let obj = cls.call1((value.to_string(),))?;
+            built.insert(key, obj.clone());
+            Ok(obj)
+        })
+        .collect()
+}
+
+/// Byte order of a buffer's integers, from its struct format prefix.
+fn u64_reader(format: &str) -> Option<fn([u8; 8]) -> u64> {
+    let (prefix, code) = format.split_at(format.len().saturating_sub(1));
+    if !matches!(code, "Q" | "q" | "L" | "l" | "N" | "n") {
+        return None;
+    }
+    match prefix {
+        "" | "@" | "=" => Some(u64::from_ne_bytes),
+        "<" => Some(u64::from_le_bytes),
+        ">" | "!" => Some(u64::from_be_bytes),
+        _ => None,
+    }
+}
+
+/// Order ids from a buffer or an iterable, in order.
+///
+/// A buffer (a numpy array, `bytes`, ...) holds either 16-byte items, each
+/// a `UUID.bytes`, or 64-bit integers, each the sequence number `n` of a
+/// counter id, `UUID(int=n)`. Anything else is iterated, each item an
+/// Order, `uuid.UUID` or id string.
+pub fn order_ids_from_py(ids: &Bound<'_, PyAny>) -> PyResult<Vec<Uuid>> {
+    let Ok(view) = PyMemoryView::from(ids) else {
+        return ids.try_iter()?.map(|id| extract_order_id(&id?)).collect();
+    };
+    let itemsize: usize = view.getattr("itemsize")?.extract()?;
+    let format: String = view.getattr("format")?.extract()?;
+    let raw = PyBuffer::<u8>::get(&view.call_method1("cast", ("B",))?)?.to_vec(ids.py())?;
+    if itemsize == 8 {
+        if let Some(read) = u64_reader(&format) {
+            return Ok(raw
+                .chunks_exact(8)
+                .map(|chunk| Uuid::from_u64_pair(0, read(chunk.try_into().expect("8 bytes"))))
+                .collect());
+        }
+    }
+    if itemsize != 16 && !(itemsize == 1 && raw.len() % 16 == 0) {
+        return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "Expected 16-byte UUIDs or 64-bit integer ids, got items of format {:?}",
+            format
+        )));
+    }
+    Ok(raw
+        .chunks_exact(16)
+        .map(|chunk| Uuid::from_bytes(chunk.try_into().expect("16 bytes")))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_conversions_match_the_python_constructors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let ids = [Uuid::from_u64_pair(1, 2), Uuid::from_u64_pair(u64::MAX, 0)];
+            let uuid = py.import("uuid").unwrap().getattr("UUID").unwrap();
+            for (obj, id) in uuids_to_py(py, ids).unwrap().iter().zip(ids) {
+                let expected = uuid.call1((id.to_string(),)).unwrap();
+                assert!(obj.eq(&expected).unwrap());
+                assert_eq!(obj.hash().unwrap(), expected.hash().unwrap());
+                assert_eq!(obj.str().unwrap().to_string(), id.to_string());
+            }
+
+            let values = [
+                Decimal::new(10, 1),
+                Decimal::new(100, 2),
+                Decimal::new(10, 1),
+            ];
+            let objs = decimals_to_py(py, values).unwrap();
+            let text: Vec<String> = objs
+                .iter()
+                .map(|obj| obj.str().unwrap().to_string())
+                .collect();
+            assert_eq!(text, ["1.0", "1.00", "1.0"]);
+            assert!(objs[0].is(&objs[2]));
+        });
+    }
+}
@@ -0,0 +1,127 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::order::Side;
+
+// ---------------------------------------------------------------------------
+// Corporate actions — stock splits and ticker changes applied to a book
+// ---------------------------------------------------------------------------
+
+/// How a split of `ratio` new shares per old share maps an order's price
+/// and quantity.
+///
+/// Prices divide by the ratio and round to the tick (else the price
+/// precision) away from the other side: bids down, asks up, so a book
+/// that did not cross still does not. Quantities multiply by it and round
+/// down to whole lots. Both are `None` where the result overflows.
+#[derive(Clone, Copy, Debug)]
+pub struct SplitAdjustment {
+    pub ratio: Decimal,
+    pub tick: Option<Decimal>,
+    pub price_precision: Option<u32>,
+    pub lot_size: i64,
+}
+
+impl SplitAdjustment {
+    pub fn price(&self, side: Side, price: Decimal) -> Option<Decimal> {
+        let strategy = match side {
+            Side::BID => RoundingStrategy::ToNegativeInfinity,
+            Side::ASK => RoundingStrategy::ToPositiveInfinity,
+        };
+        let adjusted = price.checked_div(self.ratio)?;
+        let rounded = match (self.tick, self.price_precision) {
+            (Some(tick), _) => adjusted
+                .checked_div(tick)?
+                .round_dp_with_strategy(0, strategy)
+                .checked_mul(tick)?,
+            (None, Some(places)) => adjusted.round_dp_with_strategy(places, strategy),
+            (None, None) => adjusted,
+        };
+        Some(rounded.normalize())
+    }
+
+    pub fn quantity(&self, quantity: i64) -> Option<i64> {
+        let adjusted = Decimal::from(quantity)
+            .checked_mul(self.ratio)?
+            .floor()
+            .to_i64()?;
+        Some(adjusted - adjusted % self.lot_size)
+    }
+}
+
+/// Move what `map` holds under keys with symbol `old` to `new`, which
+/// `symbol_of` borrows from each key, replacing whatever it held for `new`.
+pub fn rekey_by<K: Eq + Hash, V>(
+    map: &mut HashMap<K, V>,
+    symbol_of: impl Fn(&mut K) -> &mut String,
+    old: &str,
+    new: &str,
+) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .filter_map(|(mut key, value)| {
+            let symbol = symbol_of(&mut key);
+            if symbol == new {
+                return None;
+            }
+            if symbol == old {
+                *symbol = new.to_string();
+            }
+            Some((key, value))
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_round_prices_apart_and_quantities_down() {
+        let split = SplitAdjustment {
+            ratio: Decimal::from(3),
+            tick: Some(Decimal::new(1, 2)),
+            price_precision: None,
+            lot_size: 1,
+        };
+        assert_eq!(
+            split.price(Side::BID, Decimal::from(100)),
+            Some(Decimal::new(3333, 2))
+        );
+        assert_eq!(
+            split.price(Side::ASK, Decimal::from(100)),
+            Some(Decimal::new(3334, 2))
+        );
+        assert_eq!(
+            split.price(Side::ASK, Decimal::from(99)),
+            Some(Decimal::from(33))
+        );
+        assert_eq!(split.quantity(7), Some(21));
+        assert_eq!(split.quantity(i64::MAX), None);
+
+        let reverse = SplitAdjustment {
+            ratio: Decimal::new(1, 1),
+            tick: None,
+            price_precision: None,
+            lot_size: 5,
+        };
+        assert_eq!(
+            reverse.price(Side::BID, Decimal::new(25, 1)),
+            Some(Decimal::from(25))
+        );
+        assert_eq!(reverse.price(Side::BID, Decimal::MAX), None);
+        assert_eq!(reverse.quantity(99), Some(5));
+        assert_eq!(reverse.quantity(49), Some(0));
+
+        let key = |symbol: &str, n| (symbol.to_string(), n);
+        let mut levels =
+            HashMap::from([(key("X", 1), 'a'), (key("Y", 2), 'b'), (key("Z", 3), 'c')]);
+        rekey_by(&mut levels, |(symbol, _)| symbol, "X", "Z");
+        assert_eq!(
+            levels,
+            HashMap::from([(key("Z", 1), 'a'), (key("Y", 2), 'b')])
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::book::Book;
+use crate::order::Order;
+use crate::trade::{Trade, TradeBlotter};
+
+// ---------------------------------------------------------------------------
+// DarkBook — hidden orders crossing at a lit book's midpoint
+// ---------------------------------------------------------------------------
+
+/// A resting hidden order and the smallest fill it will take.
+#[derive(Clone, Debug)]
+struct DarkOrder {
+    order: Order,
+    min_quantity: i64,
+}
+
+impl DarkOrder {
+    /// Whether the order's limit admits a fill at `price`.
+    fn accepts(&self, price: Decimal) -> bool {
+        self.order.side.price_is_matchable(self.order.price, price)
+    }
+
+    /// Smallest acceptable fill: the minimum, or what is left when less.
+    fn min_fill(&self) -> i64 {
+        self.min_quantity.min(self.order.quantity)
+    }
+}
+
+/// Fill `incoming` against the opposite-side orders of `resting`, in time
+/// priority, at `price`. Pairs where the available quantity falls short
+/// of either order's minimum are skipped; filled orders are left at zero
+/// quantity for the caller to sweep.
+fn cross_at(resting: &mut [DarkOrder], incoming: &mut DarkOrder, price: Decimal) -> Vec<Trade> {
+    let mut trades = Vec::new();
+    if !incoming.accepts(price) {
+        return trades;
+    }
+    for standing in resting.iter_mut() {
+        if incoming.order.quantity == 0 {
+            break;
+        }
+        if standing.order.side == incoming.order.side
+            || standing.order.quantity == 0
+            || !standing.accepts(price)
+        {
+            continue;
+        }
+        let quantity = incoming.order.quantity.min(standing.order.quantity);
+        if quantity < incoming.min_fill() || quantity < standing.min_fill() {
+            continue;
+        }
+        for dark in [&mut *incoming, &mut *standing] {
+            dark.order.quantity -= quantity;
+            dark.order.fills.record(price, quantity);
+        }
+        trades.push(
+            Trade::from_rust(incoming.order.id, standing.order.id, quantity, price)
+                .with_symbol(&incoming.order.symbol)
+                .with_standing(standing.order.price, standing.order.quantity),
+        );
+    }
+    trades
+}
+
+/// Crossing network holding hidden orders against a lit `Book`.
+///
+/// Orders never show in the lit book and only execute at the midpoint of
+/// its best bid and offer, read when the dark book matches. An order takes
+/// part only while its limit accepts the midpoint, and never fills for
+/// less than its `min_quantity` (or its whole remainder, when smaller).
+#[pyclass]
+pub struct DarkBook {
+    lit: Py<Book>,
+    /// Resting orders per symbol in time priority, both sides together.
+    orders: HashMap<String, Vec<DarkOrder>>,
+}
+
+#[pymethods]
+impl DarkBook {
+    #[new]
+    fn new(lit: Py<Book>) -> Self {
+        DarkBook {
+            lit,
+            orders: HashMap::new(),
+        }
+    }
+
+    /// The lit book whose midpoint prices this book's crosses.
+    #[getter]
+    fn lit(&self, py: Python<'_>) -> Py<Book> {
+        self.lit.clone_ref(py)
+    }
+
+    /// Midpoint of the lit book's best bid and offer for `symbol`, or None
+    /// while either side is empty.
+    fn midpoint(&self, symbol: &str, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.lit.borrow(py).midpoint(symbol))
+    }
+
+    /// Cross `order` against resting hidden orders at the lit midpoint and
+    /// rest any remainder. Orders are checked against the lit book's
+    /// instruments and precision; stop and auction orders are refused.
+    #[pyo3(name = "match", signature = (order, *, min_quantity = None))]
+    fn match_order(
+        &mut self,
+        order: PyRef<Order>,
+        min_quantity: Option<i64>,
+        py: Python<'_>,
+    ) -> PyResult<TradeBlotter> {
+        if order.stop_price.is_some() || order.auction.is_some() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "DarkBook accepts plain limit orders only",
+            ));
+        }
+        let min_quantity = min_quantity.unwrap_or(1);
+        if min_quantity <= 0 || min_quantity > order.quantity {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "min_quantity must be between 1 and the order quantity {}, got {}",
+                order.quantity, min_quantity
+            )));
+        }
+        let resting = self.orders.entry(order.symbol.clone()).or_default();
+        if resting.iter().any(|dark| dark.order.id == order.id) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Order {} is already resting in the dark book",
+                order.id
+            )));
+        }
+        let lit = self.lit.borrow(py);
+        lit.validate_order(&order)?;
+        let mut incoming = DarkOrder {
+            order: order.clone(),
+            min_quantity,
+        };
+        let trades = match lit.midpoint(&order.symbol) {
+            Some(price) => cross_at(resting, &mut incoming, price),
+            None => Vec::new(),
+        };
+        resting.retain(|dark| dark.order.quantity > 0);
+        if incoming.order.quantity > 0 {
+            resting.push(incoming.clone());
+        }
+        Ok(lit.blotter(incoming.order, trades))
+    }
+
+    /// Re-cross resting orders in `symbol` at the current lit midpoint, as
+    /// after the lit quote moves. Each order, oldest first, is matched
+    /// against those behind it and reported as the incoming side.
+    fn cross(&mut self, symbol: &str, py: Python<'_>) -> Vec<Trade> {
+        let (Some(price), Some(resting)) = (
+            self.lit.borrow(py).midpoint(symbol),
+            self.orders.get_mut(symbol),
+        ) else {
+            return Vec::new();
+        };
+        let mut trades = Vec::new();
+        for i in 0..resting.len() {
+            let (head, behind) = resting.split_at_mut(i + 1);
+            let incoming = &mut head[i];
+            if incoming.order.quantity > 0 {
+                trades.extend(cross_at(behind, incoming, price));
+            }
+        }
+        resting.retain(|dark| dark.order.quantity > 0);
+        trades
+    }
+
+    /// Withdraw a resting hidden order; KeyError if it is not resting.
+    fn cancel(&mut self, order: PyRef<Order>, py: Python<'_>) -> PyResult<()> {
+        let removed = self.orders.get_mut(&order.symbol).and_then(|resting| {
+            let idx = resting.iter().position(|dark| dark.order.id == order.id)?;
+            Some(resting.remove(idx))
+        });
+        match removed {
+            Some(_) => Ok(()),
+            None => Err(pyo3::exceptions::PyKeyError::new_err(
+                crate::order::uuid_to_py(py, order.id)?,
+            )),
+        }
+    }
+
+    /// Resting hidden orders in `symbol`, oldest first.
+    fn orders(&self, symbol: &str) -> Vec<Order> {
+        self.orders
+            .get(symbol)
+            .map(|resting| resting.iter().map(|dark| dark.order.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Side;
+
+    fn dark(side: Side, price: f64, quantity: i64, min_quantity: i64) -> DarkOrder {
+        DarkOrder {
+            order: Order::try_new(side, "X".to_string(), price, quantity).unwrap(),
+            min_quantity,
+        }
+    }
+
+    #[test]
+    fn crosses_respect_limits_and_minimum_quantity() {
+        let mid = Decimal::new(105, 1);
+        let mut resting = vec![
+            dark(Side::ASK, 11.0, 5, 1),
+            dark(Side::ASK, 10.0, 12, 10),
+            dark(Side::ASK, 10.0, 8, 1),
+        ];
+        let mut incoming = dark(Side::BID, 11.0, 6, 1);
+        let trades = cross_at(&mut resting, &mut incoming, mid);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].standing_order_id, resting[2].order.id);
+        assert_eq!((trades[0].fill_quantity, trades[0].fill_price), (6, mid));
+        assert_eq!(trades[0].standing_remaining, 2);
+        assert_eq!(incoming.order.quantity, 0);
+        assert_eq!(incoming.order.fills.quantity, 6);
+    }
+}
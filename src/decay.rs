@@ -0,0 +1,75 @@
+// ---------------------------------------------------------------------------
+// LiquidityDecay — simulated-time ageing of synthetic L2 levels
+// ---------------------------------------------------------------------------
+
+/// Quantity a synthetic level was last set to, and the clock time it was
+/// set (or last refreshed) at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelAnchor {
+    pub quantity: i64,
+    pub since: i64,
+}
+
+/// How synthetic levels age as the simulated clock advances.
+///
+/// With a `half_life` a level's quantity halves every `half_life` clock
+/// units after it was set; with `refresh_after` it returns to the set
+/// quantity every `refresh_after` units, as if the liquidity provider
+/// requoted. Trading only ever takes quantity away between refreshes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityDecay {
+    pub half_life: Option<i64>,
+    pub refresh_after: Option<i64>,
+}
+
+impl LiquidityDecay {
+    /// The level's anchor at `now`, moved forward past any refresh that
+    /// fell due, and the quantity it should show. `refreshed` is whether a
+    /// refresh fell due.
+    pub fn step(&self, anchor: LevelAnchor, now: i64) -> (LevelAnchor, i64, bool) {
+        let mut anchor = anchor;
+        let mut refreshed = false;
+        if let Some(period) = self.refresh_after {
+            let elapsed = now - anchor.since;
+            if elapsed >= period {
+                anchor.since += elapsed / period * period;
+                refreshed = true;
+            }
+        }
+        let quantity = match self.half_life {
+            Some(half_life) => {
+                let halvings = (now - anchor.since) as f64 / half_life as f64;
+                (anchor.quantity as f64 * 0.5f64.powf(halvings)).floor() as i64
+            }
+            None => anchor.quantity,
+        };
+        (anchor, quantity, refreshed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_halve_per_half_life_and_refill_on_refresh() {
+        let anchor = LevelAnchor {
+            quantity: 100,
+            since: 0,
+        };
+        let decay = LiquidityDecay {
+            half_life: Some(10),
+            refresh_after: None,
+        };
+        assert_eq!(decay.step(anchor, 10), (anchor, 50, false));
+        assert_eq!(decay.step(anchor, 25).1, 17);
+
+        let refresh = LiquidityDecay {
+            half_life: Some(10),
+            refresh_after: Some(30),
+        };
+        let (moved, quantity, refreshed) = refresh.step(anchor, 70);
+        assert_eq!((moved.since, quantity, refreshed), (60, 50, true));
+        assert!(!refresh.step(anchor, 29).2);
+    }
+}
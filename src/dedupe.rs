@@ -0,0 +1,83 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::trade::TradeBlotter;
+
+/// Keys remembered by a new book before the oldest are forgotten.
+pub const DEFAULT_DEDUPE_CAPACITY: usize = 10_000;
+
+// ---------------------------------------------------------------------------
+// DedupeCache — blotters of recent submissions by idempotency key
+// ---------------------------------------------------------------------------
+
+/// Bounded map from submission `dedupe_key` to the blotter it produced,
+/// evicting the oldest key once `capacity` is exceeded.
+#[derive(Debug)]
+pub struct DedupeCache {
+    capacity: usize,
+    blotters: HashMap<String, TradeBlotter>,
+    /// Keys in insertion order, oldest first.
+    arrivals: VecDeque<String>,
+}
+
+impl Default for DedupeCache {
+    fn default() -> Self {
+        DedupeCache::new(DEFAULT_DEDUPE_CAPACITY)
+    }
+}
+
+impl DedupeCache {
+    pub fn new(capacity: usize) -> Self {
+        DedupeCache {
+            capacity,
+            blotters: HashMap::new(),
+            arrivals: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&TradeBlotter> {
+        self.blotters.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, blotter: TradeBlotter) {
+        if self.blotters.insert(key.clone(), blotter).is_none() {
+            self.arrivals.push_back(key);
+        }
+        self.evict();
+    }
+
+    /// Change the bound, forgetting the oldest keys beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.arrivals.len() > self.capacity {
+            if let Some(oldest) = self.arrivals.pop_front() {
+                self.blotters.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{Order, Side};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn oldest_keys_are_evicted_past_capacity() {
+        let order = Order::try_new(Side::BID, "AAPL".to_string(), 10.0, 1).unwrap();
+        let blotter = TradeBlotter::from_rust(order, Vec::new(), Decimal::ONE, 2);
+        let mut cache = DedupeCache::new(2);
+        for key in ["a", "b", "c"] {
+            cache.insert(key.to_string(), blotter.clone());
+        }
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some() && cache.get("c").is_some());
+        cache.set_capacity(1);
+        assert_eq!(cache.blotters.len(), 1);
+        assert!(cache.get("c").is_some());
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+// ---------------------------------------------------------------------------
+// DisconnectSchedule — delayed cancel-on-disconnect
+// ---------------------------------------------------------------------------
+
+/// Sessions that have disconnected with a grace period, each with the
+/// simulated time at which its orders are cancelled unless it reconnects.
+#[derive(Clone, Debug, Default)]
+pub struct DisconnectSchedule {
+    deadlines: HashMap<String, i64>,
+}
+
+impl DisconnectSchedule {
+    /// Cancel `session`'s orders at `deadline`; a session already waiting
+    /// keeps the earlier of its deadlines.
+    pub fn schedule(&mut self, session: String, deadline: i64) {
+        let at = self.deadlines.entry(session).or_insert(deadline);
+        *at = (*at).min(deadline);
+    }
+
+    /// Call off `session`'s pending cancel; false if none was pending.
+    pub fn cancel(&mut self, session: &str) -> bool {
+        self.deadlines.remove(session).is_some()
+    }
+
+    /// Remove and return the sessions due by `now`, earliest first.
+    pub fn take_due(&mut self, now: i64) -> Vec<String> {
+        let mut due: Vec<(i64, String)> = self
+            .deadlines
+            .iter()
+            .filter(|(_, &deadline)| deadline <= now)
+            .map(|(session, &deadline)| (deadline, session.clone()))
+            .collect();
+        due.sort();
+        for (_, session) in &due {
+            self.deadlines.remove(session);
+        }
+        due.into_iter().map(|(_, session)| session).collect()
+    }
+
+    pub fn deadlines(&self) -> &HashMap<String, i64> {
+        &self.deadlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_fall_due_in_deadline_order_unless_reconnected() {
+        let mut schedule = DisconnectSchedule::default();
+        schedule.schedule("b".to_string(), 20);
+        schedule.schedule("a".to_string(), 15);
+        schedule.schedule("b".to_string(), 30);
+        schedule.schedule("c".to_string(), 10);
+        assert!(schedule.cancel("c"));
+        assert!(!schedule.cancel("c"));
+        assert!(schedule.take_due(14).is_empty());
+        assert_eq!(schedule.take_due(20), vec!["a", "b"]);
+        assert!(schedule.deadlines().is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::events::to_account_event;
+use crate::publisher::BusEvent;
+
+// ---------------------------------------------------------------------------
+// DropCopy — copies of executions and order events for chosen accounts
+// ---------------------------------------------------------------------------
+
+/// A drop-copy subscription: every order and trade event of orders booked
+/// to `accounts`, whoever submitted them, handed to a callable (as typed
+/// `Event`s with `account` set) and/or appended to a JSON-lines journal.
+#[derive(Debug)]
+pub struct DropCopy {
+    accounts: HashSet<String>,
+    sink: Option<PyObject>,
+    journal: Option<BufWriter<File>>,
+}
+
+impl DropCopy {
+    pub fn new(
+        accounts: HashSet<String>,
+        sink: Option<PyObject>,
+        path: Option<&str>,
+    ) -> PyResult<Self> {
+        if sink.is_none() && path.is_none() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "A drop copy needs a sink, a path, or both",
+            ));
+        }
+        let journal = path
+            .map(|path| File::options().create(true).append(true).open(path))
+            .transpose()?
+            .map(BufWriter::new);
+        Ok(DropCopy {
+            accounts,
+            sink,
+            journal,
+        })
+    }
+
+    pub fn covers(&self, account: &str) -> bool {
+        self.accounts.contains(account)
+    }
+
+    /// Hand one event copied for `account` to the sink and journal.
+    pub fn deliver(&mut self, py: Python<'_>, account: &str, event: &BusEvent) -> PyResult<()> {
+        if let Some(sink) = &self.sink {
+            sink.call1(py, (to_account_event(py, event, Some(account))?,))?;
+        }
+        if let Some(journal) = self.journal.as_mut() {
+            let record = event.to_py(py, true)?;
+            record.bind(py).set_item("account", account)?;
+            let line: String = py
+                .import("json")?
+                .call_method1("dumps", (record,))?
+                .extract()?;
+            writeln!(journal, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> PyResult<()> {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.flush()?;
+        }
+        Ok(())
+    }
+}
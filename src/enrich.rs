@@ -0,0 +1,271 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyMapping};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::order::{decimal_cls, decimal_to_py};
+use crate::trade::Trade;
+
+// ---------------------------------------------------------------------------
+// Trade enrichment — extra fields added to trades before they leave the book
+// ---------------------------------------------------------------------------
+
+/// A value of `Trade.extra`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExtraValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    Text(String),
+}
+
+impl ExtraValue {
+    pub fn from_py(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = value.py();
+        if let Ok(flag) = value.downcast::<pyo3::types::PyBool>() {
+            return Ok(ExtraValue::Bool(flag.is_true()));
+        }
+        if let Ok(int) = value.extract::<i64>() {
+            return Ok(ExtraValue::Int(int));
+        }
+        if let Ok(float) = value.downcast::<pyo3::types::PyFloat>() {
+            return Ok(ExtraValue::Float(float.value()));
+        }
+        if let Ok(text) = value.extract::<String>() {
+            return Ok(ExtraValue::Text(text));
+        }
+        if value.is_instance(decimal_cls(py)?.bind(py))? {
+            return Ok(ExtraValue::Decimal(crate::order::extract_decimal(
+                value, "extra",
+            )?));
+        }
+        Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "Trade extra values must be bool, int, float, str or Decimal, got {}",
+            value.get_type().name()?
+        )))
+    }
+
+    /// Python form; with `plain`, Decimals become strings.
+    pub fn to_py(&self, py: Python<'_>, plain: bool) -> PyResult<PyObject> {
+        Ok(match self {
+            ExtraValue::Bool(flag) => flag.into_pyobject(py)?.to_owned().into_any().unbind(),
+            ExtraValue::Int(int) => int.into_pyobject(py)?.into_any().unbind(),
+            ExtraValue::Float(float) => float.into_pyobject(py)?.into_any().unbind(),
+            ExtraValue::Decimal(value) if plain => {
+                value.to_string().into_pyobject(py)?.into_any().unbind()
+            }
+            ExtraValue::Decimal(value) => decimal_to_py(py, *value)?,
+            ExtraValue::Text(text) => text.into_pyobject(py)?.into_any().unbind(),
+        })
+    }
+}
+
+/// Fields enrichers added to a trade, by name.
+pub type Extras = BTreeMap<String, ExtraValue>;
+
+pub fn extras_to_py<'py>(
+    py: Python<'py>,
+    extras: &Extras,
+    plain: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (key, value) in extras {
+        dict.set_item(key, value.to_py(py, plain)?)?;
+    }
+    Ok(dict)
+}
+
+/// What one enricher adds.
+#[derive(Debug)]
+pub enum Enricher {
+    /// `incoming_fee` and `standing_fee`: the fill's notional times the
+    /// taker and maker rate. Both sides of an auction uncross pay the
+    /// taker rate.
+    Fees {
+        maker_rate: Decimal,
+        taker_rate: Decimal,
+    },
+    /// `venue`: a fixed venue name.
+    Venue(String),
+    /// `liquidity`: how the incoming order traded, "removed" in continuous
+    /// matching or "auction" in an uncross.
+    Liquidity,
+    /// A Python callable taking the Trade and returning a mapping of fields
+    /// to add, or None.
+    Python(PyObject),
+}
+
+impl Enricher {
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        match self {
+            Enricher::Fees {
+                maker_rate,
+                taker_rate,
+            } => Enricher::Fees {
+                maker_rate: *maker_rate,
+                taker_rate: *taker_rate,
+            },
+            Enricher::Venue(venue) => Enricher::Venue(venue.clone()),
+            Enricher::Liquidity => Enricher::Liquidity,
+            Enricher::Python(callable) => Enricher::Python(callable.clone_ref(py)),
+        }
+    }
+
+    fn enrich(&self, trade: &mut Trade, auction: bool) -> PyResult<()> {
+        match self {
+            Enricher::Fees {
+                maker_rate,
+                taker_rate,
+            } => {
+                let notional = trade.fill_price * Decimal::from(trade.fill_quantity);
+                let standing_rate = if auction { taker_rate } else { maker_rate };
+                trade.extra.insert(
+                    "incoming_fee".to_string(),
+                    ExtraValue::Decimal(notional * taker_rate),
+                );
+                trade.extra.insert(
+                    "standing_fee".to_string(),
+                    ExtraValue::Decimal(notional * standing_rate),
+                );
+            }
+            Enricher::Venue(venue) => {
+                trade
+                    .extra
+                    .insert("venue".to_string(), ExtraValue::Text(venue.clone()));
+            }
+            Enricher::Liquidity => {
+                let flag = if auction { "auction" } else { "removed" };
+                trade
+                    .extra
+                    .insert("liquidity".to_string(), ExtraValue::Text(flag.to_string()));
+            }
+            Enricher::Python(callable) => Python::with_gil(|py| -> PyResult<()> {
+                let fields = callable.call1(py, (Py::new(py, trade.clone())?,))?;
+                let fields = fields.bind(py);
+                if fields.is_none() {
+                    return Ok(());
+                }
+                let fields = fields.downcast::<PyMapping>()?;
+                for item in fields.items()?.iter() {
+                    let (key, value): (String, Bound<'_, PyAny>) = item.extract()?;
+                    trade.extra.insert(key, ExtraValue::from_py(&value)?);
+                }
+                Ok(())
+            })?,
+        }
+        Ok(())
+    }
+}
+
+/// A named enricher in the pipeline.
+#[derive(Debug)]
+struct Named {
+    name: String,
+    enricher: Enricher,
+}
+
+/// Enrichers in the order they were added; each sees the fields those
+/// before it added.
+#[derive(Debug, Default)]
+pub struct Enrichers {
+    pipeline: Vec<Named>,
+}
+
+impl Enrichers {
+    pub fn add(&mut self, name: String, enricher: Enricher) -> PyResult<()> {
+        if self.pipeline.iter().any(|e| e.name == name) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "An enricher named '{}' is already registered",
+                name
+            )));
+        }
+        self.pipeline.push(Named { name, enricher });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.pipeline.len();
+        self.pipeline.retain(|e| e.name != name);
+        self.pipeline.len() != before
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.pipeline.iter().map(|e| e.name.clone()).collect()
+    }
+
+    /// Run every trade through the pipeline. The trades have already
+    /// happened, so an enricher that raises is reported through
+    /// `sys.unraisablehook` and the trade keeps the fields added so far.
+    pub fn apply(&self, trades: &mut [Trade], auction: bool) {
+        if self.pipeline.is_empty() {
+            return;
+        }
+        for trade in trades {
+            for named in &self.pipeline {
+                if let Err(err) = named.enricher.enrich(trade, auction) {
+                    Python::with_gil(|py| err.write_unraisable(py, None));
+                }
+            }
+        }
+    }
+
+    pub fn clone_ref(&self, py: Python<'_>) -> Self {
+        Enrichers {
+            pipeline: self
+                .pipeline
+                .iter()
+                .map(|e| Named {
+                    name: e.name.clone(),
+                    enricher: e.enricher.clone_ref(py),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn built_in_enrichers_add_their_fields() {
+        let mut enrichers = Enrichers::default();
+        let fees = Enricher::Fees {
+            maker_rate: Decimal::new(-1, 3),
+            taker_rate: Decimal::new(2, 3),
+        };
+        enrichers.add("fees".to_string(), fees).unwrap();
+        enrichers
+            .add("venue".to_string(), Enricher::Venue("XNAS".to_string()))
+            .unwrap();
+        enrichers
+            .add("liquidity".to_string(), Enricher::Liquidity)
+            .unwrap();
+        assert!(enrichers
+            .add("venue".to_string(), Enricher::Liquidity)
+            .is_err());
+
+        let trade = || Trade::from_rust(Uuid::new_v4(), Uuid::new_v4(), 10, Decimal::from(50));
+        let mut trades = [trade()];
+        enrichers.apply(&mut trades, false);
+        let extra = &trades[0].extra;
+        assert_eq!(extra["incoming_fee"], ExtraValue::Decimal(Decimal::ONE));
+        assert_eq!(
+            extra["standing_fee"],
+            ExtraValue::Decimal(Decimal::new(-5, 1))
+        );
+        assert_eq!(extra["venue"], ExtraValue::Text("XNAS".to_string()));
+        assert_eq!(extra["liquidity"], ExtraValue::Text("removed".to_string()));
+
+        let mut uncrossed = [trade()];
+        enrichers.apply(&mut uncrossed, true);
+        let extra = &uncrossed[0].extra;
+        assert_eq!(extra["standing_fee"], ExtraValue::Decimal(Decimal::ONE));
+        assert_eq!(extra["liquidity"], ExtraValue::Text("auction".to_string()));
+
+        assert!(enrichers.remove("fees"));
+        assert_eq!(enrichers.names(), ["venue", "liquidity"]);
+    }
+}
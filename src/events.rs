@@ -0,0 +1,398 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::auction::TradingState;
+use crate::enrich::{extras_to_py, Extras};
+use crate::order::{decimal_to_py, uuid_to_py, Side};
+use crate::publisher::BusEvent;
+
+// ---------------------------------------------------------------------------
+// Event — typed book events delivered by set_publisher(encoding="event")
+// ---------------------------------------------------------------------------
+
+/// Base class of every book event. `kind` names the concrete event, as in
+/// the `type` field of the dict encoding: "order_accepted",
+/// "order_cancelled", "order_expired", "trade", "level", "state_changed",
+/// "quotes_pulled", "split" or "symbol_changed".
+#[pyclass(subclass)]
+#[derive(Clone, Debug)]
+pub struct Event {
+    kind: &'static str,
+    symbol: String,
+    account: Option<String>,
+}
+
+#[pymethods]
+impl Event {
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Account a drop copy delivered the event for; None elsewhere.
+    #[getter]
+    fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Event(kind={}, symbol={})", self.kind, self.symbol)
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+/// An order passed the book's checks and was accepted.
+#[pyclass(extends = Event)]
+pub struct OrderAccepted {
+    order_id: Uuid,
+    side: Side,
+    price: Decimal,
+    quantity: i64,
+}
+
+#[pymethods]
+impl OrderAccepted {
+    #[getter]
+    fn order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.order_id)
+    }
+
+    #[getter]
+    fn side(&self) -> Side {
+        self.side
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+}
+
+/// A working order was cancelled; `remaining` is what was still open.
+#[pyclass(extends = Event)]
+pub struct OrderCancelled {
+    order_id: Uuid,
+    remaining: i64,
+}
+
+#[pymethods]
+impl OrderCancelled {
+    #[getter]
+    fn order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.order_id)
+    }
+
+    #[getter]
+    fn remaining(&self) -> i64 {
+        self.remaining
+    }
+}
+
+/// A good-till-date order reached its expiry on the simulated clock.
+#[pyclass(extends = Event)]
+pub struct OrderExpired {
+    order_id: Uuid,
+    remaining: i64,
+}
+
+#[pymethods]
+impl OrderExpired {
+    #[getter]
+    fn order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.order_id)
+    }
+
+    #[getter]
+    fn remaining(&self) -> i64 {
+        self.remaining
+    }
+}
+
+/// One execution.
+#[pyclass(extends = Event)]
+pub struct TradeExecuted {
+    trade_id: Uuid,
+    incoming_order_id: Uuid,
+    standing_order_id: Uuid,
+    price: Decimal,
+    quantity: i64,
+    extra: Extras,
+}
+
+#[pymethods]
+impl TradeExecuted {
+    #[getter]
+    fn trade_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.trade_id)
+    }
+
+    #[getter]
+    fn incoming_order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.incoming_order_id)
+    }
+
+    #[getter]
+    fn standing_order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.standing_order_id)
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    /// Fields the book's trade enrichers added.
+    #[getter]
+    fn extra<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        extras_to_py(py, &self.extra, false)
+    }
+}
+
+/// New aggregate quantity at a price level; 0 means the level is gone.
+#[pyclass(extends = Event)]
+pub struct LevelChanged {
+    side: Side,
+    price: Decimal,
+    quantity: i64,
+}
+
+#[pymethods]
+impl LevelChanged {
+    #[getter]
+    fn side(&self) -> Side {
+        self.side
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+}
+
+/// A symbol moved to a new trading phase.
+#[pyclass(extends = Event)]
+pub struct StateChanged {
+    state: TradingState,
+}
+
+#[pymethods]
+impl StateChanged {
+    #[getter]
+    fn state(&self) -> TradingState {
+        self.state
+    }
+}
+
+/// An account's quotes were pulled after too many fills in too short a
+/// window; `symbol` is where the tripping fill printed.
+#[pyclass(extends = Event)]
+pub struct QuotesPulled {
+    account: String,
+    fills: usize,
+    cancelled: usize,
+}
+
+#[pymethods]
+impl QuotesPulled {
+    #[getter]
+    fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Fills inside the protection window when it tripped.
+    #[getter]
+    fn fills(&self) -> usize {
+        self.fills
+    }
+
+    /// Quote orders cancelled.
+    #[getter]
+    fn cancelled(&self) -> usize {
+        self.cancelled
+    }
+}
+
+/// A symbol's orders were restated for a stock split: prices divided by
+/// `ratio` and quantities multiplied by it.
+#[pyclass(extends = Event)]
+pub struct SplitApplied {
+    ratio: Decimal,
+}
+
+#[pymethods]
+impl SplitApplied {
+    /// New shares per old share; below 1 for a reverse split.
+    #[getter]
+    fn ratio(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.ratio)
+    }
+}
+
+/// A symbol was renamed; `symbol` is the old name.
+#[pyclass(extends = Event)]
+pub struct SymbolChanged {
+    new_symbol: String,
+}
+
+#[pymethods]
+impl SymbolChanged {
+    #[getter]
+    fn new_symbol(&self) -> &str {
+        &self.new_symbol
+    }
+}
+
+fn new_event<T: pyo3::PyClass<BaseType = Event>>(
+    py: Python<'_>,
+    kind: &'static str,
+    symbol: &str,
+    account: Option<&str>,
+    event: T,
+) -> PyResult<PyObject> {
+    let base = Event {
+        kind,
+        symbol: symbol.to_string(),
+        account: account.map(str::to_string),
+    };
+    let init = PyClassInitializer::from(base).add_subclass(event);
+    Ok(Py::new(py, init)?.into_any())
+}
+
+/// The typed Python form of a bus event.
+pub fn to_event(py: Python<'_>, event: &BusEvent) -> PyResult<PyObject> {
+    to_account_event(py, event, None)
+}
+
+/// The typed Python form of a bus event copied for `account`.
+pub fn to_account_event(
+    py: Python<'_>,
+    event: &BusEvent,
+    account: Option<&str>,
+) -> PyResult<PyObject> {
+    let kind = event.kind();
+    match event.clone() {
+        BusEvent::OrderAccepted {
+            symbol,
+            order_id,
+            side,
+            price,
+            quantity,
+        } => {
+            let accepted = OrderAccepted {
+                order_id,
+                side,
+                price,
+                quantity,
+            };
+            new_event(py, kind, &symbol, account, accepted)
+        }
+        BusEvent::OrderCancelled {
+            symbol,
+            order_id,
+            remaining,
+        } => new_event(
+            py,
+            kind,
+            &symbol,
+            account,
+            OrderCancelled {
+                order_id,
+                remaining,
+            },
+        ),
+        BusEvent::OrderExpired {
+            symbol,
+            order_id,
+            remaining,
+        } => new_event(
+            py,
+            kind,
+            &symbol,
+            account,
+            OrderExpired {
+                order_id,
+                remaining,
+            },
+        ),
+        BusEvent::Trade {
+            symbol,
+            trade_id,
+            incoming_order_id,
+            standing_order_id,
+            price,
+            quantity,
+            extra,
+        } => {
+            let trade = TradeExecuted {
+                trade_id,
+                incoming_order_id,
+                standing_order_id,
+                price,
+                quantity,
+                extra,
+            };
+            new_event(py, kind, &symbol, account, trade)
+        }
+        BusEvent::Level {
+            symbol,
+            side,
+            price,
+            quantity,
+        } => {
+            let level = LevelChanged {
+                side,
+                price,
+                quantity,
+            };
+            new_event(py, kind, &symbol, account, level)
+        }
+        BusEvent::StateChanged { symbol, state } => {
+            new_event(py, kind, &symbol, account, StateChanged { state })
+        }
+        BusEvent::QuotesPulled {
+            symbol,
+            account: quoting_account,
+            fills,
+            cancelled,
+        } => {
+            let pulled = QuotesPulled {
+                account: quoting_account,
+                fills,
+                cancelled,
+            };
+            new_event(py, kind, &symbol, account, pulled)
+        }
+        BusEvent::Split { symbol, ratio } => {
+            new_event(py, kind, &symbol, account, SplitApplied { ratio })
+        }
+        BusEvent::SymbolChanged { symbol, new_symbol } => {
+            new_event(py, kind, &symbol, account, SymbolChanged { new_symbol })
+        }
+    }
+}
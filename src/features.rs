@@ -0,0 +1,246 @@
+use pyo3::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+use crate::snapshot::{Snapshot, SnapshotLevel};
+
+// ---------------------------------------------------------------------------
+// Features — online microstructure features of one symbol
+// ---------------------------------------------------------------------------
+
+/// One feature of `Book.enable_features`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// Best ask less best bid.
+    Spread,
+    /// Halfway between the best bid and ask.
+    Mid,
+    /// Best bid and ask weighted by the size on the opposite side.
+    Microprice,
+    /// (bid size - ask size) / (bid size + ask size) over the top levels.
+    Imbalance(usize),
+    /// Order flow imbalance at the best bid and offer, summed over the
+    /// trailing window of clock units.
+    Ofi(i64),
+}
+
+impl Feature {
+    /// Parse a name such as `"spread"`, `"imbalance_5"` or `"ofi_1s"`.
+    /// Windows with a time suffix (ns, us, ms, s) are converted to clock
+    /// units of `clock_unit`; bare numbers are clock units.
+    pub fn parse(name: &str, clock_unit: &str) -> PyResult<Self> {
+        let invalid = || {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid feature '{}'. Expected 'spread', 'mid', 'microprice', \
+                 'imbalance_<levels>' or 'ofi_<window>'.",
+                name
+            ))
+        };
+        let feature = match name.split_once('_') {
+            None => match name {
+                "spread" => Feature::Spread,
+                "mid" => Feature::Mid,
+                "microprice" => Feature::Microprice,
+                _ => return Err(invalid()),
+            },
+            Some(("imbalance", levels)) => {
+                Feature::Imbalance(levels.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?)
+            }
+            Some(("ofi", window)) => {
+                let window = parse_window(window, clock_unit)?.ok_or_else(invalid)?;
+                Feature::Ofi(window)
+            }
+            Some(_) => return Err(invalid()),
+        };
+        Ok(feature)
+    }
+}
+
+/// Nanoseconds in one `unit`.
+fn nanos(unit: &str) -> Option<i64> {
+    match unit {
+        "ns" => Some(1),
+        "us" => Some(1_000),
+        "ms" => Some(1_000_000),
+        "s" => Some(1_000_000_000),
+        _ => None,
+    }
+}
+
+/// A positive window like `"500"` or `"1s"` in clock units; `Ok(None)` if
+/// it does not parse.
+fn parse_window(window: &str, clock_unit: &str) -> PyResult<Option<i64>> {
+    let clock = nanos(clock_unit).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Invalid clock_unit '{}'. Expected 'ns', 'us', 'ms' or 's'.",
+            clock_unit
+        ))
+    })?;
+    let digits = window.len()
+        - window
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    let (count, unit) = window.split_at(digits);
+    let Ok(count) = count.parse::<i64>() else {
+        return Ok(None);
+    };
+    let units = match unit {
+        "" => Some(count),
+        unit => nanos(unit).map(|n| count * n / clock),
+    };
+    Ok(units.filter(|&units| units > 0))
+}
+
+/// Best bid and ask as (price, size), each when present.
+pub(crate) type Touch = [Option<(f64, f64)>; 2];
+
+/// Order flow imbalance between two touches (Cont, Kukanov and Stoikov):
+/// size added to the bid or taken from the ask counts positive.
+pub(crate) fn order_flow(before: &Touch, after: &Touch) -> f64 {
+    let flow =
+        |before: Option<(f64, f64)>, after: Option<(f64, f64)>, bid: bool| match (before, after) {
+            (Some((p0, q0)), Some((p1, q1))) => {
+                let improved = if bid { p1 >= p0 } else { p1 <= p0 };
+                let retreated = if bid { p1 <= p0 } else { p1 >= p0 };
+                (if improved { q1 } else { 0.0 }) - (if retreated { q0 } else { 0.0 })
+            }
+            (before, after) => after.map_or(0.0, |a| a.1) - before.map_or(0.0, |b| b.1),
+        };
+    flow(before[0], after[0], true) - flow(before[1], after[1], false)
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Enabled features of one symbol and the rolling state they need.
+#[derive(Clone, Debug)]
+pub struct FeatureSet {
+    pub names: Vec<String>,
+    features: Vec<Feature>,
+    touch: Touch,
+    /// (time, order flow) of each touch change inside the longest window.
+    flows: VecDeque<(i64, f64)>,
+}
+
+impl FeatureSet {
+    pub fn new(names: Vec<String>, clock_unit: &str) -> PyResult<Self> {
+        let features = names
+            .iter()
+            .map(|name| Feature::parse(name, clock_unit))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(FeatureSet {
+            names,
+            features,
+            touch: [None, None],
+            flows: VecDeque::new(),
+        })
+    }
+
+    /// Levels per side the current values need.
+    pub fn depth(&self) -> usize {
+        self.features
+            .iter()
+            .map(|feature| match feature {
+                Feature::Imbalance(levels) => *levels,
+                _ => 1,
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn longest_window(&self) -> Option<i64> {
+        self.features
+            .iter()
+            .filter_map(|feature| match feature {
+                Feature::Ofi(window) => Some(*window),
+                _ => None,
+            })
+            .max()
+    }
+
+    /// Take in the book's best levels after a change at clock `now` (0
+    /// before the clock starts).
+    pub fn observe(&mut self, now: i64, bid: Option<(Decimal, i64)>, ask: Option<(Decimal, i64)>) {
+        let touch = [bid, ask].map(|side| side.map(|(p, q)| (to_f64(p), q as f64)));
+        let Some(window) = self.longest_window() else {
+            self.touch = touch;
+            return;
+        };
+        if touch != self.touch {
+            self.flows.push_back((now, order_flow(&self.touch, &touch)));
+            self.touch = touch;
+        }
+        while self.flows.front().is_some_and(|&(t, _)| t <= now - window) {
+            self.flows.pop_front();
+        }
+    }
+
+    /// Current value of every feature, in order, from `snapshot` (at
+    /// least `depth()` deep) and the flows up to clock `now`. Undefined
+    /// values, such as the spread of a one-sided book, are NaN.
+    pub fn values(&self, now: i64, snapshot: Option<&Snapshot>) -> Vec<f64> {
+        let size = |level: &SnapshotLevel| to_f64(Decimal::from(level.quantity) * level.unit);
+        let total =
+            |levels: &[SnapshotLevel], n: usize| -> f64 { levels.iter().take(n).map(size).sum() };
+        let best = |levels: &[SnapshotLevel]| levels.first().map(|l| (to_f64(l.price), size(l)));
+        let bids = snapshot.map_or(&[][..], |s| &s.bids);
+        let asks = snapshot.map_or(&[][..], |s| &s.asks);
+        let touch = (best(bids), best(asks));
+        self.features
+            .iter()
+            .map(|feature| match (feature, touch) {
+                (Feature::Spread, (Some((bid, _)), Some((ask, _)))) => ask - bid,
+                (Feature::Mid, (Some((bid, _)), Some((ask, _)))) => (bid + ask) / 2.0,
+                (Feature::Microprice, (Some((bid, bq)), Some((ask, aq)))) => {
+                    (bid * aq + ask * bq) / (bq + aq)
+                }
+                (Feature::Imbalance(n), _) => {
+                    let (bid, ask) = (total(bids, *n), total(asks, *n));
+                    if bid + ask == 0.0 {
+                        f64::NAN
+                    } else {
+                        (bid - ask) / (bid + ask)
+                    }
+                }
+                (Feature::Ofi(window), _) => self
+                    .flows
+                    .iter()
+                    .filter(|&&(t, _)| t > now - window)
+                    .map(|&(_, flow)| flow)
+                    .sum(),
+                _ => f64::NAN,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_parse_with_windows_in_clock_units() {
+        assert_eq!(
+            Feature::parse("imbalance_5", "ns").unwrap(),
+            Feature::Imbalance(5)
+        );
+        assert_eq!(Feature::parse("ofi_1s", "ms").unwrap(), Feature::Ofi(1_000));
+        assert_eq!(Feature::parse("ofi_250", "ms").unwrap(), Feature::Ofi(250));
+        assert!(Feature::parse("ofi_1ms", "s").is_err());
+        assert!(Feature::parse("imbalance_0", "ns").is_err());
+        assert!(Feature::parse("vwap", "ns").is_err());
+    }
+
+    #[test]
+    fn order_flow_counts_bid_adds_and_ask_takes() {
+        let touch = |bid: (f64, f64), ask: (f64, f64)| [Some(bid), Some(ask)];
+        let start = touch((10.0, 5.0), (11.0, 5.0));
+        // More size at the same bid, and the ask lifted to a higher price.
+        assert_eq!(order_flow(&start, &touch((10.0, 8.0), (11.0, 5.0))), 3.0);
+        assert_eq!(order_flow(&start, &touch((10.0, 5.0), (12.0, 2.0))), 5.0);
+        // The bid drops a level.
+        assert_eq!(order_flow(&start, &touch((9.0, 4.0), (11.0, 5.0))), -5.0);
+    }
+}
@@ -0,0 +1,231 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::order::{extract_decimal, Side};
+
+// ---------------------------------------------------------------------------
+// FeedUpdate — normalized L2/L3 market-data messages
+// ---------------------------------------------------------------------------
+
+/// One normalized market-data message, parsed from a dict such as
+/// `{"type": "change_level", "symbol": "BTC-USD", "side": "bid",
+/// "price": "64000.5", "quantity": 3}`.
+///
+/// Level messages (`add_level`, `change_level`, `delete_level`) maintain
+/// aggregated L2 depth; order messages (`add_order`, `cancel_order`,
+/// `execute_order`) maintain individual L3 orders keyed by the venue's
+/// `order_id`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeedUpdate {
+    SetLevel {
+        symbol: String,
+        side: Side,
+        price: Decimal,
+        quantity: i64,
+    },
+    AddOrder {
+        order_id: String,
+        symbol: String,
+        side: Side,
+        price: Decimal,
+        quantity: i64,
+    },
+    /// Cancel or execute part of an order. `quantity = None` removes
+    /// whatever is left.
+    ReduceOrder {
+        order_id: String,
+        quantity: Option<i64>,
+        executed: bool,
+    },
+}
+
+fn required<'py>(msg: &Bound<'py, PyDict>, field: &str) -> PyResult<Bound<'py, pyo3::PyAny>> {
+    msg.get_item(field)?.ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Feed update is missing required field '{}'",
+            field
+        ))
+    })
+}
+
+fn optional<'py>(
+    msg: &Bound<'py, PyDict>,
+    field: &str,
+) -> PyResult<Option<Bound<'py, pyo3::PyAny>>> {
+    Ok(msg.get_item(field)?.filter(|value| !value.is_none()))
+}
+
+fn extract_side(value: &Bound<'_, pyo3::PyAny>) -> PyResult<Side> {
+    if let Ok(side) = value.extract::<Side>() {
+        return Ok(side);
+    }
+    let text: String = value.str()?.extract()?;
+    match text.to_ascii_lowercase().as_str() {
+        "bid" | "buy" => Ok(Side::BID),
+        "ask" | "sell" => Ok(Side::ASK),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Invalid side '{}'. Expected 'bid' or 'ask'.",
+            text
+        ))),
+    }
+}
+
+impl FeedUpdate {
+    pub fn from_dict(msg: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let kind: String = required(msg, "type")?.extract()?;
+        let symbol = || -> PyResult<String> { required(msg, "symbol")?.extract() };
+        let side = || extract_side(&required(msg, "side")?);
+        let price = || extract_decimal(&required(msg, "price")?, "price");
+        let order_id = || -> PyResult<String> { required(msg, "order_id")?.str()?.extract() };
+        match kind.as_str() {
+            "add_level" | "change_level" => Ok(FeedUpdate::SetLevel {
+                symbol: symbol()?,
+                side: side()?,
+                price: price()?,
+                quantity: required(msg, "quantity")?.extract()?,
+            }),
+            "delete_level" => Ok(FeedUpdate::SetLevel {
+                symbol: symbol()?,
+                side: side()?,
+                price: price()?,
+                quantity: 0,
+            }),
+            "add_order" => Ok(FeedUpdate::AddOrder {
+                order_id: order_id()?,
+                symbol: symbol()?,
+                side: side()?,
+                price: price()?,
+                quantity: required(msg, "quantity")?.extract()?,
+            }),
+            "cancel_order" | "execute_order" => Ok(FeedUpdate::ReduceOrder {
+                order_id: order_id()?,
+                quantity: optional(msg, "quantity")?
+                    .map(|q| q.extract())
+                    .transpose()?,
+                executed: kind == "execute_order",
+            }),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown feed update type '{}'",
+                kind
+            ))),
+        }
+    }
+}
+
+/// Optional `sequence` number carried by a feed message.
+pub fn message_sequence(msg: &Bound<'_, PyDict>) -> PyResult<Option<u64>> {
+    optional(msg, "sequence")?.map(|s| s.extract()).transpose()
+}
+
+/// Optional `symbol` carried by a feed message.
+pub fn message_symbol(msg: &Bound<'_, PyDict>) -> PyResult<Option<String>> {
+    optional(msg, "symbol")?.map(|s| s.extract()).transpose()
+}
+
+// ---------------------------------------------------------------------------
+// SequenceTracker — per-symbol gap detection and reordering
+// ---------------------------------------------------------------------------
+
+/// Sequence-number bookkeeping for one symbol's delta feed.
+///
+/// Messages ahead of the expected sequence are buffered and the symbol is
+/// flagged as gapped; when the missing messages arrive (or the symbol is
+/// resynced from a snapshot) the buffered run is released in order.
+#[derive(Clone, Debug, Default)]
+pub struct SequenceTracker {
+    pub last: Option<u64>,
+    buffered: BTreeMap<u64, FeedUpdate>,
+}
+
+impl SequenceTracker {
+    /// Accept message `seq`, returning the updates now ready to apply in
+    /// sequence order. Duplicates and stale messages are dropped.
+    pub fn admit(&mut self, seq: u64, update: FeedUpdate) -> Vec<FeedUpdate> {
+        match self.last {
+            Some(last) if seq <= last => Vec::new(),
+            Some(last) if seq > last + 1 => {
+                self.buffered.insert(seq, update);
+                Vec::new()
+            }
+            _ => {
+                self.last = Some(seq);
+                let mut ready = vec![update];
+                ready.extend(self.drain_contiguous());
+                ready
+            }
+        }
+    }
+
+    /// Restart the sequence at a snapshot taken as of `seq`, returning the
+    /// buffered updates that follow it contiguously.
+    pub fn resync(&mut self, seq: u64) -> Vec<FeedUpdate> {
+        self.last = Some(seq);
+        self.buffered = self.buffered.split_off(&(seq + 1));
+        self.drain_contiguous()
+    }
+
+    /// `(expected, received)` while messages are missing, else `None`.
+    pub fn gap(&self) -> Option<(u64, u64)> {
+        let (&next, _) = self.buffered.first_key_value()?;
+        Some((self.last.map_or(0, |l| l + 1), next))
+    }
+
+    fn drain_contiguous(&mut self) -> Vec<FeedUpdate> {
+        let mut ready = Vec::new();
+        while let Some(last) = self.last {
+            match self.buffered.remove(&(last + 1)) {
+                Some(update) => {
+                    ready.push(update);
+                    self.last = Some(last + 1);
+                }
+                None => break,
+            }
+        }
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(quantity: i64) -> FeedUpdate {
+        FeedUpdate::SetLevel {
+            symbol: "X".to_string(),
+            side: Side::BID,
+            price: Decimal::ONE,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn tracker_buffers_out_of_order_messages_until_gap_fills() {
+        let mut tracker = SequenceTracker::default();
+        assert_eq!(tracker.admit(10, level(1)), vec![level(1)]);
+        assert!(tracker.admit(12, level(3)).is_empty());
+        assert!(tracker.admit(13, level(4)).is_empty());
+        assert_eq!(tracker.gap(), Some((11, 12)));
+        assert!(tracker.admit(10, level(9)).is_empty());
+
+        assert_eq!(
+            tracker.admit(11, level(2)),
+            vec![level(2), level(3), level(4)]
+        );
+        assert_eq!(tracker.gap(), None);
+        assert_eq!(tracker.last, Some(13));
+    }
+
+    #[test]
+    fn resync_discards_stale_buffer_and_releases_the_rest() {
+        let mut tracker = SequenceTracker::default();
+        tracker.admit(1, level(1));
+        tracker.admit(5, level(5));
+        tracker.admit(6, level(6));
+        tracker.admit(9, level(9));
+
+        assert_eq!(tracker.resync(5), vec![level(6)]);
+        assert_eq!(tracker.gap(), Some((7, 9)));
+    }
+}
@@ -0,0 +1,170 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+
+use crate::features::order_flow;
+use crate::order::Side;
+
+// ---------------------------------------------------------------------------
+// OrderFlow — per-event order flow imbalance and classified trade flow
+// ---------------------------------------------------------------------------
+
+/// Column names of `Book.order_flow`, in order.
+pub const COLUMNS: [&str; 8] = [
+    "time",
+    "ofi",
+    "buy_volume",
+    "sell_volume",
+    "signed_volume",
+    "mid",
+    "bid_size",
+    "ask_size",
+];
+
+/// Lee-Ready side of a trade at `price`: the quote test against the
+/// midpoint prevailing before it, else the tick test against earlier
+/// trades (`tick` is the direction of the last price change). None when
+/// neither decides.
+pub fn lee_ready(price: Decimal, mid: Option<Decimal>, tick: Ordering) -> Option<Side> {
+    let quote = mid.map_or(Ordering::Equal, |mid| price.cmp(&mid));
+    match quote.then(tick) {
+        Ordering::Greater => Some(Side::BID),
+        Ordering::Less => Some(Side::ASK),
+        Ordering::Equal => None,
+    }
+}
+
+/// Best bid and ask as (price, size), each when present.
+type Quote = [Option<(Decimal, i64)>; 2];
+
+fn midpoint(quote: &Quote) -> Option<Decimal> {
+    match quote {
+        [Some((bid, _)), Some((ask, _))] => Some((bid + ask) / Decimal::TWO),
+        _ => None,
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Order flow of one symbol: a row per event that moved its quote or
+/// traded, in the columns of [`COLUMNS`].
+#[derive(Clone, Debug, Default)]
+pub struct OrderFlow {
+    quote: Quote,
+    last_price: Option<Decimal>,
+    /// Direction of the last trade-to-trade price change.
+    tick: Option<Ordering>,
+    /// Trades since the last row, as (price, quantity).
+    pending: Vec<(Decimal, i64)>,
+    columns: [Vec<f64>; 8],
+}
+
+impl OrderFlow {
+    pub fn new(bid: Option<(Decimal, i64)>, ask: Option<(Decimal, i64)>) -> Self {
+        OrderFlow {
+            quote: [bid, ask],
+            ..OrderFlow::default()
+        }
+    }
+
+    /// Hold trades until the event that made them is observed.
+    pub fn trade(&mut self, price: Decimal, quantity: i64) {
+        self.pending.push((price, quantity));
+    }
+
+    /// Close an event at clock `now` that left the quote at `bid`/`ask`:
+    /// classify its trades against the quote before it and record the
+    /// change in quote as order flow imbalance.
+    pub fn observe(&mut self, now: i64, bid: Option<(Decimal, i64)>, ask: Option<(Decimal, i64)>) {
+        let quote = [bid, ask];
+        if quote == self.quote && self.pending.is_empty() {
+            return;
+        }
+        let mid = midpoint(&self.quote);
+        let (mut buys, mut sells) = (0, 0);
+        for (price, quantity) in std::mem::take(&mut self.pending) {
+            if let Some(last) = self.last_price {
+                if price != last {
+                    self.tick = Some(price.cmp(&last));
+                }
+            }
+            self.last_price = Some(price);
+            match lee_ready(price, mid, self.tick.unwrap_or(Ordering::Equal)) {
+                Some(Side::BID) => buys += quantity,
+                Some(Side::ASK) => sells += quantity,
+                None => {}
+            }
+        }
+        let as_f64 = |q: &Quote| q.map(|side| side.map(|(p, n)| (to_f64(p), n as f64)));
+        let ofi = order_flow(&as_f64(&self.quote), &as_f64(&quote));
+        let row = [
+            now as f64,
+            ofi,
+            buys as f64,
+            sells as f64,
+            (buys - sells) as f64,
+            midpoint(&quote).map_or(f64::NAN, to_f64),
+            bid.map_or(0.0, |(_, size)| size as f64),
+            ask.map_or(0.0, |(_, size)| size as f64),
+        ];
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        self.quote = quote;
+    }
+
+    /// The recorded rows, column by column.
+    pub fn columns(&self) -> &[Vec<f64>; 8] {
+        &self.columns
+    }
+
+    /// Forget the recorded rows, keeping the state new rows build on.
+    pub fn clear(&mut self) {
+        for column in &mut self.columns {
+            column.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trades_are_classified_by_quote_then_tick() {
+        let px = |n: i64| Decimal::from(n);
+        assert_eq!(
+            lee_ready(px(11), Some(px(10)), Ordering::Less),
+            Some(Side::BID)
+        );
+        assert_eq!(
+            lee_ready(px(9), Some(px(10)), Ordering::Greater),
+            Some(Side::ASK)
+        );
+        assert_eq!(
+            lee_ready(px(10), Some(px(10)), Ordering::Greater),
+            Some(Side::BID)
+        );
+        assert_eq!(lee_ready(px(10), None, Ordering::Equal), None);
+
+        let mut flow = OrderFlow::new(Some((px(99), 5)), Some((px(101), 5)));
+        // A buyer lifts 3 of the offer.
+        flow.trade(px(101), 3);
+        flow.observe(7, Some((px(99), 5)), Some((px(101), 2)));
+        // Nothing changed: no row.
+        flow.observe(8, Some((px(99), 5)), Some((px(101), 2)));
+        let columns = flow.columns();
+        assert_eq!(columns[0], [7.0]);
+        assert_eq!(columns[1], [3.0]);
+        assert_eq!(
+            (columns[2][0], columns[3][0], columns[4][0]),
+            (3.0, 0.0, 3.0)
+        );
+        assert_eq!(columns[5], [100.0]);
+        assert_eq!((columns[6][0], columns[7][0]), (5.0, 2.0));
+        flow.clear();
+        assert!(flow.columns()[0].is_empty());
+    }
+}
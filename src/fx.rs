@@ -0,0 +1,114 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+// ---------------------------------------------------------------------------
+// FxTable — exchange rates into a base currency
+// ---------------------------------------------------------------------------
+
+/// Rates of each currency in units of the base currency, for reporting
+/// amounts from instruments in different currencies in one.
+#[derive(Clone, Debug, Default)]
+pub struct FxTable {
+    base: Option<String>,
+    /// Units of the base currency one unit of each currency is worth.
+    rates: HashMap<String, Decimal>,
+}
+
+impl FxTable {
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    /// Report in `base` from now on. Rates quoted against another base
+    /// no longer apply and are dropped.
+    pub fn set_base(&mut self, base: String) {
+        if self.base.as_ref() != Some(&base) {
+            self.rates.clear();
+        }
+        self.base = Some(base);
+    }
+
+    pub fn set_rate(&mut self, currency: String, rate: Decimal) -> PyResult<()> {
+        let Some(base) = self.base.as_deref() else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "No base currency is set; call set_base_currency() first",
+            ));
+        };
+        if rate <= Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "FX rate must be greater than zero",
+            ));
+        }
+        if currency == base {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{} is the base currency; its rate is always 1",
+                currency
+            )));
+        }
+        self.rates.insert(currency, rate);
+        Ok(())
+    }
+
+    /// Units of the base currency one unit of `currency` is worth.
+    pub fn rate(&self, currency: &str) -> Option<Decimal> {
+        if self.base.as_deref() == Some(currency) {
+            return Some(Decimal::ONE);
+        }
+        self.rates.get(currency).copied()
+    }
+
+    /// `amount` of `from` in units of `to`, crossing through the base.
+    pub fn convert(&self, amount: Decimal, from: &str, to: &str) -> PyResult<Decimal> {
+        if from == to {
+            return Ok(amount);
+        }
+        let rate = |currency: &str| {
+            self.rate(currency).ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "No FX rate for {}; call set_fx_rate() first",
+                    currency
+                ))
+            })
+        };
+        Ok(amount * rate(from)? / rate(to)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_cross_through_the_base_currency() {
+        let mut fx = FxTable::default();
+        assert!(fx.set_rate("EUR".to_string(), Decimal::ONE).is_err());
+        fx.set_base("USD".to_string());
+        fx.set_rate("EUR".to_string(), Decimal::new(125, 2))
+            .unwrap();
+        fx.set_rate("GBP".to_string(), Decimal::new(150, 2))
+            .unwrap();
+        assert!(fx.set_rate("USD".to_string(), Decimal::TWO).is_err());
+        assert!(fx.set_rate("JPY".to_string(), Decimal::ZERO).is_err());
+
+        let hundred = Decimal::from(100);
+        assert_eq!(
+            fx.convert(hundred, "EUR", "USD").unwrap(),
+            Decimal::from(125)
+        );
+        assert_eq!(
+            fx.convert(hundred, "USD", "EUR").unwrap(),
+            Decimal::from(80)
+        );
+        assert_eq!(
+            fx.convert(Decimal::from(120), "GBP", "EUR").unwrap(),
+            Decimal::from(144)
+        );
+        assert!(fx.convert(hundred, "JPY", "USD").is_err());
+        assert_eq!(fx.convert(hundred, "JPY", "JPY").unwrap(), hundred);
+
+        fx.set_base("EUR".to_string());
+        assert_eq!(fx.rate("EUR"), Some(Decimal::ONE));
+        assert_eq!(fx.rate("GBP"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use rust_decimal::Decimal;
+
+// ---------------------------------------------------------------------------
+// StateHasher — FNV-1a over a canonical byte encoding
+// ---------------------------------------------------------------------------
+
+const FNV64_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV64_PRIME: u64 = 0x0000_0100_0000_01b3;
+const FNV128_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV128_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// Platform- and release-independent hasher for `Book.state_hash`.
+///
+/// FNV-1a is specified bit-for-bit, unlike `std`'s `DefaultHasher`, and
+/// every value is written in a fixed little-endian or length-prefixed form,
+/// so equal states hash equally everywhere.
+pub struct StateHasher {
+    h64: u64,
+    h128: u128,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        StateHasher {
+            h64: FNV64_OFFSET,
+            h128: FNV128_OFFSET,
+        }
+    }
+}
+
+impl StateHasher {
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.h64 = (self.h64 ^ u64::from(byte)).wrapping_mul(FNV64_PRIME);
+            self.h128 = (self.h128 ^ u128::from(byte)).wrapping_mul(FNV128_PRIME);
+        }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(u8::from(value));
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_len(&mut self, len: usize) {
+        self.write_bytes(&(len as u64).to_le_bytes());
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Decimals hash by value: `10.50` and `10.5` are the same price.
+    pub fn write_decimal(&mut self, value: Decimal) {
+        self.write_str(&value.normalize().to_string());
+    }
+
+    pub fn write_option<T>(&mut self, value: Option<T>, write: impl FnOnce(&mut Self, T)) {
+        match value {
+            Some(inner) => {
+                self.write_u8(1);
+                write(self, inner);
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    pub fn finish64(&self) -> u64 {
+        self.h64
+    }
+
+    pub fn finish128(&self) -> u128 {
+        self.h128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_published_fnv1a_vectors() {
+        let empty = StateHasher::default();
+        assert_eq!(empty.finish64(), 0xcbf2_9ce4_8422_2325);
+
+        let mut hasher = StateHasher::default();
+        hasher.write_bytes(b"a");
+        assert_eq!(hasher.finish64(), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(
+            hasher.finish128(),
+            0xd228_cb69_6f1a_8caf_7891_2b70_4e4a_8964
+        );
+    }
+
+    #[test]
+    fn decimals_hash_by_value() {
+        let mut a = StateHasher::default();
+        let mut b = StateHasher::default();
+        a.write_decimal(Decimal::new(1050, 2));
+        b.write_decimal(Decimal::new(105, 1));
+        assert_eq!(a.finish128(), b.finish128());
+    }
+}
@@ -0,0 +1,245 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::order::{decimal_to_py, uuid_to_py};
+
+/// Lifecycle step of an order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderEventKind {
+    Accepted,
+    PartiallyFilled,
+    Filled,
+    Amended,
+    Cancelled,
+    Expired,
+}
+
+impl OrderEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderEventKind::Accepted => "accepted",
+            OrderEventKind::PartiallyFilled => "partially_filled",
+            OrderEventKind::Filled => "filled",
+            OrderEventKind::Amended => "amended",
+            OrderEventKind::Cancelled => "cancelled",
+            OrderEventKind::Expired => "expired",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrderEvent — one recorded state change
+// ---------------------------------------------------------------------------
+
+/// One state change of an order, stamped with the simulated clock.
+///
+/// `quantity` is the quantity the event concerns: the accepted or amended
+/// size, the fill size, or what was left when cancelled or expired.
+/// `remaining` is the open quantity afterwards. Fills carry their
+/// `trade_id` and execution `price`; other events carry the order's price.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderEvent {
+    pub kind: OrderEventKind,
+    pub timestamp: Option<i64>,
+    pub price: Decimal,
+    pub quantity: i64,
+    pub remaining: i64,
+    pub trade_id: Option<Uuid>,
+}
+
+#[pymethods]
+impl OrderEvent {
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.kind.as_str()
+    }
+
+    #[getter]
+    fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    #[getter]
+    fn price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.price)
+    }
+
+    #[getter]
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    #[getter]
+    fn remaining(&self) -> i64 {
+        self.remaining
+    }
+
+    #[getter]
+    fn trade_id(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.trade_id.map(|id| uuid_to_py(py, id)).transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OrderEvent(kind={}, timestamp={:?}, price={}, quantity={}, remaining={})",
+            self.kind.as_str(),
+            self.timestamp,
+            self.price,
+            self.quantity,
+            self.remaining
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrderHistories — per-order event logs
+// ---------------------------------------------------------------------------
+
+/// Event logs of every order accepted while recording is enabled. Orders
+/// accepted before that are not tracked.
+#[derive(Clone, Debug, Default)]
+pub struct OrderHistories {
+    /// Order id -> (open quantity, events in order).
+    orders: HashMap<Uuid, (i64, Vec<OrderEvent>)>,
+}
+
+impl OrderHistories {
+    pub fn events(&self, order_id: Uuid) -> Option<&[OrderEvent]> {
+        self.orders
+            .get(&order_id)
+            .map(|(_, events)| events.as_slice())
+    }
+
+    /// Start a log; a no-op for orders already tracked, such as the
+    /// resubmission of an amended order.
+    pub fn accept(&mut self, order_id: Uuid, now: Option<i64>, price: Decimal, quantity: i64) {
+        self.orders.entry(order_id).or_insert_with(|| {
+            let event = OrderEvent {
+                kind: OrderEventKind::Accepted,
+                timestamp: now,
+                price,
+                quantity,
+                remaining: quantity,
+                trade_id: None,
+            };
+            (quantity, vec![event])
+        });
+    }
+
+    pub fn fill(
+        &mut self,
+        order_id: Uuid,
+        now: Option<i64>,
+        trade_id: Uuid,
+        price: Decimal,
+        quantity: i64,
+    ) {
+        let Some((open, events)) = self.orders.get_mut(&order_id) else {
+            return;
+        };
+        *open -= quantity;
+        events.push(OrderEvent {
+            kind: if *open > 0 {
+                OrderEventKind::PartiallyFilled
+            } else {
+                OrderEventKind::Filled
+            },
+            timestamp: now,
+            price,
+            quantity,
+            remaining: *open,
+            trade_id: Some(trade_id),
+        });
+    }
+
+    pub fn amend(&mut self, order_id: Uuid, now: Option<i64>, price: Decimal, quantity: i64) {
+        let Some((open, events)) = self.orders.get_mut(&order_id) else {
+            return;
+        };
+        *open = quantity;
+        events.push(OrderEvent {
+            kind: OrderEventKind::Amended,
+            timestamp: now,
+            price,
+            quantity,
+            remaining: quantity,
+            trade_id: None,
+        });
+    }
+
+    /// Drop a trailing amend whose replace was rejected, restoring the
+    /// open quantity it replaced.
+    pub fn retract_amend(&mut self, order_id: Uuid, open_before: i64) {
+        if let Some((open, events)) = self.orders.get_mut(&order_id) {
+            if events
+                .last()
+                .is_some_and(|e| e.kind == OrderEventKind::Amended)
+            {
+                events.pop();
+                *open = open_before;
+            }
+        }
+    }
+
+    /// Record a cancel or expiry of whatever is still open.
+    pub fn close(
+        &mut self,
+        order_id: Uuid,
+        now: Option<i64>,
+        kind: OrderEventKind,
+        price: Decimal,
+    ) {
+        let Some((open, events)) = self.orders.get_mut(&order_id) else {
+            return;
+        };
+        events.push(OrderEvent {
+            kind,
+            timestamp: now,
+            price,
+            quantity: *open,
+            remaining: 0,
+            trade_id: None,
+        });
+        *open = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifecycle_tracks_open_quantity() {
+        let mut history = OrderHistories::default();
+        let (id, price) = (Uuid::from_u64_pair(0, 1), Decimal::from(10));
+        history.accept(id, Some(1), price, 10);
+        history.fill(id, Some(2), Uuid::from_u64_pair(0, 2), price, 4);
+        history.amend(id, Some(3), price, 5);
+        history.accept(id, Some(3), price, 5);
+        history.retract_amend(id, 6);
+        history.close(id, Some(4), OrderEventKind::Cancelled, price);
+
+        let events = history.events(id).unwrap();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|e| (e.kind, e.quantity, e.remaining))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (OrderEventKind::Accepted, 10, 10),
+                (OrderEventKind::PartiallyFilled, 4, 6),
+                (OrderEventKind::Cancelled, 6, 0),
+            ]
+        );
+        assert!(history.events(Uuid::nil()).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::order::Side;
+
+/// Messages on a symbol within which an add still counts as a refill.
+pub const DEFAULT_REFILL_WINDOW: usize = 1;
+/// Refills at one price and size before it is reported as an iceberg.
+pub const DEFAULT_MIN_REFILLS: u32 = 2;
+
+// ---------------------------------------------------------------------------
+// IcebergDetector — likely iceberg refills in order-by-order feeds
+// ---------------------------------------------------------------------------
+
+/// A price and size that kept reappearing after executions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IcebergCandidate {
+    pub side: Side,
+    pub price: Decimal,
+    /// Size of each refill, i.e. the displayed peak.
+    pub size: i64,
+    /// Adds of `size` at `price` that followed an execution exhausting the
+    /// previous one.
+    pub refills: u32,
+}
+
+#[derive(Debug, Default)]
+struct SymbolWatch {
+    /// The last exhausted order's side, price and original size, and the
+    /// messages seen on the symbol since.
+    exhausted: Option<(Side, Decimal, i64, usize)>,
+    candidates: Vec<IcebergCandidate>,
+}
+
+impl SymbolWatch {
+    /// Count a message since the last execution, forgetting it once past
+    /// `window`.
+    fn age(&mut self, window: usize) {
+        if let Some((_, _, _, since)) = self.exhausted.as_mut() {
+            *since += 1;
+            if *since >= window {
+                self.exhausted = None;
+            }
+        }
+    }
+}
+
+/// Watches L3 feed messages for the signature of an iceberg: an order
+/// executed away, then an order of the same size added at the same price
+/// right after, over and over.
+#[derive(Debug)]
+pub struct IcebergDetector {
+    window: usize,
+    min_refills: u32,
+    symbols: HashMap<String, SymbolWatch>,
+}
+
+impl IcebergDetector {
+    pub fn new(window: usize, min_refills: u32) -> Self {
+        IcebergDetector {
+            window,
+            min_refills,
+            symbols: HashMap::new(),
+        }
+    }
+
+    /// An execution on `symbol` that left nothing of an order originally
+    /// of `size` at `price`.
+    pub fn exhausted(&mut self, symbol: &str, side: Side, price: Decimal, size: i64) {
+        let watch = self.symbols.entry(symbol.to_string()).or_default();
+        watch.exhausted = Some((side, price, size, 0));
+    }
+
+    /// An order added on `symbol`; a refill if it repeats the exhausted
+    /// order within the window.
+    pub fn added(&mut self, symbol: &str, side: Side, price: Decimal, size: i64) {
+        let window = self.window;
+        let watch = self.symbols.entry(symbol.to_string()).or_default();
+        let refill = matches!(
+            watch.exhausted,
+            Some((s, p, q, since)) if (s, p, q) == (side, price, size) && since < window
+        );
+        if !refill {
+            watch.age(window);
+            return;
+        }
+        // The refill itself may be executed away and refilled again.
+        watch.exhausted = None;
+        match watch
+            .candidates
+            .iter_mut()
+            .find(|c| (c.side, c.price, c.size) == (side, price, size))
+        {
+            Some(candidate) => candidate.refills += 1,
+            None => watch.candidates.push(IcebergCandidate {
+                side,
+                price,
+                size,
+                refills: 1,
+            }),
+        }
+    }
+
+    /// Any other message on `symbol`, which ages the last execution.
+    pub fn other(&mut self, symbol: &str) {
+        if let Some(watch) = self.symbols.get_mut(symbol) {
+            watch.age(self.window);
+        }
+    }
+
+    /// Candidates of `symbol` with at least the minimum refills, most
+    /// refilled first.
+    pub fn report(&self, symbol: &str) -> Vec<IcebergCandidate> {
+        let mut found: Vec<IcebergCandidate> = self
+            .symbols
+            .get(symbol)
+            .map(|watch| {
+                watch
+                    .candidates
+                    .iter()
+                    .filter(|c| c.refills >= self.min_refills)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        found.sort_by_key(|c| std::cmp::Reverse(c.refills));
+        found
+    }
+
+    /// Symbols with at least one reported candidate, sorted.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .symbols
+            .keys()
+            .filter(|symbol| !self.report(symbol).is_empty())
+            .cloned()
+            .collect();
+        symbols.sort();
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refills_count_only_right_after_an_exhausting_execution() {
+        let mut detector = IcebergDetector::new(1, 2);
+        let px = Decimal::from(100);
+        for _ in 0..3 {
+            detector.exhausted("X", Side::ASK, px, 50);
+            detector.added("X", Side::ASK, px, 50);
+        }
+        // Too late: another message came in between.
+        detector.exhausted("X", Side::ASK, px, 20);
+        detector.other("X");
+        detector.added("X", Side::ASK, px, 20);
+        // Different size.
+        detector.exhausted("X", Side::BID, px, 10);
+        detector.added("X", Side::BID, px, 11);
+
+        let report = detector.report("X");
+        assert_eq!(report.len(), 1);
+        assert_eq!((report[0].size, report[0].refills), (50, 3));
+        assert_eq!(detector.symbols(), ["X"]);
+        assert!(detector.report("Y").is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+use pyo3::prelude::*;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::order::{decimal_to_py, extract_decimal, Order};
+use crate::reject::RejectCode;
+
+// ---------------------------------------------------------------------------
+// Precision — decimal places for prices and quantities
+// ---------------------------------------------------------------------------
+
+/// Decimal places blotter statistics are rounded to when no precision is
+/// configured.
+pub const DEFAULT_PRICE_PRECISION: u32 = 2;
+
+/// Largest configurable precision, leaving headroom in `Decimal`'s 28 digits.
+const MAX_PRECISION: u32 = 18;
+
+/// Decimal places a book uses for one symbol.
+///
+/// Order prices may carry at most `price` decimal places, and derived
+/// prices (blotter statistics, VWAPs) are rounded to it. Quantities stay
+/// integers counting units of `10^-quantity`, so notional values are scaled
+/// by that unit (e.g. `quantity = 8` for satoshi-denominated sizes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Precision {
+    pub price: u32,
+    pub quantity: u32,
+}
+
+impl Precision {
+    pub fn try_new(price: u32, quantity: u32) -> PyResult<Self> {
+        if price > MAX_PRECISION || quantity > MAX_PRECISION {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Precision cannot exceed {} decimal places",
+                MAX_PRECISION
+            )));
+        }
+        Ok(Precision { price, quantity })
+    }
+
+    /// Reject prices finer than the price precision.
+    pub fn check_price(&self, symbol: &str, price: Decimal) -> PyResult<()> {
+        if price.normalize().scale() > self.price {
+            return Err(RejectCode::BadTick.err(format!(
+                "Price {} for {} has more than {} decimal places",
+                price, symbol, self.price
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn round_price(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.price, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Value of one quantity unit.
+    pub fn quantity_unit(&self) -> Decimal {
+        Decimal::new(1, self.quantity)
+    }
+}
+
+/// Seconds in the day that `trading_hours` windows are expressed in.
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// ---------------------------------------------------------------------------
+// Instrument — static reference data for one symbol
+// ---------------------------------------------------------------------------
+
+/// Reference data for a tradable symbol.
+///
+/// Registered on a `Book` via `register_instrument`; the book then validates
+/// incoming orders against `tick_size`/`lot_size` and scales notional values
+/// (blotter `total_cost`, snapshot notionals) by `multiplier`.
+///
+/// `trading_hours` is a list of `(open, close)` pairs expressed in seconds
+/// since midnight. When the symbol's session schedule on a book has a
+/// `day_length`, orders arriving outside them are rejected, placing the
+/// clock within that day in whatever unit it counts; without one the clock
+/// has no known day and the hours are not checked.
+///
+/// Zero and negative prices (spreads, some futures) are rejected unless
+/// `allow_non_positive_prices=True`.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Instrument {
+    pub symbol: String,
+    pub tick_size: Option<Decimal>,
+    pub lot_size: i64,
+    pub currency: String,
+    pub multiplier: Decimal,
+    pub trading_hours: Vec<(u32, u32)>,
+    pub allow_non_positive_prices: bool,
+}
+
+#[pymethods]
+impl Instrument {
+    #[new]
+    #[pyo3(signature = (symbol, tick_size = None, lot_size = 1, currency = "USD".to_string(), multiplier = None, trading_hours = None, allow_non_positive_prices = false))]
+    fn new(
+        symbol: String,
+        tick_size: Option<&Bound<'_, pyo3::PyAny>>,
+        lot_size: i64,
+        currency: String,
+        multiplier: Option<&Bound<'_, pyo3::PyAny>>,
+        trading_hours: Option<Vec<(u32, u32)>>,
+        allow_non_positive_prices: bool,
+    ) -> PyResult<Self> {
+        let tick_size = tick_size
+            .map(|t| extract_decimal(t, "tick_size"))
+            .transpose()?;
+        let multiplier = match multiplier {
+            Some(m) => extract_decimal(m, "multiplier")?,
+            None => Decimal::ONE,
+        };
+        let mut inst = Self::try_new(
+            symbol,
+            tick_size,
+            lot_size,
+            currency,
+            multiplier,
+            trading_hours.unwrap_or_default(),
+        )?;
+        inst.allow_non_positive_prices = allow_non_positive_prices;
+        Ok(inst)
+    }
+
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    #[getter]
+    fn tick_size(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.tick_size)
+    }
+
+    #[getter]
+    fn lot_size(&self) -> i64 {
+        self.lot_size
+    }
+
+    #[getter]
+    fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    #[getter]
+    fn multiplier(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.multiplier)
+    }
+
+    #[getter]
+    fn trading_hours(&self) -> Vec<(u32, u32)> {
+        self.trading_hours.clone()
+    }
+
+    #[getter]
+    fn allow_non_positive_prices(&self) -> bool {
+        self.allow_non_positive_prices
+    }
+
+    /// Whether `second_of_day` falls inside any trading-hours window.
+    /// Instruments without configured hours trade around the clock.
+    pub fn is_trading_time(&self, second_of_day: u32) -> bool {
+        self.trading_hours.is_empty()
+            || self
+                .trading_hours
+                .iter()
+                .any(|&(open, close)| open <= second_of_day && second_of_day < close)
+    }
+
+    /// Notional value of `quantity` units at `price`, scaled by the multiplier.
+    fn notional(
+        &self,
+        price: &Bound<'_, pyo3::PyAny>,
+        quantity: i64,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let price = extract_decimal(price, "price")?;
+        decimal_to_py(py, self.notional_of(price, quantity))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Instrument(symbol='{}', tick_size={}, lot_size={}, currency='{}', multiplier={})",
+            self.symbol,
+            self.tick_size.map_or("None".to_string(), |t| t.to_string()),
+            self.lot_size,
+            self.currency,
+            self.multiplier
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+impl Instrument {
+    pub(crate) fn try_new(
+        symbol: String,
+        tick_size: Option<Decimal>,
+        lot_size: i64,
+        currency: String,
+        multiplier: Decimal,
+        trading_hours: Vec<(u32, u32)>,
+    ) -> PyResult<Self> {
+        if symbol.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Instrument symbol cannot be empty",
+            ));
+        }
+        if tick_size.is_some_and(|t| t <= Decimal::ZERO) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "tick_size must be greater than zero",
+            ));
+        }
+        if lot_size <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "lot_size must be greater than zero",
+            ));
+        }
+        if multiplier <= Decimal::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "multiplier must be greater than zero",
+            ));
+        }
+        if let Some(&(open, close)) = trading_hours
+            .iter()
+            .find(|&&(open, close)| open >= close || i64::from(close) > SECONDS_PER_DAY)
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid trading hours window ({}, {}): expected open < close <= {}",
+                open, close, SECONDS_PER_DAY
+            )));
+        }
+        Ok(Instrument {
+            symbol,
+            tick_size,
+            lot_size,
+            currency,
+            multiplier,
+            trading_hours,
+            allow_non_positive_prices: false,
+        })
+    }
+
+    pub fn notional_of(&self, price: Decimal, quantity: i64) -> Decimal {
+        price * Decimal::from(quantity) * self.multiplier
+    }
+
+    /// Check an order's price and quantity against the instrument's
+    /// price sign policy and its tick and lot sizes.
+    pub fn validate(&self, order: &Order) -> PyResult<()> {
+        // Market-on-open/close orders carry no meaningful price.
+        let priced = !order.auction.is_some_and(|a| a.is_market());
+        if priced && order.price <= Decimal::ZERO && !self.allow_non_positive_prices {
+            return Err(RejectCode::BadPrice.err(format!(
+                "Price {} must be positive for {}; register the instrument with \
+                 allow_non_positive_prices=True to permit zero or negative prices",
+                order.price, self.symbol
+            )));
+        }
+        if let Some(tick) = self.tick_size.filter(|_| priced) {
+            if !(order.price % tick).is_zero() {
+                return Err(RejectCode::BadTick.err(format!(
+                    "Price {} is not a multiple of tick size {} for {}",
+                    order.price, tick, self.symbol
+                )));
+            }
+        }
+        if order.quantity % self.lot_size != 0 {
+            return Err(RejectCode::BadLot.err(format!(
+                "Quantity {} is not a multiple of lot size {} for {}",
+                order.quantity, self.lot_size, self.symbol
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Side;
+
+    fn future() -> Instrument {
+        Instrument::try_new(
+            "ESZ5".to_string(),
+            Some(Decimal::from_str_exact("0.25").unwrap()),
+            1,
+            "USD".to_string(),
+            Decimal::from(50),
+            vec![(34_200, 57_600)],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn precision_checks_and_rounds_prices() {
+        let precision = Precision::try_new(8, 8).unwrap();
+        let fine = Decimal::from_str_exact("64000.12345678").unwrap();
+        assert!(precision.check_price("BTC", fine).is_ok());
+        assert!(precision.check_price("BTC", fine / Decimal::TEN).is_err());
+        assert_eq!(
+            precision.round_price(Decimal::ONE / Decimal::from(3)),
+            Decimal::from_str_exact("0.33333333").unwrap()
+        );
+        assert_eq!(precision.quantity_unit(), Decimal::new(1, 8));
+        assert!(Precision::try_new(19, 0).is_err());
+    }
+
+    #[test]
+    fn validate_enforces_tick_and_lot_sizes() {
+        let mut inst = future();
+        let on_tick = Order::try_new(Side::BID, "ESZ5".to_string(), 5000.25, 2).unwrap();
+        let off_tick = Order::try_new(Side::BID, "ESZ5".to_string(), 5000.1, 2).unwrap();
+        assert!(inst.validate(&on_tick).is_ok());
+        assert!(inst.validate(&off_tick).is_err());
+
+        inst.lot_size = 5;
+        assert!(inst.validate(&on_tick).is_err());
+    }
+
+    #[test]
+    fn validate_requires_opt_in_for_non_positive_prices() {
+        let mut inst = future();
+        let negative = Order::try_new(Side::ASK, "ESZ5".to_string(), -1.25, 1).unwrap();
+        let zero = Order::try_new(Side::ASK, "ESZ5".to_string(), 0.0, 1).unwrap();
+        assert!(inst.validate(&negative).is_err());
+        assert!(inst.validate(&zero).is_err());
+
+        inst.allow_non_positive_prices = true;
+        assert!(inst.validate(&negative).is_ok());
+        assert!(inst.validate(&zero).is_ok());
+    }
+
+    #[test]
+    fn notional_and_trading_hours() {
+        let inst = future();
+        assert_eq!(
+            inst.notional_of(Decimal::from(5000), 2),
+            Decimal::from(500_000)
+        );
+        assert!(inst.is_trading_time(34_200));
+        assert!(!inst.is_trading_time(57_600));
+        assert!(Instrument::try_new(
+            "X".to_string(),
+            None,
+            1,
+            "USD".to_string(),
+            Decimal::ONE,
+            vec![(10, 5)]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::auction::{AuctionKind, TradingState};
+use crate::book::{Book, HaltPolicy};
+use crate::order::Order;
+
+// ---------------------------------------------------------------------------
+// Journal — the book's inputs, for reconstructing past states
+// ---------------------------------------------------------------------------
+
+/// One input to the book, as the call that made it.
+#[derive(Clone, Debug)]
+pub enum JournalEntry {
+    Match(Order),
+    Cancel(Uuid),
+    Amend {
+        order_id: Uuid,
+        price: Option<Decimal>,
+        quantity: Option<i64>,
+    },
+    AdvanceTime(i64),
+    SetTradingState(String, TradingState),
+    Halt(String, HaltPolicy),
+    Resume {
+        symbol: String,
+        reopen_auction: bool,
+        reference: Option<Decimal>,
+    },
+    Uncross(String, AuctionKind, Option<Decimal>),
+    Disconnect {
+        session_id: String,
+        delay: i64,
+    },
+    Reconnect(String),
+    Split(String, Decimal),
+    SymbolChange(String, String),
+}
+
+/// How often the journal copies the book, by inputs recorded or simulated
+/// time passed since the last copy; either, both or neither.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CheckpointCadence {
+    pub every: Option<usize>,
+    pub interval: Option<i64>,
+}
+
+/// Every input accepted since journaling started, each with the simulated
+/// time after it, and copies of the book taken along the way to replay
+/// from. The first copy is the book as it was when journaling started.
+pub struct Journal {
+    /// (entries replayed into it, book) per checkpoint, oldest first.
+    checkpoints: Vec<(usize, Box<Book>)>,
+    entries: Vec<(Option<i64>, JournalEntry)>,
+    pub cadence: CheckpointCadence,
+    /// Clock reading the time cadence counts from: that of the latest
+    /// checkpoint, or the first one after it.
+    anchor: Option<i64>,
+    /// Journaled calls in progress; inputs they make themselves (such as
+    /// the resubmission of an amend) replay with them and are not recorded.
+    pub depth: usize,
+}
+
+impl Journal {
+    pub fn new(baseline: Book, cadence: CheckpointCadence) -> Self {
+        Journal {
+            anchor: baseline.current_time(),
+            checkpoints: vec![(0, Box::new(baseline))],
+            entries: Vec::new(),
+            cadence,
+            depth: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Record an input; true when the book should be checkpointed now.
+    pub fn record(&mut self, now: Option<i64>, entry: JournalEntry) -> bool {
+        self.entries.push((now, entry));
+        let (last, _) = &self.checkpoints[self.checkpoints.len() - 1];
+        let by_count = self
+            .cadence
+            .every
+            .is_some_and(|every| self.entries.len() - last >= every);
+        let by_time = match (self.cadence.interval, now) {
+            (Some(interval), Some(now)) => now - *self.anchor.get_or_insert(now) >= interval,
+            _ => false,
+        };
+        by_count || by_time
+    }
+
+    /// Keep `book`, holding the state after every recorded entry.
+    pub fn checkpoint(&mut self, book: Book) {
+        self.anchor = self.entries.last().and_then(|(now, _)| *now);
+        self.checkpoints.push((self.entries.len(), Box::new(book)));
+    }
+
+    /// Entry counts at which the book was checkpointed, oldest first.
+    pub fn checkpoint_sequences(&self) -> Vec<usize> {
+        self.checkpoints
+            .iter()
+            .map(|(sequence, _)| *sequence)
+            .collect()
+    }
+
+    /// Number of leading entries recorded at or before `timestamp`;
+    /// entries made before the clock first moved count as earliest.
+    pub fn entries_until(&self, timestamp: i64) -> usize {
+        self.entries
+            .partition_point(|(now, _)| now.is_none_or(|now| now <= timestamp))
+    }
+
+    /// A fresh book holding the state after the first `n` entries, replayed
+    /// from the latest checkpoint at or before them.
+    pub fn replay(&self, n: usize) -> pyo3::PyResult<Book> {
+        let nearest = self
+            .checkpoints
+            .partition_point(|(sequence, _)| *sequence <= n)
+            - 1;
+        let (start, checkpoint) = &self.checkpoints[nearest];
+        let mut book = checkpoint.fork();
+        for (_, entry) in &self.entries[*start..n] {
+            book.apply_journal_entry(entry)?;
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_lookups_count_entries_made_before_the_clock() {
+        let mut journal = Journal::new(Book::new(), CheckpointCadence::default());
+        journal.record(None, JournalEntry::Cancel(Uuid::nil()));
+        journal.record(Some(5), JournalEntry::AdvanceTime(5));
+        journal.record(Some(5), JournalEntry::Cancel(Uuid::nil()));
+        journal.record(Some(9), JournalEntry::AdvanceTime(9));
+        assert_eq!(journal.entries_until(4), 1);
+        assert_eq!(journal.entries_until(5), 3);
+        assert_eq!(journal.entries_until(100), 4);
+    }
+
+    #[test]
+    fn checkpoints_fall_due_by_count_or_time() {
+        let cadence = CheckpointCadence {
+            every: Some(3),
+            interval: Some(10),
+        };
+        let mut journal = Journal::new(Book::new(), cadence);
+        assert!(!journal.record(None, JournalEntry::Cancel(Uuid::nil())));
+        assert!(!journal.record(Some(5), JournalEntry::AdvanceTime(5)));
+        assert!(journal.record(Some(5), JournalEntry::Cancel(Uuid::nil())));
+        journal.checkpoint(Book::new());
+        assert!(!journal.record(Some(14), JournalEntry::AdvanceTime(14)));
+        assert!(journal.record(Some(15), JournalEntry::AdvanceTime(15)));
+        journal.checkpoint(Book::new());
+        assert!(!journal.record(Some(24), JournalEntry::AdvanceTime(24)));
+        assert_eq!(journal.checkpoint_sequences(), vec![0, 3, 5]);
+    }
+}
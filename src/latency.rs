@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Samples a new recorder keeps before dropping the oldest.
+pub const DEFAULT_LATENCY_CAPACITY: usize = 100_000;
+
+/// Phases reported by `Book.latency_stats`, each as (name, start, end)
+/// indices into `OrderTimestamps::as_array`.
+const PHASES: [(&str, usize, usize); 4] = [
+    ("risk_check", 0, 1),
+    ("match", 1, 2),
+    ("ack", 2, 3),
+    ("total", 0, 3),
+];
+
+// ---------------------------------------------------------------------------
+// LatencyRecorder — wall-clock timing of each order's processing phases
+// ---------------------------------------------------------------------------
+
+/// Nanoseconds since the recorder started at which one order was received,
+/// passed its checks, finished matching and had its blotter ready.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrderTimestamps {
+    pub received: u64,
+    pub risk_checked: u64,
+    pub matched: u64,
+    pub acked: u64,
+}
+
+impl OrderTimestamps {
+    pub fn as_array(&self) -> [u64; 4] {
+        [self.received, self.risk_checked, self.matched, self.acked]
+    }
+}
+
+/// Count, mean, median, 99th percentile and maximum of one phase, in
+/// nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseStats {
+    pub count: usize,
+    pub mean: f64,
+    pub p50: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+/// The most recent `capacity` orders' timestamps, on a monotonic clock.
+#[derive(Debug)]
+pub struct LatencyRecorder {
+    epoch: Instant,
+    capacity: usize,
+    samples: VecDeque<OrderTimestamps>,
+    /// When the order being processed finished matching, if it matched.
+    pub matched: Option<u64>,
+}
+
+impl LatencyRecorder {
+    pub fn new(capacity: usize) -> Self {
+        LatencyRecorder {
+            epoch: Instant::now(),
+            capacity,
+            samples: VecDeque::new(),
+            matched: None,
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+
+    pub fn record(&mut self, timestamps: OrderTimestamps) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(timestamps);
+    }
+
+    /// Per-phase statistics over the kept samples; empty before any.
+    pub fn stats(&self) -> Vec<(&'static str, PhaseStats)> {
+        if self.samples.is_empty() {
+            return Vec::new();
+        }
+        PHASES
+            .iter()
+            .map(|&(name, start, end)| {
+                let mut durations: Vec<u64> = self
+                    .samples
+                    .iter()
+                    .map(|t| t.as_array()[end] - t.as_array()[start])
+                    .collect();
+                durations.sort_unstable();
+                let rank = |q: f64| durations[((durations.len() - 1) as f64 * q).round() as usize];
+                let stats = PhaseStats {
+                    count: durations.len(),
+                    mean: durations.iter().sum::<u64>() as f64 / durations.len() as f64,
+                    p50: rank(0.5),
+                    p99: rank(0.99),
+                    max: durations[durations.len() - 1],
+                };
+                (name, stats)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_are_differences_of_timestamps() {
+        let mut recorder = LatencyRecorder::new(2);
+        for offset in [0, 100, 200] {
+            recorder.record(OrderTimestamps {
+                received: offset,
+                risk_checked: offset + 10,
+                matched: offset + 30 + offset,
+                acked: offset + 40 + offset,
+            });
+        }
+        let stats = recorder.stats();
+        let (name, check) = stats[0];
+        assert_eq!((name, check.count, check.max), ("risk_check", 2, 10));
+        let (name, matching) = stats[1];
+        assert_eq!((name, matching.p50, matching.max), ("match", 220, 220));
+        assert_eq!(stats[3].1.mean, 190.0);
+    }
+}
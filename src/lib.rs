@@ -1,24 +1,113 @@
 use pyo3::prelude::*;
 
+mod agents;
+mod auction;
+mod benchmarks;
 mod book;
+mod calendar;
+mod capacity;
+mod cashflow;
+mod conditions;
+mod convert;
+mod corporate;
+mod dark;
+mod decay;
+mod dedupe;
+mod disconnect;
+mod dropcopy;
+mod enrich;
+mod events;
+mod features;
+mod feed;
+mod flow;
+mod fx;
 mod getter;
+mod hashing;
+mod history;
+mod icebergs;
+mod instrument;
+mod journal;
+mod latency;
+mod liquidity;
+mod logging;
+mod market_profile;
+mod metrics;
+mod nbbo;
 mod order;
+mod pattern;
+mod pricing;
+mod profile;
+mod publisher;
+mod quality;
+mod quoting;
+mod reject;
+mod replay;
+mod server;
+mod session;
+mod settlement;
+mod sharded;
+mod shared;
 mod snapshot;
+mod spread;
+mod stops;
+mod stp;
+mod strategy;
+mod symbols;
+mod tensor;
+mod throttle;
 mod trade;
+mod validators;
 
 #[pymodule]
 fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logging::install(m.py())?;
     m.add_class::<order::Side>()?;
     m.add_class::<order::OrderStatus>()?;
     m.add_class::<order::Order>()?;
     m.add_class::<trade::OrderQueue>()?;
     m.add_class::<trade::PriceLevel>()?;
     m.add_class::<book::Book>()?;
+    m.add_class::<agents::Agent>()?;
+    m.add_class::<book::MatchIter>()?;
+    m.add_class::<book::OrderHandle>()?;
+    m.add_class::<dark::DarkBook>()?;
+    m.add_class::<sharded::ShardedBook>()?;
     m.add_class::<trade::Trade>()?;
     m.add_class::<snapshot::Snapshot>()?;
     m.add_class::<snapshot::SnapshotLevel>()?;
+    m.add_class::<tensor::TensorSnapshot>()?;
     m.add_class::<trade::TradeBlotter>()?;
+    m.add_class::<nbbo::Nbbo>()?;
+    m.add_class::<instrument::Instrument>()?;
+    m.add_class::<auction::TradingState>()?;
+    m.add_class::<auction::AuctionDesignation>()?;
+    m.add_class::<auction::AuctionResult>()?;
+    m.add_class::<auction::AuctionIndicatives>()?;
+    m.add_class::<stops::StopActivation>()?;
+    m.add_class::<stops::CascadeReport>()?;
+    m.add_class::<conditions::Condition>()?;
+    m.add_class::<server::OrderServer>()?;
+    m.add_class::<shared::SharedBookReader>()?;
+    m.add_class::<history::OrderEvent>()?;
+    m.add_class::<session::SessionStats>()?;
+    m.add_class::<replay::ReplaySummary>()?;
+    m.add_class::<reject::Rejection>()?;
+    m.add("OrderRejected", m.py().get_type::<reject::OrderRejected>())?;
+    m.add_class::<settlement::SettlementLine>()?;
+    m.add_class::<events::Event>()?;
+    m.add_class::<events::OrderAccepted>()?;
+    m.add_class::<events::OrderCancelled>()?;
+    m.add_class::<events::OrderExpired>()?;
+    m.add_class::<events::TradeExecuted>()?;
+    m.add_class::<events::LevelChanged>()?;
+    m.add_class::<events::StateChanged>()?;
+    m.add_class::<events::SplitApplied>()?;
+    m.add_class::<events::SymbolChanged>()?;
+    m.add_class::<events::QuotesPulled>()?;
     m.add_function(wrap_pyfunction!(order::bid, m)?)?;
     m.add_function(wrap_pyfunction!(order::ask, m)?)?;
+    m.add_function(wrap_pyfunction!(nbbo::nbbo, m)?)?;
+    m.add_function(wrap_pyfunction!(benchmarks::run_benchmarks, m)?)?;
+    m.add_function(wrap_pyfunction!(logging::sync_log_level, m)?)?;
     Ok(())
 }
@@ -0,0 +1,160 @@
+// ---------------------------------------------------------------------------
+// Liquidity estimates — price impact and resilience from order flow rows
+// ---------------------------------------------------------------------------
+
+/// Share of events with trades whose volume a large trade must reach when
+/// no threshold is given.
+pub const LARGE_TRADE_QUANTILE: f64 = 0.9;
+
+/// What `Book.liquidity_estimates` reports.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Estimates {
+    /// Kyle's lambda: the slope of the midpoint change on signed volume
+    /// per interval, by least squares.
+    pub kyle_lambda: Option<f64>,
+    pub r_squared: Option<f64>,
+    /// Intervals with a midpoint change to regress.
+    pub intervals: usize,
+    /// Traded volume an event needed to count as a large trade.
+    pub large_trade: Option<f64>,
+    pub large_trades: usize,
+    /// Large trades after which touch depth got back to its level before.
+    pub recovered: usize,
+    /// Mean clock time those took to recover.
+    pub resilience: Option<f64>,
+}
+
+/// Least-squares slope of `y` on `x` and its r², when `x` varies.
+fn regress(points: &[(f64, f64)]) -> (Option<f64>, Option<f64>) {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return (None, None);
+    }
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in points {
+        sxx += (x - mean_x) * (x - mean_x);
+        sxy += (x - mean_x) * (y - mean_y);
+        syy += (y - mean_y) * (y - mean_y);
+    }
+    if sxx == 0.0 {
+        return (None, None);
+    }
+    let r_squared = (syy > 0.0).then(|| sxy * sxy / (sxx * syy));
+    (Some(sxy / sxx), r_squared)
+}
+
+/// Estimate price impact and resilience from a symbol's order flow
+/// columns (see `flow::COLUMNS`), with signed volume summed and the
+/// midpoint change taken over intervals of `window` clock units.
+pub fn estimate(columns: &[Vec<f64>; 8], window: i64, large_trade: Option<f64>) -> Estimates {
+    let [time, _, buys, sells, signed, mid, bid_size, ask_size] = columns;
+    let mut estimates = Estimates::default();
+    let Some(&start) = time.first() else {
+        return estimates;
+    };
+
+    // Kyle's lambda over consecutive intervals. An interval without rows
+    // left the midpoint where it was, so the change spans back to the last
+    // interval that had one.
+    let window = window as f64;
+    let mut points = Vec::new();
+    let mut last_mid: Option<f64> = None;
+    let mut row = 0;
+    while row < time.len() {
+        let bucket = ((time[row] - start) / window).floor();
+        let (mut flow, mut end_mid) = (0.0, None);
+        while row < time.len() && ((time[row] - start) / window).floor() == bucket {
+            flow += signed[row];
+            if !mid[row].is_nan() {
+                end_mid = Some(mid[row]);
+            }
+            row += 1;
+        }
+        if let (Some(before), Some(after)) = (last_mid, end_mid) {
+            points.push((flow, after - before));
+        }
+        last_mid = end_mid;
+    }
+    estimates.intervals = points.len();
+    (estimates.kyle_lambda, estimates.r_squared) = regress(&points);
+
+    // Resilience: how long touch depth takes to get back to where it was
+    // before each large trade. The first row's depth before is unknown.
+    let traded: Vec<f64> = buys.iter().zip(sells).map(|(b, s)| b + s).collect();
+    let depth: Vec<f64> = bid_size.iter().zip(ask_size).map(|(b, a)| b + a).collect();
+    let threshold = large_trade.or_else(|| {
+        let mut sizes: Vec<f64> = traded.iter().copied().filter(|v| *v > 0.0).collect();
+        sizes.sort_by(f64::total_cmp);
+        let index = (sizes.len() as f64 * LARGE_TRADE_QUANTILE).ceil() as usize;
+        sizes.get(index.saturating_sub(1)).copied()
+    });
+    estimates.large_trade = threshold;
+    let Some(threshold) = threshold else {
+        return estimates;
+    };
+    let mut recovery = 0.0;
+    for i in 1..time.len() {
+        if traded[i] <= 0.0 || traded[i] < threshold {
+            continue;
+        }
+        estimates.large_trades += 1;
+        let before = depth[i - 1];
+        if let Some(j) = (i..time.len()).find(|&j| depth[j] >= before) {
+            estimates.recovered += 1;
+            recovery += time[j] - time[i];
+        }
+    }
+    estimates.resilience = (estimates.recovered > 0).then(|| recovery / estimates.recovered as f64);
+    estimates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impact_is_the_slope_of_mid_changes_on_flow() {
+        // time, signed volume, mid, touch depth; two rows per interval of 10.
+        let rows: [(f64, f64, f64, f64); 8] = [
+            (0.0, 0.0, 100.0, 10.0),
+            (5.0, 0.0, 100.0, 10.0),
+            (10.0, 4.0, 100.0, 6.0),
+            (15.0, 0.0, 100.2, 10.0),
+            (20.0, -2.0, 100.2, 8.0),
+            (25.0, 0.0, 100.1, 8.0),
+            (30.0, 6.0, 100.1, 4.0),
+            (35.0, 0.0, 100.4, 7.0),
+        ];
+        let mut columns: [Vec<f64>; 8] = Default::default();
+        for (t, flow, mid, depth) in rows {
+            columns[0].push(t);
+            columns[2].push(flow.max(0.0));
+            columns[3].push((-flow).max(0.0));
+            columns[4].push(flow);
+            columns[5].push(mid);
+            columns[6].push(depth / 2.0);
+            columns[7].push(depth / 2.0);
+        }
+
+        let estimates = estimate(&columns, 10, None);
+        assert_eq!(estimates.intervals, 3);
+        // Mid moves 0.05 per unit of signed volume, exactly.
+        assert!((estimates.kyle_lambda.unwrap() - 0.05).abs() < 1e-9);
+        assert!((estimates.r_squared.unwrap() - 1.0).abs() < 1e-9);
+        // The 6 lot is the large trade, and depth never got back to 8.
+        assert_eq!(estimates.large_trade, Some(6.0));
+        assert_eq!((estimates.large_trades, estimates.recovered), (1, 0));
+        assert_eq!(estimates.resilience, None);
+
+        let estimates = estimate(&columns, 10, Some(4.0));
+        assert_eq!((estimates.large_trades, estimates.recovered), (2, 1));
+        // The 4 lot's depth was back 5 later.
+        assert_eq!(estimates.resilience, Some(5.0));
+        assert_eq!(
+            estimate(&Default::default(), 10, None),
+            Estimates::default()
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use log::kv::{self, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::PyDict;
+
+// ---------------------------------------------------------------------------
+// Engine logging — `log` records bridged to Python's `logging`
+// ---------------------------------------------------------------------------
+
+/// Target of the engine's records, and the name of the Python logger they
+/// reach.
+pub const TARGET: &str = "pyorderbook.engine";
+
+static LOGGER: GILOnceCell<Py<PyAny>> = GILOnceCell::new();
+
+/// Forwards records to `logging.getLogger("pyorderbook.engine")`, with
+/// their key-values as `extra` attributes of the `LogRecord`. Filtering
+/// follows the Python logger's level, so configuring it is all it takes;
+/// `log`'s max level mirrors it so that records nobody would see are
+/// dropped without taking the GIL.
+struct PythonLogger;
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        Python::with_gil(|py| {
+            // Errors in a handler are reported by `logging` itself; there is
+            // nowhere better to send ours.
+            let _ = forward(py, record);
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route the engine's records to Python. Called once at module import.
+pub fn install(py: Python<'_>) -> PyResult<()> {
+    if log::set_logger(&PythonLogger).is_ok() {
+        sync_log_level(py)?;
+    }
+    Ok(())
+}
+
+/// Re-read the Python logger's effective level into `log`'s max level.
+/// Each new `Book` does this; call `pyorderbook.sync_log_level()` after
+/// lowering the level of a process whose books already exist.
+#[pyfunction]
+pub fn sync_log_level(py: Python<'_>) -> PyResult<()> {
+    let logger = logger(py)?;
+    let effective: i64 = logger.call_method0("getEffectiveLevel")?.extract()?;
+    let disabled: i64 = logger.getattr("manager")?.getattr("disable")?.extract()?;
+    let threshold = effective.max(disabled + 1);
+    log::set_max_level(max_level(threshold));
+    Ok(())
+}
+
+/// The most verbose `log` level a Python logger passing levels from
+/// `threshold` up lets through.
+fn max_level(threshold: i64) -> LevelFilter {
+    [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ]
+    .into_iter()
+    .find(|&level| i64::from(python_level(level)) >= threshold)
+    .map_or(LevelFilter::Off, |level| level.to_level_filter())
+}
+
+fn logger(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    LOGGER
+        .get_or_try_init(py, || {
+            let logging = py.import("logging")?;
+            Ok::<_, PyErr>(logging.call_method1("getLogger", (TARGET,))?.unbind())
+        })
+        .map(|logger| logger.bind(py))
+}
+
+/// The `logging` level number of `level`.
+fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+fn is_enabled(py: Python<'_>, level: Level) -> PyResult<bool> {
+    logger(py)?
+        .call_method1("isEnabledFor", (python_level(level),))?
+        .is_truthy()
+}
+
+fn forward(py: Python<'_>, record: &Record) -> PyResult<()> {
+    let level = python_level(record.level());
+    if !is_enabled(py, record.level())? {
+        return Ok(());
+    }
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("extra", extra_of(py, record)?)?;
+    logger(py)?.call_method(
+        "log",
+        (level, "%s", record.args().to_string()),
+        Some(&kwargs),
+    )?;
+    Ok(())
+}
+
+/// A record's key-values as a dict: integers, floats and booleans as such,
+/// anything else as its display string.
+fn extra_of<'py>(py: Python<'py>, record: &Record) -> PyResult<Bound<'py, PyDict>> {
+    struct Collect<'py> {
+        dict: Bound<'py, PyDict>,
+        err: Option<PyErr>,
+    }
+
+    impl<'kvs> VisitSource<'kvs> for Collect<'_> {
+        fn visit_pair(
+            &mut self,
+            key: kv::Key<'kvs>,
+            value: kv::Value<'kvs>,
+        ) -> Result<(), kv::Error> {
+            let set = if let Some(v) = value.to_i64() {
+                self.dict.set_item(key.as_str(), v)
+            } else if let Some(v) = value.to_bool() {
+                self.dict.set_item(key.as_str(), v)
+            } else if let Some(v) = value.to_f64() {
+                self.dict.set_item(key.as_str(), v)
+            } else {
+                self.dict.set_item(key.as_str(), value.to_string())
+            };
+            set.map_err(|err| {
+                self.err = Some(err);
+                kv::Error::msg("could not convert a key-value")
+            })
+        }
+    }
+
+    let mut collect = Collect {
+        dict: PyDict::new(py),
+        err: None,
+    };
+    if record.key_values().visit(&mut collect).is_err() {
+        if let Some(err) = collect.err {
+            return Err(err);
+        }
+    }
+    Ok(collect.dict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_values_become_typed_extra_attributes() {
+        pyo3::prepare_freethreaded_python();
+        let pairs: [(&str, kv::Value); 3] = [
+            ("quantity", kv::Value::from(5i64)),
+            ("price", kv::Value::from_display(&1.5)),
+            ("symbol", kv::Value::from("X")),
+        ];
+        let record = Record::builder()
+            .args(format_args!("trade"))
+            .key_values(&pairs)
+            .build();
+        Python::with_gil(|py| {
+            let extra = extra_of(py, &record).unwrap();
+            let get = |key| extra.get_item(key).unwrap().unwrap().to_string();
+            assert_eq!(
+                (get("quantity"), get("price"), get("symbol")),
+                ("5".to_string(), "1.5".to_string(), "X".to_string())
+            );
+            assert_eq!(python_level(Level::Warn), 30);
+            assert_eq!(max_level(0), LevelFilter::Trace);
+            assert_eq!(max_level(30), LevelFilter::Warn);
+            assert_eq!(max_level(51), LevelFilter::Off);
+        });
+    }
+}
@@ -0,0 +1,107 @@
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+// ---------------------------------------------------------------------------
+// MarketProfile — traded volume and time at each price
+// ---------------------------------------------------------------------------
+
+/// One row of a profile: the bucket's lowest price, the volume traded in
+/// it and the clock time the midpoint spent in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileLevel {
+    pub price: Decimal,
+    pub volume: i64,
+    pub time: i64,
+}
+
+/// Traded volume and time at the midpoint per exact price, bucketed when
+/// read so any bucket size can be asked for.
+#[derive(Clone, Debug, Default)]
+pub struct MarketProfile {
+    volume: BTreeMap<Decimal, i64>,
+    time: BTreeMap<Decimal, i64>,
+    /// The midpoint and the clock since it has been there.
+    mid: Option<(i64, Decimal)>,
+}
+
+impl MarketProfile {
+    pub fn new(now: i64, mid: Option<Decimal>) -> Self {
+        MarketProfile {
+            mid: mid.map(|mid| (now, mid)),
+            ..MarketProfile::default()
+        }
+    }
+
+    pub fn trade(&mut self, price: Decimal, quantity: i64) {
+        *self.volume.entry(price).or_default() += quantity;
+    }
+
+    /// The midpoint is `mid` as of clock `now`.
+    pub fn observe(&mut self, now: i64, mid: Option<Decimal>) {
+        if self.mid.map(|(_, current)| current) == mid {
+            return;
+        }
+        if let Some((since, current)) = self.mid {
+            *self.time.entry(current).or_default() += now - since;
+        }
+        self.mid = mid.map(|mid| (now, mid));
+    }
+
+    /// The profile in buckets of `bucket` (positive), lowest price first,
+    /// counting the current midpoint's time up to clock `now`.
+    pub fn levels(&self, bucket: Decimal, now: i64) -> Vec<ProfileLevel> {
+        let floor = |price: Decimal| (price / bucket).floor() * bucket;
+        let mut levels: BTreeMap<Decimal, (i64, i64)> = BTreeMap::new();
+        for (&price, &volume) in &self.volume {
+            levels.entry(floor(price)).or_default().0 += volume;
+        }
+        let open = self.mid.map(|(since, mid)| (mid, now - since));
+        for (price, time) in self.time.iter().map(|(p, t)| (*p, *t)).chain(open) {
+            levels.entry(floor(price)).or_default().1 += time;
+        }
+        levels
+            .into_iter()
+            .map(|(price, (volume, time))| ProfileLevel {
+                price: price.normalize(),
+                volume,
+                time,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_and_time_fall_into_price_buckets() {
+        let px = |n: i64, scale: u32| Decimal::new(n, scale);
+        let mut profile = MarketProfile::new(0, Some(px(1000, 1)));
+        profile.trade(px(1005, 1), 3);
+        profile.trade(px(1010, 1), 2);
+        profile.observe(10, Some(px(1010, 1)));
+        profile.trade(px(1020, 1), 4);
+        profile.observe(15, Some(px(1010, 1)));
+        profile.observe(25, None);
+        profile.observe(30, Some(px(1025, 1)));
+
+        let level = |price, volume, time| ProfileLevel {
+            price,
+            volume,
+            time,
+        };
+        assert_eq!(
+            profile.levels(Decimal::ONE, 40),
+            [
+                level(px(100, 0), 3, 10),
+                level(px(101, 0), 2, 15),
+                level(px(102, 0), 4, 10),
+            ]
+        );
+        assert_eq!(
+            profile.levels(Decimal::from(5), 40),
+            [level(px(100, 0), 9, 35)]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+// ---------------------------------------------------------------------------
+// Metrics — per-symbol counters in the Prometheus text format
+// ---------------------------------------------------------------------------
+
+/// Running event counts for one symbol. Unlike `SessionStats` they are
+/// never reset, as Prometheus counters must only grow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SymbolCounters {
+    pub orders: u64,
+    pub rejected: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    pub trades: u64,
+    pub volume: i64,
+}
+
+impl SymbolCounters {
+    /// The counter families written for each symbol: name, help text and
+    /// the counter's value.
+    pub const FAMILIES: [(&'static str, &'static str, CounterValue); 6] = [
+        ("pyorderbook_orders_total", "Orders accepted.", |c| {
+            c.orders as i64
+        }),
+        (
+            "pyorderbook_orders_rejected_total",
+            "Orders rejected.",
+            |c| c.rejected as i64,
+        ),
+        (
+            "pyorderbook_orders_cancelled_total",
+            "Orders cancelled.",
+            |c| c.cancelled as i64,
+        ),
+        (
+            "pyorderbook_orders_expired_total",
+            "Good-till-date orders expired.",
+            |c| c.expired as i64,
+        ),
+        ("pyorderbook_trades_total", "Trades printed.", |c| {
+            c.trades as i64
+        }),
+        ("pyorderbook_traded_volume_total", "Quantity traded.", |c| {
+            c.volume
+        }),
+    ];
+}
+
+pub type CounterValue = fn(&SymbolCounters) -> i64;
+
+/// Builder of a Prometheus text exposition, one metric family at a time.
+#[derive(Debug, Default)]
+pub struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    /// Write a family's `HELP` and `TYPE` lines and its samples, each a set
+    /// of `(label, value)` pairs and the sample value. Families without
+    /// samples are left out.
+    pub fn family<'a>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        help: &str,
+        samples: impl IntoIterator<Item = (Vec<(&'a str, String)>, String)>,
+    ) {
+        let mut samples = samples.into_iter().peekable();
+        if samples.peek().is_none() {
+            return;
+        }
+        // Writing to a String cannot fail.
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            self.sample(name, &labels, &value);
+        }
+    }
+
+    /// Write one sample line of the current family; `name` may carry a
+    /// suffix such as `_count`.
+    pub fn sample(&mut self, name: &str, labels: &[(&str, String)], value: &str) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value);
+    }
+
+    pub fn finish(self) -> String {
+        self.text
+    }
+}
+
+/// Escape a label value: backslashes, double quotes and newlines.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn families_render_in_the_text_format() {
+        let mut out = Exposition::default();
+        out.family(
+            "pyorderbook_trades_total",
+            "counter",
+            "Trades printed.",
+            [(vec![("symbol", "A\"B".to_string())], "3".to_string())],
+        );
+        out.family("pyorderbook_empty", "gauge", "Nothing.", []);
+        assert_eq!(
+            out.finish(),
+            "# HELP pyorderbook_trades_total Trades printed.\n\
+             # TYPE pyorderbook_trades_total counter\n\
+             pyorderbook_trades_total{symbol=\"A\\\"B\"} 3\n"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::Decimal;
+
+use crate::book::Book;
+use crate::order::{decimal_to_py, Side};
+
+// ---------------------------------------------------------------------------
+// Nbbo — consolidated best bid/offer across several venues
+// ---------------------------------------------------------------------------
+
+/// National best bid and offer for one symbol across a set of venues.
+///
+/// When several venues share the best price, all of them are attributed
+/// and their quantities are aggregated.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Nbbo {
+    pub symbol: String,
+    pub best_bid: Option<Decimal>,
+    pub bid_quantity: i64,
+    pub bid_venues: Vec<String>,
+    pub best_ask: Option<Decimal>,
+    pub ask_quantity: i64,
+    pub ask_venues: Vec<String>,
+}
+
+#[pymethods]
+impl Nbbo {
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    #[getter]
+    fn best_bid(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.best_bid)
+    }
+
+    #[getter]
+    fn bid_quantity(&self) -> i64 {
+        self.bid_quantity
+    }
+
+    #[getter]
+    fn bid_venues(&self) -> Vec<String> {
+        self.bid_venues.clone()
+    }
+
+    #[getter]
+    fn best_ask(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.best_ask)
+    }
+
+    #[getter]
+    fn ask_quantity(&self) -> i64 {
+        self.ask_quantity
+    }
+
+    #[getter]
+    fn ask_venues(&self) -> Vec<String> {
+        self.ask_venues.clone()
+    }
+
+    /// Consolidated spread, or None if either side is empty.
+    #[getter]
+    fn spread(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match (self.best_bid, self.best_ask) {
+            (Some(b), Some(a)) => decimal_to_py(py, a - b),
+            _ => Ok(py.None()),
+        }
+    }
+
+    /// True when the best bid equals the best ask across venues.
+    #[getter]
+    pub fn locked(&self) -> bool {
+        matches!((self.best_bid, self.best_ask), (Some(b), Some(a)) if b == a)
+    }
+
+    /// True when the best bid is above the best ask across venues.
+    #[getter]
+    pub fn crossed(&self) -> bool {
+        matches!((self.best_bid, self.best_ask), (Some(b), Some(a)) if b > a)
+    }
+
+    fn __repr__(&self) -> String {
+        let fmt = |p: Option<Decimal>| p.map_or("None".to_string(), |d| d.to_string());
+        format!(
+            "Nbbo(symbol='{}', bid={} x {} {:?}, ask={} x {} {:?})",
+            self.symbol,
+            fmt(self.best_bid),
+            self.bid_quantity,
+            self.bid_venues,
+            fmt(self.best_ask),
+            self.ask_quantity,
+            self.ask_venues
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+/// Top-of-book quote for one venue: (venue, best bid, best ask).
+type VenueQuote = (String, Option<(Decimal, i64)>, Option<(Decimal, i64)>);
+
+impl Nbbo {
+    /// Consolidate per-venue top-of-book quotes, preserving venue order
+    /// in the attribution lists.
+    pub fn from_quotes(symbol: &str, quotes: &[VenueQuote]) -> Self {
+        let mut nbbo = Nbbo {
+            symbol: symbol.to_string(),
+            best_bid: None,
+            bid_quantity: 0,
+            bid_venues: Vec::new(),
+            best_ask: None,
+            ask_quantity: 0,
+            ask_venues: Vec::new(),
+        };
+        for (venue, bid, ask) in quotes {
+            if let Some((price, qty)) = *bid {
+                match nbbo.best_bid {
+                    Some(best) if price < best => {}
+                    Some(best) if price == best => {
+                        nbbo.bid_quantity += qty;
+                        nbbo.bid_venues.push(venue.clone());
+                    }
+                    _ => {
+                        nbbo.best_bid = Some(price);
+                        nbbo.bid_quantity = qty;
+                        nbbo.bid_venues = vec![venue.clone()];
+                    }
+                }
+            }
+            if let Some((price, qty)) = *ask {
+                match nbbo.best_ask {
+                    Some(best) if price > best => {}
+                    Some(best) if price == best => {
+                        nbbo.ask_quantity += qty;
+                        nbbo.ask_venues.push(venue.clone());
+                    }
+                    _ => {
+                        nbbo.best_ask = Some(price);
+                        nbbo.ask_quantity = qty;
+                        nbbo.ask_venues = vec![venue.clone()];
+                    }
+                }
+            }
+        }
+        nbbo
+    }
+}
+
+/// Compute the consolidated best bid/offer for `symbol` across venues.
+///
+/// `venues` maps a venue name to its Book, e.g. `{"XNAS": book_a, "ARCA": book_b}`.
+#[pyfunction]
+pub fn nbbo(venues: &Bound<'_, PyDict>, symbol: &str) -> PyResult<Nbbo> {
+    let mut quotes: Vec<VenueQuote> = Vec::with_capacity(venues.len());
+    for (name, book_any) in venues.iter() {
+        let venue: String = name.str()?.extract()?;
+        let book: PyRef<Book> = book_any.extract()?;
+        quotes.push((
+            venue,
+            book.best_level(symbol, Side::BID),
+            book.best_level(symbol, Side::ASK),
+        ));
+    }
+    Ok(Nbbo::from_quotes(symbol, &quotes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(s: &str) -> Decimal {
+        Decimal::from_str_exact(s).unwrap()
+    }
+
+    #[test]
+    fn from_quotes_attributes_ties_and_aggregates_quantity() {
+        let quotes = vec![
+            ("A".to_string(), Some((px("10"), 5)), Some((px("11"), 7))),
+            ("B".to_string(), Some((px("10"), 3)), Some((px("12"), 1))),
+            ("C".to_string(), Some((px("9"), 50)), None),
+        ];
+
+        let nbbo = Nbbo::from_quotes("AAPL", &quotes);
+
+        assert_eq!(nbbo.best_bid, Some(px("10")));
+        assert_eq!(nbbo.bid_quantity, 8);
+        assert_eq!(nbbo.bid_venues, vec!["A", "B"]);
+        assert_eq!(nbbo.best_ask, Some(px("11")));
+        assert_eq!(nbbo.ask_quantity, 7);
+        assert_eq!(nbbo.ask_venues, vec!["A"]);
+        assert!(!nbbo.locked());
+        assert!(!nbbo.crossed());
+    }
+
+    #[test]
+    fn from_quotes_flags_locked_and_crossed_markets() {
+        let locked = Nbbo::from_quotes(
+            "X",
+            &[
+                ("A".to_string(), Some((px("10"), 1)), None),
+                ("B".to_string(), None, Some((px("10"), 1))),
+            ],
+        );
+        assert!(locked.locked());
+        assert!(!locked.crossed());
+
+        let crossed = Nbbo::from_quotes(
+            "X",
+            &[
+                ("A".to_string(), Some((px("10.5"), 1)), None),
+                ("B".to_string(), None, Some((px("10"), 1))),
+            ],
+        );
+        assert!(crossed.crossed());
+        assert!(!crossed.locked());
+
+        let empty = Nbbo::from_quotes("X", &[]);
+        assert!(!empty.locked() && !empty.crossed());
+    }
+}
@@ -1,10 +1,15 @@
 use pyo3::prelude::*;
 use pyo3::sync::GILOnceCell;
 use pyo3::types::PyType;
+use pyorderbook_core::OrderCore;
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::auction::AuctionDesignation;
+use crate::conditions::Condition;
+use crate::stops::StopTrigger;
+
 // ---------------------------------------------------------------------------
 // Cached Python class references — initialized once per process
 // ---------------------------------------------------------------------------
@@ -28,14 +33,30 @@ pub fn decimal_cls(py: Python<'_>) -> PyResult<&Py<PyType>> {
 
 /// Helper: Rust Uuid -> Python uuid.UUID
 pub fn uuid_to_py(py: Python<'_>, id: Uuid) -> PyResult<PyObject> {
-    let cls = uuid_cls(py)?;
-    Ok(cls.call1(py, (id.to_string(),))?.into())
+    Ok(crate::convert::uuid_object(py, id)?.unbind())
 }
 
 /// Helper: Rust Decimal -> Python decimal.Decimal
 pub fn decimal_to_py(py: Python<'_>, d: Decimal) -> PyResult<PyObject> {
     let cls = decimal_cls(py)?;
-    Ok(cls.call1(py, (d.to_string(),))?.into())
+    cls.call1(py, (d.to_string(),))
+}
+
+/// Helper: Python number/str/Decimal -> Rust Decimal, parsed via `str()`.
+pub fn extract_decimal(value: &Bound<'_, pyo3::PyAny>, field: &str) -> PyResult<Decimal> {
+    let as_str: String = value.str()?.extract()?;
+    Decimal::from_str_exact(&as_str)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid {}: {}", field, e)))
+}
+
+/// Helper: an Order, uuid.UUID or id string -> the order's Rust Uuid.
+pub fn extract_order_id(value: &Bound<'_, pyo3::PyAny>) -> PyResult<Uuid> {
+    if let Ok(order) = value.extract::<PyRef<Order>>() {
+        return Ok(order.id);
+    }
+    let id_str: String = value.str()?.extract()?;
+    Uuid::parse_str(&id_str)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid order_id: {}", e)))
 }
 
 // ---------------------------------------------------------------------------
@@ -48,6 +69,7 @@ pub fn decimal_to_py(py: Python<'_>, d: Decimal) -> PyResult<PyObject> {
 /// matching the Python StrEnum behavior.
 #[pyclass]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Side {
     BID,
     ASK,
@@ -66,11 +88,8 @@ impl Side {
 impl Side {
     /// Return the opposite side.
     #[getter]
-    fn other(&self) -> Side {
-        match self {
-            Side::BID => Side::ASK,
-            Side::ASK => Side::BID,
-        }
+    pub fn other(&self) -> Side {
+        pyorderbook_core::Side::from(*self).other().into()
     }
 
     fn __str__(&self) -> &'static str {
@@ -107,17 +126,29 @@ impl Side {
 impl Side {
     /// Check if incoming price can match against standing price.
     pub fn price_is_matchable(self, incoming: Decimal, standing: Decimal) -> bool {
-        match self {
-            Side::BID => incoming >= standing,
-            Side::ASK => incoming <= standing,
-        }
+        pyorderbook_core::Side::from(self).price_is_matchable(incoming, standing)
     }
 
     /// Determine the fill price for a trade.
     pub fn calc_fill_price(self, incoming: Decimal, standing: Decimal) -> Decimal {
-        match self {
-            Side::BID => incoming.min(standing),
-            Side::ASK => incoming.max(standing),
+        pyorderbook_core::Side::from(self).calc_fill_price(incoming, standing)
+    }
+}
+
+impl From<Side> for pyorderbook_core::Side {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::BID => pyorderbook_core::Side::Bid,
+            Side::ASK => pyorderbook_core::Side::Ask,
+        }
+    }
+}
+
+impl From<pyorderbook_core::Side> for Side {
+    fn from(side: pyorderbook_core::Side) -> Self {
+        match side {
+            pyorderbook_core::Side::Bid => Side::BID,
+            pyorderbook_core::Side::Ask => Side::ASK,
         }
     }
 }
@@ -135,7 +166,7 @@ impl std::fmt::Display for Side {
 /// Order status after matching.
 #[pyclass(eq, eq_int)]
 #[derive(Clone, Copy, Debug, PartialEq)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum OrderStatus {
     QUEUED,
     PARTIAL_FILL,
@@ -161,12 +192,39 @@ impl OrderStatus {
     }
 }
 
+// ---------------------------------------------------------------------------
+// FillStats — executions accumulated on an order
+// ---------------------------------------------------------------------------
+
+/// Executed quantity and prices of an order, kept up by the book as it
+/// trades.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FillStats {
+    pub quantity: i64,
+    /// Sum of price * quantity over all fills.
+    pub notional: Decimal,
+    pub last_price: Option<Decimal>,
+}
+
+impl FillStats {
+    pub fn record(&mut self, price: Decimal, quantity: i64) {
+        self.quantity += quantity;
+        self.notional += price * Decimal::from(quantity);
+        self.last_price = Some(price);
+    }
+
+    /// Volume-weighted fill price; `None` before the first fill.
+    pub fn average_price(&self) -> Option<Decimal> {
+        (self.quantity > 0).then(|| self.notional / Decimal::from(self.quantity))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Order
 // ---------------------------------------------------------------------------
 
 /// A single order in the book.
-#[pyclass]
+#[pyclass(subclass)]
 #[derive(Clone, Debug)]
 pub struct Order {
     pub id: Uuid,
@@ -175,36 +233,189 @@ pub struct Order {
     pub symbol: String,
     pub side: Side,
     pub original_quantity: i64,
+    /// Short sale flag; only valid on ASK orders.
+    pub short: bool,
+    /// Good-till-date expiry on the book's simulated clock.
+    pub expire_time: Option<i64>,
+    /// Auction-only designation (MOO/LOO/MOC/LOC).
+    pub auction: Option<AuctionDesignation>,
+    /// Stop trigger price; the order enters the book as a limit order once
+    /// a trade prints at or through it.
+    pub stop_price: Option<Decimal>,
+    /// Executions so far.
+    pub fills: FillStats,
+    /// Trading account the order belongs to, for settlement.
+    pub account: Option<String>,
+    /// Connection session that sent the order, for cancel-on-disconnect.
+    pub session_id: Option<String>,
+    /// Quantity shown at a time while resting: 0 for a hidden order, less
+    /// than `quantity` for an iceberg. None displays all of it.
+    pub display_quantity: Option<i64>,
+    /// Market price a stop order's trigger compares with `stop_price`.
+    pub stop_trigger: StopTrigger,
+    /// Book state a conditional order waits for before entering the book.
+    pub condition: Option<Condition>,
+}
+
+/// Convert via string to match Python's Decimal(str(price)) behavior.
+pub(crate) fn price_to_decimal(price: f64) -> PyResult<Decimal> {
+    let decimal_price = Decimal::from_str(&price.to_string()).map_err(|e| {
+        pyo3::exceptions::PyValueError::new_err(format!("Invalid price value: {}", e))
+    })?;
+    // Normalize -0.0 so zero-priced orders share a single level.
+    if decimal_price.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    Ok(decimal_price)
 }
 
 impl Order {
     pub(crate) fn try_new(side: Side, symbol: String, price: f64, quantity: i64) -> PyResult<Self> {
-        if quantity <= 0 {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Order quantity must be greater than zero",
-            ));
-        }
-        // Convert via string to match Python's Decimal(str(price)) behavior.
-        let price_str = price.to_string();
-        let decimal_price = Decimal::from_str(&price_str).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid price value: {}", e))
-        })?;
+        Self::try_new_at(side, symbol, price_to_decimal(price)?, quantity)
+    }
+
+    /// Like `try_new`, with an exact decimal price.
+    pub(crate) fn try_new_at(
+        side: Side,
+        symbol: String,
+        price: Decimal,
+        quantity: i64,
+    ) -> PyResult<Self> {
+        let core = OrderCore::new(side.into(), symbol, price, quantity)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
         Ok(Order {
-            id: Uuid::new_v4(),
-            price: decimal_price,
-            quantity,
-            symbol,
+            id: core.id,
+            price: core.price,
+            quantity: core.quantity,
+            symbol: core.symbol,
             side,
             original_quantity: quantity,
+            short: false,
+            expire_time: None,
+            auction: None,
+            stop_price: None,
+            fills: FillStats::default(),
+            account: None,
+            session_id: None,
+            display_quantity: None,
+            stop_trigger: StopTrigger::LastTrade,
+            condition: None,
         })
     }
+
+    /// Mark an ASK order as a short sale.
+    pub(crate) fn with_short(mut self, short: bool) -> PyResult<Self> {
+        if short && self.side != Side::ASK {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Only ASK orders can be flagged as short sales",
+            ));
+        }
+        self.short = short;
+        Ok(self)
+    }
+
+    /// Make the order good-till-date, expiring at `expire_time`.
+    pub(crate) fn with_expire_time(mut self, expire_time: Option<i64>) -> Self {
+        self.expire_time = expire_time;
+        self
+    }
+
+    /// Reserve the order for an opening or closing auction.
+    pub(crate) fn with_auction(mut self, auction: Option<AuctionDesignation>) -> Self {
+        self.auction = auction;
+        self
+    }
+
+    /// Book the order to `account`.
+    pub(crate) fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Tag the order with the connection session that sent it.
+    pub(crate) fn with_session_id(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Trigger a stop order on `stop_trigger` instead of the last trade.
+    pub(crate) fn with_stop_trigger(mut self, stop_trigger: Option<&str>) -> PyResult<Self> {
+        let Some(stop_trigger) = stop_trigger else {
+            return Ok(self);
+        };
+        if self.stop_price.is_none() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "stop_trigger applies only to stop orders",
+            ));
+        }
+        self.stop_trigger = StopTrigger::parse(stop_trigger)?;
+        Ok(self)
+    }
+
+    /// Hold the order off the book until `condition` holds.
+    pub(crate) fn with_condition(mut self, condition: Option<Condition>) -> PyResult<Self> {
+        if condition.is_some() && self.stop_price.is_some() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "An order takes either a stop_price or a condition, not both",
+            ));
+        }
+        self.condition = condition;
+        Ok(self)
+    }
+
+    /// Whether the order waits off the book for a stop price or condition.
+    pub(crate) fn is_triggered(&self) -> bool {
+        self.stop_price.is_some() || self.condition.is_some()
+    }
+
+    /// Show at most `display_quantity` of the order while it rests.
+    pub(crate) fn with_display_quantity(mut self, display_quantity: Option<i64>) -> PyResult<Self> {
+        if display_quantity.is_some_and(|d| d < 0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "display_quantity cannot be negative",
+            ));
+        }
+        self.display_quantity = display_quantity;
+        Ok(self)
+    }
+
+    /// Turn the order into a stop-limit order triggered at `stop_price`.
+    pub(crate) fn with_stop_price(mut self, stop_price: Option<f64>) -> PyResult<Self> {
+        self.stop_price = stop_price.map(price_to_decimal).transpose()?;
+        Ok(self)
+    }
 }
 
 #[pymethods]
 impl Order {
     #[new]
-    fn new(side: Side, symbol: String, price: f64, quantity: i64) -> PyResult<Self> {
-        Self::try_new(side, symbol, price, quantity)
+    #[pyo3(signature = (side, symbol, price, quantity, *, short = false, expire_time = None, auction = None, stop_price = None, account = None, session_id = None, display_quantity = None, stop_trigger = None, condition = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        side: Side,
+        symbol: String,
+        price: f64,
+        quantity: i64,
+        short: bool,
+        expire_time: Option<i64>,
+        auction: Option<AuctionDesignation>,
+        stop_price: Option<f64>,
+        account: Option<String>,
+        session_id: Option<String>,
+        display_quantity: Option<i64>,
+        stop_trigger: Option<&str>,
+        condition: Option<Condition>,
+    ) -> PyResult<Self> {
+        Self::try_new(side, symbol, price, quantity)?
+            .with_short(short)?
+            .with_expire_time(expire_time)
+            .with_auction(auction)
+            .with_stop_price(stop_price)?
+            .with_stop_trigger(stop_trigger)?
+            .with_condition(condition)?
+            .with_account(account)
+            .with_session_id(session_id)
+            .with_display_quantity(display_quantity)
     }
 
     /// Return the order id as a Python uuid.UUID.
@@ -244,9 +455,74 @@ impl Order {
         self.original_quantity
     }
 
+    #[getter]
+    fn short(&self) -> bool {
+        self.short
+    }
+
+    #[getter]
+    fn expire_time(&self) -> Option<i64> {
+        self.expire_time
+    }
+
+    #[getter]
+    fn auction(&self) -> Option<AuctionDesignation> {
+        self.auction
+    }
+
+    #[getter]
+    fn stop_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.stop_price)
+    }
+
+    #[getter]
+    fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    #[getter]
+    fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    #[getter]
+    fn display_quantity(&self) -> Option<i64> {
+        self.display_quantity
+    }
+
+    /// "last_trade", "bbo" or "midpoint" for a stop order, else None.
+    #[getter]
+    fn stop_trigger(&self) -> Option<&'static str> {
+        self.stop_price.map(|_| self.stop_trigger.as_str())
+    }
+
+    /// Book state a conditional order waits for, or None.
+    #[getter]
+    fn condition(&self) -> Option<Condition> {
+        self.condition.clone()
+    }
+
+    /// Quantity executed so far.
+    #[getter]
+    fn filled_quantity(&self) -> i64 {
+        self.fills.quantity
+    }
+
+    /// Volume-weighted price of the fills so far, or None.
+    #[getter]
+    fn average_fill_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.fills.average_price())
+    }
+
+    /// Price of the most recent fill, or None.
+    #[getter]
+    fn last_fill_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        crate::snapshot::optional_decimal_to_py(py, self.fills.last_price)
+    }
+
     /// Computed status based on remaining vs original quantity.
     #[getter]
-    fn status(&self) -> OrderStatus {
+    pub(crate) fn status(&self) -> OrderStatus {
         if self.quantity == 0 {
             OrderStatus::FILLED
         } else if self.quantity < self.original_quantity {
@@ -266,16 +542,75 @@ impl Order {
 // bid / ask convenience functions
 // ---------------------------------------------------------------------------
 
-/// Create a BID order.
+/// Create a BID order. Pass `expire_time` for a good-till-date order,
+/// `auction` to reserve it for an opening/closing auction, `stop_price`
+/// for a buy stop-limit, `account` to book it to a trading account,
+/// `session_id` to tag the connection session that sent it and
+/// `display_quantity` to rest it hidden (0) or as an iceberg. A stop's
+/// `stop_trigger` is "last_trade" (the default), "bbo" or "midpoint";
+/// `condition` holds the order until the book state satisfies it.
 #[pyfunction]
-pub fn bid(symbol: String, price: f64, quantity: i64) -> PyResult<Order> {
-    Order::new(Side::BID, symbol, price, quantity)
+#[pyo3(signature = (symbol, price, quantity, *, expire_time = None, auction = None, stop_price = None, account = None, session_id = None, display_quantity = None, stop_trigger = None, condition = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn bid(
+    symbol: String,
+    price: f64,
+    quantity: i64,
+    expire_time: Option<i64>,
+    auction: Option<AuctionDesignation>,
+    stop_price: Option<f64>,
+    account: Option<String>,
+    session_id: Option<String>,
+    display_quantity: Option<i64>,
+    stop_trigger: Option<&str>,
+    condition: Option<Condition>,
+) -> PyResult<Order> {
+    Order::try_new(Side::BID, symbol, price, quantity)?
+        .with_expire_time(expire_time)
+        .with_auction(auction)
+        .with_stop_price(stop_price)?
+        .with_stop_trigger(stop_trigger)?
+        .with_condition(condition)?
+        .with_account(account)
+        .with_session_id(session_id)
+        .with_display_quantity(display_quantity)
 }
 
-/// Create an ASK order.
+/// Create an ASK order. Pass `short=True` to flag a short sale,
+/// `expire_time` for a good-till-date order, `auction` to reserve it for
+/// an opening/closing auction, `stop_price` for a sell stop-limit,
+/// `account` to book it to a trading account, `session_id` to tag the
+/// connection session that sent it and `display_quantity` to rest it
+/// hidden (0) or as an iceberg. A stop's `stop_trigger` is "last_trade"
+/// (the default), "bbo" or "midpoint"; `condition` holds the order until
+/// the book state satisfies it.
 #[pyfunction]
-pub fn ask(symbol: String, price: f64, quantity: i64) -> PyResult<Order> {
-    Order::new(Side::ASK, symbol, price, quantity)
+#[pyo3(signature = (symbol, price, quantity, *, short = false, expire_time = None, auction = None, stop_price = None, account = None, session_id = None, display_quantity = None, stop_trigger = None, condition = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn ask(
+    symbol: String,
+    price: f64,
+    quantity: i64,
+    short: bool,
+    expire_time: Option<i64>,
+    auction: Option<AuctionDesignation>,
+    stop_price: Option<f64>,
+    account: Option<String>,
+    session_id: Option<String>,
+    display_quantity: Option<i64>,
+    stop_trigger: Option<&str>,
+    condition: Option<Condition>,
+) -> PyResult<Order> {
+    Order::try_new(Side::ASK, symbol, price, quantity)?
+        .with_short(short)?
+        .with_expire_time(expire_time)
+        .with_auction(auction)
+        .with_stop_price(stop_price)?
+        .with_stop_trigger(stop_trigger)?
+        .with_condition(condition)?
+        .with_account(account)
+        .with_session_id(session_id)
+        .with_display_quantity(display_quantity)
 }
 
 #[cfg(test)]
@@ -311,6 +646,24 @@ mod tests {
         order.quantity = 0;
         assert_eq!(order.status(), OrderStatus::FILLED);
 
+        order.fills.record(Decimal::from(10), 60);
+        order.fills.record(Decimal::from(12), 40);
+        assert_eq!(order.fills.average_price(), Decimal::from_str("10.8").ok());
+        assert_eq!(order.fills.last_price, Some(Decimal::from(12)));
+
         assert!(Order::try_new(Side::ASK, "AAPL".to_string(), 150.25, 0).is_err());
     }
+
+    #[test]
+    fn short_flag_is_ask_only() {
+        let short_ask = Order::try_new(Side::ASK, "AAPL".to_string(), 10.0, 1)
+            .unwrap()
+            .with_short(true)
+            .unwrap();
+        assert!(short_ask.short);
+        assert!(Order::try_new(Side::BID, "AAPL".to_string(), 10.0, 1)
+            .unwrap()
+            .with_short(true)
+            .is_err());
+    }
 }
@@ -0,0 +1,68 @@
+// ---------------------------------------------------------------------------
+// SymbolPattern — glob matching over symbol families
+// ---------------------------------------------------------------------------
+
+/// A symbol or a glob over symbols: `*` matches any run of characters and
+/// `?` exactly one, so `"ES*"` selects every ES expiry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolPattern(String);
+
+impl SymbolPattern {
+    pub fn new(pattern: &str) -> Self {
+        SymbolPattern(pattern.to_string())
+    }
+
+    /// Whether the pattern contains wildcards, i.e. may select more than
+    /// one symbol.
+    pub fn is_glob(&self) -> bool {
+        self.0.contains(['*', '?'])
+    }
+
+    pub fn matches(&self, symbol: &str) -> bool {
+        let pattern: Vec<char> = self.0.chars().collect();
+        let symbol: Vec<char> = symbol.chars().collect();
+        let (mut p, mut s) = (0, 0);
+        // Position of the last `*` and the symbol index it has absorbed up to.
+        let mut star: Option<(usize, usize)> = None;
+        while s < symbol.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    star = Some((p, s));
+                    p += 1;
+                }
+                Some(&c) if c == '?' || c == symbol[s] => {
+                    p += 1;
+                    s += 1;
+                }
+                _ => match star {
+                    Some((star_p, star_s)) => {
+                        p = star_p + 1;
+                        s = star_s + 1;
+                        star = Some((star_p, star_s + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_prefixes_and_single_characters() {
+        let es = SymbolPattern::new("ES*");
+        assert!(es.is_glob());
+        assert!(es.matches("ESZ5") && es.matches("ES"));
+        assert!(!es.matches("NQZ5"));
+        let month = SymbolPattern::new("ES?5");
+        assert!(month.matches("ESH5") && !month.matches("ESH25"));
+        assert!(SymbolPattern::new("*-USD").matches("BTC-USD"));
+        let exact = SymbolPattern::new("AAPL");
+        assert!(!exact.is_glob());
+        assert!(exact.matches("AAPL") && !exact.matches("AAPL2"));
+    }
+}
@@ -0,0 +1,100 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::order::Side;
+
+// ---------------------------------------------------------------------------
+// FillPricePolicy — the execution price of a continuous-matching fill
+// ---------------------------------------------------------------------------
+
+/// Rule pricing each fill between an incoming and a standing order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillPricePolicy {
+    /// The standing order's price: the aggressor pays the passive price.
+    Passive,
+    /// Halfway between the aggressor's limit and the standing price.
+    Midpoint,
+    /// A fixed reference price, when both orders accept it.
+    Reference(Decimal),
+    /// The symbol's last trade price, when both orders accept it.
+    LastSale,
+}
+
+impl FillPricePolicy {
+    pub fn parse(policy: &str, reference: Option<Decimal>) -> PyResult<Self> {
+        let parsed = match policy.to_ascii_lowercase().as_str() {
+            "passive" => FillPricePolicy::Passive,
+            "midpoint" => FillPricePolicy::Midpoint,
+            "reference" => FillPricePolicy::Reference(reference.ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(
+                    "reference_price is required for the 'reference' fill price policy",
+                )
+            })?),
+            "last_sale" => FillPricePolicy::LastSale,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid fill price policy '{}'. Expected 'passive', 'midpoint', \
+                     'reference' or 'last_sale'.",
+                    policy
+                )))
+            }
+        };
+        if reference.is_some() && !matches!(parsed, FillPricePolicy::Reference(_)) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "reference_price only applies to the 'reference' fill price policy",
+            ));
+        }
+        Ok(parsed)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FillPricePolicy::Passive => "passive",
+            FillPricePolicy::Midpoint => "midpoint",
+            FillPricePolicy::Reference(_) => "reference",
+            FillPricePolicy::LastSale => "last_sale",
+        }
+    }
+
+    /// Price of a fill between an `incoming` order on `side` and a standing
+    /// order at `standing`. Reference and last-sale prices outside the two
+    /// limits, or a missing last sale, fall back to the passive price.
+    pub fn fill_price(
+        self,
+        side: Side,
+        incoming: Decimal,
+        standing: Decimal,
+        last_sale: Option<Decimal>,
+    ) -> Decimal {
+        let passive = side.calc_fill_price(incoming, standing);
+        let within =
+            |price: &Decimal| (incoming.min(standing)..=incoming.max(standing)).contains(price);
+        match self {
+            FillPricePolicy::Passive => Some(passive),
+            FillPricePolicy::Midpoint => Some((incoming + standing) / Decimal::TWO),
+            FillPricePolicy::Reference(price) => Some(price),
+            FillPricePolicy::LastSale => last_sale,
+        }
+        .filter(within)
+        .unwrap_or(passive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_price_within_both_limits() {
+        let d = Decimal::from;
+        let fill = |policy: FillPricePolicy, last| policy.fill_price(Side::BID, d(12), d(10), last);
+        assert_eq!(fill(FillPricePolicy::Passive, None), d(10));
+        assert_eq!(fill(FillPricePolicy::Midpoint, None), d(11));
+        assert_eq!(fill(FillPricePolicy::Reference(d(11)), None), d(11));
+        assert_eq!(fill(FillPricePolicy::Reference(d(13)), None), d(10));
+        assert_eq!(fill(FillPricePolicy::LastSale, Some(d(12))), d(12));
+        assert_eq!(fill(FillPricePolicy::LastSale, None), d(10));
+        assert!(FillPricePolicy::parse("reference", None).is_err());
+        assert!(FillPricePolicy::parse("midpoint", Some(d(1))).is_err());
+    }
+}
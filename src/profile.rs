@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+/// Orders a new profiler lets pass between two it times.
+pub const DEFAULT_SAMPLE_EVERY: u64 = 100;
+
+// ---------------------------------------------------------------------------
+// Profiler — where the time of sampled orders goes
+// ---------------------------------------------------------------------------
+
+/// A phase of handling an order, as reported by `Book.profile_report`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Risk, reference data and state checks before matching.
+    Validation,
+    /// Finding the symbol's book and the level a remainder rests at.
+    LevelLookup,
+    /// Walking the opposite side's levels and filling against them.
+    Matching,
+    /// Everything else in the engine: self-trade prevention, indexing,
+    /// blotters, events and stop cascades.
+    Other,
+    /// Building and reading the Python objects `match` takes and returns.
+    PythonObjects,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Validation,
+        Phase::LevelLookup,
+        Phase::Matching,
+        Phase::Other,
+        Phase::PythonObjects,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Validation => "validation",
+            Phase::LevelLookup => "level_lookup",
+            Phase::Matching => "matching",
+            Phase::Other => "other",
+            Phase::PythonObjects => "python_objects",
+        }
+    }
+}
+
+/// Mean time of each phase per order: engine phases over the sampled
+/// orders, the Python boundary over every order `match` saw.
+#[derive(Debug)]
+pub struct Profiler {
+    sample_every: u64,
+    /// Orders the engine handled, and how many of them were timed.
+    pub orders: u64,
+    pub sampled: u64,
+    /// Orders `match` converted, timed as whole calls.
+    boundary_orders: u64,
+    totals: [u64; 5],
+    /// Start of the order being timed, and its engine phases so far.
+    current: Option<(Instant, u64)>,
+}
+
+impl Profiler {
+    pub fn new(sample_every: u64) -> Self {
+        Profiler {
+            sample_every,
+            orders: 0,
+            sampled: 0,
+            boundary_orders: 0,
+            totals: [0; 5],
+            current: None,
+        }
+    }
+
+    pub fn sample_every(&self) -> u64 {
+        self.sample_every
+    }
+
+    /// Count an order the engine starts on, timing it if its turn.
+    pub fn begin_order(&mut self) {
+        let sample = self.orders.is_multiple_of(self.sample_every);
+        self.orders += 1;
+        if sample {
+            self.sampled += 1;
+            self.current = Some((Instant::now(), 0));
+        }
+    }
+
+    /// Close the order begun last, putting its untimed rest under "other".
+    pub fn end_order(&mut self) {
+        if let Some((start, timed)) = self.current.take() {
+            let total = start.elapsed().as_nanos() as u64;
+            self.totals[Phase::Other as usize] += total.saturating_sub(timed);
+        }
+    }
+
+    /// A start time for a phase, if the current order is being timed.
+    pub fn mark(&self) -> Option<Instant> {
+        self.current.as_ref().map(|_| Instant::now())
+    }
+
+    /// Credit the time since `mark` to `phase`.
+    pub fn add(&mut self, phase: Phase, mark: Option<Instant>) {
+        let (Some(mark), Some((_, timed))) = (mark, self.current.as_mut()) else {
+            return;
+        };
+        let elapsed = mark.elapsed().as_nanos() as u64;
+        *timed += elapsed;
+        self.totals[phase as usize] += elapsed;
+    }
+
+    /// Credit a `match` call's conversions for `orders` orders.
+    pub fn add_boundary(&mut self, elapsed: Duration, orders: usize) {
+        self.boundary_orders += orders as u64;
+        self.totals[Phase::PythonObjects as usize] += elapsed.as_nanos() as u64;
+    }
+
+    /// Mean nanoseconds per order of each phase; zero before any sample.
+    pub fn means(&self) -> Vec<(Phase, f64)> {
+        Phase::ALL
+            .iter()
+            .map(|&phase| {
+                let count = match phase {
+                    Phase::PythonObjects => self.boundary_orders,
+                    _ => self.sampled,
+                };
+                let total = self.totals[phase as usize] as f64;
+                let mean = if count == 0 {
+                    0.0
+                } else {
+                    total / count as f64
+                };
+                (phase, mean)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_sampled_orders_are_timed() {
+        let mut profiler = Profiler::new(2);
+        for _ in 0..4 {
+            profiler.begin_order();
+            let mark = profiler.mark();
+            profiler.add(Phase::Matching, mark);
+            profiler.end_order();
+        }
+        assert_eq!((profiler.orders, profiler.sampled), (4, 2));
+        profiler.begin_order();
+        assert!(profiler.mark().is_some());
+        profiler.end_order();
+        profiler.begin_order();
+        assert!(profiler.mark().is_none());
+        let means = profiler.means();
+        assert_eq!(means[4], (Phase::PythonObjects, 0.0));
+    }
+}
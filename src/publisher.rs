@@ -0,0 +1,389 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::auction::TradingState;
+use crate::enrich::{extras_to_py, Extras};
+use crate::events::to_event;
+use crate::order::{decimal_to_py, uuid_to_py, Side};
+use crate::trade::Trade;
+
+// ---------------------------------------------------------------------------
+// BusEvent — market events pushed to a publisher
+// ---------------------------------------------------------------------------
+
+/// One event on the trade bus. Order lifecycle and state events are only
+/// produced for publishers using the typed `"event"` encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BusEvent {
+    OrderAccepted {
+        symbol: String,
+        order_id: Uuid,
+        side: Side,
+        price: Decimal,
+        quantity: i64,
+    },
+    OrderCancelled {
+        symbol: String,
+        order_id: Uuid,
+        remaining: i64,
+    },
+    OrderExpired {
+        symbol: String,
+        order_id: Uuid,
+        remaining: i64,
+    },
+    Trade {
+        symbol: String,
+        trade_id: Uuid,
+        incoming_order_id: Uuid,
+        standing_order_id: Uuid,
+        price: Decimal,
+        quantity: i64,
+        extra: Extras,
+    },
+    /// New aggregate quantity at a level; 0 means the level is gone.
+    Level {
+        symbol: String,
+        side: Side,
+        price: Decimal,
+        quantity: i64,
+    },
+    StateChanged {
+        symbol: String,
+        state: TradingState,
+    },
+    QuotesPulled {
+        symbol: String,
+        account: String,
+        fills: usize,
+        cancelled: usize,
+    },
+    /// Orders of `symbol` were restated for a split of `ratio` new shares
+    /// per old one.
+    Split {
+        symbol: String,
+        ratio: Decimal,
+    },
+    /// `symbol` trades as `new_symbol` from now on.
+    SymbolChanged {
+        symbol: String,
+        new_symbol: String,
+    },
+}
+
+impl BusEvent {
+    /// Name of the event, the `type` field of its dict form.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BusEvent::OrderAccepted { .. } => "order_accepted",
+            BusEvent::OrderCancelled { .. } => "order_cancelled",
+            BusEvent::OrderExpired { .. } => "order_expired",
+            BusEvent::Trade { .. } => "trade",
+            BusEvent::Level { .. } => "level",
+            BusEvent::StateChanged { .. } => "state_changed",
+            BusEvent::QuotesPulled { .. } => "quotes_pulled",
+            BusEvent::Split { .. } => "split",
+            BusEvent::SymbolChanged { .. } => "symbol_changed",
+        }
+    }
+
+    /// The order an order lifecycle event is about.
+    pub fn order_id(&self) -> Option<Uuid> {
+        match self {
+            BusEvent::OrderAccepted { order_id, .. }
+            | BusEvent::OrderCancelled { order_id, .. }
+            | BusEvent::OrderExpired { order_id, .. } => Some(*order_id),
+            _ => None,
+        }
+    }
+
+    pub fn trade(symbol: &str, trade: &Trade) -> Self {
+        BusEvent::Trade {
+            symbol: symbol.to_string(),
+            trade_id: trade.trade_id,
+            incoming_order_id: trade.incoming_order_id,
+            standing_order_id: trade.standing_order_id,
+            price: trade.fill_price,
+            quantity: trade.fill_quantity,
+            extra: trade.extra.clone(),
+        }
+    }
+
+    /// Python dict form. With `plain`, ids and prices are strings so the
+    /// dict is JSON-serializable.
+    pub fn to_py(&self, py: Python<'_>, plain: bool) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        let price_obj = |price: Decimal| -> PyResult<PyObject> {
+            if plain {
+                Ok(price.to_string().into_pyobject(py)?.into_any().unbind())
+            } else {
+                decimal_to_py(py, price)
+            }
+        };
+        let id_obj = |id: Uuid| -> PyResult<PyObject> {
+            if plain {
+                Ok(id.to_string().into_pyobject(py)?.into_any().unbind())
+            } else {
+                uuid_to_py(py, id)
+            }
+        };
+        dict.set_item("type", self.kind())?;
+        match self {
+            BusEvent::OrderAccepted {
+                symbol,
+                order_id,
+                side,
+                price,
+                quantity,
+            } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("order_id", id_obj(*order_id)?)?;
+                dict.set_item("side", side.as_str())?;
+                dict.set_item("price", price_obj(*price)?)?;
+                dict.set_item("quantity", quantity)?;
+            }
+            BusEvent::OrderCancelled {
+                symbol,
+                order_id,
+                remaining,
+            }
+            | BusEvent::OrderExpired {
+                symbol,
+                order_id,
+                remaining,
+            } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("order_id", id_obj(*order_id)?)?;
+                dict.set_item("remaining", remaining)?;
+            }
+            BusEvent::Trade {
+                symbol,
+                trade_id,
+                incoming_order_id,
+                standing_order_id,
+                price,
+                quantity,
+                extra,
+            } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("trade_id", id_obj(*trade_id)?)?;
+                dict.set_item("incoming_order_id", id_obj(*incoming_order_id)?)?;
+                dict.set_item("standing_order_id", id_obj(*standing_order_id)?)?;
+                dict.set_item("price", price_obj(*price)?)?;
+                dict.set_item("quantity", quantity)?;
+                if !extra.is_empty() {
+                    dict.set_item("extra", extras_to_py(py, extra, plain)?)?;
+                }
+            }
+            BusEvent::Level {
+                symbol,
+                side,
+                price,
+                quantity,
+            } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("side", side.as_str())?;
+                dict.set_item("price", price_obj(*price)?)?;
+                dict.set_item("quantity", quantity)?;
+            }
+            BusEvent::StateChanged { symbol, state } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("state", state.__str__())?;
+            }
+            BusEvent::QuotesPulled {
+                symbol,
+                account,
+                fills,
+                cancelled,
+            } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("account", account)?;
+                dict.set_item("fills", fills)?;
+                dict.set_item("cancelled", cancelled)?;
+            }
+            BusEvent::Split { symbol, ratio } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("ratio", price_obj(*ratio)?)?;
+            }
+            BusEvent::SymbolChanged { symbol, new_symbol } => {
+                dict.set_item("symbol", symbol)?;
+                dict.set_item("new_symbol", new_symbol)?;
+            }
+        }
+        Ok(dict.into())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// EventRing — bounded buffer between the engine and the consumer
+// ---------------------------------------------------------------------------
+
+/// Bounded FIFO of bus events plus the levels touched since the last
+/// publish. When full, the oldest events are dropped and counted rather
+/// than stalling matching.
+#[derive(Debug)]
+pub struct EventRing {
+    events: VecDeque<BusEvent>,
+    capacity: usize,
+    /// Levels touched since the last publish, as (symbol, side, price).
+    dirty_levels: Vec<(String, Side, Decimal)>,
+    pub dropped: u64,
+}
+
+impl EventRing {
+    pub fn new(capacity: usize) -> Self {
+        EventRing {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            dirty_levels: Vec::new(),
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, event: BusEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    /// Events waiting for delivery.
+    pub fn pending(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn touch_level(&mut self, symbol: &str, side: Side, price: Decimal) {
+        self.dirty_levels.push((symbol.to_string(), side, price));
+    }
+
+    /// Take the touched levels, each reported once in first-touch order.
+    pub fn take_dirty_levels(&mut self) -> Vec<(String, Side, Decimal)> {
+        let mut levels = std::mem::take(&mut self.dirty_levels);
+        let mut seen = std::collections::HashSet::new();
+        levels.retain(|key| seen.insert(key.clone()));
+        levels
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Publisher — batching delivery to a Python callback
+// ---------------------------------------------------------------------------
+
+/// How a publisher hands batches to its callback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    Dict,
+    Json,
+    Event,
+}
+
+/// Hands buffered bus events to a Python callable `batch_size` at a time,
+/// as a list of dicts, a list of typed `Event` objects, or (with
+/// `encoding="json"`) one JSON string per batch, ready for e.g. a ZeroMQ
+/// `send_string`.
+#[derive(Debug)]
+pub struct Publisher {
+    callback: PyObject,
+    batch_size: usize,
+    encoding: Encoding,
+    pub ring: EventRing,
+    pub published: u64,
+}
+
+impl Publisher {
+    pub fn new(
+        callback: PyObject,
+        batch_size: usize,
+        capacity: usize,
+        encoding: &str,
+    ) -> PyResult<Self> {
+        if batch_size == 0 || capacity < batch_size {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "batch_size must be at least 1 and no greater than capacity",
+            ));
+        }
+        let encoding = match encoding {
+            "dict" => Encoding::Dict,
+            "json" => Encoding::Json,
+            "event" => Encoding::Event,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid encoding '{}'. Expected 'dict', 'json' or 'event'.",
+                    encoding
+                )))
+            }
+        };
+        Ok(Publisher {
+            callback,
+            batch_size,
+            encoding,
+            ring: EventRing::new(capacity),
+            published: 0,
+        })
+    }
+
+    /// Whether order lifecycle and state events are published too.
+    pub fn wants_lifecycle(&self) -> bool {
+        self.encoding == Encoding::Event
+    }
+
+    /// Deliver full batches, or everything buffered when `force` is set.
+    /// A batch stays buffered if the callback raises.
+    pub fn deliver(&mut self, py: Python<'_>, force: bool) -> PyResult<()> {
+        let events = &mut self.ring.events;
+        while events.len() >= self.batch_size || (force && !events.is_empty()) {
+            let n = self.batch_size.min(events.len());
+            let batch = PyList::empty(py);
+            for event in events.iter().take(n) {
+                match self.encoding {
+                    Encoding::Event => batch.append(to_event(py, event)?)?,
+                    encoding => batch.append(event.to_py(py, encoding == Encoding::Json)?)?,
+                }
+            }
+            let payload: PyObject = if self.encoding == Encoding::Json {
+                py.import("json")?.call_method1("dumps", (batch,))?.unbind()
+            } else {
+                batch.into_any().unbind()
+            };
+            self.callback.call1(py, (payload,))?;
+            events.drain(..n);
+            self.published += n as u64;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(quantity: i64) -> BusEvent {
+        BusEvent::Level {
+            symbol: "X".to_string(),
+            side: Side::BID,
+            price: Decimal::ONE,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn ring_drops_oldest_when_full_and_dedupes_levels() {
+        let mut ring = EventRing::new(2);
+        for q in 0..5 {
+            ring.push(level(q));
+        }
+        assert_eq!(ring.pending(), 2);
+        assert_eq!(ring.dropped, 3);
+        assert_eq!(ring.events.front(), Some(&level(3)));
+
+        ring.touch_level("X", Side::BID, Decimal::ONE);
+        ring.touch_level("X", Side::ASK, Decimal::ONE);
+        ring.touch_level("X", Side::BID, Decimal::ONE);
+        assert_eq!(ring.take_dirty_levels().len(), 2);
+        assert!(ring.take_dirty_levels().is_empty());
+    }
+}
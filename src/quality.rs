@@ -0,0 +1,212 @@
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+
+use crate::flow::lee_ready;
+use crate::order::Side;
+
+// ---------------------------------------------------------------------------
+// ExecutionQuality — effective and realized spreads, price improvement
+// ---------------------------------------------------------------------------
+
+/// One classified fill and the quote it met.
+#[derive(Clone, Debug)]
+struct Fill {
+    time: i64,
+    /// 1 for a buyer-initiated fill, -1 for a seller-initiated one.
+    sign: Decimal,
+    price: Decimal,
+    quantity: Decimal,
+    /// Midpoint before the event that made the fill.
+    mid: Decimal,
+    /// Best price on the side the fill traded against, before the event.
+    quoted: Decimal,
+}
+
+/// Volume-weighted execution quality of a symbol's fills.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// Classified fills and their volume.
+    pub trades: usize,
+    pub volume: i64,
+    /// Volume left out: traded against a one-sided book, or at the
+    /// midpoint with no earlier price change to sign it.
+    pub unclassified_volume: i64,
+    /// Fills too recent for the horizon, left out of the realized spread
+    /// and price impact.
+    pub pending: usize,
+    /// 2 × |fill − mid|.
+    pub effective_spread: Option<Decimal>,
+    /// 2 × sign × (fill − mid after the horizon).
+    pub realized_spread: Option<Decimal>,
+    /// 2 × sign × (mid after the horizon − mid), the rest of the effective
+    /// spread.
+    pub price_impact: Option<Decimal>,
+    /// sign × (quoted − fill): positive when a fill beat the quote.
+    pub price_improvement: Option<Decimal>,
+}
+
+/// Fills of one symbol and its midpoint history, from which execution
+/// quality is measured at any horizon.
+#[derive(Clone, Debug)]
+pub struct ExecutionQuality {
+    bid: Option<Decimal>,
+    ask: Option<Decimal>,
+    last_price: Option<Decimal>,
+    /// Direction of the last trade-to-trade price change.
+    tick: Option<Ordering>,
+    /// Trades since the last observed event, as (price, quantity).
+    pending: Vec<(Decimal, i64)>,
+    unclassified_volume: i64,
+    fills: Vec<Fill>,
+    /// (clock, midpoint) at each change of the midpoint, oldest first.
+    mids: Vec<(i64, Option<Decimal>)>,
+}
+
+fn midpoint(bid: Option<Decimal>, ask: Option<Decimal>) -> Option<Decimal> {
+    Some((bid? + ask?) / Decimal::TWO)
+}
+
+/// Volume-weighted average, None without volume.
+fn average(total: Decimal, volume: Decimal) -> Option<Decimal> {
+    (!volume.is_zero()).then(|| (total / volume).normalize())
+}
+
+impl ExecutionQuality {
+    pub fn new(now: i64, bid: Option<Decimal>, ask: Option<Decimal>) -> Self {
+        ExecutionQuality {
+            bid,
+            ask,
+            last_price: None,
+            tick: None,
+            pending: Vec::new(),
+            unclassified_volume: 0,
+            fills: Vec::new(),
+            mids: vec![(now, midpoint(bid, ask))],
+        }
+    }
+
+    /// Hold trades until the event that made them is observed.
+    pub fn trade(&mut self, price: Decimal, quantity: i64) {
+        self.pending.push((price, quantity));
+    }
+
+    /// Close an event at clock `now` that left the best prices at
+    /// `bid`/`ask`: measure its trades against the quote before it.
+    pub fn observe(&mut self, now: i64, bid: Option<Decimal>, ask: Option<Decimal>) {
+        let mid = midpoint(self.bid, self.ask);
+        for (price, quantity) in std::mem::take(&mut self.pending) {
+            if let Some(last) = self.last_price {
+                if price != last {
+                    self.tick = Some(price.cmp(&last));
+                }
+            }
+            self.last_price = Some(price);
+            let side = lee_ready(price, mid, self.tick.unwrap_or(Ordering::Equal));
+            let quoted = match side {
+                Some(Side::BID) => self.ask,
+                Some(Side::ASK) => self.bid,
+                None => None,
+            };
+            let (Some(side), Some(mid), Some(quoted)) = (side, mid, quoted) else {
+                self.unclassified_volume += quantity;
+                continue;
+            };
+            self.fills.push(Fill {
+                time: now,
+                sign: if side == Side::BID {
+                    Decimal::ONE
+                } else {
+                    Decimal::NEGATIVE_ONE
+                },
+                price,
+                quantity: Decimal::from(quantity),
+                mid,
+                quoted,
+            });
+        }
+        let after = midpoint(bid, ask);
+        if self.mids.last().map(|(_, mid)| *mid) != Some(after) {
+            self.mids.push((now, after));
+        }
+        self.bid = bid;
+        self.ask = ask;
+    }
+
+    /// Midpoint prevailing at clock `time`.
+    fn mid_at(&self, time: i64) -> Option<Decimal> {
+        let index = self.mids.partition_point(|(at, _)| *at <= time);
+        self.mids.get(index.checked_sub(1)?)?.1
+    }
+
+    /// Execution quality of every fill so far, with the realized spread
+    /// taken `horizon` clock units after each fill, as of clock `now`.
+    pub fn report(&self, horizon: i64, now: i64) -> Report {
+        let mut report = Report {
+            unclassified_volume: self.unclassified_volume,
+            ..Report::default()
+        };
+        let (mut effective, mut improvement, mut volume) =
+            (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        let (mut realized, mut impact, mut realized_volume) =
+            (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        for fill in &self.fills {
+            report.trades += 1;
+            effective += Decimal::TWO * (fill.price - fill.mid).abs() * fill.quantity;
+            improvement += fill.sign * (fill.quoted - fill.price) * fill.quantity;
+            volume += fill.quantity;
+            let later = fill.time.saturating_add(horizon);
+            if later > now {
+                report.pending += 1;
+                continue;
+            }
+            if let Some(mid) = self.mid_at(later) {
+                realized += Decimal::TWO * fill.sign * (fill.price - mid) * fill.quantity;
+                impact += Decimal::TWO * fill.sign * (mid - fill.mid) * fill.quantity;
+                realized_volume += fill.quantity;
+            }
+        }
+        report.volume = volume.try_into().unwrap_or(i64::MAX);
+        report.effective_spread = average(effective, volume);
+        report.price_improvement = average(improvement, volume);
+        report.realized_spread = average(realized, realized_volume);
+        report.price_impact = average(impact, realized_volume);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_spread_splits_into_realized_spread_and_impact() {
+        let px = |n: i64| Some(Decimal::from(n));
+        let mut quality = ExecutionQuality::new(0, px(99), px(101));
+        // A buyer lifts the offer at 101 against a mid of 100.
+        quality.trade(Decimal::from(101), 10);
+        quality.observe(5, px(99), px(103));
+        // The mid settles at 102 later on.
+        quality.observe(20, px(101), px(103));
+
+        let report = quality.report(10, 30);
+        assert_eq!((report.trades, report.volume, report.pending), (1, 10, 0));
+        assert_eq!(report.effective_spread, px(2));
+        assert_eq!(report.price_improvement, px(0));
+        // At 15 the mid was 101; by 25 it was 102.
+        assert_eq!(report.realized_spread, px(0));
+        assert_eq!(report.price_impact, px(2));
+        let report = quality.report(20, 30);
+        assert_eq!(report.realized_spread, px(-2));
+        assert_eq!(report.price_impact, px(4));
+        // Too soon to tell.
+        let report = quality.report(30, 30);
+        assert_eq!((report.pending, report.realized_spread), (1, None));
+
+        let mut one_sided = ExecutionQuality::new(0, None, px(101));
+        one_sided.trade(Decimal::from(101), 4);
+        one_sided.observe(1, None, None);
+        let report = one_sided.report(0, 1);
+        assert_eq!((report.trades, report.unclassified_volume), (0, 4));
+        assert_eq!(report.effective_spread, None);
+    }
+}
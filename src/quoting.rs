@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+// ---------------------------------------------------------------------------
+// Quotes — two-sided quotes and market-maker protections
+// ---------------------------------------------------------------------------
+
+/// The live orders of one `Book.quote` quote.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuoteOrders {
+    pub account: Option<String>,
+    pub bid: Option<Uuid>,
+    pub ask: Option<Uuid>,
+}
+
+impl QuoteOrders {
+    pub fn ids(&self) -> impl Iterator<Item = Uuid> {
+        self.bid.into_iter().chain(self.ask)
+    }
+}
+
+/// At most `limit` events within any `window` clock units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: usize,
+    pub window: i64,
+}
+
+impl RateLimit {
+    /// Forget events that fell out of the window ending at `now`.
+    pub fn prune(&self, times: &mut VecDeque<i64>, now: i64) {
+        while times.front().is_some_and(|&t| t <= now - self.window) {
+            times.pop_front();
+        }
+    }
+}
+
+/// Per-account quote throttle and fill-count protection.
+#[derive(Clone, Debug)]
+pub struct QuoteProtection {
+    pub updates: Option<RateLimit>,
+    pub fills: Option<RateLimit>,
+    update_times: VecDeque<i64>,
+    fill_times: VecDeque<i64>,
+}
+
+impl QuoteProtection {
+    pub fn new(updates: Option<RateLimit>, fills: Option<RateLimit>) -> Self {
+        QuoteProtection {
+            updates,
+            fills,
+            update_times: VecDeque::new(),
+            fill_times: VecDeque::new(),
+        }
+    }
+
+    /// Count a quote update at `now`, or refuse it with the limit it
+    /// would break when the window is full.
+    pub fn allow_update(&mut self, now: i64) -> Result<(), RateLimit> {
+        if let Some(rate) = self.updates {
+            rate.prune(&mut self.update_times, now);
+            if self.update_times.len() >= rate.limit {
+                return Err(rate);
+            }
+            self.update_times.push_back(now);
+        }
+        Ok(())
+    }
+
+    /// Count a fill against the account's quotes at `now`. Returns the fill
+    /// count when it reaches the limit, restarting the count.
+    pub fn record_fill(&mut self, now: i64) -> Option<usize> {
+        let rate = self.fills?;
+        rate.prune(&mut self.fill_times, now);
+        self.fill_times.push_back(now);
+        if self.fill_times.len() < rate.limit {
+            return None;
+        }
+        let fills = self.fill_times.len();
+        self.fill_times.clear();
+        Some(fills)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_apply_within_a_sliding_window() {
+        let rate = RateLimit {
+            limit: 2,
+            window: 10,
+        };
+        let mut protection = QuoteProtection::new(Some(rate), Some(rate));
+        assert!(protection.allow_update(0).is_ok());
+        assert!(protection.allow_update(5).is_ok());
+        assert_eq!(protection.allow_update(9), Err(rate));
+        assert!(protection.allow_update(10).is_ok());
+
+        assert_eq!(protection.record_fill(0), None);
+        assert_eq!(protection.record_fill(10), None);
+        assert_eq!(protection.record_fill(15), Some(2));
+        assert_eq!(protection.record_fill(16), None);
+    }
+}
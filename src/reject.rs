@@ -0,0 +1,234 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::PyErrArguments;
+use uuid::Uuid;
+
+use crate::order::{extract_order_id, uuid_to_py};
+
+create_exception!(
+    pyorderbook,
+    OrderRejected,
+    PyValueError,
+    "An order the book refused. A ValueError whose `code` names the reason."
+);
+
+// ---------------------------------------------------------------------------
+// RejectCode — why the book refused an order
+// ---------------------------------------------------------------------------
+
+/// Reason codes of `OrderRejected`, exposed to Python as strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectCode {
+    /// Price not a multiple of the tick size, or finer than the precision.
+    BadTick,
+    /// Quantity not a multiple of the lot size.
+    BadLot,
+    /// Zero or negative price for an instrument that does not allow it.
+    BadPrice,
+    /// The symbol's trading phase or an auction cutoff refuses the order.
+    TradingState,
+    /// Good-till-date order already past its expiry.
+    Expired,
+    /// Short sale beyond the available locate.
+    Locate,
+    /// Order beyond the submitter's risk limits.
+    RiskLimit,
+    /// Message beyond a rate limit that rejects rather than queues.
+    Throttled,
+    /// Order id already resting, under the "reject" duplicate policy.
+    DuplicateId,
+    /// Order that would take the book past a capacity limit.
+    Capacity,
+    /// Order a registered validator refused.
+    Validation,
+}
+
+impl RejectCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RejectCode::BadTick => "bad_tick",
+            RejectCode::BadLot => "bad_lot",
+            RejectCode::BadPrice => "bad_price",
+            RejectCode::TradingState => "trading_state",
+            RejectCode::Expired => "expired",
+            RejectCode::Locate => "locate",
+            RejectCode::RiskLimit => "risk_limit",
+            RejectCode::Throttled => "throttled",
+            RejectCode::DuplicateId => "duplicate_id",
+            RejectCode::Capacity => "capacity",
+            RejectCode::Validation => "validation",
+        }
+    }
+
+    /// An `OrderRejected` error carrying this code.
+    pub fn err(self, message: String) -> PyErr {
+        PyErr::new::<OrderRejected, _>(Rejected {
+            message,
+            code: self,
+            validator: None,
+        })
+    }
+
+    /// An `OrderRejected` error carrying this code and, as its `validator`,
+    /// the name of the validator that refused the order.
+    pub fn err_from(self, validator: &str, message: String) -> PyErr {
+        PyErr::new::<OrderRejected, _>(Rejected {
+            message,
+            code: self,
+            validator: Some(validator.to_string()),
+        })
+    }
+}
+
+/// Lazy arguments of an `OrderRejected` error: the exception instance
+/// itself, so that it can carry `code` and still print as the message.
+struct Rejected {
+    message: String,
+    code: RejectCode,
+    validator: Option<String>,
+}
+
+impl PyErrArguments for Rejected {
+    fn arguments(self, py: Python<'_>) -> PyObject {
+        let value = OrderRejected::new_err(self.message).into_value(py);
+        // Setting an attribute on a fresh exception instance cannot fail.
+        let _ = value.bind(py).setattr("code", self.code.as_str());
+        if let Some(validator) = self.validator {
+            let _ = value.bind(py).setattr("validator", validator);
+        }
+        value.into_any()
+    }
+}
+
+/// Code for reporting `err` as a rejected action: its `OrderRejected`
+/// code, "invalid" for any other ValueError, or None for errors that are
+/// not about the action (which should propagate).
+pub fn code_of(py: Python<'_>, err: &PyErr) -> Option<String> {
+    if err.is_instance_of::<OrderRejected>(py) {
+        if let Ok(code) = err.value(py).getattr("code").and_then(|c| c.extract()) {
+            return Some(code);
+        }
+    }
+    err.is_instance_of::<PyValueError>(py)
+        .then(|| "invalid".to_string())
+}
+
+/// How a harness treats actions the book refuses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectPolicy {
+    /// Propagate the error, ending the run.
+    Raise,
+    /// Hand a `Rejection` back to the caller and carry on.
+    Report,
+}
+
+impl RejectPolicy {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "raise" => Ok(RejectPolicy::Raise),
+            "report" => Ok(RejectPolicy::Report),
+            _ => Err(PyValueError::new_err(format!(
+                "Invalid on_reject '{}'. Expected 'raise' or 'report'.",
+                text
+            ))),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rejection — a refused action, reported instead of raised
+// ---------------------------------------------------------------------------
+
+/// An action a simulation harness refused, with a machine-readable `code`
+/// (such as "bad_tick", "risk_limit" or "price_band") and the
+/// human-readable `reason`. `order_id` is the refused order's, when the
+/// action concerned one, and `time` the book's clock when it was refused.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct Rejection {
+    pub code: String,
+    pub reason: String,
+    pub order_id: Option<Uuid>,
+    pub time: Option<i64>,
+}
+
+impl Rejection {
+    pub fn from_rust(
+        code: String,
+        reason: String,
+        order_id: Option<Uuid>,
+        time: Option<i64>,
+    ) -> Self {
+        Rejection {
+            code,
+            reason,
+            order_id,
+            time,
+        }
+    }
+}
+
+#[pymethods]
+impl Rejection {
+    #[new]
+    #[pyo3(signature = (code, reason, order_id = None, time = None))]
+    fn new(
+        code: String,
+        reason: String,
+        order_id: Option<&Bound<'_, pyo3::PyAny>>,
+        time: Option<i64>,
+    ) -> PyResult<Self> {
+        let order_id = order_id.map(extract_order_id).transpose()?;
+        Ok(Rejection::from_rust(code, reason, order_id, time))
+    }
+
+    #[getter]
+    fn code(&self) -> &str {
+        &self.code
+    }
+
+    #[getter]
+    fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    #[getter]
+    fn order_id(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.order_id.map(|id| uuid_to_py(py, id)).transpose()
+    }
+
+    #[getter]
+    fn time(&self) -> Option<i64> {
+        self.time
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Rejection(code={:?}, reason={:?})", self.code, self.reason)
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejections_carry_their_code() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = RejectCode::BadTick.err("Price 1.05 is not a multiple".to_string());
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(err.value(py).to_string(), "Price 1.05 is not a multiple");
+            assert_eq!(code_of(py, &err).as_deref(), Some("bad_tick"));
+            let other = PyValueError::new_err("duplicate");
+            assert_eq!(code_of(py, &other).as_deref(), Some("invalid"));
+            let bug = pyo3::exceptions::PyTypeError::new_err("not an order");
+            assert_eq!(code_of(py, &bug), None);
+        });
+    }
+}
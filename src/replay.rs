@@ -0,0 +1,146 @@
+use pyo3::prelude::*;
+use std::time::Instant;
+
+use crate::trade::TradeBlotter;
+
+// ---------------------------------------------------------------------------
+// ReplaySummary — aggregate outcome of a replay
+// ---------------------------------------------------------------------------
+
+/// Totals of a `replay_parquet(..., summary=True)` run.
+///
+/// `orders_added` counts rows whose order (or its unfilled remainder) came
+/// to rest in the book. `rejects` lists `(row_index, reason)` for rows the
+/// book refused; the replay carries on past them.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct ReplaySummary {
+    pub rows: usize,
+    pub orders_added: usize,
+    pub trades: usize,
+    pub volume: i64,
+    pub rejects: Vec<(usize, String)>,
+    pub duration: f64,
+}
+
+#[pymethods]
+impl ReplaySummary {
+    #[getter]
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[getter]
+    fn orders_added(&self) -> usize {
+        self.orders_added
+    }
+
+    #[getter]
+    fn trades(&self) -> usize {
+        self.trades
+    }
+
+    #[getter]
+    fn volume(&self) -> i64 {
+        self.volume
+    }
+
+    #[getter]
+    fn rejects(&self) -> Vec<(usize, String)> {
+        self.rejects.clone()
+    }
+
+    /// Wall-clock seconds the replay took.
+    #[getter]
+    fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// Rows processed per wall-clock second.
+    #[getter]
+    fn events_per_sec(&self) -> f64 {
+        if self.duration > 0.0 {
+            self.rows as f64 / self.duration
+        } else {
+            0.0
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ReplaySummary(rows={}, orders_added={}, trades={}, volume={}, rejects={}, duration={:.3})",
+            self.rows,
+            self.orders_added,
+            self.trades,
+            self.volume,
+            self.rejects.len(),
+            self.duration
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+/// Accumulates a `ReplaySummary` row by row.
+#[derive(Debug)]
+pub struct ReplayTally {
+    started: Instant,
+    summary: ReplaySummary,
+}
+
+impl ReplayTally {
+    pub fn start() -> Self {
+        ReplayTally {
+            started: Instant::now(),
+            summary: ReplaySummary {
+                rows: 0,
+                orders_added: 0,
+                trades: 0,
+                volume: 0,
+                rejects: Vec::new(),
+                duration: 0.0,
+            },
+        }
+    }
+
+    pub fn record(&mut self, blotter: &TradeBlotter, rested: bool) {
+        let summary = &mut self.summary;
+        summary.rows += 1;
+        summary.orders_added += usize::from(rested);
+        let cascaded = blotter.cascade.activations.iter().flat_map(|a| &a.trades);
+        for trade in blotter.trades.iter().chain(cascaded) {
+            summary.trades += 1;
+            summary.volume += trade.fill_quantity;
+        }
+    }
+
+    pub fn reject(&mut self, row_idx: usize, reason: String) {
+        self.summary.rows += 1;
+        self.summary.rejects.push((row_idx, reason));
+    }
+
+    /// Close the tally; rejects are listed in row order.
+    pub fn finish(mut self) -> ReplaySummary {
+        self.summary.rejects.sort_by_key(|(row_idx, _)| *row_idx);
+        self.summary.duration = self.started.elapsed().as_secs_f64();
+        self.summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_count_as_rows() {
+        let mut tally = ReplayTally::start();
+        tally.reject(3, "Order quantity must be positive".to_string());
+        let summary = tally.finish();
+        assert_eq!((summary.rows, summary.trades, summary.volume), (1, 0, 0));
+        assert_eq!(summary.rejects[0].0, 3);
+        assert!(summary.duration >= 0.0);
+    }
+}
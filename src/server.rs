@@ -0,0 +1,749 @@
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::book::Book;
+use crate::order::{Order, Side};
+use crate::snapshot::Snapshot;
+use crate::trade::{Trade, TradeBlotter};
+
+/// Largest frame the server accepts (1 MiB).
+const MAX_FRAME: usize = 1 << 20;
+
+/// How often the accept loop checks for shutdown.
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+// ---------------------------------------------------------------------------
+// Framing — 4-byte big-endian length prefix followed by a JSON payload
+// ---------------------------------------------------------------------------
+
+/// Read one frame. Returns `Ok(None)` when the peer closed the connection.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0u8; 4];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Frame of {} bytes exceeds the {} byte limit",
+                len, MAX_FRAME
+            ),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// Write one frame.
+pub fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+// ---------------------------------------------------------------------------
+// Protocol — client requests and server responses
+// ---------------------------------------------------------------------------
+
+/// An order-entry message sent by a client.
+///
+/// ```text
+/// {"type": "new", "client_order_id": 7, "side": "bid", "symbol": "AAPL",
+///  "price": "150.25", "quantity": 100}
+/// {"type": "cancel", "order_id": "<uuid>"}
+/// {"type": "amend", "order_id": "<uuid>", "quantity": 50, "price": "150.5"}
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    New {
+        client_order_id: Value,
+        side: Side,
+        symbol: String,
+        price: Decimal,
+        quantity: i64,
+    },
+    Cancel {
+        order_id: Uuid,
+    },
+    Amend {
+        order_id: Uuid,
+        price: Option<Decimal>,
+        quantity: Option<i64>,
+    },
+}
+
+impl Request {
+    pub fn parse(payload: &[u8]) -> Result<Self, String> {
+        let msg: Value =
+            serde_json::from_slice(payload).map_err(|e| format!("Invalid JSON: {}", e))?;
+        let kind = field(&msg, "type")?
+            .as_str()
+            .ok_or("'type' must be a string")?;
+        match kind {
+            "new" => Ok(Request::New {
+                client_order_id: msg.get("client_order_id").cloned().unwrap_or(Value::Null),
+                side: parse_side(field(&msg, "side")?)?,
+                symbol: field(&msg, "symbol")?
+                    .as_str()
+                    .ok_or("'symbol' must be a string")?
+                    .to_string(),
+                price: parse_price(field(&msg, "price")?)?,
+                quantity: parse_quantity(field(&msg, "quantity")?)?,
+            }),
+            "cancel" => Ok(Request::Cancel {
+                order_id: parse_order_id(&msg)?,
+            }),
+            "amend" => Ok(Request::Amend {
+                order_id: parse_order_id(&msg)?,
+                price: msg.get("price").map(parse_price).transpose()?,
+                quantity: msg.get("quantity").map(parse_quantity).transpose()?,
+            }),
+            other => Err(format!("Unknown message type '{}'", other)),
+        }
+    }
+}
+
+fn field<'a>(msg: &'a Value, name: &str) -> Result<&'a Value, String> {
+    msg.get(name)
+        .ok_or_else(|| format!("Message is missing '{}'", name))
+}
+
+fn parse_side(value: &Value) -> Result<Side, String> {
+    match value.as_str().map(str::to_ascii_lowercase).as_deref() {
+        Some("bid" | "buy") => Ok(Side::BID),
+        Some("ask" | "sell") => Ok(Side::ASK),
+        _ => Err(format!("Invalid side {}", value)),
+    }
+}
+
+/// Prices are accepted as strings (exact) or JSON numbers.
+fn parse_price(value: &Value) -> Result<Decimal, String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        other => return Err(format!("Invalid price {}", other)),
+    };
+    text.parse::<Decimal>()
+        .or_else(|_| Decimal::from_scientific(&text))
+        .map(|p| if p.is_zero() { Decimal::ZERO } else { p })
+        .map_err(|e| format!("Invalid price {}: {}", value, e))
+}
+
+fn parse_quantity(value: &Value) -> Result<i64, String> {
+    value
+        .as_i64()
+        .ok_or_else(|| format!("Invalid quantity {}", value))
+}
+
+fn parse_order_id(msg: &Value) -> Result<Uuid, String> {
+    let raw = field(msg, "order_id")?
+        .as_str()
+        .ok_or("'order_id' must be a string")?;
+    Uuid::parse_str(raw).map_err(|e| format!("Invalid order_id '{}': {}", raw, e))
+}
+
+fn reject(reason: &str, context: Value) -> Vec<u8> {
+    let mut msg = json!({"type": "reject", "reason": reason});
+    if let (Value::Object(msg), Value::Object(context)) = (&mut msg, context) {
+        msg.extend(context);
+    }
+    msg.to_string().into_bytes()
+}
+
+fn execution(order_id: Uuid, trade: &Trade) -> Vec<u8> {
+    json!({
+        "type": "execution",
+        "order_id": order_id.to_string(),
+        "trade_id": trade.trade_id.to_string(),
+        "price": trade.fill_price.to_string(),
+        "quantity": trade.fill_quantity,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+fn error_text(err: PyErr) -> String {
+    Python::with_gil(|py| err.value(py).to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Engine — owns the Book on a dedicated thread
+// ---------------------------------------------------------------------------
+
+type ConnId = u64;
+
+enum Command {
+    Connect(ConnId, Sender<Vec<u8>>),
+    Disconnect(ConnId),
+    Request(ConnId, Request),
+    Snapshot(String, isize, Sender<Option<Snapshot>>),
+    Shutdown,
+}
+
+/// Single-threaded matching loop. Executions are routed to the session that
+/// entered each side of the trade; orders keep working after their session
+/// disconnects. Owners are forgotten once their orders leave the book.
+struct Engine {
+    book: Book,
+    sessions: HashMap<ConnId, Sender<Vec<u8>>>,
+    owners: HashMap<Uuid, ConnId>,
+}
+
+impl Engine {
+    fn run(mut self, commands: Receiver<Command>) {
+        for command in commands {
+            match command {
+                Command::Connect(conn, outbox) => {
+                    self.sessions.insert(conn, outbox);
+                }
+                Command::Disconnect(conn) => {
+                    self.sessions.remove(&conn);
+                }
+                Command::Request(conn, request) => self.handle(conn, request),
+                Command::Snapshot(symbol, depth, reply) => {
+                    let _ = reply.send(self.book.snapshot(&symbol, depth));
+                }
+                Command::Shutdown => break,
+            }
+        }
+    }
+
+    fn send(&self, conn: ConnId, frame: Vec<u8>) {
+        if let Some(outbox) = self.sessions.get(&conn) {
+            let _ = outbox.send(frame);
+        }
+    }
+
+    fn handle(&mut self, conn: ConnId, request: Request) {
+        match request {
+            Request::New {
+                client_order_id,
+                side,
+                symbol,
+                price,
+                quantity,
+            } => {
+                let matched = Order::try_new_at(side, symbol, price, quantity)
+                    .and_then(|order| self.book.match_single(&order));
+                match matched {
+                    Ok(blotter) => {
+                        self.owners.insert(blotter.order.id, conn);
+                        let ack = json!({
+                            "type": "ack",
+                            "order_id": blotter.order.id.to_string(),
+                            "client_order_id": client_order_id,
+                        });
+                        self.send(conn, ack.to_string().into_bytes());
+                        self.report(&blotter);
+                        self.forget_closed(&blotter);
+                    }
+                    Err(err) => {
+                        let context = json!({"client_order_id": client_order_id});
+                        self.send(conn, reject(&error_text(err), context));
+                    }
+                }
+            }
+            Request::Cancel { order_id } => {
+                let context = json!({"order_id": order_id.to_string()});
+                let cancelled = match self.owners.get(&order_id) {
+                    Some(&owner) if owner == conn => self.book.cancel_order(order_id),
+                    _ => Ok(false),
+                };
+                match cancelled {
+                    Ok(true) => {
+                        self.owners.remove(&order_id);
+                        let msg = json!({"type": "canceled", "order_id": order_id.to_string()});
+                        self.send(conn, msg.to_string().into_bytes());
+                    }
+                    Ok(false) => self.send(conn, reject("Unknown order", context)),
+                    Err(err) => self.send(conn, reject(&error_text(err), context)),
+                }
+            }
+            Request::Amend {
+                order_id,
+                price,
+                quantity,
+            } => {
+                let context = json!({"order_id": order_id.to_string()});
+                let amended = match self.owners.get(&order_id) {
+                    Some(&owner) if owner == conn => {
+                        self.book.amend_order(order_id, price, quantity)
+                    }
+                    _ => Ok(None),
+                };
+                match amended {
+                    Ok(Some(blotter)) => {
+                        let msg = json!({"type": "amended", "order_id": order_id.to_string()});
+                        self.send(conn, msg.to_string().into_bytes());
+                        self.report(&blotter);
+                        self.forget_closed(&blotter);
+                    }
+                    Ok(None) => self.send(conn, reject("Unknown order", context)),
+                    Err(err) => self.send(conn, reject(&error_text(err), context)),
+                }
+            }
+        }
+    }
+
+    /// Send an execution report to the owner of each side of every trade.
+    fn report(&self, blotter: &TradeBlotter) {
+        for trade in trades_of(blotter) {
+            for order_id in [trade.incoming_order_id, trade.standing_order_id] {
+                if let Some(&owner) = self.owners.get(&order_id) {
+                    self.send(owner, execution(order_id, trade));
+                }
+            }
+        }
+    }
+
+    /// Drop the owners of orders in `blotter` that are no longer open.
+    fn forget_closed(&mut self, blotter: &TradeBlotter) {
+        let touched = trades_of(blotter)
+            .flat_map(|trade| [trade.incoming_order_id, trade.standing_order_id])
+            .chain([blotter.order.id]);
+        for order_id in touched {
+            if !self.book.is_live(order_id) {
+                self.owners.remove(&order_id);
+            }
+        }
+    }
+}
+
+/// The trades of `blotter`, then those of the stops it triggered.
+fn trades_of(blotter: &TradeBlotter) -> impl Iterator<Item = &Trade> {
+    let cascade = blotter
+        .cascade
+        .activations
+        .iter()
+        .flat_map(|a| a.trades.iter());
+    blotter.trades.iter().chain(cascade)
+}
+
+// ---------------------------------------------------------------------------
+// Transport — TCP or Unix-domain sockets
+// ---------------------------------------------------------------------------
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, String),
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Listener {
+    /// Bind `"host:port"` or `"unix:/path/to/socket"`.
+    fn bind(address: &str) -> io::Result<Self> {
+        if let Some(path) = address.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Listener::Unix(UnixListener::bind(path)?, path.to_string()));
+            #[cfg(not(unix))]
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unix sockets are not supported on this platform: {}", path),
+            ));
+        }
+        Ok(Listener::Tcp(TcpListener::bind(address)?))
+    }
+
+    fn local_address(&self) -> io::Result<String> {
+        match self {
+            Listener::Tcp(listener) => Ok(listener.local_addr()?.to_string()),
+            #[cfg(unix)]
+            Listener::Unix(_, path) => Ok(format!("unix:{}", path)),
+        }
+    }
+
+    fn set_nonblocking(&self) -> io::Result<()> {
+        match self {
+            Listener::Tcp(listener) => listener.set_nonblocking(true),
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => listener.set_nonblocking(true),
+        }
+    }
+
+    fn accept(&self) -> io::Result<Stream> {
+        let stream = match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                stream.set_nodelay(true)?;
+                Stream::Tcp(stream)
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(false)?;
+                Stream::Unix(stream)
+            }
+        };
+        Ok(stream)
+    }
+}
+
+impl Stream {
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Stream::Tcp(stream) => Ok(Stream::Tcp(stream.try_clone()?)),
+            #[cfg(unix)]
+            Stream::Unix(stream) => Ok(Stream::Unix(stream.try_clone()?)),
+        }
+    }
+
+    fn shutdown(&self) {
+        let _ = match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Both),
+        };
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Connections still open, so `stop` can close them.
+type OpenStreams = Arc<Mutex<HashMap<ConnId, Stream>>>;
+
+/// Start a reader and a writer thread for one client connection. The
+/// connection leaves `open_streams` when its peer disconnects.
+fn serve_connection(
+    conn: ConnId,
+    stream: Stream,
+    commands: Sender<Command>,
+    open_streams: OpenStreams,
+) -> io::Result<()> {
+    let mut reader = stream.try_clone()?;
+    let mut writer = stream.try_clone()?;
+    open_streams.lock().unwrap().insert(conn, stream);
+
+    let (outbox, frames) = mpsc::channel::<Vec<u8>>();
+    if commands
+        .send(Command::Connect(conn, outbox.clone()))
+        .is_err()
+    {
+        return Ok(());
+    }
+    thread::spawn(move || {
+        for frame in frames {
+            if write_frame(&mut writer, &frame).is_err() {
+                break;
+            }
+        }
+    });
+    thread::spawn(move || {
+        while let Ok(Some(payload)) = read_frame(&mut reader) {
+            let command = match Request::parse(&payload) {
+                Ok(request) => Command::Request(conn, request),
+                Err(reason) => {
+                    let _ = outbox.send(reject(&reason, Value::Null));
+                    continue;
+                }
+            };
+            if commands.send(command).is_err() {
+                break;
+            }
+        }
+        let _ = commands.send(Command::Disconnect(conn));
+        if let Some(stream) = open_streams.lock().unwrap().remove(&conn) {
+            stream.shutdown();
+        }
+    });
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// OrderServer — Python handle on a running venue
+// ---------------------------------------------------------------------------
+
+/// Embedded order-entry server: a `Book` running on its own thread behind a
+/// TCP (`"host:port"`) or Unix-domain (`"unix:/path"`) socket, so several
+/// processes can trade against one simulated venue.
+///
+/// Messages are JSON objects framed by a 4-byte big-endian length. Clients
+/// send `new`, `cancel` and `amend` requests and receive `ack`, `amended`,
+/// `canceled`, `reject` and `execution` messages. Executions are sent to
+/// the connection that entered each side of the trade.
+#[pyclass]
+pub struct OrderServer {
+    address: String,
+    shutdown: Arc<AtomicBool>,
+    commands: Sender<Command>,
+    open_streams: OpenStreams,
+    accept_thread: Option<JoinHandle<()>>,
+    engine_thread: Option<JoinHandle<()>>,
+    unix_path: Option<String>,
+}
+
+#[pymethods]
+impl OrderServer {
+    /// Bind `address` and start serving immediately. Port 0 picks a free port;
+    /// read the bound address back from `address`.
+    #[new]
+    #[pyo3(signature = (address = "127.0.0.1:0"))]
+    fn new(address: &str) -> PyResult<Self> {
+        let listener = Listener::bind(address)?;
+        listener.set_nonblocking()?;
+        let bound = listener.local_address()?;
+        let unix_path = bound.strip_prefix("unix:").map(str::to_string);
+
+        let (commands, inbox) = mpsc::channel();
+        let engine = Engine {
+            book: Book::new(),
+            sessions: HashMap::new(),
+            owners: HashMap::new(),
+        };
+        let engine_thread = thread::spawn(move || engine.run(inbox));
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let open_streams = OpenStreams::default();
+        let accept_thread = {
+            let shutdown = Arc::clone(&shutdown);
+            let open_streams = Arc::clone(&open_streams);
+            let commands = commands.clone();
+            thread::spawn(move || {
+                let mut next_conn: ConnId = 0;
+                while !shutdown.load(Ordering::Acquire) {
+                    match listener.accept() {
+                        Ok(stream) => {
+                            next_conn += 1;
+                            let _ = serve_connection(
+                                next_conn,
+                                stream,
+                                commands.clone(),
+                                Arc::clone(&open_streams),
+                            );
+                        }
+                        Err(_) => thread::sleep(ACCEPT_POLL),
+                    }
+                }
+            })
+        };
+
+        Ok(OrderServer {
+            address: bound,
+            shutdown,
+            commands,
+            open_streams,
+            accept_thread: Some(accept_thread),
+            engine_thread: Some(engine_thread),
+            unix_path,
+        })
+    }
+
+    /// Bound address: `"host:port"` or `"unix:/path"`.
+    #[getter]
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        !self.shutdown.load(Ordering::Acquire)
+    }
+
+    /// L2 snapshot of the venue's book, or None if the symbol was never seen.
+    #[pyo3(signature = (symbol, depth = 5))]
+    fn snapshot(&self, symbol: String, depth: isize, py: Python<'_>) -> PyResult<Option<Snapshot>> {
+        let stopped = || pyo3::exceptions::PyRuntimeError::new_err("OrderServer is stopped");
+        let (reply, response) = mpsc::channel();
+        self.commands
+            .send(Command::Snapshot(symbol, depth, reply))
+            .map_err(|_| stopped())?;
+        py.allow_threads(move || response.recv())
+            .map_err(|_| stopped())
+    }
+
+    /// Stop accepting connections, close open ones and join the engine.
+    fn stop(&mut self, py: Python<'_>) {
+        self.shutdown.store(true, Ordering::Release);
+        let accept_thread = self.accept_thread.take();
+        let engine_thread = self.engine_thread.take();
+        py.allow_threads(|| {
+            if let Some(handle) = accept_thread {
+                let _ = handle.join();
+            }
+            let _ = self.commands.send(Command::Shutdown);
+            if let Some(handle) = engine_thread {
+                let _ = handle.join();
+            }
+        });
+        self.close_streams();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, PyTuple>, py: Python<'_>) -> bool {
+        self.stop(py);
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OrderServer(address='{}', running={})",
+            self.address,
+            self.running()
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+impl OrderServer {
+    fn close_streams(&mut self) {
+        for (_, stream) in self.open_streams.lock().unwrap().drain() {
+            stream.shutdown();
+        }
+        if let Some(path) = self.unix_path.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl Drop for OrderServer {
+    fn drop(&mut self) {
+        // Threads exit on their own once signalled; joining here could
+        // block the interpreter.
+        self.shutdown.store(true, Ordering::Release);
+        let _ = self.commands.send(Command::Shutdown);
+        self.close_streams();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip_and_detect_eof() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"{\"type\":\"cancel\"}").unwrap();
+        write_frame(&mut wire, b"").unwrap();
+        let mut reader = wire.as_slice();
+        assert_eq!(
+            read_frame(&mut reader).unwrap().as_deref(),
+            Some(&b"{\"type\":\"cancel\"}"[..])
+        );
+        assert_eq!(read_frame(&mut reader).unwrap().as_deref(), Some(&b""[..]));
+        assert!(read_frame(&mut reader).unwrap().is_none());
+
+        let oversized = ((MAX_FRAME + 1) as u32).to_be_bytes();
+        assert!(read_frame(&mut &oversized[..]).is_err());
+    }
+
+    #[test]
+    fn parse_requests() {
+        let new = br#"{"type":"new","client_order_id":"c1","side":"buy","symbol":"AAPL","price":"150.25","quantity":10}"#;
+        assert_eq!(
+            Request::parse(new).unwrap(),
+            Request::New {
+                client_order_id: json!("c1"),
+                side: Side::BID,
+                symbol: "AAPL".to_string(),
+                price: Decimal::new(15025, 2),
+                quantity: 10,
+            }
+        );
+
+        let id = Uuid::new_v4();
+        let amend = format!(r#"{{"type":"amend","order_id":"{}","price":99.5}}"#, id);
+        assert_eq!(
+            Request::parse(amend.as_bytes()).unwrap(),
+            Request::Amend {
+                order_id: id,
+                price: Some(Decimal::new(995, 1)),
+                quantity: None,
+            }
+        );
+
+        assert!(Request::parse(b"{\"type\":\"cancel\",\"order_id\":\"nope\"}").is_err());
+        assert!(Request::parse(b"{\"type\":\"replace\"}").is_err());
+        assert!(Request::parse(b"not json").is_err());
+    }
+
+    #[test]
+    fn closed_connections_and_orders_are_forgotten() {
+        let server = OrderServer::new("127.0.0.1:0").unwrap();
+        for _ in 0..20 {
+            let mut client = TcpStream::connect(&server.address).unwrap();
+            write_frame(&mut client, b"{}").unwrap();
+            assert!(read_frame(&mut client).unwrap().is_some());
+        }
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !server.open_streams.lock().unwrap().is_empty() {
+            assert!(std::time::Instant::now() < deadline, "connections leaked");
+            thread::sleep(ACCEPT_POLL);
+        }
+
+        let mut engine = Engine {
+            book: Book::new(),
+            sessions: HashMap::new(),
+            owners: HashMap::new(),
+        };
+        let new = |side, quantity| Request::New {
+            client_order_id: Value::Null,
+            side,
+            symbol: "X".to_string(),
+            price: Decimal::TEN,
+            quantity,
+        };
+        engine.handle(1, new(Side::ASK, 5));
+        engine.handle(2, new(Side::BID, 3));
+        assert_eq!(engine.owners.len(), 1);
+        engine.handle(2, new(Side::BID, 2));
+        assert!(engine.owners.is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::instrument::Precision;
+use crate::order::decimal_to_py;
+
+// ---------------------------------------------------------------------------
+// SessionStats — per-symbol trading statistics since the last session roll
+// ---------------------------------------------------------------------------
+
+/// Running trade statistics for one symbol over the current session.
+///
+/// `notional` applies the instrument multiplier, like
+/// `TradeBlotter.total_cost`; `vwap` is the volume-weighted trade price.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionStats {
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub last: Decimal,
+    pub volume: i64,
+    pub notional: Decimal,
+    pub trade_count: u64,
+    /// Sum of price * quantity, for the VWAP.
+    price_volume: Decimal,
+    /// Precision the VWAP is rounded to, if the book configures one.
+    pub precision: Option<Precision>,
+}
+
+impl SessionStats {
+    /// Stats opened by a first trade.
+    pub fn new(price: Decimal, quantity: i64, multiplier: Decimal) -> Self {
+        let mut stats = SessionStats {
+            open: price,
+            high: price,
+            low: price,
+            last: price,
+            volume: 0,
+            notional: Decimal::ZERO,
+            trade_count: 0,
+            price_volume: Decimal::ZERO,
+            precision: None,
+        };
+        stats.record(price, quantity, multiplier);
+        stats
+    }
+
+    pub fn record(&mut self, price: Decimal, quantity: i64, multiplier: Decimal) {
+        let price_volume = price * Decimal::from(quantity);
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.last = price;
+        self.volume += quantity;
+        self.price_volume += price_volume;
+        self.notional += price_volume * multiplier;
+        self.trade_count += 1;
+    }
+}
+
+#[pymethods]
+impl SessionStats {
+    #[getter]
+    fn open(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.open)
+    }
+
+    #[getter]
+    fn high(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.high)
+    }
+
+    #[getter]
+    fn low(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.low)
+    }
+
+    /// Price of the most recent trade.
+    #[getter]
+    fn last_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.last)
+    }
+
+    #[getter]
+    fn volume(&self) -> i64 {
+        self.volume
+    }
+
+    #[getter]
+    fn notional(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.notional)
+    }
+
+    #[getter]
+    fn trade_count(&self) -> u64 {
+        self.trade_count
+    }
+
+    #[getter]
+    fn vwap(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let vwap = self.price_volume / Decimal::from(self.volume);
+        decimal_to_py(py, self.precision.map_or(vwap, |p| p.round_price(vwap)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SessionStats(open={}, high={}, low={}, last_price={}, volume={}, trade_count={})",
+            self.open, self.high, self.low, self.last, self.volume, self.trade_count
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_tracks_ohlc_volume_and_notional() {
+        let multiplier = Decimal::from(10);
+        let mut stats = SessionStats::new(Decimal::from(100), 2, multiplier);
+        stats.record(Decimal::from(103), 1, multiplier);
+        stats.record(Decimal::from(99), 3, multiplier);
+        assert_eq!(
+            (stats.open, stats.high, stats.low, stats.last),
+            (
+                Decimal::from(100),
+                Decimal::from(103),
+                Decimal::from(99),
+                Decimal::from(99)
+            )
+        );
+        assert_eq!((stats.volume, stats.trade_count), (6, 3));
+        assert_eq!(stats.notional, Decimal::from(6000));
+    }
+}
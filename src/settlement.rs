@@ -0,0 +1,280 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::corporate::rekey_by;
+use crate::order::{decimal_to_py, Side};
+
+// ---------------------------------------------------------------------------
+// SettlementLine — net trading result of one account in one symbol
+// ---------------------------------------------------------------------------
+
+/// End-of-session totals of one account's trades in one symbol.
+///
+/// Notionals apply the instrument multiplier and are in `currency`. `fees`
+/// is what the report's maker and taker rates charge on the provided and
+/// taken notional, `cash_flows` the dividends and funding the position
+/// received (negative: paid), and `cash_delta` is sell notional less buy
+/// notional less fees plus cash flows.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementLine {
+    pub account: Option<String>,
+    pub symbol: String,
+    /// Currency the notionals and fees are in, None when unknown.
+    pub currency: Option<String>,
+    pub bought: i64,
+    pub sold: i64,
+    pub buy_notional: Decimal,
+    pub sell_notional: Decimal,
+    pub fees: Decimal,
+    pub cash_flows: Decimal,
+}
+
+impl SettlementLine {
+    fn cash(&self) -> Decimal {
+        self.sell_notional - self.buy_notional - self.fees + self.cash_flows
+    }
+}
+
+#[pymethods]
+impl SettlementLine {
+    /// Account the orders were booked to; None for orders without one.
+    #[getter]
+    fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// The instrument's currency, or the currency the report was asked
+    /// in; symbols without an instrument are in the base currency, if set.
+    #[getter]
+    fn currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+
+    /// Quantity bought less quantity sold.
+    #[getter]
+    fn net_quantity(&self) -> i64 {
+        self.bought - self.sold
+    }
+
+    #[getter]
+    fn bought(&self) -> i64 {
+        self.bought
+    }
+
+    #[getter]
+    fn sold(&self) -> i64 {
+        self.sold
+    }
+
+    #[getter]
+    fn buy_notional(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.buy_notional)
+    }
+
+    #[getter]
+    fn sell_notional(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.sell_notional)
+    }
+
+    #[getter]
+    fn fees(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.fees)
+    }
+
+    #[getter]
+    fn cash_flows(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.cash_flows)
+    }
+
+    #[getter]
+    fn cash_delta(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.cash())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SettlementLine(account={:?}, symbol={}, net_quantity={}, cash_delta={})",
+            self.account,
+            self.symbol,
+            self.bought - self.sold,
+            self.cash()
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SettlementLedger — running totals behind Book.settlement_report
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Position {
+    bought: i64,
+    sold: i64,
+    buy_notional: Decimal,
+    sell_notional: Decimal,
+    /// Notional of fills where the account's order was resting.
+    maker_notional: Decimal,
+    /// Notional of fills where the account's order was the aggressor.
+    taker_notional: Decimal,
+    /// Dividends and funding received, less those paid.
+    cash_flows: Decimal,
+}
+
+/// Per-(account, symbol) trade totals since the last session roll, and the
+/// net positions held into the session.
+#[derive(Clone, Debug, Default)]
+pub struct SettlementLedger {
+    positions: HashMap<(Option<String>, String), Position>,
+    /// Net quantity carried in from earlier sessions.
+    carried: HashMap<(Option<String>, String), i64>,
+}
+
+impl SettlementLedger {
+    /// Carry `old`'s totals over to symbol `new`, replacing `new`'s.
+    pub fn rename_symbol(&mut self, old: &str, new: &str) {
+        rekey_by(&mut self.positions, |(_, symbol)| symbol, old, new);
+        rekey_by(&mut self.carried, |(_, symbol)| symbol, old, new);
+    }
+
+    /// Book one side of a fill. `notional` already includes the multiplier.
+    pub fn record(
+        &mut self,
+        account: Option<&str>,
+        symbol: &str,
+        side: Side,
+        quantity: i64,
+        notional: Decimal,
+        maker: bool,
+    ) {
+        let key = (account.map(str::to_string), symbol.to_string());
+        let position = self.positions.entry(key).or_default();
+        match side {
+            Side::BID => {
+                position.bought += quantity;
+                position.buy_notional += notional;
+            }
+            Side::ASK => {
+                position.sold += quantity;
+                position.sell_notional += notional;
+            }
+        }
+        if maker {
+            position.maker_notional += notional;
+        } else {
+            position.taker_notional += notional;
+        }
+    }
+
+    /// Credit every open position in `symbol`, carried in or traded this
+    /// session, with what `payment` gives its net quantity, returning how
+    /// many were paid.
+    pub fn pay(&mut self, symbol: &str, payment: impl Fn(i64) -> Option<Decimal>) -> usize {
+        let mut held: HashMap<(Option<String>, String), i64> = self
+            .carried
+            .iter()
+            .filter(|((_, held), _)| held == symbol)
+            .map(|(key, &net)| (key.clone(), net))
+            .collect();
+        for (key, position) in &self.positions {
+            if key.1 == symbol {
+                *held.entry(key.clone()).or_default() += position.bought - position.sold;
+            }
+        }
+        let mut paid = 0;
+        for (key, net) in held {
+            if net == 0 {
+                continue;
+            }
+            if let Some(amount) = payment(net) {
+                self.positions.entry(key).or_default().cash_flows += amount;
+                paid += 1;
+            }
+        }
+        paid
+    }
+
+    /// Lines for `account` (every account when None), ordered by account
+    /// then symbol, with fees charged at the given rates of notional.
+    pub fn report(
+        &self,
+        account: Option<&str>,
+        maker_fee: Decimal,
+        taker_fee: Decimal,
+    ) -> Vec<SettlementLine> {
+        let mut lines: Vec<SettlementLine> = self
+            .positions
+            .iter()
+            .filter(|((owner, _), _)| account.is_none() || owner.as_deref() == account)
+            .map(|((owner, symbol), position)| SettlementLine {
+                account: owner.clone(),
+                symbol: symbol.clone(),
+                currency: None,
+                bought: position.bought,
+                sold: position.sold,
+                buy_notional: position.buy_notional,
+                sell_notional: position.sell_notional,
+                fees: position.maker_notional * maker_fee + position.taker_notional * taker_fee,
+                cash_flows: position.cash_flows,
+            })
+            .collect();
+        lines.sort_by(|a, b| (&a.account, &a.symbol).cmp(&(&b.account, &b.symbol)));
+        lines
+    }
+
+    /// Start a new session: fold each net position into the carried ones
+    /// and clear the totals.
+    pub fn roll(&mut self) {
+        for (key, position) in self.positions.drain() {
+            *self.carried.entry(key).or_default() += position.bought - position.sold;
+        }
+        self.carried.retain(|_, net| *net != 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_nets_fills_and_charges_fees() {
+        let mut ledger = SettlementLedger::default();
+        let d = Decimal::from;
+        ledger.record(Some("a"), "X", Side::BID, 10, d(1000), true);
+        ledger.record(Some("a"), "X", Side::ASK, 4, d(420), false);
+        ledger.record(None, "X", Side::ASK, 10, d(1000), false);
+
+        let lines = ledger.report(Some("a"), d(0), Decimal::new(1, 2));
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!((line.bought, line.sold), (10, 4));
+        assert_eq!(line.fees, Decimal::new(42, 1));
+        assert_eq!(ledger.report(None, d(0), d(0)).len(), 2);
+
+        // A dividend of 0.5 reaches the 6 long and the 10 short.
+        assert_eq!(ledger.pay("X", |net| Some(Decimal::from(net) / d(2))), 2);
+        let lines = ledger.report(None, d(0), d(0));
+        assert_eq!(lines[0].cash_flows, d(-5));
+        assert_eq!(lines[1].cash_flows, d(3));
+        assert_eq!(lines[1].cash(), d(420 - 1000 + 3));
+
+        // After a roll the carried positions are paid on fresh lines.
+        ledger.roll();
+        assert!(ledger.report(None, d(0), d(0)).is_empty());
+        assert_eq!(ledger.pay("X", |net| Some(Decimal::from(net))), 2);
+        let lines = ledger.report(None, d(0), d(0));
+        assert_eq!((lines[0].bought, lines[0].cash_flows), (0, d(-10)));
+        assert_eq!((lines[1].bought, lines[1].cash_flows), (0, d(6)));
+    }
+}
@@ -0,0 +1,283 @@
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::book::Book;
+use crate::order::Order;
+use crate::reject::RejectCode;
+use crate::snapshot::Snapshot;
+use crate::trade::TradeBlotter;
+
+/// A symbol's book, locked on its own.
+type Shard = Arc<Mutex<Book>>;
+
+fn poisoned<T>(_: T) -> PyErr {
+    pyo3::exceptions::PyRuntimeError::new_err("a shard panicked while locked")
+}
+
+// ---------------------------------------------------------------------------
+// ShardedBook — one book per symbol, each behind its own lock
+// ---------------------------------------------------------------------------
+
+/// Several symbols' books behind one object, each locked separately and
+/// matched with the GIL released, so threads submitting to different
+/// symbols run in parallel while those on one symbol take turns.
+///
+/// This is a separate class rather than sharding inside `Book`: a `Book`
+/// is borrowed mutably by every call that changes it, so one object can
+/// never be entered by two threads at once however its state is split.
+/// Each symbol gets a plain `Book` instead: matching, resting and
+/// cancelling work as there. Order ids are unique across shards, checked
+/// against a shared index of where each live order rests; other state that
+/// spans symbols (spreads, accounts) is per shard, and publishers,
+/// callbacks and the rest of `Book`'s methods are not offered.
+#[pyclass(frozen)]
+pub struct ShardedBook {
+    shards: RwLock<HashMap<String, Shard>>,
+    /// Symbol of every order that may still be live. Entries are claimed
+    /// before matching and dropped once the order is gone; a shard lock is
+    /// never taken while this one is held.
+    ids: Mutex<HashMap<Uuid, String>>,
+}
+
+impl ShardedBook {
+    fn new() -> Self {
+        ShardedBook {
+            shards: RwLock::new(HashMap::new()),
+            ids: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record `order`'s id under its symbol, refusing it if the id is live
+    /// in another symbol's shard.
+    fn claim(&self, order: &Order) -> PyResult<()> {
+        loop {
+            let other = {
+                let mut ids = self.ids.lock().map_err(poisoned)?;
+                match ids.get(&order.id) {
+                    Some(symbol) if *symbol != order.symbol => symbol.clone(),
+                    Some(_) => return Ok(()),
+                    None => {
+                        ids.insert(order.id, order.symbol.clone());
+                        return Ok(());
+                    }
+                }
+            };
+            let live = match self.existing(&other)? {
+                Some(shard) => shard.lock().map_err(poisoned)?.is_live(order.id),
+                None => false,
+            };
+            if live {
+                return Err(RejectCode::DuplicateId.err(format!(
+                    "Order {} is already resting in {}",
+                    order.id, other
+                )));
+            }
+            let mut ids = self.ids.lock().map_err(poisoned)?;
+            if ids.get(&order.id) == Some(&other) {
+                ids.remove(&order.id);
+            }
+        }
+    }
+
+    /// Drop the index entries of `order_ids` no longer live in `book`.
+    fn release(&self, book: &Book, order_ids: impl IntoIterator<Item = Uuid>) -> PyResult<()> {
+        let mut ids = self.ids.lock().map_err(poisoned)?;
+        for order_id in order_ids {
+            if !book.is_live(order_id) {
+                ids.remove(&order_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// The shard of `symbol`, created on first use.
+    fn shard(&self, symbol: &str) -> PyResult<Shard> {
+        if let Some(shard) = self.shards.read().map_err(poisoned)?.get(symbol) {
+            return Ok(shard.clone());
+        }
+        let mut shards = self.shards.write().map_err(poisoned)?;
+        let shard = shards
+            .entry(symbol.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(Book::new())));
+        Ok(shard.clone())
+    }
+
+    /// The shard of `symbol` if it has one.
+    fn existing(&self, symbol: &str) -> PyResult<Option<Shard>> {
+        Ok(self.shards.read().map_err(poisoned)?.get(symbol).cloned())
+    }
+
+    /// Match `order` in its symbol's shard. Call without the GIL: a shard
+    /// is only ever locked by threads that do not hold it.
+    fn match_in_shard(&self, order: &Order) -> PyResult<TradeBlotter> {
+        self.claim(order)?;
+        let shard = self.shard(&order.symbol)?;
+        let mut book = shard.lock().map_err(poisoned)?;
+        let result = book.match_single(order);
+        let standing = result.iter().flat_map(|blotter| &blotter.trades);
+        let touched = std::iter::once(order.id).chain(standing.map(|t| t.standing_order_id));
+        self.release(&book, touched)?;
+        result
+    }
+}
+
+#[pymethods]
+impl ShardedBook {
+    #[new]
+    fn py_new() -> Self {
+        Self::new()
+    }
+
+    /// Match an Order, or each Order of an iterable in turn, returning the
+    /// blotter or list of blotters. Only the shards of the orders' symbols
+    /// are locked, one order at a time.
+    #[pyo3(name = "match")]
+    fn match_orders(&self, py: Python<'_>, orders: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if let Ok(order) = orders.extract::<Order>() {
+            let blotter = py.allow_threads(|| self.match_in_shard(&order))?;
+            return Ok(Py::new(py, blotter)?.into_any());
+        }
+        let Ok(iter) = orders.try_iter() else {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                "Expected an Order or an iterable of Orders, got {}",
+                orders.get_type().name()?
+            )));
+        };
+        let incoming = iter
+            .map(|item| item?.extract::<Order>())
+            .collect::<PyResult<Vec<Order>>>()?;
+        let blotters = py.allow_threads(|| {
+            incoming
+                .iter()
+                .map(|order| self.match_in_shard(order))
+                .collect::<PyResult<Vec<TradeBlotter>>>()
+        })?;
+        let blotters = blotters
+            .into_iter()
+            .map(|blotter| Py::new(py, blotter))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyList::new(py, blotters)?.into())
+    }
+
+    /// Cancel a resting order. Returns False if it is not resting.
+    fn cancel(&self, py: Python<'_>, order: PyRef<'_, Order>) -> PyResult<bool> {
+        let (symbol, order_id) = (order.symbol.clone(), order.id);
+        py.allow_threads(|| {
+            let Some(shard) = self.existing(&symbol)? else {
+                return Ok(false);
+            };
+            let mut book = shard.lock().map_err(poisoned)?;
+            let cancelled = book.cancel_order(order_id)?;
+            self.release(&book, [order_id])?;
+            Ok(cancelled)
+        })
+    }
+
+    /// An L2 depth snapshot of `symbol`, or None if never seen.
+    #[pyo3(signature = (symbol, depth = 5))]
+    fn snapshot(&self, py: Python<'_>, symbol: &str, depth: isize) -> PyResult<Option<Snapshot>> {
+        py.allow_threads(|| {
+            let Some(shard) = self.existing(symbol)? else {
+                return Ok(None);
+            };
+            let book = shard.lock().map_err(poisoned)?;
+            Ok(book.snapshot(symbol, depth))
+        })
+    }
+
+    /// Symbols with a shard, sorted.
+    #[getter]
+    fn symbols(&self) -> PyResult<Vec<String>> {
+        let mut symbols: Vec<String> = self
+            .shards
+            .read()
+            .map_err(poisoned)?
+            .keys()
+            .cloned()
+            .collect();
+        symbols.sort();
+        Ok(symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Side;
+
+    #[test]
+    fn symbols_match_in_their_own_shards() {
+        let sharded = ShardedBook::new();
+        let order = |side, symbol: &str, price: f64| {
+            Order::try_new(side, symbol.to_string(), price, 5).unwrap()
+        };
+        sharded
+            .match_in_shard(&order(Side::ASK, "AAPL", 10.0))
+            .unwrap();
+        sharded
+            .match_in_shard(&order(Side::ASK, "MSFT", 20.0))
+            .unwrap();
+        let blotter = sharded
+            .match_in_shard(&order(Side::BID, "AAPL", 30.0))
+            .unwrap();
+        assert_eq!(blotter.trades.len(), 1);
+
+        let msft = sharded.existing("MSFT").unwrap().unwrap();
+        assert!(msft.lock().unwrap().best_level("MSFT", Side::ASK).is_some());
+        assert!(msft.lock().unwrap().best_level("AAPL", Side::ASK).is_none());
+        assert_eq!(sharded.symbols().unwrap(), ["AAPL", "MSFT"]);
+    }
+
+    #[test]
+    fn threads_wait_only_for_their_own_symbol() {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let sharded = ShardedBook::new();
+        let order = |side, symbol: &str| Order::try_new(side, symbol.to_string(), 10.0, 1).unwrap();
+        sharded.match_in_shard(&order(Side::ASK, "A")).unwrap();
+        sharded.match_in_shard(&order(Side::ASK, "B")).unwrap();
+
+        let a = sharded.existing("A").unwrap().unwrap();
+        let held = a.lock().unwrap();
+        std::thread::scope(|scope| {
+            let (done, finished) = mpsc::channel();
+            for symbol in ["A", "B"] {
+                let done = done.clone();
+                let sharded = &sharded;
+                scope.spawn(move || {
+                    let blotter = sharded.match_in_shard(&order(Side::BID, symbol)).unwrap();
+                    done.send((symbol, blotter.trades.len())).unwrap();
+                });
+            }
+            // B trades while A's shard is locked; A waits for it.
+            assert_eq!(finished.recv().unwrap(), ("B", 1));
+            assert!(finished.recv_timeout(Duration::from_millis(50)).is_err());
+            drop(held);
+            assert_eq!(finished.recv().unwrap(), ("A", 1));
+        });
+    }
+
+    #[test]
+    fn order_ids_are_unique_across_shards() {
+        pyo3::prepare_freethreaded_python();
+        let sharded = ShardedBook::new();
+        let resting = Order::try_new(Side::ASK, "A".to_string(), 10.0, 5).unwrap();
+        sharded.match_in_shard(&resting).unwrap();
+        let mut reused = Order::try_new(Side::ASK, "B".to_string(), 10.0, 5).unwrap();
+        reused.id = resting.id;
+        let err = sharded.match_in_shard(&reused).unwrap_err();
+        assert!(err.to_string().contains("already resting in A"));
+        assert!(sharded.existing("B").unwrap().is_none());
+
+        // Once filled, the id is free again and the index forgets it.
+        let taker = Order::try_new(Side::BID, "A".to_string(), 10.0, 5).unwrap();
+        sharded.match_in_shard(&taker).unwrap();
+        assert!(sharded.ids.lock().unwrap().is_empty());
+        sharded.match_in_shard(&reused).unwrap();
+        assert_eq!(sharded.ids.lock().unwrap().get(&reused.id).unwrap(), "B");
+    }
+}
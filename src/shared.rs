@@ -0,0 +1,322 @@
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::snapshot::Snapshot;
+
+/// Identifies a snapshot ring file and its layout version.
+const MAGIC: &[u8; 8] = b"POBRING1";
+
+/// Magic, slot count (u32), slot size (u32) and latest sequence (u64).
+const HEADER_LEN: u64 = 24;
+
+/// Offset of the latest committed sequence within the header.
+const LATEST_OFFSET: u64 = 16;
+
+/// Sequence (u64) and payload length (u32) at the start of each slot; the
+/// sequence is repeated in the slot's last eight bytes.
+const SLOT_OVERHEAD: usize = 8 + 4 + 8;
+
+/// Attempts a reader makes before concluding the writer keeps lapping it.
+const READ_RETRIES: usize = 100;
+
+// ---------------------------------------------------------------------------
+// Snapshot ring — serialized book snapshots shared between processes
+// ---------------------------------------------------------------------------
+//
+// The file holds a header and `slots` fixed-size slots. The writer fills
+// the slot for sequence `n` at `n % slots`: the sequence at its start, the
+// payload, then the sequence again at its end, and only then publishes `n`
+// as the latest in the header. A reader reads the end marker, the payload
+// and then the start marker; equal markers matching the sequence it asked
+// for mean no write overlapped the read. Put the file on a memory-backed
+// filesystem such as /dev/shm to keep it out of disk I/O.
+
+fn read_u64(file: &mut File, offset: u64) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_at(file: &mut File, offset: u64, bytes: &[u8]) -> io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(bytes)
+}
+
+/// Writing end of a snapshot ring, owned by the book that fills it.
+#[derive(Debug)]
+pub struct SnapshotRing {
+    file: File,
+    slots: u32,
+    slot_size: u32,
+    sequence: u64,
+    /// Symbols shared (a glob) and the depth of each snapshot.
+    pub symbols: String,
+    pub depth: isize,
+}
+
+impl SnapshotRing {
+    /// Create (or truncate) the ring file at `path`.
+    pub fn create(
+        path: &str,
+        slots: u32,
+        slot_size: u32,
+        symbols: String,
+        depth: isize,
+    ) -> PyResult<Self> {
+        if slots == 0 || (slot_size as usize) <= SLOT_OVERHEAD {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "slots must be positive and slot_size above {} bytes",
+                SLOT_OVERHEAD
+            )));
+        }
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(HEADER_LEN + slots as u64 * slot_size as u64)?;
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&slots.to_le_bytes());
+        header.extend_from_slice(&slot_size.to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        write_at(&mut file, 0, &header)?;
+        Ok(SnapshotRing {
+            file,
+            slots,
+            slot_size,
+            sequence: 0,
+            symbols,
+            depth,
+        })
+    }
+
+    /// Publish `snapshots` (with the book's clock) as the next sequence.
+    pub fn write(
+        &mut self,
+        time: Option<i64>,
+        snapshots: &HashMap<String, Snapshot>,
+    ) -> PyResult<()> {
+        let sequence = self.sequence + 1;
+        let frame: serde_json::Map<String, Value> = snapshots
+            .iter()
+            .map(|(symbol, snapshot)| (symbol.clone(), snapshot.to_json()))
+            .collect();
+        let payload = json!({"time": time, "snapshots": frame}).to_string();
+        let capacity = self.slot_size as usize - SLOT_OVERHEAD;
+        if payload.len() > capacity {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Snapshot frame of {} bytes exceeds the ring's {} byte slots; \
+                 share fewer symbols, less depth or larger slots",
+                payload.len(),
+                capacity
+            )));
+        }
+        let slot = HEADER_LEN + (sequence % self.slots as u64) * self.slot_size as u64;
+        let marker = sequence.to_le_bytes();
+        let mut front = Vec::with_capacity(12 + payload.len());
+        front.extend_from_slice(&marker);
+        front.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        front.extend_from_slice(payload.as_bytes());
+        write_at(&mut self.file, slot, &front)?;
+        write_at(&mut self.file, slot + self.slot_size as u64 - 8, &marker)?;
+        write_at(&mut self.file, LATEST_OFFSET, &marker)?;
+        self.sequence = sequence;
+        Ok(())
+    }
+}
+
+/// The latest frame of a ring: its sequence, the writer's clock and the
+/// snapshot of each shared symbol.
+struct Frame {
+    sequence: u64,
+    time: Option<i64>,
+    snapshots: HashMap<String, Snapshot>,
+}
+
+/// Read-only view of a book shared by another process through
+/// `Book.share_snapshots`.
+///
+/// Every call reads the latest snapshot the writer published, so the
+/// reader never sees a half-written book and never blocks the writer.
+#[pyclass]
+pub struct SharedBookReader {
+    path: String,
+    file: File,
+    slots: u32,
+    slot_size: u32,
+}
+
+impl SharedBookReader {
+    fn latest(&mut self) -> PyResult<Frame> {
+        for _ in 0..READ_RETRIES {
+            let sequence = read_u64(&mut self.file, LATEST_OFFSET)?;
+            if sequence == 0 {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "{} has no snapshot yet",
+                    self.path
+                )));
+            }
+            let slot = HEADER_LEN + (sequence % self.slots as u64) * self.slot_size as u64;
+            let end = read_u64(&mut self.file, slot + self.slot_size as u64 - 8)?;
+            let mut front = vec![0u8; self.slot_size as usize - 8];
+            self.file.seek(SeekFrom::Start(slot))?;
+            self.file.read_exact(&mut front)?;
+            let start = read_u64(&mut self.file, slot)?;
+            if start != sequence || end != sequence {
+                continue;
+            }
+            let len = u32::from_le_bytes([front[8], front[9], front[10], front[11]]) as usize;
+            let frame = front
+                .get(12..12 + len)
+                .and_then(|payload| serde_json::from_slice::<Value>(payload).ok())
+                .and_then(|payload| {
+                    let snapshots = payload["snapshots"]
+                        .as_object()?
+                        .iter()
+                        .map(|(symbol, snapshot)| {
+                            Some((symbol.clone(), Snapshot::from_json(snapshot)?))
+                        })
+                        .collect::<Option<HashMap<_, _>>>()?;
+                    Some(Frame {
+                        sequence,
+                        time: payload["time"].as_i64(),
+                        snapshots,
+                    })
+                });
+            return frame.ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Corrupt snapshot frame {} in {}",
+                    sequence, self.path
+                ))
+            });
+        }
+        Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+            "The writer of {} overwrote every frame before it could be read; \
+             use more slots",
+            self.path
+        )))
+    }
+}
+
+#[pymethods]
+impl SharedBookReader {
+    /// Open the ring file a writer created with `Book.share_snapshots`.
+    #[new]
+    fn new(path: String) -> PyResult<Self> {
+        let mut file = File::open(&path)?;
+        let mut header = [0u8; HEADER_LEN as usize];
+        file.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{} is not a pyorderbook snapshot ring",
+                path
+            )));
+        }
+        let slots = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        let slot_size = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+        Ok(SharedBookReader {
+            path,
+            file,
+            slots,
+            slot_size,
+        })
+    }
+
+    #[getter]
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Sequence of the latest published snapshot; 0 before the first.
+    #[getter]
+    fn sequence(&mut self) -> PyResult<u64> {
+        Ok(read_u64(&mut self.file, LATEST_OFFSET)?)
+    }
+
+    /// The writer's simulated clock at the latest snapshot.
+    #[getter]
+    fn current_time(&mut self) -> PyResult<Option<i64>> {
+        Ok(self.latest()?.time)
+    }
+
+    /// Latest snapshot of `symbol`, or None if the writer does not share it.
+    fn snapshot(&mut self, symbol: &str) -> PyResult<Option<Snapshot>> {
+        Ok(self.latest()?.snapshots.remove(symbol))
+    }
+
+    /// Latest snapshot of every shared symbol, as dict[symbol, Snapshot].
+    fn snapshot_all(&mut self) -> PyResult<HashMap<String, Snapshot>> {
+        Ok(self.latest()?.snapshots)
+    }
+
+    /// (sequence, dict[symbol, Snapshot]) read together from one frame.
+    fn read(&mut self) -> PyResult<(u64, HashMap<String, Snapshot>)> {
+        let frame = self.latest()?;
+        Ok((frame.sequence, frame.snapshots))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SharedBookReader(path={:?})", self.path)
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::SnapshotLevel;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn readers_see_the_latest_complete_frame() {
+        let path = std::env::temp_dir().join(format!("pob-ring-{}", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut ring = SnapshotRing::create(&path, 2, 512, "*".to_string(), 5).unwrap();
+        let mut reader = SharedBookReader::new(path.clone()).unwrap();
+        assert!(reader.latest().is_err());
+
+        let level = |price: i64, quantity| SnapshotLevel::from_rust(Decimal::from(price), quantity);
+        let snapshot = Snapshot {
+            bids: vec![level(10, 5)],
+            asks: vec![level(12, 1)],
+            spread: Some(Decimal::TWO),
+            midpoint: Some(Decimal::from(11)),
+            bid_vwap: Some(Decimal::TEN),
+            ask_vwap: None,
+            bid_notional: Decimal::from(50),
+            ask_notional: Decimal::from(12),
+        };
+        for time in 1..=3 {
+            let snapshots = HashMap::from([("X".to_string(), snapshot.clone())]);
+            ring.write(Some(time), &snapshots).unwrap();
+        }
+        let frame = reader.latest().unwrap();
+        assert_eq!((frame.sequence, frame.time), (3, Some(3)));
+        let read = &frame.snapshots["X"];
+        assert_eq!(
+            (read.bids[0].price, read.bids[0].quantity),
+            (Decimal::TEN, 5)
+        );
+        assert_eq!((read.spread, read.ask_vwap), (Some(Decimal::TWO), None));
+
+        let big = HashMap::from([(
+            "X".to_string(),
+            Snapshot {
+                bids: vec![level(10, 5); 40],
+                ..snapshot
+            },
+        )]);
+        assert!(ring.write(None, &big).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
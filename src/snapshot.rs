@@ -1,5 +1,7 @@
 use pyo3::prelude::*;
 use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::str::FromStr;
 
 use crate::order::decimal_to_py;
 
@@ -12,6 +14,8 @@ use crate::order::decimal_to_py;
 pub struct SnapshotLevel {
     pub price: Decimal,
     pub quantity: i64,
+    /// Asset amount of one quantity unit, from the book's quantity precision.
+    pub unit: Decimal,
 }
 
 #[pymethods]
@@ -26,6 +30,13 @@ impl SnapshotLevel {
         self.quantity
     }
 
+    /// Quantity as an asset amount (equal to `quantity` unless the book
+    /// sets a quantity precision).
+    #[getter]
+    fn size(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, Decimal::from(self.quantity) * self.unit)
+    }
+
     fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
         let self_obj = crate::getter::pyref_to_object(py, &slf);
         crate::getter::handle_getter_attr(py, self_obj, name)
@@ -34,8 +45,32 @@ impl SnapshotLevel {
 
 impl SnapshotLevel {
     pub fn from_rust(price: Decimal, quantity: i64) -> Self {
-        SnapshotLevel { price, quantity }
+        SnapshotLevel {
+            price,
+            quantity,
+            unit: Decimal::ONE,
+        }
+    }
+
+    pub fn with_unit(self, unit: Decimal) -> Self {
+        SnapshotLevel { unit, ..self }
     }
+
+    fn to_json(&self) -> Value {
+        json!([self.price.to_string(), self.quantity, self.unit.to_string()])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(SnapshotLevel {
+            price: decimal_from_json(value.get(0)?)?,
+            quantity: value.get(1)?.as_i64()?,
+            unit: decimal_from_json(value.get(2)?)?,
+        })
+    }
+}
+
+fn decimal_from_json(value: &Value) -> Option<Decimal> {
+    Decimal::from_str(value.as_str()?).ok()
 }
 
 // ---------------------------------------------------------------------------
@@ -51,6 +86,10 @@ pub struct Snapshot {
     pub midpoint: Option<Decimal>,
     pub bid_vwap: Option<Decimal>,
     pub ask_vwap: Option<Decimal>,
+    /// Sum of price * quantity * multiplier over the returned bid levels.
+    pub bid_notional: Decimal,
+    /// Sum of price * quantity * multiplier over the returned ask levels.
+    pub ask_notional: Decimal,
 }
 
 #[pymethods]
@@ -93,15 +132,72 @@ impl Snapshot {
         optional_decimal_to_py(py, self.ask_vwap)
     }
 
+    #[getter]
+    fn bid_notional(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.bid_notional)
+    }
+
+    #[getter]
+    fn ask_notional(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.ask_notional)
+    }
+
     fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
         let self_obj = crate::getter::pyref_to_object(py, &slf);
         crate::getter::handle_getter_attr(py, self_obj, name)
     }
 }
 
-fn optional_decimal_to_py(py: Python<'_>, value: Option<Decimal>) -> PyResult<PyObject> {
+impl Snapshot {
+    /// JSON form with decimals as strings and levels as
+    /// `[price, quantity, unit]`, read back by `from_json`.
+    pub fn to_json(&self) -> Value {
+        let levels = |levels: &[SnapshotLevel]| -> Vec<Value> {
+            levels.iter().map(SnapshotLevel::to_json).collect()
+        };
+        let optional = |d: Option<Decimal>| d.map(|d| d.to_string());
+        json!({
+            "bids": levels(&self.bids),
+            "asks": levels(&self.asks),
+            "spread": optional(self.spread),
+            "midpoint": optional(self.midpoint),
+            "bid_vwap": optional(self.bid_vwap),
+            "ask_vwap": optional(self.ask_vwap),
+            "bid_notional": self.bid_notional.to_string(),
+            "ask_notional": self.ask_notional.to_string(),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let levels = |name: &str| -> Option<Vec<SnapshotLevel>> {
+            value[name]
+                .as_array()?
+                .iter()
+                .map(SnapshotLevel::from_json)
+                .collect()
+        };
+        let optional = |name: &str| -> Option<Option<Decimal>> {
+            match &value[name] {
+                Value::Null => Some(None),
+                other => decimal_from_json(other).map(Some),
+            }
+        };
+        Some(Snapshot {
+            bids: levels("bids")?,
+            asks: levels("asks")?,
+            spread: optional("spread")?,
+            midpoint: optional("midpoint")?,
+            bid_vwap: optional("bid_vwap")?,
+            ask_vwap: optional("ask_vwap")?,
+            bid_notional: decimal_from_json(&value["bid_notional"])?,
+            ask_notional: decimal_from_json(&value["ask_notional"])?,
+        })
+    }
+}
+
+pub(crate) fn optional_decimal_to_py(py: Python<'_>, value: Option<Decimal>) -> PyResult<PyObject> {
     match value {
         Some(d) => decimal_to_py(py, d),
-        None => Ok(py.None().into()),
+        None => Ok(py.None()),
     }
 }
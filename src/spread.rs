@@ -0,0 +1,107 @@
+use rust_decimal::Decimal;
+
+use crate::order::Side;
+
+// ---------------------------------------------------------------------------
+// Spread — two-legged instrument with implied liquidity
+// ---------------------------------------------------------------------------
+
+/// A spread instrument: one unit bought buys `front` and sells `back`, at
+/// the front price less the back price. Any two of the three books imply
+/// liquidity in the third.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spread {
+    pub symbol: String,
+    pub front: String,
+    pub back: String,
+}
+
+/// One book an implied execution trades in: the side taken there as the
+/// aggressor, and whether its price counts negatively in the implied price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImpliedLeg<'a> {
+    pub symbol: &'a str,
+    pub side: Side,
+    pub negate: bool,
+}
+
+/// Best implied liquidity for one side of a symbol, and the leg prices
+/// that produce it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImpliedLevel<'a> {
+    pub price: Decimal,
+    pub quantity: i64,
+    pub legs: [(ImpliedLeg<'a>, Decimal); 2],
+}
+
+impl Spread {
+    pub fn involves(&self, symbol: &str) -> bool {
+        [&self.symbol, &self.front, &self.back]
+            .iter()
+            .any(|s| s.as_str() == symbol)
+    }
+
+    /// The two books an order for `symbol` on `side` trades in to execute
+    /// against implied liquidity; None when `symbol` is not in the spread.
+    pub fn implied_legs(&self, symbol: &str, side: Side) -> Option<[ImpliedLeg<'_>; 2]> {
+        fn leg(symbol: &str, side: Side, negate: bool) -> ImpliedLeg<'_> {
+            ImpliedLeg {
+                symbol,
+                side,
+                negate,
+            }
+        }
+        let other = side.other();
+        if symbol == self.symbol {
+            Some([leg(&self.front, side, false), leg(&self.back, other, true)])
+        } else if symbol == self.front {
+            Some([leg(&self.symbol, side, false), leg(&self.back, side, false)])
+        } else if symbol == self.back {
+            Some([
+                leg(&self.front, side, false),
+                leg(&self.symbol, other, true),
+            ])
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> ImpliedLevel<'a> {
+    /// Combine the best opposing `(price, quantity)` in each leg's book.
+    pub fn from_quotes(legs: [ImpliedLeg<'a>; 2], quotes: [(Decimal, i64); 2]) -> Self {
+        let signed = |leg: &ImpliedLeg<'_>, price: Decimal| if leg.negate { -price } else { price };
+        ImpliedLevel {
+            price: signed(&legs[0], quotes[0].0) + signed(&legs[1], quotes[1].0),
+            quantity: quotes[0].1.min(quotes[1].1),
+            legs: [(legs[0], quotes[0].0), (legs[1], quotes[1].0)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_book_is_implied_by_the_other_two() {
+        let spread = Spread {
+            symbol: "ESZ-ESH".to_string(),
+            front: "ESZ".to_string(),
+            back: "ESH".to_string(),
+        };
+        let d = Decimal::from;
+        // Buying the spread lifts the front offer and hits the back bid.
+        let legs = spread.implied_legs("ESZ-ESH", Side::BID).unwrap();
+        assert_eq!((legs[0].symbol, legs[0].side), ("ESZ", Side::BID));
+        assert_eq!((legs[1].symbol, legs[1].side), ("ESH", Side::ASK));
+        let level = ImpliedLevel::from_quotes(legs, [(d(105), 3), (d(100), 5)]);
+        assert_eq!((level.price, level.quantity), (d(5), 3));
+
+        // Buying the front lifts the spread offer and the back offer.
+        let legs = spread.implied_legs("ESZ", Side::BID).unwrap();
+        let level = ImpliedLevel::from_quotes(legs, [(d(5), 2), (d(101), 4)]);
+        assert_eq!((level.price, level.quantity), (d(106), 2));
+        assert!(spread.implied_legs("NQZ", Side::BID).is_none());
+    }
+}
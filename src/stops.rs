@@ -0,0 +1,192 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::order::{decimal_to_py, uuid_to_py, Order, Side};
+use crate::trade::Trade;
+
+/// Whether the market at `reference` triggers a stop resting at
+/// `stop_price`.
+///
+/// Buy stops trigger when the reference is at or above the stop; sell
+/// stops when it is at or below.
+pub fn stop_is_triggered(side: Side, stop_price: Decimal, reference: Decimal) -> bool {
+    match side {
+        Side::BID => reference >= stop_price,
+        Side::ASK => reference <= stop_price,
+    }
+}
+
+/// The market price a stop order compares with its stop price.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StopTrigger {
+    /// Each trade price.
+    #[default]
+    LastTrade,
+    /// The opposite best quote: the best ask for buy stops, the best bid
+    /// for sell stops.
+    Bbo,
+    /// The midpoint of the best bid and ask.
+    Midpoint,
+}
+
+impl StopTrigger {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "last_trade" => Ok(StopTrigger::LastTrade),
+            "bbo" => Ok(StopTrigger::Bbo),
+            "midpoint" => Ok(StopTrigger::Midpoint),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid stop_trigger '{}'. Expected 'last_trade', 'bbo' or 'midpoint'.",
+                text
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StopTrigger::LastTrade => "last_trade",
+            StopTrigger::Bbo => "bbo",
+            StopTrigger::Midpoint => "midpoint",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StopActivation — one triggered stop within a cascade
+// ---------------------------------------------------------------------------
+
+/// A stop order that triggered while processing an incoming order.
+///
+/// `generation` is 1 for stops triggered by the incoming order's own
+/// trades, 2 for stops triggered by generation-1 executions, and so on.
+/// Stops triggered by the quote rather than a trade have no
+/// `trigger_trade_id`; their `trigger_price` is the quote they compared.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct StopActivation {
+    pub order: Order,
+    pub generation: u32,
+    pub trigger_trade_id: Option<Uuid>,
+    pub trigger_price: Decimal,
+    pub trades: Vec<Trade>,
+}
+
+#[pymethods]
+impl StopActivation {
+    /// The activated order, with its quantity after matching.
+    #[getter]
+    fn order(&self) -> Order {
+        self.order.clone()
+    }
+
+    #[getter]
+    fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    #[getter]
+    fn trigger_trade_id(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.trigger_trade_id
+            .map(|id| uuid_to_py(py, id))
+            .transpose()
+    }
+
+    #[getter]
+    fn trigger_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.trigger_price)
+    }
+
+    /// Executions of the activated order.
+    #[getter]
+    fn trades(&self) -> Vec<Trade> {
+        self.trades.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StopActivation(order_id={}, generation={}, trigger_price={}, trades={})",
+            self.order.id,
+            self.generation,
+            self.trigger_price,
+            self.trades.len()
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CascadeReport — causal chain of stop activations
+// ---------------------------------------------------------------------------
+
+/// Stop activations caused by one incoming order, in activation order.
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct CascadeReport {
+    pub activations: Vec<StopActivation>,
+}
+
+#[pymethods]
+impl CascadeReport {
+    #[getter]
+    fn activations(&self) -> Vec<StopActivation> {
+        self.activations.clone()
+    }
+
+    /// Deepest generation reached; 0 when no stop triggered.
+    #[getter]
+    pub fn depth(&self) -> u32 {
+        self.activations
+            .iter()
+            .map(|a| a.generation)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// All executions of activated stops, in order.
+    #[getter]
+    fn trades(&self) -> Vec<Trade> {
+        self.activations
+            .iter()
+            .flat_map(|a| a.trades.iter().cloned())
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.activations.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CascadeReport(activations={}, depth={})",
+            self.activations.len(),
+            self.depth()
+        )
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_trigger_direction_depends_on_side() {
+        let stop = Decimal::from(100);
+        assert!(stop_is_triggered(Side::BID, stop, Decimal::from(100)));
+        assert!(stop_is_triggered(Side::BID, stop, Decimal::from(101)));
+        assert!(!stop_is_triggered(Side::BID, stop, Decimal::from(99)));
+        assert!(stop_is_triggered(Side::ASK, stop, Decimal::from(99)));
+        assert!(!stop_is_triggered(Side::ASK, stop, Decimal::from(101)));
+        assert_eq!(StopTrigger::parse("BBO").unwrap(), StopTrigger::Bbo);
+        assert_eq!(StopTrigger::default().as_str(), "last_trade");
+    }
+}
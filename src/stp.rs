@@ -0,0 +1,119 @@
+use pyo3::prelude::*;
+use uuid::Uuid;
+
+// ---------------------------------------------------------------------------
+// SelfTradePrevention — refusing trades within an account or group
+// ---------------------------------------------------------------------------
+
+/// What the book does when an incoming order would trade against a resting
+/// order of the same account, or of an account in the same
+/// anti-internalization group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Cancel the rest of the incoming order.
+    Newest,
+    /// Cancel the resting order and keep matching.
+    Oldest,
+    /// Cancel both.
+    Both,
+}
+
+impl SelfTradePrevention {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "cancel_newest" => Ok(SelfTradePrevention::Newest),
+            "cancel_oldest" => Ok(SelfTradePrevention::Oldest),
+            "cancel_both" => Ok(SelfTradePrevention::Both),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid self-trade prevention mode '{}'. Expected 'cancel_newest', \
+                 'cancel_oldest' or 'cancel_both'.",
+                text
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SelfTradePrevention::Newest => "cancel_newest",
+            SelfTradePrevention::Oldest => "cancel_oldest",
+            SelfTradePrevention::Both => "cancel_both",
+        }
+    }
+}
+
+/// How an incoming order matches under self-trade prevention.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PreventionPlan {
+    /// Quantity of the incoming order allowed to match.
+    pub match_quantity: i64,
+    /// Resting orders to cancel before matching.
+    pub cancel_resting: Vec<Uuid>,
+    /// Whether the incoming order's unmatched rest is cancelled rather
+    /// than left to rest.
+    pub cancel_incoming: bool,
+}
+
+/// Plan the matching of `quantity` from an order whose prevention key (its
+/// group, else its account) is `key`, against `standing`: `(id, key,
+/// quantity)` of the price-eligible resting orders in priority order.
+pub fn plan<'a>(
+    mode: SelfTradePrevention,
+    key: &str,
+    quantity: i64,
+    standing: impl IntoIterator<Item = (Uuid, Option<&'a str>, i64)>,
+) -> PreventionPlan {
+    let mut plan = PreventionPlan {
+        match_quantity: quantity,
+        cancel_resting: Vec::new(),
+        cancel_incoming: false,
+    };
+    // Quantity the incoming order reaches before the resting order at hand.
+    let mut reachable = 0;
+    for (id, owner, resting) in standing {
+        if reachable >= quantity {
+            break;
+        }
+        if owner != Some(key) {
+            reachable += resting;
+            continue;
+        }
+        if mode != SelfTradePrevention::Newest {
+            plan.cancel_resting.push(id);
+        }
+        if mode != SelfTradePrevention::Oldest {
+            plan.match_quantity = reachable;
+            plan.cancel_incoming = true;
+            break;
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_stop_or_skip_at_the_first_conflicting_order() {
+        let id = |n| Uuid::from_u64_pair(0, n);
+        let standing = [
+            (id(1), Some("b"), 3),
+            (id(2), Some("a"), 4),
+            (id(3), None, 5),
+        ];
+        let newest = plan(SelfTradePrevention::Newest, "a", 10, standing);
+        assert_eq!((newest.match_quantity, newest.cancel_incoming), (3, true));
+        assert!(newest.cancel_resting.is_empty());
+
+        let oldest = plan(SelfTradePrevention::Oldest, "a", 10, standing);
+        assert_eq!((oldest.match_quantity, oldest.cancel_incoming), (10, false));
+        assert_eq!(oldest.cancel_resting, vec![id(2)]);
+
+        let both = plan(SelfTradePrevention::Both, "a", 10, standing);
+        assert_eq!((both.match_quantity, both.cancel_resting), (3, vec![id(2)]));
+
+        // Orders the incoming one cannot reach are left alone.
+        let small = plan(SelfTradePrevention::Both, "a", 3, standing);
+        assert_eq!((small.match_quantity, small.cancel_incoming), (3, false));
+    }
+}
@@ -0,0 +1,119 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::order::{extract_order_id, Order};
+
+// ---------------------------------------------------------------------------
+// StrategyAction — orders and cancels returned by a replay strategy
+// ---------------------------------------------------------------------------
+
+/// One instruction returned from a strategy's `on_event`: an `Order` to
+/// submit, or a `("cancel", order)` tuple whose second item is the Order or
+/// its id.
+#[derive(Clone, Debug)]
+pub enum StrategyAction {
+    Submit(Box<Order>),
+    Cancel(Uuid),
+}
+
+fn extract_action(value: &Bound<'_, pyo3::PyAny>) -> PyResult<StrategyAction> {
+    if let Ok(order) = value.extract::<PyRef<Order>>() {
+        return Ok(StrategyAction::Submit(Box::new(order.clone())));
+    }
+    if let Ok(tuple) = value.downcast::<PyTuple>() {
+        if tuple.len() == 2
+            && tuple.get_item(0)?.extract::<String>().ok().as_deref() == Some("cancel")
+        {
+            return Ok(StrategyAction::Cancel(extract_order_id(
+                &tuple.get_item(1)?,
+            )?));
+        }
+    }
+    Err(pyo3::exceptions::PyTypeError::new_err(format!(
+        "Strategy actions must be Orders or ('cancel', order) tuples, got {}",
+        value.repr()?
+    )))
+}
+
+/// Parse the return value of `on_event`: None, a single action, or a list
+/// of actions.
+pub fn extract_actions(result: &Bound<'_, pyo3::PyAny>) -> PyResult<Vec<StrategyAction>> {
+    if result.is_none() {
+        return Ok(Vec::new());
+    }
+    if let Ok(list) = result.downcast::<PyList>() {
+        return list.iter().map(|item| extract_action(&item)).collect();
+    }
+    extract_action(result).map(|action| vec![action])
+}
+
+// ---------------------------------------------------------------------------
+// LatencyQueue — strategy actions in flight to the book
+// ---------------------------------------------------------------------------
+
+/// Actions waiting to reach the book, each due `latency` clock units after
+/// the event that produced it. Event times never decrease, so due times are
+/// already in order and a FIFO suffices.
+#[derive(Debug)]
+pub struct LatencyQueue {
+    latency: i64,
+    pending: VecDeque<(i64, StrategyAction)>,
+}
+
+impl LatencyQueue {
+    pub fn new(latency: i64) -> Self {
+        LatencyQueue {
+            latency,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn schedule(&mut self, now: i64, actions: Vec<StrategyAction>) {
+        let due = now.saturating_add(self.latency);
+        self.pending
+            .extend(actions.into_iter().map(|action| (due, action)));
+    }
+
+    /// Due time of the next action, if any.
+    pub fn next_due(&self) -> Option<i64> {
+        self.pending.front().map(|&(due, _)| due)
+    }
+
+    /// Next action due at or before `until`; `None` drains everything.
+    pub fn pop_due(&mut self, until: Option<i64>) -> Option<(i64, StrategyAction)> {
+        match (self.pending.front(), until) {
+            (Some(&(due, _)), Some(until)) if due > until => None,
+            _ => self.pending.pop_front(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cancel(n: u64) -> StrategyAction {
+        StrategyAction::Cancel(Uuid::from_u64_pair(0, n))
+    }
+
+    fn due_ids(queue: &mut LatencyQueue, until: Option<i64>) -> Vec<(i64, u64)> {
+        std::iter::from_fn(|| queue.pop_due(until))
+            .map(|(due, action)| match action {
+                StrategyAction::Cancel(id) => (due, id.as_u64_pair().1),
+                StrategyAction::Submit(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn actions_are_released_once_their_latency_has_elapsed() {
+        let mut queue = LatencyQueue::new(5);
+        queue.schedule(10, vec![cancel(1), cancel(2)]);
+        queue.schedule(12, vec![cancel(3)]);
+        assert!(due_ids(&mut queue, Some(14)).is_empty());
+        assert_eq!(due_ids(&mut queue, Some(15)), vec![(15, 1), (15, 2)]);
+        assert_eq!(due_ids(&mut queue, None), vec![(17, 3)]);
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+// ---------------------------------------------------------------------------
+// SymbolTable — symbols interned to small integer ids
+// ---------------------------------------------------------------------------
+
+/// A symbol interned in a [`SymbolTable`]. Resting entries and the order
+/// index carry these instead of owned strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(u32);
+
+/// Every symbol the book has seen, each once, with its id. Ids are never
+/// reused, so one stays valid for the life of the book.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    /// Symbols come from callers, hence a randomly keyed hasher.
+    ids: HashMap<String, SymbolId, ahash::RandomState>,
+    names: Vec<String>,
+}
+
+impl SymbolTable {
+    /// The id of `symbol`, interning it if it is new.
+    pub fn intern(&mut self, symbol: &str) -> SymbolId {
+        if let Some(&id) = self.ids.get(symbol) {
+            return id;
+        }
+        let id = SymbolId(self.names.len() as u32);
+        self.names.push(symbol.to_string());
+        self.ids.insert(symbol.to_string(), id);
+        id
+    }
+
+    /// The id of `symbol`, if it has been interned.
+    pub fn get(&self, symbol: &str) -> Option<SymbolId> {
+        self.ids.get(symbol).copied()
+    }
+
+    /// The symbol `id` stands for.
+    pub fn name(&self, id: SymbolId) -> &str {
+        &self.names[id.0 as usize]
+    }
+
+    /// Rename the symbol `id` stands for to `symbol`. Returns the id
+    /// `symbol` had until now, which nothing can look up any more.
+    pub fn rename(&mut self, id: SymbolId, symbol: &str) -> Option<SymbolId> {
+        let old = std::mem::replace(&mut self.names[id.0 as usize], symbol.to_string());
+        self.ids.remove(&old);
+        self.ids
+            .insert(symbol.to_string(), id)
+            .filter(|displaced| *displaced != id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_intern_once() {
+        let mut table = SymbolTable::default();
+        let aapl = table.intern("AAPL");
+        let msft = table.intern("MSFT");
+        assert_eq!(table.intern("AAPL"), aapl);
+        assert_ne!(aapl, msft);
+        assert_eq!(table.get("MSFT"), Some(msft));
+        assert_eq!(table.get("IBM"), None);
+        assert_eq!(table.name(msft), "MSFT");
+
+        let ibm = table.intern("IBM");
+        assert_eq!(table.rename(msft, "IBM"), Some(ibm));
+        assert_eq!(table.get("IBM"), Some(msft));
+        assert_eq!(table.get("MSFT"), None);
+        assert_eq!(table.name(msft), "IBM");
+    }
+}
@@ -0,0 +1,156 @@
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+use crate::snapshot::Snapshot;
+
+/// Buffer format of the elements: native-endian f64.
+const FORMAT: &std::ffi::CStr = c"d";
+
+// ---------------------------------------------------------------------------
+// TensorSnapshot — fixed-shape depth arrays for numeric consumers
+// ---------------------------------------------------------------------------
+
+/// A C-contiguous float64 array: the depth of one symbol, of shape
+/// `(2, 2, depth)` — side (bid, ask) by field (price, size) by level, best
+/// level first, padded past the last level — or a feature vector.
+///
+/// The object exposes the buffer protocol, so `numpy.asarray(snapshot)`
+/// (or `torch.frombuffer`) views its memory without copying, and
+/// `memoryview(snapshot)` works without numpy.
+#[pyclass(frozen)]
+pub struct TensorSnapshot {
+    data: Vec<f64>,
+    shape: Vec<ffi::Py_ssize_t>,
+    strides: Vec<ffi::Py_ssize_t>,
+}
+
+/// Size of one element in bytes.
+const ITEM: ffi::Py_ssize_t = std::mem::size_of::<f64>() as ffi::Py_ssize_t;
+
+impl TensorSnapshot {
+    /// Lay out `snapshot` (or nothing, for an unknown symbol) over `depth`
+    /// levels, with `fill` in the slots of missing levels.
+    pub fn new(snapshot: Option<&Snapshot>, depth: usize, fill: f64) -> Self {
+        let mut data = vec![fill; 4 * depth];
+        if let Some(snapshot) = snapshot {
+            for (side, levels) in [&snapshot.bids, &snapshot.asks].into_iter().enumerate() {
+                for (i, level) in levels.iter().take(depth).enumerate() {
+                    let size = Decimal::from(level.quantity) * level.unit;
+                    data[side * 2 * depth + i] = level.price.to_f64().unwrap_or(f64::NAN);
+                    data[(side * 2 + 1) * depth + i] = size.to_f64().unwrap_or(f64::NAN);
+                }
+            }
+        }
+        let depth = depth as ffi::Py_ssize_t;
+        TensorSnapshot {
+            data,
+            shape: vec![2, 2, depth],
+            strides: vec![2 * depth * ITEM, depth * ITEM, ITEM],
+        }
+    }
+
+    /// A one-dimensional array of `values`.
+    pub fn vector(values: Vec<f64>) -> Self {
+        TensorSnapshot {
+            shape: vec![values.len() as ffi::Py_ssize_t],
+            strides: vec![ITEM],
+            data: values,
+        }
+    }
+}
+
+#[pymethods]
+impl TensorSnapshot {
+    /// `(2, 2, depth)` for depth, `(n,)` for a feature vector.
+    #[getter]
+    fn shape<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        PyTuple::new(py, &self.shape)
+    }
+
+    /// Expose the array read-only. The view keeps this object alive and the
+    /// data never changes, so consumers may hold it indefinitely.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("TensorSnapshot is read-only"));
+        }
+        let tensor = slf.get();
+        (*view).buf = tensor.data.as_ptr() as *mut c_void;
+        (*view).len = tensor.data.len() as ffi::Py_ssize_t * ITEM;
+        (*view).itemsize = ITEM;
+        (*view).readonly = 1;
+        (*view).ndim = tensor.shape.len() as c_int;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            FORMAT.as_ptr() as *mut c_char
+        } else {
+            ptr::null_mut()
+        };
+        (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            tensor.shape.as_ptr() as *mut ffi::Py_ssize_t
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            tensor.strides.as_ptr() as *mut ffi::Py_ssize_t
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+        (*view).obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        let shape: Vec<String> = self.shape.iter().map(|n| n.to_string()).collect();
+        match shape.as_slice() {
+            [n] => format!("TensorSnapshot(shape=({},))", n),
+            _ => format!("TensorSnapshot(shape=({}))", shape.join(", ")),
+        }
+    }
+
+    fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
+        let self_obj = crate::getter::pyref_to_object(py, &slf);
+        crate::getter::handle_getter_attr(py, self_obj, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::SnapshotLevel;
+
+    #[test]
+    fn levels_are_laid_out_side_by_field_by_level() {
+        let level = |price: i64, quantity| SnapshotLevel::from_rust(Decimal::from(price), quantity);
+        let snapshot = Snapshot {
+            bids: vec![level(10, 5), level(9, 2)],
+            asks: vec![level(11, 4)],
+            spread: None,
+            midpoint: None,
+            bid_vwap: None,
+            ask_vwap: None,
+            bid_notional: Decimal::ZERO,
+            ask_notional: Decimal::ZERO,
+        };
+        let tensor = TensorSnapshot::new(Some(&snapshot), 3, -1.0);
+        assert_eq!(
+            tensor.data,
+            [10.0, 9.0, -1.0, 5.0, 2.0, -1.0, 11.0, -1.0, -1.0, 4.0, -1.0, -1.0]
+        );
+        assert_eq!(tensor.strides, [48, 24, 8]);
+        assert_eq!(TensorSnapshot::new(None, 2, 0.0).data, [0.0; 8]);
+    }
+}
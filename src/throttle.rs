@@ -0,0 +1,246 @@
+use pyo3::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+use crate::order::Order;
+use crate::quoting::RateLimit;
+
+// ---------------------------------------------------------------------------
+// Throttles — exchange message-rate limits
+// ---------------------------------------------------------------------------
+
+/// What happens to a message beyond its rate limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThrottleAction {
+    /// Refuse it with code "throttled".
+    Reject,
+    /// Hold it until the window has room.
+    Queue,
+}
+
+impl ThrottleAction {
+    pub fn parse(text: &str) -> PyResult<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "reject" => Ok(ThrottleAction::Reject),
+            "queue" => Ok(ThrottleAction::Queue),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid on_limit '{}'. Expected 'reject' or 'queue'.",
+                text
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThrottleAction::Reject => "reject",
+            ThrottleAction::Queue => "queue",
+        }
+    }
+}
+
+/// One rate limit with the send times in its current window and running
+/// counts since it was set.
+#[derive(Clone, Debug)]
+pub struct Throttle {
+    pub rate: RateLimit,
+    pub action: ThrottleAction,
+    times: VecDeque<i64>,
+    pub sent: u64,
+    pub rejected: u64,
+    /// Queued orders the book refused once released.
+    pub dropped: u64,
+}
+
+impl Throttle {
+    pub fn new(rate: RateLimit, action: ThrottleAction) -> Self {
+        Throttle {
+            rate,
+            action,
+            times: VecDeque::new(),
+            sent: 0,
+            rejected: 0,
+            dropped: 0,
+        }
+    }
+
+    fn has_room(&mut self, now: i64) -> bool {
+        self.rate.prune(&mut self.times, now);
+        self.times.len() < self.rate.limit
+    }
+
+    /// Messages sent within the window ending at `now`.
+    pub fn in_window(&self, now: i64) -> usize {
+        self.times
+            .iter()
+            .filter(|&&t| t > now - self.rate.window)
+            .count()
+    }
+}
+
+/// How a message fares against the throttles.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Admission {
+    Send,
+    Queue,
+    /// Refused by the limit of this session (None for the global limit).
+    Reject(Option<String>, RateLimit),
+}
+
+/// The book's global limit, per-session limits, and the orders queued
+/// behind them in arrival order.
+#[derive(Clone, Debug, Default)]
+pub struct Throttles {
+    pub global: Option<Throttle>,
+    pub sessions: HashMap<String, Throttle>,
+    pub queue: VecDeque<Order>,
+}
+
+impl Throttles {
+    pub fn is_empty(&self) -> bool {
+        self.global.is_none() && self.sessions.is_empty()
+    }
+
+    /// Count `order` as a message at `now` if every limit it falls under
+    /// has room; otherwise queue or refuse it. An order whose queue-mode
+    /// limit already has orders waiting queues behind them.
+    pub fn admit(&mut self, order: &Order, now: i64) -> Admission {
+        let session = order.session_id.as_deref();
+        let waiting = |throttle: Option<&Throttle>, shares: &dyn Fn(&Order) -> bool| {
+            throttle.is_some_and(|t| t.action == ThrottleAction::Queue)
+                && self.queue.iter().any(shares)
+        };
+        let behind = waiting(self.global.as_ref(), &|_| true)
+            || waiting(session.and_then(|s| self.sessions.get(s)), &|queued| {
+                queued.session_id.as_deref() == session
+            });
+        let mut full = Vec::new();
+        if let Some(global) = self.global.as_mut() {
+            if !global.has_room(now) {
+                full.push((None, global.rate, global.action));
+            }
+        }
+        if let Some((name, throttle)) =
+            session.and_then(|s| self.sessions.get_mut(s).map(|t| (s, t)))
+        {
+            if !throttle.has_room(now) {
+                full.push((Some(name.to_string()), throttle.rate, throttle.action));
+            }
+        }
+        if let Some((scope, rate, _)) = full
+            .iter()
+            .find(|(_, _, action)| *action == ThrottleAction::Reject)
+        {
+            self.throttle_mut(scope.as_deref()).rejected += 1;
+            return Admission::Reject(scope.clone(), *rate);
+        }
+        if behind || !full.is_empty() {
+            self.queue.push_back(order.clone());
+            return Admission::Queue;
+        }
+        for throttle in self
+            .global
+            .iter_mut()
+            .chain(session.and_then(|s| self.sessions.get_mut(s)))
+        {
+            throttle.times.push_back(now);
+            throttle.sent += 1;
+        }
+        Admission::Send
+    }
+
+    fn throttle_mut(&mut self, scope: Option<&str>) -> &mut Throttle {
+        match scope {
+            None => self.global.as_mut().expect("global throttle"),
+            Some(session) => self.sessions.get_mut(session).expect("session throttle"),
+        }
+    }
+
+    /// Take the queued orders that now fit their limits, in arrival order,
+    /// counting each as sent at `now`. An order that still does not fit
+    /// holds back later orders under the same limits.
+    pub fn release(&mut self, now: i64) -> Vec<Order> {
+        let waiting = std::mem::take(&mut self.queue);
+        let mut released = Vec::new();
+        for order in waiting {
+            // Orders re-queued behind a blocked one stay in arrival order.
+            if let Admission::Send = self.admit(&order, now) {
+                released.push(order);
+            }
+        }
+        released
+    }
+
+    /// Count a released `order` the book then refused against every limit
+    /// it fell under.
+    pub fn drop_released(&mut self, order: &Order) {
+        let session = order.session_id.as_deref();
+        for throttle in self
+            .global
+            .iter_mut()
+            .chain(session.and_then(|s| self.sessions.get_mut(s)))
+        {
+            throttle.dropped += 1;
+        }
+    }
+
+    /// Take a queued order out of the queue.
+    pub fn remove(&mut self, order_id: Uuid) -> Option<Order> {
+        let pos = self.queue.iter().position(|order| order.id == order_id)?;
+        self.queue.remove(pos)
+    }
+
+    /// Orders queued under the limit of `scope` (None for the global one).
+    pub fn queued(&self, scope: Option<&str>) -> usize {
+        self.queue
+            .iter()
+            .filter(|order| scope.is_none() || order.session_id.as_deref() == scope)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Side;
+
+    #[test]
+    fn limits_queue_or_reject_and_release_in_order() {
+        let order = |session: &str| {
+            Order::try_new(Side::BID, "X".to_string(), 10.0, 1)
+                .unwrap()
+                .with_session_id(Some(session.to_string()))
+        };
+        let rate = |limit| RateLimit { limit, window: 10 };
+        let mut throttles = Throttles {
+            global: Some(Throttle::new(rate(3), ThrottleAction::Queue)),
+            ..Throttles::default()
+        };
+        throttles.sessions.insert(
+            "a".to_string(),
+            Throttle::new(rate(1), ThrottleAction::Reject),
+        );
+        assert_eq!(throttles.admit(&order("a"), 0), Admission::Send);
+        assert_eq!(
+            throttles.admit(&order("a"), 1),
+            Admission::Reject(Some("a".to_string()), rate(1))
+        );
+        assert_eq!(throttles.admit(&order("b"), 2), Admission::Send);
+        assert_eq!(throttles.admit(&order("b"), 3), Admission::Send);
+        let (first, second) = (order("b"), order("b"));
+        assert_eq!(throttles.admit(&first, 4), Admission::Queue);
+        assert_eq!(throttles.admit(&second, 4), Admission::Queue);
+        assert_eq!(throttles.queued(None), 2);
+
+        let released = throttles.release(10);
+        assert_eq!(
+            released.iter().map(|o| o.id).collect::<Vec<_>>(),
+            [first.id]
+        );
+        assert_eq!(throttles.queue[0].id, second.id);
+        assert_eq!(throttles.global.as_ref().unwrap().in_window(10), 3);
+
+        throttles.drop_released(&first);
+        assert_eq!(throttles.global.as_ref().unwrap().dropped, 1);
+        assert_eq!(throttles.sessions["a"].dropped, 0);
+    }
+}
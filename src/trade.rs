@@ -1,10 +1,14 @@
 use pyo3::prelude::*;
 use rust_decimal::prelude::ToPrimitive;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use std::collections::VecDeque;
 use uuid::Uuid;
 
+use crate::enrich::{extras_to_py, Extras};
+use crate::instrument::DEFAULT_PRICE_PRECISION;
+use crate::latency::OrderTimestamps;
 use crate::order::{decimal_to_py, uuid_to_py, Order, Side};
+use crate::stops::CascadeReport;
 
 // ---------------------------------------------------------------------------
 // Trade
@@ -14,20 +18,44 @@ use crate::order::{decimal_to_py, uuid_to_py, Order, Side};
 #[pyclass]
 #[derive(Clone, Debug)]
 pub struct Trade {
+    /// Unique id of this execution.
+    pub trade_id: Uuid,
     pub incoming_order_id: Uuid,
     pub standing_order_id: Uuid,
     pub fill_quantity: i64,
     pub fill_price: Decimal,
+    pub symbol: String,
+    /// Limit price the standing order rested at.
+    pub standing_price: Decimal,
+    /// Quantity the standing order still had open after this fill.
+    pub standing_remaining: i64,
+    /// Fields added by the book's trade enrichers.
+    pub extra: Extras,
 }
 
 #[pymethods]
 impl Trade {
+    /// `standing_price` defaults to `fill_price` and `standing_remaining`
+    /// to 0, i.e. a standing order fully filled at its own price.
     #[new]
+    #[pyo3(signature = (
+        incoming_order_id,
+        standing_order_id,
+        fill_quantity,
+        fill_price,
+        *,
+        symbol = String::new(),
+        standing_price = None,
+        standing_remaining = 0,
+    ))]
     fn new(
         incoming_order_id: &Bound<'_, pyo3::PyAny>,
         standing_order_id: &Bound<'_, pyo3::PyAny>,
         fill_quantity: i64,
         fill_price: &Bound<'_, pyo3::PyAny>,
+        symbol: String,
+        standing_price: Option<&Bound<'_, pyo3::PyAny>>,
+        standing_remaining: i64,
     ) -> PyResult<Self> {
         let inc_str: String = incoming_order_id.str()?.extract()?;
         let std_str: String = standing_order_id.str()?.extract()?;
@@ -43,14 +71,37 @@ impl Trade {
             pyo3::exceptions::PyValueError::new_err(format!("Invalid fill_price: {}", e))
         })?;
 
+        let standing_price = match standing_price {
+            Some(standing_price) => {
+                let standing_str: String = standing_price.str()?.extract()?;
+                Decimal::from_str_exact(&standing_str).map_err(|e| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Invalid standing_price: {}",
+                        e
+                    ))
+                })?
+            }
+            None => price,
+        };
+
         Ok(Trade {
+            trade_id: Uuid::new_v4(),
             incoming_order_id: inc_id,
             standing_order_id: std_id,
             fill_quantity,
             fill_price: price,
+            symbol,
+            standing_price,
+            standing_remaining,
+            extra: Extras::new(),
         })
     }
 
+    #[getter]
+    fn trade_id(&self, py: Python<'_>) -> PyResult<PyObject> {
+        uuid_to_py(py, self.trade_id)
+    }
+
     #[getter]
     fn incoming_order_id(&self, py: Python<'_>) -> PyResult<PyObject> {
         uuid_to_py(py, self.incoming_order_id)
@@ -71,6 +122,30 @@ impl Trade {
         decimal_to_py(py, self.fill_price)
     }
 
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    #[getter]
+    fn standing_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.standing_price)
+    }
+
+    /// Open quantity of the standing order right after this fill; 0 once
+    /// the fill completed it.
+    #[getter]
+    fn standing_remaining(&self) -> i64 {
+        self.standing_remaining
+    }
+
+    /// Fields the book's enrichers added, e.g. fees, venue or liquidity
+    /// flag, as a new dict.
+    #[getter]
+    fn extra<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        extras_to_py(py, &self.extra, false)
+    }
+
     fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
         let self_obj = crate::getter::pyref_to_object(py, &slf);
         crate::getter::handle_getter_attr(py, self_obj, name)
@@ -85,11 +160,41 @@ impl Trade {
         fill_quantity: i64,
         fill_price: Decimal,
     ) -> Self {
-        Trade {
+        pyorderbook_core::Trade::new(
             incoming_order_id,
             standing_order_id,
             fill_quantity,
             fill_price,
+        )
+        .into()
+    }
+
+    pub fn with_symbol(mut self, symbol: &str) -> Self {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    /// Record the standing order's resting price and open quantity after
+    /// the fill.
+    pub fn with_standing(mut self, price: Decimal, remaining: i64) -> Self {
+        self.standing_price = price;
+        self.standing_remaining = remaining;
+        self
+    }
+}
+
+impl From<pyorderbook_core::Trade> for Trade {
+    fn from(trade: pyorderbook_core::Trade) -> Self {
+        Trade {
+            trade_id: trade.trade_id,
+            incoming_order_id: trade.incoming_order_id,
+            standing_order_id: trade.standing_order_id,
+            fill_quantity: trade.fill_quantity,
+            fill_price: trade.fill_price,
+            symbol: String::new(),
+            standing_price: trade.standing_price,
+            standing_remaining: trade.standing_remaining,
+            extra: Extras::new(),
         }
     }
 }
@@ -106,19 +211,25 @@ pub struct TradeBlotter {
     pub trades: Vec<Trade>,
     pub total_cost: f64,
     pub average_price: f64,
+    /// Distinct price levels the order traded through.
+    pub levels_walked: usize,
+    /// Least favourable fill price; None without trades.
+    pub worst_fill_price: Option<Decimal>,
+    /// Volume-weighted amount per unit by which fills beat the limit.
+    pub price_improvement: Decimal,
+    /// Stop orders triggered, directly or transitively, by this order.
+    pub cascade: CascadeReport,
+    /// Processing timestamps, when the book tracks latency.
+    pub timestamps: Option<OrderTimestamps>,
+    /// Orders self-trade prevention cancelled while matching this one.
+    pub prevented: Vec<Order>,
 }
 
 #[pymethods]
 impl TradeBlotter {
     #[new]
     fn new(order: Order, trades: Vec<Trade>) -> Self {
-        let (total_cost, average_price) = compute_blotter_stats(&trades);
-        TradeBlotter {
-            order,
-            trades,
-            total_cost,
-            average_price,
-        }
+        TradeBlotter::from_rust(order, trades, Decimal::ONE, DEFAULT_PRICE_PRECISION)
     }
 
     #[getter]
@@ -145,6 +256,57 @@ impl TradeBlotter {
         self.average_price
     }
 
+    /// Number of price levels the order consumed or partially filled.
+    #[getter]
+    fn levels_walked(&self) -> usize {
+        self.levels_walked
+    }
+
+    /// Highest price paid by a bid or lowest received by an ask.
+    #[getter]
+    fn worst_fill_price(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self.worst_fill_price {
+            Some(price) => decimal_to_py(py, price),
+            None => Ok(py.None()),
+        }
+    }
+
+    /// How much better than its limit the order filled on average, per
+    /// unit and weighted by fill quantity; 0 without trades.
+    #[getter]
+    fn price_improvement(&self, py: Python<'_>) -> PyResult<PyObject> {
+        decimal_to_py(py, self.price_improvement)
+    }
+
+    #[getter]
+    fn cascade(&self) -> CascadeReport {
+        self.cascade.clone()
+    }
+
+    /// Orders cancelled by self-trade prevention while this one matched:
+    /// resting orders of its account or group and, if its rest was
+    /// cancelled, this order with the quantity it had left.
+    #[getter]
+    fn prevented(&self) -> Vec<Order> {
+        self.prevented.clone()
+    }
+
+    /// Nanosecond timestamps on the book's monotonic clock at which the
+    /// order was received, risk-checked, matched and acked, as a dict;
+    /// None unless `set_latency_tracking` is on.
+    #[getter]
+    fn timestamps(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let Some(timestamps) = self.timestamps else {
+            return Ok(py.None());
+        };
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("received", timestamps.received)?;
+        dict.set_item("risk_checked", timestamps.risk_checked)?;
+        dict.set_item("matched", timestamps.matched)?;
+        dict.set_item("acked", timestamps.acked)?;
+        Ok(dict.into())
+    }
+
     fn __getattr__(slf: PyRef<'_, Self>, name: &str, py: Python<'_>) -> PyResult<PyObject> {
         let self_obj = crate::getter::pyref_to_object(py, &slf);
         crate::getter::handle_getter_attr(py, self_obj, name)
@@ -153,18 +315,61 @@ impl TradeBlotter {
 
 impl TradeBlotter {
     /// Create a TradeBlotter from Rust-native types (used internally).
-    pub fn from_rust(order: Order, trades: Vec<Trade>) -> Self {
-        let (total_cost, average_price) = compute_blotter_stats(&trades);
+    /// `multiplier` scales `total_cost` for futures-style instruments, and
+    /// both statistics are rounded to `price_precision` decimal places.
+    pub fn from_rust(
+        order: Order,
+        trades: Vec<Trade>,
+        multiplier: Decimal,
+        price_precision: u32,
+    ) -> Self {
+        let (total_cost, average_price) =
+            compute_blotter_stats(&trades, multiplier, price_precision);
+        let (levels_walked, worst_fill_price, price_improvement) =
+            compute_depth_stats(&order, &trades);
         TradeBlotter {
             order,
             trades,
             total_cost,
             average_price,
+            levels_walked,
+            worst_fill_price,
+            price_improvement: price_improvement
+                .round_dp_with_strategy(price_precision, RoundingStrategy::MidpointAwayFromZero),
+            cascade: CascadeReport::default(),
+            timestamps: None,
+            prevented: Vec::new(),
         }
     }
 }
 
-fn compute_blotter_stats(trades: &[Trade]) -> (f64, f64) {
+/// Levels walked, worst fill price and per-unit price improvement of
+/// `order`'s own fills. Trades arrive best level first, so each change of
+/// fill price is a new level.
+fn compute_depth_stats(order: &Order, trades: &[Trade]) -> (usize, Option<Decimal>, Decimal) {
+    let mut levels_walked = 0;
+    let mut last_price = None;
+    let mut improvement = Decimal::ZERO;
+    let mut filled = 0;
+    for t in trades {
+        if last_price != Some(t.fill_price) {
+            levels_walked += 1;
+            last_price = Some(t.fill_price);
+        }
+        let per_unit = match order.side {
+            Side::BID => order.price - t.fill_price,
+            Side::ASK => t.fill_price - order.price,
+        };
+        improvement += per_unit * Decimal::from(t.fill_quantity);
+        filled += t.fill_quantity;
+    }
+    if filled > 0 {
+        improvement /= Decimal::from(filled);
+    }
+    (levels_walked, last_price, improvement)
+}
+
+fn compute_blotter_stats(trades: &[Trade], multiplier: Decimal, precision: u32) -> (f64, f64) {
     if trades.is_empty() {
         return (0.0, 0.0);
     }
@@ -175,9 +380,13 @@ fn compute_blotter_stats(trades: &[Trade]) -> (f64, f64) {
         sum_price += t.fill_price;
     }
     let count = Decimal::from(trades.len() as i64);
-    let tc = sum_cost.to_f64().unwrap_or(0.0);
-    let ap = (sum_price / count).to_f64().unwrap_or(0.0);
-    ((tc * 100.0).round() / 100.0, (ap * 100.0).round() / 100.0)
+    let round = |value: Decimal| {
+        value
+            .round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero)
+            .to_f64()
+            .unwrap_or(0.0)
+    };
+    (round(sum_cost * multiplier), round(sum_price / count))
 }
 
 // ---------------------------------------------------------------------------
@@ -366,10 +575,44 @@ mod tests {
             Decimal::from_str_exact("20.789").unwrap(),
         );
 
-        let blotter = TradeBlotter::from_rust(order, vec![first, second]);
+        let blotter = TradeBlotter::from_rust(
+            order.clone(),
+            vec![first.clone(), second.clone()],
+            Decimal::ONE,
+            2,
+        );
 
         assert_eq!(blotter.total_cost, 41.04);
         assert_eq!(blotter.average_price, 15.46);
+
+        let scaled = TradeBlotter::from_rust(
+            order.clone(),
+            vec![first.clone(), second.clone()],
+            Decimal::from(50),
+            2,
+        );
+        assert_eq!(scaled.total_cost, 2051.75);
+        assert_eq!(scaled.average_price, 15.46);
+
+        let precise = TradeBlotter::from_rust(order, vec![first, second], Decimal::ONE, 8);
+        assert_eq!(precise.total_cost, 41.035);
+        assert_eq!(precise.average_price, 15.456);
+    }
+
+    #[test]
+    fn depth_stats_count_levels_and_weight_improvement() {
+        let order = Order::try_new(Side::BID, "AAPL".to_string(), 12.0, 6).unwrap();
+        let d = Decimal::from;
+        let fills = [(2, 10), (1, 11), (3, 11)]
+            .map(|(qty, price)| Trade::from_rust(order.id, Uuid::new_v4(), qty, d(price)));
+        let blotter = TradeBlotter::from_rust(order.clone(), fills.to_vec(), Decimal::ONE, 2);
+        assert_eq!(blotter.levels_walked, 2);
+        assert_eq!(blotter.worst_fill_price, Some(d(11)));
+        // (2 * 2 + 4 * 1) / 6 units
+        assert_eq!(blotter.price_improvement, Decimal::new(133, 2));
+
+        let empty = TradeBlotter::from_rust(order, Vec::new(), Decimal::ONE, 2);
+        assert_eq!((empty.levels_walked, empty.worst_fill_price), (0, None));
     }
 
     #[test]
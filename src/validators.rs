@@ -0,0 +1,195 @@
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+use crate::order::Order;
+use crate::reject::RejectCode;
+
+// ---------------------------------------------------------------------------
+// Validators — user checks an order passes before matching
+// ---------------------------------------------------------------------------
+
+/// What one validator checks.
+#[derive(Debug)]
+pub enum Check {
+    /// Only these symbols may trade.
+    Symbols(HashSet<String>),
+    /// The limit price lies within `max_deviation` (a fraction) of the
+    /// symbol's reference price: the one given for it, else the last trade,
+    /// else the midpoint. Orders pass while there is no reference.
+    PriceBand {
+        max_deviation: Decimal,
+        references: HashMap<String, Decimal>,
+    },
+    /// A Python callable taking the Order: None or True passes it, False or
+    /// a string (the reason) rejects it.
+    Python(PyObject),
+}
+
+impl Check {
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        match self {
+            Check::Symbols(symbols) => Check::Symbols(symbols.clone()),
+            Check::PriceBand {
+                max_deviation,
+                references,
+            } => Check::PriceBand {
+                max_deviation: *max_deviation,
+                references: references.clone(),
+            },
+            Check::Python(callable) => Check::Python(callable.clone_ref(py)),
+        }
+    }
+
+    /// Why `order` fails this check, if it does.
+    fn reason(
+        &self,
+        order: &Order,
+        market_reference: impl Fn(&str) -> Option<Decimal>,
+    ) -> PyResult<Option<String>> {
+        match self {
+            Check::Symbols(symbols) => Ok((!symbols.contains(&order.symbol))
+                .then(|| format!("symbol {} is not allowed", order.symbol))),
+            Check::PriceBand {
+                max_deviation,
+                references,
+            } => {
+                let reference = references
+                    .get(&order.symbol)
+                    .copied()
+                    .or_else(|| market_reference(&order.symbol));
+                let Some(reference) = reference else {
+                    return Ok(None);
+                };
+                let band = reference.abs() * max_deviation;
+                Ok(((order.price - reference).abs() > band).then(|| {
+                    format!(
+                        "price {} is more than {} from the reference {}",
+                        order.price, band, reference
+                    )
+                }))
+            }
+            Check::Python(callable) => Python::with_gil(|py| {
+                let verdict = callable.call1(py, (Py::new(py, order.clone())?,))?;
+                let verdict = verdict.bind(py);
+                if verdict.is_none() {
+                    return Ok(None);
+                }
+                if let Ok(passed) = verdict.extract::<bool>() {
+                    return Ok((!passed).then(|| "rejected".to_string()));
+                }
+                Ok(Some(verdict.str()?.extract()?))
+            }),
+        }
+    }
+}
+
+/// A named check in the pipeline.
+#[derive(Debug)]
+pub struct Validator {
+    pub name: String,
+    pub check: Check,
+}
+
+/// Validators in the order they were added; an order must pass them all,
+/// and the first it fails names the rejection.
+#[derive(Debug, Default)]
+pub struct Validators {
+    pipeline: Vec<Validator>,
+}
+
+impl Validators {
+    pub fn add(&mut self, name: String, check: Check) -> PyResult<()> {
+        if self.pipeline.iter().any(|v| v.name == name) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "A validator named '{}' is already registered",
+                name
+            )));
+        }
+        self.pipeline.push(Validator { name, check });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.pipeline.len();
+        self.pipeline.retain(|v| v.name != name);
+        self.pipeline.len() != before
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.pipeline.iter().map(|v| v.name.clone()).collect()
+    }
+
+    /// Run `order` through the pipeline, rejecting it with the first
+    /// failing validator's name and reason.
+    pub fn check(
+        &self,
+        order: &Order,
+        market_reference: impl Fn(&str) -> Option<Decimal>,
+    ) -> PyResult<()> {
+        for validator in &self.pipeline {
+            if let Some(reason) = validator.check.reason(order, &market_reference)? {
+                return Err(RejectCode::Validation.err_from(
+                    &validator.name,
+                    format!(
+                        "Order {} rejected by validator '{}': {}",
+                        order.id, validator.name, reason
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clone_ref(&self, py: Python<'_>) -> Self {
+        Validators {
+            pipeline: self
+                .pipeline
+                .iter()
+                .map(|v| Validator {
+                    name: v.name.clone(),
+                    check: v.check.clone_ref(py),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Side;
+
+    #[test]
+    fn first_failing_validator_names_the_rejection() {
+        let mut validators = Validators::default();
+        let symbols = HashSet::from(["AAPL".to_string()]);
+        validators
+            .add("universe".to_string(), Check::Symbols(symbols))
+            .unwrap();
+        let band = Check::PriceBand {
+            max_deviation: Decimal::new(1, 1),
+            references: HashMap::new(),
+        };
+        validators.add("band".to_string(), band).unwrap();
+        assert!(validators
+            .add("band".to_string(), Check::Symbols(HashSet::new()))
+            .is_err());
+
+        let order =
+            |symbol: &str, price| Order::try_new(Side::BID, symbol.to_string(), price, 1).unwrap();
+        let last = |_: &str| Some(Decimal::from(100));
+        assert!(validators.check(&order("AAPL", 105.0), last).is_ok());
+        assert!(validators.check(&order("AAPL", 111.0), last).is_err());
+        assert!(validators.check(&order("AAPL", 111.0), |_| None).is_ok());
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = validators.check(&order("MSFT", 100.0), last).unwrap_err();
+            let value = err.value(py);
+            let validator: String = value.getattr("validator").unwrap().extract().unwrap();
+            assert_eq!(validator, "universe");
+        });
+        assert!(validators.remove("universe"));
+        assert_eq!(validators.names(), ["band"]);
+    }
+}
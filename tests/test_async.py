@@ -0,0 +1,97 @@
+"""Tests for the asyncio front-end (pyorderbook.aio)."""
+
+from __future__ import annotations
+
+import asyncio
+import threading
+
+from pyorderbook import AsyncBook, Book, Trade, ask, bid
+
+
+def test_match_returns_blotters() -> None:
+    async def scenario() -> None:
+        book = AsyncBook()
+        await book.match(ask("X", 10.0, 5))
+        blotter = await book.match(bid("X", 10.0, 3))
+        assert blotter.trades[0].fill_quantity == 3
+        blotters = await book.match([bid("X", 10.0, 1), bid("X", 10.0, 1)])
+        assert len(blotters) == 2
+        assert (await book.snapshot("X")).asks == []
+
+    asyncio.run(scenario())
+
+
+def test_trade_stream_receives_executions() -> None:
+    async def scenario() -> list[Trade]:
+        book = AsyncBook()
+        stream = book.trades()
+        await book.match([ask("X", 10.0, 2), bid("X", 10.0, 1), bid("X", 10.0, 1)])
+        book.close()
+        return [trade async for trade in stream]
+
+    trades = asyncio.run(scenario())
+    assert [t.fill_quantity for t in trades] == [1, 1]
+
+
+def test_bounded_stream_drops_when_consumer_lags() -> None:
+    async def scenario() -> tuple[Trade, list[Trade]]:
+        book = AsyncBook()
+        stream = book.trades(maxsize=1)
+        await book.match([ask("X", 10.0, 2), bid("X", 10.0, 1), bid("X", 10.0, 1)])
+        first = await anext(stream)
+        stream.close()
+        return first, [trade async for trade in stream]
+
+    first, rest = asyncio.run(scenario())
+    assert first.fill_quantity == 1
+    assert rest == []
+
+
+def test_cancel_and_wrapped_book() -> None:
+    async def scenario() -> None:
+        inner = Book()
+        book = AsyncBook(inner)
+        order = bid("X", 10.0, 1)
+        await book.match(order)
+        await book.cancel(order)
+        assert inner.get_order(order.id) is None
+
+    asyncio.run(scenario())
+
+
+def test_reads_during_a_match_wait_for_it() -> None:
+    async def scenario() -> None:
+        book = AsyncBook()
+        orders = [ask("X", 10.0, 1) for _ in range(20_000)]
+        pending = asyncio.create_task(book.match(orders))
+        await asyncio.sleep(0)
+        snapshot = await book.snapshot("X")
+        assert pending.done()
+        assert snapshot.asks[0].quantity == 20_000
+
+    asyncio.run(scenario())
+
+
+def test_batch_match_leaves_the_book_usable_from_other_threads() -> None:
+    book = Book()
+    errors: list[RuntimeError] = []
+    done = threading.Event()
+
+    def read() -> None:
+        while not done.is_set():
+            try:
+                book.snapshot("X")
+            except RuntimeError as exc:
+                errors.append(exc)
+                return
+
+    reader = threading.Thread(target=read)
+    reader.start()
+    try:
+        for _ in range(5):
+            book.match([ask("X", 10.0, 1) for _ in range(5_000)])
+    finally:
+        done.set()
+        reader.join()
+    assert errors == []
+    assert book.snapshot("X").asks[0].quantity == 25_000
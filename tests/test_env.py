@@ -0,0 +1,94 @@
+"""Tests for the Gymnasium-style environment (pyorderbook.env)."""
+
+from __future__ import annotations
+
+import math
+from decimal import Decimal
+
+import pytest
+
+from pyorderbook import Book, Instrument, MarketEnv, ask, bid
+
+
+def flow() -> list:
+    return [
+        bid("X", 99, 10),
+        ask("X", 101, 10),
+        (5, ask("X", 100, 2)),
+        (9, bid("X", 100.5, 3)),
+    ]
+
+
+def test_episode_scores_fills_at_the_midpoint() -> None:
+    env = MarketEnv(flow, "X", events_per_step=2, depth=2)
+    observation, info = env.reset()
+    assert memoryview(observation).shape == (2, 2, 2)
+    assert info["position"] == 0
+
+    observation, reward, terminated, truncated, info = env.step(("bid", 100, 2))
+    assert reward == 0.0
+    assert (terminated, truncated) == (False, False)
+    assert len(info["blotters"]) == 1
+    assert memoryview(observation).tolist()[0][0] == [100.0, 99.0]
+
+    # The flow's ask at 100 fills the agent's bid, then a bid at 100.5
+    # lifts the mid to 100.75.
+    _, reward, terminated, _, info = env.step(None)
+    assert info["position"] == 2
+    assert reward == 2 * 100.75 - 200
+    assert env.book.current_time == 9
+
+    _, reward, terminated, _, info = env.step(("ask", 102, 2))
+    assert terminated
+    assert reward == 0.0
+    assert info["pnl"] == Decimal("1.5")
+    assert env.open_orders()[0].price == Decimal("102")
+
+
+def test_reset_replays_the_flow_and_actions_cancel() -> None:
+    env = MarketEnv(flow, "X", features=["spread", "mid"], max_steps=1)
+    env.reset()
+    observation, _, _, truncated, _ = env.step([("bid", 98, 1), ("bid", 97, 1)])
+    assert truncated
+    assert math.isnan(memoryview(observation).tolist()[0])
+    assert len(env.open_orders()) == 2
+    env.step(("cancel_all",))
+    assert env.open_orders() == []
+
+    env.reset()
+    assert env.book.bid_prices("X") == []
+
+
+def half_tick_book() -> Book:
+    book = Book()
+    book.register_instrument(Instrument("X", tick_size=0.5))
+    return book
+
+
+def test_refused_actions_are_reported_not_raised() -> None:
+    env = MarketEnv(
+        flow,
+        "X",
+        events_per_step=2,
+        max_position=5,
+        max_order_quantity=4,
+        price_band=0.1,
+        book_factory=half_tick_book,
+    )
+    env.reset()
+    env.step(None)
+    _, _, _, _, info = env.step(
+        [("buy", 1, 1), ("bid", 98, 5), ("bid", 80, 1), ("bid", 98.2, 1), ("bid", 98, 3)]
+    )
+    codes = [rejection.code for rejection in info["rejections"]]
+    assert codes == ["unknown_action", "risk_limit", "price_band", "bad_tick"]
+    (order,) = env.open_orders()
+    _, _, _, _, info = env.step([("bid", 97, 3), ("cancel", order), ("cancel", order)])
+    assert [r.code for r in info["rejections"]] == ["risk_limit", "unknown_order"]
+    assert info["rejections"][1].order_id == order.id
+
+    env = MarketEnv(flow, "X", on_reject="raise")
+    env.reset()
+    with pytest.raises(ValueError, match="Unknown action") as err:
+        env.step(("buy", 1, 1))
+    assert err.value.code == "unknown_action"
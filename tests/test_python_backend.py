@@ -284,6 +284,23 @@ def test_python_book_parquet_entry_points_use_reader_rows(monkeypatch: pytest.Mo
     assert len(loaded_book.order_map) == 2
 
 
+def test_python_book_parquet_on_error_policies(monkeypatch: pytest.MonkeyPatch) -> None:
+    rows = [
+        {"side": "ask", "symbol": "AAPL", "price": 10.0, "quantity": 5},
+        {"side": "buy", "symbol": "AAPL", "price": 10.0, "quantity": 3},
+    ]
+    monkeypatch.setattr(book_module, "_read_parquet_rows", lambda path: rows)
+
+    with pytest.raises(ValueError, match="Invalid side at row 1"):
+        Book().replay_parquet("events.parquet")
+    assert len(Book().replay_parquet("events.parquet", on_error="skip")) == 1
+    count, errors = Book().ingest_parquet("snapshot.parquet", on_error="collect")
+    assert count == 1
+    assert [row_idx for row_idx, _ in errors] == [1]
+    with pytest.raises(ValueError, match="Invalid on_error"):
+        Book().ingest_parquet("snapshot.parquet", on_error="ignore")
+
+
 def test_python_book_replay_parquet_rejects_non_blotter_match_result(
     monkeypatch: pytest.MonkeyPatch,
 ) -> None:
@@ -386,3 +403,34 @@ def test_order_from_parquet_row_rejects_bad_rows(row: dict[str, object], message
 def test_python_vwap_returns_none_for_empty_or_zero_quantity_levels() -> None:
     assert Book._compute_vwap([]) is None
     assert Book._compute_vwap([SnapshotLevel(Decimal("10"), 0)]) is None
+
+
+def test_python_trades_carry_standing_order_metadata() -> None:
+    book = Book()
+    book.match(ask("X", 10, 3))
+    book.match(ask("X", 11, 5))
+    trades = book.match(bid("X", 11, 6)).trades
+    assert [(t.symbol, t.standing_price, t.standing_remaining) for t in trades] == [
+        ("X", Decimal("10"), 0),
+        ("X", Decimal("11"), 2),
+    ]
+
+
+def test_python_blotter_reports_levels_walked_and_price_improvement() -> None:
+    book = Book()
+    book.match(ask("X", 10, 3))
+    book.match(ask("X", 11, 5))
+    blotter = book.match(bid("X", 12, 6))
+    assert blotter.levels_walked == 2
+    assert blotter.worst_fill_price == Decimal("11")
+    assert blotter.price_improvement == Decimal("1.50")
+
+
+def test_python_preview_leaves_book_untouched() -> None:
+    book = Book()
+    book.match(ask("X", 10, 3))
+    order = bid("X", 10, 5)
+    preview = book.preview(order)
+    assert [t.fill_quantity for t in preview.trades] == [3]
+    assert order.quantity == 5
+    assert book.ask_prices("X") == [Decimal("10")]
@@ -10,16 +10,26 @@ All tests are written to pass against both the Rust and Python backends.
 
 from __future__ import annotations
 
+import json
+import random
+import socket
+import struct
 import uuid
+from collections.abc import Iterator
 from decimal import Decimal
+from pathlib import Path
 
 import pytest
 
 import pyorderbook
 from pyorderbook import (
+    AuctionDesignation,
     Book,
+    Instrument,
+    Nbbo,
     Order,
     OrderQueue,
+    OrderServer,
     OrderStatus,
     PriceLevel,
     Side,
@@ -27,8 +37,10 @@ from pyorderbook import (
     SnapshotLevel,
     Trade,
     TradeBlotter,
+    TradingState,
     ask,
     bid,
+    nbbo,
 )
 
 # ── Backend detection ──────────────────────────────────────────────────────
@@ -1439,3 +1451,3940 @@ class TestBookGetters:
         assert "X" in lm
         assert Side.BID in lm["X"]
         assert Decimal("10") in lm["X"][Side.BID]
+
+
+# ── NBBO ──────────────────────────────────────────────────────────────────
+
+
+class TestNbbo:
+    def test_best_prices_across_venues(self) -> None:
+        a, b = Book(), Book()
+        a.match([bid("X", 10.0, 5), ask("X", 12.0, 5)])
+        b.match([bid("X", 10.5, 3), ask("X", 11.5, 4)])
+        quote = nbbo({"A": a, "B": b}, "X")
+        assert isinstance(quote, Nbbo)
+        assert quote.best_bid == Decimal("10.5")
+        assert quote.bid_venues == ["B"]
+        assert quote.best_ask == Decimal("11.5")
+        assert quote.ask_quantity == 4
+        assert quote.spread == Decimal("1")
+        assert not quote.locked
+        assert not quote.crossed
+
+    def test_ties_aggregate_quantity(self) -> None:
+        a, b = Book(), Book()
+        a.match(bid("X", 10.0, 5))
+        b.match(bid("X", 10.0, 3))
+        quote = nbbo({"A": a, "B": b}, "X")
+        assert quote.bid_quantity == 8
+        assert quote.bid_venues == ["A", "B"]
+        assert quote.best_ask is None
+        assert quote.spread is None
+
+    def test_locked_and_crossed(self) -> None:
+        a, b = Book(), Book()
+        a.match(bid("X", 10.0, 5))
+        b.match(ask("X", 10.0, 5))
+        assert nbbo({"A": a, "B": b}, "X").locked
+        b.match(ask("X", 9.5, 5))
+        assert nbbo({"A": a, "B": b}, "X").crossed
+
+    def test_unknown_symbol(self) -> None:
+        quote = nbbo({"A": Book()}, "NOPE")
+        assert quote.best_bid is None
+        assert quote.best_ask is None
+        assert quote.bid_venues == []
+
+
+# ── Instrument registry ───────────────────────────────────────────────────
+
+
+class TestInstrument:
+    def test_defaults(self) -> None:
+        inst = Instrument("AAPL")
+        assert inst.symbol == "AAPL"
+        assert inst.tick_size is None
+        assert inst.lot_size == 1
+        assert inst.currency == "USD"
+        assert inst.multiplier == Decimal("1")
+        assert inst.trading_hours == []
+
+    def test_invalid_reference_data_raises(self) -> None:
+        with pytest.raises(ValueError):
+            Instrument("X", tick_size=0)
+        with pytest.raises(ValueError):
+            Instrument("X", lot_size=0)
+        with pytest.raises(ValueError):
+            Instrument("X", trading_hours=[(100, 50)])
+
+    def test_registry_roundtrip(self) -> None:
+        book = Book()
+        assert book.instrument("ES") is None
+        book.register_instrument(Instrument("ES", tick_size="0.25", multiplier=50))
+        inst = book.instrument("ES")
+        assert inst is not None
+        assert inst.tick_size == Decimal("0.25")
+        assert set(book.instruments) == {"ES"}
+
+    def test_tick_and_lot_validation(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("ES", tick_size="0.25", lot_size=2))
+        book.match(bid("ES", 100.25, 2))
+        with pytest.raises(ValueError, match="tick size"):
+            book.match(bid("ES", 100.1, 2))
+        with pytest.raises(ValueError, match="lot size"):
+            book.enqueue_order(bid("ES", 100.0, 3))
+
+    def test_trading_hours_follow_the_scheduled_day(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("ES", trading_hours=[(100, 200)]))
+        book.advance_time(50_000)
+        # Without a day length the clock's unit is unknown: nothing is checked.
+        book.match(bid("ES", 10.0, 1))
+        # A millisecond clock: the hours open at 100_000 and close at 200_000.
+        book.set_session_schedule("ES", 0, 86_399_000, day_length=86_400_000)
+        book.advance_time(60_000)
+        with pytest.raises(ValueError, match="outside its trading hours at second 60"):
+            book.match(bid("ES", 10.0, 1))
+        book.advance_time(150_000)
+        book.match(bid("ES", 10.0, 1))
+        book.advance_time(86_400_000 + 250_000)
+        with pytest.raises(ValueError, match="outside its trading hours"):
+            book.match(bid("ES", 10.0, 1))
+        assert book.snapshot("ES").bids[0].quantity == 2
+
+    def test_multiplier_scales_blotter_and_snapshot_notional(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("ES", multiplier=50))
+        book.match(ask("ES", 10.0, 2))
+        blotter = book.match(bid("ES", 10.0, 1))
+        assert blotter.total_cost == 500.0
+        assert blotter.average_price == 10.0
+        snap = book.snapshot("ES")
+        assert snap is not None
+        assert snap.ask_notional == Decimal("500")
+        assert snap.bid_notional == Decimal("0")
+
+    def test_non_positive_prices_require_opt_in(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="allow_non_positive_prices"):
+            book.match(ask("SPR", -1.0, 1))
+        book.register_instrument(Instrument("SPR", allow_non_positive_prices=True))
+        book.match([ask("SPR", -1.0, 1), ask("SPR", 0.0, 1)])
+        blotter = book.match(bid("SPR", 0.0, 2))
+        assert [t.fill_price for t in blotter.trades] == [Decimal("-1"), Decimal("0")]
+        assert blotter.total_cost == -1.0
+
+    def test_negative_zero_price_normalized(self) -> None:
+        assert str(bid("X", -0.0, 1).price) == "0"
+
+
+# ── Short sales and locates ───────────────────────────────────────────────
+
+
+class TestShortSales:
+    def test_short_flag(self) -> None:
+        assert ask("X", 10.0, 1, short=True).short is True
+        assert ask("X", 10.0, 1).short is False
+        assert Order(Side.ASK, "X", 10.0, 1, short=True).short is True
+        assert Book().ask("X", 10.0, 1, short=True).short is True
+
+    def test_short_bid_raises(self) -> None:
+        with pytest.raises(ValueError):
+            Order(Side.BID, "X", 10.0, 1, short=True)
+
+    def test_shorts_unrestricted_without_locate_table(self) -> None:
+        book = Book()
+        book.match(ask("X", 10.0, 100, short=True))
+        assert book.available_locate("X") is None
+
+    def test_short_without_locate_rejected(self) -> None:
+        book = Book()
+        book.set_locate("X", 50)
+        with pytest.raises(ValueError, match="available to borrow"):
+            book.match(ask("X", 10.0, 100, short=True))
+        with pytest.raises(ValueError):
+            book.match(ask("Y", 10.0, 1, short=True))
+        assert len(book.order_map) == 0
+
+    def test_locate_consumed_and_restored_on_cancel(self) -> None:
+        book = Book()
+        book.set_locate("X", 100)
+        short = ask("X", 10.0, 80, short=True)
+        book.match(short)
+        assert book.available_locate("X") == 20
+        book.match(bid("X", 10.0, 30))
+        book.cancel(short)
+        assert book.available_locate("X") == 70
+
+    def test_clear_locates_disables_enforcement(self) -> None:
+        book = Book()
+        book.set_locate("X", 0)
+        book.clear_locates()
+        book.match(ask("X", 10.0, 5, short=True))
+        assert book.available_locate("X") is None
+
+
+# ── Simulated clock and GTD expiry ────────────────────────────────────────
+
+
+class TestSimulatedTime:
+    def test_clock_starts_unset(self) -> None:
+        assert Book().current_time is None
+
+    def test_gtd_order_expires_on_advance(self) -> None:
+        book = Book()
+        gtd = bid("X", 10.0, 5, expire_time=100)
+        gtc = bid("X", 9.0, 5)
+        book.match([gtd, gtc])
+        assert book.advance_time(99) == []
+        expired = book.advance_time(100)
+        assert [o.id for o in expired] == [gtd.id]
+        assert book.current_time == 100
+        assert book.get_order(gtd.id) is None
+        assert book.get_order(gtc.id) is not None
+
+    def test_expired_order_reports_remaining_quantity(self) -> None:
+        book = Book()
+        book.match(ask("X", 10.0, 5, expire_time=10))
+        book.match(bid("X", 10.0, 2))
+        (expired,) = book.advance_time(10)
+        assert expired.quantity == 3
+        assert expired.status == OrderStatus.PARTIAL_FILL
+
+    def test_filled_gtd_order_does_not_expire(self) -> None:
+        book = Book()
+        book.match(ask("X", 10.0, 5, expire_time=10))
+        book.match(bid("X", 10.0, 5))
+        assert book.advance_time(20) == []
+
+    def test_time_cannot_go_backwards(self) -> None:
+        book = Book()
+        book.advance_time(10)
+        with pytest.raises(ValueError):
+            book.advance_time(5)
+
+    def test_already_expired_order_rejected(self) -> None:
+        book = Book()
+        book.advance_time(10)
+        with pytest.raises(ValueError, match="expired"):
+            book.match(bid("X", 10.0, 1, expire_time=10))
+
+    def test_replay_parquet_advances_clock(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "timed.parquet"
+        parquet.write_table(
+            pa.table(
+                {
+                    "side": ["bid", "ask"],
+                    "symbol": ["X", "X"],
+                    "price": [10.0, 10.0],
+                    "quantity": [5, 5],
+                    "timestamp": [1, 50],
+                    "expire_time": [20, None],
+                }
+            ),
+            path,
+        )
+        book = Book()
+        blotters = book.replay_parquet(str(path))
+        # The bid expired at t=20, before the ask arrived at t=50.
+        assert blotters[1].trades == []
+        assert book.current_time == 50
+
+
+# ── Auction designations ──────────────────────────────────────────────────
+
+
+class TestAuctions:
+    def test_pre_open_orders_rest_without_matching(self) -> None:
+        book = Book()
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        book.match(ask("X", 9.0, 5))
+        blotter = book.match(bid("X", 10.0, 5))
+        assert blotter.trades == []
+        assert book.trading_state("X") == TradingState.PRE_OPEN
+
+    def test_opening_uncross_includes_on_open_orders(self) -> None:
+        book = Book()
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        moo = bid("X", 0, 10, auction=AuctionDesignation.MOO)
+        assert moo.auction == AuctionDesignation.MOO
+        book.match([moo, ask("X", 10.0, 4), ask("X", 10.5, 10)])
+        result = book.uncross("X", "open")
+        assert result.auction == "open"
+        assert result.price == Decimal("10.5")
+        assert result.volume == 10
+        assert result.imbalance == -4
+        assert all(t.fill_price == Decimal("10.5") for t in result.trades)
+        assert result.cancelled == []
+        assert book.trading_state("X") == TradingState.CONTINUOUS
+
+    def test_designated_orders_skip_continuous_matching(self) -> None:
+        book = Book()
+        book.match(ask("X", 10.0, 5))
+        loc = bid("X", 11.0, 5, auction=AuctionDesignation.LOC)
+        assert book.match(loc).trades == []
+        assert book.get_order(loc.id) is not None
+        result = book.uncross("X", "close", reference_price=10)
+        assert result.volume == 5
+        assert book.trading_state("X") == TradingState.CLOSED
+        with pytest.raises(ValueError, match="closed"):
+            book.match(bid("X", 10.0, 1))
+
+    def test_unexecuted_designated_orders_are_cancelled(self) -> None:
+        book = Book()
+        moc = ask("X", 0, 3, auction=AuctionDesignation.MOC)
+        book.match(moc)
+        result = book.uncross("X", "close")
+        assert result.price is None
+        assert [o.id for o in result.cancelled] == [moc.id]
+
+    def test_on_open_requires_pre_open(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="PRE_OPEN"):
+            book.match(bid("X", 10.0, 1, auction=AuctionDesignation.LOO))
+
+    def test_cutoff_rejects_late_designations(self) -> None:
+        book = Book()
+        book.set_auction_cutoff("X", "close", 100)
+        book.match(bid("X", 10.0, 1, auction=AuctionDesignation.LOC))
+        book.advance_time(100)
+        with pytest.raises(ValueError, match="cutoff"):
+            book.match(bid("X", 10.0, 1, auction=AuctionDesignation.LOC))
+        # Plain orders are unaffected by the cutoff.
+        book.match(bid("X", 10.0, 1))
+
+    def test_cancel_held_order(self) -> None:
+        book = Book()
+        moc = bid("X", 0, 1, auction=AuctionDesignation.MOC)
+        book.match(moc)
+        book.cancel(moc)
+        assert book.get_order(moc.id) is None
+
+
+# ── Stop orders and cascades ──────────────────────────────────────────────
+
+
+class TestStopCascade:
+    def test_stop_waits_for_trigger(self) -> None:
+        book = Book()
+        stop = ask("X", 95.0, 5, stop_price=96.0)
+        assert stop.stop_price == Decimal("96")
+        blotter = book.match(stop)
+        assert blotter.trades == []
+        assert len(blotter.cascade) == 0
+        assert book.get_order(stop.id) is not None
+        assert book.snapshot("X") is None
+
+    def test_cascade_tracks_generation_and_trigger(self) -> None:
+        book = Book()
+        book.match([bid("X", 99.0, 5), bid("X", 98.0, 5), bid("X", 97.0, 5)])
+        first = ask("X", 98.0, 5, stop_price=99.0)
+        second = ask("X", 97.0, 5, stop_price=98.0)
+        book.match([first, second])
+
+        blotter = book.match(ask("X", 99.0, 5))
+        report = blotter.cascade
+        assert report.depth == 2
+        gen1, gen2 = report.activations
+        assert gen1.order.id == first.id
+        assert gen1.generation == 1
+        assert gen1.trigger_trade_id == blotter.trades[0].trade_id
+        assert gen1.trigger_price == Decimal("99")
+        assert gen2.order.id == second.id
+        assert gen2.trigger_trade_id == gen1.trades[0].trade_id
+        assert [t.fill_price for t in report.trades] == [Decimal("98"), Decimal("97")]
+
+    def test_unfilled_stop_remainder_rests(self) -> None:
+        book = Book()
+        book.match(bid("X", 10.0, 2))
+        stop = bid("X", 11.0, 4, stop_price=10.0)
+        book.match(stop)
+        book.match(ask("X", 10.0, 2))
+        resting = book.get_order(stop.id)
+        assert resting is not None
+        assert resting.quantity == 4
+        assert book.snapshot("X").bids[0].price == Decimal("11")
+
+    def test_cancel_untriggered_stop(self) -> None:
+        book = Book()
+        stop = bid("X", 11.0, 4, stop_price=10.0)
+        book.match(stop)
+        book.cancel(stop)
+        assert book.get_order(stop.id) is None
+
+
+# ── L2 aggregated books ───────────────────────────────────────────────────
+
+
+class TestL2Updates:
+    def test_apply_l2_update_sets_level_quantity(self) -> None:
+        book = Book()
+        book.apply_l2_update("X", Side.ASK, Decimal("101.5"), 30)
+        book.apply_l2_update("X", Side.ASK, Decimal("101.5"), 12)
+        book.apply_l2_update("X", Side.BID, 100, 7)
+        snap = book.snapshot("X")
+        assert [(lvl.price, lvl.quantity) for lvl in snap.asks] == [(Decimal("101.5"), 12)]
+        assert snap.bids[0].quantity == 7
+        assert len(book.order_map) == 2
+
+    def test_zero_quantity_deletes_level(self) -> None:
+        book = Book()
+        book.apply_l2_update("X", Side.BID, 100, 7)
+        book.apply_l2_update("X", Side.BID, 100, 0)
+        book.apply_l2_update("X", Side.BID, 99, 0)
+        assert book.snapshot("X").bids == []
+
+    def test_negative_quantity_rejected(self) -> None:
+        with pytest.raises(ValueError):
+            Book().apply_l2_update("X", Side.BID, 100, -1)
+
+    def test_ingest_aggregated_snapshot(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "l2.parquet"
+        parquet.write_table(
+            pa.table(
+                {
+                    "side": ["bid", "bid", "ask"],
+                    "symbol": ["X", "X", "X"],
+                    "price": [100.0, 100.0, 101.0],
+                    "quantity": [5, 8, 3],
+                }
+            ),
+            path,
+        )
+        book = Book.from_parquet(str(path), aggregated=True)
+        snap = book.snapshot("X")
+        # Later rows for the same level replace earlier ones.
+        assert [(lvl.price, lvl.quantity) for lvl in snap.bids] == [(Decimal("100"), 8)]
+        assert snap.asks[0].quantity == 3
+        assert len(book.order_map) == 2
+
+
+# ── Market-data feed updates ──────────────────────────────────────────────
+
+
+class TestFeedUpdates:
+    @staticmethod
+    def level(kind: str, side: object, price: object, quantity: int = 0) -> dict[str, object]:
+        return {"type": kind, "symbol": "BTC", "side": side, "price": price, "quantity": quantity}
+
+    @staticmethod
+    def order(kind: str, order_id: object, quantity: int | None = None) -> dict[str, object]:
+        msg: dict[str, object] = {"type": kind, "order_id": order_id}
+        if kind == "add_order":
+            msg.update(symbol="ETH", side="ask", price=3000)
+        if quantity is not None:
+            msg["quantity"] = quantity
+        return msg
+
+    def test_level_messages(self) -> None:
+        book = Book()
+        book.apply_update(
+            [
+                self.level("add_level", "bid", "64000", 2),
+                self.level("add_level", "sell", "64001.5", 1),
+                self.level("change_level", Side.BID, 64000, 5),
+            ]
+        )
+        snap = book.snapshot("BTC")
+        assert snap.bids[0].quantity == 5
+        assert snap.asks[0].price == Decimal("64001.5")
+        book.apply_update(self.level("delete_level", "ask", "64001.5"))
+        assert book.snapshot("BTC").asks == []
+
+    def test_order_messages(self) -> None:
+        book = Book()
+        book.apply_update(self.order("add_order", 17, 4))
+        book.apply_update(self.order("add_order", 18, 1))
+        book.apply_update(self.order("execute_order", 17, 3))
+        assert book.snapshot("ETH").asks[0].quantity == 2
+        book.apply_update(self.order("cancel_order", "17"))
+        assert book.snapshot("ETH").asks[0].quantity == 1
+
+    def test_unknown_order_raises_key_error(self) -> None:
+        with pytest.raises(KeyError):
+            Book().apply_update(self.order("cancel_order", "nope"))
+
+    def test_invalid_messages_rejected(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="Unknown feed update"):
+            book.apply_update({"type": "trade"})
+        with pytest.raises(ValueError, match="missing"):
+            book.apply_update({"type": "add_level", "symbol": "X", "side": "bid"})
+        with pytest.raises(TypeError):
+            book.apply_update("add_level")
+
+
+# ── Sequenced feeds ───────────────────────────────────────────────────────
+
+
+def _seq_level(seq: int, price: int, quantity: int) -> dict[str, object]:
+    return {
+        "type": "change_level",
+        "sequence": seq,
+        "symbol": "BTC",
+        "side": "bid",
+        "price": price,
+        "quantity": quantity,
+    }
+
+
+class TestFeedSequencing:
+    def test_in_order_messages_apply(self) -> None:
+        book = Book()
+        book.apply_update([_seq_level(1, 100, 5), _seq_level(2, 101, 3)])
+        assert book.feed_sequence("BTC") == 2
+        assert book.feed_gap("BTC") is None
+        assert len(book.snapshot("BTC").bids) == 2
+
+    def test_gap_buffers_until_filled(self) -> None:
+        book = Book()
+        book.apply_update(_seq_level(1, 100, 5))
+        book.apply_update(_seq_level(3, 102, 1))
+        assert book.feed_gap("BTC") == (2, 3)
+        assert len(book.snapshot("BTC").bids) == 1
+        book.apply_update(_seq_level(2, 101, 1))
+        assert book.feed_gap("BTC") is None
+        assert book.feed_sequence("BTC") == 3
+        assert len(book.snapshot("BTC").bids) == 3
+
+    def test_duplicates_are_dropped(self) -> None:
+        book = Book()
+        book.apply_update([_seq_level(1, 100, 5), _seq_level(1, 100, 9)])
+        assert book.snapshot("BTC").bids[0].quantity == 5
+
+    def test_strict_mode_raises_on_gap(self) -> None:
+        book = Book()
+        book.apply_update(_seq_level(1, 100, 5))
+        with pytest.raises(ValueError, match="gap"):
+            book.apply_update(_seq_level(4, 100, 1), strict=True)
+
+    def test_resync_replaces_state_and_replays_buffer(self) -> None:
+        book = Book()
+        book.apply_update(_seq_level(1, 100, 5))
+        book.match(bid("BTC", 95.0, 1))
+        book.apply_update([_seq_level(5, 100, 0), _seq_level(6, 99, 4)])
+        book.resync_from_snapshot("BTC", {"bids": [[100, 7], [98, 2]], "asks": [["105", 1]]}, 4)
+        snap = book.snapshot("BTC")
+        assert [(lvl.price, lvl.quantity) for lvl in snap.bids] == [
+            (Decimal("99"), 4),
+            (Decimal("98"), 2),
+        ]
+        assert snap.asks[0].price == Decimal("105")
+        assert book.feed_sequence("BTC") == 6
+        assert book.feed_gap("BTC") is None
+
+    def test_resync_from_snapshot_object(self) -> None:
+        source = Book()
+        source.apply_l2_update("BTC", Side.ASK, 101, 3)
+        book = Book()
+        book.resync_from_snapshot("BTC", source.snapshot("BTC"), 10)
+        assert book.snapshot("BTC").asks[0].quantity == 3
+        assert book.feed_sequence("BTC") == 10
+
+    def test_sequenced_message_requires_symbol(self) -> None:
+        msg = {"type": "cancel_order", "order_id": 1, "sequence": 1}
+        with pytest.raises(ValueError, match="symbol"):
+            Book().apply_update(msg)
+
+
+# ── Trade bus publisher ───────────────────────────────────────────────────
+
+
+class TestPublisher:
+    def test_trades_and_levels_are_published(self) -> None:
+        batches: list[list[dict[str, object]]] = []
+        book = Book()
+        book.set_publisher(batches.append)
+        book.match(ask("X", 10.0, 5))
+        blotter = book.match(bid("X", 10.0, 2))
+        events = [e for batch in batches for e in batch]
+        assert events[0] == {
+            "type": "level",
+            "symbol": "X",
+            "side": "ask",
+            "price": Decimal("10"),
+            "quantity": 5,
+        }
+        trade = events[1]
+        assert trade["type"] == "trade"
+        assert trade["trade_id"] == blotter.trades[0].trade_id
+        assert trade["quantity"] == 2
+        assert events[2]["type"] == "level"
+        assert events[2]["quantity"] == 3
+
+    def test_batching_and_flush(self) -> None:
+        batches: list[list[dict[str, object]]] = []
+        book = Book()
+        book.set_publisher(batches.append, batch_size=3)
+        book.match([ask("X", 10.0, 1), ask("X", 11.0, 1)])
+        assert batches == []
+        assert book.publisher_stats() == {"pending": 2, "published": 0, "dropped": 0}
+        book.flush_publisher()
+        assert [len(b) for b in batches] == [2]
+
+    def test_cancel_publishes_removed_level(self) -> None:
+        batches: list[list[dict[str, object]]] = []
+        book = Book()
+        order = bid("X", 10.0, 1)
+        book.match(order)
+        book.set_publisher(batches.append)
+        book.cancel(order)
+        assert batches[-1][0]["quantity"] == 0
+
+    def test_json_encoding(self) -> None:
+        import json
+
+        payloads: list[str] = []
+        book = Book()
+        book.set_publisher(payloads.append, encoding="json")
+        book.apply_l2_update("X", Side.BID, Decimal("99.5"), 4)
+        assert json.loads(payloads[0]) == [
+            {"type": "level", "symbol": "X", "side": "bid", "price": "99.5", "quantity": 4}
+        ]
+
+    def test_failing_callback_keeps_events_and_drops_oldest(self) -> None:
+        def broken(batch: object) -> None:
+            raise RuntimeError("consumer down")
+
+        book = Book()
+        book.set_publisher(broken, capacity=2)
+        for price in (10.0, 11.0, 12.0):
+            with pytest.raises(RuntimeError):
+                book.match(ask("X", price, 1))
+        assert book.publisher_stats() == {"pending": 2, "published": 0, "dropped": 1}
+        book.set_publisher(None)
+        assert book.publisher_stats() is None
+
+
+# ── Amending orders ───────────────────────────────────────────────────────
+
+
+class TestAmend:
+    def test_reducing_quantity_keeps_priority(self) -> None:
+        book = Book()
+        first, second = ask("X", 10.0, 5), ask("X", 10.0, 5)
+        book.match([first, second])
+        amended = book.amend(first, quantity=2)
+        assert amended.trades == []
+        assert amended.order.quantity == 2
+        blotter = book.match(bid("X", 10.0, 3))
+        assert [t.standing_order_id for t in blotter.trades] == [first.id, second.id]
+
+    def test_price_change_requeues_and_can_trade(self) -> None:
+        book = Book()
+        resting = bid("X", 9.0, 4)
+        book.match([resting, ask("X", 10.0, 3)])
+        blotter = book.amend(resting, price=10.0)
+        assert blotter.trades[0].fill_quantity == 3
+        assert book.get_order(resting.id).quantity == 1
+
+    def test_unknown_and_invalid_amends(self) -> None:
+        book = Book()
+        order = bid("X", 9.0, 4)
+        with pytest.raises(KeyError):
+            book.amend(order, quantity=1)
+        book.match(order)
+        with pytest.raises(ValueError):
+            book.amend(order, quantity=0)
+        assert book.get_order(order.id).quantity == 4
+
+
+# ── Order-entry server ────────────────────────────────────────────────────
+
+
+def _send(sock: socket.socket, msg: dict[str, object]) -> None:
+    payload = json.dumps(msg).encode()
+    sock.sendall(struct.pack(">I", len(payload)) + payload)
+
+
+def _recv(sock: socket.socket) -> dict[str, object]:
+    def exactly(n: int) -> bytes:
+        data = b""
+        while len(data) < n:
+            chunk = sock.recv(n - len(data))
+            assert chunk, "server closed the connection"
+            data += chunk
+        return data
+
+    (length,) = struct.unpack(">I", exactly(4))
+    return json.loads(exactly(length))
+
+
+def _connect(server: OrderServer) -> socket.socket:
+    host, port = server.address.rsplit(":", 1)
+    return socket.create_connection((host, int(port)), timeout=5)
+
+
+def _new(side: str, price: str, quantity: int, cid: int) -> dict[str, object]:
+    return {
+        "type": "new",
+        "client_order_id": cid,
+        "side": side,
+        "symbol": "X",
+        "price": price,
+        "quantity": quantity,
+    }
+
+
+class TestOrderServer:
+    def test_executions_reach_both_sessions(self) -> None:
+        with OrderServer() as server, _connect(server) as maker, _connect(server) as taker:
+            _send(maker, _new("sell", "10.5", 5, 1))
+            resting = _recv(maker)
+            assert resting["type"] == "ack" and resting["client_order_id"] == 1
+
+            _send(taker, _new("buy", "11", 3, 2))
+            assert _recv(taker)["type"] == "ack"
+            taker_fill, maker_fill = _recv(taker), _recv(maker)
+            assert taker_fill["type"] == maker_fill["type"] == "execution"
+            assert taker_fill["trade_id"] == maker_fill["trade_id"]
+            assert maker_fill["order_id"] == resting["order_id"]
+            assert (maker_fill["price"], maker_fill["quantity"]) == ("10.5", 3)
+
+            snapshot = server.snapshot("X")
+            assert [(lvl.price, lvl.quantity) for lvl in snapshot.asks] == [(Decimal("10.5"), 2)]
+
+    def test_amend_cancel_and_rejects(self) -> None:
+        with OrderServer() as server, _connect(server) as client, _connect(server) as other:
+            _send(client, _new("buy", "9", 4, 1))
+            order_id = _recv(client)["order_id"]
+
+            _send(other, {"type": "cancel", "order_id": order_id})
+            assert _recv(other)["reason"] == "Unknown order"
+
+            _send(client, {"type": "amend", "order_id": order_id, "quantity": 1})
+            assert _recv(client) == {"type": "amended", "order_id": order_id}
+            assert server.snapshot("X").bids[0].quantity == 1
+
+            _send(client, {"type": "cancel", "order_id": order_id})
+            assert _recv(client) == {"type": "canceled", "order_id": order_id}
+
+            _send(client, _new("buy", "9", 0, 2))
+            rejected = _recv(client)
+            assert rejected["type"] == "reject" and rejected["client_order_id"] == 2
+
+            _send(client, {"type": "bogus"})
+            assert _recv(client)["type"] == "reject"
+
+    def test_stop_closes_connections(self) -> None:
+        server = OrderServer()
+        client = _connect(server)
+        _send(client, _new("buy", "9", 1, 1))
+        assert _recv(client)["type"] == "ack"
+        server.stop()
+        assert not server.running
+        assert client.recv(1) == b""
+        client.close()
+
+
+# ── Invariant checker ─────────────────────────────────────────────────────
+
+
+class TestCheckInvariants:
+    def test_random_operations_preserve_invariants(self) -> None:
+        rng = random.Random(7)
+        book = Book()
+        resting: list[Order] = []
+        for _ in range(500):
+            roll = rng.random()
+            if roll < 0.15 and resting:
+                order = resting.pop(rng.randrange(len(resting)))
+                if book.get_order(order.id) is not None:
+                    book.cancel(order)
+            elif roll < 0.3 and resting:
+                order = rng.choice(resting)
+                if book.get_order(order.id) is not None:
+                    book.amend(order, price=float(rng.randint(95, 105)))
+            else:
+                make = bid if rng.random() < 0.5 else ask
+                order = make("X", float(rng.randint(95, 105)), rng.randint(1, 20))
+                blotter = book.match(order)
+                if blotter.order.quantity:
+                    resting.append(order)
+            book.check_invariants()
+
+    def test_auction_and_stop_flows_preserve_invariants(self) -> None:
+        book = Book()
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        book.match([bid("X", 101.0, 5), ask("X", 99.0, 3), ask("X", 102.0, 4)])
+        book.check_invariants()  # crossed is fine before the open
+        book.uncross("X", "open")
+        book.match(bid("X", 1.0, 2, stop_price=102.0))
+        book.match(bid("X", 102.0, 1))
+        book.check_invariants()
+
+
+# ── State hashing ─────────────────────────────────────────────────────────
+
+
+def _golden_book() -> Book:
+    book = Book()
+    book.match([bid("X", 10.0, 5), bid("X", 10.0, 3), ask("X", 11.5, 2), ask("Y", 1.25, 7)])
+    book.match(ask("X", 10.0, 6))
+    return book
+
+
+class TestStateHash:
+    def test_replay_reproduces_golden_hash(self) -> None:
+        # Pinned values: a change here means replays no longer reproduce
+        # across releases.
+        assert _golden_book().state_hash() == 0x0CE173FC9A7CF1FD
+        assert _golden_book().state_hash(bits=128) == 0x2186E98CEBCC8CAFF287F41DCF2CB6A5
+
+    def test_hash_tracks_queue_order_and_quantities(self) -> None:
+        book = _golden_book()
+        before = book.state_hash()
+        first, second = bid("Z", 5.0, 1), bid("Z", 5.0, 2)
+        book.match([first, second])
+        other = _golden_book()
+        other.match([bid("Z", 5.0, 2), bid("Z", 5.0, 1)])
+        assert book.state_hash() != other.state_hash()
+
+        book.cancel(first)
+        book.cancel(second)
+        assert book.state_hash() == before
+
+    def test_rejects_unsupported_width(self) -> None:
+        with pytest.raises(ValueError):
+            Book().state_hash(bits=32)
+
+
+# ── Strategy replay ───────────────────────────────────────────────────────
+
+
+class _QuoteOnce:
+    """Lifts the first ask it sees with a bid at the same price."""
+
+    def __init__(self) -> None:
+        self.events: list[tuple[int | None, TradeBlotter]] = []
+
+    def on_event(self, book: Book, event: TradeBlotter) -> object:
+        self.events.append((book.current_time, event))
+        if len(self.events) == 1:
+            return bid("X", float(event.order.price), 2)
+        return None
+
+
+def _rows(*times: int) -> list[dict[str, object]]:
+    return [
+        {"side": "ask", "symbol": "X", "price": 10.0, "quantity": 5, "timestamp": t}
+        for t in times
+    ]
+
+
+class TestReplayWithStrategy:
+    def test_strategy_orders_arrive_after_latency(self) -> None:
+        strategy = _QuoteOnce()
+        book = Book()
+        blotters = book.replay_with_strategy(_rows(1, 3, 8), strategy, latency=5)
+        assert [t for t, _ in strategy.events] == [1, 3, 8]
+        # The bid reached the book at t=6, between the second and third rows.
+        assert len(blotters) == 4
+        assert blotters[2].order.side == Side.BID
+        assert sum(t.fill_quantity for t in blotters[2].trades) == 2
+        assert book.current_time == 8
+
+    def test_in_flight_actions_apply_after_the_stream(self) -> None:
+        book = Book()
+        blotters = book.replay_with_strategy(_rows(1), _QuoteOnce(), latency=100)
+        assert blotters[-1].order.side == Side.BID
+        assert book.current_time == 101
+
+    def test_cancels_and_parquet_sources(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "events.parquet"
+        parquet.write_table(
+            pa.table({"side": ["ask"], "symbol": ["X"], "price": [10.0], "quantity": [5]}),
+            path,
+        )
+
+        class CancelOwnAsk:
+            def on_event(self, book: Book, event: TradeBlotter) -> object:
+                return [("cancel", event.order), ("cancel", uuid.uuid4())]
+
+        book = Book()
+        book.replay_with_strategy(path, CancelOwnAsk())
+        assert book.snapshot("X").asks == []
+
+    def test_rejects_bad_actions_and_latency(self) -> None:
+        class Bad:
+            def on_event(self, book: Book, event: TradeBlotter) -> object:
+                return "buy everything"
+
+        with pytest.raises(TypeError, match="Strategy actions"):
+            Book().replay_with_strategy(_rows(1), Bad())
+        with pytest.raises(ValueError, match="latency"):
+            Book().replay_with_strategy(_rows(1), _QuoteOnce(), latency=-1)
+
+
+# ── Partial cancels ───────────────────────────────────────────────────────
+
+
+class TestReduce:
+    def test_reduce_keeps_priority_and_ledger(self) -> None:
+        book = Book()
+        first, second = ask("X", 10.0, 5), ask("X", 10.0, 5)
+        book.match([first, second, bid("X", 10.0, 1)])
+        assert book.reduce(first.id, 2) == 2
+        book.check_invariants()
+        blotter = book.match(bid("X", 10.0, 3))
+        assert [t.standing_order_id for t in blotter.trades] == [first.id, second.id]
+        assert [t.fill_quantity for t in blotter.trades] == [2, 1]
+
+    def test_reducing_everything_cancels(self) -> None:
+        book = Book()
+        order = bid("X", 9.0, 4)
+        book.match(order)
+        assert book.reduce(order, 4) == 0
+        assert book.get_order(order.id) is None
+        with pytest.raises(KeyError):
+            book.reduce(order, 1)
+
+    def test_rejects_invalid_deltas(self) -> None:
+        book = Book()
+        order = bid("X", 9.0, 4)
+        book.match(order)
+        for delta in (0, -1, 5):
+            with pytest.raises(ValueError):
+                book.reduce(order.id, delta)
+        assert book.get_order(order.id).quantity == 4
+
+
+# ── Order history ─────────────────────────────────────────────────────────
+
+
+class TestOrderHistory:
+    def test_records_lifecycle_with_clock(self) -> None:
+        book = Book()
+        book.record_order_history()
+        book.advance_time(1)
+        resting = ask("X", 10.0, 10)
+        book.match(resting)
+        book.advance_time(2)
+        taker = bid("X", 10.0, 4)
+        fill = book.match(taker).trades[0]
+        book.amend(resting, quantity=5)
+        book.advance_time(3)
+        book.cancel(resting)
+
+        events = book.order_history(resting.id)
+        assert [e.kind for e in events] == ["accepted", "partially_filled", "amended", "cancelled"]
+        assert [e.timestamp for e in events] == [1, 2, 2, 3]
+        assert (events[1].quantity, events[1].price, events[1].trade_id) == (
+            4,
+            Decimal("10"),
+            fill.trade_id,
+        )
+        assert (events[3].quantity, events[3].remaining) == (5, 0)
+        assert [e.kind for e in book.order_history(taker)] == ["accepted", "filled"]
+
+    def test_expiry_and_replace_amends(self) -> None:
+        book = Book()
+        book.record_order_history()
+        book.advance_time(0)
+        order = bid("X", 9.0, 4, expire_time=10)
+        book.match(order)
+        book.amend(order, price=9.5)
+        book.advance_time(10)
+        kinds = [e.kind for e in book.order_history(order)]
+        assert kinds == ["accepted", "amended", "expired"]
+
+    def test_recording_is_opt_in(self) -> None:
+        book = Book()
+        before = bid("X", 9.0, 4)
+        book.match(before)
+        with pytest.raises(ValueError, match="record_order_history"):
+            book.order_history(before)
+        book.record_order_history()
+        assert book.order_history(before) is None
+        book.record_order_history(False)
+        with pytest.raises(ValueError):
+            book.order_history(before)
+
+
+# ── Session statistics ────────────────────────────────────────────────────
+
+
+class TestSessionStats:
+    def test_tracks_ohlc_volume_and_notional(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("X", multiplier=10))
+        book.match([ask("X", 10.0, 5), ask("X", 11.0, 5)])
+        book.match(bid("X", 11.0, 7))
+        book.match(bid("Y", 1.0, 1))
+        book.match(ask("X", 9.0, 1))
+        stats = book.session_stats("X")
+        assert (stats.open, stats.high, stats.low, stats.last_price) == (
+            Decimal("10"),
+            Decimal("11"),
+            Decimal("10"),
+            Decimal("11"),
+        )
+        assert (stats.volume, stats.trade_count) == (7, 2)
+        assert stats.notional == Decimal("720")
+        assert round(stats.vwap, 6) == Decimal("10.285714")
+        assert book.session_stats("Y") is None
+
+    def test_roll_session_returns_closing_stats(self) -> None:
+        book = Book()
+        book.match([ask("X", 10.0, 5), bid("X", 10.0, 2)])
+        closed = book.roll_session()
+        assert closed["X"].volume == 2
+        assert book.session_stats("X") is None
+        book.match(bid("X", 10.0, 1))
+        assert book.session_stats("X").open == Decimal("10")
+
+
+# ── Precision policy ──────────────────────────────────────────────────────
+
+
+class TestPrecision:
+    def test_default_rounds_blotter_stats_to_cents(self) -> None:
+        book = Book()
+        assert book.precision() is None
+        book.match(ask("BTC", 64000.123456, 1))
+        blotter = book.match(bid("BTC", 64001.0, 1))
+        assert blotter.average_price == 64000.12
+
+    def test_symbol_precision_validates_and_scales(self) -> None:
+        book = Book()
+        book.set_precision(2)
+        book.set_precision(8, 8, symbol="BTC")
+        assert book.precision("BTC") == (8, 8)
+        assert book.precision("ETH") == (2, 0)
+        with pytest.raises(ValueError, match="decimal places"):
+            book.match(ask("ETH", 3000.001, 1))
+        with pytest.raises(ValueError, match="decimal places"):
+            book.match(ask("BTC", 64000.123456789, 1))
+
+        # Quantities are satoshis: 150_000_000 units are 1.5 BTC.
+        book.match(ask("BTC", 64000.12345678, 150_000_000))
+        blotter = book.match(bid("BTC", 64001.0, 150_000_000))
+        assert blotter.average_price == 64000.12345678
+        assert blotter.total_cost == pytest.approx(96000.18518517)
+        stats = book.session_stats("BTC")
+        assert stats.notional == Decimal("96000.185185170")
+        assert stats.vwap == Decimal("64000.12345678")
+
+    def test_rejects_excessive_precision(self) -> None:
+        with pytest.raises(ValueError):
+            Book().set_precision(19)
+
+
+# ── Fractional quantities ─────────────────────────────────────────────────
+
+
+class TestFractionalQuantities:
+    def test_book_orders_take_asset_amounts(self) -> None:
+        book = Book()
+        book.set_precision(2, 3, symbol="BTC")
+        book.set_fractional_quantities()
+        resting = book.ask("BTC", 64000.0, "0.005")
+        assert resting.quantity == 5
+        book.match(resting)
+        blotter = book.match(book.bid("BTC", 64000.0, 0.002))
+        assert blotter.trades[0].fill_quantity == 2
+        assert book.to_quantity("BTC", blotter.trades[0].fill_quantity) == Decimal("0.002")
+        level = book.snapshot("BTC").asks[0]
+        assert (level.quantity, level.size) == (3, Decimal("0.003"))
+        with pytest.raises(ValueError, match="whole multiple"):
+            book.bid("BTC", 64000.0, 0.0001)
+
+    def test_rows_are_converted(self) -> None:
+        book = Book()
+        book.set_precision(2, 8, symbol="BTC")
+        book.set_fractional_quantities()
+        rows = [
+            {"side": "ask", "symbol": "BTC", "price": 10.0, "quantity": 0.5},
+            {"side": "bid", "symbol": "BTC", "price": 10.0, "quantity": "0.25"},
+        ]
+
+        class Idle:
+            def on_event(self, book: Book, event: TradeBlotter) -> None:
+                return None
+
+        blotters = book.replay_with_strategy(rows, Idle())
+        assert blotters[1].trades[0].fill_quantity == 25_000_000
+        bad = [{"side": "bid", "symbol": "BTC", "price": 10.0, "quantity": 1e-9}]
+        with pytest.raises(ValueError, match="Invalid quantity at row 0"):
+            book.replay_with_strategy(bad, Idle())
+
+    def test_unit_mode_requires_whole_quantities(self) -> None:
+        book = Book()
+        assert book.bid("X", 1.0, 3).quantity == 3
+        with pytest.raises(ValueError):
+            book.bid("X", 1.0, 1.5)
+
+
+# ── Matching iterables ────────────────────────────────────────────────────
+
+
+class TestMatchIterables:
+    def test_tuples_and_generators_return_lists(self) -> None:
+        book = Book()
+        assert len(book.match((ask("X", 10.0, 1), ask("X", 10.0, 1)))) == 2
+        blotters = book.match(bid("X", 10.0, 1) for _ in range(2))
+        assert [len(b.trades) for b in blotters] == [1, 1]
+
+    def test_lazy_matching_streams_results(self) -> None:
+        book = Book()
+        consumed: list[int] = []
+
+        def orders() -> Iterator[Order]:
+            for i in range(3):
+                consumed.append(i)
+                yield ask("X", 10.0 + i, 1)
+
+        results = book.match(orders(), lazy=True)
+        assert consumed == []
+        first = next(results)
+        assert consumed == [0] and first.order.price == Decimal("10")
+        assert len(list(results)) == 2
+        assert len(book.snapshot("X").asks) == 3
+
+    def test_rejects_non_iterables(self) -> None:
+        with pytest.raises(TypeError, match="iterable of Orders"):
+            Book().match(42)
+
+
+# ── Skipping empty blotters ───────────────────────────────────────────────
+
+
+class TestSkipEmpty:
+    def test_match_lists_and_lazy_iterators(self) -> None:
+        book = Book()
+        orders = [ask("X", 10.0, 1), ask("X", 11.0, 1), bid("X", 10.0, 1), bid("X", 9.0, 1)]
+        blotters = book.match(orders, skip_empty=True)
+        assert [b.order.id for b in blotters] == [orders[2].id]
+        lazy = book.match(iter([bid("X", 9.0, 1), bid("X", 11.0, 1)]), lazy=True, skip_empty=True)
+        assert [len(b.trades) for b in lazy] == [1]
+
+    def test_replays_keep_only_trading_rows(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "seed.parquet"
+        parquet.write_table(
+            pa.table(
+                {
+                    "side": ["ask", "ask", "bid"],
+                    "symbol": ["X"] * 3,
+                    "price": [10.0, 11.0, 10.0],
+                    "quantity": [1, 1, 1],
+                }
+            ),
+            path,
+        )
+        assert len(Book().replay_parquet(str(path), skip_empty=True)) == 1
+
+        seen: list[TradeBlotter] = []
+
+        class Recorder:
+            def on_event(self, book: Book, event: TradeBlotter) -> None:
+                seen.append(event)
+
+        blotters = Book().replay_with_strategy(path, Recorder(), skip_empty=True)
+        assert len(blotters) == 1 and len(seen) == 3
+
+
+# ── Batch enqueue ─────────────────────────────────────────────────────────
+
+
+class TestEnqueueOrders:
+    def test_orders_rest_in_arrival_order_per_level(self) -> None:
+        book = Book()
+        book.enqueue_order(ask("X", 11.0, 1))
+        orders = [ask("X", 12.0, 2), ask("X", 10.0, 3), ask("X", 10.0, 4), bid("X", 9.0, 5)]
+        assert book.enqueue_orders(orders) == 4
+        snap = book.snapshot("X")
+        assert [(lvl.price, lvl.quantity) for lvl in snap.asks] == [
+            (Decimal("10"), 7),
+            (Decimal("11"), 1),
+            (Decimal("12"), 2),
+        ]
+        assert [(lvl.price, lvl.quantity) for lvl in snap.bids] == [(Decimal("9"), 5)]
+        trades = book.match(bid("X", 10.0, 5)).trades
+        assert [t.standing_order_id for t in trades] == [orders[1].id, orders[2].id]
+
+    def test_accepts_columns(self) -> None:
+        book = Book()
+        columns = {
+            "side": ["bid", "ask"],
+            "symbol": ["X", "X"],
+            "price": [9.5, 10.5],
+            "quantity": [3, 4],
+        }
+        assert book.enqueue_orders(columns) == 2
+        snap = book.snapshot("X")
+        assert (snap.bids[0].quantity, snap.asks[0].quantity) == (3, 4)
+        with pytest.raises(ValueError, match="same length"):
+            book.enqueue_orders({**columns, "quantity": [1]})
+
+    def test_rejection_keeps_earlier_orders(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("X", tick_size="0.5"))
+        with pytest.raises(ValueError):
+            book.enqueue_orders([bid("X", 9.5, 1), bid("X", 9.7, 1), bid("X", 9.0, 1)])
+        assert [lvl.price for lvl in book.snapshot("X").bids] == [Decimal("9.5")]
+
+
+# ── Live order handles ────────────────────────────────────────────────────
+
+
+class TestOrderHandles:
+    def test_handle_tracks_fills_and_snapshot_freezes(self) -> None:
+        book = Book()
+        resting = ask("X", 10.0, 10)
+        book.match(resting)
+        handle = book.get_order(resting.id)
+        assert isinstance(handle, Order)
+        frozen = handle.snapshot()
+        book.match(bid("X", 10.0, 4))
+        assert (handle.quantity, handle.status) == (6, OrderStatus.PARTIAL_FILL)
+        assert book.order_map[resting.id].get_quantity() == 6
+        assert (frozen.quantity, frozen.status) == (10, OrderStatus.QUEUED)
+        book.match(bid("X", 10.0, 6))
+        assert handle.quantity == 0 and not handle.resting
+        assert handle.id == resting.id
+
+    def test_handle_follows_amend_and_cancel(self) -> None:
+        book = Book()
+        resting = bid("X", 10.0, 5)
+        book.enqueue_order(resting)
+        handle = book.get_order(resting.id)
+        book.amend(resting, quantity=3)
+        assert handle.quantity == 3 and handle.resting
+        book.cancel(handle)
+        assert not handle.resting
+        assert book.get_order(resting.id) is None
+
+
+# ── Fill accounting ───────────────────────────────────────────────────────
+
+
+class TestFillAccounting:
+    def test_incoming_and_resting_orders_accumulate_fills(self) -> None:
+        book = Book()
+        first, second = ask("X", 10.0, 3), ask("X", 11.0, 3)
+        book.enqueue_orders([first, second])
+        blotter = book.match(bid("X", 11.0, 4))
+        assert blotter.order.filled_quantity == 4
+        assert blotter.order.average_fill_price == Decimal("10.25")
+        assert blotter.order.last_fill_price == Decimal("11")
+        handle = book.get_order(second.id)
+        assert (handle.filled_quantity, handle.last_fill_price) == (1, Decimal("11"))
+        book.match(bid("X", 11.0, 1))
+        assert handle.filled_quantity == 2 and handle.average_fill_price == Decimal("11")
+
+    def test_unfilled_order_has_no_fill_prices(self) -> None:
+        order = bid("X", 10.0, 1)
+        assert order.filled_quantity == 0
+        assert order.average_fill_price is None and order.last_fill_price is None
+
+    def test_fills_survive_amend(self) -> None:
+        book = Book()
+        resting = bid("X", 10.0, 5)
+        book.match(resting)
+        book.match(ask("X", 10.0, 2))
+        book.amend(resting, quantity=2)
+        handle = book.get_order(resting.id)
+        assert (handle.quantity, handle.filled_quantity) == (2, 2)
+
+
+# ── Level ordering ────────────────────────────────────────────────────────
+
+
+class TestLevelOrdering:
+    def _book(self) -> Book:
+        book = Book()
+        prices = [(bid, 9.0), (bid, 10.0), (bid, 8.0), (ask, 12.0), (ask, 11.0), (ask, 13.0)]
+        book.enqueue_orders([side("X", price, 1) for side, price in prices])
+        return book
+
+    def test_levels_and_level_map_are_best_first(self) -> None:
+        book = self._book()
+        assert [lvl.price for lvl in book.levels["X"][Side.BID]] == [10, 9, 8]
+        assert [lvl.price for lvl in book.levels["X"][Side.ASK]] == [11, 12, 13]
+        assert list(book.level_map["X"][Side.BID]) == [10, 9, 8]
+        assert list(book.level_map["X"][Side.ASK]) == [11, 12, 13]
+
+    def test_sorted_price_views(self) -> None:
+        book = self._book()
+        assert book.bid_prices("X") == [Decimal("10"), Decimal("9"), Decimal("8")]
+        assert book.ask_prices("X") == [Decimal("11"), Decimal("12"), Decimal("13")]
+        assert book.bid_prices("Y") == []
+
+
+# ── Banded snapshots ──────────────────────────────────────────────────────
+
+
+class TestBandedSnapshot:
+    def test_levels_are_summed_per_band(self) -> None:
+        book = Book()
+        quotes = [
+            (bid, 10.04, 1),
+            (bid, 10.01, 2),
+            (bid, 9.97, 3),
+            (ask, 10.06, 4),
+            (ask, 10.21, 5),
+        ]
+        book.enqueue_orders([side("X", price, qty) for side, price, qty in quotes])
+        snap = book.banded_snapshot("X", "0.1")
+        assert [(b.price, b.quantity) for b in snap.bids] == [
+            (Decimal("10.0"), 3),
+            (Decimal("9.9"), 3),
+        ]
+        assert [(b.price, b.quantity) for b in snap.asks] == [
+            (Decimal("10.0"), 4),
+            (Decimal("10.2"), 5),
+        ]
+        assert snap.spread == Decimal("0.02")
+        top = book.banded_snapshot("X", 0.1, depth=1)
+        assert len(top.bids) == 1 and top.bid_notional == Decimal("30.06")
+
+    def test_unknown_symbol_and_invalid_width(self) -> None:
+        book = Book()
+        assert book.banded_snapshot("X", "0.1") is None
+        book.enqueue_order(bid("X", 10.0, 1))
+        with pytest.raises(ValueError, match="band_width"):
+            book.banded_snapshot("X", 0)
+
+
+# ── Level order ids ───────────────────────────────────────────────────────
+
+
+class TestLevelOrders:
+    def test_ids_and_quantities_in_priority_order(self) -> None:
+        book = Book()
+        orders = [bid("X", 10.0, 1), bid("X", 10.0, 2), bid("X", 10.0, 3)]
+        book.enqueue_orders(orders)
+        book.match(ask("X", 10.0, 1))
+        assert book.level_orders("X", Side.BID, 10) == [(orders[1].id, 2), (orders[2].id, 3)]
+        assert book.level_orders("X", Side.BID, Decimal("10"), limit=1) == [(orders[1].id, 2)]
+
+    def test_missing_level_is_empty(self) -> None:
+        book = Book()
+        assert book.level_orders("X", Side.ASK, 10) == []
+        book.enqueue_order(ask("X", 11.0, 1))
+        assert book.level_orders("X", Side.ASK, 10) == []
+
+
+# ── Symbol globs ──────────────────────────────────────────────────────────
+
+
+class TestSymbolGlobs:
+    def _book(self) -> Book:
+        book = Book()
+        for symbol in ("ESZ5", "ESH6", "NQZ5"):
+            book.enqueue_orders([bid(symbol, 10.0, 2), ask(symbol, 11.0, 2)])
+        return book
+
+    def test_cancel_all_matching_symbols(self) -> None:
+        book = self._book()
+        stop = bid("ESZ5", 12.0, 1, stop_price=11.5)
+        book.match(stop)
+        assert book.cancel_all("ES*") == 5
+        assert book.get_order(stop.id) is None
+        assert set(book.snapshot_all()) == {"ESZ5", "ESH6", "NQZ5"}
+        assert book.snapshot_all("ES*")["ESZ5"].bids == []
+        assert book.snapshot_all("NQ?5")["NQZ5"].bids[0].quantity == 2
+        assert book.cancel_all() == 2
+
+    def test_session_stats_glob_returns_dict(self) -> None:
+        book = self._book()
+        book.match(bid("ESZ5", 11.0, 1))
+        book.match(bid("NQZ5", 11.0, 1))
+        family = book.session_stats("ES*")
+        assert list(family) == ["ESZ5"] and family["ESZ5"].volume == 1
+        assert book.session_stats("NQZ5").volume == 1
+        assert book.session_stats("ESH6") is None
+
+
+# ── Typed events ──────────────────────────────────────────────────────────
+
+
+class TestTypedEvents:
+    def test_event_stream_covers_order_lifecycle(self) -> None:
+        from pyorderbook import (
+            Event,
+            LevelChanged,
+            OrderAccepted,
+            OrderCancelled,
+            OrderExpired,
+            StateChanged,
+            TradeExecuted,
+        )
+
+        events: list[Event] = []
+        book = Book()
+        book.set_publisher(events.extend, encoding="event")
+        resting = ask("X", 10.0, 5)
+        book.match(resting)
+        blotter = book.match(bid("X", 10.0, 2))
+        book.cancel(resting)
+        book.advance_time(1)
+        gtd = bid("X", 9.0, 1, expire_time=2)
+        book.match(gtd)
+        book.advance_time(2)
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+
+        assert all(isinstance(e, Event) and e.symbol == "X" for e in events)
+        kinds = [type(e) for e in events]
+        assert kinds == [
+            OrderAccepted,
+            LevelChanged,
+            OrderAccepted,
+            TradeExecuted,
+            LevelChanged,
+            OrderCancelled,
+            LevelChanged,
+            OrderAccepted,
+            LevelChanged,
+            OrderExpired,
+            LevelChanged,
+            StateChanged,
+        ]
+        assert (events[0].order_id, events[0].quantity) == (resting.id, 5)
+        assert events[3].trade_id == blotter.trades[0].trade_id
+        assert (events[5].kind, events[5].remaining) == ("order_cancelled", 3)
+        assert events[9].get_order_id() == gtd.id
+        assert events[-1].state == TradingState.PRE_OPEN
+
+    def test_dict_stream_is_unchanged(self) -> None:
+        batches: list[list[dict[str, object]]] = []
+        book = Book()
+        book.set_publisher(batches.append)
+        book.match(bid("X", 10.0, 1))
+        book.set_trading_state("X", TradingState.CLOSED)
+        assert [e["type"] for batch in batches for e in batch] == ["level"]
+        with pytest.raises(ValueError, match="'event'"):
+            book.set_publisher(batches.append, encoding="xml")
+
+
+# ── Replay summaries ──────────────────────────────────────────────────────
+
+
+class TestReplaySummary:
+    def test_summary_tallies_rows_trades_and_rejects(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "session.parquet"
+        parquet.write_table(
+            pa.table(
+                {
+                    "side": ["ask", "bid", "bid", "bid"],
+                    "symbol": ["X"] * 4,
+                    "price": [10.0, 10.0, 10.0, 9.0],
+                    "quantity": [2, 1, -1, 3],
+                }
+            ),
+            path,
+        )
+        from pyorderbook import ReplaySummary
+
+        book = Book()
+        summary = book.replay_parquet(str(path), summary=True)
+        assert isinstance(summary, ReplaySummary)
+        assert (summary.rows, summary.orders_added) == (4, 2)
+        assert (summary.trades, summary.volume) == (1, 1)
+        assert [idx for idx, _ in summary.rejects] == [2]
+        assert summary.duration >= 0 and summary.events_per_sec >= 0
+        assert book.bid_prices("X") == [Decimal("9")]
+        assert "rows=4" in repr(summary)
+
+
+# ── Row error policy ──────────────────────────────────────────────────────
+
+
+def _messy_parquet(tmp_path: Path) -> str:
+    pa = pytest.importorskip("pyarrow")
+    parquet = pytest.importorskip("pyarrow.parquet")
+    path = tmp_path / "messy.parquet"
+    parquet.write_table(
+        pa.table(
+            {
+                "side": ["ask", "buy", "bid", "bid"],
+                "symbol": ["X", "X", "", "X"],
+                "price": ["10", "10", "10", "abc"],
+                "quantity": [2, 1, 1, 1],
+            }
+        ),
+        path,
+    )
+    return str(path)
+
+
+class TestRowErrorPolicy:
+    def test_raise_is_the_default(self, tmp_path: Path) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="Invalid side at row 1"):
+            book.replay_parquet(_messy_parquet(tmp_path))
+        assert book.ask_prices("X") == []
+
+    def test_skip_and_collect(self, tmp_path: Path) -> None:
+        messy = _messy_parquet(tmp_path)
+        assert len(Book().replay_parquet(messy, on_error="skip")) == 1
+        blotters, errors = Book().replay_parquet(messy, on_error="collect")
+        assert len(blotters) == 1
+        assert [row_idx for row_idx, _ in errors] == [1, 2, 3]
+        assert "Symbol cannot be empty" in errors[1][1]
+        count, errors = Book().ingest_parquet(messy, on_error="collect")
+        assert (count, len(errors)) == (1, 3)
+        assert Book.from_parquet(messy, on_error="skip").ask_prices("X") == [Decimal("10")]
+        with pytest.raises(ValueError, match="ingest_parquet"):
+            Book.from_parquet(messy, on_error="collect")
+
+    def test_summary_and_strategy_replays(self, tmp_path: Path) -> None:
+        messy = _messy_parquet(tmp_path)
+        summary = Book().replay_parquet(messy, summary=True, on_error="collect")
+        assert summary.rows == 4
+        assert [row_idx for row_idx, _ in summary.rejects] == [1, 2, 3]
+
+        class Idle:
+            def on_event(self, book: Book, event: TradeBlotter) -> None:
+                return None
+
+        rows = [{"side": "bid", "symbol": "X", "price": 10.0}]
+        blotters, errors = Book().replay_with_strategy(rows, Idle(), on_error="collect")
+        assert blotters == [] and errors[0][0] == 0
+        with pytest.raises(ValueError, match="on_error"):
+            Book().replay_with_strategy(rows, Idle(), on_error="ignore")
+
+
+# ── Column mapping ────────────────────────────────────────────────────────
+
+
+class TestColumnMapping:
+    def test_renamed_columns_and_side_codes(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = str(tmp_path / "venue.parquet")
+        parquet.write_table(
+            pa.table(
+                {
+                    "Side": [2, 1, 3],
+                    "ticker": ["x", "x", "x"],
+                    "px": [10.0, 10.0, 9.0],
+                    "size": [2, 1, 1],
+                    "ts": [1, 2, 3],
+                }
+            ),
+            path,
+        )
+        columns = {"side": "Side", "symbol": "ticker", "price": "px", "quantity": "size"}
+        transforms = {"side": {1: "bid", 2: "ask"}, "symbol": str.upper}
+        book = Book()
+        blotters, errors = book.replay_parquet(
+            path,
+            columns=columns | {"timestamp": "ts"},
+            transforms=transforms,
+            on_error="collect",
+        )
+        assert [len(b.trades) for b in blotters] == [0, 1]
+        assert book.ask_prices("X") == [Decimal("10")]
+        assert errors[0][0] == 2 and "Unmapped side" in errors[0][1]
+        with pytest.raises(ValueError, match=r"missing \[side, price, quantity\]"):
+            Book().ingest_parquet(path, columns={"symbol": "ticker"})
+        with pytest.raises(ValueError, match="Unknown field 'venue'"):
+            Book().replay_parquet(path, columns={"venue": "exchange"})
+
+    def test_strategy_rows_are_remapped(self) -> None:
+        class Idle:
+            def on_event(self, book: Book, event: TradeBlotter) -> None:
+                return None
+
+        rows = [{"s": "B", "symbol": "X", "price": 10.0, "quantity": 1}]
+        book = Book()
+        book.replay_with_strategy(
+            rows,
+            Idle(),
+            columns={"side": "s"},
+            transforms={"side": lambda code: {"B": "bid", "S": "ask"}[code]},
+        )
+        assert book.bid_prices("X") == [Decimal("10")]
+
+
+# ── Multi-file replay ─────────────────────────────────────────────────────
+
+
+class TestReplayMany:
+    def test_files_merge_by_timestamp(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+
+        def write(name: str, sides: list[str], times: list[int]) -> Path:
+            path = tmp_path / name
+            table = {
+                "side": sides,
+                "symbol": ["X"] * len(sides),
+                "price": [10.0] * len(sides),
+                "quantity": [1] * len(sides),
+                "timestamp": times,
+            }
+            parquet.write_table(pa.table(table), path)
+            return path
+
+        venue_a = write("a.parquet", ["ask", "bid"], [1, 5])
+        venue_b = write("b.parquet", ["bid", "ask"], [3, 5])
+        book = Book()
+        blotters = book.replay_many([venue_a, str(venue_b)])
+        assert [len(b.trades) for b in blotters] == [0, 1, 0, 1]
+        assert [b.order.side for b in blotters[2:]] == [Side.BID, Side.ASK]
+        assert book.current_time == 5
+        assert len(Book().replay_many([venue_a, venue_b], skip_empty=True)) == 2
+
+        with pytest.raises(TypeError, match="list of paths"):
+            Book().replay_many(str(venue_a))
+        with pytest.raises(ValueError, match="Invalid by"):
+            Book().replay_many([venue_a], by="symbol")
+        untimed = tmp_path / "untimed.parquet"
+        parquet.write_table(
+            pa.table({"side": ["bid"], "symbol": ["X"], "price": [1.0], "quantity": [1]}),
+            untimed,
+        )
+        with pytest.raises(ValueError, match="no timestamp"):
+            Book().replay_many([untimed])
+
+
+# ── Parquet export ────────────────────────────────────────────────────────
+
+
+class TestExportParquet:
+    def _book(self) -> Book:
+        book = Book()
+        book.match(
+            [
+                bid("AAPL", 10.0, 3),
+                bid("AAPL", 11.0, 2),
+                ask("AAPL", 12.0, 4),
+                Order(Side.BID, "MSFT", 20.0, 1, expire_time=50),
+            ]
+        )
+        return book
+
+    def test_round_trips_through_ingest(self, tmp_path: Path) -> None:
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = str(tmp_path / "book.parquet")
+        assert self._book().export_parquet(path, compression="zstd") == 4
+        metadata = parquet.ParquetFile(path).metadata
+        assert metadata.row_group(0).column(0).compression == "ZSTD"
+        rows = parquet.read_table(path).to_pylist()
+        assert [(r["side"], r["price"]) for r in rows[:3]] == [
+            ("bid", 11.0),
+            ("bid", 10.0),
+            ("ask", 12.0),
+        ]
+        assert rows[3]["expire_time"] == 50
+        restored = Book.from_parquet(path)
+        assert restored.bid_prices("AAPL") == [Decimal("11"), Decimal("10")]
+        assert restored.snapshot("MSFT").bids[0].quantity == 1
+
+    def test_hive_partitions_by_symbol(self, tmp_path: Path) -> None:
+        pytest.importorskip("pyarrow.parquet")
+        root = tmp_path / "dataset"
+        self._book().export_parquet(str(root), partition_by=["symbol"])
+        assert sorted(p.name for p in root.iterdir()) == ["symbol=AAPL", "symbol=MSFT"]
+        assert Book.from_parquet(str(root)).ask_prices("AAPL") == [Decimal("12")]
+        with pytest.raises(ValueError, match="Cannot partition by 'date'"):
+            self._book().export_parquet(str(root), partition_by=["date"])
+
+
+# ── Seeding from levels ───────────────────────────────────────────────────
+
+
+class TestFromLevels:
+    def test_levels_become_synthetic_orders(self) -> None:
+        book = Book.from_levels(
+            {
+                "AAPL": {"bid": [(99.5, 10), ("99", 5)], "ask": [(Decimal("100.5"), 7)]},
+                "MSFT": {"ask": [(300, 1), (301, 0)]},
+            }
+        )
+        assert book.bid_prices("AAPL") == [Decimal("99.5"), Decimal("99")]
+        assert book.ask_prices("MSFT") == [Decimal("300")]
+        assert len(book.order_map) == 4
+        blotter = book.match(bid("AAPL", 100.5, 3))
+        assert blotter.trades[0].fill_quantity == 3
+        assert book.snapshot("AAPL").asks[0].quantity == 4
+
+    def test_rejects_malformed_input(self) -> None:
+        with pytest.raises(ValueError, match="Invalid side 'buy' for X"):
+            Book.from_levels({"X": {"buy": [(1.0, 1)]}})
+        with pytest.raises(TypeError, match="must be a dict"):
+            Book.from_levels({"X": [(1.0, 1)]})
+        with pytest.raises(ValueError, match="cannot be negative"):
+            Book.from_levels({"X": {"bid": [(1.0, -1)]}})
+
+
+# ── Crossed snapshots ─────────────────────────────────────────────────────
+
+
+class TestCrossedSnapshots:
+    CROSSED = {"bid": [(101, 5), (99, 2)], "ask": [(100, 3), (102, 1), (103, 0)]}
+
+    def test_drop_is_the_default(self) -> None:
+        book = Book.from_levels({"X": self.CROSSED})
+        assert book.bid_prices("X") == [Decimal("99")]
+        assert book.ask_prices("X") == [Decimal("102")]
+
+    def test_uncross_by_matching_trades_the_overlap(self) -> None:
+        book = Book.from_levels({"X": self.CROSSED}, on_crossed="uncross_by_matching")
+        assert book.bid_prices("X") == [Decimal("101"), Decimal("99")]
+        assert book.snapshot("X").bids[0].quantity == 2
+        assert book.ask_prices("X") == [Decimal("102")]
+        assert book.session_stats("X").volume == 3
+
+    def test_error_rejects_crossed_and_placeholder_levels(self) -> None:
+        with pytest.raises(ValueError, match="crossed: best bid 101 >= best ask 100"):
+            Book.from_levels({"X": {"bid": [(101, 5)], "ask": [(100, 3)]}}, on_crossed="error")
+        with pytest.raises(ValueError, match="Zero-quantity ask level at 103"):
+            Book.from_levels({"X": {"ask": [(103, 0)]}}, on_crossed="error")
+        with pytest.raises(ValueError, match="Invalid on_crossed"):
+            Book.from_levels({}, on_crossed="ignore")
+
+    def test_resync_applies_the_policy(self) -> None:
+        book = Book()
+        snapshot = {"bids": [[101, 5], [99, 2]], "asks": [[100, 3]]}
+        with pytest.raises(ValueError, match="crossed"):
+            book.resync_from_snapshot("X", snapshot, 1, on_crossed="error")
+        book.resync_from_snapshot("X", snapshot, 1)
+        assert (book.bid_prices("X"), book.ask_prices("X")) == ([Decimal("99")], [])
+
+
+# ── Settlement report ────────────────────────────────────────────────────────
+
+
+class TestSettlementReport:
+    def _traded_book(self):
+        book = Book()
+        book.match(ask("X", 100, 10, account="mm"))
+        book.match(bid("X", 100, 4, account="fund"))
+        book.match(bid("X", 100, 6))
+        return book
+
+    def test_nets_fills_per_account_and_symbol(self):
+        from pyorderbook import SettlementLine
+
+        lines = self._traded_book().settlement_report()
+        assert [line.account for line in lines] == [None, "fund", "mm"]
+        assert all(isinstance(line, SettlementLine) for line in lines)
+        mm = lines[2]
+        assert (mm.symbol, mm.sold, mm.bought, mm.net_quantity) == ("X", 10, 0, -10)
+        assert mm.sell_notional == Decimal("1000")
+        assert lines[1].cash_delta == Decimal("-400")
+
+    def test_filters_by_account_and_charges_fees(self):
+        book = self._traded_book()
+        (fund,) = book.settlement_report("fund", maker_fee="0.001", taker_fee="0.01")
+        assert fund.fees == Decimal("4")
+        assert fund.cash_delta == Decimal("-404")
+        (mm,) = book.settlement_report("mm", maker_fee="-0.001", taker_fee="0.01")
+        assert mm.fees == Decimal("-1")
+        assert book.settlement_report("nobody") == []
+
+    def test_roll_session_starts_a_new_report(self):
+        book = self._traded_book()
+        book.roll_session()
+        assert book.settlement_report() == []
+        book.match(bid("X", 100, 1, account="fund"))
+        assert book.settlement_report() == []
+
+
+# ── Standing order metadata on trades ────────────────────────────────────────
+
+
+class TestTradeStandingMetadata:
+    def test_trades_record_standing_price_and_remaining(self):
+        book = Book()
+        book.match(ask("X", 10, 3))
+        book.match(ask("X", 11, 5))
+        trades = book.match(bid("X", 12, 6)).trades
+        assert [(t.symbol, t.standing_price, t.standing_remaining) for t in trades] == [
+            ("X", Decimal("10"), 0),
+            ("X", Decimal("11"), 2),
+        ]
+
+    def test_auction_trades_use_the_ask_as_standing_order(self):
+        book = Book()
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        book.match(bid("X", 11, 4))
+        book.match(ask("X", 9, 6))
+        (trade,) = book.uncross("X", "open").trades
+        standing = (trade.symbol, trade.standing_price, trade.standing_remaining)
+        assert standing == ("X", Decimal("9"), 2)
+
+    def test_constructor_defaults_to_a_completed_standing_order(self):
+        t = Trade(uuid.uuid4(), uuid.uuid4(), 5, Decimal("10"))
+        assert (t.symbol, t.standing_price, t.standing_remaining) == ("", Decimal("10"), 0)
+        t = Trade(
+            uuid.uuid4(),
+            uuid.uuid4(),
+            5,
+            Decimal("10"),
+            symbol="X",
+            standing_price="9.5",
+            standing_remaining=3,
+        )
+        assert (t.standing_price, t.get_standing_remaining()) == (Decimal("9.5"), 3)
+
+
+# ── Depth consumed ───────────────────────────────────────────────────────────
+
+
+class TestDepthConsumed:
+    def test_sweep_reports_levels_worst_price_and_improvement(self):
+        book = Book()
+        book.match([ask("X", 10, 3), ask("X", 11, 2), ask("X", 11, 2), ask("X", 12, 5)])
+        blotter = book.match(bid("X", 12, 8))
+        assert blotter.levels_walked == 3
+        assert blotter.worst_fill_price == Decimal("12")
+        # (3 * 2 + 4 * 1 + 1 * 0) / 8 units
+        assert blotter.price_improvement == Decimal("1.25")
+
+    def test_ask_improvement_and_unfilled_orders(self):
+        book = Book()
+        book.match(bid("X", 10, 5))
+        blotter = book.match(ask("X", 9, 5))
+        assert (blotter.levels_walked, blotter.get_worst_fill_price()) == (1, Decimal("10"))
+        assert blotter.price_improvement == Decimal("1")
+        rested = book.match(ask("X", 11, 5))
+        assert (rested.levels_walked, rested.worst_fill_price) == (0, None)
+        assert rested.price_improvement == 0
+
+
+# ── Preview ──────────────────────────────────────────────────────────────────
+
+
+class TestPreview:
+    def test_preview_matches_like_match_without_committing(self):
+        book = Book()
+        book.match([ask("X", 10, 3), ask("X", 11, 5), ask("Y", 10, 1)])
+        book.roll_session()
+        order = bid("X", 11, 6)
+        preview = book.preview(order)
+        assert [(t.fill_price, t.fill_quantity) for t in preview.trades] == [
+            (Decimal("10"), 3),
+            (Decimal("11"), 3),
+        ]
+        assert preview.order.quantity == 0
+        assert book.ask_prices("X") == [Decimal("10"), Decimal("11")]
+        assert order.id not in book.order_map
+        assert book.session_stats("X") is None
+
+        actual = book.match(order)
+        assert [t.fill_quantity for t in actual.trades] == [3, 3]
+        assert actual.levels_walked == preview.levels_walked
+
+    def test_preview_applies_book_checks_and_stops(self):
+        book = Book()
+        book.set_locate("X", 5)
+        with pytest.raises(ValueError, match="available to borrow"):
+            book.preview(ask("X", 10, 10, short=True))
+        assert book.available_locate("X") == 5
+
+        book.match([bid("X", 99, 5), bid("X", 98, 5)])
+        stop = ask("X", 98, 5, stop_price=99)
+        book.match(stop)
+        preview = book.preview(ask("X", 99, 5))
+        assert preview.cascade.activations[0].order.id == stop.id
+        assert book.get_order(stop.id) is not None
+        assert book.bid_prices("X") == [Decimal("99"), Decimal("98")]
+
+
+# ── Idempotent submission ────────────────────────────────────────────────────
+
+
+class TestDedupeKey:
+    def test_resubmission_returns_original_blotter(self):
+        book = Book()
+        book.match(ask("X", 10, 5))
+        first = book.match(bid("X", 10, 3), dedupe_key="fill-1")
+        again = book.match(bid("X", 10, 3), dedupe_key="fill-1")
+        assert again.trades[0].trade_id == first.trades[0].trade_id
+        assert again.order.id == first.order.id
+        assert book.snapshot("X").asks[0].quantity == 2
+
+    def test_rejections_are_not_remembered(self):
+        book = Book()
+        book.set_locate("X", 0)
+        with pytest.raises(ValueError):
+            book.match(ask("X", 10, 5, short=True), dedupe_key="k")
+        book.set_locate("X", 5)
+        assert book.match(ask("X", 10, 5, short=True), dedupe_key="k").order.quantity == 5
+        assert len(book.order_map) == 1
+
+    def test_capacity_bounds_the_cache(self):
+        book = Book()
+        book.set_dedupe_capacity(1)
+        book.match(ask("X", 10, 1), dedupe_key="a")
+        book.match(ask("X", 10, 1), dedupe_key="b")
+        book.match(ask("X", 10, 1), dedupe_key="a")
+        assert book.snapshot("X").asks[0].quantity == 3
+
+    def test_dedupe_key_needs_a_single_order(self):
+        with pytest.raises(TypeError, match="single Order"):
+            Book().match([bid("X", 10, 1)], dedupe_key="k")
+
+
+# ── Timestamp tie-break ──────────────────────────────────────────────────────
+
+
+class TestTieBreak:
+    def _venues(self, tmp_path: Path) -> list[Path]:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        paths = []
+        for name, side, order_id in [("a", "ask", "7"), ("b", "bid", "3")]:
+            path = tmp_path / f"{name}.parquet"
+            table = {
+                "side": [side],
+                "symbol": ["X"],
+                "price": [10.0],
+                "quantity": [1],
+                "timestamp": [5],
+                "order_id": [order_id],
+            }
+            parquet.write_table(pa.table(table), path)
+            paths.append(path)
+        return paths
+
+    def test_ties_follow_the_chosen_rule(self, tmp_path: Path) -> None:
+        paths = self._venues(tmp_path)
+
+        def sides(tie_break: str) -> list[Side]:
+            blotters = Book().replay_many(paths, tie_break=tie_break)
+            return [b.order.side for b in blotters]
+
+        assert sides("file") == [Side.ASK, Side.BID]
+        assert sides("order_id") == [Side.BID, Side.ASK]
+        assert sides("bid_first") == [Side.BID, Side.ASK]
+        assert sides("ask_first") == [Side.ASK, Side.BID]
+        swapped = Book().replay_many(paths[::-1], tie_break="order_id")
+        assert [b.order.side for b in swapped] == sides("order_id")
+
+    def test_order_id_rule_needs_the_column(self, tmp_path: Path) -> None:
+        pa = pytest.importorskip("pyarrow")
+        parquet = pytest.importorskip("pyarrow.parquet")
+        path = tmp_path / "plain.parquet"
+        table = {
+            "side": ["bid"],
+            "symbol": ["X"],
+            "price": [1.0],
+            "quantity": [1],
+            "timestamp": [1],
+        }
+        parquet.write_table(pa.table(table), path)
+        with pytest.raises(ValueError, match="no order_id"):
+            Book().replay_many([path], tie_break="order_id")
+        with pytest.raises(ValueError, match="Invalid tie_break"):
+            Book().replay_many([path], tie_break="random")
+
+
+# ── Liquidity decay ──────────────────────────────────────────────────────────
+
+
+class TestLiquidityDecay:
+    def _book(self) -> Book:
+        book = Book.from_levels({"X": {"bid": [(99, 100)], "ask": [(101, 80)]}})
+        book.advance_time(0)
+        return book
+
+    def test_levels_halve_each_half_life(self) -> None:
+        book = self._book()
+        book.set_liquidity_decay(half_life=10)
+        book.advance_time(10)
+        snapshot = book.snapshot("X")
+        assert (snapshot.bids[0].quantity, snapshot.asks[0].quantity) == (50, 40)
+        book.advance_time(100)
+        assert book.bid_prices("X") == [] and book.ask_prices("X") == []
+
+    def test_refresh_refills_consumed_levels(self) -> None:
+        book = self._book()
+        book.set_liquidity_decay(refresh_after=30)
+        book.match(bid("X", 101, 80))
+        assert book.ask_prices("X") == []
+        book.advance_time(29)
+        assert book.ask_prices("X") == []
+        book.advance_time(30)
+        assert book.snapshot("X").asks[0].quantity == 80
+
+    def test_refresh_skips_levels_the_book_moved_through(self) -> None:
+        book = self._book()
+        book.set_liquidity_decay(refresh_after=30)
+        book.match(bid("X", 101, 90))
+        assert book.bid_prices("X") == [Decimal("101"), Decimal("99")]
+        book.advance_time(30)
+        assert book.ask_prices("X") == []
+
+    def test_levels_stay_static_without_a_model(self) -> None:
+        book = self._book()
+        book.advance_time(1000)
+        assert book.snapshot("X").bids[0].quantity == 100
+        with pytest.raises(ValueError, match="half_life must be positive"):
+            book.set_liquidity_decay(half_life=0)
+
+
+# ── Halt and resume ──────────────────────────────────────────────────────────
+
+
+class TestHaltResume:
+    def test_orders_queue_during_halt_and_match_on_resume(self) -> None:
+        book = Book()
+        book.match(ask("X", 10, 5))
+        assert book.halt("X") == []
+        assert book.trading_state("X") == TradingState.HALTED
+        queued = bid("X", 10, 3)
+        assert book.match(queued).trades == []
+        assert book.get_order(queued.id) is not None
+        assert book.bid_prices("X") == []
+        cancelled = bid("X", 10, 1)
+        book.match(cancelled)
+        book.cancel(cancelled)
+
+        blotters = book.resume("X")
+        assert [len(b.trades) for b in blotters] == [1]
+        assert book.trading_state("X") == TradingState.CONTINUOUS
+        assert book.snapshot("X").asks[0].quantity == 2
+
+    def test_open_order_policies(self) -> None:
+        def halted(policy: str) -> tuple[Book, list]:
+            book = Book()
+            book.match([bid("X", 9, 5), ask("X", 8, 1, stop_price=9)])
+            return book, book.halt("X", open_orders=policy)
+
+        book, cancelled = halted("keep")
+        assert cancelled == [] and len(book.order_map) == 1
+        book, cancelled = halted("cancel_market_and_stop")
+        assert [o.stop_price for o in cancelled] == [Decimal("9")]
+        assert book.bid_prices("X") == [Decimal("9")]
+        book, cancelled = halted("cancel_all")
+        assert len(cancelled) == 2 and book.bid_prices("X") == []
+        with pytest.raises(ValueError, match="Invalid open_orders"):
+            Book().halt("X", open_orders="flatten")
+
+    def test_resume_through_reopening_auction(self) -> None:
+        book = Book()
+        book.halt("X")
+        book.match([bid("X", 11, 4), ask("X", 9, 6)])
+        result = book.resume("X", reopen_auction=True, reference_price=10)
+        assert result.volume == 4
+        assert book.trading_state("X") == TradingState.CONTINUOUS
+        assert book.snapshot("X").asks[0].quantity == 2
+
+    def test_halt_and_resume_check_the_state(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="not halted"):
+            book.resume("X")
+        book.halt("X")
+        with pytest.raises(ValueError, match="trading is halted"):
+            book.halt("X")
+
+
+# ── Auction indicatives ──────────────────────────────────────────────────────
+
+
+class TestAuctionIndicatives:
+    def test_indicatives_follow_arriving_orders(self) -> None:
+        from pyorderbook import AuctionIndicatives
+
+        book = Book()
+        book.set_trading_state("X", TradingState.PRE_OPEN)
+        book.match(bid("X", 10.5, 10))
+        empty = book.auction_indicatives("X")
+        assert isinstance(empty, AuctionIndicatives)
+        assert (empty.auction, empty.price, empty.matched_volume) == ("open", None, 0)
+
+        book.match(ask("X", 10, 4))
+        ind = book.auction_indicatives("X")
+        assert (ind.price, ind.matched_volume) == (Decimal("10"), 4)
+        assert (ind.imbalance_side, ind.imbalance_quantity) == (Side.BID, 6)
+
+        book.match(ask("X", 0, 8, auction=AuctionDesignation.MOO))
+        ind = book.auction_indicatives("X")
+        assert ind.matched_volume == 10
+        assert (ind.imbalance_side, ind.imbalance_quantity) == (Side.ASK, 2)
+        assert book.uncross("X", "open").volume == ind.matched_volume
+
+    def test_close_auction_and_phases_without_one(self) -> None:
+        book = Book()
+        book.match(bid("X", 10, 5))
+        book.match(ask("X", 0, 3, auction=AuctionDesignation.MOC))
+        ind = book.auction_indicatives("X")
+        assert (ind.auction, ind.matched_volume, ind.price) == ("close", 3, Decimal("10"))
+        assert ind.imbalance_side == Side.BID
+        book.halt("X")
+        with pytest.raises(ValueError, match="No auction pending"):
+            book.auction_indicatives("X")
+
+
+# ── Fill price policies ──────────────────────────────────────────────────────
+
+
+class TestFillPricePolicy:
+    def _fill(self, book: Book) -> Decimal:
+        book.match(ask("X", 10, 1))
+        return book.match(bid("X", 12, 1)).trades[0].fill_price
+
+    def test_midpoint_and_reference_policies(self) -> None:
+        book = Book()
+        assert book.fill_price_policy == "passive"
+        book.set_fill_price_policy("midpoint")
+        assert self._fill(book) == Decimal("11")
+        assert book.bid_prices("X") == [] and book.ask_prices("X") == []
+
+        book.set_fill_price_policy("reference", reference_price="11.5")
+        assert self._fill(book) == Decimal("11.5")
+        book.set_fill_price_policy("reference", reference_price=13)
+        assert self._fill(book) == Decimal("10")
+
+    def test_last_sale_policy(self) -> None:
+        book = Book()
+        book.set_fill_price_policy("last_sale")
+        assert self._fill(book) == Decimal("10")
+        book.match(ask("X", 11, 1))
+        book.match(bid("X", 11, 1))
+        assert self._fill(book) == Decimal("11")
+        assert book.preview(bid("X", 12, 1)).trades == []
+
+    def test_policy_arguments_are_checked(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="reference_price is required"):
+            book.set_fill_price_policy("reference")
+        with pytest.raises(ValueError, match="only applies"):
+            book.set_fill_price_policy("midpoint", reference_price=1)
+        with pytest.raises(ValueError, match="Invalid fill price policy"):
+            book.set_fill_price_policy("vwap")
+
+
+# ── Dark book ────────────────────────────────────────────────────────────────
+
+
+class TestDarkBook:
+    def _quoted(self) -> Book:
+        lit = Book()
+        lit.match(bid("X", 10, 100))
+        lit.match(ask("X", 11, 100))
+        return lit
+
+    def test_crosses_at_lit_midpoint_without_touching_lit(self) -> None:
+        from pyorderbook import DarkBook
+
+        lit = self._quoted()
+        dark = DarkBook(lit)
+        assert dark.lit is lit
+        assert dark.midpoint("X") == Decimal("10.5")
+        resting = ask("X", 10.2, 30)
+        assert dark.match(resting).trades == []
+        blotter = dark.match(bid("X", 11, 20))
+        trade = blotter.trades[0]
+        assert (trade.fill_price, trade.fill_quantity) == (Decimal("10.5"), 20)
+        assert trade.standing_order_id == resting.id
+        assert [o.quantity for o in dark.orders("X")] == [10]
+        assert [qty for _, qty in lit.level_orders("X", Side.BID, 10)] == [100]
+
+    def test_limits_and_minimum_quantity_gate_fills(self) -> None:
+        from pyorderbook import DarkBook
+
+        dark = DarkBook(self._quoted())
+        dark.match(ask("X", 10.8, 50))
+        dark.match(ask("X", 10.4, 5))
+        assert dark.match(bid("X", 11, 40), min_quantity=10).trades == []
+        assert [t.fill_quantity for t in dark.match(ask("X", 10.4, 60)).trades] == [40]
+        assert dark.cross("X") == []
+        with pytest.raises(ValueError, match="min_quantity"):
+            dark.match(bid("X", 11, 5), min_quantity=6)
+
+    def test_cross_follows_lit_quote_and_cancel(self) -> None:
+        from pyorderbook import DarkBook
+
+        lit = self._quoted()
+        dark = DarkBook(lit)
+        low_bid = bid("X", 10.4, 10)
+        dark.match(low_bid)
+        dark.match(ask("X", 10, 10))
+        assert dark.cross("X") == []
+        lit.match(ask("X", 10.5, 100))
+        trades = dark.cross("X")
+        assert [(t.fill_price, t.fill_quantity) for t in trades] == [(Decimal("10.25"), 10)]
+        assert dark.orders("X") == []
+        with pytest.raises(KeyError):
+            dark.cancel(low_bid)
+        with pytest.raises(ValueError, match="plain limit orders"):
+            dark.match(bid("X", 11, 1, stop_price=12))
+
+
+# ── Spreads and implied liquidity ────────────────────────────────────────────
+
+
+class TestImpliedSpreads:
+    def _spread_book(self) -> Book:
+        book = Book()
+        book.register_spread("S", "F", "B")
+        book.match(ask("F", 105, 3))
+        book.match(bid("B", 100, 5))
+        return book
+
+    def test_legs_imply_spread_liquidity(self) -> None:
+        book = self._spread_book()
+        assert book.implied_bbo("S") == (None, (Decimal("5"), 3))
+        assert len(book.preview(bid("S", 5, 1)).trades) == 1
+        assert book.implied_bbo("S") == (None, (Decimal("5"), 3))
+
+        blotter = book.match(bid("S", 5, 4))
+        assert [(t.symbol, t.fill_price, t.fill_quantity) for t in blotter.trades] == [
+            ("S", Decimal("5"), 3)
+        ]
+        assert blotter.order.quantity == 1
+        assert book.ask_prices("F") == []
+        assert book.session_stats("F").volume == 3
+        assert [qty for _, qty in book.level_orders("B", Side.BID, 100)] == [2]
+
+    def test_spread_and_leg_imply_the_other_leg(self) -> None:
+        book = self._spread_book()
+        book.match(bid("S", 5, 4))
+        # Resting spread bid 5 plus back bid 100 imply a front bid of 105.
+        assert book.implied_bbo("F")[0] == (Decimal("105"), 1)
+        trade = book.match(ask("F", 104, 2)).trades[0]
+        assert (trade.symbol, trade.fill_price, trade.fill_quantity) == ("F", Decimal("105"), 1)
+        assert book.bid_prices("S") == []
+        assert book.implied_bbo("F") == (None, None)
+
+    def test_register_spread_validates_legs(self) -> None:
+        book = Book()
+        book.register_spread("S", "F", "B")
+        with pytest.raises(ValueError, match="already registered"):
+            book.register_spread("S", "F", "C")
+        with pytest.raises(ValueError, match="distinct legs"):
+            book.register_spread("T", "F", "F")
+
+
+# ── Order event callbacks ────────────────────────────────────────────────────
+
+
+class TestOrderCallbacks:
+    def test_resting_order_hears_later_fills(self) -> None:
+        book = Book()
+        events: list = []
+        resting = bid("X", 10, 5)
+        book.match(resting, on_event=events.append)
+        assert events == []
+        book.match(ask("X", 10, 2))
+        book.match(ask("X", 9, 4))
+        assert [(e.kind, e.quantity) for e in events] == [("trade", 2), ("trade", 3)]
+        assert all(e.standing_order_id == resting.id for e in events)
+        book.match(bid("X", 9, 1))
+        assert len(events) == 2
+
+    def test_cancel_and_expiry_reach_queue(self) -> None:
+        import queue
+
+        book = Book()
+        sink: queue.Queue = queue.Queue()
+        cancelled = bid("X", 10, 5)
+        book.match(cancelled, on_event=sink)
+        book.cancel(cancelled)
+        event = sink.get_nowait()
+        assert (event.kind, event.order_id, event.remaining) == ("order_cancelled", cancelled.id, 5)
+
+        book.match(ask("X", 11, 3, expire_time=10), on_event=sink)
+        book.advance_time(10)
+        assert sink.get_nowait().kind == "order_expired"
+        assert sink.empty()
+
+    def test_on_event_is_checked(self) -> None:
+        book = Book()
+        with pytest.raises(TypeError, match="callable or have a put method"):
+            book.match(bid("X", 10, 1), on_event=42)
+        with pytest.raises(TypeError, match="on_event applies to a single Order"):
+            book.match([bid("X", 10, 1)], on_event=print)
+
+
+# ── Bulk cancel by predicate ─────────────────────────────────────────────────
+
+
+class TestCancelWhere:
+    def test_filters_combine(self) -> None:
+        book = Book()
+        stale = [bid("X", 9, 1), bid("X", 10, 1), ask("X", 12, 1)]
+        for order in stale:
+            book.match(order)
+        seq = book.sequence
+        fresh = bid("X", 8, 1)
+        book.match(fresh)
+        book.match(bid("Y", 9, 1))
+
+        assert book.cancel_where(symbol="X", older_than_seq=seq, price_below=10) == [stale[0].id]
+        assert book.cancel_where(symbol="X", side=Side.ASK) == [stale[2].id]
+        assert set(book.cancel_where(symbol="X", price_above="7.5")) == {stale[1].id, fresh.id}
+        assert book.bid_prices("X") == [] and book.bid_prices("Y") == [Decimal("9")]
+
+    def test_held_orders_and_no_match(self) -> None:
+        book = Book()
+        stop = bid("X", 11, 1, stop_price=11)
+        book.match(stop)
+        assert book.cancel_where(side=Side.ASK) == []
+        assert book.cancel_where(price_above=10) == [stop.id]
+
+
+# ── Two-sided quotes ─────────────────────────────────────────────────────────
+
+
+class TestQuote:
+    def test_requote_replaces_both_sides(self) -> None:
+        book = Book()
+        bid_blotter, ask_blotter = book.quote("X", 10, 5, 11, 5, "mm1")
+        assert bid_blotter.trades == [] and ask_blotter.trades == []
+        book.quote("X", 10.5, 3, 11.5, 4, "mm1")
+        assert book.bid_prices("X") == [Decimal("10.5")]
+        assert book.ask_prices("X") == [Decimal("11.5")]
+
+        book.match(bid("X", 12, 1))
+        bid_blotter, ask_blotter = book.quote("X", 10, 2, 11, 0, "mm1")
+        assert ask_blotter is None
+        assert book.ask_prices("X") == []
+        assert [qty for _, qty in book.level_orders("X", Side.BID, 10)] == [2]
+
+    def test_quote_ids_are_scoped_to_their_symbol(self) -> None:
+        book = Book()
+        book.quote("X", 10, 5, 11, 5, "mm1")
+        book.quote("Y", 20, 5, 21, 5, "mm1")
+        assert book.bid_prices("X") == [Decimal("10")]
+        book.apply_symbol_change("Y", "Z")
+        book.quote("Z", 19, 5, 22, 5, "mm1")
+        assert book.bid_prices("Z") == [Decimal("19")]
+        assert book.ask_prices("X") == [Decimal("11")]
+
+    def test_quote_can_trade_and_rejects_leave_old_quote(self) -> None:
+        book = Book()
+        book.match(ask("X", 10, 2))
+        bid_blotter, _ = book.quote("X", 10, 5, 12, 5, "mm1")
+        assert bid_blotter.trades[0].fill_quantity == 2
+        with pytest.raises(ValueError, match="must be below its ask"):
+            book.quote("X", 12, 1, 11, 1, "mm1")
+        with pytest.raises(ValueError):
+            book.quote("X", 9, -1, 13, 1, "mm1")
+        assert book.bid_prices("X") == [Decimal("10")]
+        assert book.ask_prices("X") == [Decimal("12")]
+
+
+# ── Market-maker protections ─────────────────────────────────────────────────
+
+
+class TestQuoteProtection:
+    def test_update_rate_is_throttled_per_account(self) -> None:
+        book = Book()
+        book.set_quote_protection("mm", max_updates=2, update_window=10)
+        book.quote("X", 10, 1, 11, 1, "q", account="mm")
+        book.quote("X", 10, 2, 11, 2, "q", account="mm")
+        with pytest.raises(ValueError, match="limited to 2 updates per 10"):
+            book.quote("X", 10, 3, 11, 3, "q", account="mm")
+        book.quote("Y", 10, 3, 11, 3, "other")
+        book.advance_time(10)
+        book.quote("X", 10, 3, 11, 3, "q", account="mm")
+        assert [qty for _, qty in book.level_orders("X", Side.BID, 10)] == [3]
+
+    def test_fill_burst_pulls_all_quotes_with_event(self) -> None:
+        from pyorderbook import QuotesPulled
+
+        book = Book()
+        events: list = []
+        book.set_publisher(events.extend, batch_size=1, encoding="event")
+        book.set_quote_protection("mm", max_fills=2, fill_window=5)
+        book.quote("X", 10, 5, 11, 5, "x", account="mm")
+        book.quote("Y", 20, 5, 21, 5, "y", account="mm")
+        book.match(ask("X", 10, 1))
+        assert book.ask_prices("Y") == [Decimal("21")]
+        book.match(bid("X", 11, 1))
+        pulled = [e for e in events if isinstance(e, QuotesPulled)]
+        assert [(e.symbol, e.account, e.fills, e.cancelled) for e in pulled] == [("X", "mm", 2, 4)]
+        assert book.bid_prices("X") == [] and book.ask_prices("Y") == []
+
+    def test_limits_must_pair(self) -> None:
+        with pytest.raises(ValueError, match="max_fills and fill_window"):
+            Book().set_quote_protection("mm", max_fills=3)
+
+
+# ── Drop copy ────────────────────────────────────────────────────────────────
+
+
+class TestDropCopy:
+    def test_copies_events_of_covered_accounts(self, tmp_path: Path) -> None:
+        book = Book()
+        copies: list = []
+        path = tmp_path / "drop.jsonl"
+        book.add_drop_copy(["risk"], copies.append, path=str(path))
+        mine = bid("X", 10, 5, account="risk")
+        book.match(mine)
+        book.match(bid("X", 9, 5, account="other"))
+        book.match(ask("X", 10, 2))
+        book.cancel(mine)
+
+        assert [(e.kind, e.account) for e in copies] == [
+            ("order_accepted", "risk"),
+            ("trade", "risk"),
+            ("order_cancelled", "risk"),
+        ]
+        assert copies[1].standing_order_id == mine.id
+        assert copies[2].remaining == 3
+        lines = [json.loads(line) for line in path.read_text().splitlines()]
+        assert [(line["type"], line["account"]) for line in lines] == [
+            ("order_accepted", "risk"),
+            ("trade", "risk"),
+            ("order_cancelled", "risk"),
+        ]
+        assert lines[1]["price"] == "10"
+
+    def test_needs_a_destination(self) -> None:
+        with pytest.raises(ValueError, match="sink, a path"):
+            Book().add_drop_copy(["risk"])
+
+
+# ── Latency tracking ─────────────────────────────────────────────────────────
+
+
+class TestLatencyTracking:
+    def test_blotters_carry_ordered_timestamps(self) -> None:
+        book = Book()
+        assert book.latency_stats() is None
+        assert book.match(bid("X", 10, 1)).timestamps is None
+        book.set_latency_tracking(capacity=2)
+        assert book.latency_stats() == {}
+        book.match(ask("X", 11, 1))
+        stamps = book.match(bid("X", 11, 1)).timestamps
+        order = ["received", "risk_checked", "matched", "acked"]
+        assert [stamps[k] for k in order] == sorted(stamps[k] for k in order)
+
+        book.match(bid("X", 9, 1))
+        stats = book.latency_stats()
+        assert set(stats) == {"risk_check", "match", "ack", "total"}
+        assert stats["total"]["count"] == 2
+        total = stats["total"]
+        assert total["p50_ns"] <= total["p99_ns"] <= total["max_ns"]
+        book.set_latency_tracking(False)
+        assert book.latency_stats() is None
+
+
+# ── Journal replay ───────────────────────────────────────────────────────────
+
+
+class TestJournalReplay:
+    def test_rebuilds_past_states(self) -> None:
+        book = Book()
+        book.match(bid("X", 9, 5))
+        book.record_journal()
+        assert book.journal_length == 0
+        resting = ask("X", 11, 4, expire_time=20)
+        book.match(resting)
+        book.advance_time(10)
+        book.match(bid("X", 11, 1))
+        book.amend(resting, quantity=2)
+        book.advance_time(30)
+        assert book.journal_length == 5
+
+        start = book.at_sequence(0)
+        assert start.bid_prices("X") == [Decimal("9")]
+        assert not start.ask_prices("X")
+        before_trade = book.at_sequence(2)
+        assert before_trade.current_time == 10
+        assert before_trade.get_order(resting.id).quantity == 4
+        assert book.at_time(10).get_order(resting.id).quantity == 2
+        assert book.at_time(29).get_order(resting.id) is not None
+        assert book.at_time(30).get_order(resting.id) is None
+        assert book.at_time(30).state_hash(64) == book.state_hash(64)
+        assert book.at_time(5).journal_length is None
+
+    def test_needs_the_journal(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="record_journal"):
+            book.at_sequence(0)
+        book.record_journal()
+        with pytest.raises(ValueError, match="beyond"):
+            book.at_sequence(1)
+
+    def test_checkpoints_by_count_and_time(self) -> None:
+        book = Book()
+        book.record_journal(checkpoint_every=3, checkpoint_interval=100)
+        orders = [bid("X", 10 - i, 1, expire_time=150) for i in range(4)]
+        for order in orders:
+            book.match(order)
+        assert book.journal_checkpoints == [0, 3]
+        book.advance_time(50)
+        book.advance_time(150)
+        assert book.journal_checkpoints == [0, 3, 6]
+        assert book.at_sequence(2).bid_prices("X") == [Decimal("10"), Decimal("9")]
+        assert book.at_sequence(5).current_time == 50
+        assert len(book.at_sequence(5).bid_prices("X")) == 4
+        assert book.at_time(150).bid_prices("X") == []
+        with pytest.raises(ValueError, match="positive"):
+            book.record_journal(checkpoint_every=0)
+
+
+# ── Shared snapshots ─────────────────────────────────────────────────────────
+
+
+class TestSharedSnapshots:
+    def test_reader_sees_the_latest_book(self, tmp_path: Path) -> None:
+        from pyorderbook import SharedBookReader
+
+        path = str(tmp_path / "book.ring")
+        book = Book()
+        book.match(bid("ES", 100, 5))
+        book.share_snapshots(path, symbols="E*", depth=2, slots=4, slot_size=4096)
+        reader = SharedBookReader(path)
+        assert reader.sequence == 1
+        assert reader.snapshot("ES").bids[0].quantity == 5
+        assert reader.snapshot("NQ") is None
+
+        book.match(ask("ES", 101, 3))
+        book.match(bid("NQ", 50, 1))
+        book.advance_time(7)
+        sequence, snapshots = reader.read()
+        assert sequence == 4
+        assert set(snapshots) == {"ES"}
+        assert snapshots["ES"].spread == Decimal("1")
+        assert reader.current_time == 7
+
+        book.share_snapshots(None)
+        book.match(bid("ES", 100, 1))
+        assert reader.sequence == 4
+
+    def test_rejects_other_files(self, tmp_path: Path) -> None:
+        from pyorderbook import SharedBookReader
+
+        path = tmp_path / "other"
+        path.write_bytes(b"x" * 64)
+        with pytest.raises(ValueError, match="not a pyorderbook snapshot ring"):
+            SharedBookReader(str(path))
+
+
+# ── Tensor snapshots ─────────────────────────────────────────────────────────
+
+
+class TestTensorSnapshot:
+    def test_fixed_shape_float_buffer(self) -> None:
+        book = Book()
+        book.match(bid("X", 10, 5))
+        book.match(bid("X", 9.5, 2))
+        book.match(ask("X", 11, 4))
+        tensor = book.tensor_snapshot("X", 3, fill=-1.0)
+        assert tensor.shape == (2, 2, 3)
+        view = memoryview(tensor)
+        assert (view.format, view.shape, view.readonly) == ("d", (2, 2, 3), True)
+        assert view.tolist() == [
+            [[10.0, 9.5, -1.0], [5.0, 2.0, -1.0]],
+            [[11.0, -1.0, -1.0], [4.0, -1.0, -1.0]],
+        ]
+        assert memoryview(book.tensor_snapshot("Y", 2)).tolist() == [[[0.0] * 2] * 2] * 2
+
+
+# ── Online features ──────────────────────────────────────────────────────────
+
+
+class TestOnlineFeatures:
+    def test_feature_vector_tracks_the_book(self) -> None:
+        import math
+
+        book = Book()
+        names = ["spread", "microprice", "imbalance_2", "ofi_1s"]
+        book.enable_features("X", names, clock_unit="ms")
+        assert book.feature_names("X") == names
+        spread, micro, imbalance, ofi = memoryview(book.feature_vector("X")).tolist()
+        assert math.isnan(spread) and math.isnan(micro) and math.isnan(imbalance)
+        assert ofi == 0.0
+
+        book.match(bid("X", 10, 3))
+        book.match(bid("X", 9, 3))
+        book.match(ask("X", 11, 1))
+        vector = book.feature_vector("X")
+        assert vector.shape == (4,)
+        spread, micro, imbalance, ofi = memoryview(vector).tolist()
+        assert spread == 1.0
+        assert micro == (10 * 1 + 11 * 3) / 4
+        assert imbalance == (6 - 1) / 7
+        assert ofi == 3.0 - 1.0
+
+        book.advance_time(2_000)
+        book.match(bid("X", 10, 2))
+        assert memoryview(book.feature_vector("X")).tolist()[3] == 2.0
+
+    def test_unknown_features_are_rejected(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="Invalid feature"):
+            book.enable_features("X", ["vwap"])
+        with pytest.raises(KeyError):
+            book.feature_vector("X")
+
+
+# ── Rejection codes ──────────────────────────────────────────────────────────
+
+
+class TestRejectionCodes:
+    def test_book_errors_carry_a_code(self) -> None:
+        from pyorderbook import OrderRejected
+
+        book = Book()
+        book.register_instrument(Instrument("X", tick_size=0.5))
+        with pytest.raises(OrderRejected, match="tick size") as err:
+            book.match(bid("X", 10.2, 1))
+        assert isinstance(err.value, ValueError)
+        assert err.value.code == "bad_tick"
+
+    def test_strategy_replay_reports_refused_orders(self) -> None:
+        class OffTick:
+            def __init__(self) -> None:
+                self.rejections: list = []
+
+            def on_event(self, book: Book, event: TradeBlotter) -> object:
+                return bid("X", 9.99, 1) if not self.rejections else None
+
+            def on_reject(self, book: Book, rejection: object) -> object:
+                self.rejections.append(rejection)
+                return bid("X", 9.5, 1)
+
+        book = Book()
+        book.register_instrument(Instrument("X", tick_size=0.5))
+        with pytest.raises(ValueError, match="tick size"):
+            book.replay_with_strategy(_rows(1), OffTick())
+
+        book = Book()
+        book.register_instrument(Instrument("X", tick_size=0.5))
+        strategy = OffTick()
+        blotters = book.replay_with_strategy(_rows(1, 2), strategy, on_reject="report")
+        (rejection,) = strategy.rejections
+        assert rejection.code == "bad_tick"
+        assert "9.99" in rejection.reason
+        assert rejection.time == 1
+        # The replacement bid reached the book before the second row.
+        assert [b.order.price for b in blotters] == [10, Decimal("9.5"), 10]
+        with pytest.raises(ValueError, match="on_reject"):
+            book.replay_with_strategy(_rows(3), OffTick(), on_reject="ignore")
+
+
+# ── Multi-agent simulation ───────────────────────────────────────────────────
+
+
+class _Lifter:
+    """Buys `quantity` at the price of every ask row it sees."""
+
+    def __init__(self, quantity: int) -> None:
+        self.quantity = quantity
+        self.rejections: list = []
+
+    def on_event(self, book: Book, event: TradeBlotter) -> object:
+        return bid("X", float(event.order.price), self.quantity)
+
+    def on_reject(self, book: Book, rejection: object) -> object:
+        self.rejections.append(rejection)
+        return None
+
+
+class TestMultiAgentSimulation:
+    def test_agents_trade_in_due_time_order_with_own_accounts(self) -> None:
+        from pyorderbook import Agent
+
+        fast = Agent(_Lifter(2), "fast", latency=1)
+        slow = Agent(_Lifter(2), "slow", latency=3, max_position=3)
+        book = Book()
+        blotters = book.simulate(_rows(10, 20), [slow, fast])
+
+        # Each ask row holds 5; the fast agent reaches it first both times.
+        assert [b.order.account for b in blotters] == [None, "fast", "slow", None, "fast"]
+        assert [b.order.account for b in fast.blotters] == ["fast", "fast"]
+        assert fast.positions == {"X": 4}
+        assert slow.positions == {"X": 2}
+        (rejection,) = slow.rejections
+        assert rejection.code == "risk_limit"
+        assert rejection.time == 23
+        assert len(slow.strategy.rejections) == 1
+        # Marked at the last trade price, 10, so neither agent is up or down.
+        assert fast.pnl == Decimal(0)
+        assert book.settlement_report("slow")[0].bought == 2
+
+    def test_simulate_validates_agents(self) -> None:
+        from pyorderbook import Agent
+
+        with pytest.raises(ValueError, match="distinct"):
+            Book().simulate(_rows(1), [Agent(_Lifter(1), "a"), Agent(_Lifter(1), "a")])
+        with pytest.raises(ValueError, match="latency"):
+            Agent(_Lifter(1), "a", latency=-1)
+
+
+# ── Self-trade prevention ────────────────────────────────────────────────────
+
+
+class TestSelfTradePrevention:
+    def test_groups_extend_prevention_across_accounts(self) -> None:
+        book = Book()
+        book.set_self_trade_prevention("cancel_newest")
+        book.set_anti_internalization_group("desk", ["alice", "bob"])
+        other = ask("X", 10, 2, account="carol")
+        own = ask("X", 10, 3, account="alice")
+        book.match([other, own])
+
+        blotter = book.match(bid("X", 10, 4, account="bob"))
+        assert sum(t.fill_quantity for t in blotter.trades) == 2
+        (rest,) = blotter.prevented
+        assert (rest.id, rest.quantity) == (blotter.order.id, 2)
+        assert book.get_order(blotter.order.id) is None
+        assert book.get_order(own.id) is not None
+
+        # Outside the group, the same accounts trade freely.
+        book.set_anti_internalization_group(None, ["bob"])
+        assert book.anti_internalization_group("bob") is None
+        blotter = book.match(bid("X", 10, 3, account="bob"))
+        assert blotter.prevented == []
+        assert sum(t.fill_quantity for t in blotter.trades) == 3
+
+    def test_cancel_oldest_and_both(self) -> None:
+        book = Book()
+        book.set_self_trade_prevention("cancel_oldest")
+        assert book.self_trade_prevention == "cancel_oldest"
+        own = ask("X", 10, 3, account="a")
+        other = ask("X", 11, 3, account="b")
+        book.match([own, other])
+        blotter = book.match(bid("X", 11, 4, account="a"))
+        assert [o.id for o in blotter.prevented] == [own.id]
+        assert [t.standing_order_id for t in blotter.trades] == [other.id]
+        assert book.get_order(blotter.order.id).quantity == 1
+
+        book.set_self_trade_prevention("cancel_both")
+        mine = book.match(ask("X", 12, 2, account="a")).order
+        blotter = book.match(bid("X", 12, 2, account="a"))
+        assert {o.id for o in blotter.prevented} == {mine.id, blotter.order.id}
+        assert book.snapshot("X").asks == []
+        with pytest.raises(ValueError, match="self-trade"):
+            book.set_self_trade_prevention("decrement")
+        book.set_self_trade_prevention(None)
+        assert book.self_trade_prevention is None
+
+
+# ── Cancel on disconnect ─────────────────────────────────────────────────────
+
+
+class TestCancelOnDisconnect:
+    def test_disconnect_cancels_only_the_sessions_orders(self) -> None:
+        book = Book()
+        events: list = []
+        first = bid("X", 10, 5, session_id="s1")
+        stop = ask("X", 9, 2, stop_price=9.5, session_id="s1")
+        other = bid("X", 10, 3, session_id="s2")
+        book.match(first, on_event=events.append)
+        book.match([stop, other, bid("X", 9, 1)])
+        assert first.session_id == "s1"
+
+        assert book.disconnect("s1") == [first.id, stop.id]
+        assert [(e.kind, e.remaining) for e in events] == [("order_cancelled", 5)]
+        assert book.get_order(other.id) is not None
+        assert book.disconnect("s1") == []
+
+    def test_delayed_disconnect_waits_for_the_clock_and_reconnect(self) -> None:
+        book = Book()
+        with pytest.raises(ValueError, match="advance_time"):
+            book.disconnect("s1", delay=5)
+        book.advance_time(100)
+        book.match(bid("X", 10, 5, session_id="s1"))
+        assert book.disconnect("s1", delay=5) == []
+        assert book.pending_disconnects() == {"s1": 105}
+        late = book.match(bid("X", 9, 1, session_id="s1")).order
+        book.advance_time(104)
+        assert len(book.bid_prices("X")) == 2
+        book.advance_time(105)
+        assert book.bid_prices("X") == []
+        assert book.get_order(late.id) is None
+
+        book.match(bid("X", 10, 5, session_id="s1"))
+        book.disconnect("s1", delay=5)
+        assert book.reconnect("s1")
+        assert not book.reconnect("s1")
+        book.advance_time(200)
+        assert book.bid_prices("X") == [Decimal("10")]
+
+
+# ── Rate limits ──────────────────────────────────────────────────────────────
+
+
+class TestRateLimits:
+    def test_session_limit_rejects_with_a_code(self) -> None:
+        book = Book()
+        book.set_rate_limit(2, 10, session_id="s1")
+        book.match([bid("X", 9, 1, session_id="s1"), bid("X", 9, 1, session_id="s1")])
+        with pytest.raises(ValueError, match="throttled") as err:
+            book.match(bid("X", 9, 1, session_id="s1"))
+        assert err.value.code == "throttled"
+        book.match(bid("X", 9, 1, session_id="s2"))
+
+        stats = book.rate_limit_stats()
+        assert stats["global"] is None
+        s1 = stats["sessions"]["s1"]
+        assert (s1["in_window"], s1["utilization"], s1["sent"], s1["rejected"]) == (2, 1.0, 2, 1)
+        book.advance_time(10)
+        book.match(bid("X", 9, 1, session_id="s1"))
+        assert book.rate_limit_stats()["sessions"]["s1"]["in_window"] == 1
+
+    def test_global_limit_queues_until_the_clock_frees_room(self) -> None:
+        book = Book()
+        book.advance_time(0)
+        book.set_rate_limit(1, 5, on_limit="queue")
+        book.match(ask("X", 10, 5))
+        queued = bid("X", 10, 2)
+        cancelled = bid("X", 9, 1)
+        blotter = book.match(queued)
+        book.match(cancelled)
+        assert blotter.trades == []
+        assert book.rate_limit_stats()["global"]["queued"] == 2
+        book.cancel(cancelled)
+
+        book.advance_time(4)
+        assert book.get_order(queued.id) is None
+        book.advance_time(5)
+        assert book.snapshot("X").asks[0].quantity == 3
+        assert book.rate_limit_stats()["global"]["queued"] == 0
+        with pytest.raises(ValueError, match="together"):
+            book.set_rate_limit(1, None)
+        book.set_rate_limit(None, None)
+        assert book.rate_limit_stats()["global"] is None
+
+    def test_queued_order_refused_on_release_is_counted(self) -> None:
+        book = Book()
+        book.advance_time(0)
+        book.set_rate_limit(1, 5, on_limit="queue")
+        book.match(ask("X", 10, 5))
+        expiring = bid("X", 10, 2, expire_time=3)
+        assert book.match(expiring).trades == []
+
+        book.advance_time(5)
+        assert book.get_order(expiring.id) is None
+        assert book.snapshot("X").asks[0].quantity == 5
+        stats = book.rate_limit_stats()["global"]
+        assert (stats["sent"], stats["queued"], stats["dropped"]) == (2, 0, 1)
+        assert 'pyorderbook_orders_rejected_total{symbol="X"} 1' in book.metrics_text()
+
+
+# ── Hidden and iceberg orders ────────────────────────────────────────────────
+
+
+class TestDisplayPriority:
+    def test_displayed_quantity_trades_before_hidden(self) -> None:
+        book = Book()
+        hidden = ask("X", 10, 5, display_quantity=0)
+        lit = ask("X", 10, 3)
+        book.match([hidden, lit])
+        assert hidden.display_quantity == 0
+        assert book.snapshot("X").asks[0].quantity == 3
+
+        blotter = book.match(bid("X", 10, 4))
+        assert [(t.standing_order_id, t.fill_quantity) for t in blotter.trades] == [
+            (lit.id, 3),
+            (hidden.id, 1),
+        ]
+        # Only hidden quantity is left, so the level is not shown.
+        assert book.snapshot("X").asks == []
+        assert book.get_order(hidden.id).quantity == 4
+
+    def test_iceberg_tranches_lose_priority_when_refreshed(self) -> None:
+        book = Book()
+        iceberg = bid("X", 10, 10, display_quantity=2)
+        later = bid("X", 10, 3)
+        book.match([iceberg, later])
+        assert book.snapshot("X").bids[0].quantity == 5
+
+        blotter = book.match(ask("X", 10, 4))
+        assert [(t.standing_order_id, t.fill_quantity) for t in blotter.trades] == [
+            (iceberg.id, 2),
+            (later.id, 2),
+        ]
+        assert book.snapshot("X").bids[0].quantity == 3
+        with pytest.raises(ValueError, match="display_quantity"):
+            bid("X", 10, 1, display_quantity=-1)
+
+
+# ── Stop trigger references ──────────────────────────────────────────────────
+
+
+class TestStopTriggerReference:
+    def test_bbo_stop_triggers_on_the_opposite_quote(self) -> None:
+        book = Book()
+        book.match([bid("X", 99, 5), ask("X", 101, 5), ask("X", 103, 5)])
+        stop = bid("X", 103, 2, stop_price=102, stop_trigger="bbo")
+        last = bid("X", 103, 2, stop_price=102)
+        assert (stop.stop_trigger, last.stop_trigger) == ("bbo", "last_trade")
+        book.match([stop, last])
+
+        # Lifting the 101 offer prints at 101, below the last-trade stop,
+        # but moves the ask to 103; the quote stop's fill there then
+        # triggers the last-trade stop.
+        blotter = book.match(bid("X", 101, 5))
+        first, second = blotter.cascade.activations
+        assert (first.order.id, first.generation) == (stop.id, 1)
+        assert first.trigger_trade_id is None
+        assert first.trigger_price == Decimal("103")
+        assert (second.order.id, second.generation) == (last.id, 2)
+        assert second.trigger_trade_id == first.trades[0].trade_id
+
+    def test_midpoint_stop_triggers_on_cancel_and_on_arrival(self) -> None:
+        book = Book()
+        best = bid("X", 99, 5)
+        book.match([bid("X", 95, 5), best, ask("X", 101, 5)])
+        stop = ask("X", 90, 1, stop_price=99, stop_trigger="midpoint")
+        assert book.match(stop).cascade.activations == []
+        book.cancel(best)
+        # The mid fell to 98, so the sell stop fired and sold into the 95 bid.
+        assert book.snapshot("X").bids[0].quantity == 4
+
+        through = book.match(ask("X", 90, 1, stop_price=99, stop_trigger="midpoint"))
+        assert len(through.cascade.activations) == 1
+        with pytest.raises(ValueError, match="stop orders"):
+            bid("X", 10, 1, stop_trigger="bbo")
+        with pytest.raises(ValueError, match="stop_trigger"):
+            bid("X", 10, 1, stop_price=11, stop_trigger="mark")
+
+
+# ── Metrics text ─────────────────────────────────────────────────────────────
+
+
+class TestMetricsText:
+    def test_counters_and_depth_gauges_per_symbol(self) -> None:
+        book = Book()
+        book.set_rate_limit(2, 10, session_id="s1")
+        resting = bid("X", 9, 4, session_id="s1")
+        book.match([resting, ask("X", 11, 5), ask("X", 12, 1), bid("Y", 5, 2)])
+        book.match(bid("X", 11, 2, session_id="s1"))
+        with pytest.raises(ValueError):
+            book.match(bid("X", 11, 2, session_id="s1"))
+        book.cancel(resting)
+
+        lines = book.metrics_text().splitlines()
+        assert "# TYPE pyorderbook_orders_total counter" in lines
+        for sample in [
+            'pyorderbook_orders_total{symbol="X"} 4',
+            'pyorderbook_orders_total{symbol="Y"} 1',
+            'pyorderbook_orders_rejected_total{symbol="X"} 1',
+            'pyorderbook_orders_cancelled_total{symbol="X"} 1',
+            'pyorderbook_trades_total{symbol="X"} 1',
+            'pyorderbook_traded_volume_total{symbol="X"} 2',
+            'pyorderbook_resting_orders{symbol="X"} 2',
+            'pyorderbook_depth_quantity{symbol="X",side="ask"} 4',
+            'pyorderbook_depth_levels{symbol="X",side="ask"} 2',
+            'pyorderbook_best_price{symbol="X",side="ask"} 11',
+            'pyorderbook_best_price{symbol="Y",side="bid"} 5',
+        ]:
+            assert sample in lines
+        assert not any(
+            line.startswith('pyorderbook_best_price{symbol="X",side="bid"')
+            for line in lines
+        )
+        assert not any("latency" in line for line in lines)
+
+    def test_latency_summary_when_tracking(self) -> None:
+        book = Book()
+        book.set_latency_tracking()
+        book.match([ask("X", 10, 1), bid("X", 10, 1)])
+        text = book.metrics_text()
+        assert "# TYPE pyorderbook_latency_ns summary" in text
+        assert 'quantile="0.99"' in text
+        assert "pyorderbook_latency_ns_count{" in text
+
+
+# ── Engine logging ───────────────────────────────────────────────────────────
+
+
+class TestEngineLogging:
+    def test_records_reach_the_python_logger_with_extra_fields(self) -> None:
+        import logging
+
+        records: list[logging.LogRecord] = []
+
+        class Capture(logging.Handler):
+            def emit(self, record: logging.LogRecord) -> None:
+                records.append(record)
+
+        logger = logging.getLogger("pyorderbook.engine")
+        handler = Capture()
+        logger.addHandler(handler)
+        logger.setLevel(logging.DEBUG)
+        try:
+            book = Book()
+            book.register_instrument(Instrument("X", tick_size=1))
+            resting = ask("X", 10, 5)
+            book.match([resting, bid("X", 10, 2)])
+            with pytest.raises(ValueError):
+                book.match(bid("X", 10.5, 1))
+            book.halt("X")
+            logger.setLevel(logging.WARNING)
+            book.resume("X")
+        finally:
+            logger.removeHandler(handler)
+            logger.setLevel(logging.NOTSET)
+
+        events = [(r.levelname, r.event) for r in records]
+        assert events == [
+            ("DEBUG", "accepted"),
+            ("DEBUG", "accepted"),
+            ("INFO", "trade"),
+            ("WARNING", "rejected"),
+            ("INFO", "state_changed"),
+        ]
+        trade = records[2]
+        assert (trade.symbol, trade.quantity, trade.price) == ("X", 2, "10")
+        assert trade.standing_order_id == str(resting.id)
+        assert records[3].code == "bad_tick"
+        halted = records[4]
+        assert halted.getMessage() == "X trading state continuous -> halted"
+        assert (halted.symbol, halted.to) == ("X", "halted")
+
+    def test_unconfigured_logger_is_never_consulted(self) -> None:
+        import logging
+
+        logger = logging.getLogger("pyorderbook.engine")
+        root = logging.getLogger()
+        calls: list[int] = []
+
+        def is_enabled_for(level: int) -> bool:
+            calls.append(level)
+            return logging.Logger.isEnabledFor(logger, level)
+
+        root_level = root.level
+        logger.isEnabledFor = is_enabled_for  # type: ignore[method-assign]
+        root.setLevel(logging.WARNING)
+        try:
+            book = Book()
+            book.match([ask("X", 10, 1002)] + [bid("X", 10, 1) for _ in range(1000)])
+            assert calls == []
+            logger.setLevel(logging.INFO)
+            book.match(bid("X", 10, 1))
+            assert calls == []
+            pyorderbook.sync_log_level()
+            book.match(bid("X", 10, 1))
+            assert set(calls) == {logging.INFO}
+        finally:
+            del logger.isEnabledFor
+            logger.setLevel(logging.NOTSET)
+            root.setLevel(root_level)
+            pyorderbook.sync_log_level()
+
+
+# ── Debug checks ─────────────────────────────────────────────────────────────
+
+
+class TestDebugChecks:
+    def test_sound_inputs_pass_the_checks(self) -> None:
+        assert Book().debug_checks is False
+        book = Book(debug_checks=True)
+        assert book.debug_checks is True
+        resting = bid("X", 99, 10)
+        book.match([resting, ask("X", 101, 10, display_quantity=2), ask("X", 102, 5)])
+        book.match(bid("X", 103, 2, stop_price=101))
+        book.amend(resting, quantity=6)
+        book.match(ask("X", 99, 3))
+        book.match(bid("X", 101, 4))
+        book.halt("X")
+        book.cancel(resting)
+        book.resume("X")
+        book.check_invariants()
+        assert book.snapshot("X").asks[0].price == Decimal("101")
+
+
+# ── Duplicate order ids ──────────────────────────────────────────────────────
+
+
+class TestDuplicateOrderIds:
+    def test_reject_is_the_default(self) -> None:
+        book = Book(debug_checks=True)
+        assert book.duplicate_policy == "reject"
+        order = bid("X", 10, 5)
+        book.enqueue_order(order)
+        with pytest.raises(ValueError, match="already resting") as err:
+            book.enqueue_order(order)
+        assert err.value.code == "duplicate_id"
+        assert book.snapshot("X").bids[0].quantity == 5
+        with pytest.raises(ValueError, match="duplicate policy"):
+            book.set_duplicate_policy("ignore")
+
+    def test_replace_removes_the_resting_entry(self) -> None:
+        book = Book(debug_checks=True)
+        book.set_duplicate_policy("replace")
+        order = bid("X", 10, 5)
+        book.enqueue_order(order)
+        book.enqueue_order(order)
+        assert [(lvl.price, lvl.quantity) for lvl in book.snapshot("X").bids] == [(10, 5)]
+        assert len(book.order_map) == 1
+        book.cancel(order)
+        assert book.snapshot("X").bids == []
+
+    def test_allow_multiple_reaches_the_earliest_first(self) -> None:
+        book = Book(debug_checks=True)
+        book.set_duplicate_policy("allow_multiple")
+        order = bid("X", 10, 5)
+        book.enqueue_order(order)
+        order.quantity = 3
+        book.enqueue_order(order)
+        assert book.snapshot("X").bids[0].quantity == 8
+
+        # The earlier copy fills first and the later one takes over its id.
+        book.match(ask("X", 10, 6))
+        assert book.order_map[order.id].quantity == 2
+        book.enqueue_order(order)
+        book.cancel(order)
+        assert book.snapshot("X").bids[0].quantity == 3
+        book.enqueue_order(order)
+        assert book.cancel_all("X") == 2
+        assert book.snapshot("X").bids == []
+
+
+# ── Capacity limits ──────────────────────────────────────────────────────────
+
+
+class TestCapacityLimits:
+    def test_reject_refuses_orders_past_a_cap(self) -> None:
+        book = Book(debug_checks=True)
+        assert book.capacity_stats() is None
+        book.set_capacity_limits(max_orders_per_symbol=2, max_levels_per_side=2)
+        first = bid("X", 10, 1)
+        book.match([first, bid("X", 9, 1), bid("Y", 8, 1)])
+        with pytest.raises(ValueError, match="2 orders per symbol") as err:
+            book.match(bid("X", 10, 1))
+        assert err.value.code == "capacity"
+        book.cancel(first)
+        book.match(bid("X", 10, 1))
+        book.set_capacity_limits(max_levels_per_side=1)
+        book.match(bid("Y", 8, 1))
+        with pytest.raises(ValueError, match="1 levels per side"):
+            book.match(bid("Y", 7, 1))
+        # Stop orders are held, not resting.
+        book.match(bid("Y", 20, 1, stop_price=15))
+
+        stats = book.capacity_stats()
+        assert (stats["max_levels_per_side"], stats["on_limit"]) == (1, "reject")
+        assert (stats["rejected"], stats["evicted"]) == (1, 0)
+        with pytest.raises(ValueError, match="at least 1"):
+            book.set_capacity_limits(max_total_orders=0)
+        with pytest.raises(ValueError, match="on_limit"):
+            book.set_capacity_limits(max_total_orders=5, on_limit="drop")
+        book.set_capacity_limits()
+        assert book.capacity_stats() is None
+
+    def test_evict_cancels_the_worst_priced_orders(self) -> None:
+        book = Book(debug_checks=True)
+        book.set_capacity_limits(max_total_orders=3, on_limit="evict")
+        deep = bid("X", 8, 1)
+        book.match([bid("X", 10, 1), deep, bid("X", 9, 1), bid("X", 9, 1)])
+        assert [(lvl.price, lvl.quantity) for lvl in book.snapshot("X").bids] == [(10, 1), (9, 2)]
+        assert deep.id not in book.order_map
+
+        # Eviction works on the arriving order's side, so a new order at
+        # the worst price there is itself evicted.
+        book.match(ask("X", 12, 1))
+        assert book.snapshot("X").asks == []
+        book.set_capacity_limits(max_levels_per_side=1, on_limit="evict")
+        book.enqueue_orders([ask("X", 12, 1), ask("X", 11, 1)])
+        assert [lvl.price for lvl in book.snapshot("X").asks] == [11]
+        # Caps bite as orders rest: the bid side keeps its two levels.
+        assert len(book.snapshot("X").bids) == 2
+        assert book.capacity_stats()["evicted"] == 1
+
+
+# ── Allocation pools ─────────────────────────────────────────────────────────
+
+
+class TestAllocationPools:
+    def test_stats_show_levels_and_entries_reused(self) -> None:
+        book = Book()
+        for _ in range(3):
+            book.match([ask("X", 101, 1), ask("X", 102, 1)])
+            book.match(bid("X", 102, 2))
+        stats = book.stats()
+        assert (stats["resting_orders"], stats["price_levels"]) == (0, 0)
+        # The first round allocates; the filled asks' entries and emptied
+        # levels serve the later rounds.
+        assert (stats["levels_allocated"], stats["levels_reused"]) == (2, 4)
+        assert (stats["entries_allocated"], stats["entries_reused"]) == (2, 4)
+        assert (stats["spare_levels"], stats["spare_entries"]) == (2, 2)
+
+
+# ── Level cache ──────────────────────────────────────────────────────────────
+
+
+class TestLevelCache:
+    def test_repeated_prices_hit_the_cache(self) -> None:
+        book = Book(debug_checks=True)
+        orders = [bid("X", 100, 1) for _ in range(3)]
+        book.match(orders)
+        book.cancel(orders[1])
+        stats = book.stats()
+        # The first order opens the level; the rest find it from the cache.
+        assert (stats["level_cache_hits"], stats["level_cache_misses"]) == (3, 1)
+
+        # A level opening below shifts the cached index; the next lookup
+        # misses, searches, and is cached again.
+        book.match(bid("X", 99, 1))
+        book.match([bid("X", 100, 1), bid("X", 100, 1)])
+        stats = book.stats()
+        assert (stats["level_cache_hits"], stats["level_cache_misses"]) == (4, 3)
+        assert [lvl.quantity for lvl in book.snapshot("X").bids] == [4, 1]
+
+
+# ── Boundary conversion ──────────────────────────────────────────────────────
+
+
+class TestBoundaryConversion:
+    def test_batch_built_ids_and_prices_behave_like_python_ones(self) -> None:
+        import pickle
+
+        book = Book()
+        price = Decimal("100.10")
+        orders = [bid("X", price, 1), bid("X", price, 2), bid("X", Decimal("99.5"), 1)]
+        book.match(orders)
+
+        ids = list(book.order_map)
+        assert sorted(ids) == sorted(o.id for o in orders)
+        for key in ids:
+            assert type(key) is uuid.UUID and key.version == 4
+            assert pickle.loads(pickle.dumps(key)) == key
+            assert hash(key) == hash(uuid.UUID(str(key)))
+        assert [oid for oid, _ in book.level_orders("X", Side.BID, price)] == [
+            orders[0].id,
+            orders[1].id,
+        ]
+
+        assert book.bid_prices("X") == [Decimal("100.1"), Decimal("99.5")]
+        assert list(book.level_map["X"][Side.BID]) == book.bid_prices("X")
+
+
+# ── Symbol interning ─────────────────────────────────────────────────────────
+
+
+class TestSymbolInterning:
+    def test_symbols_round_trip_through_their_ids(self) -> None:
+        book = Book()
+        book.match([bid("AAPL", 10, 1), ask("MSFT", 20, 2), bid("IBM", 5, 3)])
+        book.match(ask("AAPL", 11, 4))
+
+        assert {order.symbol for order in book.order_map.values()} == {"AAPL", "MSFT", "IBM"}
+        assert book.snapshot("MSFT").asks[0].quantity == 2
+        assert book.ask_prices("AAPL") == [Decimal("11")]
+        assert not book.bid_prices("MSFT")
+        assert set(book.snapshot_all()) == {"AAPL", "MSFT", "IBM"}
+
+        trades = book.match(ask("IBM", 5, 3))
+        assert trades.trades and not book.bid_prices("IBM")
+
+
+# ── Benchmarks ───────────────────────────────────────────────────────────────
+
+
+class TestRunBenchmarks:
+    def test_times_each_scenario_and_gates_on_a_baseline(self) -> None:
+        from pyorderbook import run_benchmarks
+
+        results = run_benchmarks(ops=200, runs=1)
+        assert set(results) == {"insert_heavy", "cancel_heavy", "match_heavy"}
+        for result in results.values():
+            assert result["ops"] == 200
+            assert result["best_ns"] > 0
+            assert result["ns_per_op"] == pytest.approx(result["best_ns"] / 200)
+
+        slow = {name: {"ns_per_op": 1e12} for name in results}
+        run_benchmarks(ops=200, runs=1, baseline=slow)
+        fast = {"match_heavy": {"ns_per_op": 1e-6}}
+        with pytest.raises(RuntimeError, match="match_heavy"):
+            run_benchmarks(ops=200, runs=1, baseline=fast)
+        with pytest.raises(ValueError):
+            run_benchmarks(ops=0)
+
+
+# ── Profiling ────────────────────────────────────────────────────────────────
+
+
+class TestProfiling:
+    def test_reports_phase_shares_of_sampled_orders(self) -> None:
+        book = Book()
+        assert book.profile_report() is None
+        book.set_profiling(sample_every=2)
+        book.match([ask("X", 10 + i % 5, 1) for i in range(10)])
+        book.match(bid("X", 20, 3))
+
+        report = book.profile_report()
+        assert (report["orders"], report["sampled"], report["sample_every"]) == (11, 6, 2)
+        phases = report["phases"]
+        assert set(phases) == {
+            "validation",
+            "level_lookup",
+            "matching",
+            "other",
+            "python_objects",
+        }
+        assert phases["matching"]["mean_ns"] > 0
+        assert sum(p["share"] for p in phases.values()) == pytest.approx(1.0)
+        assert report["bound"] in ("boundary", "matching")
+
+        with pytest.raises(ValueError):
+            book.set_profiling(sample_every=0)
+        book.set_profiling(False)
+        assert book.profile_report() is None
+
+
+# ── Sharded book ─────────────────────────────────────────────────────────────
+
+
+class TestShardedBook:
+    def test_threads_on_different_symbols_match_independently(self) -> None:
+        import threading
+
+        from pyorderbook import ShardedBook
+
+        book = ShardedBook()
+        symbols = ["A", "B", "C", "D"]
+
+        def trade(symbol: str) -> None:
+            for _ in range(200):
+                book.match(ask(symbol, 10, 2))
+                book.match([bid(symbol, 10, 1), bid(symbol, 10, 1)])
+
+        threads = [threading.Thread(target=trade, args=(s,)) for s in symbols]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+
+        assert book.symbols == symbols
+        for symbol in symbols:
+            snapshot = book.snapshot(symbol)
+            assert not snapshot.bids and not snapshot.asks
+        assert book.snapshot("Z") is None
+
+        resting = ask("A", 11, 3)
+        assert not book.match(resting).trades
+        assert book.cancel(resting)
+        assert not book.cancel(resting)
+
+
+# ── Vectorized cancel ────────────────────────────────────────────────────────
+
+
+class TestCancelMany:
+    def test_cancels_ids_from_buffers_and_iterables(self) -> None:
+        import array
+
+        book = Book()
+        orders = [bid("X", 10, 1) for _ in range(4)]
+        book.match(orders)
+        missing = uuid.uuid4()
+
+        packed = b"".join(o.id.bytes for o in orders[:2]) + missing.bytes
+        assert book.cancel_many(packed) == [True, True, False]
+        assert book.cancel_many([orders[2], str(orders[3].id), orders[0].id]) == [
+            True,
+            True,
+            False,
+        ]
+        assert not book.bid_prices("X")
+
+        # 64-bit integers are sequence ids, n standing for UUID(int=n).
+        assert book.cancel_many(array.array("Q", [2, 7])) == [False, False]
+
+        with pytest.raises(TypeError):
+            book.cancel_many(array.array("d", [1.0]))
+
+    def test_accepts_numpy_arrays(self) -> None:
+        np = pytest.importorskip("numpy")
+
+        book = Book()
+        orders = [ask("X", 10, 1) for _ in range(3)]
+        book.match(orders)
+        ids = np.array([o.id.bytes for o in orders], dtype="S16")
+        assert book.cancel_many(ids[::-1].copy()) == [True, True, True]
+        assert book.cancel_many(np.frombuffer(ids.tobytes(), np.uint8).reshape(3, 16)) == [
+            False
+        ] * 3
+
+
+# ── Validators ───────────────────────────────────────────────────────────────
+
+
+class TestValidators:
+    def test_pipeline_rejects_with_the_failing_validator(self) -> None:
+        from pyorderbook import OrderRejected
+
+        book = Book()
+        book.add_symbol_allow_list({"X", "Y"})
+        book.add_price_band("0.1", references={"Y": 50})
+        book.add_validator("lot_cap", lambda order: order.quantity <= 100 or "too large")
+        assert book.validators == ["symbol_allow_list", "price_band", "lot_cap"]
+
+        def rejected(order: object) -> tuple[str, str]:
+            with pytest.raises(OrderRejected) as info:
+                book.match(order)
+            return info.value.code, info.value.validator
+
+        assert rejected(bid("Z", 10, 1)) == ("validation", "symbol_allow_list")
+        assert rejected(bid("Y", 60, 1)) == ("validation", "price_band")
+        book.match(bid("Y", 54, 1))
+        # X has no reference until it trades.
+        book.match([bid("X", 10, 5), ask("X", 10, 5)])
+        assert rejected(ask("X", 12, 1)) == ("validation", "price_band")
+        with pytest.raises(OrderRejected, match="too large"):
+            book.match(bid("X", 10, 101))
+
+        with pytest.raises(ValueError):
+            book.add_price_band("0.2")
+        with pytest.raises(TypeError):
+            book.add_validator("bad", 3)
+        assert book.remove_validator("price_band")
+        assert not book.remove_validator("price_band")
+        book.match(ask("X", 12, 1))
+
+
+# ── Trade enrichment ─────────────────────────────────────────────────────────
+
+
+class TestTradeEnrichment:
+    def test_enrichers_add_fields_to_returned_and_published_trades(self) -> None:
+        import json
+
+        payloads: list[str] = []
+        book = Book()
+        book.set_publisher(payloads.append, encoding="json")
+        book.add_fee_enricher("-0.001", "0.002")
+        book.add_venue_enricher("XSIM")
+        book.add_liquidity_enricher()
+        book.add_trade_enricher(
+            "size_bucket", lambda trade: {"block": trade.extra["incoming_fee"] > 1}
+        )
+        assert book.trade_enrichers == ["fees", "venue", "liquidity", "size_bucket"]
+
+        book.match(ask("X", 50, 10))
+        trade = book.match(bid("X", 50, 10)).trades[0]
+        assert trade.extra == {
+            "incoming_fee": Decimal("1"),
+            "standing_fee": Decimal("-0.5"),
+            "venue": "XSIM",
+            "liquidity": "removed",
+            "block": False,
+        }
+        published = [e for p in payloads for e in json.loads(p) if e["type"] == "trade"]
+        assert published[0]["extra"]["venue"] == "XSIM"
+        assert Decimal(published[0]["extra"]["incoming_fee"]) == 1
+
+        with pytest.raises(ValueError):
+            book.add_venue_enricher("OTHER")
+        with pytest.raises(TypeError):
+            book.add_trade_enricher("bad", 3)
+        assert book.remove_trade_enricher("fees")
+        assert not book.remove_trade_enricher("fees")
+
+    def test_failing_enricher_is_reported_and_the_trade_kept(self) -> None:
+        import sys
+
+        reported: list[object] = []
+        book = Book()
+        book.add_venue_enricher("XSIM")
+        book.add_trade_enricher("broken", lambda trade: 1 / 0)
+        hook = sys.unraisablehook
+        sys.unraisablehook = reported.append
+        try:
+            book.match(ask("X", 50, 1))
+            blotter = book.match(bid("X", 50, 1))
+        finally:
+            sys.unraisablehook = hook
+        assert len(reported) == 1
+        assert blotter.trades[0].extra == {"venue": "XSIM"}
+
+
+# ── Conditional orders ───────────────────────────────────────────────────────
+
+
+class TestConditionalOrders:
+    def test_order_waits_until_every_clause_holds(self) -> None:
+        from pyorderbook import Condition
+
+        book = Book()
+        book.match([bid("X", 99, 5), ask("X", 102, 5)])
+        condition = Condition.spread_at_most(1) & Condition.depth_at_least(8)
+        assert repr(condition) == "Condition(spread <= 1 & depth >= 8)"
+        waiting = bid("X", 102, 10, condition=condition)
+        assert waiting.condition == condition
+        assert book.match(waiting).cascade.activations == []
+        assert book.snapshot("X").bids[0].quantity == 5
+
+        # The spread narrows to 1 but the offer shows only 5.
+        assert book.match(ask("X", 100, 5)).cascade.activations == []
+        blotter = book.match(ask("X", 100, 5))
+        (activation,) = blotter.cascade.activations
+        assert activation.order.id == waiting.id
+        assert activation.trigger_trade_id is None
+        assert activation.trigger_price == Decimal("100")
+        assert sum(t.fill_quantity for t in activation.trades) == 10
+
+    def test_conditional_orders_cancel_and_reject_like_stops(self) -> None:
+        from pyorderbook import Condition
+
+        book = Book()
+        waiting = ask("X", 90, 1, condition=Condition.depth_at_least(3, side=Side.BID))
+        book.match(waiting)
+        book.cancel(waiting)
+        with pytest.raises(KeyError):
+            book.cancel(waiting)
+        book.match([bid("X", 95, 3), ask("X", 90, 1, condition=Condition.depth_at_least(3))])
+        assert book.snapshot("X").bids[0].quantity == 2
+
+        with pytest.raises(ValueError, match="stop_price or a condition"):
+            bid("X", 10, 1, stop_price=11, condition=Condition.spread_at_most(1))
+        with pytest.raises(ValueError):
+            Condition.spread_at_most(-1)
+        with pytest.raises(ValueError):
+            Condition.depth_at_least(0)
+
+
+# ── Iceberg detection ────────────────────────────────────────────────────────
+
+
+class TestIcebergDetection:
+    def test_refills_after_executions_are_reported_per_symbol(self) -> None:
+        book = Book()
+        assert book.iceberg_report() is None
+        book.set_iceberg_detection()
+
+        def add(order_id: str, quantity: int, price: str = "100") -> dict[str, object]:
+            return {
+                "type": "add_order",
+                "order_id": order_id,
+                "symbol": "X",
+                "side": "ask",
+                "price": price,
+                "quantity": quantity,
+            }
+
+        def execute(order_id: str) -> dict[str, object]:
+            return {"type": "execute_order", "order_id": order_id}
+
+        updates = [add("t0", 50)]
+        for n in range(1, 4):
+            updates += [execute(f"t{n - 1}"), add(f"t{n}", 50)]
+        # A cancel is not an execution, and a different size is no refill.
+        updates += [add("c", 20, "101"), {"type": "cancel_order", "order_id": "c"}]
+        updates += [add("c2", 20, "101"), execute("c2"), add("c3", 30, "101")]
+        book.apply_update(updates)
+
+        assert book.iceberg_report() == {
+            "X": [{"side": "ask", "price": Decimal("100"), "size": 50, "refills": 3}]
+        }
+        assert book.iceberg_report("Y") == {"Y": []}
+
+        book.set_iceberg_detection(window=2, min_refills=1)
+        book.apply_update([execute("t3"), add("other", 5, "99"), add("t4", 50)])
+        assert book.iceberg_report()["X"][0]["refills"] == 1
+        with pytest.raises(ValueError):
+            book.set_iceberg_detection(window=0)
+        book.set_iceberg_detection(False)
+        assert book.iceberg_report() is None
+
+
+# ── Order flow ──────────────────────────────────────────────────────────────
+
+
+class TestOrderFlow:
+    def test_rows_record_ofi_and_classified_trades(self) -> None:
+        book = Book()
+        resting = bid("X", 99, 5)
+        book.match([ask("X", 101, 5), resting])
+        with pytest.raises(KeyError):
+            book.order_flow("X")
+        book.enable_order_flow("X")
+
+        book.advance_time(10)
+        book.match(bid("X", 101, 3))
+        book.advance_time(20)
+        book.cancel(resting)
+        flow = {name: memoryview(column).tolist() for name, column in book.order_flow("X").items()}
+        assert flow["buy_volume"] == [3.0, 0.0]
+        assert flow["sell_volume"] == [0.0, 0.0]
+        assert flow["signed_volume"] == [3.0, 0.0]
+        # Shrinking the offer is buying pressure; pulling the bid selling.
+        assert flow["ofi"] == [3.0, -5.0]
+        assert flow["mid"][0] == 100.0
+        assert flow["mid"][1] != flow["mid"][1]  # NaN: one-sided
+        assert flow["time"] == [10.0, 20.0]
+
+        assert len(memoryview(book.order_flow("X", clear=True)["ofi"])) == 2
+        assert len(memoryview(book.order_flow("X")["ofi"])) == 0
+
+    def test_feed_executions_are_classified_against_the_quote(self) -> None:
+        book = Book()
+        book.enable_order_flow("X")
+        book.apply_update(
+            [
+                {"type": "add_level", "symbol": "X", "side": "bid", "price": "99", "quantity": 4},
+                {
+                    "type": "add_order",
+                    "order_id": "a",
+                    "symbol": "X",
+                    "side": "ask",
+                    "price": "101",
+                    "quantity": 10,
+                },
+                {"type": "execute_order", "order_id": "a", "quantity": 6},
+            ]
+        )
+        flow = book.order_flow("X")
+        assert memoryview(flow["buy_volume"]).tolist() == [0.0, 0.0, 6.0]
+        assert memoryview(flow["ofi"]).tolist() == [4.0, -10.0, 6.0]
+        book.enable_order_flow("X", False)
+        with pytest.raises(KeyError):
+            book.order_flow("X")
+
+
+# ── Execution quality ───────────────────────────────────────────────────────
+
+
+class TestExecutionQuality:
+    def test_spreads_are_measured_against_the_quote_and_later_mid(self) -> None:
+        book = Book()
+        book.match([bid("X", 99, 5), ask("X", 101, 5)])
+        with pytest.raises(KeyError):
+            book.execution_quality("X")
+        book.enable_execution_quality("X")
+
+        book.advance_time(10)
+        book.match(bid("X", 101, 2))
+        book.advance_time(20)
+        book.match(bid("X", 100, 1))
+        book.advance_time(30)
+
+        quality = book.execution_quality("X", 5)
+        assert quality["trades"] == 1
+        assert quality["volume"] == 2
+        assert quality["pending"] == 0
+        assert quality["effective_spread"] == Decimal("2")
+        assert quality["price_improvement"] == Decimal("0")
+        # Five clock units on the mid was still 100: the spread was all earned.
+        assert quality["realized_spread"] == Decimal("2")
+        assert quality["price_impact"] == Decimal("0")
+        # By 25 the new bid lifted the mid to 100.5.
+        quality = book.execution_quality("X", 15)
+        assert quality["realized_spread"] == Decimal("1")
+        assert quality["price_impact"] == Decimal("1")
+
+        quality = book.execution_quality("X", 100)
+        assert quality["pending"] == 1
+        assert quality["realized_spread"] is None
+        with pytest.raises(ValueError):
+            book.execution_quality("X", -1)
+        book.enable_execution_quality("X", False)
+        with pytest.raises(KeyError):
+            book.execution_quality("X")
+
+
+# ── Liquidity estimates ─────────────────────────────────────────────────────
+
+
+class TestLiquidityEstimates:
+    def test_impact_and_resilience_come_from_the_order_flow(self) -> None:
+        book = Book()
+        book.match([bid("X", 99, 5), bid("X", 98, 3), ask("X", 101, 5), ask("X", 102, 5)])
+        with pytest.raises(KeyError):
+            book.liquidity_estimates("X", 10)
+        book.enable_order_flow("X")
+
+        for time, order in [
+            (10, bid("X", 101, 2)),
+            (20, bid("X", 101, 3)),  # clears the offer: mid 100.5
+            (30, ask("X", 101, 5)),  # the offer is back: mid 100
+            (40, ask("X", 99, 5)),  # clears the bid, leaving 8 at the touch
+            (50, bid("X", 98.5, 5)),  # touch depth back to 10
+        ]:
+            book.advance_time(time)
+            book.match(order)
+
+        estimates = book.liquidity_estimates("X", 10)
+        assert estimates["intervals"] == 4
+        assert estimates["kyle_lambda"] > 0
+        assert 0 < estimates["r_squared"] <= 1
+        assert estimates["large_trade"] == 5.0
+        assert (estimates["large_trades"], estimates["recovered"]) == (1, 1)
+        assert estimates["resilience"] == 10.0
+
+        # Every trade large, except the first row's, whose depth before is
+        # not recorded. Clearing the offer at 20 left the touch deeper.
+        estimates = book.liquidity_estimates("X", 10, large_trade=1)
+        assert (estimates["large_trades"], estimates["recovered"]) == (2, 2)
+        assert estimates["resilience"] == 5.0
+        with pytest.raises(ValueError):
+            book.liquidity_estimates("X", 0)
+
+
+# ── Volume profile ──────────────────────────────────────────────────────────
+
+
+class TestVolumeProfile:
+    def test_volume_and_time_at_price_per_bucket(self) -> None:
+        book = Book()
+        book.match([bid("X", 99, 5), ask("X", 101, 5), ask("X", 102, 5)])
+        with pytest.raises(KeyError):
+            book.volume_profile("X", 1)
+        book.enable_volume_profile("X")
+
+        book.advance_time(10)
+        book.match(bid("X", 101, 5))  # mid 100 -> 100.5
+        book.advance_time(30)
+        book.match(bid("X", 102, 2))
+
+        assert book.volume_profile("X", 1) == [
+            {"price": Decimal("100"), "volume": 0, "time": 30},
+            {"price": Decimal("101"), "volume": 5, "time": 0},
+            {"price": Decimal("102"), "volume": 2, "time": 0},
+        ]
+        assert book.volume_profile("X", "0.5") == [
+            {"price": Decimal("100"), "volume": 0, "time": 10},
+            {"price": Decimal("100.5"), "volume": 0, "time": 20},
+            {"price": Decimal("101"), "volume": 5, "time": 0},
+            {"price": Decimal("102"), "volume": 2, "time": 0},
+        ]
+        with pytest.raises(ValueError):
+            book.volume_profile("X", 0)
+        book.enable_volume_profile("X", False)
+        with pytest.raises(KeyError):
+            book.volume_profile("X", 1)
+
+
+# ── Currencies ──────────────────────────────────────────────────────────────
+
+
+class TestCurrencies:
+    def test_settlement_lines_convert_into_a_chosen_currency(self) -> None:
+        book = Book()
+        book.register_instrument(Instrument("ES", multiplier=50))
+        book.register_instrument(Instrument("FDAX", currency="EUR", multiplier=25))
+        for symbol, price in [("ES", 100), ("FDAX", 200), ("X", 10)]:
+            book.match(ask(symbol, price, 1, account="mm"))
+            book.match(bid(symbol, price, 1, account="fund"))
+
+        lines = book.settlement_report("fund")
+        assert [(line.symbol, line.currency) for line in lines] == [
+            ("ES", "USD"),
+            ("FDAX", "EUR"),
+            ("X", None),
+        ]
+        with pytest.raises(ValueError, match="set_base_currency"):
+            book.set_fx_rate("EUR", "1.25")
+        book.set_base_currency("USD")
+        assert book.base_currency == "USD"
+        with pytest.raises(ValueError, match="EUR"):
+            book.settlement_report("fund", currency="USD")
+        book.set_fx_rate("EUR", "1.25")
+
+        lines = book.settlement_report("fund", taker_fee="0.01", currency="USD")
+        assert [(line.currency, line.buy_notional) for line in lines] == [
+            ("USD", Decimal("5000")),
+            ("USD", Decimal("6250")),
+            ("USD", Decimal("10")),
+        ]
+        assert lines[1].fees == Decimal("62.5")
+        _, fdax, _ = book.settlement_report("fund", currency="EUR")
+        assert (fdax.currency, fdax.buy_notional) == ("EUR", Decimal("5000"))
+
+        assert book.convert(100, "EUR") == Decimal("125")
+        assert book.convert(125, "USD", "EUR") == Decimal("100")
+        with pytest.raises(ValueError):
+            book.convert(1, "JPY")
+        with pytest.raises(ValueError):
+            book.set_fx_rate("GBP", 0)
+
+
+# ── Corporate actions ───────────────────────────────────────────────────────
+
+
+class TestCorporateActions:
+    def test_split_restates_orders_in_priority_order(self) -> None:
+        from pyorderbook import Event, SplitApplied
+
+        events: list[Event] = []
+        book = Book(debug_checks=True)
+        book.set_publisher(events.extend, encoding="event")
+        book.register_instrument(Instrument("X", tick_size="0.01"))
+        better, worse = bid("X", 100.01, 3), bid("X", 100.00, 5)
+        offer = ask("X", 100.03, 4)
+        for order in [worse, better, offer]:
+            book.match(order)
+        book.match(bid("X", 100.03, 1))
+
+        assert book.apply_split("X", 2) == []
+        assert book.bid_prices("X") == [Decimal("50")]
+        assert book.ask_prices("X") == [Decimal("50.02")]
+        assert book.level_orders("X", Side.BID, 50) == [(better.id, 6), (worse.id, 10)]
+        restated = book.get_order(offer.id)
+        assert (restated.quantity, restated.original_quantity) == (6, 8)
+        assert restated.filled_quantity == 2
+        (split,) = [e for e in events if isinstance(e, SplitApplied)]
+        assert (split.symbol, split.ratio) == ("X", Decimal("2"))
+
+        odd_lot, round_lot = bid("Y", 5, 7), bid("Y", 4, 30)
+        book.match(odd_lot)
+        book.match(round_lot)
+        assert [o.id for o in book.apply_split("Y", "0.1")] == [odd_lot.id]
+        assert book.level_orders("Y", Side.BID, 40) == [(round_lot.id, 3)]
+        with pytest.raises(ValueError, match="ratio"):
+            book.apply_split("X", 0)
+
+    def test_overflowing_split_leaves_the_book_unchanged(self) -> None:
+        book = Book(debug_checks=True)
+        book.record_journal()
+        book.match([bid("X", 10, 1), bid("X", 9, 10**17)])
+        before = book.state_hash(64)
+        with pytest.raises(ValueError, match="overflows"):
+            book.apply_split("X", "100000000000000")
+        with pytest.raises(ValueError, match="overflows"):
+            book.apply_split("X", "0." + "0" * 27 + "1")
+        assert book.state_hash(64) == before
+        assert book.at_sequence(book.journal_length).state_hash(64) == before
+        book.check_invariants()
+
+    def test_symbol_change_moves_the_book_to_the_new_name(self) -> None:
+        from pyorderbook import Event, SymbolChanged
+
+        events: list[Event] = []
+        book = Book(debug_checks=True)
+        book.set_publisher(events.extend, encoding="event")
+        book.register_instrument(Instrument("FB", tick_size="0.01"))
+        resting = bid("FB", 10, 5)
+        book.match(resting)
+        book.record_journal()
+
+        book.apply_symbol_change("FB", "META")
+        assert book.bid_prices("META") == [Decimal("10")]
+        assert not book.bid_prices("FB")
+        assert book.instrument("META").symbol == "META"
+        assert book.instrument("FB") is None
+        assert book.get_order(resting.id).snapshot().symbol == "META"
+        (renamed,) = [e for e in events if isinstance(e, SymbolChanged)]
+        assert (renamed.symbol, renamed.new_symbol) == ("FB", "META")
+
+        blotter = book.match(ask("META", 10, 2))
+        assert blotter.trades[0].standing_order_id == resting.id
+        book.match(bid("FB", 1, 1))
+        assert book.bid_prices("FB") == [Decimal("1")]
+        with pytest.raises(ValueError, match="in use"):
+            book.apply_symbol_change("META", "FB")
+        assert book.at_sequence(book.journal_length).state_hash(64) == book.state_hash(64)
+
+
+# ── Session schedules ───────────────────────────────────────────────────────
+
+
+class TestSessionSchedule:
+    def test_clock_moves_symbol_through_its_day(self) -> None:
+        from pyorderbook import OrderRejected
+
+        book = Book()
+        book.set_session_schedule("X", 30, 60, pre_open=10, day_length=100)
+        book.advance_time(5)
+        # Overnight: yesterday's close has passed.
+        assert book.trading_state("X") == TradingState.CLOSED
+        with pytest.raises(OrderRejected, match="closed"):
+            book.match(bid("X", 101, 5))
+
+        book.advance_time(10)
+        assert book.trading_state("X") == TradingState.PRE_OPEN
+        book.match(bid("X", 101, 5))
+        book.match(ask("X", 100, 3))
+        expiring = ask("X", 99, 4, expire_time=20)
+        book.match(expiring)
+        assert book.ask_prices("X") == [Decimal("99"), Decimal("100")]
+
+        # The GTD ask expires before the opening auction crosses the rest.
+        assert [o.id for o in book.advance_time(45)] == [expiring.id]
+        assert book.trading_state("X") == TradingState.CONTINUOUS
+        assert book.session_stats("X").volume == 3
+        assert not book.ask_prices("X")
+
+        book.advance_time(115)
+        assert book.trading_state("X") == TradingState.PRE_OPEN
+        book.halt("X")
+        book.advance_time(130)
+        assert book.trading_state("X") == TradingState.HALTED
+        assert book.remove_session_schedule("X")
+        assert not book.remove_session_schedule("X")
+
+    def test_post_market_session_trades_after_the_close(self) -> None:
+        book = Book()
+        book.set_session_schedule("X", 0, 10, post=20)
+        book.advance_time(0)
+        book.match(bid("X", 10, 1))
+        book.advance_time(10)
+        assert book.trading_state("X") == TradingState.CONTINUOUS
+        assert book.match(ask("X", 10, 1)).trades
+        book.advance_time(20)
+        assert book.trading_state("X") == TradingState.CLOSED
+        with pytest.raises(ValueError, match="after open"):
+            book.set_session_schedule("X", 10, 10)
+
+
+# ── Cash flows ──────────────────────────────────────────────────────────────
+
+
+class TestCashFlows:
+    def test_dividends_pay_longs_and_charge_shorts_on_the_clock(self) -> None:
+        book = Book()
+        book.advance_time(0)
+        book.match(ask("X", 100, 10, account="mm"))
+        book.match(bid("X", 100, 4, account="fund"))
+        book.add_cash_flow("X", 10, "0.5")
+        assert book.pending_cash_flows == 1
+
+        book.advance_time(9)
+        assert all(line.cash_flows == 0 for line in book.settlement_report())
+        book.advance_time(10)
+        assert book.pending_cash_flows == 0
+        (fund,) = book.settlement_report("fund")
+        assert fund.cash_flows == Decimal("2")
+        assert fund.cash_delta == Decimal("-398")
+        (mm,) = book.settlement_report("mm")
+        assert mm.cash_flows == Decimal("-2")
+
+    def test_funding_charges_longs_a_rate_of_the_marked_notional(self) -> None:
+        book = Book()
+        book.match(ask("X", 100, 10, account="mm"))
+        book.match(bid("X", 100, 10, account="fund"))
+        book.match(bid("X", 98, 1))
+        book.match(ask("X", 102, 1))
+        book.add_cash_flow("X", 5, "0.01", kind="funding")
+        book.advance_time(5)
+        # 1% of 10 marked at the 100 midpoint.
+        (fund,) = book.settlement_report("fund")
+        assert fund.cash_flows == Decimal("-10")
+        (mm,) = book.settlement_report("mm")
+        assert mm.cash_flows == Decimal("10")
+
+    def test_positions_carried_across_a_session_roll_are_paid(self) -> None:
+        book = Book()
+        book.advance_time(0)
+        book.match(ask("X", 100, 10, account="mm"))
+        book.match(bid("X", 100, 4, account="fund"))
+        book.add_cash_flow("X", 10, "0.5")
+        book.roll_session()
+        assert book.settlement_report() == []
+        book.advance_time(10)
+        (fund,) = book.settlement_report("fund")
+        assert (fund.net_quantity, fund.cash_flows, fund.cash_delta) == (0, 2, 2)
+        (mm,) = book.settlement_report("mm")
+        assert mm.cash_flows == Decimal("-2")
+
+        # Trades in the new session add to the carried position.
+        book.match(bid("X", 100, 6, account="fund"))
+        book.add_cash_flow("X", 20, "1")
+        book.advance_time(20)
+        (fund,) = book.settlement_report("fund")
+        assert fund.cash_flows == Decimal("12")
+
+    def test_rejects_past_timestamps_and_unknown_kinds(self) -> None:
+        book = Book()
+        book.advance_time(10)
+        with pytest.raises(ValueError, match="not after the clock"):
+            book.add_cash_flow("X", 10, "1")
+        with pytest.raises(ValueError, match="Unknown cash flow kind"):
+            book.add_cash_flow("X", 20, "1", kind="coupon")
@@ -0,0 +1,264 @@
+//! WebAssembly bindings for the pyorderbook matching engine.
+//!
+//! Built with `wasm-pack build wasm --target web`, this exposes the same
+//! matching logic as the Python package to JavaScript for browser
+//! visualizations and teaching demos. Prices and quantities cross the
+//! boundary as JS numbers; prices are converted to exact decimals through
+//! their shortest string form, as the Python binding does for floats.
+
+use pyorderbook_core::{Book as CoreBook, OrderCore, Side, Trade};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+
+/// Largest integer a JS number represents exactly.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+fn parse_side(side: &str) -> Result<Side, String> {
+    match side.to_ascii_lowercase().as_str() {
+        "bid" | "buy" => Ok(Side::Bid),
+        "ask" | "sell" => Ok(Side::Ask),
+        _ => Err(format!("Invalid side '{}': expected 'bid' or 'ask'", side)),
+    }
+}
+
+fn parse_price(price: f64) -> Result<Decimal, String> {
+    if !price.is_finite() {
+        return Err(format!("Invalid price {}", price));
+    }
+    let decimal: Decimal = price
+        .to_string()
+        .parse()
+        .map_err(|e| format!("Invalid price {}: {}", price, e))?;
+    // Normalize -0 so zero-priced orders share a single level.
+    Ok(if decimal.is_zero() {
+        Decimal::ZERO
+    } else {
+        decimal
+    })
+}
+
+fn parse_quantity(quantity: f64) -> Result<i64, String> {
+    if quantity.fract() != 0.0 || quantity <= 0.0 || quantity > MAX_SAFE_INTEGER {
+        return Err(format!(
+            "Invalid quantity {}: expected a positive integer",
+            quantity
+        ));
+    }
+    Ok(quantity as i64)
+}
+
+fn to_number(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+// ---------------------------------------------------------------------------
+// Result types
+// ---------------------------------------------------------------------------
+
+/// One execution.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Fill {
+    trade_id: Uuid,
+    incoming_order_id: Uuid,
+    standing_order_id: Uuid,
+    price: f64,
+    quantity: f64,
+}
+
+#[wasm_bindgen]
+impl Fill {
+    #[wasm_bindgen(getter, js_name = tradeId)]
+    pub fn trade_id(&self) -> String {
+        self.trade_id.to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = incomingOrderId)]
+    pub fn incoming_order_id(&self) -> String {
+        self.incoming_order_id.to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = standingOrderId)]
+    pub fn standing_order_id(&self) -> String {
+        self.standing_order_id.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn price(&self) -> f64 {
+        self.price
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn quantity(&self) -> f64 {
+        self.quantity
+    }
+}
+
+impl From<Trade> for Fill {
+    fn from(trade: Trade) -> Self {
+        Fill {
+            trade_id: trade.trade_id,
+            incoming_order_id: trade.incoming_order_id,
+            standing_order_id: trade.standing_order_id,
+            price: to_number(trade.fill_price),
+            quantity: trade.fill_quantity as f64,
+        }
+    }
+}
+
+/// Outcome of `Book.submit`: the new order's id, its unfilled quantity
+/// (now resting) and the executions it caused.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Submission {
+    order_id: Uuid,
+    remaining: f64,
+    trades: Vec<Fill>,
+}
+
+#[wasm_bindgen]
+impl Submission {
+    #[wasm_bindgen(getter, js_name = orderId)]
+    pub fn order_id(&self) -> String {
+        self.order_id.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn remaining(&self) -> f64 {
+        self.remaining
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn trades(&self) -> Vec<Fill> {
+        self.trades.clone()
+    }
+}
+
+/// One aggregated price level.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Level {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+impl From<(Decimal, i64)> for Level {
+    fn from((price, quantity): (Decimal, i64)) -> Self {
+        Level {
+            price: to_number(price),
+            quantity: quantity as f64,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Book
+// ---------------------------------------------------------------------------
+
+/// Multi-symbol limit order book (`new Book()` in JavaScript).
+#[wasm_bindgen(js_name = Book)]
+#[derive(Default)]
+pub struct OrderBook {
+    book: CoreBook,
+}
+
+#[wasm_bindgen(js_class = Book)]
+impl OrderBook {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> OrderBook {
+        OrderBook::default()
+    }
+
+    /// Submit a limit order; it matches immediately and any remainder rests.
+    /// `side` is "bid"/"buy" or "ask"/"sell".
+    pub fn submit(
+        &mut self,
+        side: &str,
+        symbol: &str,
+        price: f64,
+        quantity: f64,
+    ) -> Result<Submission, String> {
+        let order = OrderCore::new(
+            parse_side(side)?,
+            symbol,
+            parse_price(price)?,
+            parse_quantity(quantity)?,
+        )
+        .map_err(|e| e.to_string())?;
+        let result = self.book.match_order(&order);
+        Ok(Submission {
+            order_id: order.id,
+            remaining: result.remaining_quantity as f64,
+            trades: result.trades.into_iter().map(Fill::from).collect(),
+        })
+    }
+
+    /// Cancel a resting order; false if it is not in the book.
+    pub fn cancel(&mut self, order_id: &str) -> bool {
+        Uuid::parse_str(order_id)
+            .ok()
+            .and_then(|id| self.book.cancel(id))
+            .is_some()
+    }
+
+    /// Up to `levels` aggregated levels of one side, best first.
+    pub fn depth(&self, symbol: &str, side: &str, levels: usize) -> Result<Vec<Level>, String> {
+        Ok(self
+            .book
+            .depth(symbol, parse_side(side)?, levels)
+            .into_iter()
+            .map(Level::from)
+            .collect())
+    }
+
+    #[wasm_bindgen(js_name = bestBid)]
+    pub fn best_bid(&self, symbol: &str) -> Option<Level> {
+        self.book.best_bid(symbol).map(Level::from)
+    }
+
+    #[wasm_bindgen(js_name = bestAsk)]
+    pub fn best_ask(&self, symbol: &str) -> Option<Level> {
+        self.book.best_ask(symbol).map(Level::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submit_matches_and_reports_depth() {
+        let mut book = OrderBook::new();
+        let resting = book.submit("sell", "AAPL", 150.25, 10.0).unwrap();
+        assert!(resting.trades.is_empty());
+
+        let taker = book.submit("buy", "AAPL", 151.0, 4.0).unwrap();
+        assert_eq!(taker.remaining, 0.0);
+        assert_eq!(taker.trades[0].standing_order_id(), resting.order_id());
+        assert_eq!(
+            (taker.trades[0].price, taker.trades[0].quantity),
+            (150.25, 4.0)
+        );
+        assert_eq!(
+            book.depth("AAPL", "ask", 5).unwrap(),
+            vec![Level {
+                price: 150.25,
+                quantity: 6.0
+            }]
+        );
+        assert!(book.cancel(&resting.order_id()));
+        assert!(book.best_ask("AAPL").is_none());
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        let mut book = OrderBook::new();
+        assert!(book.submit("hold", "AAPL", 1.0, 1.0).is_err());
+        assert!(book.submit("bid", "AAPL", f64::NAN, 1.0).is_err());
+        assert!(book.submit("bid", "AAPL", 1.0, 1.5).is_err());
+        assert!(book.submit("bid", "AAPL", 1.0, 0.0).is_err());
+        assert!(!book.cancel("not-a-uuid"));
+    }
+}